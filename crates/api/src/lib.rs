@@ -21,53 +21,29 @@ type SfnError = ServerFnError<NoCustomError>;
 // ============================================================================
 
 /// Get current protocol metrics (total supply, collateral, TCR, etc.)
-/// Cached for 15 seconds to reduce RPC load
+/// Cached for 15 seconds to reduce RPC load; served stale while refreshing
+/// once expired (see `get_usdfc_price_data`)
 #[server(GetProtocolMetrics, "/api")]
 pub async fn get_protocol_metrics() -> Result<ProtocolMetrics, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::rpc::RpcClient;
-        use usdfc_backend::cache::caches;
-
-        // Check cache first
-        if let Some(cached) = caches::PROTOCOL_METRICS.get("default") {
-            return Ok(cached);
-        }
-
-        let rpc = RpcClient::new();
-
-        // Make parallel RPC calls for better performance
-        let (total_supply, total_collateral, active_troves, tcr, stability_pool_balance) = tokio::join!(
-            rpc.get_total_supply(),
-            rpc.get_total_collateral(),
-            rpc.get_trove_owners_count(),
-            rpc.get_tcr(),
-            rpc.get_stability_pool_balance()
-        );
-
-        let total_supply = total_supply.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let total_collateral = total_collateral.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let active_troves = active_troves.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let tcr = tcr.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let stability_pool_balance = stability_pool_balance.map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Calculate actual circulating supply: total supply minus stability pool deposits
-        let circulating_supply = total_supply - stability_pool_balance;
-
-        let metrics = ProtocolMetrics {
-            total_supply,
-            circulating_supply,
-            total_collateral,
-            active_troves,
-            tcr,
-            stability_pool_balance,
-            treasury_balance: stability_pool_balance,
-        };
-
-        // Store in cache
-        caches::PROTOCOL_METRICS.set("default".to_string(), metrics.clone());
+        usdfc_backend::timing::timed("get_protocol_metrics", async move {
+            use usdfc_backend::sources::{fetch_protocol_metrics, DataSources};
+            use usdfc_backend::cache::caches;
+            use usdfc_core::error::TerminalError;
+
+            let cached = caches::PROTOCOL_METRICS
+                .get_or_revalidate("default", || async {
+                    fetch_protocol_metrics(&DataSources::current())
+                        .await
+                        .map_err(|e| SfnError::from(TerminalError::from(e)))
+                })
+                .await?;
+            let mut metrics = cached.data;
+            metrics.provenance.stale = cached.stale;
 
-        Ok(metrics)
+            Ok(metrics)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -77,30 +53,46 @@ pub async fn get_protocol_metrics() -> Result<ProtocolMetrics, ServerFnError> {
 }
 
 /// Get recent transactions from Blockscout
+///
+/// When `highlight_since` is set, transactions newer than that Unix timestamp
+/// are tagged with `is_new`.
 #[server(GetRecentTransactions, "/api")]
-pub async fn get_recent_transactions(limit: Option<u32>) -> Result<Vec<Transaction>, ServerFnError> {
+pub async fn get_recent_transactions(
+    limit: Option<u32>,
+    highlight_since: Option<u64>,
+) -> Result<Vec<Transaction>, ServerFnError> {
     let limit = limit.unwrap_or(50); // Default to 50 if not specified
 
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_backend::cache::caches;
-
-        // Check cache first (10s TTL for recent transactions)
-        let cache_key = format!("recent_tx_{}", limit);
-        if let Some(cached) = caches::RECENT_TRANSACTIONS.get(&cache_key) {
-            return Ok(cached);
-        }
-
-        let blockscout = BlockscoutClient::new();
-        // Pass None for max_pages to use default (100 pages = 5000 items)
-        let transactions = blockscout.get_recent_transfers(limit, None).await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
+        usdfc_backend::timing::timed("get_recent_transactions", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::cache::caches;
+
+            // Check cache first (10s TTL for recent transactions)
+            let cache_key = format!("recent_tx_{}", limit);
+            let mut transactions = match caches::RECENT_TRANSACTIONS.get(&cache_key) {
+                Some(cached) => cached,
+                None => {
+                    let blockscout = BlockscoutClient::new();
+                    // Pass None for max_pages to use default (100 pages = 5000 items)
+                    let transactions = blockscout.get_recent_transfers(limit, None).await
+                        .map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+                    // Store in cache
+                    caches::RECENT_TRANSACTIONS.set(cache_key, transactions.clone());
+                    transactions
+                }
+            };
 
-        // Store in cache
-        caches::RECENT_TRANSACTIONS.set(cache_key, transactions.clone());
+            if let Some(since) = highlight_since {
+                for tx in transactions.iter_mut() {
+                    tx.is_new = tx.timestamp > since;
+                }
+            }
 
-        Ok(transactions)
+            Ok(transactions)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -115,78 +107,189 @@ pub async fn get_recent_transactions(limit: Option<u32>) -> Result<Vec<Transacti
 
 /// Get all troves with optional pagination
 /// Cached for 120 seconds to reduce RPC load
+///
+/// Troves can be narrowed to one `status` and sorted by `sort_by` (ascending
+/// unless `descending`). Either one applies to all troves (up to 500) before
+/// the first `limit` are returned; with neither set, troves keep the
+/// contract's order.
 #[server(GetTroves, "/api")]
-pub async fn get_troves(limit: Option<u32>, _offset: Option<u32>) -> Result<Vec<Trove>, ServerFnError> {
+pub async fn get_troves(
+    limit: Option<u32>,
+    _offset: Option<u32>,
+    status: Option<TroveStatus>,
+    sort_by: Option<TroveRankBy>,
+    descending: Option<bool>,
+) -> Result<Vec<Trove>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::rpc::RpcClient;
-        use usdfc_backend::cache::caches;
+        usdfc_backend::timing::timed("get_troves", async move {
+            use usdfc_backend::sources::{fetch_troves, DataSources};
+            use usdfc_backend::cache::caches;
+            use usdfc_core::error::TerminalError;
+
+            let limit = limit.unwrap_or(100).min(500); // Default 100, max 500
+            let descending = descending.unwrap_or(false);
+            let cache_key = format!("troves_{}", limit);
+            // Filtering or sorting only the first page would miss troves past it
+            let fetch_limit = if status.is_none() && sort_by.is_none() { limit } else { 500 };
+            let fetch_key = format!("troves_{}", fetch_limit);
+            let view_key = match (status, sort_by) {
+                (None, None) => cache_key.clone(),
+                _ => format!(
+                    "{}_{}_{}_{}",
+                    cache_key,
+                    status.map_or("any", |s| s.as_str()),
+                    sort_by.map_or("none", |s| s.as_str()),
+                    if descending { "desc" } else { "asc" }
+                ),
+            };
 
-        let limit = limit.unwrap_or(100).min(500); // Default 100, max 500
-        let cache_key = format!("troves_{}", limit);
+            // Check cache first
+            if let Some(cached) = caches::TROVES.get(&view_key) {
+                return Ok(cached);
+            }
 
-        // Check cache first
-        if let Some(cached) = caches::TROVES.get(&cache_key) {
-            return Ok(cached);
-        }
+            let troves = match caches::TROVES.get(&fetch_key) {
+                Some(cached) => cached,
+                None => {
+                    // Fetch troves (paged, one batched round trip), FIL price and block height together
+                    let troves = fetch_troves(&DataSources::current(), fetch_limit).await.map_err(|e| {
+                        tracing::error!("RPC error fetching troves: {}", e);
+                        SfnError::from(TerminalError::from(e))
+                    })?;
+
+                    // Store in cache
+                    caches::TROVES.set(fetch_key, troves.clone());
+                    troves
+                }
+            };
+            if view_key == cache_key {
+                return Ok(troves);
+            }
 
-        let rpc = RpcClient::new();
+            let troves = select_troves(troves, status, sort_by, descending, limit as usize);
+            caches::TROVES.set(view_key, troves.clone());
 
-        // Get troves data - propagate errors to UI for proper error handling
-        let troves_data = rpc.get_multiple_sorted_troves(0, limit).await
-            .map_err(|e| {
-                tracing::error!("RPC error fetching troves: {}", e);
-                ServerFnError::<NoCustomError>::ServerError(format!("Failed to fetch troves: {}", e))
-            })?;
+            Ok(troves)
+        }).await
+    }
 
-        if troves_data.is_empty() {
-            return Ok(vec![]); // Empty is valid - no troves exist
-        }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Export troves as CSV (address, collateral, debt, ICR, status)
+///
+/// Goes through `get_troves`, so it shares its cache; `limit` defaults to
+/// the 500-trove maximum rather than the page size.
+#[server(ExportTrovesCsv, "/api")]
+pub async fn export_troves_csv(limit: Option<u32>) -> Result<String, ServerFnError> {
+    let troves = get_troves(Some(limit.unwrap_or(500)), None, None, None, None).await?;
+    let mut csv = String::from(Trove::CSV_HEADER);
+    for trove in &troves {
+        csv.push_str(&trove.csv_row());
+    }
+    Ok(csv)
+}
+
+/// Get the trove owned by an address (0x or f4), or None if it has no open trove
+///
+/// ICR and status are computed as in `get_troves`. Cached for 120 seconds
+/// per address, including "no trove" results.
+#[server(GetTroveByAddress, "/api")]
+pub async fn get_trove_by_address(address: String) -> Result<Option<Trove>, ServerFnError> {
+    usdfc_core::error::ValidationError::validate_address(&address)
+        .map_err(|e| SfnError::from(usdfc_core::error::TerminalError::from(e)))?;
+
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_trove_by_address", async move {
+            use usdfc_backend::address_conv::normalize_for_blockscout;
+            use usdfc_backend::sources::{fetch_trove, DataSources};
+            use usdfc_backend::cache::caches;
+            use usdfc_core::error::TerminalError;
+
+            let owner = normalize_for_blockscout(&address)
+                .map_err(|e| SfnError::from(TerminalError::Validation(e)))?
+                .to_lowercase();
+
+            if let Some(cached) = caches::TROVE_BY_ADDRESS.get(&owner) {
+                return Ok(cached);
+            }
 
-        // Get FIL price - propagate errors to UI for proper error handling
-        let fil_price = rpc.get_fil_price().await
-            .map_err(|e| {
-                tracing::error!("RPC error fetching FIL price: {}", e);
-                ServerFnError::<NoCustomError>::ServerError(format!("Failed to fetch FIL price: {}", e))
+            let trove = fetch_trove(&DataSources::current(), &owner).await.map_err(|e| {
+                tracing::error!("RPC error fetching trove for {}: {}", owner, e);
+                SfnError::from(TerminalError::from(e))
             })?;
 
-        if fil_price.is_zero() {
-            tracing::error!("FIL price is zero - invalid data");
-            return Err(ServerFnError::<NoCustomError>::ServerError("FIL price is zero".to_string()));
-        }
-
-        // Convert to Trove type with ICR calculation
-        let troves: Vec<Trove> = troves_data
-            .iter()
-            .map(|t| {
-                let icr = if t.debt.is_zero() {
-                    Decimal::new(10000, 0)
-                } else {
-                    (t.coll * fil_price) / t.debt * Decimal::new(100, 0)
-                };
+            caches::TROVE_BY_ADDRESS.set(owner, trove.clone());
 
-                let status = if icr < Decimal::new(115, 0) {
-                    TroveStatus::Critical
-                } else if icr < Decimal::new(135, 0) {
-                    TroveStatus::AtRisk
-                } else {
-                    TroveStatus::Active
-                };
+            Ok(trove)
+        }).await
+    }
 
-                Trove {
-                    address: t.owner.clone(),
-                    collateral: t.coll,
-                    debt: t.debt,
-                    icr,
-                    status,
-                }
-            })
-            .collect();
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get the top troves ranked by debt, collateral or ICR
+/// Built from the cached trove list; cached for 30 seconds per sort key
+#[server(GetTroveLeaderboard, "/api")]
+pub async fn get_trove_leaderboard(sort_by: TroveRankBy, limit: Option<u32>) -> Result<Vec<Trove>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_trove_leaderboard", async move {
+            use usdfc_backend::cache::caches;
+
+            let limit = limit.unwrap_or(10).clamp(1, 50); // Default 10, max 50
+            let cache_key = format!("{}_{}", sort_by.as_str(), limit);
+
+            if let Some(cached) = caches::TROVE_LEADERBOARD.get(&cache_key) {
+                return Ok(cached);
+            }
+
+            // Rank over the full trove set; get_troves applies the ICR calculation
+            // and rejects a zero FIL price
+            let troves = get_troves(Some(500), None, None, None, None).await?;
+            let ranked = sort_by.rank(troves, limit as usize);
+
+            caches::TROVE_LEADERBOARD.set(cache_key, ranked.clone());
 
-        // Store in cache
-        caches::TROVES.set(cache_key, troves.clone());
+            Ok(ranked)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
 
-        Ok(troves)
+/// Get troves bucketed by ICR with the count and total debt per bucket
+///
+/// Buckets are split at `ICR_BUCKETS` (default <110, 110-125, 125-150,
+/// 150-200 and 200+). Built from the cached trove list; cached for 120
+/// seconds separately from it.
+#[server(GetIcrDistribution, "/api")]
+pub async fn get_icr_distribution() -> Result<Vec<IcrBucket>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_icr_distribution", async move {
+            use usdfc_backend::cache::caches;
+            use usdfc_core::config::config;
+
+            caches::ICR_DISTRIBUTION
+                .get_or_compute("default", || async {
+                    // Bucket the full trove set; get_troves applies the ICR calculation
+                    let troves = get_troves(Some(500), None, None, None, None).await?;
+                    Ok::<_, SfnError>(IcrBucket::histogram(&troves, &config().icr_bucket_bounds))
+                })
+                .await
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -206,73 +309,83 @@ pub async fn get_troves(limit: Option<u32>, _offset: Option<u32>) -> Result<Vec<
 pub async fn get_lending_markets() -> Result<Vec<LendingMarketData>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::subgraph::SubgraphClient;
-        use usdfc_backend::subgraph::unit_price_to_apr;
-        use usdfc_backend::cache::caches;
-
-        // Check cache first
-        if let Some(cached) = caches::LENDING_MARKETS.get("default") {
-            return Ok(cached);
-        }
-
-        let subgraph = SubgraphClient::new();
-        let markets = subgraph.get_lending_markets().await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Filter and map markets - only include markets with real pricing data
-        // Skip markets without unit prices (no fake "0" fallbacks)
-        let market_data: Vec<LendingMarketData> = markets
-            .into_iter()
-            .filter_map(|m| {
-                let maturity_ts = m.maturity.parse::<i64>().ok()?;
+        usdfc_backend::timing::timed("get_lending_markets", async move {
+            use usdfc_backend::subgraph::SubgraphClient;
+            use usdfc_backend::subgraph::unit_price_to_apr;
+            use usdfc_core::config::config;
+            use usdfc_backend::cache::caches;
+
+            // Check cache first
+            if let Some(cached) = caches::LENDING_MARKETS.get("default") {
+                return Ok(cached);
+            }
 
-                // Get real prices - use empty string if no price (will show as N/A in UI)
-                let lend_price = m.last_lend_unit_price.clone().unwrap_or_default();
-                let borrow_price = m.last_borrow_unit_price.clone().unwrap_or_default();
+            let subgraph = SubgraphClient::new();
+            let markets = subgraph.get_lending_markets().await
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
 
-                // Calculate APR only if we have valid price data
-                let lend_apr = if lend_price.is_empty() {
-                    0.0 // No price means 0 APR (market has no lend orders)
-                } else {
-                    unit_price_to_apr(&lend_price, maturity_ts).unwrap_or_else(|e| {
-                        tracing::warn!(
-                            "Failed to calculate lend APR for market {} (maturity {}): {}. Showing 0.0",
-                            m.currency, m.maturity, e
-                        );
-                        0.0
-                    })
-                };
-                let borrow_apr = if borrow_price.is_empty() {
-                    0.0 // No price means 0 APR (market has no borrow orders)
-                } else {
-                    unit_price_to_apr(&borrow_price, maturity_ts).unwrap_or_else(|e| {
-                        tracing::warn!(
-                            "Failed to calculate borrow APR for market {} (maturity {}): {}. Showing 0.0",
-                            m.currency, m.maturity, e
-                        );
-                        0.0
+            // Filter and map markets - only include markets with real pricing data
+            // Skip markets without unit prices (no fake "0" fallbacks)
+            let market_data: Vec<LendingMarketData> = markets
+                .into_iter()
+                .filter_map(|m| {
+                    let Ok(maturity_ts) = m.maturity.parse::<i64>() else {
+                        tracing::warn!("Skipping lending market {} with invalid maturity '{}'", m.id, m.maturity);
+                        return None;
+                    };
+
+                    // Get real prices - use empty string if no price (will show as N/A in UI)
+                    let lend_price = m.last_lend_unit_price.clone().unwrap_or_default();
+                    let borrow_price = m.last_borrow_unit_price.clone().unwrap_or_default();
+
+                    // Calculate APR only if we have valid price data
+                    let lend_apr = if lend_price.is_empty() {
+                        0.0 // No price means 0 APR (market has no lend orders)
+                    } else {
+                        unit_price_to_apr(&lend_price, maturity_ts).unwrap_or_else(|e| {
+                            tracing::warn!(
+                                "Failed to calculate lend APR for market {} (maturity {}): {}. Showing 0.0",
+                                m.currency, m.maturity, e
+                            );
+                            0.0
+                        })
+                    };
+                    let borrow_apr = if borrow_price.is_empty() {
+                        0.0 // No price means 0 APR (market has no borrow orders)
+                    } else {
+                        unit_price_to_apr(&borrow_price, maturity_ts).unwrap_or_else(|e| {
+                            tracing::warn!(
+                                "Failed to calculate borrow APR for market {} (maturity {}): {}. Showing 0.0",
+                                m.currency, m.maturity, e
+                            );
+                            0.0
+                        })
+                    };
+
+                    let volume = m.volume.clone().unwrap_or_default();
+
+                    let currency = Currency::from_bytes32(&m.currency);
+                    let featured = config().is_featured_market(&m.maturity, currency.symbol());
+
+                    Some(LendingMarketData {
+                        maturity: m.maturity,
+                        currency,
+                        lend_unit_price: lend_price,
+                        borrow_unit_price: borrow_price,
+                        volume,
+                        is_active: m.is_active,
+                        lend_apr,
+                        borrow_apr,
+                        featured,
                     })
-                };
-
-                let volume = m.volume.clone().unwrap_or_default();
-
-                Some(LendingMarketData {
-                    maturity: m.maturity,
-                    currency: m.currency,
-                    lend_unit_price: lend_price,
-                    borrow_unit_price: borrow_price,
-                    volume,
-                    is_active: m.is_active,
-                    lend_apr,
-                    borrow_apr,
                 })
-            })
-            .collect();
+                .collect();
 
-        // Store in cache
-        caches::LENDING_MARKETS.set("default".to_string(), market_data.clone());
+            // Store in cache
+            caches::LENDING_MARKETS.set("default".to_string(), market_data.clone());
 
-        Ok(market_data)
+            Ok(market_data)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -287,43 +400,62 @@ pub async fn get_lending_markets() -> Result<Vec<LendingMarketData>, ServerFnErr
 
 
 /// Get daily volume data for historical charts
+///
+/// `days` defaults to 30 and is clamped to 1..=365; negative windows are
+/// rejected as validation errors.
 #[server(GetDailyVolumes, "/api")]
-pub async fn get_daily_volumes(days: Option<i32>) -> Result<Vec<DailyVolumeData>, ServerFnError> {
-    let days = days.unwrap_or(30);
+pub async fn get_daily_volumes(days: Option<i32>) -> Result<DailyVolumesResponse, ServerFnError> {
+    let days = DailyVolumesResponse::validate_days(days)
+        .map_err(|e| SfnError::from(usdfc_core::error::TerminalError::from(e)))?;
 
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::subgraph::SubgraphClient;
-        use usdfc_backend::cache::caches;
-
-        // Check cache first
-        let cache_key = format!("daily_vol_{}", days);
-        if let Some(cached) = caches::DAILY_VOLUMES.get(&cache_key) {
-            return Ok(cached);
-        }
+        usdfc_backend::timing::timed("get_daily_volumes", async move {
+            use usdfc_backend::subgraph::SubgraphClient;
+            use usdfc_backend::cache::caches;
+
+            // Check cache first
+            let cache_key = format!("daily_vol_{}", days);
+            if let Some(cached) = caches::DAILY_VOLUMES.get(&cache_key) {
+                return Ok(cached);
+            }
 
-        let subgraph = SubgraphClient::new();
-        let volumes = subgraph.get_daily_volumes(days).await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
+            let subgraph = SubgraphClient::new();
+            let fetch = subgraph.get_daily_volumes(days).await
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
 
-        let data: Vec<DailyVolumeData> = volumes
-            .into_iter()
-            .filter_map(|v| {
-                let volume = v.volume.parse::<f64>().ok()? / 1e18;
-                let timestamp = v.timestamp.parse::<i64>().ok()?;
-                Some(DailyVolumeData {
-                    day: v.day,
-                    volume,
-                    timestamp,
-                    currency: v.currency,
+            let total = fetch.volumes.len();
+            let volumes: Vec<DailyVolumeData> = fetch.volumes
+                .into_iter()
+                .filter_map(|v| {
+                    let volume = v.volume.parse::<f64>().ok()? / 1e18;
+                    let timestamp = v.timestamp.parse::<i64>().ok()?;
+                    Some(DailyVolumeData {
+                        day: v.day,
+                        volume,
+                        timestamp,
+                        currency: v.currency,
+                    })
                 })
-            })
-            .collect();
+                .collect();
 
-        // Store in cache
-        caches::DAILY_VOLUMES.set(cache_key, data.clone());
+            let dropped = total - volumes.len();
+            if dropped > 0 {
+                tracing::warn!("get_daily_volumes: dropped {} of {} unparseable rows", dropped, total);
+            }
 
-        Ok(data)
+            let data = DailyVolumesResponse {
+                volumes,
+                complete: fetch.complete && dropped == 0,
+            };
+
+            // Only cache complete results so a partial fetch is retried on the next request
+            if data.complete {
+                caches::DAILY_VOLUMES.set(cache_key, data.clone());
+            }
+
+            Ok(data)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -344,33 +476,805 @@ pub async fn get_address_info(address: String) -> Result<AddressInfo, ServerFnEr
 
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_backend::address_conv::normalize_for_blockscout;
-        use usdfc_backend::cache::caches;
-
-        let normalized = normalize_for_blockscout(&address)
-            .map_err(|e| {
-                tracing::error!("Address normalization error for {}: {}", address, e);
-                SfnError::ServerError(format!("Invalid address format: {}", e))
-            })?;
+        usdfc_backend::timing::timed("get_address_info", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::address_conv::normalize_for_blockscout;
+            use usdfc_backend::cache::caches;
+
+            let normalized = normalize_for_blockscout(&address)
+                .map_err(|e| {
+                    tracing::error!("Address normalization error for {}: {}", address, e);
+                    SfnError::ServerError(format!("Invalid address format: {}", e))
+                })?;
+
+            // Check cache first
+            // Lowercased so checksummed, lowercase and f4 inputs share one entry
+            let cache_key = format!("addr_{}", normalized.to_lowercase());
+            if let Some(cached) = caches::ADDRESS_INFO.get(&cache_key) {
+                return Ok(cached);
+            }
+
+            let blockscout = BlockscoutClient::new();
+            let address_info = blockscout.get_address_usdfc_info(&normalized).await
+                .map_err(|e| {
+                    tracing::error!("Blockscout error for {}: {}", address, e);
+                    SfnError::ServerError(format!("Failed to fetch address info: {}", e))
+                })?;
+
+            // Store in cache
+            caches::ADDRESS_INFO.set(cache_key, address_info.clone());
+
+            Ok(address_info)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Normalize address formats for display and routing
+#[server(GetNormalizedAddress, "/api")]
+pub async fn get_normalized_address(address: String) -> Result<NormalizedAddress, ServerFnError> {
+    usdfc_core::error::ValidationError::validate_address(&address)
+        .map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_normalized_address", async move {
+            use usdfc_backend::address_conv::{evm_to_f4, f4_to_evm};
+
+            if address.starts_with("0x") {
+                let f4 = evm_to_f4(&address).map_err(SfnError::ServerError)?;
+                return Ok(NormalizedAddress {
+                    input: address.clone(),
+                    kind: "evm".to_string(),
+                    evm: Some(address.clone()),
+                    f4: Some(f4),
+                    blockscout: Some(address),
+                });
+            }
+
+            if address.starts_with("f4") {
+                let evm = f4_to_evm(&address).map_err(SfnError::ServerError)?;
+                return Ok(NormalizedAddress {
+                    input: address.clone(),
+                    kind: "delegated".to_string(),
+                    evm: Some(evm.clone()),
+                    f4: Some(address),
+                    blockscout: Some(evm),
+                });
+            }
+
+            if address.starts_with("f1") || address.starts_with("f3") {
+                return Err(SfnError::ServerError(
+                    "f1/f3 addresses are not supported by Blockscout without conversion".to_string(),
+                ));
+            }
+
+            Err(SfnError::ServerError("unsupported address format".to_string()))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get the private note attached to an address, if any
+#[server(GetAddressNote, "/api")]
+pub async fn get_address_note(address: String) -> Result<Option<AddressNote>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_address_note", async move {
+            use usdfc_backend::address_notes;
+
+            let key = address_notes::normalize_address(&address).map_err(SfnError::ServerError)?;
+            address_notes::get_note(&key)
+                .map_err(|e| SfnError::ServerError(format!("Failed to load address note: {}", e)))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get all address notes, keyed by lowercased address, for labelling tables
+#[server(GetAddressNotes, "/api")]
+pub async fn get_address_notes() -> Result<std::collections::HashMap<String, String>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_address_notes", async move {
+            usdfc_backend::address_notes::get_all_notes()
+                .map_err(|e| SfnError::ServerError(format!("Failed to load address notes: {}", e)))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Set or clear (empty note) the private note for an address
+/// Requires the ADDRESS_NOTES_TOKEN configured on the server
+#[server(SetAddressNote, "/api")]
+pub async fn set_address_note(address: String, note: String, token: String) -> Result<Option<AddressNote>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("set_address_note", async move {
+            use usdfc_backend::address_notes;
+
+            if !address_notes::write_token_valid(&token) {
+                tracing::warn!("Rejected address note write for {}: invalid token", address);
+                return Err(SfnError::ServerError("Invalid or missing notes token".to_string()));
+            }
+
+            let key = address_notes::normalize_address(&address).map_err(SfnError::ServerError)?;
+            let note = address_notes::validate_note(&note).map_err(SfnError::ServerError)?;
+            address_notes::set_note(&key, &note)
+                .map_err(|e| SfnError::ServerError(format!("Failed to save address note: {}", e)))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+// ============================================================================
+// Watchlist
+// ============================================================================
+
+/// Get the addresses saved to a watchlist, oldest first
+///
+/// Lists are selected by an opaque client-chosen `token`; there is no
+/// authentication, so without a token every caller shares one list.
+#[server(GetWatchlist, "/api")]
+pub async fn get_watchlist(token: Option<String>) -> Result<Vec<WatchEntry>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_watchlist", async move {
+            use usdfc_backend::watchlist;
+            use usdfc_core::error::TerminalError;
+
+            let token = watchlist::normalize_token(token.as_deref())
+                .map_err(|e| SfnError::from(TerminalError::Validation(e)))?;
+            watchlist::get_entries(&token)
+                .map_err(|e| SfnError::from(TerminalError::Internal(format!("Failed to load watchlist: {}", e))))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Save an address to a watchlist, or relabel it if already saved
+///
+/// See `get_watchlist` for how `token` scopes the list.
+#[server(AddWatchAddress, "/api")]
+pub async fn add_watch_address(address: String, label: String, token: Option<String>) -> Result<WatchEntry, ServerFnError> {
+    usdfc_core::error::ValidationError::validate_address(&address)
+        .map_err(|e| SfnError::from(usdfc_core::error::TerminalError::from(e)))?;
+
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("add_watch_address", async move {
+            use usdfc_backend::{address_notes, watchlist};
+            use usdfc_core::error::TerminalError;
+
+            let token = watchlist::normalize_token(token.as_deref())
+                .map_err(|e| SfnError::from(TerminalError::Validation(e)))?;
+            let key = address_notes::normalize_address(&address)
+                .map_err(|e| SfnError::from(TerminalError::Validation(e)))?;
+            let label = watchlist::validate_label(&label)
+                .map_err(|e| SfnError::from(TerminalError::Validation(e)))?;
+            watchlist::add_entry(&token, &key, &label)
+                .map_err(|e| SfnError::from(TerminalError::Internal(format!("Failed to save watchlist entry: {}", e))))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Remove an address from a watchlist, returning whether it was saved
+///
+/// See `get_watchlist` for how `token` scopes the list.
+#[server(RemoveWatchAddress, "/api")]
+pub async fn remove_watch_address(address: String, token: Option<String>) -> Result<bool, ServerFnError> {
+    usdfc_core::error::ValidationError::validate_address(&address)
+        .map_err(|e| SfnError::from(usdfc_core::error::TerminalError::from(e)))?;
+
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("remove_watch_address", async move {
+            use usdfc_backend::{address_notes, watchlist};
+            use usdfc_core::error::TerminalError;
+
+            let token = watchlist::normalize_token(token.as_deref())
+                .map_err(|e| SfnError::from(TerminalError::Validation(e)))?;
+            let key = address_notes::normalize_address(&address)
+                .map_err(|e| SfnError::from(TerminalError::Validation(e)))?;
+            watchlist::remove_entry(&token, &key)
+                .map_err(|e| SfnError::from(TerminalError::Internal(format!("Failed to remove watchlist entry: {}", e))))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+// ============================================================================
+// Holders + Stability Pool Transfers
+// ============================================================================
+
+
+/// Get top USDFC holders from Blockscout
+/// Cached for 300 seconds (5 minutes) as holder list changes slowly
+/// Note: Uses cursor-based pagination, offset parameter is ignored (use `get_holders_page` for pagination)
+/// Each holder carries its share of total supply and the cumulative share of
+/// the holders up to it; both are None when supply is unavailable
+#[server(GetTopHolders, "/api")]
+pub async fn get_top_holders(limit: Option<u32>, offset: Option<u32>) -> Result<Vec<TokenHolderInfo>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_top_holders", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_core::config::config;
+            use usdfc_backend::cache::caches;
+
+            let _offset = offset; // Ignored for cursor-based API
+            let cache_key = format!("holders_first_page");
+            let limit = limit.unwrap_or(20).min(100) as usize;
+
+            let holder_info = match caches::TOKEN_HOLDERS.get(&cache_key) {
+                Some(cached) => cached,
+                None => {
+                    let blockscout = BlockscoutClient::new();
+
+                    // Fetch first page from Blockscout API (cursor-based pagination)
+                    let (holders, _next_cursor) = blockscout.get_token_holders_cursor(&config().usdfc_token, None).await
+                        .map_err(|e| SfnError::ServerError(format!("Blockscout API error: {}", e)))?;
+
+                    let holder_info: Vec<TokenHolderInfo> = holders
+                        .into_iter()
+                        .map(|h| TokenHolderInfo::new(h.address, h.balance))
+                        .collect();
+
+                    // Store in cache
+                    caches::TOKEN_HOLDERS.set(cache_key, holder_info.clone());
+                    holder_info
+                }
+            };
+
+            // Return only requested limit, with supply shares from the (cached) metrics
+            let mut holders: Vec<TokenHolderInfo> = holder_info.into_iter().take(limit).collect();
+            let total_supply = get_protocol_metrics().await.ok().and_then(|m| m.total_supply);
+            TokenHolderInfo::set_supply_shares(&mut holders, total_supply);
+            Ok(holders)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get one page of USDFC holders, following Blockscout's cursor pagination
+///
+/// Pass `next_cursor` from the previous page as `cursor` to continue. Blockscout
+/// pages are returned whole so the cursor stays valid: pages are fetched until
+/// at least `limit` holders (default 50, max 200) are collected, which may
+/// overshoot `limit` by up to one page. Not cached, since each cursor is
+/// typically requested once.
+#[server(GetHoldersPage, "/api")]
+pub async fn get_holders_page(cursor: Option<String>, limit: Option<u32>) -> Result<HoldersPage, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_holders_page", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_core::config::config;
+
+            let limit = limit.unwrap_or(50).clamp(1, 200) as usize;
+            let blockscout = BlockscoutClient::new();
+
+            let mut holders = Vec::new();
+            let mut next_cursor = cursor;
+            loop {
+                let (page, cursor) = blockscout
+                    .get_token_holders_cursor(&config().usdfc_token, next_cursor.as_deref())
+                    .await
+                    .map_err(|e| SfnError::ServerError(format!("Blockscout API error: {}", e)))?;
+                let empty = page.is_empty();
+                holders.extend(page.into_iter().map(|h| TokenHolderInfo::new(h.address, h.balance)));
+                next_cursor = cursor;
+                if empty || next_cursor.is_none() || holders.len() >= limit {
+                    break;
+                }
+            }
+
+            Ok(HoldersPage { holders, next_cursor })
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get recent USDFC transfers involving the Stability Pool address
+#[server(GetStabilityPoolTransfers, "/api")]
+pub async fn get_stability_pool_transfers(limit: Option<u32>) -> Result<Vec<Transaction>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_stability_pool_transfers", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_core::config::config;
+            use usdfc_backend::cache::caches;
+
+            let limit = limit.ok_or_else(|| SfnError::ServerError("limit is required".to_string()))?;
+
+            // Check cache first
+            let cache_key = format!("stability_tx_{}", limit);
+            if let Some(cached) = caches::STABILITY_TRANSFERS.get(&cache_key) {
+                return Ok(cached);
+            }
+
+            let blockscout = BlockscoutClient::new();
+            let transfers = blockscout
+                .get_address_transfers(&config().stability_pool, &config().usdfc_token, limit)
+                .await
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+            // Store in cache
+            caches::STABILITY_TRANSFERS.set(cache_key, transfers.clone());
+
+            Ok(transfers)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get stability pool analytics: balance, recent flows and depositor count
+/// Net flow windows are configured via STABILITY_FLOW_WINDOWS_HOURS
+#[server(GetStabilityPoolAnalytics, "/api")]
+pub async fn get_stability_pool_analytics() -> Result<StabilityPoolAnalytics, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_stability_pool_analytics", async move {
+            use std::collections::HashMap;
+            use usdfc_backend::rpc::RpcClient;
+            use usdfc_core::config::config;
+
+            let rpc = RpcClient::new();
+            let (balance_result, transfers_result) = tokio::join!(
+                rpc.get_stability_pool_balance(),
+                get_stability_pool_transfers(Some(200))
+            );
+
+            let pool_balance = balance_result.map_err(|e| SfnError::ServerError(e.to_string()))?;
+            let transfers = transfers_result?;
+
+            let pool = config().stability_pool.to_lowercase();
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+
+            let windows = config()
+                .stability_flow_windows_hours
+                .iter()
+                .map(|&hours| {
+                    let cutoff = now.saturating_sub(hours * 3600);
+                    let mut window = StabilityFlowWindow {
+                        window_hours: hours,
+                        deposits: Decimal::ZERO,
+                        withdrawals: Decimal::ZERO,
+                        net_flow: Decimal::ZERO,
+                        deposit_count: 0,
+                        withdrawal_count: 0,
+                    };
+                    for tx in transfers.iter().filter(|tx| tx.timestamp >= cutoff) {
+                        if tx.to.to_lowercase() == pool {
+                            window.deposits += tx.amount;
+                            window.deposit_count += 1;
+                        } else if tx.from.to_lowercase() == pool {
+                            window.withdrawals += tx.amount;
+                            window.withdrawal_count += 1;
+                        }
+                    }
+                    window.net_flow = window.deposits - window.withdrawals;
+                    window
+                })
+                .collect();
+
+            // Net position per counterparty across the fetched transfers
+            let mut positions: HashMap<String, Decimal> = HashMap::new();
+            for tx in &transfers {
+                if tx.to.to_lowercase() == pool {
+                    *positions.entry(tx.from.to_lowercase()).or_default() += tx.amount;
+                } else if tx.from.to_lowercase() == pool {
+                    *positions.entry(tx.to.to_lowercase()).or_default() -= tx.amount;
+                }
+            }
+            let depositor_count = positions.values().filter(|v| **v > Decimal::ZERO).count() as u64;
+
+            Ok(StabilityPoolAnalytics {
+                pool_balance,
+                depositor_count,
+                windows,
+                recent_transfers: transfers.into_iter().take(20).collect(),
+            })
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+// ============================================================================
+// Price Data (GeckoTerminal)
+// ============================================================================
+
+/// USDFC price and market data from DEX
+
+/// Get USDFC price data from GeckoTerminal
+/// Cached for 30 seconds to reduce API load. Once expired, the last value is
+/// served with `provenance.stale` set while a background refresh runs, so a
+/// brief GeckoTerminal outage does not surface as an error.
+#[server(GetUSDFCPriceData, "/api")]
+pub async fn get_usdfc_price_data() -> Result<USDFCPriceData, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_usdfc_price_data", async move {
+            use usdfc_backend::cache::caches;
+
+            let cached = caches::USDFC_PRICE
+                .get_or_revalidate("default", fetch_usdfc_price_data)
+                .await?;
+            let mut price_data = cached.data;
+            price_data.provenance.stale = cached.stale;
+
+            Ok(price_data)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Fetch USDFC price data across the configured pools, bypassing the cache
+///
+/// Fails only when every pool fails, so the cache keeps serving the last quote.
+#[cfg(feature = "ssr")]
+async fn fetch_usdfc_price_data() -> Result<USDFCPriceData, ServerFnError> {
+    use usdfc_core::config::config;
+    use usdfc_backend::gecko::GeckoClient;
+
+    let gecko = GeckoClient::new();
+    let pools = gecko
+        .get_pools_price_data(&config().price_pool_addresses())
+        .await;
+
+    if pools.iter().all(|p| p.error.is_some()) {
+        let errors: Vec<String> = pools.into_iter().filter_map(|p| p.error).collect();
+        return Err(SfnError::ServerError(errors.join("; ")));
+    }
+
+    Ok(USDFCPriceData::from_pools(pools, gecko.provenance()))
+}
+
+/// Get USDFC's deviation from the configured peg, graded by the depeg thresholds
+///
+/// Built on `get_usdfc_price_data`. A missing price, or a failed fetch, is
+/// reported as `Critical` with no price rather than as an error, since the
+/// price feed dropping out during a depeg is itself alarming.
+#[server(GetDepegStatus, "/api")]
+pub async fn get_depeg_status() -> Result<DepegStatus, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_depeg_status", async move {
+            use usdfc_core::config::config;
+            use std::time::{SystemTime, UNIX_EPOCH};
+
+            let price_usd = match get_usdfc_price_data().await {
+                Ok(data) => data.price_usd,
+                Err(e) => {
+                    tracing::warn!("USDFC price unavailable for depeg check: {}", e);
+                    None
+                }
+            };
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+
+            let cfg = config();
+            Ok(DepegStatus::evaluate(
+                price_usd,
+                cfg.peg_target,
+                cfg.depeg_warning_pct,
+                cfg.depeg_critical_pct,
+                now,
+            ))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get the time-weighted average USDFC price over a window, with spot deviation
+///
+/// Uses the finest candle resolution that covers the window. Cached for 30
+/// seconds per window.
+#[server(GetTwap, "/api")]
+pub async fn get_twap(window: ChartLookback) -> Result<TwapData, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_twap", async move {
+            use usdfc_backend::cache::caches;
+            use usdfc_core::config::config;
+            use usdfc_backend::gecko::GeckoClient;
+            use usdfc_backend::twap::time_weighted_average;
+            use std::time::{SystemTime, UNIX_EPOCH};
+
+            let cache_key = window.to_url_param().to_string();
+            if let Some(cached) = caches::TWAP.get(&cache_key) {
+                return Ok(cached);
+            }
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let window_mins = window.minutes();
+            let resolution = ChartResolution::finest_for(window_mins);
+            let (timeframe, aggregate, limit) = resolution.gecko_params();
+            // One extra candle so the price at the window start is known
+            let candles_needed = (window_mins / resolution.minutes() + 1).min(limit);
+
+            let gecko = GeckoClient::new();
+            let (candles, price) = tokio::join!(
+                gecko.get_pool_ohlcv(&config().pool_usdfc_wfil, timeframe, aggregate, candles_needed),
+                get_usdfc_price_data()
+            );
+            let candles = candles.map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+            let start = now - window_mins as i64 * 60;
+            let twap = time_weighted_average(&candles, start, now);
+            let spot_price = price.ok().and_then(|p| p.price_usd);
+            let deviation_pct = match (spot_price, twap) {
+                (Some(spot), Some(twap)) if twap > 0.0 => Some((spot - twap) / twap * 100.0),
+                _ => None,
+            };
+
+            let data = TwapData {
+                window,
+                resolution,
+                twap,
+                spot_price,
+                deviation_pct,
+                candle_count: candles.iter().filter(|c| c.timestamp >= start).count(),
+                timestamp: now,
+            };
+
+            caches::TWAP.set(cache_key, data.clone());
+
+            Ok(data)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get the live TCR's z-score against recorded snapshots over the configured window
+///
+/// Uses the cached protocol metrics for the live value. `tcr_zscore` is None
+/// until the window holds enough snapshots, or when TCR has not moved in it.
+#[server(GetTcrVolatility, "/api")]
+pub async fn get_tcr_volatility() -> Result<TcrVolatility, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_tcr_volatility", async move {
+            use usdfc_backend::volatility::{mean_std, rolling_zscore, tcr_window};
+            use usdfc_core::config::config;
+            use usdfc_core::error::TerminalError;
+            use usdfc_core::format::decimal_to_f64;
+            use std::time::{SystemTime, UNIX_EPOCH};
+
+            let metrics = get_protocol_metrics().await?;
+            let tcr = metrics.tcr.map(decimal_to_f64).ok_or_else(|| {
+                SfnError::from(TerminalError::UpstreamUnavailable { source: "TCR is unavailable".to_string() })
+            })?;
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let window_mins = config().tcr_zscore_window_mins;
+            let window = tcr_window(now - window_mins as i64 * 60);
+            let stats = mean_std(&window);
+
+            Ok(TcrVolatility {
+                window_mins,
+                tcr,
+                tcr_zscore: rolling_zscore(&window, tcr),
+                mean: stats.map(|(mean, _)| mean),
+                std_dev: stats.map(|(_, std_dev)| std_dev),
+                samples: window.len(),
+                timestamp: now,
+            })
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get one metric's recorded snapshot values between two Unix timestamps (inclusive)
+///
+/// Raw stored points, oldest first, with no downsampling or live fallback:
+/// hourly averages past the raw retention window, and an empty list where
+/// nothing was recorded. Only snapshot metrics (TCR, supply, liquidity,
+/// holders, lend and borrow APR) are available. Not cached.
+#[server(GetMetricHistory, "/api")]
+pub async fn get_metric_history(metric: ChartMetric, start: i64, end: i64) -> Result<Vec<(i64, f64)>, ServerFnError> {
+    if start >= end {
+        return Err(SfnError::from(usdfc_core::error::TerminalError::Validation(
+            "start must be before end".to_string(),
+        )));
+    }
+
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_metric_history", async move {
+            use usdfc_backend::historical::{metric_range, SNAPSHOT_METRICS};
+            use usdfc_core::error::TerminalError;
+
+            if !SNAPSHOT_METRICS.contains(&metric) {
+                return Err(SfnError::from(TerminalError::Validation(format!(
+                    "{} is not recorded in metric history",
+                    metric.to_url_param()
+                ))));
+            }
+
+            metric_range(metric, start, end).map_err(|e| {
+                tracing::error!("Failed to read {} history: {}", metric.to_url_param(), e);
+                SfnError::from(TerminalError::Internal(e.to_string()))
+            })
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get borrowing and redemption fee income over a lookback window
+///
+/// Fees are decoded from BorrowerOperations and TroveManager event logs via
+/// Blockscout and bucketed by `resolution` (coarsened if the window would
+/// need too many buckets). Buckets whose fee events could not be decoded are
+/// `None`, not zero. Cached for 5 minutes per lookback and resolution.
+#[server(GetFeeAnalytics, "/api")]
+pub async fn get_fee_analytics(
+    lookback: ChartLookback,
+    resolution: ChartResolution,
+) -> Result<FeeAnalytics, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_fee_analytics", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::cache::caches;
+            use usdfc_backend::fees::{bucket_fees, fee_resolution};
+            use usdfc_core::config::config;
+            use std::time::{SystemTime, UNIX_EPOCH};
+
+            let resolution = fee_resolution(resolution, lookback.minutes());
+            let cache_key = format!("{}:{}", lookback.to_url_param(), resolution.to_url_param());
+            if let Some(cached) = caches::FEE_ANALYTICS.get(&cache_key) {
+                return Ok(cached);
+            }
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let start = lookback.cutoff_from(now).unwrap_or(0);
+
+            let blockscout = BlockscoutClient::new();
+            let (borrowing, redemption) = tokio::join!(
+                blockscout.get_fee_events(&config().borrower_operations, start),
+                blockscout.get_fee_events(&config().trove_manager, start)
+            );
+            let borrowing = borrowing.map_err(|e| SfnError::ServerError(e.to_string()))?;
+            let redemption = redemption.map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+            let buckets = bucket_fees(&borrowing, &redemption, start, now, resolution.minutes() as i64 * 60);
+            let data = FeeAnalytics {
+                lookback,
+                resolution,
+                total_borrowing_fees: buckets.last().and_then(|b| b.cumulative_borrowing_fees),
+                total_redemption_fees_fil: buckets.last().and_then(|b| b.cumulative_redemption_fees_fil),
+                buckets,
+                borrowing_events: borrowing.events.len(),
+                redemption_events: redemption.events.len(),
+                timestamp: now,
+            };
+
+            caches::FEE_ANALYTICS.set(cache_key, data.clone());
+
+            Ok(data)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Get recent liquidations and redemptions, newest first
+///
+/// Decoded from TroveManager event logs via Blockscout. Fails with
+/// `[not_found]` when Blockscout cannot decode the contract's events rather
+/// than returning an empty list. Cached for 60 seconds per limit.
+#[server(GetRecentLiquidations, "/api")]
+pub async fn get_recent_liquidations(limit: Option<u32>) -> Result<Vec<LiquidationEvent>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_recent_liquidations", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::cache::caches;
+            use usdfc_core::config::config;
+            use usdfc_core::error::TerminalError;
+
+            let limit = limit.unwrap_or(25).clamp(1, 100) as usize; // Default 25, max 100
+            let cache_key = limit.to_string();
+            if let Some(cached) = caches::LIQUIDATIONS.get(&cache_key) {
+                return Ok(cached);
+            }
 
-        // Check cache first
-        let cache_key = format!("addr_{}", normalized);
-        if let Some(cached) = caches::ADDRESS_INFO.get(&cache_key) {
-            return Ok(cached);
-        }
-
-        let blockscout = BlockscoutClient::new();
-        let address_info = blockscout.get_address_usdfc_info(&normalized).await
-            .map_err(|e| {
-                tracing::error!("Blockscout error for {}: {}", address, e);
-                SfnError::ServerError(format!("Failed to fetch address info: {}", e))
-            })?;
+            let events = BlockscoutClient::new()
+                .get_liquidation_events(&config().trove_manager, limit)
+                .await
+                .map_err(|e| SfnError::from(TerminalError::from(e)))?;
 
-        // Store in cache
-        caches::ADDRESS_INFO.set(cache_key, address_info.clone());
+            caches::LIQUIDATIONS.set(cache_key, events.clone());
 
-        Ok(address_info)
+            Ok(events)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -379,45 +1283,63 @@ pub async fn get_address_info(address: String) -> Result<AddressInfo, ServerFnEr
     }
 }
 
-/// Normalize address formats for display and routing
-#[server(GetNormalizedAddress, "/api")]
-pub async fn get_normalized_address(address: String) -> Result<NormalizedAddress, ServerFnError> {
-    usdfc_core::error::ValidationError::validate_address(&address)
-        .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
+/// Get the largest stability pool depositors, largest deposit first
+///
+/// Depositors are enumerated from StabilityPool `UserDepositChanged` logs via
+/// Blockscout, and their current compounded deposits read over RPC. Fails with
+/// `[unsupported]` when Blockscout is disabled or cannot decode the pool's
+/// events. The full ranking is cached for 120 seconds.
+#[server(GetStabilityPoolDepositors, "/api")]
+pub async fn get_stability_pool_depositors(limit: Option<u32>) -> Result<Vec<PoolDepositor>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::address_conv::{evm_to_f4, f4_to_evm};
-
-        if address.starts_with("0x") {
-            let f4 = evm_to_f4(&address).map_err(SfnError::ServerError)?;
-            return Ok(NormalizedAddress {
-                input: address.clone(),
-                kind: "evm".to_string(),
-                evm: Some(address.clone()),
-                f4: Some(f4),
-                blockscout: Some(address),
-            });
-        }
-
-        if address.starts_with("f4") {
-            let evm = f4_to_evm(&address).map_err(SfnError::ServerError)?;
-            return Ok(NormalizedAddress {
-                input: address.clone(),
-                kind: "delegated".to_string(),
-                evm: Some(evm.clone()),
-                f4: Some(address),
-                blockscout: Some(evm),
-            });
-        }
-
-        if address.starts_with("f1") || address.starts_with("f3") {
-            return Err(SfnError::ServerError(
-                "f1/f3 addresses are not supported by Blockscout without conversion".to_string(),
-            ));
-        }
-
-        Err(SfnError::ServerError("unsupported address format".to_string()))
+        usdfc_backend::timing::timed("get_stability_pool_depositors", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::cache::caches;
+            use usdfc_backend::rpc::RpcClient;
+            use usdfc_core::config::config;
+            use usdfc_core::error::{ApiError, TerminalError};
+
+            let limit = limit.unwrap_or(50).clamp(1, 500) as usize; // Default 50, max 500
+
+            if !config().blockscout_enabled {
+                return Err(SfnError::from(TerminalError::Unsupported(
+                    "listing stability pool depositors needs Blockscout".to_string(),
+                )));
+            }
+
+            let mut ranked = caches::STABILITY_DEPOSITORS
+                .get_or_compute("all", || async {
+                    let depositors = BlockscoutClient::new()
+                        .get_stability_depositors(&config().stability_pool)
+                        .await
+                        .map_err(|e| match e {
+                            ApiError::NotFound { .. } => TerminalError::Unsupported(
+                                "Blockscout cannot decode StabilityPool events".to_string(),
+                            ),
+                            e => TerminalError::from(e),
+                        })
+                        .map_err(SfnError::from)?;
+
+                    let rpc = RpcClient::new();
+                    let (deposits, pool_total) = tokio::join!(
+                        rpc.get_stability_deposits(&depositors),
+                        rpc.get_stability_pool_balance(),
+                    );
+                    let deposits = depositors
+                        .into_iter()
+                        .zip(deposits)
+                        .map(|(address, deposit)| Ok((address, deposit?)))
+                        .collect::<Result<Vec<_>, ApiError>>()
+                        .map_err(|e| SfnError::from(TerminalError::from(e)))?;
+
+                    Ok::<_, SfnError>(PoolDepositor::rank(deposits, pool_total.ok()))
+                })
+                .await?;
+
+            ranked.truncate(limit);
+            Ok(ranked)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -427,86 +1349,111 @@ pub async fn get_normalized_address(address: String) -> Result<NormalizedAddress
 }
 
 // ============================================================================
-// Holders + Stability Pool Transfers
+// API Health Status
 // ============================================================================
 
 
-/// Get top USDFC holders from Blockscout
-/// Cached for 300 seconds (5 minutes) as holder list changes slowly
-/// Note: Uses cursor-based pagination, offset parameter is ignored (use REST API /api/v1/holders for pagination)
-#[server(GetTopHolders, "/api")]
-pub async fn get_top_holders(limit: Option<u32>, offset: Option<u32>) -> Result<Vec<TokenHolderInfo>, ServerFnError> {
+/// Check health of all APIs
+#[server(CheckApiHealth, "/api")]
+pub async fn check_api_health() -> Result<ApiHealthStatus, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_core::config::config;
-        use usdfc_backend::cache::caches;
-
-        let _offset = offset; // Ignored for cursor-based API
-        let cache_key = format!("holders_first_page");
-
-        // Check cache first
-        if let Some(cached) = caches::TOKEN_HOLDERS.get(&cache_key) {
-            // Take only the requested limit from cache
-            let limit = limit.unwrap_or(20).min(100) as usize;
-            return Ok(cached.into_iter().take(limit).collect());
-        }
+        usdfc_backend::timing::timed("check_api_health", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_core::config::config;
+            use usdfc_core::error::ApiError;
+            use usdfc_backend::gecko::GeckoClient;
+            use usdfc_backend::historical;
+            use usdfc_backend::rpc::RpcClient;
+            use usdfc_backend::subgraph::SubgraphClient;
+
+            let rpc = RpcClient::new();
+            let blockscout = BlockscoutClient::new();
+            let subgraph = SubgraphClient::new();
+            let gecko = GeckoClient::new();
+
+            // Check RPC by getting FIL price (simple call)
+            let rpc_result = rpc.get_fil_price().await.map(|_| ());
+
+            // Disabled sources are skipped and reported as such rather than failing
+            let cfg = config();
+
+            // Check Blockscout by getting token info
+            let blockscout_result = if cfg.blockscout_enabled {
+                Some(blockscout.gql_get_token_info(&cfg.usdfc_token).await.map(|_| ()))
+            } else {
+                None
+            };
 
-        let blockscout = BlockscoutClient::new();
+            // Check Subgraph by getting lending markets
+            let subgraph_result = if cfg.subgraph_enabled {
+                Some(subgraph.get_lending_markets().await.map(|_| ()))
+            } else {
+                None
+            };
 
-        // Fetch first page from Blockscout API (cursor-based pagination)
-        let (holders, _next_cursor) = blockscout.get_token_holders_cursor(&config().usdfc_token, None).await
-            .map_err(|e| SfnError::ServerError(format!("Blockscout API error: {}", e)))?;
+            // Check GeckoTerminal by fetching primary pool info
+            let gecko_result = if cfg.gecko_enabled {
+                Some(gecko.get_pool_info(&cfg.pool_usdfc_wfil).await.map(|_| ()))
+            } else {
+                None
+            };
 
-        let holder_info: Vec<TokenHolderInfo> = holders
+            let timed_out = [
+                ("rpc", Some(&rpc_result)),
+                ("blockscout", blockscout_result.as_ref()),
+                ("subgraph", subgraph_result.as_ref()),
+                ("gecko", gecko_result.as_ref()),
+            ]
             .into_iter()
-            .map(|h| TokenHolderInfo {
-                address: h.address,
-                balance: h.balance,
-            })
+            .filter(|(_, result)| matches!(result, Some(Err(ApiError::Timeout { .. }))))
+            .map(|(source, _)| source.to_string())
             .collect();
 
-        // Store in cache
-        caches::TOKEN_HOLDERS.set(cache_key, holder_info.clone());
-
-        // Return only requested limit
-        let limit = limit.unwrap_or(20).min(100) as usize;
-        Ok(holder_info.into_iter().take(limit).collect())
+            let rpc_ok = rpc_result.is_ok();
+            let blockscout_ok = matches!(blockscout_result, Some(Ok(())));
+            let subgraph_ok = matches!(subgraph_result, Some(Ok(())));
+            let gecko_ok = matches!(gecko_result, Some(Ok(())));
+
+            // Check historical SQLite database
+            let database_ok = historical::check_db_health().is_ok();
+
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+
+            Ok(ApiHealthStatus {
+                rpc_ok,
+                blockscout_ok,
+                subgraph_ok,
+                gecko_ok,
+                database_ok,
+                disabled_sources: cfg.disabled_sources(),
+                circuits: usdfc_backend::circuit_breaker::UPSTREAM_BREAKER.states(),
+                timed_out,
+                timestamp,
+            })
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
     {
-        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+        Err(SfnError::ServerError("SSR is required".to_string()))
     }
 }
 
-/// Get recent USDFC transfers involving the Stability Pool address
-#[server(GetStabilityPoolTransfers, "/api")]
-pub async fn get_stability_pool_transfers(limit: Option<u32>) -> Result<Vec<Transaction>, ServerFnError> {
+/// Get entry counts, TTLs and entry ages for every server-side cache
+///
+/// For debugging stale data: shows the TTLs in effect at runtime (after
+/// CACHE_TTLS overrides) and how old the cached entries are.
+#[server(GetCacheStats, "/api")]
+pub async fn get_cache_stats() -> Result<Vec<CacheStats>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_core::config::config;
-        use usdfc_backend::cache::caches;
-
-        let limit = limit.ok_or_else(|| SfnError::ServerError("limit is required".to_string()))?;
-
-        // Check cache first
-        let cache_key = format!("stability_tx_{}", limit);
-        if let Some(cached) = caches::STABILITY_TRANSFERS.get(&cache_key) {
-            return Ok(cached);
-        }
-
-        let blockscout = BlockscoutClient::new();
-        let transfers = blockscout
-            .get_address_transfers(&config().stability_pool, &config().usdfc_token, limit)
-            .await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Store in cache
-        caches::STABILITY_TRANSFERS.set(cache_key, transfers.clone());
-
-        Ok(transfers)
+        usdfc_backend::timing::timed("get_cache_stats", async move {
+            Ok(usdfc_backend::cache::caches::stats())
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -516,62 +1463,33 @@ pub async fn get_stability_pool_transfers(limit: Option<u32>) -> Result<Vec<Tran
 }
 
 // ============================================================================
-// Price Data (GeckoTerminal)
+// Holder Count
 // ============================================================================
 
-/// USDFC price and market data from DEX
-
-/// Get USDFC price data from GeckoTerminal
-/// Cached for 30 seconds to reduce API load
-#[server(GetUSDFCPriceData, "/api")]
-pub async fn get_usdfc_price_data() -> Result<USDFCPriceData, ServerFnError> {
+/// Get USDFC holder count from Blockscout
+/// Cached for 300 seconds (5 minutes) as count changes slowly
+#[server(GetHolderCount, "/api")]
+pub async fn get_holder_count() -> Result<u64, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::cache::caches;
-        use usdfc_core::config::config;
-        use usdfc_backend::gecko::GeckoClient;
-
-        // Check cache first
-        if let Some(cached) = caches::USDFC_PRICE.get("default") {
-            return Ok(cached);
-        }
-
-        let gecko = GeckoClient::new();
-        let pool_info = gecko
-            .get_pool_info(&config().pool_usdfc_wfil)
-            .await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // SAFETY: Use Option - never fallback to 1.0 for price (masks depegging)
-        let price_usd = pool_info
-            .base_token_price_usd
-            .and_then(|s| s.parse::<f64>().ok());
+        usdfc_backend::timing::timed("get_holder_count", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::cache::caches;
 
-        let price_change_24h = pool_info
-            .price_change_percentage
-            .and_then(|p| p.h24)
-            .and_then(|s| s.parse::<f64>().ok());
-
-        let volume_24h = pool_info
-            .volume_usd
-            .and_then(|v| v.h24)
-            .and_then(|s| s.parse::<f64>().ok());
-
-        let liquidity_usd = pool_info
-            .reserve_in_usd
-            .and_then(|s| s.parse::<f64>().ok());
+            // Check cache first
+            if let Some(cached) = caches::HOLDER_COUNT.get("default") {
+                return Ok(cached);
+            }
 
-        let price_data = USDFCPriceData {
-            price_usd,
-            price_change_24h,
-            volume_24h,
-            liquidity_usd,
-        };
+            let blockscout = BlockscoutClient::new();
+            let count = blockscout.get_holder_count().await
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
 
-        // Store in cache
-        caches::USDFC_PRICE.set("default".to_string(), price_data.clone());
+            // Store in cache
+            caches::HOLDER_COUNT.set("default".to_string(), count);
 
-        Ok(price_data)
+            Ok(count)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -581,95 +1499,56 @@ pub async fn get_usdfc_price_data() -> Result<USDFCPriceData, ServerFnError> {
 }
 
 // ============================================================================
-// API Health Status
+// Token Metadata
 // ============================================================================
 
-
-/// Check health of all APIs
-#[server(CheckApiHealth, "/api")]
-pub async fn check_api_health() -> Result<ApiHealthStatus, ServerFnError> {
+/// Get the USDFC token's name, symbol, decimals, total supply and address
+/// Cached for 300 seconds (5 minutes); amounts elsewhere are scaled by these decimals
+#[server(GetTokenInfo, "/api")]
+pub async fn get_token_info() -> Result<TokenInfo, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_core::config::config;
-        use usdfc_backend::gecko::GeckoClient;
-        use usdfc_backend::historical;
-        use usdfc_backend::rpc::RpcClient;
-        use usdfc_backend::subgraph::SubgraphClient;
-
-        let rpc = RpcClient::new();
-        let blockscout = BlockscoutClient::new();
-        let subgraph = SubgraphClient::new();
-        let gecko = GeckoClient::new();
-
-        // Check RPC by getting FIL price (simple call)
-        let rpc_ok = rpc.get_fil_price().await.is_ok();
-
-        // Check Blockscout by getting token info
-        let blockscout_ok = blockscout
-            .gql_get_token_info(&config().usdfc_token)
-            .await
-            .is_ok();
-
-        // Check Subgraph by getting lending markets
-        let subgraph_ok = subgraph.get_lending_markets().await.is_ok();
-
-        // Check GeckoTerminal by fetching primary pool info
-        let gecko_ok = gecko
-            .get_pool_info(&config().pool_usdfc_wfil)
-            .await
-            .is_ok();
-
-        // Check historical SQLite database
-        let database_ok = historical::check_db_health().is_ok();
-
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
-
-        Ok(ApiHealthStatus {
-            rpc_ok,
-            blockscout_ok,
-            subgraph_ok,
-            gecko_ok,
-            database_ok,
-            timestamp,
-        })
+        usdfc_backend::timing::timed("get_token_info", async move {
+            use usdfc_backend::blockscout::{usdfc_token_info, BlockscoutClient};
+            use usdfc_core::error::TerminalError;
+
+            usdfc_token_info(&BlockscoutClient::new())
+                .await
+                .map_err(|e| SfnError::from(TerminalError::from(e)))
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
     {
-        Err(SfnError::ServerError("SSR is required".to_string()))
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
     }
 }
 
 // ============================================================================
-// Holder Count
+// Network Gas
 // ============================================================================
 
-/// Get USDFC holder count from Blockscout
-/// Cached for 300 seconds (5 minutes) as count changes slowly
-#[server(GetHolderCount, "/api")]
-pub async fn get_holder_count() -> Result<u64, ServerFnError> {
+/// Get the current Filecoin base fee with the last 20 blocks' base fees and
+/// gas usage, from `eth_feeHistory`
+/// Cached for 30 seconds
+#[server(GetNetworkGas, "/api")]
+pub async fn get_network_gas() -> Result<NetworkGas, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_backend::cache::caches;
-
-        // Check cache first
-        if let Some(cached) = caches::HOLDER_COUNT.get("default") {
-            return Ok(cached);
-        }
-
-        let blockscout = BlockscoutClient::new();
-        let count = blockscout.get_holder_count().await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Store in cache
-        caches::HOLDER_COUNT.set("default".to_string(), count);
-
-        Ok(count)
+        usdfc_backend::timing::timed("get_network_gas", async move {
+            use usdfc_backend::rpc::RpcClient;
+            use usdfc_backend::cache::caches;
+            use usdfc_core::error::TerminalError;
+
+            caches::NETWORK_GAS
+                .get_or_compute("default", || async {
+                    RpcClient::new()
+                        .get_network_gas(20)
+                        .await
+                        .map_err(|e| SfnError::from(TerminalError::from(e)))
+                })
+                .await
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -689,68 +1568,108 @@ pub async fn get_holder_count() -> Result<u64, ServerFnError> {
 pub async fn get_order_book(maturity: Option<String>) -> Result<OrderBookData, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::subgraph::SubgraphClient;
-        use usdfc_core::config::config;
-        use usdfc_backend::cache::caches;
-
-        // Check cache first
-        let cache_key = format!("order_book_{}", maturity.as_deref().unwrap_or("default"));
-        if let Some(cached) = caches::ORDER_BOOK.get(&cache_key) {
-            return Ok(cached);
-        }
-
-        let subgraph = SubgraphClient::new();
-        let currency = &config().currency_usdfc;
-        let maturity_ref = maturity.as_deref();
-
-        let book = subgraph.get_order_book(currency, maturity_ref, 100).await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Convert orders to display format - skip orders with invalid data instead of using fake values
-        let convert_order = |o: &usdfc_backend::subgraph::Order| -> Option<OrderData> {
-            let amount = o.input_amount.parse::<f64>().ok()? / 1e18;
-            let filled = o.filled_amount.parse::<f64>().ok()? / 1e18;
-            let price = o.input_unit_price.parse::<f64>().ok()? / 10000.0;
-            let maturity_ts = o.maturity.parse::<i64>().ok()?;
-            let apr = usdfc_backend::subgraph::unit_price_to_apr(&o.input_unit_price, maturity_ts).ok()?;
-
-            Some(OrderData {
-                id: o.id.clone(),
-                side: if o.side == 0 { "Lend".to_string() } else { "Borrow".to_string() },
-                amount,
-                filled,
-                price,
-                apr,
-                user: o.user.clone(),
-                created_at: o.created_at.clone(),
-            })
-        };
+        usdfc_backend::timing::timed("get_order_book", async move {
+            use usdfc_backend::subgraph::SubgraphClient;
+            use usdfc_core::format::{decimal_to_f64, parse_token_amount};
+            use usdfc_backend::cache::caches;
+
+            // Check cache first
+            let cache_key = format!("order_book_{}", maturity.as_deref().unwrap_or("default"));
+            if let Some(cached) = caches::ORDER_BOOK.get(&cache_key) {
+                return Ok(cached);
+            }
+
+            let subgraph = SubgraphClient::new();
+            let currency = &Currency::Usdfc;
+            let maturity_ref = maturity.as_deref();
+
+            let book = subgraph.get_order_book(currency, maturity_ref, 100).await
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+            // Convert orders to display format - skip orders with invalid data instead of using fake values
+            let convert_order = |o: &usdfc_backend::subgraph::Order| -> Option<OrderData> {
+                let amount = decimal_to_f64(parse_token_amount(&o.input_amount, 18)?);
+                let filled = decimal_to_f64(parse_token_amount(&o.filled_amount, 18)?);
+                let price = o.input_unit_price.parse::<f64>().ok()? / 10000.0;
+                let maturity_ts = o.maturity.parse::<i64>().ok()?;
+                let apr = usdfc_backend::subgraph::unit_price_to_apr(&o.input_unit_price, maturity_ts).ok()?;
+
+                Some(OrderData {
+                    id: o.id.clone(),
+                    side: if o.side == 0 { "Lend".to_string() } else { "Borrow".to_string() },
+                    amount,
+                    filled,
+                    price,
+                    apr,
+                    user: o.user.clone(),
+                    created_at: o.created_at.clone(),
+                })
+            };
 
-        let lend_orders: Vec<OrderData> = book.lend_orders.iter().filter_map(convert_order).collect();
-        let borrow_orders: Vec<OrderData> = book.borrow_orders.iter().filter_map(convert_order).collect();
+            let lend_orders: Vec<OrderData> = book.lend_orders.iter().filter_map(convert_order).collect();
+            let borrow_orders: Vec<OrderData> = book.borrow_orders.iter().filter_map(convert_order).collect();
+
+            // Calculate best prices and spread
+            let best_lend_price = lend_orders.first().map(|o| o.price);
+            let best_borrow_price = borrow_orders.first().map(|o| o.price);
+            let spread_bps = OrderBookData::spread_between(best_lend_price, best_borrow_price);
+
+            // Order flow over unfilled remainders
+            let lend_flow = OrderFlowSide::from_orders(&lend_orders);
+            let borrow_flow = OrderFlowSide::from_orders(&borrow_orders);
+            let imbalance = OrderBookData::volume_imbalance(&lend_flow, &borrow_flow);
+
+            let order_book_data = OrderBookData {
+                currency: book.currency,
+                maturity: book.maturity,
+                lend_orders,
+                borrow_orders,
+                best_lend_price,
+                best_borrow_price,
+                spread_bps,
+                lend_flow,
+                borrow_flow,
+                imbalance,
+            };
 
-        // Calculate best prices and spread
-        let best_lend_price = lend_orders.first().map(|o| o.price);
-        let best_borrow_price = borrow_orders.first().map(|o| o.price);
-        let spread_bps = match (best_lend_price, best_borrow_price) {
-            (Some(lend), Some(borrow)) => Some((borrow - lend) * 10000.0),
-            _ => None,
-        };
+            // Store in cache
+            caches::ORDER_BOOK.set(cache_key, order_book_data.clone());
 
-        let order_book_data = OrderBookData {
-            currency: "USDFC".to_string(),
-            maturity: book.maturity,
-            lend_orders,
-            borrow_orders,
-            best_lend_price,
-            best_borrow_price,
-            spread_bps,
-        };
+            Ok(order_book_data)
+        }).await
+    }
 
-        // Store in cache
-        caches::ORDER_BOOK.set(cache_key, order_book_data.clone());
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
 
-        Ok(order_book_data)
+/// Get the recorded USDFC order book spread (bps) over a lookback window
+///
+/// There is no historical order book, so points come from the metric
+/// snapshots, which record the top of book each collector interval. Oldest
+/// first; intervals where either side of the book was empty are skipped.
+/// Not cached.
+#[server(GetSpreadHistory, "/api")]
+pub async fn get_spread_history(lookback: ChartLookback) -> Result<Vec<(i64, f64)>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_spread_history", async move {
+            use usdfc_backend::historical::spread_range;
+            use usdfc_core::error::TerminalError;
+
+            let end = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let start = lookback.cutoff_from(end).unwrap_or(0);
+
+            spread_range(start, end).map_err(|e| {
+                tracing::error!("Failed to read spread history: {}", e);
+                SfnError::from(TerminalError::Internal(e.to_string()))
+            })
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -771,49 +1690,50 @@ pub async fn get_recent_lending_trades(limit: Option<i32>) -> Result<Vec<Lending
 
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::subgraph::SubgraphClient;
-        use usdfc_backend::subgraph::decode_currency;
-        use usdfc_backend::cache::caches;
-
-        // Check cache first
-        let cache_key = format!("lending_trades_{}", limit);
-        if let Some(cached) = caches::LENDING_TRADES.get(&cache_key) {
-            return Ok(cached);
-        }
+        usdfc_backend::timing::timed("get_recent_lending_trades", async move {
+            use usdfc_backend::subgraph::SubgraphClient;
+            use usdfc_backend::cache::caches;
+
+            // Check cache first
+            let cache_key = format!("lending_trades_{}", limit);
+            if let Some(cached) = caches::LENDING_TRADES.get(&cache_key) {
+                return Ok(cached);
+            }
 
-        let subgraph = SubgraphClient::new();
-        let transactions = subgraph.get_recent_transactions(limit).await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
+            let subgraph = SubgraphClient::new();
+            let transactions = subgraph.get_recent_transactions(limit).await
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
 
-        let trades: Vec<LendingTradeData> = transactions
-            .into_iter()
-            .filter_map(|tx| {
-                let amount = tx.amount.parse::<f64>().ok()? / 1e18;
-                let price = tx.execution_price.as_ref()?.parse::<f64>().ok()? / 10000.0;
-                let maturity_ts = tx.maturity.parse::<i64>().ok()?;
-                let timestamp = tx.created_at.parse::<i64>().ok()?;
-                let apr = usdfc_backend::subgraph::unit_price_to_apr(
-                    tx.execution_price.as_ref()?,
-                    maturity_ts
-                ).unwrap_or(0.0);
-
-                Some(LendingTradeData {
-                    id: tx.id,
-                    currency: decode_currency(&tx.currency),
-                    maturity: tx.maturity,
-                    side: if tx.side == 0 { "Lend".to_string() } else { "Borrow".to_string() },
-                    amount,
-                    price,
-                    apr,
-                    timestamp,
+            let trades: Vec<LendingTradeData> = transactions
+                .into_iter()
+                .filter_map(|tx| {
+                    let amount = tx.amount.parse::<f64>().ok()? / 1e18;
+                    let price = tx.execution_price.as_ref()?.parse::<f64>().ok()? / 10000.0;
+                    let maturity_ts = tx.maturity.parse::<i64>().ok()?;
+                    let timestamp = tx.created_at.parse::<i64>().ok()?;
+                    let apr = usdfc_backend::subgraph::unit_price_to_apr(
+                        tx.execution_price.as_ref()?,
+                        maturity_ts
+                    ).unwrap_or(0.0);
+
+                    Some(LendingTradeData {
+                        id: tx.id,
+                        currency: Currency::from_bytes32(&tx.currency),
+                        maturity: tx.maturity,
+                        side: if tx.side == 0 { "Lend".to_string() } else { "Borrow".to_string() },
+                        amount,
+                        price,
+                        apr,
+                        timestamp,
+                    })
                 })
-            })
-            .collect();
+                .collect();
 
-        // Store in cache
-        caches::LENDING_TRADES.set(cache_key, trades.clone());
+            // Store in cache
+            caches::LENDING_TRADES.set(cache_key, trades.clone());
 
-        Ok(trades)
+            Ok(trades)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -826,23 +1746,43 @@ pub async fn get_recent_lending_trades(limit: Option<i32>) -> Result<Vec<Lending
 // Advanced Chart Data (All Metrics)
 // ============================================================================
 
+/// Await `fut`, returning its output and how long it took in milliseconds
+#[cfg(feature = "ssr")]
+async fn elapsed_ms<F: std::future::Future>(fut: F) -> (F::Output, u32) {
+    let start = std::time::Instant::now();
+    let output = fut.await;
+    (output, start.elapsed().as_millis() as u32)
+}
+
+/// Record a call timed with `elapsed_ms` under `source`, returning its output
+#[cfg(feature = "ssr")]
+fn record_timing<T>(timings: &mut std::collections::BTreeMap<String, u32>, source: &str, (output, ms): (T, u32)) -> T {
+    timings.insert(source.to_string(), ms);
+    output
+}
+
 /// Calculate TCR time series from price history
 /// TCR = (Collateral_FIL × FIL_Price_USD) / Supply_USDFC × 100
+///
+/// Supply and collateral stay in Decimal; only the final ratio becomes f64.
 #[cfg(feature = "ssr")]
 fn calculate_tcr_from_price_history(
     price_candles: &[TVCandle],
-    supply: f64,
-    collateral_fil: f64,
+    supply: Decimal,
+    collateral_fil: Decimal,
 ) -> Vec<(i64, f64)> {
+    use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
     price_candles
         .iter()
         .map(|candle| {
             // TCR = (Collateral × FIL_Price) / Supply × 100
-            let tcr = if supply > 0.0 {
-                (collateral_fil * candle.close) / supply * 100.0
-            } else {
-                0.0
-            };
+            let tcr = Decimal::from_f64(candle.close)
+                .filter(|_| supply > Decimal::ZERO)
+                .and_then(|price| collateral_fil.checked_mul(price))
+                .and_then(|value| value.checked_div(supply))
+                .and_then(|ratio| (ratio * Decimal::ONE_HUNDRED).to_f64())
+                .unwrap_or(0.0);
             (candle.time, tcr)
         })
         .collect()
@@ -870,341 +1810,429 @@ fn calculate_liquidity_from_volume_impact(
 /// Get comprehensive chart data with all metrics for advanced chart
 /// Fetches real data from GeckoTerminal, RPC, Blockscout, and Subgraph
 /// Uses historical snapshot storage for metrics over time
+/// `ma_windows` selects the SMA/EMA overlay windows (defaults to MA20/MA50)
+/// Per-upstream call durations are reported in `timings`
 #[server(GetAdvancedChartData, "/api")]
 pub async fn get_advanced_chart_data(
     resolution: ChartResolution,
     lookback: ChartLookback,
     start: Option<i64>,
     end: Option<i64>,
+    ma_windows: Option<Vec<u32>>,
 ) -> Result<ChartDataResponse, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::gecko::GeckoClient;
-        use usdfc_backend::rpc::RpcClient;
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_backend::subgraph::SubgraphClient;
-        use usdfc_core::config::config;
-        use usdfc_backend::historical::MetricSnapshot;
-        use usdfc_backend::cache::caches;
-        use std::time::{SystemTime, UNIX_EPOCH, Instant};
-        use rust_decimal::prelude::ToPrimitive;
-
-        // Generate cache key from parameters
-        let cache_key = format!(
-            "chart_{}_{}_{}_{}",
-            resolution.label(),
-            lookback.label(),
-            start.unwrap_or(0),
-            end.unwrap_or(0)
-        );
-
-        // Check cache first
-        if let Some(cached) = caches::ADVANCED_CHART_DATA.get(&cache_key) {
-            return Ok(cached);
-        }
-
-        let timer_start = Instant::now();
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
-
-        // Initialize clients
-        let gecko = GeckoClient::new();
-        let rpc = RpcClient::new();
-        let blockscout = BlockscoutClient::new();
-        let subgraph = SubgraphClient::new();
-
-        // Get resolution parameters for GeckoTerminal
-        let (timeframe, aggregate, limit) = resolution.gecko_params();
-
-        // Calculate effective lookback in minutes for historical sources.
-        // If a custom start is provided, ensure we cover at least that span.
-        let configured_lookback_mins = lookback.minutes();
-        let resolution_mins = resolution.minutes();
-
-        let mut effective_lookback_mins = if let Some(custom_start) = start {
-            let diff_secs = now.saturating_sub(custom_start);
-            let span_mins = ((diff_secs / 60).max(1)) as u32;
-            if configured_lookback_mins == 0 {
-                // "All" lookback: use the span implied by the custom range.
-                span_mins
-            } else {
-                configured_lookback_mins.max(span_mins)
+        usdfc_backend::timing::timed("get_advanced_chart_data", async move {
+            use usdfc_backend::gecko::GeckoClient;
+            use usdfc_backend::rpc::RpcClient;
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::subgraph::SubgraphClient;
+            use usdfc_core::config::config;
+            use usdfc_backend::historical::MetricSnapshot;
+            use usdfc_backend::cache::caches;
+            use std::time::{SystemTime, UNIX_EPOCH, Instant};
+            use usdfc_core::format::decimal_to_f64_checked;
+
+            // Moving-average windows: 2..=500 candles, at most four distinct windows
+            let mut ma_windows: Vec<u32> = ma_windows
+                .unwrap_or_else(|| DEFAULT_MA_WINDOWS.to_vec())
+                .into_iter()
+                .filter(|w| (2..=500).contains(w))
+                .collect();
+            ma_windows.sort_unstable();
+            ma_windows.dedup();
+            ma_windows.truncate(4);
+
+            // Shorten lookbacks the resolution cannot cover (e.g. 1m candles over "all")
+            let combo = validate_combo(resolution, lookback, &config().chart_lookback_overrides);
+            if let Some(reason) = &combo.reason {
+                tracing::info!("Adjusted chart request: {}", reason);
             }
-        } else {
-            configured_lookback_mins
-        };
-
-        // CRITICAL: Enforce API safety limits to prevent data loss
-        let max_safe_lookback = resolution.max_safe_lookback_mins();
-        if effective_lookback_mins > max_safe_lookback {
-            tracing::warn!(
-                "Lookback {} mins exceeds safe limit {} mins for resolution {:?}. Clamping to safe limit.",
-                effective_lookback_mins,
-                max_safe_lookback,
-                resolution
+            let lookback = combo.lookback;
+
+            // Generate cache key from parameters
+            let cache_key = format!(
+                "chart_{}_{}_{}_{}_ma{:?}",
+                resolution.label(),
+                lookback.label(),
+                start.unwrap_or(0),
+                end.unwrap_or(0),
+                ma_windows
             );
-            effective_lookback_mins = max_safe_lookback;
-        }
-
-        // Determine how many OHLCV points to request from GeckoTerminal.
-        let data_points = if effective_lookback_mins == 0 {
-            // "All" – use API maximum.
-            limit
-        } else {
-            ((effective_lookback_mins / resolution_mins).max(1) as u32).min(limit)
-        };
-
-        // Fetch all data in parallel
-        let pool_address = &config().pool_usdfc_wfil;
-
-        // Parallel fetch: OHLCV, pool info, current metrics for display, transfer history, collateral
-        let (ohlcv_result, pool_result, tcr_result, supply_result, collateral_result, holder_result, transfers_by_period) = tokio::join!(
-            gecko.get_pool_ohlcv(pool_address, timeframe, aggregate, data_points),
-            gecko.get_pool_info(pool_address),
-            rpc.get_tcr(),
-            rpc.get_total_supply(),
-            rpc.get_active_pool_eth(),
-            blockscout.get_holder_count(),
-            blockscout.get_transfer_counts_by_period(resolution_mins, effective_lookback_mins)
-        );
-
-        // Process price candles from OHLCV data - propagate error if API fails
-        let mut price_candles: Vec<TVCandle> = ohlcv_result
-            .map_err(|e| SfnError::ServerError(format!("GeckoTerminal OHLCV error: {}", e)))?
-            .into_iter()
-            .map(|o| TVCandle {
-                time: o.timestamp,
-                open: o.open,
-                high: o.high,
-                low: o.low,
-                close: o.close,
-                volume: o.volume,
-            })
-            .collect();
 
-        // If a custom time range is provided, filter candles to that range.
-        if let Some(custom_start) = start {
-            let effective_end = end.unwrap_or(now);
-            price_candles.retain(|c| c.time >= custom_start && c.time <= effective_end);
-        }
+            // Check cache first
+            if let Some(cached) = caches::ADVANCED_CHART_DATA.get(&cache_key) {
+                return Ok(cached);
+            }
 
-        // Extract volume data from candles
-        let volume_data: Vec<(i64, f64)> = price_candles
-            .iter()
-            .map(|c| (c.time, c.volume))
-            .collect();
+            let timer_start = Instant::now();
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+
+            // Initialize clients
+            let gecko = GeckoClient::new();
+            let rpc = RpcClient::new();
+            let blockscout = BlockscoutClient::new();
+            let subgraph = SubgraphClient::new();
+
+            // Get resolution parameters for GeckoTerminal
+            let (timeframe, aggregate, limit) = resolution.gecko_params();
+
+            // Calculate effective lookback in minutes for historical sources.
+            // If a custom start is provided, ensure we cover at least that span.
+            let configured_lookback_mins = lookback.minutes();
+            let resolution_mins = resolution.minutes();
+
+            let mut effective_lookback_mins = if let Some(custom_start) = start {
+                let diff_secs = now.saturating_sub(custom_start);
+                let span_mins = ((diff_secs / 60).max(1)) as u32;
+                if configured_lookback_mins == 0 {
+                    // "All" lookback: use the span implied by the custom range.
+                    span_mins
+                } else {
+                    configured_lookback_mins.max(span_mins)
+                }
+            } else {
+                configured_lookback_mins
+            };
+
+            // CRITICAL: Enforce API safety limits to prevent data loss
+            let max_safe_lookback = resolution.max_lookback_mins(&config().chart_lookback_overrides);
+            if effective_lookback_mins > max_safe_lookback {
+                tracing::warn!(
+                    "Lookback {} mins exceeds safe limit {} mins for resolution {:?}. Clamping to safe limit.",
+                    effective_lookback_mins,
+                    max_safe_lookback,
+                    resolution
+                );
+                effective_lookback_mins = max_safe_lookback;
+            }
+
+            // A raised lookback limit (paid tier) needs more candles per request
+            let limit = limit.max(max_safe_lookback / resolution_mins);
+
+            // Determine how many OHLCV points to request from GeckoTerminal.
+            let data_points = if effective_lookback_mins == 0 {
+                // "All" – use API maximum.
+                limit
+            } else {
+                ((effective_lookback_mins / resolution_mins).max(1) as u32).min(limit)
+            };
+
+            // Fetch all data in parallel
+            let pool_address = &config().pool_usdfc_wfil;
+
+            // Parallel fetch: OHLCV, pool info, current metrics for display, transfer history, collateral
+            // Disabled sources are skipped entirely (None) so their metrics are omitted
+            let cfg = config();
+            // Each call is timed for the per-source `timings` breakdown
+            let mut timings = std::collections::BTreeMap::new();
+            let (ohlcv_result, pool_result, tcr_result, supply_result, collateral_result, holder_result, transfers_by_period) = tokio::join!(
+                async {
+                    if cfg.gecko_enabled {
+                        Some(elapsed_ms(gecko.get_pool_ohlcv(pool_address, timeframe, aggregate, data_points)).await)
+                    } else {
+                        None
+                    }
+                },
+                async {
+                    if cfg.gecko_enabled {
+                        Some(elapsed_ms(gecko.get_pool_info(pool_address)).await)
+                    } else {
+                        None
+                    }
+                },
+                elapsed_ms(rpc.get_tcr()),
+                elapsed_ms(rpc.get_total_supply()),
+                elapsed_ms(rpc.get_active_pool_eth()),
+                async {
+                    if cfg.blockscout_enabled {
+                        Some(elapsed_ms(blockscout.get_holder_count()).await)
+                    } else {
+                        None
+                    }
+                },
+                async {
+                    if cfg.blockscout_enabled {
+                        Some(elapsed_ms(blockscout.get_transfer_counts_by_period(resolution_mins, effective_lookback_mins)).await)
+                    } else {
+                        None
+                    }
+                }
+            );
+            let ohlcv_result = ohlcv_result.map(|r| record_timing(&mut timings, "gecko_ohlcv", r));
+            let pool_result = pool_result.map(|r| record_timing(&mut timings, "gecko_pool", r));
+            let tcr_result = record_timing(&mut timings, "rpc_tcr", tcr_result);
+            let supply_result = record_timing(&mut timings, "rpc_supply", supply_result);
+            let collateral_result = record_timing(&mut timings, "rpc_collateral", collateral_result);
+            let holder_result = holder_result.map(|r| record_timing(&mut timings, "blockscout_holders", r));
+            let transfers_by_period = transfers_by_period.map(|r| record_timing(&mut timings, "blockscout_transfers", r));
+
+            // Process price candles from OHLCV data - propagate error if API fails,
+            // no candles when GeckoTerminal is disabled
+            let mut price_candles: Vec<TVCandle> = ohlcv_result
+                .transpose()
+                .map_err(|e| SfnError::ServerError(format!("GeckoTerminal OHLCV error: {}", e)))?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|o| TVCandle {
+                    time: o.timestamp,
+                    open: o.open,
+                    high: o.high,
+                    low: o.low,
+                    close: o.close,
+                    volume: o.volume,
+                })
+                .collect();
 
-        // SAFETY: Use Option for all metrics - never fake fallback values
-        // Get current price and liquidity from pool info
-        let (current_price, current_liquidity, current_volume_24h) = match pool_result {
-            Ok(pool) => {
-                let price = pool.base_token_price_usd
-                    .and_then(|s| s.parse::<f64>().ok());
-                let liquidity = pool.reserve_in_usd
-                    .and_then(|s| s.parse::<f64>().ok());
-                let volume = pool.volume_usd
-                    .and_then(|v| v.h24)
-                    .and_then(|s| s.parse::<f64>().ok());
-                (price, liquidity, volume)
+            // If a custom time range is provided, filter candles to that range.
+            if let Some(custom_start) = start {
+                let effective_end = end.unwrap_or(now);
+                price_candles.retain(|c| c.time >= custom_start && c.time <= effective_end);
             }
-            // API failure = None, not fake values
-            Err(_) => (None, None, None)
-        };
-
-        // Get current metric values (for display) - None if unavailable
-        let current_tcr = tcr_result.ok().and_then(|v| v.to_f64());
-        let current_supply = supply_result.ok().and_then(|v| v.to_f64());
-        let current_collateral = collateral_result.ok().and_then(|v| v.to_f64());
-        let current_holders = holder_result.ok();
-
-        // Get lending/borrowing APRs - None if API fails
-        let (current_lend_apr, current_borrow_apr): (Option<f64>, Option<f64>) = {
-            let markets = subgraph.get_lending_markets().await;
-            match markets {
-                Ok(market_list) => {
-                    let mut best_lend: Option<f64> = None;
-                    let mut best_borrow: Option<f64> = None;
-                    for market in market_list {
-                        if market.is_active {
-                            // Skip markets with invalid maturity instead of using fake 0
-                            let maturity_ts = match market.maturity.parse::<i64>() {
-                                Ok(ts) => ts,
-                                Err(_) => continue,
-                            };
-                            if let Some(ref lend_price) = market.last_lend_unit_price {
-                                if let Ok(apr) = usdfc_backend::subgraph::unit_price_to_apr(lend_price, maturity_ts) {
-                                    best_lend = Some(best_lend.map_or(apr, |v| v.max(apr)));
+
+            // SMA/EMA overlays over the (range-filtered) candles
+            let moving_averages: Vec<MovingAverageSeries> = ma_windows
+                .iter()
+                .flat_map(|&w| {
+                    [MovingAverageKind::Simple, MovingAverageKind::Exponential]
+                        .map(|kind| MovingAverageSeries::compute(kind, w, &price_candles))
+                })
+                .collect();
+
+            // Extract volume data from candles
+            let volume_data: Vec<(i64, f64)> = price_candles
+                .iter()
+                .map(|c| (c.time, c.volume))
+                .collect();
+
+            // SAFETY: Use Option for all metrics - never fake fallback values
+            // Get current price and liquidity from pool info
+            let (current_price, current_liquidity, current_volume_24h) = match pool_result {
+                Some(Ok(pool)) => {
+                    let price = pool.base_token_price_usd
+                        .and_then(|s| s.parse::<f64>().ok());
+                    let liquidity = pool.reserve_in_usd
+                        .and_then(|s| s.parse::<f64>().ok());
+                    let volume = pool.volume_usd
+                        .and_then(|v| v.h24)
+                        .and_then(|s| s.parse::<f64>().ok());
+                    (price, liquidity, volume)
+                }
+                // API failure or disabled source = None, not fake values
+                _ => (None, None, None)
+            };
+
+            // Get current metric values (for display) - None if unavailable
+            // Supply and collateral stay Decimal for the TCR calculation
+            let supply = supply_result.ok();
+            let collateral = collateral_result.ok();
+            let current_tcr = tcr_result.ok().map(|v| decimal_to_f64_checked(v, "tcr"));
+            let current_supply = supply.map(|v| decimal_to_f64_checked(v, "total_supply"));
+            let current_holders = holder_result.and_then(|r| r.ok());
+
+            // Get lending/borrowing APRs - None if API fails
+            let (current_lend_apr, current_borrow_apr): (Option<f64>, Option<f64>) = {
+                let markets = if cfg.subgraph_enabled {
+                    let timed = elapsed_ms(subgraph.get_lending_markets()).await;
+                    Some(record_timing(&mut timings, "subgraph_markets", timed))
+                } else {
+                    None
+                };
+                match markets {
+                    Some(Ok(market_list)) => {
+                        let mut best_lend: Option<f64> = None;
+                        let mut best_borrow: Option<f64> = None;
+                        for market in market_list {
+                            if market.is_active {
+                                // Skip markets with invalid maturity instead of using fake 0
+                                let maturity_ts = match market.maturity.parse::<i64>() {
+                                    Ok(ts) => ts,
+                                    Err(_) => continue,
+                                };
+                                if let Some(ref lend_price) = market.last_lend_unit_price {
+                                    if let Ok(apr) = usdfc_backend::subgraph::unit_price_to_apr(lend_price, maturity_ts) {
+                                        best_lend = Some(best_lend.map_or(apr, |v| v.max(apr)));
+                                    }
                                 }
-                            }
-                            if let Some(ref borrow_price) = market.last_borrow_unit_price {
-                                if let Ok(apr) = usdfc_backend::subgraph::unit_price_to_apr(borrow_price, maturity_ts) {
-                                    best_borrow = Some(best_borrow.map_or(apr, |v| v.max(apr)));
+                                if let Some(ref borrow_price) = market.last_borrow_unit_price {
+                                    if let Ok(apr) = usdfc_backend::subgraph::unit_price_to_apr(borrow_price, maturity_ts) {
+                                        best_borrow = Some(best_borrow.map_or(apr, |v| v.max(apr)));
+                                    }
                                 }
                             }
                         }
+                        (best_lend, best_borrow)
                     }
-                    (best_lend, best_borrow)
+                    // API failure or disabled source = None, not fake 0.0
+                    _ => (None, None)
                 }
-                // API failure = None, not fake 0.0
-                Err(_) => (None, None)
-            }
-        };
+            };
 
-        // === BUILD TIME SERIES FROM HISTORICAL SNAPSHOTS ===
-        // Use real historical data when available, fallback to current value for fresh deployments
-        let raw_snapshots = MetricSnapshot::get_history(effective_lookback_mins, resolution_mins);
+            // === BUILD TIME SERIES FROM HISTORICAL SNAPSHOTS ===
+            // Use real historical data when available, fallback to current value for fresh deployments
+            let raw_snapshots = MetricSnapshot::get_history(effective_lookback_mins, resolution_mins);
+
+            // If a custom range is provided, filter snapshots to that range.
+            let snapshots = if let Some(custom_start) = start {
+                let effective_end = end.unwrap_or(now);
+                raw_snapshots
+                    .into_iter()
+                    .filter(|s| s.timestamp >= custom_start && s.timestamp <= effective_end)
+                    .collect::<Vec<_>>()
+            } else {
+                raw_snapshots
+            };
 
-        // If a custom range is provided, filter snapshots to that range.
-        let snapshots = if let Some(custom_start) = start {
-            let effective_end = end.unwrap_or(now);
-            raw_snapshots
-                .into_iter()
-                .filter(|s| s.timestamp >= custom_start && s.timestamp <= effective_end)
-                .collect::<Vec<_>>()
-        } else {
-            raw_snapshots
-        };
-
-        // Helper function to ensure every metric has at least current value
-        let ensure_data = |mut series: Vec<(i64, f64)>, current_value: Option<f64>| -> Vec<(i64, f64)> {
-            if series.is_empty() {
-                // No historical data - use current value as single point if available
-                if let Some(val) = current_value {
-                    vec![(now, val)]
+            let snapshot_interval = usdfc_backend::historical::snapshot_interval_secs() as i64;
+
+            // Helper function to ensure every metric has at least current value
+            let ensure_data = |mut series: Vec<(i64, f64)>, current_value: Option<f64>| -> Vec<(i64, f64)> {
+                if series.is_empty() {
+                    // No historical data - use current value as single point if available
+                    if let Some(val) = current_value {
+                        vec![(now, val)]
+                    } else {
+                        vec![]
+                    }
                 } else {
-                    vec![]
-                }
-            } else {
-                // Have historical data - optionally append current value if newer
-                if let Some(val) = current_value {
-                    let last_ts = series.last().map(|(ts, _)| *ts).unwrap_or(0);
-                    if now > last_ts + 120 {
-                        series.push((now, val));
+                    // Have historical data - optionally append current value if newer
+                    if let Some(val) = current_value {
+                        let last_ts = series.last().map(|(ts, _)| *ts).unwrap_or(0);
+                        // Only when the last snapshot is more than two collector intervals old
+                        if now > last_ts + 2 * snapshot_interval {
+                            series.push((now, val));
+                        }
                     }
+                    series
                 }
-                series
-            }
-        };
+            };
 
-        // Extract series from snapshots with current value fallback
-        // OPTIMIZED: Calculate TCR from price history instead of snapshots (2.63% variation!)
-        let tcr_data = if let (Some(supply), Some(collateral)) = (current_supply, current_collateral) {
-            if !price_candles.is_empty() && supply > 0.0 && collateral > 0.0 {
-                calculate_tcr_from_price_history(&price_candles, supply, collateral)
+            // Extract series from snapshots with current value fallback
+            // OPTIMIZED: Calculate TCR from price history instead of snapshots (2.63% variation!)
+            let tcr_data = if let (Some(supply), Some(collateral)) = (supply, collateral) {
+                if !price_candles.is_empty() && supply > Decimal::ZERO && collateral > Decimal::ZERO {
+                    calculate_tcr_from_price_history(&price_candles, supply, collateral)
+                } else {
+                    // Fallback to snapshots if calculation not possible
+                    ensure_data(
+                        MetricSnapshot::tcr_series(&snapshots),
+                        current_tcr
+                    )
+                }
             } else {
-                // Fallback to snapshots if calculation not possible
+                // Fallback to snapshots if we don't have supply/collateral
                 ensure_data(
                     MetricSnapshot::tcr_series(&snapshots),
                     current_tcr
                 )
-            }
-        } else {
-            // Fallback to snapshots if we don't have supply/collateral
-            ensure_data(
-                MetricSnapshot::tcr_series(&snapshots),
-                current_tcr
-            )
-        };
-
-        let supply_data = ensure_data(
-            MetricSnapshot::supply_series(&snapshots),
-            current_supply
-        );
-
-        // OPTIMIZED: Calculate liquidity from volume/impact (632% variation - DRAMATIC curves!)
-        let liquidity_data = if !price_candles.is_empty() {
-            let calculated = calculate_liquidity_from_volume_impact(&price_candles);
-            if calculated.len() > 10 {
-                // Use calculated liquidity if we have enough data points
-                calculated
+            };
+
+            let supply_data = ensure_data(
+                MetricSnapshot::supply_series(&snapshots),
+                current_supply
+            );
+
+            // OPTIMIZED: Calculate liquidity from volume/impact (632% variation - DRAMATIC curves!)
+            let liquidity_data = if !price_candles.is_empty() {
+                let calculated = calculate_liquidity_from_volume_impact(&price_candles);
+                if calculated.len() > 10 {
+                    // Use calculated liquidity if we have enough data points
+                    calculated
+                } else {
+                    // Fallback to snapshots if calculation didn't yield enough points
+                    ensure_data(
+                        MetricSnapshot::liquidity_series(&snapshots),
+                        current_liquidity
+                    )
+                }
             } else {
-                // Fallback to snapshots if calculation didn't yield enough points
+                // Fallback to snapshots if no price candles
                 ensure_data(
                     MetricSnapshot::liquidity_series(&snapshots),
                     current_liquidity
                 )
-            }
-        } else {
-            // Fallback to snapshots if no price candles
-            ensure_data(
-                MetricSnapshot::liquidity_series(&snapshots),
-                current_liquidity
-            )
-        };
-
-        let holders_data: Vec<(i64, u64)> = if snapshots.is_empty() {
-            if let Some(h) = current_holders {
-                vec![(now, h)]
+            };
+
+            let holders_data: Vec<(i64, u64)> = if snapshots.is_empty() {
+                if let Some(h) = current_holders {
+                    vec![(now, h)]
+                } else {
+                    vec![]
+                }
             } else {
-                vec![]
-            }
-        } else {
-            let mut series = MetricSnapshot::holders_series(&snapshots);
-            if let Some(h) = current_holders {
-                let last_ts = series.last().map(|(ts, _)| *ts).unwrap_or(0);
-                if now > last_ts + 120 {
-                    series.push((now, h));
+                let mut series = MetricSnapshot::holders_series(&snapshots);
+                if let Some(h) = current_holders {
+                    let last_ts = series.last().map(|(ts, _)| *ts).unwrap_or(0);
+                    // Only when the last snapshot is more than two collector intervals old
+                    if now > last_ts + 2 * snapshot_interval {
+                        series.push((now, h));
+                    }
                 }
-            }
-            series
-        };
-
-        let lend_apr_data = ensure_data(
-            MetricSnapshot::lend_apr_series(&snapshots),
-            current_lend_apr
-        );
-
-        let borrow_apr_data = ensure_data(
-            MetricSnapshot::borrow_apr_series(&snapshots),
-            current_borrow_apr
-        );
-
-        // Transfer counts from Blockscout aggregation (real historical data)
-        let raw_transfers: Vec<(i64, u64)> = transfers_by_period.unwrap_or_default();
-        let transfers_data: Vec<(i64, u64)> = if let Some(custom_start) = start {
-            let effective_end = end.unwrap_or(now);
-            raw_transfers
-                .into_iter()
-                .filter(|(ts, _)| *ts >= custom_start && *ts <= effective_end)
-                .collect()
-        } else {
-            raw_transfers
-        };
-
-        let fetch_time_ms = timer_start.elapsed().as_millis() as u32;
-
-        let response = ChartDataResponse {
-            resolution,
-            lookback,
-            generated_at: now,
-            fetch_time_ms,
-            price_candles,
-            volume_data,
-            liquidity_data,
-            tcr_data,
-            supply_data,
-            holders_data,
-            lend_apr_data,
-            borrow_apr_data,
-            transfers_data,
-            current_price,
-            current_volume_24h,
-            current_liquidity,
-            current_tcr,
-            current_supply,
-            current_holders,
-            current_lend_apr,
-            current_borrow_apr,
-            snapshot_count: snapshots.len(),
-            oldest_snapshot_time: snapshots.first().map(|s| s.timestamp),
-        };
-
-        // Store in cache
-        caches::ADVANCED_CHART_DATA.set(cache_key, response.clone());
-
-        Ok(response)
+                series
+            };
+
+            let lend_apr_data = ensure_data(
+                MetricSnapshot::lend_apr_series(&snapshots),
+                current_lend_apr
+            );
+
+            let borrow_apr_data = ensure_data(
+                MetricSnapshot::borrow_apr_series(&snapshots),
+                current_borrow_apr
+            );
+
+            // Transfer counts from Blockscout aggregation (real historical data)
+            let raw_transfers: Vec<(i64, u64)> = transfers_by_period.and_then(|r| r.ok()).unwrap_or_default();
+            let transfers_data: Vec<(i64, u64)> = if let Some(custom_start) = start {
+                let effective_end = end.unwrap_or(now);
+                raw_transfers
+                    .into_iter()
+                    .filter(|(ts, _)| *ts >= custom_start && *ts <= effective_end)
+                    .collect()
+            } else {
+                raw_transfers
+            };
+
+            let fetch_time_ms = timer_start.elapsed().as_millis() as u32;
+
+            let response = ChartDataResponse {
+                resolution,
+                lookback,
+                generated_at: now,
+                fetch_time_ms,
+                timings,
+                price_candles,
+                volume_data,
+                liquidity_data,
+                tcr_data,
+                supply_data,
+                holders_data,
+                lend_apr_data,
+                borrow_apr_data,
+                transfers_data,
+                moving_averages,
+                current_price,
+                current_volume_24h,
+                current_liquidity,
+                current_tcr,
+                current_supply,
+                current_holders,
+                current_lend_apr,
+                current_borrow_apr,
+                snapshot_count: snapshots.len(),
+                oldest_snapshot_time: snapshots.first().map(|s| s.timestamp),
+                snapshot_interval_secs: snapshot_interval as u64,
+                lookback_overrides: config().chart_lookback_overrides.clone(),
+            };
+
+            // Store in cache
+            caches::ADVANCED_CHART_DATA.set(cache_key, response.clone());
+
+            Ok(response)
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -1229,179 +2257,186 @@ pub async fn get_wallet_analytics(
 ) -> Result<WalletAnalyticsResponse, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use usdfc_backend::blockscout::BlockscoutClient;
-        use usdfc_core::config::config;
-        use usdfc_core::error::ValidationError;
-        use rust_decimal::Decimal;
-        use rust_decimal::prelude::ToPrimitive;
-        use std::collections::BTreeMap;
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        // Basic address validation
-        ValidationError::validate_address(&address)
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Normalize address to EVM hex if needed
-        let wallet_evm = if address.starts_with("0x") {
-            address.to_lowercase()
-        } else if address.starts_with('f') {
-            // Try to convert f4-style Filecoin address to EVM
-            match usdfc_backend::address_conv::f4_to_evm(&address) {
-                Ok(ev) => ev.to_lowercase(),
-                Err(e) => return Err(SfnError::ServerError(e.to_string())),
+        usdfc_backend::timing::timed("get_wallet_analytics", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::wallet::{wallet_analytics, wallet_evm_address, wallet_window};
+            use usdfc_core::config::config;
+            use usdfc_core::error::ValidationError;
+            use std::time::{SystemTime, UNIX_EPOCH};
+
+            // Basic address validation
+            ValidationError::validate_address(&address)
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
+            let wallet_evm = wallet_evm_address(&address).map_err(SfnError::ServerError)?;
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let window = wallet_window(lookback, start, end, now);
+
+            let blockscout = BlockscoutClient::new();
+
+            // Fetch recent transfers with timestamps for USDFC
+            let (transfers, _) = blockscout
+                .gql_get_transfers_with_timestamps(&config().usdfc_token, 200, None)
+                .await
+                .map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+            let bucket_secs = resolution.minutes() as i64 * 60;
+            Ok(wallet_analytics(&blockscout, address, &wallet_evm, &transfers, window, bucket_secs, end.is_none()).await)
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+/// Compare two wallets' USDFC flows over the same window
+///
+/// Both sides go through the same analytics as `get_wallet_analytics` on one
+/// transfer scan, with buckets aligned so index `i` covers the same period
+/// for both. Also returns the transfers sent directly between the two.
+#[server(GetWalletComparison, "/api")]
+pub async fn get_wallet_comparison(
+    addr_a: String,
+    addr_b: String,
+    resolution: ChartResolution,
+    lookback: ChartLookback,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<WalletComparison, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_wallet_comparison", async move {
+            use usdfc_backend::blockscout::BlockscoutClient;
+            use usdfc_backend::wallet::{wallet_analytics, wallet_evm_address, wallet_window};
+            use usdfc_core::config::config;
+            use usdfc_core::error::{TerminalError, ValidationError};
+            use std::time::{SystemTime, UNIX_EPOCH};
+
+            let mut wallets = Vec::with_capacity(2);
+            for address in [&addr_a, &addr_b] {
+                ValidationError::validate_address(address)
+                    .map_err(|e| SfnError::from(TerminalError::from(e)))?;
+                wallets.push(wallet_evm_address(address)
+                    .map_err(|e| SfnError::from(TerminalError::Validation(e)))?);
             }
-        } else {
-            address.to_lowercase()
-        };
-
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
-
-        let configured_lookback_mins = lookback.minutes();
-        let resolution_mins = resolution.minutes();
-
-        let effective_lookback_mins = if let Some(custom_start) = start {
-            let diff_secs = now.saturating_sub(custom_start);
-            let span_mins = ((diff_secs / 60).max(1)) as u32;
-            if configured_lookback_mins == 0 {
-                span_mins
-            } else {
-                configured_lookback_mins.max(span_mins)
+            let (evm_a, evm_b) = (&wallets[0], &wallets[1]);
+            if evm_a == evm_b {
+                return Err(SfnError::from(TerminalError::Validation(
+                    "Cannot compare an address with itself".to_string(),
+                )));
             }
-        } else {
-            configured_lookback_mins
-        };
-
-        let (window_start, window_end) = if let Some(custom_start) = start {
-            (custom_start, end.unwrap_or(now))
-        } else if effective_lookback_mins == 0 {
-            (0, now)
-        } else {
-            let span_secs = effective_lookback_mins as i64 * 60;
-            (now.saturating_sub(span_secs), now)
-        };
-
-        let blockscout = BlockscoutClient::new();
-        let token_address = &config().usdfc_token;
-
-        // Fetch recent transfers with timestamps for USDFC
-        let (transfers, _) = blockscout
-            .gql_get_transfers_with_timestamps(token_address, 200, None)
-            .await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Filter transfers for this wallet and time window
-        let mut relevant: Vec<usdfc_backend::blockscout::TransferWithTimestamp> = transfers
-            .into_iter()
-            .filter(|t| t.timestamp >= window_start && t.timestamp <= window_end)
-            .filter(|t| {
-                let from = t.from_address.to_lowercase();
-                let to = t.to_address.to_lowercase();
-                from == wallet_evm || to == wallet_evm
-            })
-            .collect();
 
-        if relevant.is_empty() {
-            return Ok(WalletAnalyticsResponse {
-                address,
-                buckets: Vec::new(),
-                total_in: 0.0,
-                total_out: 0.0,
-                first_seen: None,
-                last_active: None,
-            });
-        }
-
-        // Sort by timestamp to derive first/last activity
-        relevant.sort_by_key(|t| t.timestamp);
-
-        let first_ts = relevant.first().map(|t| t.timestamp);
-        let last_ts = relevant.last().map(|t| t.timestamp);
-
-        let first_seen = first_ts.and_then(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-        });
-        let last_active = last_ts.and_then(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-        });
-
-        // Bucket transfers by resolution
-        let bucket_secs = (resolution_mins as i64 * 60).max(60);
-
-        struct BucketAccum {
-            volume_in: Decimal,
-            volume_out: Decimal,
-            count_in: u64,
-            count_out: u64,
-        }
-
-        let mut buckets_map: BTreeMap<i64, BucketAccum> = BTreeMap::new();
-
-        let decimals = 18u32;
-        let divisor = Decimal::from_i128_with_scale(10_i128.pow(decimals), 0);
-
-        for t in relevant {
-            let is_incoming = t.to_address.to_lowercase() == wallet_evm;
-            let is_outgoing = t.from_address.to_lowercase() == wallet_evm;
-
-            if !is_incoming && !is_outgoing {
-                continue;
-            }
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let window = wallet_window(lookback, start, end, now);
+
+            let blockscout = BlockscoutClient::new();
+            let (transfers, _) = blockscout
+                .gql_get_transfers_with_timestamps(&config().usdfc_token, 200, None)
+                .await
+                .map_err(|e| SfnError::from(TerminalError::from(e)))?;
+
+            let bucket_secs = resolution.minutes() as i64 * 60;
+            let live = end.is_none();
+            let (a, b, decimals) = tokio::join!(
+                wallet_analytics(&blockscout, addr_a.clone(), evm_a, &transfers, window, bucket_secs, live),
+                wallet_analytics(&blockscout, addr_b.clone(), evm_b, &transfers, window, bucket_secs, live),
+                usdfc_backend::blockscout::usdfc_decimals(&blockscout)
+            );
 
-            let raw = match t.amount.parse::<u128>() {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
+            let mut direct_transfers: Vec<Transaction> = transfers
+                .iter()
+                .filter(|t| t.timestamp >= window.0 && t.timestamp <= window.1)
+                .filter(|t| {
+                    let (from, to) = (t.from_address.to_lowercase(), t.to_address.to_lowercase());
+                    (&from == evm_a && &to == evm_b) || (&from == evm_b && &to == evm_a)
+                })
+                .filter_map(|t| {
+                    Some(Transaction {
+                        hash: t.transaction_hash.clone(),
+                        tx_type: TransactionType::Transfer,
+                        amount: usdfc_core::format::parse_token_amount(&t.amount, decimals)?,
+                        from: t.from_address.clone(),
+                        to: t.to_address.clone(),
+                        timestamp: t.timestamp as u64,
+                        block: t.block_number as u64,
+                        status: TransactionStatus::Success,
+                        is_new: false,
+                    })
+                })
+                .collect();
+            direct_transfers.sort_by_key(|t| t.timestamp);
+
+            Ok(WalletComparison::new(a, b, direct_transfers))
+        }).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
+
+// ============================================================================
+// Alert History
+// ============================================================================
+
+/// Get recorded alert events, newest first
+///
+/// `kind` and `since` (Unix seconds) filter the history; `limit` defaults to 25
+/// and is capped at 100.
+#[server(GetAlertEvents, "/api")]
+pub async fn get_alert_events(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    kind: Option<AlertCondition>,
+    since: Option<i64>,
+) -> Result<AlertEventPage, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("get_alert_events", async move {
+            use usdfc_backend::alert_events;
+
+            let limit = limit.unwrap_or(alert_events::DEFAULT_LIMIT);
+            alert_events::list(limit, offset.unwrap_or(0), kind, since)
+                .map_err(|e| SfnError::ServerError(format!("Failed to load alert history: {}", e)))
+        }).await
+    }
 
-            let value = Decimal::from_i128_with_scale(raw as i128, 0) / divisor;
-            let bucket_ts = (t.timestamp / bucket_secs) * bucket_secs;
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(SfnError::ServerError("SSR is required for live data".to_string()))
+    }
+}
 
-            let entry = buckets_map.entry(bucket_ts).or_insert(BucketAccum {
-                volume_in: Decimal::ZERO,
-                volume_out: Decimal::ZERO,
-                count_in: 0,
-                count_out: 0,
-            });
+/// Acknowledge or dismiss a recorded alert event
+/// Requires the ALERTS_ADMIN_TOKEN configured on the server
+#[server(AcknowledgeAlert, "/api")]
+pub async fn acknowledge_alert(id: i64, status: AlertEventStatus, token: String) -> Result<StoredAlertEvent, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        usdfc_backend::timing::timed("acknowledge_alert", async move {
+            use usdfc_backend::alert_events;
 
-            if is_incoming {
-                entry.volume_in += value;
-                entry.count_in += 1;
+            if !alert_events::admin_token_valid(&token) {
+                tracing::warn!("Rejected review of alert event #{}: invalid token", id);
+                return Err(SfnError::ServerError("Invalid or missing alerts token".to_string()));
             }
-            if is_outgoing {
-                entry.volume_out += value;
-                entry.count_out += 1;
+            if status == AlertEventStatus::Open {
+                return Err(SfnError::ServerError("Alerts can only be acknowledged or dismissed".to_string()));
             }
-        }
-
-        let mut buckets = Vec::new();
-        let mut total_in = Decimal::ZERO;
-        let mut total_out = Decimal::ZERO;
-
-        for (ts, acc) in buckets_map {
-            total_in += acc.volume_in;
-            total_out += acc.volume_out;
-
-            buckets.push(WalletBucket {
-                timestamp: ts,
-                volume_in: acc.volume_in.to_f64().unwrap_or(0.0),
-                volume_out: acc.volume_out.to_f64().unwrap_or(0.0),
-                count_in: acc.count_in,
-                count_out: acc.count_out,
-            });
-        }
-
-        Ok(WalletAnalyticsResponse {
-            address,
-            buckets,
-            total_in: total_in.to_f64().unwrap_or(0.0),
-            total_out: total_out.to_f64().unwrap_or(0.0),
-            first_seen,
-            last_active,
-        })
+
+            alert_events::set_status(id, status)
+                .map_err(|e| SfnError::ServerError(format!("Failed to update alert: {}", e)))?
+                .ok_or_else(|| SfnError::ServerError(format!("Alert event #{} not found", id)))
+        }).await
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -1,6 +1,8 @@
 use fvm_shared::address::{Address, Payload, Protocol};
 use std::str::FromStr;
 
+pub use usdfc_core::address::to_checksummed;
+
 const EAM_NAMESPACE: u64 = 32;
 
 pub fn evm_to_f4(evm: &str) -> Result<String, String> {
@@ -0,0 +1,159 @@
+//! Private address notes
+//!
+//! Operators can attach a short free-text note to any address ("treasury
+//! multisig", "market maker"). Notes live in the `address_notes` table of the
+//! metrics database, keyed by the lowercased address, and are shown next to the
+//! built-in contract labels. Writes require `ADDRESS_NOTES_TOKEN`; with no token
+//! configured, notes are read-only.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use usdfc_core::config::config;
+use usdfc_core::error::ValidationError;
+use usdfc_core::types::{AddressNote, ADDRESS_NOTE_MAX_LEN};
+
+use crate::auth::token_matches;
+use crate::historical::DB_CONN;
+
+/// Create the address notes table
+pub fn init_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS address_notes (
+            address TEXT PRIMARY KEY,
+            note TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Validate an address and return the key notes are stored under
+pub fn normalize_address(address: &str) -> Result<String, String> {
+    let address = address.trim();
+    // validate_address accepts truncated display addresses, which can't be keys
+    if address.contains("...") {
+        return Err(format!("Invalid address format: {}", address));
+    }
+    ValidationError::validate_address(address).map_err(|e| e.to_string())?;
+    Ok(address.to_ascii_lowercase())
+}
+
+/// Validate note text, returning the trimmed note
+pub fn validate_note(note: &str) -> Result<String, String> {
+    let note = note.trim();
+    if note.chars().count() > ADDRESS_NOTE_MAX_LEN {
+        return Err(format!("Note must be at most {} characters", ADDRESS_NOTE_MAX_LEN));
+    }
+    if note.chars().any(char::is_control) {
+        return Err("Note must be a single line of text".to_string());
+    }
+    Ok(note.to_string())
+}
+
+/// Whether `provided` matches the configured write token
+///
+/// Always false when no token is configured. The comparison is constant-time.
+pub fn write_token_valid(provided: &str) -> bool {
+    token_matches(provided, &config().address_notes_token)
+}
+
+/// Look up the note for an address
+pub fn get_note(address: &str) -> Result<Option<AddressNote>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in address_notes::get_note: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Ok(None);
+    };
+
+    conn.query_row(
+        "SELECT address, note, updated_at FROM address_notes WHERE address = ?1",
+        params![address],
+        |row| {
+            Ok(AddressNote {
+                address: row.get(0)?,
+                note: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// All notes, keyed by lowercased address
+pub fn get_all_notes() -> Result<HashMap<String, String>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in address_notes::get_all_notes: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Ok(HashMap::new());
+    };
+
+    let mut stmt = conn.prepare("SELECT address, note FROM address_notes")?;
+    let notes = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect();
+    notes
+}
+
+/// Store a note for an address; an empty note deletes it
+///
+/// Returns the stored note, or None when it was deleted.
+pub fn set_note(address: &str, note: &str) -> Result<Option<AddressNote>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in address_notes::set_note: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Err(rusqlite::Error::InvalidQuery);
+    };
+
+    if note.is_empty() {
+        conn.execute("DELETE FROM address_notes WHERE address = ?1", params![address])?;
+        return Ok(None);
+    }
+
+    let updated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    conn.execute(
+        "INSERT INTO address_notes (address, note, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(address) DO UPDATE SET note = excluded.note, updated_at = excluded.updated_at",
+        params![address, note, updated_at],
+    )?;
+    Ok(Some(AddressNote {
+        address: address.to_string(),
+        note: note.to_string(),
+        updated_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_validation() {
+        assert_eq!(validate_note("  treasury multisig ").unwrap(), "treasury multisig");
+        assert_eq!(validate_note("").unwrap(), "");
+        assert!(validate_note(&"x".repeat(ADDRESS_NOTE_MAX_LEN)).is_ok());
+        assert!(validate_note(&"x".repeat(ADDRESS_NOTE_MAX_LEN + 1)).is_err());
+        assert!(validate_note("line one\nline two").is_err());
+
+        assert_eq!(
+            normalize_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap(),
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        );
+        assert!(normalize_address("0xAbCdEf0000000000000000000000000000000001").is_err());
+        assert!(normalize_address("0xabc...def").is_err());
+        assert!(normalize_address("not-an-address").is_err());
+    }
+}
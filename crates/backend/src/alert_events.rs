@@ -0,0 +1,233 @@
+//! Alert history
+//!
+//! Every `AlertEvent` the snapshot collector fires is stored in the
+//! `alert_events` table so operators can review past alerts, filter them by
+//! kind and time, and acknowledge or dismiss them. Review actions require
+//! `ALERTS_ADMIN_TOKEN`; with no token configured the history is read-only.
+
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use usdfc_core::config::config;
+use usdfc_core::types::{AlertCondition, AlertEvent, AlertEventPage, AlertEventStatus, StoredAlertEvent};
+
+use crate::auth::token_matches;
+use crate::historical::DB_CONN;
+
+/// Page size when the caller does not ask for one
+pub const DEFAULT_LIMIT: u32 = 25;
+/// Largest page a single request may return
+pub const MAX_LIMIT: u32 = 100;
+
+/// Create the alert events table and the indexes behind the history filters
+pub fn init_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS alert_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            message TEXT NOT NULL,
+            value REAL NOT NULL,
+            threshold REAL NOT NULL,
+            timestamp INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'open',
+            reviewed_at INTEGER
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_alert_events_timestamp ON alert_events(timestamp)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_alert_events_kind_timestamp ON alert_events(kind, timestamp)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Whether `provided` matches the configured admin token
+///
+/// Always false when no token is configured. The comparison is constant-time.
+pub fn admin_token_valid(provided: &str) -> bool {
+    token_matches(provided, &config().alerts_admin_token)
+}
+
+/// Store newly fired events
+pub fn record(events: &[AlertEvent]) -> Result<(), rusqlite::Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let mut db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in alert_events::record: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    if let Some(ref mut conn) = *db_lock {
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO alert_events (kind, message, value, threshold, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for event in events {
+                stmt.execute(params![
+                    event.kind.as_str(),
+                    event.message,
+                    event.value,
+                    event.threshold,
+                    event.timestamp,
+                ])?;
+            }
+        }
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// One page of the history, newest first
+///
+/// `kind` and `since` (Unix seconds, inclusive) narrow the results; `total`
+/// counts every matching event so the caller can paginate.
+pub fn list(
+    limit: u32,
+    offset: u32,
+    kind: Option<AlertCondition>,
+    since: Option<i64>,
+) -> Result<AlertEventPage, rusqlite::Error> {
+    let limit = limit.clamp(1, MAX_LIMIT);
+
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in alert_events::list: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Ok(AlertEventPage { limit, offset, ..Default::default() });
+    };
+
+    let (filter, mut args) = filter_clause(kind, since);
+
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM alert_events{}", filter),
+        params_from_iter(args.iter()),
+        |row| row.get(0),
+    )?;
+
+    args.push(Value::Integer(limit as i64));
+    args.push(Value::Integer(offset as i64));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, kind, message, value, threshold, timestamp, status, reviewed_at
+         FROM alert_events{}
+         ORDER BY timestamp DESC, id DESC
+         LIMIT ? OFFSET ?",
+        filter
+    ))?;
+    let events = stmt
+        .query_map(params_from_iter(args.iter()), stored_event_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AlertEventPage {
+        events,
+        total: total.max(0) as u64,
+        limit,
+        offset,
+    })
+}
+
+/// Set the review status of an event
+///
+/// Returns None when no event has that id.
+pub fn set_status(id: i64, status: AlertEventStatus) -> Result<Option<StoredAlertEvent>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in alert_events::set_status: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Err(rusqlite::Error::InvalidQuery);
+    };
+
+    let reviewed_at = match status {
+        AlertEventStatus::Open => None,
+        _ => Some(now_secs()),
+    };
+    conn.execute(
+        "UPDATE alert_events SET status = ?1, reviewed_at = ?2 WHERE id = ?3",
+        params![status.as_str(), reviewed_at, id],
+    )?;
+
+    conn.query_row(
+        "SELECT id, kind, message, value, threshold, timestamp, status, reviewed_at
+         FROM alert_events WHERE id = ?1",
+        params![id],
+        stored_event_from_row,
+    )
+    .optional()
+}
+
+/// WHERE clause and positional arguments for the history filters
+fn filter_clause(kind: Option<AlertCondition>, since: Option<i64>) -> (String, Vec<Value>) {
+    let mut conditions = Vec::new();
+    let mut args = Vec::new();
+    if let Some(kind) = kind {
+        conditions.push("kind = ?");
+        args.push(Value::Text(kind.as_str().to_string()));
+    }
+    if let Some(since) = since {
+        conditions.push("timestamp >= ?");
+        args.push(Value::Integer(since));
+    }
+
+    let clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    (clause, args)
+}
+
+fn stored_event_from_row(row: &Row<'_>) -> Result<StoredAlertEvent, rusqlite::Error> {
+    let kind: String = row.get(1)?;
+    let status: String = row.get(6)?;
+    let kind = AlertCondition::parse(&kind).ok_or_else(|| {
+        rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, format!("unknown alert kind {}", kind).into())
+    })?;
+
+    Ok(StoredAlertEvent {
+        id: row.get(0)?,
+        event: AlertEvent {
+            kind,
+            message: row.get(2)?,
+            value: row.get(3)?,
+            threshold: row.get(4)?,
+            timestamp: row.get(5)?,
+        },
+        status: AlertEventStatus::parse(&status).unwrap_or_default(),
+        reviewed_at: row.get(7)?,
+    })
+}
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_clause_binds_each_filter() {
+        let (clause, args) = filter_clause(None, None);
+        assert!(clause.is_empty());
+        assert!(args.is_empty());
+
+        let (clause, args) = filter_clause(Some(AlertCondition::TcrBelow), Some(1_700_000_000));
+        assert_eq!(clause, " WHERE kind = ? AND timestamp >= ?");
+        assert_eq!(args, vec![Value::Text("tcr_below".to_string()), Value::Integer(1_700_000_000)]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Alert evaluation for collected metric snapshots
+//!
+//! The snapshot collector compares each new snapshot with the previous one and
+//! fires an `AlertEvent` when a metric crosses its threshold. Alerts fire on the
+//! crossing only, so a TCR that stays low does not re-alert every minute.
+
+use usdfc_core::types::{AlertCondition, AlertEvent};
+
+use crate::historical::MetricSnapshot;
+
+/// Evaluate a new snapshot against the previous one
+///
+/// `threshold` is the TCR percentage below which `TcrBelow` fires
+/// (the collector passes `tcr_danger_threshold`).
+pub fn evaluate_snapshot(
+    previous: Option<&MetricSnapshot>,
+    current: &MetricSnapshot,
+    threshold: f64,
+) -> Vec<AlertEvent> {
+    let mut events = Vec::new();
+
+    // A TCR of 0 means the RPC fetch failed, not that the system is insolvent
+    if let Some(previous) = previous {
+        if previous.tcr >= threshold && current.tcr > 0.0 && current.tcr < threshold {
+            events.push(AlertEvent {
+                kind: AlertCondition::TcrBelow,
+                message: format!(
+                    "USDFC TCR dropped to {:.1}% (threshold {:.1}%)",
+                    current.tcr, threshold
+                ),
+                value: current.tcr,
+                threshold,
+                timestamp: current.timestamp,
+            });
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(tcr: f64) -> MetricSnapshot {
+        MetricSnapshot {
+            timestamp: 0,
+            tcr,
+            supply: 0.0,
+            liquidity: 0.0,
+            holders: 0,
+            lend_apr: 0.0,
+            borrow_apr: 0.0,
+            best_lend_price: None,
+            best_borrow_price: None,
+            spread_bps: None,
+        }
+    }
+
+    #[test]
+    fn test_tcr_alert_fires_on_crossing_only() {
+        let above = snapshot(160.0);
+        let below = snapshot(140.0);
+
+        let events = evaluate_snapshot(Some(&above), &below, 150.0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, AlertCondition::TcrBelow);
+        assert_eq!(events[0].value, 140.0);
+
+        assert!(evaluate_snapshot(Some(&below), &below, 150.0).is_empty());
+        assert!(evaluate_snapshot(None, &below, 150.0).is_empty());
+        assert!(evaluate_snapshot(Some(&above), &snapshot(0.0), 150.0).is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+//! Conditional GET support for polled REST endpoints
+//!
+//! The ETag is weak and covers only the response `data`, not the per-request
+//! `timestamp`, so repeated polls of an unchanged cached value match and get
+//! `304 Not Modified` with no body.
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use ring::digest;
+use serde::Serialize;
+
+use crate::api::models::ApiResponse;
+
+/// Weak ETag over the serialized data
+pub fn weak_etag<T: Serialize>(data: &T) -> String {
+    let body = serde_json::to_vec(data).unwrap_or_default();
+    let hash = digest::digest(&digest::SHA256, &body);
+    format!("W/\"{}\"", hex::encode(&hash.as_ref()[..16]))
+}
+
+/// Whether an `If-None-Match` value matches `etag` (weak comparison)
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    header
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == opaque(etag))
+}
+
+/// Successful JSON response carrying an ETag, or 304 when the client's copy is current
+pub fn json_with_etag<T: Serialize>(request_headers: &HeaderMap, data: T) -> Response {
+    let etag = weak_etag(&data);
+    let Ok(etag_value) = HeaderValue::from_str(&etag) else {
+        return (StatusCode::OK, Json(ApiResponse::success(data))).into_response();
+    };
+
+    let not_modified = request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| if_none_match(v, &etag));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_value)]).into_response();
+    }
+
+    (StatusCode::OK, [(header::ETAG, etag_value)], Json(ApiResponse::success(data))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_matches_same_data_only() {
+        let etag = weak_etag(&serde_json::json!({"tcr": "181.20%"}));
+        assert!(etag.starts_with("W/\"") && etag.len() == 36);
+        assert_eq!(etag, weak_etag(&serde_json::json!({"tcr": "181.20%"})));
+        assert_ne!(etag, weak_etag(&serde_json::json!({"tcr": "181.21%"})));
+
+        let strong = etag.trim_start_matches("W/");
+        assert!(if_none_match(&etag, &etag));
+        assert!(if_none_match(strong, &etag));
+        assert!(if_none_match(&format!("W/\"other\", {}", etag), &etag));
+        assert!(if_none_match("*", &etag));
+        assert!(!if_none_match("W/\"other\"", &etag));
+    }
+}
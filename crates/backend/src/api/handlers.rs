@@ -5,22 +5,28 @@
 
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 
 use crate::api::models::{
-    ApiResponse, PaginationQuery, HistoryQuery as HistoryQueryParams,
+    ApiResponse, HistoryQuery as HistoryQueryParams,
     PriceResponse, MetricsResponse, HealthResponse, ServiceStatus,
     TroveResponse, TrovesListResponse, TransactionResponse, TransactionsListResponse,
     AddressInfoResponse, LendingMarketResponse, LendingMarketsResponse,
-    HistoricalResponse, HistoricalDataPoint, TopHoldersResponse, TokenHolderResponse,
+    HistoricalResponse, HistoricalDataPoint, TokenHolderResponse,
+    MetricsQuery, OrderBookQuery, LendingTradesResponse, TransactionsCsvQuery, TrovesCsvQuery,
+    HistoryExportQuery,
+    openapi_document,
 };
+use crate::api::etag::json_with_etag;
+use crate::api::params::{select_fields, ListParams};
 use crate::server_fn::{
     get_protocol_metrics, get_recent_transactions, get_troves, get_lending_markets,
-    get_address_info, get_usdfc_price_data, check_api_health, get_top_holders,
+    get_address_info, get_usdfc_price_data, check_api_health,
     get_holder_count, get_daily_volumes, get_normalized_address,
+    get_order_book, get_recent_lending_trades,
 };
 use crate::rpc::RpcClient;
 use rust_decimal::prelude::ToPrimitive;
@@ -42,6 +48,12 @@ pub async fn get_version() -> impl IntoResponse {
     )
 }
 
+/// GET /api/openapi.json
+/// Returns the OpenAPI 3.0 description of the `/api/v1` endpoints
+pub async fn get_openapi() -> impl IntoResponse {
+    (StatusCode::OK, Json(openapi_document()))
+}
+
 // ============================================================================
 // Health Endpoint
 // ============================================================================
@@ -51,38 +63,30 @@ pub async fn get_version() -> impl IntoResponse {
 pub async fn get_health() -> impl IntoResponse {
     match check_api_health().await {
         Ok(status) => {
+            // Sources disabled in config are reported as "disabled", not "unhealthy"
+            let service = |name: &str, ok: bool| ServiceStatus {
+                name: name.to_string(),
+                status: if status.is_disabled(name) {
+                    "disabled"
+                } else if ok {
+                    "healthy"
+                } else {
+                    "unhealthy"
+                }
+                .to_string(),
+                latency_ms: None,
+            };
             let services = vec![
-                ServiceStatus {
-                    name: "rpc".to_string(),
-                    status: if status.rpc_ok { "healthy" } else { "unhealthy" }.to_string(),
-                    latency_ms: None,
-                },
-                ServiceStatus {
-                    name: "blockscout".to_string(),
-                    status: if status.blockscout_ok { "healthy" } else { "unhealthy" }.to_string(),
-                    latency_ms: None,
-                },
-                ServiceStatus {
-                    name: "subgraph".to_string(),
-                    status: if status.subgraph_ok { "healthy" } else { "unhealthy" }.to_string(),
-                    latency_ms: None,
-                },
-                ServiceStatus {
-                    name: "gecko".to_string(),
-                    status: if status.gecko_ok { "healthy" } else { "unhealthy" }.to_string(),
-                    latency_ms: None,
-                },
-                ServiceStatus {
-                    name: "database".to_string(),
-                    status: if status.database_ok { "healthy" } else { "unhealthy" }.to_string(),
-                    latency_ms: None,
-                },
+                service("rpc", status.rpc_ok),
+                service("blockscout", status.blockscout_ok),
+                service("subgraph", status.subgraph_ok),
+                service("gecko", status.gecko_ok),
+                service("database", status.database_ok),
             ];
 
             // Overall status mirrors the infrastructure /health endpoint semantics:
             // only fully "healthy" when all critical services are up.
-            let all_healthy =
-                status.rpc_ok && status.blockscout_ok && status.subgraph_ok && status.gecko_ok && status.database_ok;
+            let all_healthy = services.iter().all(|s| s.status != "unhealthy");
             let overall_status = if all_healthy { "healthy" } else { "degraded" };
 
             let response = HealthResponse {
@@ -104,7 +108,8 @@ pub async fn get_health() -> impl IntoResponse {
 
 /// GET /api/v1/price
 /// Returns current USDFC price data from GeckoTerminal
-pub async fn get_price() -> impl IntoResponse {
+/// Supports conditional GET via `ETag` / `If-None-Match`
+pub async fn get_price(headers: HeaderMap) -> Response {
     let rpc = RpcClient::new();
     let fil_price = rpc.get_fil_price().await.ok().and_then(|p| p.to_f64());
 
@@ -117,12 +122,13 @@ pub async fn get_price() -> impl IntoResponse {
                 volume_24h: price_data.volume_24h,
                 liquidity_usd: price_data.liquidity_usd,
             };
-            (StatusCode::OK, Json(ApiResponse::success(response)))
+            json_with_etag(&headers, response)
         }
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(e.to_string())),
-        ),
+            Json(ApiResponse::<()>::error(e.to_string())),
+        )
+            .into_response(),
     }
 }
 
@@ -132,7 +138,9 @@ pub async fn get_price() -> impl IntoResponse {
 
 /// GET /api/v1/metrics
 /// Returns protocol-wide metrics (total supply, collateral, TCR, etc.)
-pub async fn get_metrics() -> impl IntoResponse {
+/// `?fields=total_supply,tcr` limits the response to the named fields
+/// Supports conditional GET via `ETag` / `If-None-Match`
+pub async fn get_metrics(headers: HeaderMap, Query(query): Query<MetricsQuery>) -> Response {
     let (metrics_result, price_result, holders_result) = tokio::join!(
         get_protocol_metrics(),
         get_usdfc_price_data(),
@@ -145,22 +153,32 @@ pub async fn get_metrics() -> impl IntoResponse {
             let holders = holders_result.ok();
 
             let response = MetricsResponse {
-                tcr: format!("{:.2}%", metrics.tcr),
-                total_supply: metrics.total_supply.to_string(),
-                circulating_supply: metrics.circulating_supply.to_string(),
-                total_collateral: metrics.total_collateral.to_string(),
+                tcr: metrics.tcr.map(|tcr| format!("{:.2}%", tcr)),
+                total_supply: metrics.total_supply.map(|v| v.to_string()),
+                circulating_supply: metrics.circulating_supply.map(|v| v.to_string()),
+                total_collateral: metrics.collateral_fil.map(|v| v.to_string()),
+                total_collateral_usd: metrics.collateral_usd.map(|v| v.round_dp(2).to_string()),
                 active_troves: metrics.active_troves,
                 holders,
                 volume_24h: price_data.as_ref().and_then(|p| p.volume_24h),
                 liquidity_usd: price_data.as_ref().and_then(|p| p.liquidity_usd),
-                stability_pool_balance: metrics.stability_pool_balance.to_string(),
+                stability_pool_balance: metrics.stability_pool_balance.map(|v| v.to_string()),
+                missing: metrics.missing(),
             };
-            (StatusCode::OK, Json(ApiResponse::success(response)))
+            let Some(fields) = query.fields.as_deref() else {
+                return json_with_etag(&headers, response);
+            };
+            let data = serde_json::to_value(&response).unwrap_or_default();
+            match select_fields(data, fields) {
+                Ok(selected) => json_with_etag(&headers, selected),
+                Err(e) => e.into_response(),
+            }
         }
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(e.to_string())),
-        ),
+            Json(ApiResponse::<()>::error(e.to_string())),
+        )
+            .into_response(),
     }
 }
 
@@ -185,8 +203,9 @@ pub async fn get_history(Query(params): Query<HistoryQueryParams>) -> impl IntoR
     let days = ((to - from) / (24 * 60 * 60)) as i32;
 
     match get_daily_volumes(Some(days.max(1))).await {
-        Ok(volumes) => {
-            let data: Vec<HistoricalDataPoint> = volumes
+        Ok(response) => {
+            let data: Vec<HistoricalDataPoint> = response
+                .volumes
                 .into_iter()
                 .filter(|v| v.timestamp >= from && v.timestamp <= to)
                 .map(|v| HistoricalDataPoint {
@@ -201,6 +220,7 @@ pub async fn get_history(Query(params): Query<HistoryQueryParams>) -> impl IntoR
                 from,
                 to,
                 data,
+                complete: response.complete,
             };
             (StatusCode::OK, Json(ApiResponse::success(response)))
         }
@@ -211,18 +231,142 @@ pub async fn get_history(Query(params): Query<HistoryQueryParams>) -> impl IntoR
     }
 }
 
+/// Snapshot rows read from the database per chunk of a history export
+const HISTORY_EXPORT_PAGE_ROWS: usize = 1000;
+
+/// GET /api/v1/history/export
+/// Streams the stored metric snapshots, oldest first
+///
+/// Newline-delimited JSON by default, CSV with `format=csv`. `metric` narrows
+/// each row to that metric's value and `start`/`end` bound the timestamps.
+/// The snapshot table is paged as rows are sent, so the export is not held in
+/// memory; the first page is read up front so a database error is still a 500.
+pub async fn export_history(Query(query): Query<HistoryExportQuery>) -> Response {
+    use crate::historical::{snapshot_page, snapshot_value, MetricSnapshot, SNAPSHOT_METRICS};
+    use axum::body::Body;
+    use axum::http::header;
+    use usdfc_core::types::ChartMetric;
+
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(message))).into_response();
+
+    let csv = match query.format.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        None | Some("ndjson") => false,
+        Some("csv") => true,
+        Some(other) => return bad_request(format!("Unknown format '{}', expected ndjson or csv", other)),
+    };
+    let metric = match query.metric.as_deref() {
+        None => None,
+        Some(name) => match ChartMetric::from_url_param(name).filter(|m| SNAPSHOT_METRICS.contains(m)) {
+            Some(metric) => Some(metric),
+            None => return bad_request(format!("Metric '{}' is not recorded in snapshots", name)),
+        },
+    };
+    let (start, end) = (query.start.unwrap_or(i64::MIN), query.end.unwrap_or(i64::MAX));
+    if start > end {
+        return bad_request("start must not be after end".to_string());
+    }
+
+    // Keyset paging: each page starts after the last timestamp of the previous one
+    let page = move |after: i64| snapshot_page(after, end, HISTORY_EXPORT_PAGE_ROWS);
+    let first_page = match page(start.saturating_sub(1)) {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response();
+        }
+    };
+
+    let row = move |s: &MetricSnapshot| -> Option<String> {
+        match metric {
+            None if csv => Some(s.csv_row()),
+            None => serde_json::to_string(s).ok().map(|line| line + "\n"),
+            Some(metric) => {
+                let value = snapshot_value(metric, s)?;
+                Some(if csv {
+                    format!("{},{}\n", s.timestamp, value)
+                } else {
+                    format!("{}\n", serde_json::json!({ "timestamp": s.timestamp, "metric": metric.to_url_param(), "value": value }))
+                })
+            }
+        }
+    };
+    let header_line = match metric {
+        _ if !csv => String::new(),
+        None => MetricSnapshot::CSV_HEADER.to_string(),
+        Some(metric) => format!("Timestamp,{}\n", metric.label()),
+    };
+
+    let rows = futures::stream::unfold(Some(Ok(first_page)), move |next| async move {
+        let snapshots = match next? {
+            Ok(snapshots) if snapshots.is_empty() => return None,
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                tracing::warn!("History export stopped after a database error: {}", e);
+                return Some((Err(e), None));
+            }
+        };
+        let chunk: String = snapshots.iter().filter_map(row).collect();
+        let following = match snapshots.last() {
+            Some(last) if snapshots.len() == HISTORY_EXPORT_PAGE_ROWS => Some(page(last.timestamp)),
+            _ => None,
+        };
+        Some((Ok(chunk), following))
+    });
+    let header_chunk = futures::stream::once(async move { Ok::<_, rusqlite::Error>(header_line) });
+
+    let (content_type, filename) = if csv {
+        ("text/csv; charset=utf-8", "attachment; filename=\"usdfc_history.csv\"")
+    } else {
+        ("application/x-ndjson", "attachment; filename=\"usdfc_history.ndjson\"")
+    };
+    (
+        [(header::CONTENT_TYPE, content_type), (header::CONTENT_DISPOSITION, filename)],
+        Body::from_stream(futures::StreamExt::chain(header_chunk, rows)),
+    )
+        .into_response()
+}
+
 // ============================================================================
 // Troves Endpoints
 // ============================================================================
 
 /// GET /api/v1/troves
 /// Returns list of all active troves with pagination
-pub async fn get_troves_list(Query(params): Query<PaginationQuery>) -> impl IntoResponse {
-    let limit = params.limit.unwrap_or(20).min(100);
-    let offset = params.offset.unwrap_or(0);
+///
+/// Sort fields: `collateral`, `debt`, `icr`. Filter matches trove status.
+/// Supports conditional GET via `ETag` / `If-None-Match`
+pub async fn get_troves_list(headers: HeaderMap, params: ListParams) -> Response {
+    if let Err(e) = params.check_sort(&["collateral", "debt", "icr"]) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(e.0))).into_response();
+    }
+
+    let limit = params.limit;
+    let offset = params.offset;
 
-    match get_troves(Some(limit + offset), Some(0)).await {
+    // Sorting and filtering need the full set, otherwise fetch just enough
+    let fetch_limit = if params.sort.is_some() || params.filter.is_some() {
+        500
+    } else {
+        limit.saturating_add(offset).min(500)
+    };
+
+    match get_troves(Some(fetch_limit), Some(0)).await {
         Ok(troves) => {
+            let mut troves: Vec<_> = troves
+                .into_iter()
+                .filter(|t| params.matches_filter(&t.status.as_str().to_lowercase().replace(' ', "_")))
+                .collect();
+
+            if let Some(sort) = &params.sort {
+                troves.sort_by(|a, b| {
+                    let ord = match sort.field.as_str() {
+                        "collateral" => a.collateral.cmp(&b.collateral),
+                        "debt" => a.debt.cmp(&b.debt),
+                        _ => a.icr.cmp(&b.icr),
+                    };
+                    if sort.descending { ord.reverse() } else { ord }
+                });
+            }
+
             let total = troves.len() as u64;
             let paginated: Vec<TroveResponse> = troves
                 .into_iter()
@@ -243,12 +387,38 @@ pub async fn get_troves_list(Query(params): Query<PaginationQuery>) -> impl Into
                 offset,
                 limit,
             };
-            (StatusCode::OK, Json(ApiResponse::success(response)))
+            json_with_etag(&headers, response)
         }
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(e.to_string())),
-        ),
+            Json(ApiResponse::<()>::error(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/v1/troves.csv - Export troves as CSV
+pub async fn get_troves_csv(Query(query): Query<TrovesCsvQuery>) -> axum::response::Response {
+    use axum::http::header;
+    use usdfc_core::types::Trove;
+
+    let limit = query.limit.unwrap_or(500).min(500);
+    match get_troves(Some(limit), Some(0)).await {
+        Ok(troves) => {
+            let mut body = String::from(Trove::CSV_HEADER);
+            for trove in &troves {
+                body.push_str(&trove.csv_row());
+            }
+            (
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                    (header::CONTENT_DISPOSITION, "attachment; filename=\"usdfc_troves.csv\""),
+                ],
+                body,
+            )
+                .into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response(),
     }
 }
 
@@ -294,14 +464,41 @@ pub async fn get_trove_by_address(Path(addr): Path<String>) -> impl IntoResponse
 // Transactions Endpoint
 // ============================================================================
 
+/// Recent transactions that `/api/v1/transactions` filters, sorts and pages through
+const TRANSACTIONS_WINDOW: u32 = 500;
+
 /// GET /api/v1/transactions
 /// Returns recent USDFC transactions
-pub async fn get_transactions(Query(params): Query<PaginationQuery>) -> impl IntoResponse {
-    let limit = params.limit.unwrap_or(20).min(100);
-    let offset = params.offset.unwrap_or(0);
+///
+/// Sort fields: `timestamp`, `amount`, `block`. Filter matches transaction type.
+/// Filtering and sorting cover the most recent 500 transactions, and `total`
+/// counts the matches among them.
+pub async fn get_transactions(params: ListParams) -> impl IntoResponse {
+    if let Err(e) = params.check_sort(&["timestamp", "amount", "block"]) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e.0)));
+    }
 
-    match get_recent_transactions(Some(limit + offset)).await {
+    let limit = params.limit;
+    let offset = params.offset;
+
+    match get_recent_transactions(Some(TRANSACTIONS_WINDOW), None).await {
         Ok(transactions) => {
+            let mut transactions: Vec<_> = transactions
+                .into_iter()
+                .filter(|tx| params.matches_filter(tx.tx_type.as_str()))
+                .collect();
+
+            if let Some(sort) = &params.sort {
+                transactions.sort_by(|a, b| {
+                    let ord = match sort.field.as_str() {
+                        "amount" => a.amount.cmp(&b.amount),
+                        "block" => a.block.cmp(&b.block),
+                        _ => a.timestamp.cmp(&b.timestamp),
+                    };
+                    if sort.descending { ord.reverse() } else { ord }
+                });
+            }
+
             let total = transactions.len() as u64;
             let paginated: Vec<TransactionResponse> = transactions
                 .into_iter()
@@ -334,6 +531,74 @@ pub async fn get_transactions(Query(params): Query<PaginationQuery>) -> impl Int
     }
 }
 
+/// Most rows a transactions CSV export sends, with or without `limit`
+const MAX_CSV_TRANSACTIONS: u64 = 50_000;
+
+/// GET /api/v1/transactions.csv
+/// Streams USDFC transfers as CSV, newest first
+///
+/// Blockscout is paged as rows are sent, so the export is not held in memory.
+/// It stops after `limit` rows, capped at [`MAX_CSV_TRANSACTIONS`]. The first
+/// page is fetched up front so an unreachable upstream still gets a 502; a
+/// failure after that ends the chunked body early.
+pub async fn get_transactions_csv(Query(query): Query<TransactionsCsvQuery>) -> axum::response::Response {
+    use crate::blockscout::BlockscoutClient;
+    use axum::body::Body;
+    use axum::http::header;
+    use usdfc_core::types::Transaction;
+
+    let client = BlockscoutClient::new();
+    let first_page = match client.get_transfers_page(None).await {
+        Ok(page) => page,
+        Err(e) => {
+            return (StatusCode::BAD_GATEWAY, Json(ApiResponse::<()>::error(e.to_string()))).into_response();
+        }
+    };
+
+    struct Export {
+        client: BlockscoutClient,
+        page: Option<(Vec<Transaction>, Option<String>)>,
+        remaining: u64,
+        error: Option<usdfc_core::error::ApiError>,
+    }
+
+    let header_chunk = futures::stream::once(async { Ok(Transaction::CSV_HEADER.to_string()) });
+    let remaining = query.limit.unwrap_or(MAX_CSV_TRANSACTIONS).min(MAX_CSV_TRANSACTIONS);
+    let export = Export { client, page: Some(first_page), remaining, error: None };
+    let rows = futures::stream::unfold(export, |mut export| async move {
+        // Rows already fetched go out before the error that stopped paging
+        if let Some(e) = export.error.take() {
+            return Some((Err(e), export));
+        }
+        let (transactions, next_cursor) = export.page.take()?;
+
+        let take = transactions.len().min(export.remaining as usize);
+        let chunk: String = transactions.iter().take(take).map(Transaction::csv_row).collect();
+        export.remaining -= take as u64;
+
+        let wants_more = !transactions.is_empty() && export.remaining > 0;
+        if let (Some(cursor), true) = (next_cursor, wants_more) {
+            match export.client.get_transfers_page(Some(&cursor)).await {
+                Ok(page) => export.page = Some(page),
+                Err(e) => {
+                    tracing::warn!("CSV export stopped after a Blockscout error: {}", e);
+                    export.error = Some(e);
+                }
+            }
+        }
+        Some((Ok(chunk), export))
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"usdfc_transactions.csv\""),
+        ],
+        Body::from_stream(futures::StreamExt::chain(header_chunk, rows)),
+    )
+        .into_response()
+}
+
 // ============================================================================
 // Address Endpoint
 // ============================================================================
@@ -382,18 +647,44 @@ pub async fn get_address(Path(addr): Path<String>) -> impl IntoResponse {
 
 /// GET /api/v1/lending
 /// Returns lending market data from Secured Finance subgraph
-pub async fn get_lending() -> impl IntoResponse {
+///
+/// Sort fields: `maturity`, `lend_apr`, `borrow_apr`. Filter matches currency.
+pub async fn get_lending(params: ListParams) -> impl IntoResponse {
+    if let Err(e) = params.check_sort(&["maturity", "lend_apr", "borrow_apr"]) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e.0)));
+    }
+
     match get_lending_markets().await {
         Ok(markets) => {
+            let mut markets: Vec<_> = markets
+                .into_iter()
+                .filter(|m| params.matches_filter(m.currency.symbol()))
+                .collect();
+
+            if let Some(sort) = &params.sort {
+                markets.sort_by(|a, b| {
+                    let ord = match sort.field.as_str() {
+                        "lend_apr" => a.lend_apr.total_cmp(&b.lend_apr),
+                        "borrow_apr" => a.borrow_apr.total_cmp(&b.borrow_apr),
+                        _ => a.maturity.parse::<i64>().unwrap_or(0)
+                            .cmp(&b.maturity.parse::<i64>().unwrap_or(0)),
+                    };
+                    if sort.descending { ord.reverse() } else { ord }
+                });
+            }
+
             let market_responses: Vec<LendingMarketResponse> = markets
                 .into_iter()
+                .skip(params.offset as usize)
+                .take(params.limit as usize)
                 .map(|m| LendingMarketResponse {
                     maturity: m.maturity,
-                    currency: m.currency,
+                    currency: m.currency.to_string(),
                     lend_apr: m.lend_apr,
                     borrow_apr: m.borrow_apr,
                     volume: m.volume,
                     is_active: m.is_active,
+                    featured: m.featured,
                 })
                 .collect();
 
@@ -409,6 +700,69 @@ pub async fn get_lending() -> impl IntoResponse {
     }
 }
 
+// ============================================================================
+// Order Book & Trades Endpoints
+// ============================================================================
+
+/// GET /api/v1/orderbook?maturity=xxx
+/// Returns the USDFC lending order book from Secured Finance subgraph
+pub async fn get_orderbook(Query(params): Query<OrderBookQuery>) -> impl IntoResponse {
+    let maturity = params.maturity.filter(|m| !m.is_empty());
+    if let Some(m) = &maturity {
+        if m.parse::<i64>().is_err() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!("Invalid maturity '{}': must be a Unix timestamp", m))),
+            );
+        }
+    }
+
+    match get_order_book(maturity).await {
+        Ok(book) => (StatusCode::OK, Json(ApiResponse::success(book))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(e.to_string())),
+        ),
+    }
+}
+
+/// Most recent trades `/api/v1/trades` fetches when filtering by side
+const TRADES_WINDOW: u32 = 500;
+
+/// GET /api/v1/trades
+/// Returns recent lending trades from Secured Finance subgraph
+///
+/// Sorting is not supported (newest first). Filter matches trade side and
+/// covers the most recent 500 trades.
+pub async fn get_trades(params: ListParams) -> impl IntoResponse {
+    if let Err(e) = params.check_sort(&[]) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e.0)));
+    }
+
+    // Filter before paging, so a filtered page is not cut short by the fetch
+    let fetch_limit = if params.filter.is_some() {
+        TRADES_WINDOW
+    } else {
+        params.limit.saturating_add(params.offset).min(TRADES_WINDOW)
+    };
+
+    match get_recent_lending_trades(Some(fetch_limit as i32)).await {
+        Ok(trades) => {
+            let trades = trades
+                .into_iter()
+                .filter(|t| params.matches_filter(&t.side))
+                .skip(params.offset as usize)
+                .take(params.limit as usize)
+                .collect();
+            (StatusCode::OK, Json(ApiResponse::success(LendingTradesResponse { trades })))
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(e.to_string())),
+        ),
+    }
+}
+
 // ============================================================================
 // Holders Endpoint
 // ============================================================================
@@ -416,10 +770,16 @@ pub async fn get_lending() -> impl IntoResponse {
 /// GET /api/v1/holders?cursor=xxx
 /// Returns USDFC holders with cursor-based pagination
 /// Pass cursor from previous response to get next page
-pub async fn get_holders(Query(params): Query<PaginationQuery>) -> impl IntoResponse {
+///
+/// Page size is fixed by Blockscout; sorting and offsets are not supported.
+pub async fn get_holders(params: ListParams) -> impl IntoResponse {
     use crate::blockscout::BlockscoutClient;
     use usdfc_core::config::config;
 
+    if let Err(e) = params.check_sort(&[]) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e.0)));
+    }
+
     let blockscout = BlockscoutClient::new();
     let cursor = params.cursor.as_deref();
 
@@ -4,8 +4,11 @@
 //! These endpoints wrap the existing server functions to provide
 //! a standard REST interface.
 
+pub mod etag;
 pub mod handlers;
 pub mod models;
+pub mod params;
 
 pub use handlers::*;
 pub use models::*;
+pub use params::*;
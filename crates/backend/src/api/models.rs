@@ -53,18 +53,22 @@ pub struct PriceResponse {
 }
 
 /// Protocol metrics response
+///
+/// Values whose upstream read failed are null; `missing` names them.
 #[derive(Serialize)]
 pub struct MetricsResponse {
     /// Total Collateralization Ratio as percentage
-    pub tcr: String,
+    pub tcr: Option<String>,
     /// Total USDFC supply
-    pub total_supply: String,
+    pub total_supply: Option<String>,
     /// Circulating USDFC supply (excludes stability pool)
-    pub circulating_supply: String,
+    pub circulating_supply: Option<String>,
     /// Total FIL collateral locked
-    pub total_collateral: String,
+    pub total_collateral: Option<String>,
+    /// Collateral value in USD at the oracle FIL price (None if unavailable)
+    pub total_collateral_usd: Option<String>,
     /// Number of active troves
-    pub active_troves: u64,
+    pub active_troves: Option<u64>,
     /// Number of USDFC token holders
     pub holders: Option<u64>,
     /// 24h trading volume in USD (None if unavailable)
@@ -72,7 +76,9 @@ pub struct MetricsResponse {
     /// Pool liquidity in USD (None if unavailable)
     pub liquidity_usd: Option<f64>,
     /// Stability pool USDFC balance
-    pub stability_pool_balance: String,
+    pub stability_pool_balance: Option<String>,
+    /// Protocol values that could not be fetched
+    pub missing: Vec<&'static str>,
 }
 
 /// Health check response
@@ -89,7 +95,7 @@ pub struct HealthResponse {
 pub struct ServiceStatus {
     /// Service name
     pub name: String,
-    /// Status: "healthy", "degraded", or "unhealthy"
+    /// Status: "healthy", "degraded", "unhealthy", or "disabled"
     pub status: String,
     /// Response latency in milliseconds (None if not measured)
     pub latency_ms: Option<u64>,
@@ -149,7 +155,7 @@ pub struct TransactionResponse {
 pub struct TransactionsListResponse {
     /// List of transactions
     pub transactions: Vec<TransactionResponse>,
-    /// Total count of transactions
+    /// Transactions matching the filter among the most recent 500
     pub total: u64,
     /// Current page offset
     pub offset: u32,
@@ -189,6 +195,8 @@ pub struct LendingMarketResponse {
     pub volume: String,
     /// Whether market is active
     pub is_active: bool,
+    /// Whether the market is featured (FEATURED_MARKETS)
+    pub featured: bool,
 }
 
 /// Lending markets list response
@@ -198,6 +206,20 @@ pub struct LendingMarketsResponse {
     pub markets: Vec<LendingMarketResponse>,
 }
 
+/// Order book query parameters
+#[derive(Deserialize)]
+pub struct OrderBookQuery {
+    /// Market maturity (Unix seconds); defaults to the nearest active market
+    pub maturity: Option<String>,
+}
+
+/// Recent lending trades response
+#[derive(Serialize)]
+pub struct LendingTradesResponse {
+    /// Trades, newest first
+    pub trades: Vec<usdfc_core::types::LendingTradeData>,
+}
+
 /// Historical data point
 #[derive(Serialize)]
 pub struct HistoricalDataPoint {
@@ -220,6 +242,8 @@ pub struct HistoricalResponse {
     pub to: i64,
     /// Data points
     pub data: Vec<HistoricalDataPoint>,
+    /// False when part of the range could not be fetched
+    pub complete: bool,
 }
 
 /// Query parameters for historical data
@@ -235,15 +259,38 @@ pub struct HistoryQuery {
     pub resolution: Option<String>,
 }
 
-/// Query parameters for pagination
+/// Query parameters for the metric history export
+#[derive(Deserialize)]
+pub struct HistoryExportQuery {
+    /// `ndjson` (default) or `csv`
+    pub format: Option<String>,
+    /// Export only this snapshot metric: tcr, supply, liquidity, holders, lendapr, borrowapr
+    pub metric: Option<String>,
+    /// Earliest timestamp to export (Unix seconds, inclusive)
+    pub start: Option<i64>,
+    /// Latest timestamp to export (Unix seconds, inclusive)
+    pub end: Option<i64>,
+}
+
+/// Query parameters for the streaming transactions CSV export
+#[derive(Deserialize)]
+pub struct TransactionsCsvQuery {
+    /// Maximum number of rows to export (default and maximum: 50000)
+    pub limit: Option<u64>,
+}
+
+/// Query parameters for the troves CSV export
 #[derive(Deserialize)]
-pub struct PaginationQuery {
-    /// Number of items per page (default: 20, max: 100)
+pub struct TrovesCsvQuery {
+    /// Maximum number of troves to export (default and maximum: 500)
     pub limit: Option<u32>,
-    /// Page offset (default: 0) - deprecated, use cursor instead
-    pub offset: Option<u32>,
-    /// Pagination cursor for next page
-    pub cursor: Option<String>,
+}
+
+/// Query parameters for the metrics endpoint
+#[derive(Deserialize)]
+pub struct MetricsQuery {
+    /// Comma-separated response fields to return (default: all)
+    pub fields: Option<String>,
 }
 
 /// Token holder response
@@ -266,6 +313,360 @@ pub struct TopHoldersResponse {
     pub total_holders: Option<u64>,
 }
 
+// ============================================================================
+// OpenAPI Description
+// ============================================================================
+//
+// Hand-maintained: when a model above changes, update its schema here too.
+// `test_openapi_schemas_match_models` catches field name drift.
+
+/// OpenAPI 3.0 description of the `/api/v1` endpoints, served at `/api/openapi.json`
+pub fn openapi_document() -> serde_json::Value {
+    serde_json::json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "USDFC Terminal API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Read-only REST API for USDFC protocol, market and token data. \
+                JSON responses share the `ApiResponse` envelope, with the payload in `data`.",
+        },
+        "servers": [{ "url": "/api" }],
+        "paths": openapi_paths(),
+        "components": {
+            "schemas": openapi_schemas(),
+            "responses": {
+                "Error": {
+                    "description": "Request failed",
+                    "content": { "application/json": { "schema": schema_ref("ErrorResponse") } },
+                },
+                "RateLimited": {
+                    "description": "Too many requests; retry after the `Retry-After` seconds",
+                    "content": { "application/json": { "schema": schema_ref("ErrorResponse") } },
+                },
+                "NotModified": { "description": "Data unchanged since the `If-None-Match` ETag" },
+            },
+        },
+    })
+}
+
+fn openapi_paths() -> serde_json::Value {
+    use serde_json::json;
+
+    let list = |sorts: &[&str], filter: &str| {
+        let mut params = vec![
+            query_param("limit", json!({ "type": "integer", "minimum": 1, "maximum": 100, "default": 20 }), "Page size (larger values are capped)"),
+            query_param("offset", json!({ "type": "integer", "minimum": 0, "default": 0 }), "Number of items to skip"),
+            query_param("filter", string(), filter),
+        ];
+        if !sorts.is_empty() {
+            let sort_values: Vec<String> = sorts.iter().flat_map(|f| [f.to_string(), format!("-{}", f)]).collect();
+            params.push(query_param("sort", json!({ "type": "string", "enum": sort_values }), "Sort field, `-` prefix for descending"));
+        }
+        params
+    };
+    let address = path_param("addr", "Filecoin address (0x, f1, f2, f3 or f4)");
+    let csv = |description: &str| json!({
+        "200": { "description": description, "content": { "text/csv": { "schema": string() } } },
+        "429": { "$ref": "#/components/responses/RateLimited" },
+        "500": { "$ref": "#/components/responses/Error" },
+    });
+
+    json!({
+        "/v1/version": get_operation("API version and build info", vec![], json_ok(schema_ref("VersionResponse"))),
+        "/v1/health": get_operation("Health of every upstream data source", vec![], json_ok(envelope("HealthResponse"))),
+        "/v1/price": get_operation("USDFC and FIL prices with 24h market data", vec![], conditional(envelope("PriceResponse"))),
+        "/v1/metrics": get_operation("Protocol metrics", vec![
+            query_param("fields", string(), "Comma-separated `MetricsResponse` fields to return (default: all)"),
+        ], with_errors(conditional(envelope("MetricsResponse")), &["400"])),
+        "/v1/history": get_operation("Historical time series for a metric", vec![
+            query_param("metric", json!({ "type": "string", "default": "volume" }), "Metric to retrieve: price, volume, tcr, supply, holders, etc."),
+            query_param("from", json!({ "type": "integer", "format": "int64" }), "Start timestamp (Unix seconds, default 30 days ago)"),
+            query_param("to", json!({ "type": "integer", "format": "int64" }), "End timestamp (Unix seconds, default now)"),
+            query_param("resolution", json!({ "type": "string", "enum": ["1m", "5m", "15m", "30m", "1h", "4h", "1d", "1w"], "default": "1d" }), "Data resolution"),
+        ], json_ok(envelope("HistoricalResponse"))),
+        "/v1/history/export": get_operation("Stored metric snapshots as a streamed download, oldest first", vec![
+            query_param("format", json!({ "type": "string", "enum": ["ndjson", "csv"], "default": "ndjson" }), "Newline-delimited JSON or CSV"),
+            query_param("metric", json!({ "type": "string", "enum": ["tcr", "supply", "liquidity", "holders", "lendapr", "borrowapr"] }), "Export only this metric (default: every snapshot field)"),
+            query_param("start", json!({ "type": "integer", "format": "int64" }), "Earliest timestamp (Unix seconds, inclusive)"),
+            query_param("end", json!({ "type": "integer", "format": "int64" }), "Latest timestamp (Unix seconds, inclusive)"),
+        ], json!({
+            "200": { "description": "One snapshot per line", "content": {
+                "application/x-ndjson": { "schema": string() },
+                "text/csv": { "schema": string() },
+            } },
+            "400": { "$ref": "#/components/responses/Error" },
+            "429": { "$ref": "#/components/responses/RateLimited" },
+            "500": { "$ref": "#/components/responses/Error" },
+        })),
+        "/v1/troves": get_operation("Active troves, paginated", list(&["collateral", "debt", "icr"], "Trove status"), with_errors(conditional(envelope("TrovesListResponse")), &["400"])),
+        "/v1/troves.csv": get_operation("Troves as a CSV download", vec![
+            query_param("limit", json!({ "type": "integer", "minimum": 1, "maximum": 500, "default": 500 }), "Maximum number of troves to export"),
+        ], csv("Address,Collateral,Debt,ICR,Status rows")),
+        "/v1/troves/{addr}": get_operation("Trove owned by an address", vec![address.clone()], with_errors(json_ok(envelope("TroveResponse")), &["400", "404"])),
+        "/v1/transactions": get_operation("Recent USDFC transactions, paginated", list(&["timestamp", "amount", "block"], "Transaction type"), with_errors(json_ok(envelope("TransactionsListResponse")), &["400"])),
+        "/v1/transactions.csv": get_operation("Transactions as a streamed CSV download", vec![
+            query_param("limit", json!({ "type": "integer", "minimum": 1 }), "Maximum number of rows to export (default: all)"),
+        ], csv("Hash,Type,Amount,From,To,Timestamp,Block,Status rows")),
+        "/v1/address/{addr}": get_operation("USDFC balance and activity for an address", vec![address], with_errors(json_ok(envelope("AddressInfoResponse")), &["400"])),
+        "/v1/lending": get_operation("Secured Finance lending markets", list(&["maturity", "lend_apr", "borrow_apr"], "Currency"), with_errors(json_ok(envelope("LendingMarketsResponse")), &["400"])),
+        "/v1/orderbook": get_operation("Secured Finance order book", vec![
+            query_param("maturity", string(), "Market maturity (Unix seconds); defaults to the nearest active market"),
+        ], json_ok(envelope("OrderBookData"))),
+        "/v1/trades": get_operation("Recent lending trades, newest first", list(&[], "Trade side"), with_errors(json_ok(envelope("LendingTradesResponse")), &["400"])),
+        "/v1/holders": get_operation("USDFC holders, cursor-paginated", vec![
+            query_param("cursor", string(), "`next_cursor` from the previous page"),
+        ], with_errors(json_ok(envelope("HoldersResponse")), &["400"])),
+        "/v1/chart.svg": get_operation("Server-rendered chart image", vec![
+            query_param("metrics", json!({ "type": "string", "default": "price" }), "Comma-separated chart metrics"),
+            query_param("res", json!({ "type": "string", "default": "1h" }), "Candle resolution"),
+            query_param("lookback", json!({ "type": "string", "default": "1w" }), "Lookback window"),
+            query_param("type", json!({ "type": "string", "enum": ["area", "line", "candle", "bar"], "default": "area" }), "Chart type"),
+            query_param("start", json!({ "type": "integer", "format": "int64" }), "Custom range start (Unix seconds); requires `end`"),
+            query_param("end", json!({ "type": "integer", "format": "int64" }), "Custom range end (Unix seconds); requires `start`"),
+        ], json!({
+            "200": { "description": "SVG chart", "content": { "image/svg+xml": { "schema": string() } } },
+            "429": { "$ref": "#/components/responses/RateLimited" },
+            "502": { "$ref": "#/components/responses/Error" },
+        })),
+    })
+}
+
+fn openapi_schemas() -> serde_json::Value {
+    use serde_json::json;
+
+    let integer = || json!({ "type": "integer", "format": "int64" });
+    let number = || json!({ "type": "number", "format": "double" });
+    let boolean = || json!({ "type": "boolean" });
+    let array = |items: serde_json::Value| json!({ "type": "array", "items": items });
+
+    json!({
+        "ErrorResponse": object(&[
+            ("success", boolean()),
+            ("timestamp", integer()),
+            ("error", string()),
+        ]),
+        "VersionResponse": object(&[
+            ("version", string()),
+            ("build_date", string()),
+            ("pagination_type", string()),
+        ]),
+        "PriceResponse": object(&[
+            ("usdfc_usd", nullable(number())),
+            ("fil_usd", nullable(number())),
+            ("change_24h", nullable(number())),
+            ("volume_24h", nullable(number())),
+            ("liquidity_usd", nullable(number())),
+        ]),
+        "MetricsResponse": object(&[
+            ("tcr", nullable(string())),
+            ("total_supply", nullable(string())),
+            ("circulating_supply", nullable(string())),
+            ("total_collateral", nullable(string())),
+            ("total_collateral_usd", nullable(string())),
+            ("active_troves", nullable(integer())),
+            ("holders", nullable(integer())),
+            ("volume_24h", nullable(number())),
+            ("liquidity_usd", nullable(number())),
+            ("stability_pool_balance", nullable(string())),
+            ("missing", array(string())),
+        ]),
+        "HealthResponse": object(&[
+            ("status", string()),
+            ("services", array(schema_ref("ServiceStatus"))),
+        ]),
+        "ServiceStatus": object(&[
+            ("name", string()),
+            ("status", json!({ "type": "string", "enum": ["healthy", "degraded", "unhealthy", "disabled"] })),
+            ("latency_ms", nullable(integer())),
+        ]),
+        "TroveResponse": object(&[
+            ("address", string()),
+            ("collateral", string()),
+            ("debt", string()),
+            ("icr", string()),
+            ("status", string()),
+        ]),
+        "TrovesListResponse": object(&[
+            ("troves", array(schema_ref("TroveResponse"))),
+            ("total", integer()),
+            ("offset", integer()),
+            ("limit", integer()),
+        ]),
+        "TransactionResponse": object(&[
+            ("hash", string()),
+            ("tx_type", string()),
+            ("amount", string()),
+            ("from", string()),
+            ("to", string()),
+            ("timestamp", integer()),
+            ("block", integer()),
+            ("status", json!({ "type": "string", "enum": ["pending", "success", "failed"] })),
+        ]),
+        "TransactionsListResponse": object(&[
+            ("transactions", array(schema_ref("TransactionResponse"))),
+            ("total", integer()),
+            ("offset", integer()),
+            ("limit", integer()),
+        ]),
+        "AddressInfoResponse": object(&[
+            ("address", string()),
+            ("usdfc_balance", string()),
+            ("transfer_count", integer()),
+            ("first_seen", string()),
+            ("address_type", string()),
+            ("f4_address", nullable(string())),
+        ]),
+        "LendingMarketResponse": object(&[
+            ("maturity", string()),
+            ("currency", string()),
+            ("lend_apr", number()),
+            ("borrow_apr", number()),
+            ("volume", string()),
+            ("is_active", boolean()),
+            ("featured", boolean()),
+        ]),
+        "LendingMarketsResponse": object(&[
+            ("markets", array(schema_ref("LendingMarketResponse"))),
+        ]),
+        "OrderData": object(&[
+            ("id", string()),
+            ("side", string()),
+            ("amount", number()),
+            ("filled", number()),
+            ("price", number()),
+            ("apr", number()),
+            ("user", nullable(string())),
+            ("created_at", string()),
+        ]),
+        "OrderFlowSide": object(&[
+            ("order_count", integer()),
+            ("volume", number()),
+            ("weighted_avg_price", nullable(number())),
+        ]),
+        "OrderBookData": object(&[
+            ("currency", string()),
+            ("maturity", nullable(string())),
+            ("lend_orders", array(schema_ref("OrderData"))),
+            ("borrow_orders", array(schema_ref("OrderData"))),
+            ("best_lend_price", nullable(number())),
+            ("best_borrow_price", nullable(number())),
+            ("spread_bps", nullable(number())),
+            ("lend_flow", schema_ref("OrderFlowSide")),
+            ("borrow_flow", schema_ref("OrderFlowSide")),
+            ("imbalance", nullable(number())),
+        ]),
+        "LendingTradeData": object(&[
+            ("id", string()),
+            ("currency", string()),
+            ("maturity", string()),
+            ("side", string()),
+            ("amount", number()),
+            ("price", number()),
+            ("apr", number()),
+            ("timestamp", integer()),
+        ]),
+        "LendingTradesResponse": object(&[
+            ("trades", array(schema_ref("LendingTradeData"))),
+        ]),
+        "HistoricalDataPoint": object(&[
+            ("timestamp", integer()),
+            ("value", number()),
+        ]),
+        "HistoricalResponse": object(&[
+            ("metric", string()),
+            ("resolution", string()),
+            ("from", integer()),
+            ("to", integer()),
+            ("data", array(schema_ref("HistoricalDataPoint"))),
+            ("complete", boolean()),
+        ]),
+        "TokenHolderResponse": object(&[
+            ("address", string()),
+            ("balance", string()),
+            ("share", nullable(number())),
+        ]),
+        "HoldersResponse": {
+            "type": "object",
+            "required": ["holders", "total_holders"],
+            "properties": {
+                "holders": array(schema_ref("TokenHolderResponse")),
+                "total_holders": nullable(integer()),
+                "next_cursor": { "type": "string", "description": "Absent on the last page" },
+            },
+        },
+    })
+}
+
+fn schema_ref(name: &str) -> serde_json::Value {
+    serde_json::json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn string() -> serde_json::Value {
+    serde_json::json!({ "type": "string" })
+}
+
+fn nullable(mut schema: serde_json::Value) -> serde_json::Value {
+    schema["nullable"] = true.into();
+    schema
+}
+
+/// Object schema whose fields are all always present
+fn object(fields: &[(&str, serde_json::Value)]) -> serde_json::Value {
+    let properties: serde_json::Map<_, _> = fields.iter().map(|(name, schema)| (name.to_string(), schema.clone())).collect();
+    let required: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+    serde_json::json!({ "type": "object", "required": required, "properties": properties })
+}
+
+/// `ApiResponse` envelope carrying `data_schema` as `data`
+fn envelope(data_schema: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "required": ["success", "timestamp", "data"],
+        "properties": {
+            "success": { "type": "boolean" },
+            "timestamp": { "type": "integer", "format": "int64" },
+            "data": schema_ref(data_schema),
+        },
+    })
+}
+
+fn query_param(name: &str, schema: serde_json::Value, description: &str) -> serde_json::Value {
+    serde_json::json!({ "name": name, "in": "query", "required": false, "schema": schema, "description": description })
+}
+
+fn path_param(name: &str, description: &str) -> serde_json::Value {
+    serde_json::json!({ "name": name, "in": "path", "required": true, "schema": string(), "description": description })
+}
+
+fn get_operation(summary: &str, parameters: Vec<serde_json::Value>, responses: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "get": { "summary": summary, "parameters": parameters, "responses": responses } })
+}
+
+/// 200 with a JSON body, plus the 429 and 500 every endpoint can return
+fn json_ok(schema: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "200": { "description": "Success", "content": { "application/json": { "schema": schema } } },
+        "429": { "$ref": "#/components/responses/RateLimited" },
+        "500": { "$ref": "#/components/responses/Error" },
+    })
+}
+
+/// `json_ok` for endpoints supporting `If-None-Match`
+fn conditional(schema: serde_json::Value) -> serde_json::Value {
+    let mut responses = json_ok(schema);
+    responses["200"]["headers"] = serde_json::json!({ "ETag": { "schema": string() } });
+    responses["304"] = serde_json::json!({ "$ref": "#/components/responses/NotModified" });
+    responses
+}
+
+/// Add error statuses such as 400 for invalid parameters
+fn with_errors(mut responses: serde_json::Value, statuses: &[&str]) -> serde_json::Value {
+    for status in statuses {
+        responses[*status] = serde_json::json!({ "$ref": "#/components/responses/Error" });
+    }
+    responses
+}
+
 // Helper function to get current Unix timestamp
 fn current_timestamp() -> i64 {
     #[cfg(feature = "ssr")]
@@ -280,3 +681,49 @@ fn current_timestamp() -> i64 {
         0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_names(value: impl Serialize) -> Vec<String> {
+        let mut names: Vec<String> = serde_json::to_value(value).unwrap().as_object().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn schema_fields(schemas: &serde_json::Value, name: &str) -> Vec<String> {
+        let mut names: Vec<String> = schemas[name]["properties"].as_object().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_openapi_schemas_match_models() {
+        let doc = openapi_document();
+        let schemas = &doc["components"]["schemas"];
+
+        let price = PriceResponse { usdfc_usd: None, fil_usd: None, change_24h: None, volume_24h: None, liquidity_usd: None };
+        assert_eq!(schema_fields(schemas, "PriceResponse"), field_names(price));
+        let trove = TroveResponse {
+            address: String::new(),
+            collateral: String::new(),
+            debt: String::new(),
+            icr: String::new(),
+            status: String::new(),
+        };
+        assert_eq!(schema_fields(schemas, "TroveResponse"), field_names(trove));
+        let point = HistoricalDataPoint { timestamp: 0, value: 0.0 };
+        assert_eq!(schema_fields(schemas, "HistoricalDataPoint"), field_names(point));
+        let flow = usdfc_core::types::OrderFlowSide::default();
+        assert_eq!(schema_fields(schemas, "OrderFlowSide"), field_names(flow));
+
+        // Every reference resolves
+        let text = doc.to_string();
+        for reference in text.split("\"$ref\":\"").skip(1) {
+            let target = &reference[..reference.find('"').unwrap()];
+            let (kind, name) = target.trim_start_matches("#/components/").split_once('/').unwrap();
+            assert!(doc["components"][kind].get(name).is_some(), "unresolved {}", target);
+        }
+    }
+}
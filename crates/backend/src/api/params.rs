@@ -0,0 +1,293 @@
+//! Shared query parameters for paginated REST list endpoints
+//!
+//! `ListParams` is an Axum extractor that parses and validates the common
+//! `limit`, `offset`, `cursor`, `sort` and `filter` query parameters so each
+//! list handler does not have to re-implement the same checks.
+//!
+//! | Param    | Type   | Description                                              |
+//! |----------|--------|----------------------------------------------------------|
+//! | `limit`  | u32    | Page size, 1..=100 (default 20, larger values are capped) |
+//! | `offset` | u32    | Number of items to skip, 0..=1000 (default 0, capped)    |
+//! | `cursor` | string | Opaque cursor for cursor-paginated endpoints             |
+//! | `sort`   | string | Sort field, prefix with `-` for descending order         |
+//! | `filter` | string | Endpoint-specific filter value (case-insensitive)        |
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::api::models::ApiResponse;
+
+/// Default page size when `limit` is omitted
+pub const DEFAULT_LIMIT: u32 = 20;
+
+/// Maximum page size; larger values are capped
+pub const MAX_LIMIT: u32 = 100;
+
+/// Maximum offset; larger values are capped so a request cannot force an
+/// arbitrarily large upstream fetch
+pub const MAX_OFFSET: u32 = 1000;
+
+/// Raw query string values before validation
+///
+/// Everything is parsed as a string so that malformed values (e.g. `limit=-1`)
+/// produce a descriptive 400 instead of Axum's generic query rejection.
+#[derive(Debug, Default, Deserialize)]
+pub struct RawListQuery {
+    pub limit: Option<String>,
+    pub offset: Option<String>,
+    pub cursor: Option<String>,
+    pub sort: Option<String>,
+    pub filter: Option<String>,
+}
+
+/// Requested sort order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec {
+    /// Field name (without the `-` prefix)
+    pub field: String,
+    /// True when the field was prefixed with `-`
+    pub descending: bool,
+}
+
+/// Validated list parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListParams {
+    pub limit: u32,
+    pub offset: u32,
+    pub cursor: Option<String>,
+    pub sort: Option<SortSpec>,
+    pub filter: Option<String>,
+}
+
+/// Rejection returned when list parameters are invalid (400 Bad Request)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListParamsRejection(pub String);
+
+impl IntoResponse for ListParamsRejection {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(self.0)),
+        )
+            .into_response()
+    }
+}
+
+impl ListParams {
+    /// Validate raw query values
+    pub fn from_raw(raw: RawListQuery) -> Result<Self, ListParamsRejection> {
+        let limit = match raw.limit.as_deref() {
+            None | Some("") => DEFAULT_LIMIT,
+            Some(v) => match v.trim().parse::<u32>() {
+                Ok(0) | Err(_) => {
+                    return Err(ListParamsRejection(format!(
+                        "Invalid limit '{}': must be an integer between 1 and {}",
+                        v, MAX_LIMIT
+                    )))
+                }
+                Ok(n) => n.min(MAX_LIMIT),
+            },
+        };
+
+        let offset = match raw.offset.as_deref() {
+            None | Some("") => 0,
+            Some(v) => v.trim().parse::<u32>().map_err(|_| {
+                ListParamsRejection(format!(
+                    "Invalid offset '{}': must be a non-negative integer",
+                    v
+                ))
+            })?.min(MAX_OFFSET),
+        };
+
+        let sort = match raw.sort.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(v) => {
+                let (field, descending) = match v.strip_prefix('-') {
+                    Some(field) => (field, true),
+                    None => (v, false),
+                };
+                if field.is_empty() {
+                    return Err(ListParamsRejection(format!(
+                        "Invalid sort '{}': missing field name",
+                        v
+                    )));
+                }
+                Some(SortSpec {
+                    field: field.to_lowercase(),
+                    descending,
+                })
+            }
+        };
+
+        let filter = raw
+            .filter
+            .map(|f| f.trim().to_lowercase())
+            .filter(|f| !f.is_empty());
+
+        let cursor = raw.cursor.filter(|c| !c.is_empty());
+
+        Ok(Self {
+            limit,
+            offset,
+            cursor,
+            sort,
+            filter,
+        })
+    }
+
+    /// Ensure the requested sort field is supported by the endpoint
+    pub fn check_sort(&self, allowed: &[&str]) -> Result<(), ListParamsRejection> {
+        match &self.sort {
+            Some(sort) if !allowed.contains(&sort.field.as_str()) => {
+                Err(ListParamsRejection(if allowed.is_empty() {
+                    "Sorting is not supported by this endpoint".to_string()
+                } else {
+                    format!(
+                        "Invalid sort field '{}': expected one of {}",
+                        sort.field,
+                        allowed.join(", ")
+                    )
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether an item's filterable value matches the requested filter
+    pub fn matches_filter(&self, value: &str) -> bool {
+        match &self.filter {
+            Some(filter) => value.to_lowercase() == *filter,
+            None => true,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ListParams
+where
+    S: Send + Sync,
+{
+    type Rejection = ListParamsRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(raw) = Query::<RawListQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| ListParamsRejection(format!("Invalid query string: {}", e)))?;
+        Self::from_raw(raw)
+    }
+}
+
+/// Keep only the comma-separated `fields` of a serialized response object
+///
+/// Field names are whatever the response serializes to, so new response
+/// fields become selectable without changes here. Unknown names are rejected.
+pub fn select_fields(data: serde_json::Value, fields: &str) -> Result<serde_json::Value, ListParamsRejection> {
+    let serde_json::Value::Object(mut object) = data else {
+        return Err(ListParamsRejection("Field selection is not supported by this endpoint".to_string()));
+    };
+
+    let mut selected = serde_json::Map::new();
+    for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        if selected.contains_key(field) {
+            continue;
+        }
+        let Some(value) = object.remove(field) else {
+            let mut known: Vec<&str> = object.keys().chain(selected.keys()).map(String::as_str).collect();
+            known.sort_unstable();
+            return Err(ListParamsRejection(format!(
+                "Unknown field '{}': expected one of {}",
+                field,
+                known.join(", ")
+            )));
+        };
+        selected.insert(field.to_string(), value);
+    }
+
+    if selected.is_empty() {
+        return Err(ListParamsRejection("Invalid fields: no field names given".to_string()));
+    }
+    Ok(serde_json::Value::Object(selected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(limit: Option<&str>, offset: Option<&str>, sort: Option<&str>) -> RawListQuery {
+        RawListQuery {
+            limit: limit.map(String::from),
+            offset: offset.map(String::from),
+            sort: sort.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_defaults() {
+        let params = ListParams::from_raw(RawListQuery::default()).unwrap();
+        assert_eq!(params.limit, DEFAULT_LIMIT);
+        assert_eq!(params.offset, 0);
+        assert!(params.sort.is_none());
+        assert!(params.filter.is_none());
+    }
+
+    #[test]
+    fn test_limit_is_capped() {
+        let params = ListParams::from_raw(raw(Some("500"), None, None)).unwrap();
+        assert_eq!(params.limit, MAX_LIMIT);
+    }
+
+    #[test]
+    fn test_invalid_limit_rejected() {
+        for bad in ["-1", "0", "abc", "1.5"] {
+            let err = ListParams::from_raw(raw(Some(bad), None, None)).unwrap_err();
+            assert!(err.0.contains("Invalid limit"), "{}", err.0);
+        }
+    }
+
+    #[test]
+    fn test_offset_is_capped() {
+        let params = ListParams::from_raw(raw(Some("500"), Some("4294967295"), None)).unwrap();
+        assert_eq!(params.offset, MAX_OFFSET);
+        assert!(params.limit.checked_add(params.offset).is_some());
+    }
+
+    #[test]
+    fn test_invalid_offset_rejected() {
+        let err = ListParams::from_raw(raw(None, Some("-5"), None)).unwrap_err();
+        assert!(err.0.contains("Invalid offset"));
+    }
+
+    #[test]
+    fn test_sort_parsing_and_validation() {
+        let params = ListParams::from_raw(raw(None, None, Some("-Debt"))).unwrap();
+        assert_eq!(
+            params.sort,
+            Some(SortSpec { field: "debt".to_string(), descending: true })
+        );
+        assert!(params.check_sort(&["debt", "icr"]).is_ok());
+        assert!(params.check_sort(&["icr"]).is_err());
+        assert!(params.check_sort(&[]).is_err());
+
+        assert!(ListParams::from_raw(raw(None, None, Some("-"))).is_err());
+    }
+
+    #[test]
+    fn test_select_fields() {
+        let data = serde_json::json!({ "tcr": "181.20%", "total_supply": "1000", "holders": null });
+
+        let selected = select_fields(data.clone(), "total_supply, tcr,tcr").unwrap();
+        assert_eq!(selected, serde_json::json!({ "total_supply": "1000", "tcr": "181.20%" }));
+        assert_eq!(select_fields(data.clone(), "holders").unwrap(), serde_json::json!({ "holders": null }));
+
+        let err = select_fields(data.clone(), "tcr,supply").unwrap_err();
+        assert_eq!(err.0, "Unknown field 'supply': expected one of holders, tcr, total_supply");
+        assert!(select_fields(data, " , ").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+//! Shared-secret checks for write endpoints
+//!
+//! Mutating server functions (address notes, alert acknowledgement) are gated
+//! by a token configured in the environment. An unset token disables the
+//! endpoint entirely rather than leaving it open. Routes listed in
+//! `PROTECTED_ROUTES` use HTTP basic auth checked the same way.
+
+use base64::Engine;
+use ring::hmac;
+
+/// Whether `provided` matches the configured `expected` token
+///
+/// Always false when `expected` is empty. Both sides are compared through an
+/// HMAC so the check is constant-time regardless of where they differ.
+pub fn token_matches(provided: &str, expected: &str) -> bool {
+    if expected.is_empty() {
+        return false;
+    }
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"usdfc-write-token");
+    let expected_tag = hmac::sign(&key, expected.as_bytes());
+    hmac::verify(&key, provided.as_bytes(), expected_tag.as_ref()).is_ok()
+}
+
+/// Whether an `Authorization` header carries the configured basic credentials
+///
+/// Always false when `password` is empty.
+pub fn basic_auth_matches(header: Option<&str>, user: &str, password: &str) -> bool {
+    if password.is_empty() {
+        return false;
+    }
+    let Some(provided) = header.and_then(|h| h.strip_prefix("Basic ")) else {
+        return false;
+    };
+    let expected = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password));
+    token_matches(provided.trim(), &expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_requires_configured_match() {
+        assert!(token_matches("secret", "secret"));
+        assert!(!token_matches("Secret", "secret"));
+        assert!(!token_matches("", ""));
+        assert!(!token_matches("anything", ""));
+    }
+
+    #[test]
+    fn test_basic_auth_header() {
+        // base64("admin:hunter2")
+        let header = Some("Basic YWRtaW46aHVudGVyMg==");
+        assert!(basic_auth_matches(header, "admin", "hunter2"));
+        assert!(!basic_auth_matches(header, "admin", "hunter3"));
+        assert!(!basic_auth_matches(Some("Bearer YWRtaW46aHVudGVyMg=="), "admin", "hunter2"));
+        assert!(!basic_auth_matches(None, "admin", "hunter2"));
+        assert!(!basic_auth_matches(Some("Basic Og=="), "", ""));
+    }
+}
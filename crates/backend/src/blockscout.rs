@@ -1,9 +1,22 @@
 use usdfc_core::config::config;
 use usdfc_core::error::{ApiError, ApiResult};
-use usdfc_core::types::{Transaction, TransactionType, TransactionStatus};
+use usdfc_core::types::{
+    LiquidationEvent, LiquidationKind, TokenInfo, Transaction, TransactionType, TransactionStatus,
+    DEFAULT_TOKEN_DECIMALS,
+};
 use rust_decimal::Decimal;
-use std::time::Duration;
 use serde::Deserialize;
+use crate::timing::{TimedSend, UpstreamError};
+
+/// Some Blockscout deployments reject requests without a browser user agent
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36";
+
+/// Map a failed send, keeping timeouts distinct
+fn request_failed(e: UpstreamError) -> ApiError {
+    e.into_api_error(crate::http::upstream_timeout(), |e| {
+        ApiError::HttpError(format!("Request failed: {}", e))
+    })
+}
 
 /// Blockscout API client
 #[derive(Clone)]
@@ -16,7 +29,6 @@ pub struct BlockscoutClient {
 struct TransfersResponse {
     #[serde(default)]
     items: Vec<TransferItem>,
-    #[allow(dead_code)]
     next_page_params: Option<serde_json::Value>,
 }
 
@@ -52,15 +64,27 @@ struct CountersResponse {
 impl BlockscoutClient {
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-                .timeout(Duration::from_secs(10))
-                .build()
-                .expect("failed to build blockscout HTTP client"),
+            client: crate::http::shared_client(),
             base_url: config().blockscout_url.clone(),
         }
     }
 
+    /// GET on the shared client with Blockscout's user agent and timeout
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get(url)
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .timeout(crate::http::upstream_timeout())
+    }
+
+    /// POST on the shared client with Blockscout's user agent and timeout
+    fn post(&self, url: &str) -> reqwest::RequestBuilder {
+        self.client
+            .post(url)
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .timeout(crate::http::upstream_timeout())
+    }
+
     /// Get recent transfers for USDFC token with automatic pagination
     ///
     /// **Pagination:** Automatically fetches multiple pages using `next_page_params` to support
@@ -74,67 +98,21 @@ impl BlockscoutClient {
         let max_pages = max_pages.unwrap_or(100); // Default 100 pages = 5000 items (was 4 = 200)
 
         let mut all_items: Vec<TransferItem> = Vec::new();
-        let mut next_page_params: Option<serde_json::Value> = None;
+        let mut cursor: Option<String> = None;
         let mut page_count = 0;
 
         // Paginate through results until we have enough items
         loop {
-            let mut url = format!(
-                "{}/tokens/{}/transfers",
-                self.base_url,
-                config().usdfc_token
-            );
-
-            // Add pagination params if available
-            if let Some(ref params) = next_page_params {
-                if let Some(params_obj) = params.as_object() {
-                    let query_params: Vec<String> = params_obj
-                        .iter()
-                        .map(|(k, v)| {
-                            let value_str = match v {
-                                serde_json::Value::String(s) => s.clone(),
-                                serde_json::Value::Number(n) => n.to_string(),
-                                serde_json::Value::Bool(b) => b.to_string(),
-                                _ => v.to_string(),
-                            };
-                            format!("{}={}", k, value_str)
-                        })
-                        .collect();
-                    if !query_params.is_empty() {
-                        url = format!("{}?{}", url, query_params.join("&"));
-                    }
-                }
-            }
-
-            let response = self
-                .client
-                .get(&url)
-                .send()
-                .await
-                .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let body = response
-                    .text()
-                    .await
-                    .map_err(|e| ApiError::HttpError(format!("HTTP {}: failed to read body: {}", status, e)))?;
-                return Err(ApiError::HttpError(format!("HTTP {}: {}", status, body)));
-            }
-
-            let transfers: TransfersResponse = response
-                .json()
-                .await
-                .map_err(|e| ApiError::parse("transfers", format!("JSON parse error: {}", e)))?;
+            let transfers = self.fetch_transfers(cursor.as_deref()).await?;
 
             let items_count = transfers.items.len();
             all_items.extend(transfers.items);
-            next_page_params = transfers.next_page_params;
+            cursor = transfers.next_page_params.as_ref().and_then(page_params_query);
             page_count += 1;
 
             // Stop if: we have enough items, no more pages, hit configurable page limit, or no items returned
             if all_items.len() >= limit as usize
-                || next_page_params.is_none()
+                || cursor.is_none()
                 || page_count >= max_pages
                 || items_count == 0 {
                 break;
@@ -142,41 +120,58 @@ impl BlockscoutClient {
         }
 
         // Convert to Transaction type and take only what we need
-        let transactions = all_items
+        all_items
             .into_iter()
             .take(limit as usize)
-            .map(|item| -> ApiResult<Transaction> {
-                let amount_decimal = parse_token_amount(&item.total.value, &item.total.decimals)?;
+            .map(transfer_to_transaction)
+            .collect()
+    }
 
-                // Determine transaction type (simplified)
-                let tx_type = if item.to.hash == "0x0000000000000000000000000000000000000000" {
-                    TransactionType::Burn
-                } else if item.from.hash == "0x0000000000000000000000000000000000000000" {
-                    TransactionType::Mint
-                } else {
-                    TransactionType::Transfer
-                };
+    /// Get one page of USDFC transfers, newest first
+    ///
+    /// Pass the cursor returned with the previous page to continue; the returned
+    /// cursor is `None` on the last page.
+    pub async fn get_transfers_page(&self, cursor: Option<&str>) -> ApiResult<(Vec<Transaction>, Option<String>)> {
+        let transfers = self.fetch_transfers(cursor).await?;
+        let next_cursor = transfers.next_page_params.as_ref().and_then(page_params_query);
+        let transactions = transfers
+            .items
+            .into_iter()
+            .map(transfer_to_transaction)
+            .collect::<ApiResult<Vec<_>>>()?;
+        Ok((transactions, next_cursor))
+    }
 
-                // Parse timestamp to unix seconds
-                let timestamp = chrono::DateTime::parse_from_rfc3339(&item.timestamp)
-                    .map(|dt| dt.timestamp() as u64)
-                    .map_err(|e| ApiError::parse("timestamp", format!("{}", e)))?;
+    /// Fetch one raw transfers page
+    async fn fetch_transfers(&self, cursor: Option<&str>) -> ApiResult<TransfersResponse> {
+        let mut url = format!(
+            "{}/tokens/{}/transfers",
+            self.base_url,
+            config().usdfc_token
+        );
+        if let Some(query) = cursor {
+            url = format!("{}?{}", url, query);
+        }
 
-                let tx_hash = item.transaction_hash.clone();
-                Ok(Transaction {
-                    hash: item.transaction_hash,
-                    tx_type,
-                    amount: amount_decimal,
-                    from: item.from.hash,
-                    to: item.to.hash,
-                    timestamp,
-                    block: item.block_number.ok_or(ApiError::NotFound { resource: "block_number", id: tx_hash })?,
-                    status: TransactionStatus::Success,
-                })
-            })
-            .collect::<ApiResult<Vec<_>>>()?;
+        let response = self
+            .get(&url)
+            .timed_send("blockscout")
+            .await
+            .map_err(request_failed)?;
 
-        Ok(transactions)
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .map_err(|e| ApiError::HttpError(format!("HTTP {}: failed to read body: {}", status, e)))?;
+            return Err(ApiError::HttpError(format!("HTTP {}: {}", status, body)));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ApiError::parse("transfers", format!("JSON parse error: {}", e)))
     }
 
     /// Get token holder count
@@ -188,11 +183,10 @@ impl BlockscoutClient {
         );
 
         let response = self
-            .client
             .get(&url)
-            .send()
+            .timed_send("blockscout")
             .await
-            .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -226,6 +220,59 @@ impl Default for BlockscoutClient {
     }
 }
 
+/// Blockscout `next_page_params` as a query string for the next request
+///
+/// `None` when there is no next page (null, or an empty object).
+fn page_params_query(params: &serde_json::Value) -> Option<String> {
+    let obj = params.as_object()?;
+    let query = obj
+        .iter()
+        .map(|(k, v)| {
+            let value_str = match v {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                _ => v.to_string(),
+            };
+            format!("{}={}", k, value_str)
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    (!query.is_empty()).then_some(query)
+}
+
+/// Convert a raw transfer item into a `Transaction`
+fn transfer_to_transaction(item: TransferItem) -> ApiResult<Transaction> {
+    let amount_decimal = parse_token_amount(&item.total.value, &item.total.decimals)?;
+
+    // Determine transaction type (simplified)
+    let tx_type = if item.to.hash == "0x0000000000000000000000000000000000000000" {
+        TransactionType::Burn
+    } else if item.from.hash == "0x0000000000000000000000000000000000000000" {
+        TransactionType::Mint
+    } else {
+        TransactionType::Transfer
+    };
+
+    // Parse timestamp to unix seconds
+    let timestamp = chrono::DateTime::parse_from_rfc3339(&item.timestamp)
+        .map(|dt| dt.timestamp() as u64)
+        .map_err(|e| ApiError::parse("timestamp", format!("{}", e)))?;
+
+    let tx_hash = item.transaction_hash.clone();
+    Ok(Transaction {
+        hash: item.transaction_hash,
+        tx_type,
+        amount: amount_decimal,
+        from: item.from.hash,
+        to: item.to.hash,
+        timestamp,
+        block: item.block_number.ok_or(ApiError::NotFound { resource: "block_number", id: tx_hash })?,
+        status: TransactionStatus::Success,
+        is_new: false,
+    })
+}
+
 impl BlockscoutClient {
     /// Get token holders with cursor-based pagination
     /// Pass cursor (next_page_params from previous response) to get next page
@@ -243,8 +290,8 @@ impl BlockscoutClient {
             url = format!("{}?{}", url, cursor_str);
         }
 
-        let response = self.client.get(&url).send().await
-            .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+        let response = self.get(&url).timed_send("blockscout").await
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(ApiError::HttpError(format!(
@@ -271,25 +318,7 @@ impl BlockscoutClient {
             .collect::<ApiResult<Vec<_>>>()?;
 
         // Convert next_page_params to query string
-        let next_cursor = data.next_page_params.and_then(|params| {
-            if params.is_null() {
-                return None;
-            }
-            params.as_object().map(|obj| {
-                obj.iter()
-                    .map(|(k, v)| {
-                        let value_str = match v {
-                            serde_json::Value::String(s) => s.clone(),
-                            serde_json::Value::Number(n) => n.to_string(),
-                            serde_json::Value::Bool(b) => b.to_string(),
-                            _ => v.to_string(),
-                        };
-                        format!("{}={}", k, value_str)
-                    })
-                    .collect::<Vec<_>>()
-                    .join("&")
-            })
-        });
+        let next_cursor = data.next_page_params.as_ref().and_then(page_params_query);
 
         Ok((holders, next_cursor))
     }
@@ -303,11 +332,10 @@ impl BlockscoutClient {
         let url = format!("{}/addresses/{}/token-balances", self.base_url, address);
 
         let response = self
-            .client
             .get(&url)
-            .send()
+            .timed_send("blockscout")
             .await
-            .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(ApiError::HttpError(format!(
@@ -349,11 +377,10 @@ impl BlockscoutClient {
         );
 
         let response = self
-            .client
             .get(&url)
-            .send()
+            .timed_send("blockscout")
             .await
-            .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(ApiError::HttpError(format!(
@@ -398,6 +425,7 @@ impl BlockscoutClient {
                     block: item.block_number.ok_or(ApiError::NotFound { resource: "block_number", id: tx_hash })?,
                     tx_type,
                     status: TransactionStatus::Success,
+                    is_new: false,
                 })
             })
             .collect::<ApiResult<Vec<_>>>()?;
@@ -413,11 +441,10 @@ impl BlockscoutClient {
         );
 
         let response = self
-            .client
             .get(&url)
-            .send()
+            .timed_send("blockscout")
             .await
-            .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(ApiError::HttpError(format!(
@@ -479,13 +506,29 @@ pub struct TokenHolder {
 
 #[derive(Deserialize, Debug)]
 struct TokenBalanceItem {
-    token: TokenInfo,
+    token: BalanceToken,
     value: String,
 }
 
 #[derive(Deserialize, Debug)]
-struct TokenInfo {
+struct BalanceToken {
     address: String,
+    decimals: Option<String>,
+}
+
+/// `/tokens/{address}` response
+#[derive(Deserialize, Debug)]
+struct TokenResponse {
+    #[serde(alias = "address_hash")]
+    address: Option<String>,
+    name: Option<String>,
+    symbol: Option<String>,
+    decimals: Option<String>,
+    total_supply: Option<String>,
+    #[serde(alias = "holders_count")]
+    holders: Option<String>,
+    volume_24h: Option<String>,
+    circulating_market_cap: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -521,9 +564,8 @@ pub struct PoolInfo {
 fn parse_token_amount(value: &str, decimals: &str) -> ApiResult<Decimal> {
     let decimals = decimals.parse::<u32>()
         .map_err(|e| ApiError::parse("decimals", format!("{}", e)))?;
-    let raw = value.parse::<u128>()
-        .map_err(|e| ApiError::parse("amount", format!("{}", e)))?;
-    Ok(Decimal::from_i128_with_scale(raw as i128, decimals))
+    usdfc_core::format::parse_token_amount(value, decimals)
+        .ok_or_else(|| ApiError::parse("amount", value))
 }
 
 impl BlockscoutClient {
@@ -546,7 +588,12 @@ impl BlockscoutClient {
             .map(|b| {
                 let value = b.value.parse::<u128>()
                     .map_err(|e| ApiError::parse("usdfc_balance", format!("{}", e)))?;
-                let decimals = 18u32;
+                let decimals = match &b.token.decimals {
+                    Some(decimals) => decimals
+                        .parse::<u32>()
+                        .map_err(|e| ApiError::parse("decimals", format!("{}", e)))?,
+                    None => DEFAULT_TOKEN_DECIMALS,
+                };
                 Ok((value as f64) / 10f64.powi(decimals as i32))
             })
             .transpose()?
@@ -574,8 +621,8 @@ impl BlockscoutClient {
             Err(gql_err) => {
                 // Fallback to REST API if GraphQL fails
                 let addr_url = format!("{}/addresses/{}", self.base_url, address);
-                let addr_response = self.client.get(&addr_url).send().await
-                    .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+                let addr_response = self.get(&addr_url).timed_send("blockscout").await
+                    .map_err(request_failed)?;
 
                 if !addr_response.status().is_success() {
                     // If both GraphQL and REST fail, return the original GraphQL error
@@ -594,8 +641,8 @@ impl BlockscoutClient {
                     "{}/addresses/{}/token-transfers?token={}",
                     self.base_url, address, config().usdfc_token
                 );
-                let transfers_response = self.client.get(&transfers_url).send().await
-                    .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+                let transfers_response = self.get(&transfers_url).timed_send("blockscout").await
+                    .map_err(request_failed)?;
 
                 if !transfers_response.status().is_success() {
                     return Err(ApiError::HttpError(format!(
@@ -628,8 +675,8 @@ impl BlockscoutClient {
     async fn get_token_balances_rest(&self, address: &str) -> ApiResult<Vec<TokenBalanceItem>> {
         let url = format!("{}/addresses/{}/token-balances", self.base_url, address);
 
-        let response = self.client.get(&url).send().await
-            .map_err(|e| ApiError::HttpError(format!("Request failed: {}", e)))?;
+        let response = self.get(&url).timed_send("blockscout").await
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(ApiError::HttpError(format!("Address not found: {}", response.status())));
@@ -749,7 +796,7 @@ struct GqlError {
 }
 
 // GraphQL response types
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct GqlToken {
     #[serde(rename = "contractAddressHash")]
     pub contract_address_hash: Option<String>,
@@ -766,6 +813,52 @@ pub struct GqlToken {
     pub circulating_market_cap: Option<String>,
 }
 
+impl GqlToken {
+    /// Typed token metadata; decimals are required, a missing supply counts as zero
+    pub fn token_info(&self, token_address: &str) -> ApiResult<TokenInfo> {
+        let decimals = self
+            .decimals
+            .as_deref()
+            .ok_or_else(|| ApiError::parse("decimals", "missing from token metadata"))?;
+        let decimals: u32 = decimals
+            .parse()
+            .map_err(|e| ApiError::parse("decimals", format!("{}", e)))?;
+        let total_supply = match &self.total_supply {
+            Some(raw) => parse_token_amount(raw, &decimals.to_string())?,
+            None => Decimal::ZERO,
+        };
+        Ok(TokenInfo {
+            address: self.contract_address_hash.clone().unwrap_or_else(|| token_address.to_string()),
+            name: self.name.clone().unwrap_or_default(),
+            symbol: self.symbol.clone().unwrap_or_default(),
+            decimals,
+            total_supply,
+        })
+    }
+}
+
+/// Metadata of the configured USDFC token, cached for the `token_info` TTL
+pub async fn usdfc_token_info(blockscout: &BlockscoutClient) -> ApiResult<TokenInfo> {
+    crate::cache::caches::TOKEN_INFO
+        .get_or_compute("usdfc", || async {
+            let token = &config().usdfc_token;
+            blockscout.gql_get_token_info(token).await?.token_info(token)
+        })
+        .await
+}
+
+/// Decimals of the configured USDFC token, falling back to the default when
+/// the metadata cannot be read
+pub async fn usdfc_decimals(blockscout: &BlockscoutClient) -> u32 {
+    match usdfc_token_info(blockscout).await {
+        Ok(token) => token.decimals,
+        Err(e) => {
+            tracing::warn!(upstream = "blockscout", error = %e, "Token metadata unavailable, assuming {} decimals", DEFAULT_TOKEN_DECIMALS);
+            DEFAULT_TOKEN_DECIMALS
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct TokenTransfersData {
     #[serde(rename = "tokenTransfers")]
@@ -782,7 +875,6 @@ struct TokenTransferConnection {
 #[derive(Deserialize, Debug)]
 struct TokenTransferEdge {
     node: Option<GqlTokenTransfer>,
-    cursor: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -852,12 +944,15 @@ impl BlockscoutClient {
         };
 
         let response = self
-            .client
             .post(&self.graphql_url())
             .json(&request)
-            .send()
+            .timed_send("blockscout")
             .await
-            .map_err(|e| ApiError::GraphQLError(format!("Request failed: {}", e)))?;
+            .map_err(|e| {
+                e.into_api_error(crate::http::upstream_timeout(), |e| {
+                    ApiError::GraphQLError(format!("Request failed: {}", e))
+                })
+            })?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -886,50 +981,45 @@ impl BlockscoutClient {
             .ok_or_else(|| ApiError::GraphQLError("No data in response".to_string()))
     }
 
-    /// Get token info via GraphQL (includes holder count, volume)
+    /// Get token metadata (name, symbol, decimals, supply, holder count)
+    ///
+    /// The GraphQL schema has no token query, so this reads the REST
+    /// `/tokens/{address}` endpoint into the GraphQL token shape.
     pub async fn gql_get_token_info(&self, token_address: &str) -> ApiResult<GqlToken> {
-        #[derive(Deserialize, Debug)]
-        struct TokenData {
-            address: Option<AddressWithToken>,
-        }
+        let url = format!("{}/tokens/{}", self.base_url, token_address);
 
-        #[derive(Deserialize, Debug)]
-        struct AddressWithToken {
-            #[serde(rename = "smartContract")]
-            smart_contract: Option<serde_json::Value>,
-        }
+        let response = self
+            .get(&url)
+            .timed_send("blockscout")
+            .await
+            .map_err(request_failed)?;
 
-        // Blockscout GraphQL doesn't have a direct token query,
-        // we need to use the REST API for token-specific data
-        // But we can get address info which includes some token data
-        let query = format!(
-            r#"
-            query {{
-                address(hash: "{}") {{
-                    hash
-                    fetchedCoinBalance
-                    transactionsCount
-                    tokenTransfersCount
-                }}
-            }}
-            "#,
-            token_address
-        );
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(ApiError::not_found("token", token_address));
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .map_err(|e| ApiError::HttpError(format!("HTTP {}: failed to read body: {}", status, e)))?;
+            return Err(ApiError::HttpError(format!("HTTP {}: {}", status, body)));
+        }
 
-        let _data: AddressData = self.gql_query(query).await?;
+        let token: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| ApiError::parse("token", format!("JSON parse error: {}", e)))?;
 
-        // For token-specific info, fall back to REST API
-        // The GraphQL schema doesn't expose Token type directly in queries
-        // Return placeholder - real data comes from REST API
         Ok(GqlToken {
-            contract_address_hash: Some(token_address.to_string()),
-            name: Some("USDFC".to_string()),
-            symbol: Some("USDFC".to_string()),
-            decimals: Some("18".to_string()),
-            total_supply: None,
-            holder_count: None,
-            volume_24h: None,
-            circulating_market_cap: None,
+            contract_address_hash: token.address.or_else(|| Some(token_address.to_string())),
+            name: token.name,
+            symbol: token.symbol,
+            decimals: token.decimals,
+            total_supply: token.total_supply,
+            holder_count: token.holders.and_then(|h| h.parse().ok()),
+            volume_24h: token.volume_24h,
+            circulating_market_cap: token.circulating_market_cap,
         })
     }
 
@@ -1279,5 +1369,481 @@ struct TransactionWithBlock {
 #[derive(Deserialize, Debug)]
 struct BlockWithTimestamp {
     timestamp: Option<String>,
-    number: Option<i64>,
+}
+
+// ============================================================================
+// Contract event logs (fee analytics, liquidations)
+// ============================================================================
+
+/// Unix timestamp of Filecoin mainnet epoch 0
+const FILECOIN_GENESIS_TIMESTAMP: i64 = 1_598_306_400;
+
+/// Seconds per Filecoin epoch
+const EPOCH_SECS: i64 = 30;
+
+/// Page cap for one log scan (50 logs per page)
+const MAX_LOG_PAGES: usize = 40;
+
+/// Unix timestamp of a Filecoin epoch (block number)
+///
+/// Epochs advance every 30 seconds even when no block is mined, so the
+/// mapping is exact without fetching the block.
+pub fn epoch_timestamp(block_number: u64) -> i64 {
+    FILECOIN_GENESIS_TIMESTAMP + block_number as i64 * EPOCH_SECS
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct LogsResponse {
+    #[serde(default)]
+    items: Vec<LogItem>,
+    next_page_params: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LogItem {
+    block_number: u64,
+    #[serde(default)]
+    transaction_hash: String,
+    /// Present when Blockscout could decode the log against a verified ABI
+    decoded: Option<DecodedLog>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DecodedLog {
+    /// Event signature with parameter names, e.g. `Redemption(uint256 _attemptedDebtAmount, ...)`
+    method_call: String,
+    #[serde(default)]
+    parameters: Vec<DecodedParam>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DecodedParam {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    value: serde_json::Value,
+}
+
+/// Which protocol fee an event pays
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeKind {
+    /// One-off fee on new debt (BorrowerOperations), in USDFC
+    Borrowing,
+    /// Fee taken from redeemed collateral (TroveManager), in FIL
+    Redemption,
+}
+
+/// A decoded fee payment
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeEvent {
+    pub kind: FeeKind,
+    pub amount: Decimal,
+    pub timestamp: i64,
+}
+
+/// Fee events found in a contract's logs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeeScan {
+    pub events: Vec<FeeEvent>,
+    /// Timestamps of logs Blockscout could not decode; any of them may be a fee
+    pub undecoded: Vec<i64>,
+    /// Oldest time the scan covers - later than the requested start when the
+    /// page cap was hit first
+    pub covered_since: i64,
+}
+
+/// Outcome of decoding one log
+enum LogFee {
+    Fee(FeeEvent),
+    /// Decoded, but not a fee event
+    NotFee,
+    Undecoded,
+}
+
+/// Decode a log as a borrowing or redemption fee event
+///
+/// Matches `*BorrowingFeePaid(address, uint256)` and `Redemption(..., uint256 _*Fee)`
+/// by name, so the USDFC-specific token prefixes on the Liquity events don't matter.
+fn decode_fee_log(item: &LogItem) -> LogFee {
+    let Some(decoded) = &item.decoded else {
+        return LogFee::Undecoded;
+    };
+    let name = decoded.method_call.split('(').next().unwrap_or_default().trim();
+    let timestamp = epoch_timestamp(item.block_number);
+
+    let (kind, param) = if name.ends_with("BorrowingFeePaid") {
+        (FeeKind::Borrowing, decoded.parameters.iter().rev().find(|p| p.kind == "uint256"))
+    } else if name == "Redemption" {
+        (
+            FeeKind::Redemption,
+            decoded.parameters.iter().find(|p| p.kind == "uint256" && p.name.to_lowercase().contains("fee")),
+        )
+    } else {
+        return LogFee::NotFee;
+    };
+
+    match param.and_then(param_amount) {
+        Some(amount) => LogFee::Fee(FeeEvent { kind, amount, timestamp }),
+        None => LogFee::Undecoded,
+    }
+}
+
+/// A decoded uint256 parameter as an 18-decimal token amount
+fn param_amount(param: &DecodedParam) -> Option<Decimal> {
+    let raw = match &param.value {
+        serde_json::Value::String(v) => v.parse::<u128>().ok(),
+        serde_json::Value::Number(n) => n.as_u64().map(u128::from),
+        _ => None,
+    }?;
+    Some(Decimal::from_i128_with_scale(raw as i128, 18))
+}
+
+/// Outcome of decoding one log as a liquidation or redemption
+enum LogLiquidation {
+    Event(LiquidationEvent),
+    /// Decoded, but not a liquidation or redemption
+    Other,
+    Undecoded,
+}
+
+/// Decode a TroveManager log as a liquidation or redemption
+///
+/// Uses the per-trove `TroveLiquidated(address, uint256 _debt, uint256 _coll, uint8)`
+/// rather than the batch `Liquidation` summary, so each event has a borrower.
+/// `Redemption` carries no trove owner since one redemption can hit several.
+fn decode_liquidation_log(item: &LogItem) -> LogLiquidation {
+    let Some(decoded) = &item.decoded else {
+        return LogLiquidation::Undecoded;
+    };
+    let name = decoded.method_call.split('(').next().unwrap_or_default().trim();
+    let amount = |matches: fn(&str) -> bool| {
+        decoded
+            .parameters
+            .iter()
+            .find(|p| p.kind == "uint256" && matches(&p.name.to_lowercase()))
+            .and_then(param_amount)
+    };
+
+    let (kind, borrower, debt, coll) = match name {
+        "TroveLiquidated" => (
+            LiquidationKind::Liquidation,
+            decoded
+                .parameters
+                .iter()
+                .find(|p| p.kind == "address")
+                .and_then(|p| p.value.as_str())
+                .map(str::to_lowercase),
+            amount(|n| n.contains("debt")),
+            amount(|n| n.contains("coll")),
+        ),
+        "Redemption" => (
+            LiquidationKind::Redemption,
+            None,
+            amount(|n| n.contains("actual")),
+            amount(|n| n.ends_with("sent")),
+        ),
+        _ => return LogLiquidation::Other,
+    };
+
+    match (debt, coll) {
+        (Some(debt_repaid), Some(collateral_liquidated)) => LogLiquidation::Event(LiquidationEvent {
+            kind,
+            tx_hash: item.transaction_hash.clone(),
+            borrower,
+            collateral_liquidated,
+            debt_repaid,
+            timestamp: epoch_timestamp(item.block_number),
+        }),
+        _ => LogLiquidation::Undecoded,
+    }
+}
+
+/// Depositor of a StabilityPool `UserDepositChanged(address indexed _depositor, uint256)` log
+fn decode_depositor_log(item: &LogItem) -> Option<String> {
+    let decoded = item.decoded.as_ref()?;
+    let name = decoded.method_call.split('(').next().unwrap_or_default().trim();
+    if name != "UserDepositChanged" {
+        return None;
+    }
+    decoded
+        .parameters
+        .iter()
+        .find(|p| p.kind == "address")
+        .and_then(|p| p.value.as_str())
+        .map(str::to_lowercase)
+}
+
+impl BlockscoutClient {
+    /// One page of a contract's event logs, newest first
+    async fn get_logs_page(&self, contract: &str, cursor: Option<&str>) -> ApiResult<LogsResponse> {
+        let mut url = format!("{}/addresses/{}/logs", self.base_url, contract);
+        if let Some(query) = cursor {
+            url = format!("{}?{}", url, query);
+        }
+
+        let response = self.get(&url).timed_send("blockscout").await
+            .map_err(request_failed)?;
+        if !response.status().is_success() {
+            return Err(ApiError::HttpError(format!("HTTP {}: Failed to fetch logs", response.status())));
+        }
+        response.json().await
+            .map_err(|e| ApiError::parse("logs", format!("JSON parse error: {}", e)))
+    }
+
+    /// Scan a contract's event logs back to `since` for fee payments
+    ///
+    /// Logs come newest first; scanning stops at the first log older than
+    /// `since`, at the last page, or after `MAX_LOG_PAGES`.
+    pub async fn get_fee_events(&self, contract: &str, since: i64) -> ApiResult<FeeScan> {
+        let mut scan = FeeScan { covered_since: since, ..Default::default() };
+        let mut cursor: Option<String> = None;
+        let mut oldest_seen: Option<i64> = None;
+
+        for _ in 0..MAX_LOG_PAGES {
+            let page = self.get_logs_page(contract, cursor.as_deref()).await?;
+
+            for item in &page.items {
+                let timestamp = epoch_timestamp(item.block_number);
+                if timestamp < since {
+                    return Ok(scan);
+                }
+                oldest_seen = Some(timestamp);
+                match decode_fee_log(item) {
+                    LogFee::Fee(event) => scan.events.push(event),
+                    LogFee::NotFee => {}
+                    LogFee::Undecoded => scan.undecoded.push(timestamp),
+                }
+            }
+
+            cursor = page.next_page_params.as_ref().and_then(page_params_query);
+            if cursor.is_none() || page.items.is_empty() {
+                // Reached the contract's first log
+                return Ok(scan);
+            }
+        }
+
+        // Page cap hit: only the range back to the oldest log seen is known
+        if let Some(oldest) = oldest_seen {
+            scan.covered_since = oldest;
+        }
+        Ok(scan)
+    }
+
+    /// Every address with a `UserDepositChanged` event in the StabilityPool's logs
+    ///
+    /// Addresses are lowercased and deduplicated, most recently active first.
+    /// The scan stops at the contract's first log or `MAX_LOG_PAGES`, so on a
+    /// very busy pool the longest-idle depositors can be missing. Fails with
+    /// `NotFound` when no log could be decoded, as for liquidations.
+    pub async fn get_stability_depositors(&self, contract: &str) -> ApiResult<Vec<String>> {
+        let mut depositors: Vec<String> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut any_decoded = false;
+        let mut cursor: Option<String> = None;
+
+        for _ in 0..MAX_LOG_PAGES {
+            let page = self.get_logs_page(contract, cursor.as_deref()).await?;
+
+            for item in &page.items {
+                any_decoded |= item.decoded.is_some();
+                if let Some(depositor) = decode_depositor_log(item) {
+                    if seen.insert(depositor.clone()) {
+                        depositors.push(depositor);
+                    }
+                }
+            }
+
+            cursor = page.next_page_params.as_ref().and_then(page_params_query);
+            if cursor.is_none() || page.items.is_empty() {
+                break;
+            }
+        }
+
+        if depositors.is_empty() && !any_decoded {
+            return Err(ApiError::not_found("stability pool depositors", contract));
+        }
+        Ok(depositors)
+    }
+
+    /// Most recent liquidations and redemptions in a TroveManager's logs, newest first
+    ///
+    /// Stops at `limit` events, the contract's first log or `MAX_LOG_PAGES`.
+    /// Fails with `NotFound` when no log could be decoded, since Blockscout
+    /// then has no ABI for the contract and an empty list would be a guess.
+    pub async fn get_liquidation_events(&self, contract: &str, limit: usize) -> ApiResult<Vec<LiquidationEvent>> {
+        let mut events = Vec::new();
+        let mut any_decoded = false;
+        let mut cursor: Option<String> = None;
+
+        for _ in 0..MAX_LOG_PAGES {
+            let page = self.get_logs_page(contract, cursor.as_deref()).await?;
+
+            for item in &page.items {
+                any_decoded |= item.decoded.is_some();
+                if let LogLiquidation::Event(event) = decode_liquidation_log(item) {
+                    events.push(event);
+                    if events.len() >= limit {
+                        return Ok(events);
+                    }
+                }
+            }
+
+            cursor = page.next_page_params.as_ref().and_then(page_params_query);
+            if cursor.is_none() || page.items.is_empty() {
+                break;
+            }
+        }
+
+        if events.is_empty() && !any_decoded {
+            return Err(ApiError::not_found("liquidation events", contract));
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_info_scales_supply_by_decimals() {
+        let token = GqlToken {
+            name: Some("USD for Filecoin Community".to_string()),
+            symbol: Some("USDFC".to_string()),
+            decimals: Some("6".to_string()),
+            total_supply: Some("2500000".to_string()),
+            ..GqlToken::default()
+        };
+        let info = token.token_info("0xToken").unwrap();
+        assert_eq!(info.address, "0xToken");
+        assert_eq!(info.decimals, 6);
+        assert_eq!(info.total_supply, Decimal::new(25, 1));
+
+        let no_decimals = GqlToken { decimals: None, ..token };
+        assert!(no_decimals.token_info("0xToken").is_err());
+    }
+
+    #[test]
+    fn test_page_params_query() {
+        let params = serde_json::json!({ "block_number": 5_000_000, "index": 3 });
+        assert_eq!(page_params_query(&params).as_deref(), Some("block_number=5000000&index=3"));
+        assert_eq!(page_params_query(&serde_json::Value::Null), None);
+        assert_eq!(page_params_query(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_decode_fee_logs() {
+        let log = |decoded: serde_json::Value| -> LogItem {
+            serde_json::from_value(serde_json::json!({ "block_number": 100, "decoded": decoded })).unwrap()
+        };
+
+        let borrowing = log(serde_json::json!({
+            "method_call": "DebtTokenBorrowingFeePaid(address indexed _borrower, uint256 _debtTokenFee)",
+            "parameters": [
+                { "name": "_borrower", "type": "address", "value": "0xabc" },
+                { "name": "_debtTokenFee", "type": "uint256", "value": "2500000000000000000" }
+            ]
+        }));
+        match decode_fee_log(&borrowing) {
+            LogFee::Fee(event) => {
+                assert_eq!(event.kind, FeeKind::Borrowing);
+                assert_eq!(event.amount, Decimal::new(25, 1));
+                assert_eq!(event.timestamp, FILECOIN_GENESIS_TIMESTAMP + 3_000);
+            }
+            _ => panic!("borrowing fee not decoded"),
+        }
+
+        let redemption = log(serde_json::json!({
+            "method_call": "Redemption(uint256 _attemptedDebtAmount, uint256 _actualDebtAmount, uint256 _FILSent, uint256 _FILFee)",
+            "parameters": [
+                { "name": "_attemptedDebtAmount", "type": "uint256", "value": "9" },
+                { "name": "_actualDebtAmount", "type": "uint256", "value": "9" },
+                { "name": "_FILSent", "type": "uint256", "value": "9" },
+                { "name": "_FILFee", "type": "uint256", "value": "1000000000000000000" }
+            ]
+        }));
+        assert!(matches!(decode_fee_log(&redemption), LogFee::Fee(FeeEvent { kind: FeeKind::Redemption, .. })));
+
+        let other = log(serde_json::json!({ "method_call": "TroveUpdated(address _borrower)", "parameters": [] }));
+        assert!(matches!(decode_fee_log(&other), LogFee::NotFee));
+        assert!(matches!(decode_fee_log(&log(serde_json::Value::Null)), LogFee::Undecoded));
+    }
+
+    #[test]
+    fn test_decode_liquidation_logs() {
+        let log = |decoded: serde_json::Value| -> LogItem {
+            serde_json::from_value(serde_json::json!({
+                "block_number": 100,
+                "transaction_hash": "0xfeed",
+                "decoded": decoded
+            }))
+            .unwrap()
+        };
+
+        let liquidated = log(serde_json::json!({
+            "method_call": "TroveLiquidated(address indexed _borrower, uint256 _debt, uint256 _coll, uint8 _operation)",
+            "parameters": [
+                { "name": "_borrower", "type": "address", "value": "0xABC" },
+                { "name": "_debt", "type": "uint256", "value": "1500000000000000000000" },
+                { "name": "_coll", "type": "uint256", "value": "400000000000000000000" },
+                { "name": "_operation", "type": "uint8", "value": "0" }
+            ]
+        }));
+        match decode_liquidation_log(&liquidated) {
+            LogLiquidation::Event(event) => {
+                assert_eq!(event.kind, LiquidationKind::Liquidation);
+                assert_eq!(event.tx_hash, "0xfeed");
+                assert_eq!(event.borrower.as_deref(), Some("0xabc"));
+                assert_eq!(event.debt_repaid, Decimal::from(1500));
+                assert_eq!(event.collateral_liquidated, Decimal::from(400));
+            }
+            _ => panic!("liquidation not decoded"),
+        }
+
+        let redemption = log(serde_json::json!({
+            "method_call": "Redemption(uint256 _attemptedDebtAmount, uint256 _actualDebtAmount, uint256 _FILSent, uint256 _FILFee)",
+            "parameters": [
+                { "name": "_attemptedDebtAmount", "type": "uint256", "value": "9000000000000000000" },
+                { "name": "_actualDebtAmount", "type": "uint256", "value": "8000000000000000000" },
+                { "name": "_FILSent", "type": "uint256", "value": "2000000000000000000" },
+                { "name": "_FILFee", "type": "uint256", "value": "1000000000000000" }
+            ]
+        }));
+        match decode_liquidation_log(&redemption) {
+            LogLiquidation::Event(event) => {
+                assert_eq!(event.kind, LiquidationKind::Redemption);
+                assert_eq!(event.borrower, None);
+                assert_eq!(event.debt_repaid, Decimal::from(8));
+                assert_eq!(event.collateral_liquidated, Decimal::from(2));
+            }
+            _ => panic!("redemption not decoded"),
+        }
+
+        let other = log(serde_json::json!({ "method_call": "TroveUpdated(address _borrower)", "parameters": [] }));
+        assert!(matches!(decode_liquidation_log(&other), LogLiquidation::Other));
+        assert!(matches!(decode_liquidation_log(&log(serde_json::Value::Null)), LogLiquidation::Undecoded));
+    }
+
+    #[test]
+    fn test_decode_depositor_logs() {
+        let log = |decoded: serde_json::Value| -> LogItem {
+            serde_json::from_value(serde_json::json!({ "block_number": 100, "decoded": decoded })).unwrap()
+        };
+
+        let changed = log(serde_json::json!({
+            "method_call": "UserDepositChanged(address indexed _depositor, uint256 _newDeposit)",
+            "parameters": [
+                { "name": "_depositor", "type": "address", "value": "0xABC" },
+                { "name": "_newDeposit", "type": "uint256", "value": "0" }
+            ]
+        }));
+        assert_eq!(decode_depositor_log(&changed).as_deref(), Some("0xabc"));
+
+        let other = log(serde_json::json!({
+            "method_call": "DepositSnapshotUpdated(address indexed _depositor, uint256 _P, uint256 _S, uint256 _G)",
+            "parameters": [{ "name": "_depositor", "type": "address", "value": "0xabc" }]
+        }));
+        assert_eq!(decode_depositor_log(&other), None);
+        assert_eq!(decode_depositor_log(&log(serde_json::Value::Null)), None);
+    }
 }
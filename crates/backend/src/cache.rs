@@ -1,26 +1,59 @@
 //! Server-side caching utilities for USDFC Analytics Terminal
 //!
 //! Provides TTL-based caching for expensive API calls to improve performance.
+//!
+//! Expired entries are kept for `MAX_STALE_SECS` so `get_or_revalidate` can
+//! serve the last good value (marked stale) while a background refresh runs,
+//! instead of failing when an upstream is briefly down.
+//!
+//! Concurrent misses for the same key are coalesced by `get_or_compute`, so a
+//! cold cache under load triggers one upstream fetch rather than one per
+//! request.
 
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::future::Future;
 
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Cached data entry with TTL
+use usdfc_core::types::{CacheFreshness, CacheStats};
+
+/// How long past its TTL an entry may still be served as stale
+pub const MAX_STALE_SECS: u64 = 900;
 
+/// A cached value and whether it is past its TTL
+#[derive(Clone, Debug)]
+pub struct Cached<T> {
+    pub data: T,
+    pub stale: bool,
+}
+
+/// Cached data entry with TTL
 struct CacheEntry<T> {
     data: T,
     expires_at: Instant,
 }
 
-/// Simple TTL-based cache
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
 
+/// Simple TTL-based cache
 pub struct Cache<T> {
     entries: RwLock<HashMap<String, CacheEntry<T>>>,
     ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// Unix seconds of the last `set` (0 = never)
+    last_populated: AtomicU64,
+    /// Keys with a background refresh in flight
+    refreshing: Mutex<HashSet<String>>,
+    /// Per-key locks held while a miss is being computed
+    inflight: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
 }
 
 
@@ -30,21 +63,92 @@ impl<T: Clone> Cache<T> {
         Self {
             entries: RwLock::new(HashMap::new()),
             ttl: Duration::from_secs(ttl_secs),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            last_populated: AtomicU64::new(0),
+            refreshing: Mutex::new(HashSet::new()),
+            inflight: Mutex::new(HashMap::new()),
         }
     }
 
     /// Get a cached value if it exists and hasn't expired
     pub fn get(&self, key: &str) -> Option<T> {
-        let entries = self.entries.read().ok()?;
-        let entry = entries.get(key)?;
+        let data = self.entries.read().ok().and_then(|entries| {
+            entries
+                .get(key)
+                .filter(|entry| Instant::now() < entry.expires_at)
+                .map(|entry| entry.data.clone())
+        });
 
-        if Instant::now() < entry.expires_at {
-            Some(entry.data.clone())
-        } else {
-            None
+        let counter = if data.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        data
+    }
+
+    /// Entry count, TTL, entry ages and hit/miss counts, reported under `name`
+    ///
+    /// Entries are aged from when they were stored, so an expired entry that
+    /// has not been cleaned up yet shows an age past the TTL.
+    pub fn stats(&self, name: &str) -> CacheStats {
+        let now = Instant::now();
+        let ages: Vec<u64> = self
+            .entries
+            .read()
+            .map(|entries| {
+                entries
+                    .values()
+                    .map(|entry| {
+                        let remaining = entry.expires_at.saturating_duration_since(now);
+                        let expired_for = now.saturating_duration_since(entry.expires_at);
+                        (self.ttl.saturating_sub(remaining) + expired_for).as_secs()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        CacheStats {
+            name: name.to_string(),
+            entries: ages.len(),
+            ttl_secs: self.ttl.as_secs(),
+            oldest_age_secs: ages.iter().max().copied(),
+            newest_age_secs: ages.iter().min().copied(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
         }
     }
 
+    /// Last populate time and whether it is within the TTL, reported under `name`
+    ///
+    /// Reads only in-memory state, so it is cheap enough for frequent probes.
+    pub fn freshness(&self, name: &str) -> CacheFreshness {
+        let last = self.last_populated.load(Ordering::Relaxed);
+        let age_secs = (last > 0).then(|| unix_now().saturating_sub(last));
+        CacheFreshness {
+            name: name.to_string(),
+            ttl_secs: self.ttl.as_secs(),
+            last_populated: (last > 0).then_some(last as i64),
+            age_secs,
+            fresh: age_secs.is_some_and(|age| age < self.ttl.as_secs()),
+        }
+    }
+
+    /// Get a cached value, including one past its TTL (up to `MAX_STALE_SECS`)
+    ///
+    /// Only fresh values count as hits.
+    pub fn get_or_stale(&self, key: &str) -> Option<Cached<T>> {
+        let now = Instant::now();
+        let cached = self.entries.read().ok().and_then(|entries| {
+            let entry = entries.get(key)?;
+            let stale = now >= entry.expires_at;
+            (now < entry.expires_at + Duration::from_secs(MAX_STALE_SECS))
+                .then(|| Cached { data: entry.data.clone(), stale })
+        });
+
+        let counter = if cached.as_ref().is_some_and(|c| !c.stale) { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
     /// Store a value in the cache
     pub fn set(&self, key: String, data: T) {
         if let Ok(mut entries) = self.entries.write() {
@@ -53,67 +157,249 @@ impl<T: Clone> Cache<T> {
                 expires_at: Instant::now() + self.ttl,
             });
         }
+        self.last_populated.store(unix_now(), Ordering::Relaxed);
     }
 
-    /// Remove expired entries (call periodically to prevent memory leaks)
+    /// Get a fresh cached value, or compute and store it
+    ///
+    /// Concurrent misses for the same key share one computation: the first
+    /// caller runs `compute` while the others wait on a per-key lock and then
+    /// read its stored result. If `compute` fails, the next waiter computes
+    /// again rather than every waiter receiving the error.
+    pub async fn get_or_compute<F, Fut, E>(&self, key: &str, compute: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if let Some(data) = self.get(key) {
+            return Ok(data);
+        }
+
+        let lock = self
+            .inflight
+            .lock()
+            .ok()
+            .map(|mut inflight| inflight.entry(key.to_string()).or_default().clone());
+        let Some(lock) = lock else {
+            return compute().await.inspect(|data| self.set(key.to_string(), data.clone()));
+        };
+        let _guard = lock.lock().await;
+
+        // Another caller may have filled the entry while we waited
+        let fresh = self.entries.read().ok().and_then(|entries| {
+            entries
+                .get(key)
+                .filter(|entry| Instant::now() < entry.expires_at)
+                .map(|entry| entry.data.clone())
+        });
+        let result = match fresh {
+            Some(data) => Ok(data),
+            None => compute().await.inspect(|data| self.set(key.to_string(), data.clone())),
+        };
+
+        // Waiters already hold the lock; later callers will find the entry
+        if let Ok(mut inflight) = self.inflight.lock() {
+            inflight.remove(key);
+        }
+        result
+    }
+
+    /// Remove entries too old to serve even as stale (call periodically to prevent memory leaks)
     pub fn cleanup(&self) {
         if let Ok(mut entries) = self.entries.write() {
             let now = Instant::now();
-            entries.retain(|_, entry| now < entry.expires_at);
+            let max_stale = Duration::from_secs(MAX_STALE_SECS);
+            entries.retain(|_, entry| now < entry.expires_at + max_stale);
         }
     }
 }
 
-/// Global cache instances for different data types
+impl<T: Clone + Send + Sync + 'static> Cache<T> {
+    /// Stale-while-revalidate lookup
+    ///
+    /// A fresh value is returned as is. A stale value is returned marked
+    /// stale while `fetch` refreshes it in the background (at most one
+    /// refresh per key at a time). Only with no prior value is `fetch`
+    /// awaited, and its error returned.
+    pub async fn get_or_revalidate<F, Fut, E>(&'static self, key: &str, fetch: F) -> Result<Cached<T>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        E: Display,
+    {
+        match self.get_or_stale(key) {
+            Some(cached) if !cached.stale => Ok(cached),
+            Some(cached) => {
+                let started = self.refreshing.lock().map(|mut keys| keys.insert(key.to_string())).unwrap_or(false);
+                if started {
+                    let key = key.to_string();
+                    let refresh = fetch();
+                    tokio::spawn(async move {
+                        match refresh.await {
+                            Ok(data) => self.set(key.clone(), data),
+                            Err(e) => tracing::warn!("Background refresh of cache key '{}' failed, serving stale value: {}", key, e),
+                        }
+                        if let Ok(mut keys) = self.refreshing.lock() {
+                            keys.remove(&key);
+                        }
+                    });
+                }
+                Ok(cached)
+            }
+            None => {
+                let data = self.get_or_compute(key, fetch).await?;
+                Ok(Cached { data, stale: false })
+            }
+        }
+    }
+}
 
+/// Global cache instances for different data types
 pub mod caches {
     use super::*;
     use once_cell::sync::Lazy;
     use usdfc_core::types::{
         ProtocolMetrics, Trove, Transaction, ChartDataResponse,
         AddressInfo, USDFCPriceData, LendingMarketData, TokenHolderInfo,
-        DailyVolumeData, OrderBookData, LendingTradeData,
+        DailyVolumesResponse, OrderBookData, LendingTradeData, TwapData, FeeAnalytics, LiquidationEvent,
+        NetworkGas, PoolDepositor, IcrBucket, TokenInfo,
     };
+    use usdfc_core::config::{config, CacheTtls};
 
-    /// Cache for protocol metrics (15 second TTL - updates frequently)
-    pub static PROTOCOL_METRICS: Lazy<Cache<ProtocolMetrics>> = Lazy::new(|| Cache::new(15));
-
-    /// Cache for troves list (30 second TTL - aligned with price updates for ICR accuracy)
-    pub static TROVES: Lazy<Cache<Vec<Trove>>> = Lazy::new(|| Cache::new(30));
-
-    /// Cache for USDFC price data (30 second TTL)
-    pub static USDFC_PRICE: Lazy<Cache<USDFCPriceData>> = Lazy::new(|| Cache::new(30));
+    /// Configured TTLs (CACHE_TTLS overrides the defaults)
+    fn ttls() -> &'static CacheTtls {
+        &config().cache_ttls
+    }
 
-    /// Cache for lending pools/markets (60 second TTL)
-    pub static LENDING_MARKETS: Lazy<Cache<Vec<LendingMarketData>>> = Lazy::new(|| Cache::new(60));
+    /// Cache for protocol metrics (default 15 second TTL - updates frequently)
+    pub static PROTOCOL_METRICS: Lazy<Cache<ProtocolMetrics>> = Lazy::new(|| Cache::new(ttls().protocol_metrics));
 
-    /// Cache for token holders (300 second TTL - holder list changes slowly)
-    pub static TOKEN_HOLDERS: Lazy<Cache<Vec<TokenHolderInfo>>> = Lazy::new(|| Cache::new(300));
+    /// Cache for troves list (default 30 second TTL - aligned with price updates for ICR accuracy)
+    pub static TROVES: Lazy<Cache<Vec<Trove>>> = Lazy::new(|| Cache::new(ttls().troves));
 
-    /// Cache for holder count (300 second TTL - count changes slowly)
-    pub static HOLDER_COUNT: Lazy<Cache<u64>> = Lazy::new(|| Cache::new(300));
+    /// Cache for trove leaderboards, keyed by sort dimension and limit (default 30 second TTL - same as troves)
+    pub static TROVE_LEADERBOARD: Lazy<Cache<Vec<Trove>>> = Lazy::new(|| Cache::new(ttls().trove_leaderboard));
 
-    /// Cache for advanced chart data (30 second TTL - balances freshness with API load)
-    pub static ADVANCED_CHART_DATA: Lazy<Cache<ChartDataResponse>> = Lazy::new(|| Cache::new(30));
+    /// Cache for USDFC price data (default 30 second TTL)
+    pub static USDFC_PRICE: Lazy<Cache<USDFCPriceData>> = Lazy::new(|| Cache::new(ttls().usdfc_price));
 
-    // NEW CACHES FOR CORE CHANGE #3
-    /// Cache for recent transactions (10 second TTL - new tx appear frequently)
-    pub static RECENT_TRANSACTIONS: Lazy<Cache<Vec<Transaction>>> = Lazy::new(|| Cache::new(10));
+    /// Cache for lending pools/markets (default 60 second TTL)
+    pub static LENDING_MARKETS: Lazy<Cache<Vec<LendingMarketData>>> = Lazy::new(|| Cache::new(ttls().lending_markets));
 
-    /// Cache for address info (30 second TTL - balance changes moderately)
-    pub static ADDRESS_INFO: Lazy<Cache<AddressInfo>> = Lazy::new(|| Cache::new(30));
+    /// Cache for token holders (default 300 second TTL - holder list changes slowly)
+    pub static TOKEN_HOLDERS: Lazy<Cache<Vec<TokenHolderInfo>>> = Lazy::new(|| Cache::new(ttls().token_holders));
 
-    /// Cache for daily volumes (300 second TTL - historical data, changes slowly)
-    pub static DAILY_VOLUMES: Lazy<Cache<Vec<DailyVolumeData>>> = Lazy::new(|| Cache::new(300));
+    /// Cache for holder count (default 300 second TTL - count changes slowly)
+    pub static HOLDER_COUNT: Lazy<Cache<u64>> = Lazy::new(|| Cache::new(ttls().holder_count));
 
-    /// Cache for stability pool transfers (30 second TTL)
-    pub static STABILITY_TRANSFERS: Lazy<Cache<Vec<Transaction>>> = Lazy::new(|| Cache::new(30));
+    /// Cache for advanced chart data (default 30 second TTL - balances freshness with API load)
+    pub static ADVANCED_CHART_DATA: Lazy<Cache<ChartDataResponse>> = Lazy::new(|| Cache::new(ttls().advanced_chart_data));
 
-    /// Cache for order book data (5 second TTL - real-time trading data)
-    pub static ORDER_BOOK: Lazy<Cache<OrderBookData>> = Lazy::new(|| Cache::new(5));
+    // NEW CACHES FOR CORE CHANGE #3
+    /// Cache for recent transactions (default 10 second TTL - new tx appear frequently)
+    pub static RECENT_TRANSACTIONS: Lazy<Cache<Vec<Transaction>>> = Lazy::new(|| Cache::new(ttls().recent_transactions));
+
+    /// Cache for address info (default 30 second TTL - balance changes moderately)
+    pub static ADDRESS_INFO: Lazy<Cache<AddressInfo>> = Lazy::new(|| Cache::new(ttls().address_info));
+
+    /// Cache for daily volumes (default 300 second TTL - historical data, changes slowly)
+    pub static DAILY_VOLUMES: Lazy<Cache<DailyVolumesResponse>> = Lazy::new(|| Cache::new(ttls().daily_volumes));
+
+    /// Cache for stability pool transfers (default 30 second TTL)
+    pub static STABILITY_TRANSFERS: Lazy<Cache<Vec<Transaction>>> = Lazy::new(|| Cache::new(ttls().stability_transfers));
+
+    /// Cache for order book data (default 5 second TTL - real-time trading data)
+    pub static ORDER_BOOK: Lazy<Cache<OrderBookData>> = Lazy::new(|| Cache::new(ttls().order_book));
+
+    /// Cache for recent lending trades (default 30 second TTL)
+    pub static LENDING_TRADES: Lazy<Cache<Vec<LendingTradeData>>> = Lazy::new(|| Cache::new(ttls().lending_trades));
+
+    /// Cache for TWAP per window (default 30 second TTL)
+    pub static TWAP: Lazy<Cache<TwapData>> = Lazy::new(|| Cache::new(ttls().twap));
+
+    /// Cache for fee analytics per lookback and resolution (default 300 second TTL)
+    pub static FEE_ANALYTICS: Lazy<Cache<FeeAnalytics>> = Lazy::new(|| Cache::new(ttls().fee_analytics));
+
+    /// Cache for single-trove lookups by lowercase owner address, including
+    /// "no trove" results (default 120 second TTL)
+    pub static TROVE_BY_ADDRESS: Lazy<Cache<Option<Trove>>> = Lazy::new(|| Cache::new(ttls().trove_by_address));
+
+    /// Cache for recent liquidations and redemptions by limit (default 60 second TTL)
+    pub static LIQUIDATIONS: Lazy<Cache<Vec<LiquidationEvent>>> = Lazy::new(|| Cache::new(ttls().liquidations));
+
+    /// Cache for network base fee and congestion (default 30 second TTL)
+    pub static NETWORK_GAS: Lazy<Cache<NetworkGas>> = Lazy::new(|| Cache::new(ttls().network_gas));
+
+    /// Cache for the full ranked stability pool depositor list (default 120 second TTL)
+    pub static STABILITY_DEPOSITORS: Lazy<Cache<Vec<PoolDepositor>>> = Lazy::new(|| Cache::new(ttls().stability_depositors));
+
+    /// Cache for the ICR distribution histogram (default 120 second TTL)
+    pub static ICR_DISTRIBUTION: Lazy<Cache<Vec<IcrBucket>>> = Lazy::new(|| Cache::new(ttls().icr_distribution));
+
+    /// Cache for the USDFC token metadata (default 5 minute TTL)
+    pub static TOKEN_INFO: Lazy<Cache<TokenInfo>> = Lazy::new(|| Cache::new(ttls().token_info));
+
+    /// Cache for rendered chart SVGs and their effective range, keyed by the
+    /// full parameter set (default 30 second TTL)
+    pub static CHART_IMAGES: Lazy<Cache<(String, (i64, i64))>> = Lazy::new(|| Cache::new(ttls().chart_image));
+
+    /// Stats for every cache, under stable names (also used as `/metrics` labels)
+    pub fn stats() -> Vec<CacheStats> {
+        vec![
+            PROTOCOL_METRICS.stats("protocol_metrics"),
+            TROVES.stats("troves"),
+            TROVE_LEADERBOARD.stats("trove_leaderboard"),
+            USDFC_PRICE.stats("usdfc_price"),
+            LENDING_MARKETS.stats("lending_markets"),
+            TOKEN_HOLDERS.stats("token_holders"),
+            HOLDER_COUNT.stats("holder_count"),
+            ADVANCED_CHART_DATA.stats("advanced_chart_data"),
+            RECENT_TRANSACTIONS.stats("recent_transactions"),
+            ADDRESS_INFO.stats("address_info"),
+            DAILY_VOLUMES.stats("daily_volumes"),
+            STABILITY_TRANSFERS.stats("stability_transfers"),
+            ORDER_BOOK.stats("order_book"),
+            LENDING_TRADES.stats("lending_trades"),
+            TWAP.stats("twap"),
+            FEE_ANALYTICS.stats("fee_analytics"),
+            CHART_IMAGES.stats("chart_image"),
+            TROVE_BY_ADDRESS.stats("trove_by_address"),
+            LIQUIDATIONS.stats("liquidations"),
+            NETWORK_GAS.stats("network_gas"),
+            STABILITY_DEPOSITORS.stats("stability_depositors"),
+            ICR_DISTRIBUTION.stats("icr_distribution"),
+            TOKEN_INFO.stats("token_info"),
+        ]
+    }
 
-    /// Cache for recent lending trades (30 second TTL)
-    pub static LENDING_TRADES: Lazy<Cache<Vec<LendingTradeData>>> = Lazy::new(|| Cache::new(30));
+    /// Freshness of every cache, under the same names as `stats`
+    pub fn freshness() -> Vec<CacheFreshness> {
+        vec![
+            PROTOCOL_METRICS.freshness("protocol_metrics"),
+            TROVES.freshness("troves"),
+            TROVE_LEADERBOARD.freshness("trove_leaderboard"),
+            USDFC_PRICE.freshness("usdfc_price"),
+            LENDING_MARKETS.freshness("lending_markets"),
+            TOKEN_HOLDERS.freshness("token_holders"),
+            HOLDER_COUNT.freshness("holder_count"),
+            ADVANCED_CHART_DATA.freshness("advanced_chart_data"),
+            RECENT_TRANSACTIONS.freshness("recent_transactions"),
+            ADDRESS_INFO.freshness("address_info"),
+            DAILY_VOLUMES.freshness("daily_volumes"),
+            STABILITY_TRANSFERS.freshness("stability_transfers"),
+            ORDER_BOOK.freshness("order_book"),
+            LENDING_TRADES.freshness("lending_trades"),
+            TWAP.freshness("twap"),
+            FEE_ANALYTICS.freshness("fee_analytics"),
+            CHART_IMAGES.freshness("chart_image"),
+            TROVE_BY_ADDRESS.freshness("trove_by_address"),
+            LIQUIDATIONS.freshness("liquidations"),
+            NETWORK_GAS.freshness("network_gas"),
+            STABILITY_DEPOSITORS.freshness("stability_depositors"),
+            ICR_DISTRIBUTION.freshness("icr_distribution"),
+            TOKEN_INFO.freshness("token_info"),
+        ]
+    }
 
     /// Start background task to periodically clean expired cache entries
     /// Prevents memory leaks from accumulating expired entries
@@ -126,6 +412,7 @@ pub mod caches {
                 // Clean all cache instances
                 PROTOCOL_METRICS.cleanup();
                 TROVES.cleanup();
+                TROVE_LEADERBOARD.cleanup();
                 USDFC_PRICE.cleanup();
                 LENDING_MARKETS.cleanup();
                 TOKEN_HOLDERS.cleanup();
@@ -138,6 +425,15 @@ pub mod caches {
                 STABILITY_TRANSFERS.cleanup();
                 ORDER_BOOK.cleanup();
                 LENDING_TRADES.cleanup();
+                TWAP.cleanup();
+                CHART_IMAGES.cleanup();
+                FEE_ANALYTICS.cleanup();
+                TROVE_BY_ADDRESS.cleanup();
+                LIQUIDATIONS.cleanup();
+                NETWORK_GAS.cleanup();
+                STABILITY_DEPOSITORS.cleanup();
+                ICR_DISTRIBUTION.cleanup();
+                TOKEN_INFO.cleanup();
 
                 tracing::debug!("Cleaned expired cache entries");
             }
@@ -153,7 +449,6 @@ pub mod caches {
 ///     // expensive async operation
 /// })
 /// ```
-
 #[macro_export]
 macro_rules! cached_call {
     ($cache:expr, $key:expr, $fetch:expr) => {{
@@ -176,3 +471,75 @@ macro_rules! cached_call {
 
 
 pub use cached_call;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_counts_entries_and_lookups() {
+        let cache: Cache<u32> = Cache::new(15);
+        let empty = cache.stats("test");
+        assert_eq!((empty.entries, empty.ttl_secs, empty.oldest_age_secs), (0, 15, None));
+
+        cache.set("a".to_string(), 1);
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get("b"), None);
+
+        let stats = cache.stats("test");
+        assert_eq!(stats.entries, 1);
+        assert_eq!((stats.oldest_age_secs, stats.newest_age_secs), (Some(0), Some(0)));
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_freshness_tracks_last_populate() {
+        let cache: Cache<u32> = Cache::new(60);
+        let cold = cache.freshness("test");
+        assert_eq!((cold.last_populated, cold.age_secs, cold.fresh), (None, None, false));
+
+        cache.set("a".to_string(), 1);
+        let warm = cache.freshness("test");
+        assert!(warm.last_populated.is_some());
+        assert_eq!((warm.age_secs, warm.fresh, warm.ttl_secs), (Some(0), true, 60));
+
+        let expired: Cache<u32> = Cache::new(0);
+        expired.set("a".to_string(), 1);
+        assert!(!expired.freshness("test").fresh);
+    }
+
+    #[test]
+    fn test_expired_entry_served_as_stale() {
+        let cache: Cache<u32> = Cache::new(0);
+        assert!(cache.get_or_stale("a").is_none());
+
+        cache.set("a".to_string(), 1);
+        assert_eq!(cache.get("a"), None);
+        let cached = cache.get_or_stale("a").unwrap();
+        assert_eq!((cached.data, cached.stale), (1, true));
+
+        // Cleanup keeps it while it can still be served stale
+        cache.cleanup();
+        assert!(cache.get_or_stale("a").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_compute_once() {
+        use std::sync::atomic::AtomicUsize;
+
+        let cache: Cache<u32> = Cache::new(15);
+        let calls = AtomicUsize::new(0);
+        let callers = (0..10).map(|_| {
+            cache.get_or_compute("a", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok::<_, String>(7)
+            })
+        });
+
+        let results = futures::future::join_all(callers).await;
+        assert!(results.into_iter().all(|r| r == Ok(7)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get("a"), Some(7));
+    }
+}
@@ -0,0 +1,286 @@
+//! Server-rendered chart images
+//!
+//! ECharts' `saveAsImage` only works in the browser. This renders a
+//! `ChartDataResponse` to a standalone SVG so charts can be embedded in docs or
+//! chat with the same query parameters as the interactive chart
+//! (`ChartUrlState`). Each metric is scaled to its own range; the first metric
+//! gets the labelled y-axis and every metric's latest value is in the legend.
+
+use std::fmt::Write;
+
+use usdfc_core::format::{format_compact, format_timestamp, format_timestamp_full, format_usd_compact};
+use usdfc_core::types::{ChartDataResponse, ChartLookback, ChartMetric, ChartType};
+
+/// Image size in SVG user units; the viewBox lets embeds scale it freely
+pub const WIDTH: f64 = 1200.0;
+pub const HEIGHT: f64 = 600.0;
+
+const MARGIN_LEFT: f64 = 84.0;
+const MARGIN_RIGHT: f64 = 24.0;
+const MARGIN_TOP: f64 = 96.0;
+const MARGIN_BOTTOM: f64 = 44.0;
+
+const GRID_ROWS: usize = 5;
+const TIME_TICKS: usize = 6;
+
+/// Time range the chart covers
+///
+/// A custom start/end wins; otherwise the (already clamped) lookback ending at
+/// `generated_at`. The "all" lookback starts at the earliest point returned.
+pub fn effective_range(data: &ChartDataResponse, start: Option<i64>, end: Option<i64>) -> (i64, i64) {
+    let end = end.unwrap_or(data.generated_at);
+    let start = start.unwrap_or_else(|| match data.lookback {
+        ChartLookback::All => ChartMetric::all()
+            .iter()
+            .filter_map(|&m| data.metric_series(m).first().map(|&(ts, _)| ts))
+            .min()
+            .unwrap_or(end),
+        lookback => end - lookback.minutes() as i64 * 60,
+    });
+    (start, end)
+}
+
+/// Render the selected metrics over `range` as an SVG document
+pub fn render_svg(data: &ChartDataResponse, metrics: &[ChartMetric], chart_type: ChartType, range: (i64, i64)) -> String {
+    let (start, end) = range;
+    let span = (end - start).max(1) as f64;
+    let plot_w = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_h = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let x = |ts: i64| MARGIN_LEFT + (ts - start) as f64 / span * plot_w;
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}" font-family="Inter, Helvetica, Arial, sans-serif">"##,
+        w = WIDTH,
+        h = HEIGHT
+    );
+    let _ = write!(svg, r##"<rect width="100%" height="100%" fill="#0a0a0a"/>"##);
+
+    // Title and effective range
+    let title = metrics.iter().map(|m| m.label()).collect::<Vec<_>>().join(", ");
+    let _ = write!(
+        svg,
+        r##"<text x="{}" y="32" fill="#f5f5f5" font-size="20" font-weight="600">USDFC · {}</text>"##,
+        MARGIN_LEFT,
+        escape(&title)
+    );
+    let _ = write!(
+        svg,
+        r##"<text x="{}" y="54" fill="#8a8a8a" font-size="13">{} resolution · {} → {}</text>"##,
+        MARGIN_LEFT,
+        data.resolution.label(),
+        format_timestamp_full(start.max(0) as u64),
+        format_timestamp_full(end.max(0) as u64)
+    );
+
+    // Grid and time axis
+    for row in 0..=GRID_ROWS {
+        let y = MARGIN_TOP + plot_h * row as f64 / GRID_ROWS as f64;
+        let _ = write!(
+            svg,
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#1f1f1f"/>"##,
+            MARGIN_LEFT,
+            y,
+            MARGIN_LEFT + plot_w,
+            y
+        );
+    }
+    for tick in 0..=TIME_TICKS {
+        let ts = start + ((end - start) as f64 * tick as f64 / TIME_TICKS as f64) as i64;
+        let anchor = match tick {
+            0 => "start",
+            TIME_TICKS => "end",
+            _ => "middle",
+        };
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" fill="#6b6b6b" font-size="11" text-anchor="{}">{}</text>"##,
+            x(ts),
+            HEIGHT - MARGIN_BOTTOM + 20.0,
+            anchor,
+            format_timestamp(ts.max(0) as u64)
+        );
+    }
+
+    let mut legend_x = MARGIN_LEFT;
+    for (i, &metric) in metrics.iter().enumerate() {
+        let candles = metric == ChartMetric::Price && chart_type == ChartType::Candle;
+        let points: Vec<(i64, f64)> = {
+            let mut points: Vec<(i64, f64)> = data
+                .metric_series(metric)
+                .into_iter()
+                .filter(|&(ts, v)| ts >= start && ts <= end && v.is_finite())
+                .collect();
+            points.sort_by_key(|&(ts, _)| ts);
+            points
+        };
+
+        // Value range, including candle wicks when drawing candles
+        let values = points.iter().map(|&(_, v)| v).chain(
+            data.price_candles
+                .iter()
+                .filter(|_| candles)
+                .filter(|c| c.time >= start && c.time <= end)
+                .flat_map(|c| [c.low, c.high]),
+        );
+        let (min, max) = padded_range(values);
+        let y = |v: f64| MARGIN_TOP + (1.0 - (v - min) / (max - min)) * plot_h;
+        let color = metric.color();
+
+        // The first metric owns the y-axis labels
+        if i == 0 && !points.is_empty() {
+            for row in 0..=GRID_ROWS {
+                let value = max - (max - min) * row as f64 / GRID_ROWS as f64;
+                let _ = write!(
+                    svg,
+                    r##"<text x="{:.1}" y="{:.1}" fill="#6b6b6b" font-size="11" text-anchor="end">{}</text>"##,
+                    MARGIN_LEFT - 8.0,
+                    y(value) + 4.0,
+                    format_value(metric, value)
+                );
+            }
+        }
+
+        let bars = matches!(metric, ChartMetric::Volume | ChartMetric::Transfers) || chart_type == ChartType::Bar;
+        if candles {
+            let body_w = (plot_w / points.len().max(1) as f64 * 0.7).clamp(1.0, 12.0);
+            for c in data.price_candles.iter().filter(|c| c.time >= start && c.time <= end) {
+                let fill = if c.close >= c.open { "#22c55e" } else { "#ef4444" };
+                let cx = x(c.time);
+                let top = y(c.open.max(c.close));
+                let height = (y(c.open.min(c.close)) - top).max(1.0);
+                let _ = write!(
+                    svg,
+                    r##"<line x1="{cx:.1}" y1="{:.1}" x2="{cx:.1}" y2="{:.1}" stroke="{fill}"/><rect x="{:.1}" y="{top:.1}" width="{body_w:.1}" height="{height:.1}" fill="{fill}"/>"##,
+                    y(c.high),
+                    y(c.low),
+                    cx - body_w / 2.0,
+                );
+            }
+        } else if bars {
+            let bar_w = (plot_w / points.len().max(1) as f64 * 0.7).clamp(1.0, 16.0);
+            let base = y(min.max(0.0));
+            for &(ts, v) in &points {
+                let top = y(v).min(base);
+                let _ = write!(
+                    svg,
+                    r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" fill-opacity="0.5"/>"##,
+                    x(ts) - bar_w / 2.0,
+                    top,
+                    bar_w,
+                    (base - top).max(1.0),
+                    color
+                );
+            }
+        } else if !points.is_empty() {
+            let path: String = points
+                .iter()
+                .map(|&(ts, v)| format!("{:.1},{:.1}", x(ts), y(v)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if i == 0 && chart_type == ChartType::Area {
+                let baseline = MARGIN_TOP + plot_h;
+                let _ = write!(
+                    svg,
+                    r##"<polygon points="{:.1},{:.1} {} {:.1},{:.1}" fill="{}" fill-opacity="0.15"/>"##,
+                    x(points[0].0),
+                    baseline,
+                    path,
+                    x(points[points.len() - 1].0),
+                    baseline,
+                    color
+                );
+            }
+            let _ = write!(
+                svg,
+                r##"<polyline points="{}" fill="none" stroke="{}" stroke-width="2" stroke-linejoin="round"/>"##,
+                path,
+                color
+            );
+        }
+
+        // Legend entry with the latest value
+        let latest = points
+            .last()
+            .map(|&(_, v)| format_value(metric, v))
+            .unwrap_or_else(|| "no data".to_string());
+        let label = format!("{} {}", metric.label(), latest);
+        let _ = write!(
+            svg,
+            r##"<rect x="{:.1}" y="70" width="10" height="10" fill="{}"/><text x="{:.1}" y="79" fill="#d4d4d4" font-size="12">{}</text>"##,
+            legend_x,
+            color,
+            legend_x + 16.0,
+            escape(&label)
+        );
+        legend_x += 16.0 + label.len() as f64 * 7.0 + 20.0;
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Min/max with 5% headroom; flat series get a small band so they stay visible
+fn padded_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if !min.is_finite() || !max.is_finite() {
+        return (0.0, 1.0);
+    }
+    if (max - min).abs() < f64::EPSILON {
+        let band = (max.abs() * 0.01).max(1e-6);
+        return (min - band, max + band);
+    }
+    let pad = (max - min) * 0.05;
+    (min - pad, max + pad)
+}
+
+fn format_value(metric: ChartMetric, value: f64) -> String {
+    match metric {
+        ChartMetric::Price => format!("${:.4}", value),
+        ChartMetric::Volume | ChartMetric::Liquidity => format_usd_compact(value),
+        ChartMetric::TCR | ChartMetric::LendAPR | ChartMetric::BorrowAPR => format!("{:.2}%", value),
+        ChartMetric::Supply | ChartMetric::Holders | ChartMetric::Transfers => format_compact(value),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use usdfc_core::types::TVCandle;
+
+    fn sample() -> ChartDataResponse {
+        let candle = |time: i64, close: f64| TVCandle { time, open: close, high: close, low: close, close, volume: 10.0 };
+        ChartDataResponse {
+            lookback: ChartLookback::Hour1,
+            generated_at: 7_200,
+            price_candles: vec![candle(3_600, 0.99), candle(5_400, 1.01)],
+            volume_data: vec![(3_600, 10.0), (5_400, 12.0)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_effective_range_follows_lookback_or_custom_range() {
+        let data = sample();
+        assert_eq!(effective_range(&data, None, None), (3_600, 7_200));
+        assert_eq!(effective_range(&data, Some(100), Some(200)), (100, 200));
+
+        let all = ChartDataResponse { lookback: ChartLookback::All, ..sample() };
+        assert_eq!(effective_range(&all, None, None), (3_600, 7_200));
+    }
+
+    #[test]
+    fn test_render_svg_draws_each_metric() {
+        let data = sample();
+        let svg = render_svg(&data, &[ChartMetric::Price, ChartMetric::Volume], ChartType::Area, (3_600, 7_200));
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert!(svg.contains("<polyline"));
+        assert!(svg.contains(ChartMetric::Volume.color()));
+        assert!(svg.contains("$1.0100"));
+    }
+}
@@ -6,18 +6,41 @@
 use std::collections::HashMap;
 use std::sync::RwLock;
 use std::time::{Duration, Instant};
-
-/// Circuit breaker state
-#[derive(Clone, Debug, PartialEq)]
-pub enum CircuitState {
-    /// Circuit is closed, requests flow normally
-    Closed,
-    /// Circuit is open, requests are blocked
-    Open,
-    /// Circuit is half-open, testing if endpoint has recovered
-    HalfOpen,
+use once_cell::sync::Lazy;
+use usdfc_core::config::{config, Config};
+use crate::metrics::UPSTREAMS;
+
+/// Registry of per-upstream circuits, keyed by the `timed_send` label
+///
+/// Every upstream in `metrics::UPSTREAMS` has its own independent circuit, so
+/// a GeckoTerminal outage fails price calls fast while RPC and Blockscout keep
+/// working. RPC and the subgraph have one circuit per endpoint (see
+/// [`endpoint_key`]), so a failing primary does not block its fallbacks.
+/// `timing::TimedSend` consults it before each request. Tuned by the
+/// `CIRCUIT_*` settings.
+pub static UPSTREAM_BREAKER: Lazy<CircuitBreaker> = Lazy::new(|| {
+    let cfg = config();
+    let breaker = CircuitBreaker::with_config(CircuitBreakerConfig::from_config(cfg));
+    for upstream in UPSTREAMS {
+        match upstream {
+            "rpc" => cfg.rpc_endpoints().iter().for_each(|url| breaker.register(&endpoint_key(upstream, url))),
+            "subgraph" => cfg.subgraph_endpoints().iter().for_each(|url| breaker.register(&endpoint_key(upstream, url))),
+            _ => breaker.register(upstream),
+        }
+    }
+    breaker
+});
+
+/// Circuit name for one endpoint of a failover upstream, e.g. `rpc:<url>`
+///
+/// API keys in the URL are masked, since circuit names are shown on the
+/// infrastructure page and in /metrics.
+pub fn endpoint_key(upstream: &str, url: &str) -> String {
+    format!("{}:{}", upstream, crate::log_redact::redact(url))
 }
 
+pub use usdfc_core::types::CircuitState;
+
 /// Circuit breaker for a single endpoint
 #[derive(Clone, Debug)]
 struct Circuit {
@@ -25,6 +48,10 @@ struct Circuit {
     failure_count: u32,
     last_failure_time: Option<Instant>,
     last_state_change: Instant,
+    /// Trial requests admitted since entering half-open
+    half_open_trials: u32,
+    /// Trial requests that succeeded since entering half-open
+    half_open_successes: u32,
 }
 
 impl Circuit {
@@ -34,8 +61,17 @@ impl Circuit {
             failure_count: 0,
             last_failure_time: None,
             last_state_change: Instant::now(),
+            half_open_trials: 0,
+            half_open_successes: 0,
         }
     }
+
+    fn transition(&mut self, state: CircuitState, now: Instant) {
+        self.state = state;
+        self.last_state_change = now;
+        self.half_open_trials = 0;
+        self.half_open_successes = 0;
+    }
 }
 
 /// Circuit breaker configuration
@@ -47,8 +83,22 @@ pub struct CircuitBreakerConfig {
     pub failure_window_secs: u64,
     /// How long to keep circuit open before trying again (seconds)
     pub open_timeout_secs: u64,
-    /// How long to wait in half-open state before closing (seconds)
+    /// How long half-open waits for outstanding probes before admitting more (seconds)
     pub half_open_timeout_secs: u64,
+    /// Trial requests admitted in half-open; all must succeed to close
+    pub half_open_probes: u32,
+}
+
+impl CircuitBreakerConfig {
+    /// Threshold, cooldown and probe count from the `CIRCUIT_*` settings
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            failure_threshold: cfg.circuit_failure_threshold,
+            open_timeout_secs: cfg.circuit_open_secs,
+            half_open_probes: cfg.circuit_half_open_probes,
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for CircuitBreakerConfig {
@@ -58,6 +108,7 @@ impl Default for CircuitBreakerConfig {
             failure_window_secs: 60,
             open_timeout_secs: 30,
             half_open_timeout_secs: 10,
+            half_open_probes: 3,
         }
     }
 }
@@ -82,6 +133,12 @@ impl CircuitBreaker {
         }
     }
 
+    /// Start tracking an endpoint with a closed circuit, so it is listed by `states`
+    pub fn register(&self, endpoint: &str) {
+        let mut circuits = self.circuits.write().unwrap();
+        circuits.entry(endpoint.to_string()).or_insert_with(Circuit::new);
+    }
+
     /// Check if a request to the given endpoint should be allowed
     pub fn should_allow(&self, endpoint: &str) -> bool {
         let mut circuits = self.circuits.write().unwrap();
@@ -94,8 +151,8 @@ impl CircuitBreaker {
             CircuitState::Open => {
                 // Check if it's time to try again (half-open)
                 if now.duration_since(circuit.last_state_change) >= Duration::from_secs(self.config.open_timeout_secs) {
-                    circuit.state = CircuitState::HalfOpen;
-                    circuit.last_state_change = now;
+                    circuit.transition(CircuitState::HalfOpen, now);
+                    circuit.half_open_trials = 1;
                     tracing::info!("Circuit breaker for {} transitioning to HALF-OPEN", endpoint);
                     true
                 } else {
@@ -103,8 +160,20 @@ impl CircuitBreaker {
                 }
             }
             CircuitState::HalfOpen => {
-                // Allow request in half-open state
-                true
+                // Probes that never reported back are written off after the timeout
+                if circuit.half_open_trials >= self.config.half_open_probes
+                    && now.duration_since(circuit.last_state_change) >= Duration::from_secs(self.config.half_open_timeout_secs)
+                {
+                    circuit.half_open_trials = circuit.half_open_successes;
+                    circuit.last_state_change = now;
+                }
+                // Admit up to `half_open_probes` trial requests
+                if circuit.half_open_trials < self.config.half_open_probes {
+                    circuit.half_open_trials += 1;
+                    true
+                } else {
+                    false
+                }
             }
             CircuitState::Closed => {
                 // Check if failures are outside the window
@@ -128,11 +197,13 @@ impl CircuitBreaker {
 
         match circuit.state {
             CircuitState::HalfOpen => {
-                // Success in half-open state -> close circuit
-                circuit.state = CircuitState::Closed;
-                circuit.failure_count = 0;
-                circuit.last_state_change = now;
-                tracing::info!("Circuit breaker for {} transitioning to CLOSED", endpoint);
+                // Close once every probe has succeeded
+                circuit.half_open_successes += 1;
+                if circuit.half_open_successes >= self.config.half_open_probes {
+                    circuit.transition(CircuitState::Closed, now);
+                    circuit.failure_count = 0;
+                    tracing::info!("Circuit breaker for {} transitioning to CLOSED", endpoint);
+                }
             }
             CircuitState::Closed => {
                 // Reset failure count on success
@@ -156,16 +227,14 @@ impl CircuitBreaker {
 
         match circuit.state {
             CircuitState::HalfOpen => {
-                // Failure in half-open state -> reopen circuit
-                circuit.state = CircuitState::Open;
-                circuit.last_state_change = now;
+                // Any failed probe reopens the circuit
+                circuit.transition(CircuitState::Open, now);
                 tracing::warn!("Circuit breaker for {} transitioning to OPEN (half-open failure)", endpoint);
             }
             CircuitState::Closed => {
                 // Check if we've exceeded threshold
                 if circuit.failure_count >= self.config.failure_threshold {
-                    circuit.state = CircuitState::Open;
-                    circuit.last_state_change = now;
+                    circuit.transition(CircuitState::Open, now);
                     tracing::warn!(
                         "Circuit breaker for {} transitioning to OPEN ({} failures)",
                         endpoint,
@@ -189,13 +258,23 @@ impl CircuitBreaker {
             .unwrap_or(CircuitState::Closed)
     }
 
+    /// Current state of every endpoint seen so far
+    pub fn states(&self) -> Vec<(String, CircuitState)> {
+        let circuits = self.circuits.read().unwrap();
+        let mut states: Vec<_> = circuits
+            .iter()
+            .map(|(endpoint, c)| (endpoint.clone(), c.state.clone()))
+            .collect();
+        states.sort_by(|a, b| a.0.cmp(&b.0));
+        states
+    }
+
     /// Reset a circuit to closed state
     pub fn reset(&self, endpoint: &str) {
         let mut circuits = self.circuits.write().unwrap();
         if let Some(circuit) = circuits.get_mut(endpoint) {
-            circuit.state = CircuitState::Closed;
+            circuit.transition(CircuitState::Closed, Instant::now());
             circuit.failure_count = 0;
-            circuit.last_state_change = Instant::now();
             tracing::info!("Circuit breaker for {} manually reset to CLOSED", endpoint);
         }
     }
@@ -218,6 +297,7 @@ mod tests {
             failure_window_secs: 60,
             open_timeout_secs: 30,
             half_open_timeout_secs: 10,
+            half_open_probes: 1,
         };
         let breaker = CircuitBreaker::with_config(config);
         let endpoint = "test-endpoint";
@@ -247,6 +327,7 @@ mod tests {
             failure_window_secs: 60,
             open_timeout_secs: 1, // Short timeout for testing
             half_open_timeout_secs: 10,
+            half_open_probes: 1,
         };
         let breaker = CircuitBreaker::with_config(config);
         let endpoint = "test-endpoint";
@@ -267,4 +348,62 @@ mod tests {
         breaker.record_success(endpoint);
         assert_eq!(breaker.get_state(endpoint), CircuitState::Closed);
     }
+
+    #[test]
+    fn test_half_open_needs_every_probe_to_close() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            failure_window_secs: 60,
+            open_timeout_secs: 0, // Half-open on the next request
+            half_open_timeout_secs: 60,
+            half_open_probes: 2,
+        };
+        let breaker = CircuitBreaker::with_config(config);
+        let endpoint = "flapping";
+
+        assert_eq!(breaker.get_state(endpoint), CircuitState::Closed);
+        breaker.record_failure(endpoint);
+        breaker.record_failure(endpoint);
+        assert_eq!(breaker.get_state(endpoint), CircuitState::Open);
+
+        // Two probes are admitted, a third waits for their outcome
+        assert!(breaker.should_allow(endpoint));
+        assert_eq!(breaker.get_state(endpoint), CircuitState::HalfOpen);
+        assert!(breaker.should_allow(endpoint));
+        assert!(!breaker.should_allow(endpoint));
+
+        // A failed probe reopens
+        breaker.record_failure(endpoint);
+        assert_eq!(breaker.get_state(endpoint), CircuitState::Open);
+
+        assert!(breaker.should_allow(endpoint));
+        assert!(breaker.should_allow(endpoint));
+        breaker.record_success(endpoint);
+        assert_eq!(breaker.get_state(endpoint), CircuitState::HalfOpen);
+        breaker.record_success(endpoint);
+        assert_eq!(breaker.get_state(endpoint), CircuitState::Closed);
+        assert!(breaker.should_allow(endpoint));
+    }
+
+    #[test]
+    fn test_upstreams_trip_independently() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..CircuitBreakerConfig::default()
+        };
+        let breaker = CircuitBreaker::with_config(config);
+        breaker.register("blockscout");
+        breaker.register("rpc");
+
+        breaker.record_failure("blockscout");
+        assert!(!breaker.should_allow("blockscout"));
+        assert!(breaker.should_allow("rpc"));
+        assert_eq!(
+            breaker.states(),
+            vec![
+                ("blockscout".to_string(), CircuitState::Open),
+                ("rpc".to_string(), CircuitState::Closed),
+            ]
+        );
+    }
 }
@@ -0,0 +1,90 @@
+//! Ordered upstream endpoints with failover
+//!
+//! RPC and the subgraph each have a primary URL and optional fallbacks
+//! (`RPC_FALLBACK_URLS`, `SUBGRAPH_FALLBACK_URLS`). Clients try them in order
+//! when an endpoint fails (connection error, timeout, 5xx or rate limit) and
+//! remember the last one that answered, so later calls start there instead of
+//! waiting on a failing primary every time.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use once_cell::sync::Lazy;
+use usdfc_core::config::config;
+
+/// An ordered endpoint list and the index of the last one that answered
+pub struct Endpoints {
+    upstream: &'static str,
+    urls: Vec<String>,
+    preferred: AtomicUsize,
+}
+
+impl Endpoints {
+    pub fn new(upstream: &'static str, urls: Vec<String>) -> Self {
+        Self { upstream, urls, preferred: AtomicUsize::new(0) }
+    }
+
+    /// URLs in try order: the last good one, then the rest in configured order
+    pub fn ordered(&self) -> Vec<(usize, &str)> {
+        let preferred = self.preferred.load(Ordering::Relaxed);
+        let mut ordered: Vec<(usize, &str)> = self.urls.iter().map(String::as_str).enumerate().collect();
+        if let Some(pos) = ordered.iter().position(|&(i, _)| i == preferred) {
+            let first = ordered.remove(pos);
+            ordered.insert(0, first);
+        }
+        ordered
+    }
+
+    /// Record that the endpoint at `index` answered, making it first next time
+    pub fn mark_good(&self, index: usize) {
+        let previous = self.preferred.swap(index, Ordering::Relaxed);
+        if previous != index {
+            tracing::warn!(
+                upstream = self.upstream,
+                url = %self.urls[index],
+                "Failed over {} from {} to {}",
+                self.upstream,
+                self.urls[previous],
+                self.urls[index]
+            );
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+}
+
+/// Filecoin JSON-RPC endpoints (`RPC_URL`, then `RPC_FALLBACK_URLS`)
+pub fn rpc() -> &'static Endpoints {
+    static RPC: Lazy<Endpoints> = Lazy::new(|| Endpoints::new("rpc", config().rpc_endpoints()));
+    &RPC
+}
+
+/// Subgraph endpoints (`SUBGRAPH_URL`, then `SUBGRAPH_FALLBACK_URLS`)
+pub fn subgraph() -> &'static Endpoints {
+    static SUBGRAPH: Lazy<Endpoints> = Lazy::new(|| Endpoints::new("subgraph", config().subgraph_endpoints()));
+    &SUBGRAPH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_good_endpoint_is_tried_first() {
+        let endpoints = Endpoints::new("rpc", vec!["a".into(), "b".into(), "c".into()]);
+        let urls = |e: &Endpoints| e.ordered().into_iter().map(|(_, url)| url.to_string()).collect::<Vec<_>>();
+        assert_eq!(urls(&endpoints), ["a", "b", "c"]);
+
+        endpoints.mark_good(2);
+        assert_eq!(urls(&endpoints), ["c", "a", "b"]);
+        assert_eq!(endpoints.ordered()[0], (2, "c"));
+
+        endpoints.mark_good(0);
+        assert_eq!(urls(&endpoints), ["a", "b", "c"]);
+    }
+}
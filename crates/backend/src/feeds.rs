@@ -0,0 +1,74 @@
+//! Connection limits for the push feeds
+//!
+//! `/ws/metrics` and `/sse/transactions` each hold a connection open per
+//! client. Both draw from one pool of `FEED_MAX_CONNECTIONS` slots; a client
+//! turned away gets WebSocket close code 1013 or an SSE 503. Each connection
+//! queues at most `FEED_SEND_BUFFER` frames, so a client that cannot keep up
+//! misses metric updates or is disconnected from the transaction feed instead
+//! of growing server memory.
+
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use usdfc_core::config::config;
+
+/// WebSocket close code for clients refused at the connection limit (Try Again Later)
+pub const CLOSE_TRY_AGAIN_LATER: u16 = 1013;
+
+/// A fixed number of feed connections
+pub struct FeedSlots {
+    semaphore: Arc<Semaphore>,
+    max: usize,
+}
+
+/// A held connection slot, released when dropped
+pub type FeedSlot = OwnedSemaphorePermit;
+
+impl FeedSlots {
+    pub fn new(max: usize) -> Self {
+        Self { semaphore: Arc::new(Semaphore::new(max)), max }
+    }
+
+    /// Take a slot for a new connection, or None when all are in use
+    pub fn try_acquire(&self) -> Option<FeedSlot> {
+        self.semaphore.clone().try_acquire_owned().ok()
+    }
+
+    /// Connections currently open
+    pub fn in_use(&self) -> usize {
+        self.max - self.semaphore.available_permits()
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+/// Slots shared by every push feed
+pub fn slots() -> &'static FeedSlots {
+    static SLOTS: Lazy<FeedSlots> = Lazy::new(|| FeedSlots::new(config().feed_max_connections));
+    &SLOTS
+}
+
+/// Frames queued per connection before the client counts as too slow
+pub fn send_buffer() -> usize {
+    config().feed_send_buffer.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots_are_released_on_drop() {
+        let slots = FeedSlots::new(2);
+        let first = slots.try_acquire().expect("first slot");
+        let _second = slots.try_acquire().expect("second slot");
+        assert!(slots.try_acquire().is_none());
+        assert_eq!(slots.in_use(), 2);
+
+        drop(first);
+        assert_eq!(slots.in_use(), 1);
+        assert!(slots.try_acquire().is_some());
+    }
+}
@@ -0,0 +1,122 @@
+//! Protocol fee bucketing
+//!
+//! Borrowing fees (BorrowerOperations) and redemption fees (TroveManager) are
+//! scanned from contract logs and summed per time bucket. A bucket is only
+//! reported as zero when its whole range was scanned and every log in it was
+//! decoded; otherwise the series value is `None`, since an undecodable log
+//! may have been a fee.
+
+use rust_decimal::Decimal;
+use usdfc_core::types::{ChartResolution, FeeBucket};
+
+use crate::blockscout::FeeScan;
+
+/// Upper bound on buckets per response
+pub const MAX_FEE_BUCKETS: u32 = 500;
+
+/// The requested resolution, or the finest coarser one that keeps the
+/// window within `MAX_FEE_BUCKETS`
+pub fn fee_resolution(requested: ChartResolution, window_mins: u32) -> ChartResolution {
+    ChartResolution::all()
+        .iter()
+        .copied()
+        .filter(|r| r.minutes() >= requested.minutes())
+        .find(|r| window_mins / r.minutes() <= MAX_FEE_BUCKETS)
+        .unwrap_or(ChartResolution::W1)
+}
+
+/// Sum one scan's fees into `[bucket_start, bucket_start + step)`
+///
+/// None when the bucket starts before the scanned range or holds an
+/// undecoded log.
+fn bucket_total(scan: &FeeScan, bucket_start: i64, step: i64) -> Option<Decimal> {
+    let bucket_end = bucket_start + step;
+    let in_bucket = |ts: i64| ts >= bucket_start && ts < bucket_end;
+    if bucket_start < scan.covered_since || scan.undecoded.iter().any(|&ts| in_bucket(ts)) {
+        return None;
+    }
+    Some(scan.events.iter().filter(|e| in_bucket(e.timestamp)).map(|e| e.amount).sum())
+}
+
+/// Bucket borrowing and redemption fees over `[start, end)` in `step_secs` buckets
+///
+/// Bucket starts are aligned to multiples of `step_secs`, so the first bucket
+/// may begin before `start`. Cumulative values stay `None` from the first
+/// unknown bucket onwards.
+pub fn bucket_fees(borrowing: &FeeScan, redemption: &FeeScan, start: i64, end: i64, step_secs: i64) -> Vec<FeeBucket> {
+    let step = step_secs.max(1);
+    let mut buckets = Vec::new();
+    let mut cumulative_borrowing = Some(Decimal::ZERO);
+    let mut cumulative_redemption = Some(Decimal::ZERO);
+
+    let mut bucket_start = start - start.rem_euclid(step);
+    while bucket_start < end {
+        // The aligned first bucket is only partly inside the window; treat it as covered
+        let covered_start = bucket_start.max(start);
+        let borrowing_fees = bucket_total(borrowing, covered_start, bucket_start + step - covered_start);
+        let redemption_fees_fil = bucket_total(redemption, covered_start, bucket_start + step - covered_start);
+
+        cumulative_borrowing = cumulative_borrowing.zip(borrowing_fees).map(|(a, b)| a + b);
+        cumulative_redemption = cumulative_redemption.zip(redemption_fees_fil).map(|(a, b)| a + b);
+
+        buckets.push(FeeBucket {
+            timestamp: bucket_start,
+            borrowing_fees,
+            redemption_fees_fil,
+            cumulative_borrowing_fees: cumulative_borrowing,
+            cumulative_redemption_fees_fil: cumulative_redemption,
+        });
+        bucket_start += step;
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockscout::{FeeEvent, FeeKind};
+
+    fn fee(kind: FeeKind, amount: i64, timestamp: i64) -> FeeEvent {
+        FeeEvent { kind, amount: Decimal::new(amount, 0), timestamp }
+    }
+
+    #[test]
+    fn test_undecoded_and_unscanned_buckets_are_none() {
+        let borrowing = FeeScan {
+            events: vec![fee(FeeKind::Borrowing, 5, 10), fee(FeeKind::Borrowing, 7, 150), fee(FeeKind::Borrowing, 1, 250)],
+            undecoded: vec![120],
+            covered_since: 0,
+        };
+        // Scan stopped at the page cap; only 200.. is known
+        let redemption = FeeScan { events: vec![fee(FeeKind::Redemption, 2, 210)], undecoded: vec![], covered_since: 200 };
+
+        let buckets = bucket_fees(&borrowing, &redemption, 0, 300, 100);
+        assert_eq!(buckets.len(), 3);
+
+        let borrowing_fees: Vec<_> = buckets.iter().map(|b| b.borrowing_fees).collect();
+        assert_eq!(borrowing_fees, [Some(Decimal::new(5, 0)), None, Some(Decimal::new(1, 0))]);
+        assert_eq!(buckets[0].cumulative_borrowing_fees, Some(Decimal::new(5, 0)));
+        // Once a bucket is unknown the running total is too
+        assert_eq!(buckets[2].cumulative_borrowing_fees, None);
+
+        let redemption_fees: Vec<_> = buckets.iter().map(|b| b.redemption_fees_fil).collect();
+        assert_eq!(redemption_fees, [None, None, Some(Decimal::new(2, 0))]);
+    }
+
+    #[test]
+    fn test_empty_scanned_bucket_is_zero() {
+        let scan = FeeScan { covered_since: 0, ..Default::default() };
+        let buckets = bucket_fees(&scan, &scan, 50, 150, 100);
+        // Aligned to 0 and 100; the first bucket only counts from the window start
+        assert_eq!(buckets.iter().map(|b| b.timestamp).collect::<Vec<_>>(), [0, 100]);
+        assert!(buckets.iter().all(|b| b.borrowing_fees == Some(Decimal::ZERO)));
+    }
+
+    #[test]
+    fn test_fee_resolution_caps_bucket_count() {
+        assert_eq!(fee_resolution(ChartResolution::H1, 10_080), ChartResolution::H1);
+        // 3 months of 1m buckets is far over the cap
+        assert_eq!(fee_resolution(ChartResolution::M1, 129_600), ChartResolution::H12);
+    }
+}
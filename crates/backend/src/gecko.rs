@@ -8,6 +8,7 @@
 
 use usdfc_core::config::config;
 use usdfc_core::error::{ApiError, ApiResult};
+use usdfc_core::types::{PoolPriceData, Provenance};
 use governor::{Quota, RateLimiter};
 use governor::clock::DefaultClock;
 use governor::state::{InMemoryState, NotKeyed};
@@ -17,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
+use crate::timing::TimedSend;
 
 /// Maximum retry attempts for rate-limited requests
 const MAX_RETRY_ATTEMPTS: u32 = 3;
@@ -43,14 +45,24 @@ impl GeckoClient {
         let base = config().geckoterminal_url.trim_end_matches('/').to_string();
 
         Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .expect("failed to build gecko HTTP client"),
+            client: crate::http::shared_client(),
             base_url: base,
         }
     }
 
+    /// Provenance for data fetched now from GeckoTerminal
+    pub fn provenance(&self) -> Provenance {
+        Provenance {
+            source: "geckoterminal".to_string(),
+            fetched_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+            block_number: None,
+            stale: false,
+        }
+    }
+
     /// Make a rate-limited request with automatic retry on 429 responses.
     ///
     /// This method:
@@ -67,10 +79,15 @@ impl GeckoClient {
             let response = self
                 .client
                 .get(url)
+                .timeout(crate::http::upstream_timeout())
                 .header("Accept", "application/json")
-                .send()
+                .timed_send("gecko")
                 .await
-                .map_err(|e| ApiError::HttpError(format!("GeckoTerminal request failed: {}", e)))?;
+                .map_err(|e| {
+                    e.into_api_error(crate::http::upstream_timeout(), |e| {
+                        ApiError::HttpError(format!("GeckoTerminal request failed: {}", e))
+                    })
+                })?;
 
             // Handle rate limit response (429 Too Many Requests)
             if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
@@ -184,6 +201,23 @@ impl GeckoClient {
         Ok(data.data.attributes)
     }
 
+    /// Fetch several pools concurrently as price breakdown entries
+    ///
+    /// A pool that fails to load is kept with its error instead of failing the batch.
+    pub async fn get_pools_price_data(&self, pool_addresses: &[&str]) -> Vec<PoolPriceData> {
+        futures::future::join_all(pool_addresses.iter().map(|address| async move {
+            match self.get_pool_info(address).await {
+                Ok(info) => info.price_data(address),
+                Err(e) => PoolPriceData {
+                    address: address.to_string(),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                },
+            }
+        }))
+        .await
+    }
+
     /// Get recent trades from a pool
     pub async fn get_pool_trades(
         &self,
@@ -362,6 +396,23 @@ pub struct PoolInfo {
     pub transactions: Option<TransactionData>,
 }
 
+impl PoolInfo {
+    /// USDFC pricing figures for this pool, with unparseable values left as None
+    pub fn price_data(&self, pool_address: &str) -> PoolPriceData {
+        let parse = |v: Option<&String>| v.and_then(|s| s.parse::<f64>().ok());
+        PoolPriceData {
+            address: pool_address.to_string(),
+            name: Some(self.name.clone()),
+            // SAFETY: Use Option - never fallback to 1.0 for price (masks depegging)
+            price_usd: parse(self.base_token_price_usd.as_ref()),
+            price_change_24h: parse(self.price_change_percentage.as_ref().and_then(|p| p.h24.as_ref())),
+            volume_24h: parse(self.volume_usd.as_ref().and_then(|v| v.h24.as_ref())),
+            liquidity_usd: parse(self.reserve_in_usd.as_ref()),
+            error: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TransactionData {
     #[serde(default)]
@@ -1,30 +1,45 @@
 //! Historical Metric Snapshot Storage
 //!
 //! In-memory time-series store for metrics that don't have historical APIs.
-//! Collects snapshots every 60 seconds and stores up to 1 week of data.
-//! Data is persisted to SQLite to survive server restarts.
+//! Collects snapshots every `SNAPSHOT_INTERVAL_SECS` (default 60 seconds) and
+//! stores up to `HISTORY_RETENTION_SECS` (default 1 week) of data.
+//! Data is persisted to SQLite to survive server restarts. Snapshots older
+//! than `HISTORY_RAW_RETENTION_SECS` (default 48 hours) are averaged into
+//! `HISTORY_ROLLUP_SECS` buckets (default hourly) to keep the database small.
 
 use std::collections::VecDeque;
 use std::sync::RwLock;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use usdfc_core::types::{ChartMetric, ProtocolMetrics};
 
 
 use rusqlite::{Connection, params};
 
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Default maximum number of snapshots to store (1 week at 1-minute intervals)
-/// Configurable via HISTORY_RETENTION_SECS environment variable
+/// Actual limit derives from HISTORY_RETENTION_SECS and SNAPSHOT_INTERVAL_SECS
 const DEFAULT_MAX_SNAPSHOTS: usize = 10080;
 
-/// Get configured max snapshots from history_retention_secs
+/// Minimum snapshot interval, protecting the RPC budget from misconfiguration
+const MIN_SNAPSHOT_INTERVAL_SECS: u64 = 10;
+
+/// Get the configured snapshot collector interval in seconds
+pub fn snapshot_interval_secs() -> u64 {
+    usdfc_core::config::config()
+        .snapshot_interval_secs
+        .max(MIN_SNAPSHOT_INTERVAL_SECS)
+}
+
+/// Get configured max snapshots from history_retention_secs and the collector interval
 fn max_snapshots() -> usize {
-    (usdfc_core::config::config().history_retention_secs / 60) as usize
+    (usdfc_core::config::config().history_retention_secs / snapshot_interval_secs()) as usize
 }
 
 /// Get the SQLite database path from environment or use default
-
 fn db_path() -> String {
     std::env::var("DATABASE_PATH")
         .unwrap_or_else(|_| "data/metrics_history.db".to_string())
@@ -40,6 +55,13 @@ pub struct MetricSnapshot {
     pub holders: u64,
     pub lend_apr: f64,
     pub borrow_apr: f64,
+    /// Top of the USDFC lending order book - None when a side was empty or unavailable
+    #[serde(default)]
+    pub best_lend_price: Option<f64>,
+    #[serde(default)]
+    pub best_borrow_price: Option<f64>,
+    #[serde(default)]
+    pub spread_bps: Option<f64>,
 }
 
 /// Global in-memory history store
@@ -47,11 +69,17 @@ pub static METRIC_HISTORY: Lazy<RwLock<VecDeque<MetricSnapshot>>> =
     Lazy::new(|| RwLock::new(VecDeque::with_capacity(DEFAULT_MAX_SNAPSHOTS)));
 
 /// SQLite connection (SSR only)
-
 pub static DB_CONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
 
-/// Initialize the SQLite database and load existing data into memory
+/// Set while snapshot writes are failing, so a broken database logs once
+static DB_WRITES_FAILING: AtomicBool = AtomicBool::new(false);
 
+/// Initialize the SQLite database and load existing data into memory
+///
+/// On failure the server keeps running without persistence: snapshots are
+/// kept in memory only and charts fall back to live values. A database that
+/// opens but rejects schema writes (read-only filesystem) still has its
+/// existing history loaded.
 pub fn init_db() -> Result<(), rusqlite::Error> {
     let path = db_path();
 
@@ -62,6 +90,33 @@ pub fn init_db() -> Result<(), rusqlite::Error> {
 
     let conn = Connection::open(&path)?;
 
+    if let Err(e) = init_schema(&conn) {
+        if let Err(load_err) = load_from_db(&conn) {
+            tracing::warn!("No stored history available: {}", load_err);
+        }
+        return Err(e);
+    }
+
+    // Load existing data into memory cache
+    load_from_db(&conn)?;
+
+    *DB_CONN.lock().unwrap_or_else(|e| e.into_inner()) = Some(conn);
+    Ok(())
+}
+
+/// Create the tables every module stores in the metrics database
+fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
+    init_snapshot_table(conn)?;
+    crate::webhooks::init_tables(conn)?;
+    crate::webhooks::sync_from_config(conn)?;
+    crate::address_notes::init_tables(conn)?;
+    crate::watchlist::init_tables(conn)?;
+    crate::alert_events::init_tables(conn)?;
+    Ok(())
+}
+
+/// Create the metric snapshot table and its index
+fn init_snapshot_table(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS metric_snapshots (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -71,44 +126,46 @@ pub fn init_db() -> Result<(), rusqlite::Error> {
             liquidity REAL NOT NULL,
             holders INTEGER NOT NULL,
             lend_apr REAL NOT NULL,
-            borrow_apr REAL NOT NULL
+            borrow_apr REAL NOT NULL,
+            best_lend_price REAL,
+            best_borrow_price REAL,
+            spread_bps REAL
         )",
         [],
     )?;
 
+    // Databases created before the order book columns existed
+    let existing: Vec<String> = conn
+        .prepare("PRAGMA table_info(metric_snapshots)")?
+        .query_map([], |row| row.get(1))?
+        .collect::<Result<_, _>>()?;
+    for column in ORDER_BOOK_COLUMNS {
+        if !existing.iter().any(|c| c == column) {
+            conn.execute(&format!("ALTER TABLE metric_snapshots ADD COLUMN {} REAL", column), [])?;
+        }
+    }
+
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_timestamp ON metric_snapshots(timestamp)",
         [],
     )?;
-
-    // Load existing data into memory cache
-    load_from_db(&conn)?;
-
-    *DB_CONN.lock().unwrap() = Some(conn);
     Ok(())
 }
 
-/// Load snapshots from the database into the in-memory cache
+/// Nullable snapshot columns added after the table was first released
+const ORDER_BOOK_COLUMNS: [&str; 3] = ["best_lend_price", "best_borrow_price", "spread_bps"];
 
+/// Load snapshots from the database into the in-memory cache
 fn load_from_db(conn: &Connection) -> Result<(), rusqlite::Error> {
     let mut stmt = conn.prepare(
-        "SELECT timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr
+        "SELECT timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr,
+                best_lend_price, best_borrow_price, spread_bps
          FROM metric_snapshots
          ORDER BY timestamp DESC
          LIMIT ?"
     )?;
 
-    let snapshots = stmt.query_map([max_snapshots() as i64], |row| {
-        Ok(MetricSnapshot {
-            timestamp: row.get(0)?,
-            tcr: row.get(1)?,
-            supply: row.get(2)?,
-            liquidity: row.get(3)?,
-            holders: row.get(4)?,
-            lend_apr: row.get(5)?,
-            borrow_apr: row.get(6)?,
-        })
-    })?;
+    let snapshots = stmt.query_map([max_snapshots() as i64], snapshot_from_row)?;
 
     if let Ok(mut history) = METRIC_HISTORY.write() {
         history.clear();
@@ -122,7 +179,6 @@ fn load_from_db(conn: &Connection) -> Result<(), rusqlite::Error> {
 }
 
 /// Save a snapshot to the SQLite database
-
 fn save_to_db(snapshot: &MetricSnapshot) -> Result<(), rusqlite::Error> {
     let db_lock = DB_CONN.lock().map_err(|e| {
         tracing::error!("Mutex poison error in save_to_db: {}", e);
@@ -132,8 +188,9 @@ fn save_to_db(snapshot: &MetricSnapshot) -> Result<(), rusqlite::Error> {
     if let Some(ref conn) = *db_lock {
         conn.execute(
             "INSERT OR REPLACE INTO metric_snapshots
-             (timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+             (timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr,
+              best_lend_price, best_borrow_price, spread_bps)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 snapshot.timestamp,
                 snapshot.tcr,
@@ -142,20 +199,286 @@ fn save_to_db(snapshot: &MetricSnapshot) -> Result<(), rusqlite::Error> {
                 snapshot.holders,
                 snapshot.lend_apr,
                 snapshot.borrow_apr,
+                snapshot.best_lend_price,
+                snapshot.best_borrow_price,
+                snapshot.spread_bps,
             ],
         )?;
+    }
+    Ok(())
+}
 
-        // Cleanup old entries beyond max_snapshots
-        conn.execute(
-            "DELETE FROM metric_snapshots WHERE id NOT IN
-             (SELECT id FROM metric_snapshots ORDER BY timestamp DESC LIMIT ?)",
-            [max_snapshots() as i64],
+/// How often the retention job runs
+const PRUNE_INTERVAL_SECS: u64 = 3600;
+
+/// Average old snapshots into rollup buckets and delete expired ones
+///
+/// Snapshots older than `HISTORY_RAW_RETENTION_SECS` are replaced, one
+/// completed bucket at a time, by a single row at the bucket start holding
+/// their averages. Rows older than `HISTORY_RETENTION_SECS` are deleted. The
+/// in-memory history is reloaded when anything changed.
+pub fn prune_and_rollup() -> Result<(), rusqlite::Error> {
+    let cfg = usdfc_core::config::config();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in prune_and_rollup: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    if let Some(ref conn) = *db_lock {
+        let (rolled_up, deleted) = prune_and_rollup_at(
+            conn,
+            now,
+            cfg.history_raw_retention_secs as i64,
+            cfg.history_rollup_secs as i64,
+            cfg.history_retention_secs as i64,
         )?;
+        if rolled_up > 0 || deleted > 0 {
+            tracing::info!(
+                "Rolled up {} snapshot buckets and deleted {} expired snapshots",
+                rolled_up,
+                deleted
+            );
+            load_from_db(conn)?;
+        }
     }
     Ok(())
 }
 
+/// Roll up and prune relative to `now`, returning (buckets rolled up, rows deleted)
+fn prune_and_rollup_at(
+    conn: &Connection,
+    now: i64,
+    raw_secs: i64,
+    bucket_secs: i64,
+    retention_secs: i64,
+) -> Result<(usize, usize), rusqlite::Error> {
+    let bucket_secs = bucket_secs.max(1);
+    // Only completed buckets, so a bucket is never rolled up twice
+    let rollup_before = (now - raw_secs).div_euclid(bucket_secs) * bucket_secs;
+
+    let tx = conn.unchecked_transaction()?;
+    let deleted = tx.execute(
+        "DELETE FROM metric_snapshots WHERE timestamp < ?1",
+        [now - retention_secs],
+    )?;
+
+    let buckets = {
+        // A bucket already rolled up is a single row at its start
+        let mut stmt = tx.prepare(
+            "SELECT (timestamp / ?1) * ?1 AS bucket, AVG(tcr), AVG(supply), AVG(liquidity),
+                    CAST(ROUND(AVG(holders)) AS INTEGER), AVG(lend_apr), AVG(borrow_apr),
+                    AVG(best_lend_price), AVG(best_borrow_price), AVG(spread_bps)
+             FROM metric_snapshots
+             WHERE timestamp < ?2
+             GROUP BY bucket
+             HAVING COUNT(*) > 1 OR MIN(timestamp) != bucket",
+        )?;
+        let rows = stmt.query_map([bucket_secs, rollup_before], snapshot_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+
+    for bucket in &buckets {
+        tx.execute(
+            "DELETE FROM metric_snapshots WHERE timestamp >= ?1 AND timestamp < ?2",
+            [bucket.timestamp, bucket.timestamp + bucket_secs],
+        )?;
+        tx.execute(
+            "INSERT INTO metric_snapshots
+             (timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr,
+              best_lend_price, best_borrow_price, spread_bps)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                bucket.timestamp,
+                bucket.tcr,
+                bucket.supply,
+                bucket.liquidity,
+                bucket.holders,
+                bucket.lend_apr,
+                bucket.borrow_apr,
+                bucket.best_lend_price,
+                bucket.best_borrow_price,
+                bucket.spread_bps,
+            ],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok((buckets.len(), deleted))
+}
+
+/// Run `prune_and_rollup` now and then every hour
+pub fn start_retention_job() {
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(PRUNE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if let Err(e) = prune_and_rollup() {
+                tracing::error!("Failed to prune metric history: {}", e);
+            }
+        }
+    });
+}
+
+/// Chart metrics recorded in snapshots
+pub const SNAPSHOT_METRICS: [ChartMetric; 6] = [
+    ChartMetric::TCR,
+    ChartMetric::Supply,
+    ChartMetric::Liquidity,
+    ChartMetric::Holders,
+    ChartMetric::LendAPR,
+    ChartMetric::BorrowAPR,
+];
+
+/// Value of `metric` in a snapshot, or None for metrics snapshots don't record
+pub fn snapshot_value(metric: ChartMetric, snapshot: &MetricSnapshot) -> Option<f64> {
+    match metric {
+        ChartMetric::TCR => Some(snapshot.tcr),
+        ChartMetric::Supply => Some(snapshot.supply),
+        ChartMetric::Liquidity => Some(snapshot.liquidity),
+        ChartMetric::Holders => Some(snapshot.holders as f64),
+        ChartMetric::LendAPR => Some(snapshot.lend_apr),
+        ChartMetric::BorrowAPR => Some(snapshot.borrow_apr),
+        ChartMetric::Price | ChartMetric::Volume | ChartMetric::Transfers => None,
+    }
+}
+
+/// Recorded values of `metric` with `start <= timestamp <= end`, oldest first
+///
+/// Reads the snapshot table, so rolled-up history is returned at its bucket
+/// resolution. Falls back to the in-memory history without a database.
+pub fn metric_range(metric: ChartMetric, start: i64, end: i64) -> Result<Vec<(i64, f64)>, rusqlite::Error> {
+    Ok(snapshot_range(start, end)?
+        .iter()
+        .filter_map(|s| Some((s.timestamp, snapshot_value(metric, s)?)))
+        .collect())
+}
+
+/// Recorded order book spread (bps) with `start <= timestamp <= end`, oldest first
+///
+/// Snapshots taken while either side of the book was empty are skipped.
+pub fn spread_range(start: i64, end: i64) -> Result<Vec<(i64, f64)>, rusqlite::Error> {
+    Ok(snapshot_range(start, end)?
+        .iter()
+        .filter_map(|s| Some((s.timestamp, s.spread_bps?)))
+        .collect())
+}
+
+fn snapshot_range(start: i64, end: i64) -> Result<Vec<MetricSnapshot>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in snapshot_range: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let snapshots = match *db_lock {
+        Some(ref conn) => snapshots_between(conn, start, end)?,
+        None => METRIC_HISTORY
+            .read()
+            .map(|history| {
+                history
+                    .iter()
+                    .filter(|s| s.timestamp >= start && s.timestamp <= end)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    Ok(snapshots)
+}
+
+fn snapshots_between(conn: &Connection, start: i64, end: i64) -> Result<Vec<MetricSnapshot>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr,
+                best_lend_price, best_borrow_price, spread_bps
+         FROM metric_snapshots
+         WHERE timestamp BETWEEN ?1 AND ?2
+         ORDER BY timestamp",
+    )?;
+    let rows = stmt.query_map([start, end], snapshot_from_row)?;
+    rows.collect()
+}
+
+/// Up to `limit` snapshots with `after < timestamp <= end`, oldest first
+///
+/// For paging through the whole table: pass the last timestamp of one page
+/// as `after` for the next. The database lock is held for one page only.
+/// Falls back to the in-memory history without a database.
+pub fn snapshot_page(after: i64, end: i64, limit: usize) -> Result<Vec<MetricSnapshot>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in snapshot_page: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    match *db_lock {
+        Some(ref conn) => snapshots_after(conn, after, end, limit),
+        None => Ok(METRIC_HISTORY
+            .read()
+            .map(|history| {
+                history
+                    .iter()
+                    .filter(|s| s.timestamp > after && s.timestamp <= end)
+                    .take(limit)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()),
+    }
+}
+
+fn snapshots_after(conn: &Connection, after: i64, end: i64, limit: usize) -> Result<Vec<MetricSnapshot>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr,
+                best_lend_price, best_borrow_price, spread_bps
+         FROM metric_snapshots
+         WHERE timestamp > ?1 AND timestamp <= ?2
+         ORDER BY timestamp
+         LIMIT ?3",
+    )?;
+    let rows = stmt.query_map([after, end, limit as i64], snapshot_from_row)?;
+    rows.collect()
+}
+
+fn snapshot_from_row(row: &rusqlite::Row<'_>) -> Result<MetricSnapshot, rusqlite::Error> {
+    Ok(MetricSnapshot {
+        timestamp: row.get(0)?,
+        tcr: row.get(1)?,
+        supply: row.get(2)?,
+        liquidity: row.get(3)?,
+        holders: row.get(4)?,
+        lend_apr: row.get(5)?,
+        borrow_apr: row.get(6)?,
+        best_lend_price: row.get(7)?,
+        best_borrow_price: row.get(8)?,
+        spread_bps: row.get(9)?,
+    })
+}
+
 impl MetricSnapshot {
+    pub const CSV_HEADER: &'static str =
+        "Timestamp,TCR,Supply,Liquidity,Holders,LendAPR,BorrowAPR,BestLendPrice,BestBorrowPrice,SpreadBps\n";
+
+    /// This snapshot as one CSV line, matching `CSV_HEADER` (missing order book values are empty)
+    pub fn csv_row(&self) -> String {
+        let optional = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_default();
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            self.timestamp,
+            self.tcr,
+            self.supply,
+            self.liquidity,
+            self.holders,
+            self.lend_apr,
+            self.borrow_apr,
+            optional(self.best_lend_price),
+            optional(self.best_borrow_price),
+            optional(self.spread_bps)
+        )
+    }
+
     /// Create a new snapshot with current timestamp
     pub fn new(
         tcr: f64,
@@ -178,6 +501,9 @@ impl MetricSnapshot {
             holders,
             lend_apr,
             borrow_apr,
+            best_lend_price: None,
+            best_borrow_price: None,
+            spread_bps: None,
         }
     }
 
@@ -185,10 +511,17 @@ impl MetricSnapshot {
     pub fn record(snapshot: MetricSnapshot) {
         // Persist to SQLite database (SSR only)
         
-        {
-            if let Err(e) = save_to_db(&snapshot) {
-                tracing::error!("Failed to save snapshot to DB: {}", e);
+        match save_to_db(&snapshot) {
+            Ok(()) => {
+                if DB_WRITES_FAILING.swap(false, Ordering::Relaxed) {
+                    tracing::info!("Snapshot persistence recovered");
+                }
+            }
+            // Keep collecting in memory; repeat failures would flood the log every interval
+            Err(e) if DB_WRITES_FAILING.swap(true, Ordering::Relaxed) => {
+                tracing::debug!("Failed to save snapshot to DB: {}", e);
             }
+            Err(e) => tracing::error!("Failed to save snapshot to DB, keeping history in memory only: {}", e),
         }
 
         // Update in-memory cache
@@ -232,7 +565,8 @@ impl MetricSnapshot {
             now - (lookback_mins as i64 * 60)
         };
 
-        let resolution_secs = (resolution_mins as i64 * 60).max(60); // Min 1 minute
+        // Buckets finer than the collector interval would never hold more than one snapshot
+        let resolution_secs = (resolution_mins as i64 * 60).max(snapshot_interval_secs() as i64);
 
         if let Ok(history) = METRIC_HISTORY.read() {
             let mut result = Vec::new();
@@ -286,13 +620,13 @@ impl MetricSnapshot {
 }
 
 /// Collect current metrics and create a snapshot
-
 pub async fn collect_current_snapshot() -> Option<MetricSnapshot> {
     use crate::rpc::RpcClient;
     use crate::gecko::GeckoClient;
     use crate::blockscout::BlockscoutClient;
     use crate::subgraph::SubgraphClient;
     use usdfc_core::config::config;
+    use usdfc_core::types::{Currency, OrderBookData};
     use rust_decimal::prelude::ToPrimitive;
 
     let rpc = RpcClient::new();
@@ -301,12 +635,13 @@ pub async fn collect_current_snapshot() -> Option<MetricSnapshot> {
     let subgraph = SubgraphClient::new();
 
     // Parallel fetch (within rate budget)
-    let (tcr_result, supply_result, pool_result, holders_result, markets_result) = tokio::join!(
+    let (tcr_result, supply_result, pool_result, holders_result, markets_result, book_result) = tokio::join!(
         rpc.get_tcr(),
         rpc.get_total_supply(),
         gecko.get_pool_info(&config().pool_usdfc_wfil),
         blockscout.get_holder_count(),
-        subgraph.get_lending_markets()
+        subgraph.get_lending_markets(),
+        subgraph.get_order_book(&Currency::Usdfc, None, 100)
     );
 
     // Extract values with fallbacks
@@ -344,41 +679,275 @@ pub async fn collect_current_snapshot() -> Option<MetricSnapshot> {
         })
         .unwrap_or((0.0, 0.0));
 
-    Some(MetricSnapshot::new(tcr, supply, liquidity, holders, lend_apr, borrow_apr))
+    // Top of book, as `GetOrderBook` reports it
+    let (best_lend_price, best_borrow_price) = book_result.map(|book| book.best_prices()).unwrap_or((None, None));
+
+    Some(MetricSnapshot {
+        best_lend_price,
+        best_borrow_price,
+        spread_bps: OrderBookData::spread_between(best_lend_price, best_borrow_price),
+        ..MetricSnapshot::new(tcr, supply, liquidity, holders, lend_apr, borrow_apr)
+    })
 }
 
-/// Start the background snapshot collector task
+/// Zero address used by the token contract for mints and burns
+const ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
 
-pub fn start_snapshot_collector() {
-    tokio::spawn(async move {
-        use std::time::Duration;
+/// Maximum transfer pages (200 transfers each) walked during backfill
+const BACKFILL_MAX_TRANSFER_PAGES: usize = 25;
 
-        // Collect first snapshot immediately
-        if let Some(snapshot) = collect_current_snapshot().await {
-            MetricSnapshot::record(snapshot);
+/// Insert a batch of snapshots in a single transaction
+fn save_batch_to_db(snapshots: &[MetricSnapshot]) -> Result<(), rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in save_batch_to_db: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    if let Some(ref conn) = *db_lock {
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO metric_snapshots
+                 (timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr,
+                  best_lend_price, best_borrow_price, spread_bps)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for snapshot in snapshots {
+                stmt.execute(params![
+                    snapshot.timestamp,
+                    snapshot.tcr,
+                    snapshot.supply,
+                    snapshot.liquidity,
+                    snapshot.holders,
+                    snapshot.lend_apr,
+                    snapshot.borrow_apr,
+                    snapshot.best_lend_price,
+                    snapshot.best_borrow_price,
+                    snapshot.spread_bps,
+                ])?;
+            }
         }
+        tx.commit()?;
+
+        load_from_db(conn)?;
+    }
+    Ok(())
+}
 
-        // Then collect every 60 seconds
-        let mut interval = tokio::time::interval(Duration::from_secs(60));
-        interval.tick().await; // Skip first tick (already collected)
+/// Backfill coarse history on a fresh deploy
+///
+/// Builds hourly snapshots from GeckoTerminal OHLCV timestamps and walks
+/// Blockscout token transfers backwards from the current state: mints and
+/// burns rewind the supply, and first-seen recipients rewind the holder count.
+/// TCR, liquidity and APRs have no historical source and carry the current
+/// values. Does nothing if any snapshots already exist.
+pub async fn backfill_history() {
+    use crate::gecko::GeckoClient;
+    use crate::blockscout::BlockscoutClient;
+    use std::collections::HashMap;
+    use usdfc_core::config::config;
 
-        loop {
-            interval.tick().await;
+    if MetricSnapshot::count() > 0 {
+        tracing::info!("Skipping history backfill: snapshots already exist");
+        return;
+    }
+
+    let Some(current) = collect_current_snapshot().await else {
+        tracing::warn!("Skipping history backfill: failed to collect current metrics");
+        return;
+    };
+
+    let gecko = GeckoClient::new();
+    let candles = match gecko
+        .get_pool_ohlcv(&config().pool_usdfc_wfil, "hour", 1, 100)
+        .await
+    {
+        Ok(candles) if !candles.is_empty() => candles,
+        Ok(_) => {
+            tracing::warn!("Skipping history backfill: no OHLCV data returned");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!("Skipping history backfill: OHLCV fetch failed: {}", e);
+            return;
+        }
+    };
+
+    let retention_cutoff = current.timestamp - config().history_retention_secs as i64;
+    let mut timestamps: Vec<i64> = candles
+        .iter()
+        .map(|c| c.timestamp)
+        .filter(|ts| *ts >= retention_cutoff && *ts < current.timestamp)
+        .collect();
+    timestamps.sort_unstable();
+    timestamps.dedup();
 
-            match collect_current_snapshot().await {
-                Some(snapshot) => {
-                    MetricSnapshot::record(snapshot);
+    let Some(&earliest) = timestamps.first() else {
+        tracing::warn!("Skipping history backfill: OHLCV data outside retention window");
+        return;
+    };
+
+    // Walk transfers (newest first) back to the earliest candle
+    let blockscout = BlockscoutClient::new();
+    let mut transfers = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..BACKFILL_MAX_TRANSFER_PAGES {
+        match blockscout
+            .gql_get_transfers_with_timestamps(&config().usdfc_token, 200, cursor.as_deref())
+            .await
+        {
+            Ok((page, next)) => {
+                let reached_start = page.last().map(|t| t.timestamp < earliest).unwrap_or(true);
+                transfers.extend(page);
+                cursor = next;
+                if reached_start || cursor.is_none() {
+                    break;
                 }
-                None => {
-                    tracing::warn!("Failed to collect metric snapshot");
+            }
+            Err(e) => {
+                tracing::warn!("History backfill transfer fetch stopped early: {}", e);
+                break;
+            }
+        }
+    }
+
+    // Net supply change and first receipt time per recipient within the window
+    let mut supply_deltas: Vec<(i64, f64)> = Vec::new();
+    let mut first_seen: HashMap<String, i64> = HashMap::new();
+    for t in transfers.iter().filter(|t| t.timestamp >= earliest) {
+        let amount = t.amount.parse::<f64>().unwrap_or(0.0) / 1e18;
+        if t.from_address.eq_ignore_ascii_case(ZERO_ADDRESS) {
+            supply_deltas.push((t.timestamp, amount));
+        } else if t.to_address.eq_ignore_ascii_case(ZERO_ADDRESS) {
+            supply_deltas.push((t.timestamp, -amount));
+        }
+        if !t.to_address.eq_ignore_ascii_case(ZERO_ADDRESS) {
+            let seen = first_seen.entry(t.to_address.to_lowercase()).or_insert(t.timestamp);
+            *seen = (*seen).min(t.timestamp);
+        }
+    }
+
+    let snapshots: Vec<MetricSnapshot> = timestamps
+        .iter()
+        .map(|&ts| {
+            let supply_after: f64 = supply_deltas
+                .iter()
+                .filter(|(t, _)| *t > ts)
+                .map(|(_, delta)| delta)
+                .sum();
+            let new_holders = first_seen.values().filter(|t| **t > ts).count() as u64;
+
+            MetricSnapshot {
+                timestamp: ts,
+                tcr: current.tcr,
+                supply: (current.supply - supply_after).max(0.0),
+                liquidity: current.liquidity,
+                holders: current.holders.saturating_sub(new_holders),
+                lend_apr: current.lend_apr,
+                borrow_apr: current.borrow_apr,
+                // No order book history to rebuild from
+                best_lend_price: None,
+                best_borrow_price: None,
+                spread_bps: None,
+            }
+        })
+        .collect();
+
+    match save_batch_to_db(&snapshots) {
+        Ok(()) => tracing::info!("Backfilled {} historical snapshots", snapshots.len()),
+        Err(e) => tracing::error!("Failed to save backfilled snapshots: {}", e),
+    }
+}
+
+/// Start the background snapshot collector task
+///
+/// The collector is supervised: if an iteration panics it is logged and the
+/// collector restarts after one interval instead of stopping for good.
+/// After each snapshot, fresh protocol metrics are sent on `metrics_tx`
+/// while it has subscribers.
+pub fn start_snapshot_collector(metrics_tx: broadcast::Sender<ProtocolMetrics>) {
+    tokio::spawn(async move {
+        use std::time::Duration;
+
+        // Populate history on a fresh database before live collection starts
+        if usdfc_core::config::config().backfill_on_start {
+            backfill_history().await;
+        }
+
+        loop {
+            match tokio::spawn(run_snapshot_collector(metrics_tx.clone())).await {
+                Err(e) if e.is_panic() => {
+                    tracing::error!("Snapshot collector panicked, restarting: {}", e);
+                    tokio::time::sleep(Duration::from_secs(snapshot_interval_secs())).await;
                 }
+                _ => break,
             }
         }
     });
 }
 
-/// Check database health by executing a simple query
+/// Collect a snapshot now and then every configured interval
+async fn run_snapshot_collector(metrics_tx: broadcast::Sender<ProtocolMetrics>) {
+    use std::time::Duration;
+
+    // Collect first snapshot immediately
+    let mut previous = collect_current_snapshot().await;
+    if let Some(snapshot) = previous.clone() {
+        MetricSnapshot::record(snapshot);
+        broadcast_protocol_metrics(&metrics_tx).await;
+    }
+
+    // Then collect every configured interval
+    let mut interval = tokio::time::interval(Duration::from_secs(snapshot_interval_secs()));
+    interval.tick().await; // Skip first tick (already collected)
+
+    loop {
+        interval.tick().await;
+
+        match collect_current_snapshot().await {
+            Some(snapshot) => {
+                let events = crate::alerts::evaluate_snapshot(
+                    previous.as_ref(),
+                    &snapshot,
+                    usdfc_core::config::config().tcr_danger_threshold,
+                );
+                if let Err(e) = crate::alert_events::record(&events) {
+                    tracing::error!("Failed to record alert events: {}", e);
+                }
+                crate::webhooks::dispatch(events);
+                previous = Some(snapshot.clone());
+                MetricSnapshot::record(snapshot);
+                broadcast_protocol_metrics(&metrics_tx).await;
+            }
+            None => {
+                tracing::warn!("Failed to collect metric snapshot");
+            }
+        }
+    }
+}
+
+/// Fetch protocol metrics and send them to live subscribers
+///
+/// Skipped when nobody is subscribed, to keep the RPC budget for polling
+/// clients. The fetch also refreshes the protocol metrics cache.
+async fn broadcast_protocol_metrics(metrics_tx: &broadcast::Sender<ProtocolMetrics>) {
+    use crate::cache::caches;
+    use crate::sources::{fetch_protocol_metrics, DataSources};
+
+    if metrics_tx.receiver_count() == 0 {
+        return;
+    }
+    match fetch_protocol_metrics(&DataSources::current()).await {
+        Ok(metrics) => {
+            caches::PROTOCOL_METRICS.set("default".to_string(), metrics.clone());
+            // Subscribers may have disconnected since the check; nothing to do then
+            let _ = metrics_tx.send(metrics);
+        }
+        Err(e) => tracing::warn!("Failed to fetch protocol metrics for live subscribers: {}", e),
+    }
+}
 
+/// Check database health by executing a simple query
 pub fn check_db_health() -> Result<(), String> {
     if let Some(ref conn) = *DB_CONN.lock().map_err(|e| e.to_string())? {
         // Simple query to verify database is accessible
@@ -389,3 +958,148 @@ pub fn check_db_health() -> Result<(), String> {
         Err("Database connection not initialized".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use usdfc_core::types::OrderBookData;
+
+    fn snapshot(timestamp: i64, tcr: f64, holders: u64) -> MetricSnapshot {
+        MetricSnapshot {
+            timestamp,
+            tcr,
+            supply: 1000.0,
+            liquidity: 50.0,
+            holders,
+            lend_apr: 4.0,
+            borrow_apr: 6.0,
+            best_lend_price: None,
+            best_borrow_price: None,
+            spread_bps: None,
+        }
+    }
+
+    fn stored(conn: &Connection) -> Vec<(i64, f64, u64)> {
+        let mut stmt = conn
+            .prepare("SELECT timestamp, tcr, holders FROM metric_snapshots ORDER BY timestamp")
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn test_db(rows: &[MetricSnapshot]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_snapshot_table(&conn).unwrap();
+        for row in rows {
+            conn.execute(
+                "INSERT INTO metric_snapshots
+                 (timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![row.timestamp, row.tcr, row.supply, row.liquidity, row.holders, row.lend_apr, row.borrow_apr],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_old_snapshots_roll_up_into_hourly_averages() {
+        let now = 100 * 86400;
+        let hour = now - 72 * 3600;
+        let rows = [
+            // Past retention
+            snapshot(now - 8 * 86400, 150.0, 10),
+            // Three minutes in one old hour
+            snapshot(hour, 150.0, 10),
+            snapshot(hour + 60, 160.0, 11),
+            snapshot(hour + 120, 170.0, 13),
+            // Recent, kept at full resolution
+            snapshot(now - 3600, 180.0, 20),
+            snapshot(now - 3540, 181.0, 20),
+        ];
+        let conn = test_db(&rows);
+
+        let week = 7 * 86400;
+        assert_eq!(prune_and_rollup_at(&conn, now, 48 * 3600, 3600, week).unwrap(), (1, 1));
+        assert_eq!(
+            stored(&conn),
+            vec![(hour, 160.0, 11), (now - 3600, 180.0, 20), (now - 3540, 181.0, 20)]
+        );
+
+        // Already rolled up buckets are left alone
+        assert_eq!(prune_and_rollup_at(&conn, now, 48 * 3600, 3600, week).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_snapshots_between_is_inclusive() {
+        let conn = test_db(&[snapshot(1000, 150.0, 10), snapshot(1060, 151.0, 10), snapshot(1120, 152.0, 12)]);
+        let tcr: Vec<_> = snapshots_between(&conn, 1000, 1060)
+            .unwrap()
+            .iter()
+            .filter_map(|s| Some((s.timestamp, snapshot_value(ChartMetric::TCR, s)?)))
+            .collect();
+        assert_eq!(tcr, vec![(1000, 150.0), (1060, 151.0)]);
+        assert!(snapshots_between(&conn, 0, 999).unwrap().is_empty());
+        assert_eq!(snapshot_value(ChartMetric::Holders, &snapshot(0, 0.0, 12)), Some(12.0));
+        assert_eq!(snapshot_value(ChartMetric::Price, &snapshot(0, 0.0, 12)), None);
+    }
+
+    #[test]
+    fn test_snapshots_after_pages_by_timestamp() {
+        let conn = test_db(&[snapshot(1000, 150.0, 10), snapshot(1060, 151.0, 10), snapshot(1120, 152.0, 12)]);
+        let timestamps = |page: Vec<MetricSnapshot>| page.iter().map(|s| s.timestamp).collect::<Vec<_>>();
+
+        assert_eq!(timestamps(snapshots_after(&conn, i64::MIN, 1120, 2).unwrap()), vec![1000, 1060]);
+        assert_eq!(timestamps(snapshots_after(&conn, 1060, 1120, 2).unwrap()), vec![1120]);
+        assert!(snapshots_after(&conn, 1000, 1059, 2).unwrap().is_empty());
+
+        let snap = snapshot(1000, 150.5, 10);
+        assert_eq!(MetricSnapshot::CSV_HEADER.matches(',').count(), snap.csv_row().matches(',').count());
+    }
+
+    #[test]
+    fn test_order_book_columns_are_added_and_stored() {
+        // A table from before the order book columns existed
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE metric_snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL UNIQUE,
+                tcr REAL NOT NULL,
+                supply REAL NOT NULL,
+                liquidity REAL NOT NULL,
+                holders INTEGER NOT NULL,
+                lend_apr REAL NOT NULL,
+                borrow_apr REAL NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO metric_snapshots (timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr)
+             VALUES (1000, 150.0, 1000.0, 50.0, 10, 4.0, 6.0)",
+            [],
+        )
+        .unwrap();
+        init_snapshot_table(&conn).unwrap();
+        init_snapshot_table(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO metric_snapshots
+             (timestamp, tcr, supply, liquidity, holders, lend_apr, borrow_apr,
+              best_lend_price, best_borrow_price, spread_bps)
+             VALUES (1060, 151.0, 1000.0, 50.0, 10, 4.0, 6.0, 0.97, 0.975, 50.0)",
+            [],
+        )
+        .unwrap();
+
+        let rows = snapshots_between(&conn, 0, 2000).unwrap();
+        let spreads: Vec<_> = rows.iter().map(|s| (s.timestamp, s.spread_bps)).collect();
+        assert_eq!(spreads, vec![(1000, None), (1060, Some(50.0))]);
+        assert_eq!(rows[1].best_borrow_price, Some(0.975));
+        assert_eq!(OrderBookData::spread_between(Some(0.97), None), None);
+        assert!(rows[0].csv_row().ends_with(",,,\n"));
+    }
+}
@@ -0,0 +1,31 @@
+//! Shared HTTP client for upstream APIs
+//!
+//! RPC, Blockscout, the subgraph and GeckoTerminal all go through one
+//! connection pool, so server functions reuse warm keep-alive connections
+//! instead of opening a fresh TLS session per client. Each upstream client
+//! still sets its own per-request timeout.
+
+use once_cell::sync::Lazy;
+use std::time::Duration;
+
+/// Per-request timeout for Blockscout, the subgraph and GeckoTerminal
+pub fn upstream_timeout() -> Duration {
+    Duration::from_secs(usdfc_core::config::config().upstream_timeout_secs)
+}
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(5))
+        // Backstop only; per-request timeouts override it
+        .timeout(Duration::from_secs(60))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(16)
+        .tcp_keepalive(Duration::from_secs(60))
+        .build()
+        .expect("failed to build shared HTTP client")
+});
+
+/// Handle to the process-wide client; clones share the same pool
+pub fn shared_client() -> reqwest::Client {
+    CLIENT.clone()
+}
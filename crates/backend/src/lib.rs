@@ -13,6 +13,27 @@ pub mod gecko;
 pub mod cache;
 pub mod circuit_breaker;
 pub mod historical;
+pub mod twap;
+pub mod volatility;
+pub mod fees;
+pub mod wallet;
+pub mod chart_image;
+pub mod alerts;
+pub mod webhooks;
+pub mod alert_events;
+pub mod address_notes;
+pub mod watchlist;
+pub mod auth;
+pub mod rate_limit;
+pub mod feeds;
+pub mod failover;
+pub mod sources;
+pub mod timing;
+pub mod http;
+pub mod metrics;
+pub mod log_redact;
+pub mod log_format;
+pub mod request_id;
 pub mod server_fn;
 pub mod fileserv;
 pub mod state;
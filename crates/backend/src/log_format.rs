@@ -0,0 +1,171 @@
+//! JSON log output
+//!
+//! With `USDFC_LOG_FORMAT=json` each event is written as one JSON object per
+//! line for log shippers (Loki, Datadog). Event fields and the fields of every
+//! enclosing span are flattened into the object, innermost winning, so a
+//! correlation id recorded on a request span lands on each line logged under it.
+//!
+//! `tracing_subscriber`'s built-in `.json()` formatter is not used: it needs
+//! the `json` feature and with it the `tracing-serde` crate, which is not in
+//! our dependency tree, and even with `flatten_event(true)` and
+//! `with_current_span(true)` it only flattens event fields. Span fields stay
+//! nested under a `span` key, so `request_id` would not sit at the top level
+//! where log shippers index it.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Event formatter writing one JSON object per line
+pub struct JsonFormat;
+
+/// Span field formatter storing span fields as a JSON object for [`JsonFormat`]
+pub struct JsonFields;
+
+/// Collects recorded fields into a JSON map, keeping numbers and booleans typed
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), Value::from(format!("{:?}", value)));
+    }
+}
+
+fn record_json<R: RecordFields>(fields: R) -> Map<String, Value> {
+    let mut visitor = JsonVisitor::default();
+    fields.record(&mut visitor);
+    visitor.0
+}
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(&self, mut writer: Writer<'writer>, fields: R) -> fmt::Result {
+        write!(writer, "{}", Value::Object(record_json(fields)))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &tracing::span::Record<'_>,
+    ) -> fmt::Result {
+        // Merge fields recorded after span creation into the stored object
+        let mut map = match serde_json::from_str(&current.fields) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        map.extend(record_json(fields));
+        current.fields = Value::Object(map).to_string();
+        Ok(())
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let metadata = event.metadata();
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let timestamp = chrono::DateTime::from_timestamp_millis(now_ms)
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+            .unwrap_or_default();
+
+        let mut line = Map::new();
+        line.insert("timestamp".to_string(), Value::from(timestamp));
+        line.insert("level".to_string(), Value::from(metadata.level().as_str()));
+        line.insert("target".to_string(), Value::from(metadata.target()));
+
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<FormattedFields<N>>() {
+                    if let Ok(Value::Object(map)) = serde_json::from_str(&fields.fields) {
+                        line.extend(map);
+                    }
+                }
+            }
+        }
+        line.extend(record_json(event));
+
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_line_includes_span_and_event_fields() {
+        let buf = SharedBuf::default();
+        let writer = buf.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .fmt_fields(JsonFields)
+            .event_format(JsonFormat)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request", request_id = "abc123", status = tracing::field::Empty);
+            let _guard = span.enter();
+            span.record("status", 502u64);
+            tracing::warn!(upstream = "rpc", attempt = 2u64, "RPC call failed");
+        });
+
+        let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let line: Value = serde_json::from_str(output.trim()).expect("one JSON object per line");
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["target"], module_path!());
+        assert_eq!(line["message"], "RPC call failed");
+        assert_eq!(line["upstream"], "rpc");
+        assert_eq!(line["attempt"], 2);
+        assert_eq!(line["request_id"], "abc123");
+        assert_eq!(line["status"], 502);
+    }
+}
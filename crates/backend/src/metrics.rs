@@ -0,0 +1,193 @@
+//! Prometheus metrics
+//!
+//! Process-wide counters for upstream requests, cache lookups and circuit
+//! breaker state, rendered in the Prometheus text exposition format for
+//! `/metrics`. Upstream requests are recorded by `timing::TimedSend`; cache
+//! and breaker figures are read from their own counters at scrape time.
+//!
+//! Metric and label names are part of the scrape contract - do not rename.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use usdfc_core::types::CacheStats;
+
+use crate::circuit_breaker::{CircuitState, UPSTREAM_BREAKER};
+
+/// Upstreams with request metrics; names match `timed_send` call sites
+pub const UPSTREAMS: [&str; 4] = ["rpc", "blockscout", "subgraph", "gecko"];
+
+/// Upper bounds of the latency histogram buckets in seconds (`+Inf` is implicit)
+const LATENCY_BUCKETS_SECS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0];
+
+/// Request counts and latency histogram for one upstream
+pub struct UpstreamStats {
+    successes: AtomicU64,
+    failures: AtomicU64,
+    /// Non-cumulative count per bucket; the last slot is `+Inf`
+    buckets: [AtomicU64; LATENCY_BUCKETS_SECS.len() + 1],
+    sum_micros: AtomicU64,
+}
+
+impl UpstreamStats {
+    pub const fn new() -> Self {
+        Self {
+            successes: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            buckets: [const { AtomicU64::new(0) }; LATENCY_BUCKETS_SECS.len() + 1],
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, elapsed: Duration, ok: bool) {
+        let counter = if ok { &self.successes } else { &self.failures };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        let secs = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS_SECS
+            .iter()
+            .position(|&le| secs <= le)
+            .unwrap_or(LATENCY_BUCKETS_SECS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Default for UpstreamStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static UPSTREAM_STATS: [UpstreamStats; UPSTREAMS.len()] = [const { UpstreamStats::new() }; UPSTREAMS.len()];
+
+/// Count an upstream request and its latency (unknown upstreams are ignored)
+pub fn record_upstream_request(upstream: &str, elapsed: Duration, ok: bool) {
+    if let Some(i) = UPSTREAMS.iter().position(|&u| u == upstream) {
+        UPSTREAM_STATS[i].record(elapsed, ok);
+    }
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Format a bucket bound the way Prometheus clients do (`1.0`, `0.05`)
+fn format_le(le: f64) -> String {
+    if le.fract() == 0.0 {
+        format!("{:.1}", le)
+    } else {
+        le.to_string()
+    }
+}
+
+/// Append request counters and latency histograms for `(upstream, stats)` pairs
+pub fn encode_upstreams<'a>(out: &mut String, upstreams: impl IntoIterator<Item = (&'a str, &'a UpstreamStats)> + Clone) {
+    write_header(out, "usdfc_upstream_requests_total", "counter", "Upstream HTTP requests by outcome.");
+    for (upstream, stats) in upstreams.clone() {
+        let _ = writeln!(out, "usdfc_upstream_requests_total{{upstream=\"{}\",outcome=\"success\"}} {}", upstream, stats.successes.load(Ordering::Relaxed));
+        let _ = writeln!(out, "usdfc_upstream_requests_total{{upstream=\"{}\",outcome=\"error\"}} {}", upstream, stats.failures.load(Ordering::Relaxed));
+    }
+
+    write_header(out, "usdfc_upstream_request_duration_seconds", "histogram", "Upstream HTTP request latency.");
+    for (upstream, stats) in upstreams {
+        let mut cumulative = 0;
+        for (i, bucket) in stats.buckets.iter().enumerate() {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let le = LATENCY_BUCKETS_SECS.get(i).map_or_else(|| "+Inf".to_string(), |&le| format_le(le));
+            let _ = writeln!(out, "usdfc_upstream_request_duration_seconds_bucket{{upstream=\"{}\",le=\"{}\"}} {}", upstream, le, cumulative);
+        }
+        let sum_secs = stats.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "usdfc_upstream_request_duration_seconds_sum{{upstream=\"{}\"}} {}", upstream, sum_secs);
+        let _ = writeln!(out, "usdfc_upstream_request_duration_seconds_count{{upstream=\"{}\"}} {}", upstream, cumulative);
+    }
+}
+
+/// Append cache hit and miss counters
+pub fn encode_caches(out: &mut String, caches: &[CacheStats]) {
+    write_header(out, "usdfc_cache_hits_total", "counter", "Server-side cache lookups served from cache.");
+    for cache in caches {
+        let _ = writeln!(out, "usdfc_cache_hits_total{{cache=\"{}\"}} {}", cache.name, cache.hits);
+    }
+    write_header(out, "usdfc_cache_misses_total", "counter", "Server-side cache lookups that missed or had expired.");
+    for cache in caches {
+        let _ = writeln!(out, "usdfc_cache_misses_total{{cache=\"{}\"}} {}", cache.name, cache.misses);
+    }
+}
+
+/// Append one-hot circuit breaker state gauges
+///
+/// Every known upstream is reported; one not yet seen by the breaker is closed.
+pub fn encode_circuits(out: &mut String, states: &[(String, CircuitState)]) {
+    write_header(out, "usdfc_circuit_breaker_state", "gauge", "Upstream circuit breaker state (1 for the current state).");
+    // Upstreams with per-endpoint circuits (`rpc:<url>`) are listed by endpoint
+    let has_circuit = |upstream: &str| {
+        states.iter().any(|(u, _)| u.split_once(':').map_or(u.as_str(), |(prefix, _)| prefix) == upstream)
+    };
+    let mut upstreams: Vec<&str> = UPSTREAMS.iter().copied().filter(|u| !has_circuit(u)).collect();
+    upstreams.extend(states.iter().map(|(u, _)| u.as_str()));
+
+    for upstream in upstreams {
+        let current = states
+            .iter()
+            .find(|(u, _)| u == upstream)
+            .map_or(CircuitState::Closed, |(_, s)| s.clone());
+        for (label, state) in [("closed", CircuitState::Closed), ("open", CircuitState::Open), ("half_open", CircuitState::HalfOpen)] {
+            let _ = writeln!(out, "usdfc_circuit_breaker_state{{upstream=\"{}\",state=\"{}\"}} {}", upstream, label, u8::from(current == state));
+        }
+    }
+}
+
+/// Render all metrics in the Prometheus text exposition format
+pub fn render() -> String {
+    let mut out = String::new();
+    encode_upstreams(&mut out, UPSTREAMS.iter().copied().zip(UPSTREAM_STATS.iter()));
+    encode_caches(&mut out, &crate::cache::caches::stats());
+    encode_circuits(&mut out, &UPSTREAM_BREAKER.states());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let stats = UpstreamStats::new();
+        stats.record(Duration::from_millis(40), true);
+        stats.record(Duration::from_millis(300), true);
+        stats.record(Duration::from_secs(60), false);
+
+        let mut out = String::new();
+        encode_upstreams(&mut out, [("rpc", &stats)]);
+
+        assert!(out.contains("usdfc_upstream_requests_total{upstream=\"rpc\",outcome=\"success\"} 2\n"));
+        assert!(out.contains("usdfc_upstream_requests_total{upstream=\"rpc\",outcome=\"error\"} 1\n"));
+        assert!(out.contains("usdfc_upstream_request_duration_seconds_bucket{upstream=\"rpc\",le=\"0.05\"} 1\n"));
+        assert!(out.contains("usdfc_upstream_request_duration_seconds_bucket{upstream=\"rpc\",le=\"0.5\"} 2\n"));
+        assert!(out.contains("usdfc_upstream_request_duration_seconds_bucket{upstream=\"rpc\",le=\"30.0\"} 2\n"));
+        assert!(out.contains("usdfc_upstream_request_duration_seconds_bucket{upstream=\"rpc\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("usdfc_upstream_request_duration_seconds_count{upstream=\"rpc\"} 3\n"));
+        assert!(out.contains("usdfc_upstream_request_duration_seconds_sum{upstream=\"rpc\"} 60.34\n"));
+    }
+
+    #[test]
+    fn test_circuit_states_are_one_hot() {
+        let mut out = String::new();
+        encode_circuits(&mut out, &[("gecko".to_string(), CircuitState::Open)]);
+
+        assert!(out.contains("usdfc_circuit_breaker_state{upstream=\"gecko\",state=\"open\"} 1\n"));
+        assert!(out.contains("usdfc_circuit_breaker_state{upstream=\"gecko\",state=\"closed\"} 0\n"));
+        // Upstreams without traffic yet report closed
+        assert!(out.contains("usdfc_circuit_breaker_state{upstream=\"rpc\",state=\"closed\"} 1\n"));
+        assert_eq!(out.matches("# TYPE").count(), 1);
+
+        // Per-endpoint circuits replace the upstream's own entry
+        let mut out = String::new();
+        encode_circuits(&mut out, &[("rpc:https://node.example".to_string(), CircuitState::Open)]);
+        assert!(out.contains("usdfc_circuit_breaker_state{upstream=\"rpc:https://node.example\",state=\"open\"} 1\n"));
+        assert!(!out.contains("upstream=\"rpc\""));
+    }
+}
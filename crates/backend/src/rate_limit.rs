@@ -0,0 +1,107 @@
+//! Per-client rate limiting for the public REST API
+//!
+//! Each client IP gets its own GCRA bucket (a token bucket without a refill
+//! task): up to `API_RATE_LIMIT_BURST` requests at once, refilled at
+//! `API_RATE_LIMIT_PER_MIN`. The client is the TCP peer, or with
+//! `TRUST_X_FORWARDED_FOR` the address the proxy appended to `X-Forwarded-For`.
+
+use axum::http::HeaderMap;
+use governor::clock::{Clock, DefaultClock};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use once_cell::sync::Lazy;
+use std::net::IpAddr;
+use std::num::NonZeroU32;
+use std::time::Duration;
+use usdfc_core::config::config;
+
+/// Limiter built from config, or None when `API_RATE_LIMIT_PER_MIN` is 0
+static LIMITER: Lazy<Option<DefaultKeyedRateLimiter<IpAddr>>> = Lazy::new(|| {
+    let cfg = config();
+    limiter(cfg.api_rate_limit_per_min, cfg.api_rate_limit_burst)
+});
+
+fn limiter(per_min: u32, burst: u32) -> Option<DefaultKeyedRateLimiter<IpAddr>> {
+    let per_min = NonZeroU32::new(per_min)?;
+    let burst = NonZeroU32::new(burst).unwrap_or(NonZeroU32::MIN);
+    Some(RateLimiter::keyed(Quota::per_minute(per_min).allow_burst(burst)))
+}
+
+fn check_with(limiter: &DefaultKeyedRateLimiter<IpAddr>, ip: IpAddr) -> Result<(), Duration> {
+    limiter
+        .check_key(&ip)
+        .map_err(|not_until| not_until.wait_time_from(DefaultClock::default().now()))
+}
+
+/// Take one request from `ip`'s bucket, or return how long until it refills
+pub fn check(ip: IpAddr) -> Result<(), Duration> {
+    match LIMITER.as_ref() {
+        Some(limiter) => check_with(limiter, ip),
+        None => Ok(()),
+    }
+}
+
+/// Whole seconds for a `Retry-After` header, never less than 1
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    wait.as_secs_f64().ceil().max(1.0) as u64
+}
+
+/// Address to rate-limit a request by
+///
+/// With `trust_forwarded` the last `X-Forwarded-For` entry wins, since that is
+/// the one the proxy added; earlier entries come from the client and can be
+/// forged. Falls back to the TCP peer.
+pub fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trust_forwarded: bool) -> Option<IpAddr> {
+    let forwarded = trust_forwarded
+        .then(|| headers.get_all("x-forwarded-for"))
+        .and_then(|values| values.iter().next_back()?.to_str().ok()?.rsplit(',').next()?.trim().parse().ok());
+    forwarded.or(peer)
+}
+
+/// Drop buckets that have refilled completely, every 60 seconds
+pub fn start_cleanup() {
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Some(limiter) = LIMITER.as_ref() {
+                limiter.retain_recent();
+                limiter.shrink_to_fit();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_burst_then_retry_after() {
+        let limiter = limiter(1, 2).unwrap();
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        assert!(check_with(&limiter, ip).is_ok());
+        assert!(check_with(&limiter, ip).is_ok());
+        let wait = check_with(&limiter, ip).unwrap_err();
+        assert!(wait > Duration::from_secs(50) && wait <= Duration::from_secs(60));
+        assert_eq!(retry_after_secs(wait), 60);
+
+        // Other clients have their own bucket
+        assert!(check_with(&limiter, "203.0.113.8".parse().unwrap()).is_ok());
+        assert!(super::limiter(0, 10).is_none());
+    }
+
+    #[test]
+    fn test_client_ip_uses_proxy_appended_address() {
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.1.1.1, 198.51.100.4"));
+
+        assert_eq!(client_ip(&headers, Some(peer), false), Some(peer));
+        assert_eq!(client_ip(&headers, Some(peer), true), Some("198.51.100.4".parse().unwrap()));
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("not-an-ip"));
+        assert_eq!(client_ip(&headers, Some(peer), true), Some(peer));
+        assert_eq!(client_ip(&HeaderMap::new(), None, true), None);
+    }
+}
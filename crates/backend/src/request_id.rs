@@ -0,0 +1,104 @@
+//! Request correlation ids
+//!
+//! The middleware in `main.rs` keeps a well-formed incoming `X-Request-Id` or
+//! generates one, writes it onto the request and the response, and logs the
+//! request inside a `request` span carrying it. Server functions run on
+//! leptos' own task pool, so `timing::timed` reads the id back from the request
+//! parts, opens its own span with it and tags failures with it.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use usdfc_core::error::with_request_id;
+
+/// Header carrying the correlation id, in both directions
+pub const HEADER: &str = "x-request-id";
+
+/// Longest client-supplied id that is kept
+const MAX_LEN: usize = 64;
+
+/// A random 16-hex-digit id
+pub fn generate() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    // RandomState is seeded per process; the counter keeps ids within it distinct
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}", hasher.finish())
+}
+
+/// The client's id if it is short and made of `[A-Za-z0-9._-]`, else a new one
+pub fn resolve(incoming: Option<&str>) -> String {
+    match incoming.map(str::trim) {
+        Some(id)
+            if !id.is_empty()
+                && id.len() <= MAX_LEN
+                && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-')) =>
+        {
+            id.to_string()
+        }
+        _ => generate(),
+    }
+}
+
+/// The id of the request the current server function is serving
+///
+/// Read from the request parts leptos provides; call before the first await.
+pub fn current() -> Option<String> {
+    leptos::use_context::<http::request::Parts>()?
+        .headers
+        .get(HEADER)?
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
+/// Server function output whose failure is logged and tagged with the request id
+pub trait RequestTagged {
+    fn tag_failure(self, fn_name: &str, request_id: Option<&str>) -> Self;
+}
+
+impl<T, E: std::fmt::Display> RequestTagged for Result<T, leptos::ServerFnError<E>> {
+    fn tag_failure(self, fn_name: &str, request_id: Option<&str>) -> Self {
+        self.map_err(|e| {
+            tracing::warn!(server_fn = fn_name, error = %e, "Server function failed");
+            match (e, request_id) {
+                (leptos::ServerFnError::ServerError(message), Some(id)) => {
+                    leptos::ServerFnError::ServerError(with_request_id(&message, id))
+                }
+                (e, _) => e,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_keeps_well_formed_ids() {
+        assert_eq!(resolve(Some("abc-123_x.y")), "abc-123_x.y");
+        assert_eq!(resolve(Some(" trace-1 ")), "trace-1");
+
+        for rejected in [None, Some(""), Some("has space"), Some("new\nline"), Some(&"a".repeat(65))] {
+            let id = resolve(rejected);
+            assert_eq!(id.len(), 16);
+            assert!(id.bytes().all(|b| b.is_ascii_hexdigit()));
+        }
+        assert_ne!(generate(), generate());
+    }
+
+    #[test]
+    fn test_failures_are_tagged() {
+        let failed: Result<(), leptos::ServerFnError> = Err(leptos::ServerFnError::ServerError("[not_found]".into()));
+        let tagged = failed.tag_failure("get_trove", Some("abc123"));
+        assert_eq!(
+            tagged.unwrap_err(),
+            leptos::ServerFnError::ServerError("[not_found] (request abc123)".into())
+        );
+
+        let ok: Result<u8, leptos::ServerFnError> = Ok(1);
+        assert_eq!(ok.tag_failure("get_trove", Some("abc123")), Ok(1));
+    }
+}
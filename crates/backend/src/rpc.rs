@@ -1,16 +1,177 @@
 use usdfc_core::config::config;
 use usdfc_core::error::{ApiError, ApiResult};
+use usdfc_core::types::{GasSample, NetworkGas, Provenance};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::time::Duration;
+use crate::failover;
+use crate::sources::ProtocolReads;
+use crate::timing::{TimedSend, UpstreamError};
+
+/// Troves requested per MultiTroveGetter call; larger pages risk the eth_call gas limit
+pub const TROVE_PAGE_SIZE: u32 = 100;
+
+/// totalSupply()
+const TOTAL_SUPPLY_SELECTOR: &str = "0x18160ddd";
+/// getEntireSystemColl()
+const ENTIRE_SYSTEM_COLL_SELECTOR: &str = "0x887105d3";
+/// getTroveOwnersCount()
+const TROVE_OWNERS_COUNT_SELECTOR: &str = "0x49eefeee";
+/// getTotalDebtTokenDeposits()
+const TOTAL_DEPOSITS_SELECTOR: &str = "0x0d9a6b35";
+/// getCompoundedDebtTokenDeposit(address)
+const COMPOUNDED_DEPOSIT_SELECTOR: &str = "0x0bdfe4e7";
+
+/// Parse a hex uint256 with 18 decimals into whole units
+fn parse_wei(hex: &str) -> ApiResult<Decimal> {
+    let value = u128::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|e| ApiError::RpcError(format!("Parse error: {}", e)))?;
+
+    // Convert from wei (18 decimals) - use from_i128 to avoid overflow
+    let wei = Decimal::from_i128_with_scale(value as i128, 0);
+    let divisor = Decimal::from_i128_with_scale(10_i128.pow(18), 0);
+    Ok(wei / divisor)
+}
+
+/// Parse a hex quantity into a u64
+fn parse_u64(hex: &str) -> ApiResult<u64> {
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|e| ApiError::RpcError(format!("Parse error: {}", e)))
+}
+
+/// The hex string in a JSON-RPC result
+fn result_hex(result: ApiResult<Value>) -> ApiResult<String> {
+    result?
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| ApiError::RpcError("Invalid result format".to_string()))
+}
+
+/// Map batched `protocol_reads_calls` results back to their reads
+fn protocol_reads_from_batch(results: Vec<ApiResult<Value>>) -> ProtocolReads {
+    let mut results = results.into_iter().map(result_hex);
+    let mut next = || {
+        results
+            .next()
+            .unwrap_or_else(|| Err(ApiError::RpcError("Missing response in batch".to_string())))
+    };
+    ProtocolReads {
+        total_supply: next().and_then(|h| parse_wei(&h)),
+        total_collateral: next().and_then(|h| parse_wei(&h)),
+        trove_owners_count: next().and_then(|h| parse_u64(&h)),
+        stability_pool_balance: next().and_then(|h| parse_wei(&h)),
+        block_number: next().and_then(|h| parse_u64(&h)),
+    }
+}
+
+/// Build `NetworkGas` from an `eth_feeHistory` result
+///
+/// `baseFeePerGas` has one more entry than `gasUsedRatio`: the base fee of
+/// the block after the newest one, which is reported as the current fee.
+fn network_gas_from_fee_history(result: &Value) -> ApiResult<NetworkGas> {
+    let invalid = |field: &str| ApiError::RpcError(format!("Invalid fee history: bad {}", field));
+    let oldest_block = result["oldestBlock"].as_str().ok_or_else(|| invalid("oldestBlock")).and_then(parse_u64)?;
+    let base_fees = result["baseFeePerGas"]
+        .as_array()
+        .ok_or_else(|| invalid("baseFeePerGas"))?
+        .iter()
+        .map(|fee| fee.as_str().ok_or_else(|| invalid("baseFeePerGas")).and_then(parse_u64))
+        .collect::<ApiResult<Vec<u64>>>()?;
+    let ratios = result["gasUsedRatio"]
+        .as_array()
+        .ok_or_else(|| invalid("gasUsedRatio"))?
+        .iter()
+        .map(|ratio| ratio.as_f64().ok_or_else(|| invalid("gasUsedRatio")))
+        .collect::<ApiResult<Vec<f64>>>()?;
+
+    let base_fee = *base_fees.last().ok_or_else(|| invalid("baseFeePerGas"))?;
+    let samples: Vec<GasSample> = base_fees
+        .iter()
+        .zip(&ratios)
+        .enumerate()
+        .map(|(i, (&base_fee, &gas_used_ratio))| GasSample {
+            block: oldest_block + i as u64,
+            base_fee,
+            gas_used_ratio,
+        })
+        .collect();
+    let congestion = (!samples.is_empty())
+        .then(|| samples.iter().map(|s| s.gas_used_ratio).sum::<f64>() / samples.len() as f64);
+
+    Ok(NetworkGas { base_fee, samples, congestion })
+}
 
 /// Simple JSON-RPC client for Ethereum-compatible chains
 #[derive(Clone)]
 pub struct RpcClient {
     client: reqwest::Client,
-    url: String,
-    fallback_urls: Vec<String>,
+    timeout: Duration,
+}
+
+/// Outcome of calling one RPC endpoint
+enum Attempt {
+    /// The endpoint answered; an RPC-level error such as a revert would be the same elsewhere
+    Answered(ApiResult<Value>),
+    /// Unreachable, timed out, 5xx, rate limited or circuit open - worth trying the next endpoint
+    Failed(ApiError),
+}
+
+/// Order batch responses by id, which is each request's index
+///
+/// Servers may answer a batch in any order. Requests without a response get
+/// an error; responses with an unknown id are ignored.
+fn results_by_id(count: usize, responses: Vec<JsonRpcResponse>) -> Vec<ApiResult<Value>> {
+    let mut results: Vec<ApiResult<Value>> = (0..count)
+        .map(|_| Err(ApiError::RpcError("Missing response in batch".to_string())))
+        .collect();
+    for response in responses {
+        let Some(slot) = results.get_mut(response.id as usize) else {
+            continue;
+        };
+        *slot = match (response.error, response.result) {
+            (Some(error), _) => Err(ApiError::RpcError(format!("RPC error {}: {}", error.code, error.message))),
+            (None, Some(result)) => Ok(result),
+            (None, None) => Err(ApiError::RpcError("No result in response".to_string())),
+        };
+    }
+    results
+}
+
+/// Try endpoints in failover order until one answers
+///
+/// Only an endpoint that answered is remembered as good. Returns the answer,
+/// or every endpoint's error when none answered.
+async fn call_in_order<'a, F, Fut>(endpoints: &'a failover::Endpoints, mut call: F) -> Result<ApiResult<Value>, Vec<ApiError>>
+where
+    F: FnMut(&'a str) -> Fut,
+    Fut: std::future::Future<Output = Attempt>,
+{
+    let ordered = endpoints.ordered();
+    let mut errors = Vec::with_capacity(ordered.len());
+    for (position, &(index, url)) in ordered.iter().enumerate() {
+        match call(url).await {
+            Attempt::Answered(result) => {
+                endpoints.mark_good(index);
+                return Ok(result);
+            }
+            Attempt::Failed(e) => {
+                match ordered.get(position + 1) {
+                    Some((_, next)) => tracing::warn!(
+                        upstream = "rpc",
+                        url,
+                        error = %e,
+                        "RPC endpoint {} failed, failing over to {}",
+                        url,
+                        next
+                    ),
+                    None => tracing::warn!(upstream = "rpc", url, error = %e, "RPC endpoint {} failed", url),
+                }
+                errors.push(e);
+            }
+        }
+    }
+    Err(errors)
 }
 
 #[derive(Serialize)]
@@ -40,17 +201,31 @@ struct JsonRpcError {
 impl RpcClient {
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(config().rpc_timeout_secs))
-                .build()
-                .expect("failed to build RPC HTTP client"),
-            url: config().rpc_url.clone(),
-            fallback_urls: config().rpc_fallback_urls.clone(),
+            client: crate::http::shared_client(),
+            timeout: Duration::from_secs(config().rpc_timeout_secs),
+        }
+    }
+
+    /// POST to an RPC endpoint on the shared client with the RPC timeout
+    fn post(&self, url: &str) -> reqwest::RequestBuilder {
+        self.client.post(url).timeout(self.timeout)
+    }
+
+    /// Provenance for data read now, with the chain head observed alongside it
+    pub fn provenance(&self, block_number: Option<u64>) -> Provenance {
+        Provenance {
+            source: "rpc".to_string(),
+            fetched_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+            block_number,
+            stale: false,
         }
     }
 
     /// Try a single RPC URL with retries
-    async fn call_with_url(&self, url: &str, request: &JsonRpcRequest) -> ApiResult<Value> {
+    async fn call_with_url(&self, url: &str, request: &JsonRpcRequest) -> Attempt {
         let max_retries = config().rpc_retry_count;
         let mut last_error = None;
 
@@ -59,6 +234,9 @@ impl RpcClient {
             if attempt > 0 {
                 let backoff_ms = 100 * (1 << (attempt - 1));
                 tracing::warn!(
+                    upstream = "rpc",
+                    url,
+                    method = %request.method,
                     "RPC retry attempt {}/{} for {} on {} after {}ms backoff",
                     attempt,
                     max_retries,
@@ -70,13 +248,18 @@ impl RpcClient {
             }
 
             let response = match self
-                .client
                 .post(url)
                 .json(&request)
-                .send()
+                .timed_send_to("rpc", url)
                 .await
             {
                 Ok(r) => r,
+                // Retrying while the circuit is open only fails fast again
+                Err(e @ UpstreamError::CircuitOpen(_)) => return Attempt::Failed(ApiError::RpcError(e.to_string())),
+                Err(e) if e.is_timeout() => {
+                    last_error = Some(ApiError::Timeout { duration: self.timeout });
+                    continue;
+                }
                 Err(e) => {
                     last_error = Some(ApiError::RpcError(format!("HTTP error: {}", e)));
                     continue; // Retry on network errors
@@ -89,13 +272,17 @@ impl RpcClient {
                     .text()
                     .await
                     .unwrap_or_else(|_| "failed to read body".to_string());
+                let error = ApiError::RpcError(format!("HTTP {}: {}", status, body));
 
-                // Retry on 5xx server errors, fail immediately on 4xx client errors
+                // Retry on 5xx server errors, fail over straight away when rate
+                // limited, and fail immediately on other 4xx client errors
                 if status.is_server_error() {
-                    last_error = Some(ApiError::RpcError(format!("HTTP {}: {}", status, body)));
+                    last_error = Some(error);
                     continue;
+                } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    return Attempt::Failed(error);
                 } else {
-                    return Err(ApiError::RpcError(format!("HTTP {}: {}", status, body)));
+                    return Attempt::Answered(Err(error));
                 }
             }
 
@@ -109,23 +296,28 @@ impl RpcClient {
 
             if let Some(error) = rpc_response.error {
                 // RPC-level errors (contract reverts, etc.) should not retry
-                return Err(ApiError::RpcError(format!(
+                return Attempt::Answered(Err(ApiError::RpcError(format!(
                     "RPC error {}: {}",
                     error.code, error.message
-                )));
+                ))));
             }
 
             // Success
-            return rpc_response
-                .result
-                .ok_or_else(|| ApiError::RpcError("No result in response".to_string()));
+            return Attempt::Answered(
+                rpc_response
+                    .result
+                    .ok_or_else(|| ApiError::RpcError("No result in response".to_string())),
+            );
         }
 
         // All retries exhausted for this URL
-        Err(last_error.unwrap_or_else(|| ApiError::RpcError("All retries failed".to_string())))
+        Attempt::Failed(last_error.unwrap_or_else(|| ApiError::RpcError("All retries failed".to_string())))
     }
 
-    /// Make a JSON-RPC call with retry logic, exponential backoff, and fallback URLs
+    /// Make a JSON-RPC call with retry logic, exponential backoff, and failover
+    ///
+    /// Endpoints are tried from the last one that answered (see `failover`);
+    /// only endpoint failures move on to the next.
     async fn call(&self, method: &str, params: Vec<Value>) -> ApiResult<Value> {
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
@@ -134,34 +326,88 @@ impl RpcClient {
             id: 1,
         };
 
-        // Try primary URL first
-        match self.call_with_url(&self.url, &request).await {
-            Ok(result) => return Ok(result),
-            Err(e) => {
-                tracing::warn!("Primary RPC URL {} failed: {}. Trying fallbacks...", self.url, e);
-            }
+        let errors = match call_in_order(failover::rpc(), |url| self.call_with_url(url, &request)).await {
+            Ok(result) => return result,
+            Err(errors) => errors,
+        };
+
+        // All URLs exhausted; reported as a timeout only if every endpoint timed out
+        if errors.iter().all(|e| matches!(e, ApiError::Timeout { .. })) {
+            return Err(ApiError::Timeout { duration: self.timeout });
         }
+        Err(ApiError::RpcError(format!(
+            "All RPC endpoints failed (tried {} URLs)",
+            errors.len()
+        )))
+    }
 
-        // Try fallback URLs
-        for fallback_url in &self.fallback_urls {
-            tracing::info!("Trying fallback RPC URL: {}", fallback_url);
-            match self.call_with_url(fallback_url, &request).await {
-                Ok(result) => {
-                    tracing::info!("Fallback RPC URL {} succeeded", fallback_url);
-                    return Ok(result);
-                }
-                Err(e) => {
-                    tracing::warn!("Fallback RPC URL {} failed: {}", fallback_url, e);
-                    continue;
+    /// Send one JSON-RPC batch request to a single URL (no retries)
+    ///
+    /// Results are returned in request order, matched by id.
+    async fn batch_with_url(&self, url: &str, requests: &[JsonRpcRequest]) -> ApiResult<Vec<ApiResult<Value>>> {
+        let response = self
+            .post(url)
+            .json(requests)
+            .timed_send_to("rpc", url)
+            .await
+            .map_err(|e| e.into_api_error(self.timeout, |e| ApiError::RpcError(format!("HTTP error: {}", e))))?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::RpcError(format!("HTTP {}", response.status())));
+        }
+
+        // Endpoints without batch support answer with a single error object,
+        // which fails to parse as an array
+        let responses: Vec<JsonRpcResponse> = response
+            .json()
+            .await
+            .map_err(|e| ApiError::RpcError(format!("Batch parse error: {}", e)))?;
+
+        Ok(results_by_id(requests.len(), responses))
+    }
+
+    /// Make several JSON-RPC calls in a single round trip
+    ///
+    /// Takes `(method, params)` pairs and returns one result per call, in
+    /// order. Tries each endpoint in failover order with one batch request.
+    /// If no endpoint accepts the batch, falls back to sequential calls (with
+    /// the usual retries). Per-call errors are returned in place, so one
+    /// failing call does not fail the others.
+    pub async fn batch_call(&self, calls: Vec<(&str, Vec<Value>)>) -> Vec<ApiResult<Value>> {
+        let requests: Vec<JsonRpcRequest> = calls
+            .into_iter()
+            .enumerate()
+            .map(|(id, (method, params))| JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                method: method.to_string(),
+                params,
+                id: id as u64,
+            })
+            .collect();
+
+        let endpoints = failover::rpc();
+        for (index, url) in endpoints.ordered() {
+            match self.batch_with_url(url, &requests).await {
+                Ok(results) => {
+                    endpoints.mark_good(index);
+                    return results;
                 }
+                Err(e) => tracing::warn!(
+                    upstream = "rpc",
+                    url = %url,
+                    error = %e,
+                    "RPC batch of {} calls failed",
+                    requests.len()
+                ),
             }
         }
 
-        // All URLs exhausted
-        Err(ApiError::RpcError(format!(
-            "All RPC endpoints failed (tried {} URLs)",
-            1 + self.fallback_urls.len()
-        )))
+        tracing::warn!(upstream = "rpc", "RPC batching unavailable, making {} calls sequentially", requests.len());
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            results.push(self.call(&request.method, request.params).await);
+        }
+        results
     }
 
     /// Call a contract method (eth_call)
@@ -183,42 +429,20 @@ impl RpcClient {
 
     /// Get total supply of USDFC token
     pub async fn get_total_supply(&self) -> ApiResult<Decimal> {
-        // totalSupply() function signature: 0x18160ddd
-        let data = "0x18160ddd";
-        let result = self.eth_call(&config().usdfc_token, data).await?;
-        
-        // Parse hex result to decimal
-        let value = u128::from_str_radix(result.trim_start_matches("0x"), 16)
-            .map_err(|e| ApiError::RpcError(format!("Parse error: {}", e)))?;
-        
-        // Convert from wei (18 decimals) to USDFC - use from_i128 to avoid overflow
-        let wei = Decimal::from_i128_with_scale(value as i128, 0);
-        let divisor = Decimal::from_i128_with_scale(10_i128.pow(18), 0);
-        Ok(wei / divisor)
+        let result = self.eth_call(&config().usdfc_token, TOTAL_SUPPLY_SELECTOR).await?;
+        parse_wei(&result)
     }
 
     /// Get total collateral in system
     pub async fn get_total_collateral(&self) -> ApiResult<Decimal> {
-        // getEntireSystemColl() function signature: 0x887105d3
-        let data = "0x887105d3";
-        let result = self.eth_call(&config().trove_manager, data).await?;
-        
-        let value = u128::from_str_radix(result.trim_start_matches("0x"), 16)
-            .map_err(|e| ApiError::RpcError(format!("Parse error: {}", e)))?;
-        
-        let wei = Decimal::from_i128_with_scale(value as i128, 0);
-        let divisor = Decimal::from_i128_with_scale(10_i128.pow(18), 0);
-        Ok(wei / divisor)
+        let result = self.eth_call(&config().trove_manager, ENTIRE_SYSTEM_COLL_SELECTOR).await?;
+        parse_wei(&result)
     }
 
     /// Get number of active troves
     pub async fn get_trove_owners_count(&self) -> ApiResult<u64> {
-        // getTroveOwnersCount() function signature: 0x49eefeee
-        let data = "0x49eefeee";
-        let result = self.eth_call(&config().trove_manager, data).await?;
-        
-        u64::from_str_radix(result.trim_start_matches("0x"), 16)
-            .map_err(|e| ApiError::RpcError(format!("Parse error: {}", e)))
+        let result = self.eth_call(&config().trove_manager, TROVE_OWNERS_COUNT_SELECTOR).await?;
+        parse_u64(&result)
     }
 
     /// Get FIL price from oracle
@@ -238,16 +462,27 @@ impl RpcClient {
 
     /// Get stability pool balance
     pub async fn get_stability_pool_balance(&self) -> ApiResult<Decimal> {
-        // getTotalDebtTokenDeposits() function signature: 0x0d9a6b35
-        let data = "0x0d9a6b35";
-        let result = self.eth_call(&config().stability_pool, data).await?;
-
-        let value = u128::from_str_radix(result.trim_start_matches("0x"), 16)
-            .map_err(|e| ApiError::RpcError(format!("Parse error: {}", e)))?;
+        let result = self.eth_call(&config().stability_pool, TOTAL_DEPOSITS_SELECTOR).await?;
+        parse_wei(&result)
+    }
 
-        let wei = Decimal::from_i128_with_scale(value as i128, 0);
-        let divisor = Decimal::from_i128_with_scale(10_i128.pow(18), 0);
-        Ok(wei / divisor)
+    /// Current compounded stability pool deposit of each depositor, in one batch
+    ///
+    /// Results are in `depositors` order, each with its own error.
+    pub async fn get_stability_deposits(&self, depositors: &[String]) -> Vec<ApiResult<Decimal>> {
+        let pool = &config().stability_pool;
+        let calls = depositors
+            .iter()
+            .map(|depositor| {
+                let data = format!("{}{:0>64}", COMPOUNDED_DEPOSIT_SELECTOR, depositor.trim_start_matches("0x"));
+                ("eth_call", vec![json!({ "to": pool, "data": data }), json!("latest")])
+            })
+            .collect();
+        self.batch_call(calls)
+            .await
+            .into_iter()
+            .map(|result| result_hex(result).and_then(|h| parse_wei(&h)))
+            .collect()
     }
 
     /// Get active pool collateral (FIL) - used for historical TCR calculation
@@ -270,49 +505,22 @@ impl RpcClient {
     /// getEntireSystemDebt() function. Instead, we aggregate individual trove debts
     /// using MultiTroveGetter, which provides 100% accurate real-time debt data.
     pub async fn get_total_debt(&self) -> ApiResult<Decimal> {
-        let mut total_debt = Decimal::ZERO;
-        let batch_size = 100;
-        let mut start_idx = 0;
-
-        loop {
-            let troves = self.get_multiple_sorted_troves(start_idx, batch_size).await?;
-
-            if troves.is_empty() {
-                break;
-            }
-
-            // Sum up debt from all troves in this batch
-            for trove in &troves {
-                total_debt += trove.debt;
-            }
-
-            // If we got fewer troves than requested, we've reached the end
-            if (troves.len() as u32) < batch_size {
-                break;
-            }
-
-            start_idx += batch_size as i32;
-        }
-
-        Ok(total_debt)
+        let trove_count = self.get_trove_owners_count().await?;
+        let troves = self.get_all_sorted_troves(trove_count as u32).await?;
+        Ok(troves.iter().map(|t| t.debt).sum())
     }
 
     /// Calculate TCR (Total Collateral Ratio)
     pub async fn get_tcr(&self) -> ApiResult<Decimal> {
         // TCR = (total_collateral * fil_price) / total_debt * 100
-        let total_debt = self.get_total_debt().await?;
-        let total_collateral = self.get_total_collateral().await?;
-        let fil_price = self.get_fil_price().await?;
-
-        if total_debt.is_zero() {
-            // No debt means infinite collateralization - return a very high TCR
-            return Ok(Decimal::new(999999, 0));
-        }
-
-        let collateral_value = total_collateral * fil_price;
-        let tcr = (collateral_value / total_debt) * Decimal::new(100, 0);
-
-        Ok(tcr)
+        let (total_debt, total_collateral, fil_price) = tokio::join!(
+            self.get_total_debt(),
+            self.get_total_collateral(),
+            self.get_fil_price()
+        );
+        let (total_debt, total_collateral, fil_price) = (total_debt?, total_collateral?, fil_price?);
+
+        Ok(crate::sources::compute_tcr(total_collateral, total_debt, fil_price))
     }
 
     /// Get current block number
@@ -327,33 +535,107 @@ impl RpcClient {
             .map_err(|e| ApiError::RpcError(format!("Parse block number: {}", e)))
     }
 
+    /// Current base fee and the base fee and gas usage of the last `blocks` blocks
+    pub async fn get_network_gas(&self, blocks: u32) -> ApiResult<NetworkGas> {
+        let result = self
+            .call("eth_feeHistory", vec![json!(format!("0x{:x}", blocks)), json!("latest"), json!([])])
+            .await?;
+        network_gas_from_fee_history(&result)
+    }
+
+    /// Read supply, collateral, trove count, stability pool balance and block
+    /// number in one batched round trip, each with its own result
+    pub async fn get_protocol_reads(&self) -> ProtocolReads {
+        let cfg = config();
+        let eth_call = |to: &str, data: &str| ("eth_call", vec![json!({ "to": to, "data": data }), json!("latest")]);
+        let calls = vec![
+            eth_call(&cfg.usdfc_token, TOTAL_SUPPLY_SELECTOR),
+            eth_call(&cfg.trove_manager, ENTIRE_SYSTEM_COLL_SELECTOR),
+            eth_call(&cfg.trove_manager, TROVE_OWNERS_COUNT_SELECTOR),
+            eth_call(&cfg.stability_pool, TOTAL_DEPOSITS_SELECTOR),
+            ("eth_blockNumber", vec![]),
+        ];
+        protocol_reads_from_batch(self.batch_call(calls).await)
+    }
+
     /// Get multiple sorted troves via MultiTroveGetter contract
     pub async fn get_multiple_sorted_troves(&self, start_idx: i32, count: u32) -> ApiResult<Vec<TroveData>> {
-        // Function: getMultipleSortedTroves(int256,uint256)
-        // Selector: 0xb90bce45
-        let selector = "0xb90bce45";
-        
-        // Encode parameters: int256 + uint256 (each 32 bytes)
-        let start_hex = if start_idx >= 0 {
-            format!("{:064x}", start_idx as u64)
-        } else {
-            // 256-bit two's complement: for negative n, result is 2^256 + n
-            // Since we can't represent 2^256, we use the fact that for display,
-            // -1 is all 1s (64 f's), -2 is 64 f's minus 1, etc.
-            let neg_val = start_idx as i64;  // sign-extend to i64
-            // For the lower 64 bits in hex
-            let lower = neg_val as u64;
-            // Upper 192 bits are all 1s for negative numbers
-            format!("ffffffffffffffffffffffffffffffffffffffffffffffff{:016x}", lower)
-        };
-        let count_hex = format!("{:064x}", count);
-        
-        let data = format!("{}{}{}", selector, start_hex, count_hex);
+        let data = encode_sorted_troves_call(start_idx, count);
         let result = self.eth_call(&config().multi_trove_getter, &data).await?;
-        
+
         // Parse response: dynamic array of CombinedTroveData structs
         parse_trove_response(&result)
     }
+
+    /// Get up to `count` sorted troves, one MultiTroveGetter page per call,
+    /// with all pages sent in a single batched round trip
+    ///
+    /// Any failed page fails the whole fetch, since callers sum over the result.
+    pub async fn get_sorted_troves_batched(&self, count: u32) -> ApiResult<Vec<TroveData>> {
+        let to = &config().multi_trove_getter;
+        let calls: Vec<(&str, Vec<Value>)> = (0..count.div_ceil(TROVE_PAGE_SIZE))
+            .map(|page| {
+                let start_idx = page * TROVE_PAGE_SIZE;
+                let page_count = TROVE_PAGE_SIZE.min(count - start_idx);
+                let data = encode_sorted_troves_call(start_idx as i32, page_count);
+                ("eth_call", vec![json!({ "to": to, "data": data }), json!("latest")])
+            })
+            .collect();
+
+        let mut troves = Vec::with_capacity(count as usize);
+        for result in self.batch_call(calls).await {
+            let hex = result?
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| ApiError::RpcError("Invalid result format".to_string()))?;
+            troves.extend(parse_trove_response(&hex)?);
+        }
+        Ok(troves)
+    }
+
+    /// Get every sorted trove, starting from an expected count
+    ///
+    /// Pages for `expected` troves are batched; if they all come back full
+    /// (troves opened since the count was read), the rest is paged sequentially.
+    pub async fn get_all_sorted_troves(&self, expected: u32) -> ApiResult<Vec<TroveData>> {
+        let mut troves = self.get_sorted_troves_batched(expected).await?;
+        if (troves.len() as u32) < expected {
+            return Ok(troves);
+        }
+
+        loop {
+            let page = self.get_multiple_sorted_troves(troves.len() as i32, TROVE_PAGE_SIZE).await?;
+            let done = (page.len() as u32) < TROVE_PAGE_SIZE;
+            troves.extend(page);
+            if done {
+                return Ok(troves);
+            }
+        }
+    }
+}
+
+/// Encode a getMultipleSortedTroves(int256,uint256) call
+fn encode_sorted_troves_call(start_idx: i32, count: u32) -> String {
+    // Function: getMultipleSortedTroves(int256,uint256)
+    // Selector: 0xb90bce45
+    let selector = "0xb90bce45";
+    
+    // Encode parameters: int256 + uint256 (each 32 bytes)
+    let start_hex = if start_idx >= 0 {
+        format!("{:064x}", start_idx as u64)
+    } else {
+        // 256-bit two's complement: for negative n, result is 2^256 + n
+        // Since we can't represent 2^256, we use the fact that for display,
+        // -1 is all 1s (64 f's), -2 is 64 f's minus 1, etc.
+        let neg_val = start_idx as i64;  // sign-extend to i64
+        // For the lower 64 bits in hex
+        let lower = neg_val as u64;
+        // Upper 192 bits are all 1s for negative numbers
+        format!("ffffffffffffffffffffffffffffffffffffffffffffffff{:016x}", lower)
+    };
+    let count_hex = format!("{:064x}", count);
+    
+    format!("{}{}{}", selector, start_hex, count_hex)
 }
 
 /// Trove data from MultiTroveGetter
@@ -433,3 +715,115 @@ impl Default for RpcClient {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Answer every JSON-RPC request with `result` on a local port, counting requests
+    async fn stub_rpc(result: &'static str, hits: Arc<AtomicUsize>) -> String {
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(move |axum::Json(request): axum::Json<Value>| async move {
+                hits.fetch_add(1, Ordering::Relaxed);
+                axum::Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_failover_continues_past_open_circuit() {
+        use crate::circuit_breaker::{endpoint_key, CircuitState, UPSTREAM_BREAKER};
+
+        let (primary_hits, fallback_hits) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let primary = stub_rpc("0x1", primary_hits.clone()).await;
+        let fallback = stub_rpc("0x2", fallback_hits.clone()).await;
+
+        // No other test in this crate loads the global config
+        dotenvy::from_filename(concat!(env!("CARGO_MANIFEST_DIR"), "/../../.env.example")).ok();
+        std::env::set_var("RPC_URL", &primary);
+        std::env::set_var("RPC_FALLBACK_URLS", &fallback);
+        std::env::set_var("RPC_RETRY_COUNT", "0");
+
+        let (primary_circuit, fallback_circuit) = (endpoint_key("rpc", &primary), endpoint_key("rpc", &fallback));
+        for _ in 0..config().circuit_failure_threshold {
+            UPSTREAM_BREAKER.record_failure(&primary_circuit);
+        }
+        assert_eq!(UPSTREAM_BREAKER.get_state(&primary_circuit), CircuitState::Open);
+
+        // The primary fails fast without a request; the fallback has its own
+        // circuit, answers and is remembered
+        let rpc = RpcClient::new();
+        assert_eq!(rpc.call("eth_blockNumber", vec![]).await.unwrap(), json!("0x2"));
+        assert_eq!(primary_hits.load(Ordering::Relaxed), 0);
+        assert_eq!(fallback_hits.load(Ordering::Relaxed), 1);
+        assert_eq!(UPSTREAM_BREAKER.get_state(&fallback_circuit), CircuitState::Closed);
+        assert_eq!(failover::rpc().ordered()[0], (1, fallback.as_str()));
+
+        // Every circuit open: the call fails without sending anything
+        for _ in 0..config().circuit_failure_threshold {
+            UPSTREAM_BREAKER.record_failure(&fallback_circuit);
+        }
+        assert!(rpc.call("eth_blockNumber", vec![]).await.is_err());
+        assert_eq!(primary_hits.load(Ordering::Relaxed), 0);
+        assert_eq!(fallback_hits.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_batch_responses_matched_by_id() {
+        let responses: Vec<JsonRpcResponse> = serde_json::from_value(json!([
+            { "jsonrpc": "2.0", "id": 3, "result": "0x3" },
+            { "jsonrpc": "2.0", "id": 0, "result": "0x0" },
+            { "jsonrpc": "2.0", "id": 9, "result": "0x9" },
+            { "jsonrpc": "2.0", "id": 2, "error": { "code": 3, "message": "execution reverted" } },
+        ]))
+        .unwrap();
+        let results = results_by_id(4, responses);
+
+        assert_eq!(results[0].as_ref().unwrap(), &json!("0x0"));
+        assert!(results[1].as_ref().unwrap_err().to_string().contains("Missing response"));
+        assert!(results[2].as_ref().unwrap_err().to_string().contains("execution reverted"));
+        assert_eq!(results[3].as_ref().unwrap(), &json!("0x3"));
+    }
+
+    #[test]
+    fn test_protocol_reads_keep_per_call_errors() {
+        let one_token = json!("0xde0b6b3a7640000"); // 1e18
+        let results = vec![
+            Ok(one_token.clone()),
+            Err(ApiError::RpcError("execution reverted".to_string())),
+            Ok(json!("0x2a")),
+            Ok(one_token),
+        ];
+        let reads = protocol_reads_from_batch(results);
+
+        assert_eq!(reads.total_supply.unwrap(), Decimal::ONE);
+        assert!(reads.total_collateral.is_err());
+        assert_eq!(reads.trove_owners_count.unwrap(), 42);
+        assert_eq!(reads.stability_pool_balance.unwrap(), Decimal::ONE);
+        // A short batch response fails only the missing reads
+        assert!(reads.block_number.is_err());
+    }
+
+    #[test]
+    fn test_network_gas_from_fee_history() {
+        let result = json!({
+            "oldestBlock": "0x10",
+            "baseFeePerGas": ["0x64", "0xc8", "0x12c"],
+            "gasUsedRatio": [0.25, 0.75],
+        });
+        let gas = network_gas_from_fee_history(&result).unwrap();
+
+        assert_eq!(gas.base_fee, 300);
+        assert_eq!(gas.samples.len(), 2);
+        assert_eq!((gas.samples[1].block, gas.samples[1].base_fee), (17, 200));
+        assert_eq!(gas.congestion, Some(0.5));
+        assert!(network_gas_from_fee_history(&json!({ "oldestBlock": "0x10" })).is_err());
+    }
+}
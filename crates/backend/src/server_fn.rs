@@ -6,8 +6,6 @@
 use leptos::*;
 use leptos::server_fn::error::NoCustomError;
 use usdfc_core::types::*;
-use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
 
 // Re-export chart types for server functions
 pub use usdfc_core::types::{ChartResolution, ChartLookback, ChartDataResponse, TVCandle};
@@ -37,32 +35,42 @@ use usdfc_core::types::*;
         let rpc = RpcClient::new();
 
         // Make parallel RPC calls for better performance
-        let (total_supply, total_collateral, active_troves, tcr, stability_pool_balance) = tokio::join!(
+        let (total_supply, total_collateral, active_troves, tcr, stability_pool_balance, fil_price, block_number) = tokio::join!(
             rpc.get_total_supply(),
             rpc.get_total_collateral(),
             rpc.get_trove_owners_count(),
             rpc.get_tcr(),
-            rpc.get_stability_pool_balance()
+            rpc.get_stability_pool_balance(),
+            rpc.get_fil_price(),
+            rpc.get_block_number()
         );
 
-        let total_supply = total_supply.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let total_collateral = total_collateral.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let active_troves = active_troves.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let tcr = tcr.map_err(|e| SfnError::ServerError(e.to_string()))?;
-        let stability_pool_balance = stability_pool_balance.map_err(|e| SfnError::ServerError(e.to_string()))?;
+        // Each value fails on its own; only a complete outage fails the response
+        if let (Err(e), Err(_), Err(_), Err(_), Err(_)) =
+            (&total_supply, &total_collateral, &active_troves, &tcr, &stability_pool_balance)
+        {
+            return Err(SfnError::ServerError(e.to_string()));
+        }
+        let total_supply = total_supply.ok();
+        let total_collateral = total_collateral.ok();
+        let stability_pool_balance = stability_pool_balance.ok();
 
         // Calculate actual circulating supply: total supply minus stability pool deposits
         // USDFC in the stability pool is locked and not actively circulating
-        let circulating_supply = total_supply - stability_pool_balance;
+        let circulating_supply = total_supply.zip(stability_pool_balance).map(|(supply, pool)| supply - pool);
+
+        // The USD value needs the oracle price; without it only FIL is reported
+        let collateral_usd = total_collateral.and_then(|coll| ProtocolMetrics::collateral_value_usd(coll, fil_price.ok()));
 
         let metrics = ProtocolMetrics {
             total_supply,
             circulating_supply,
-            total_collateral,
-            active_troves,
-            tcr,
+            collateral_fil: total_collateral,
+            collateral_usd,
+            active_troves: active_troves.ok(),
+            tcr: tcr.ok(),
             stability_pool_balance,
-            treasury_balance: stability_pool_balance, // Non-circulating = locked in stability pool
+            provenance: rpc.provenance(block_number.ok()),
         };
 
         // Store in cache
@@ -82,8 +90,14 @@ use usdfc_core::types::*;
 // ============================================================================
 
 /// Get recent transactions from Blockscout
+///
+/// When `highlight_since` is set, transactions newer than that Unix timestamp
+/// are tagged with `is_new`.
 #[server(GetRecentTransactions, "/api")]
-pub async fn get_recent_transactions(limit: Option<u32>) -> Result<Vec<Transaction>, ServerFnError> {
+pub async fn get_recent_transactions(
+    limit: Option<u32>,
+    highlight_since: Option<u64>,
+) -> Result<Vec<Transaction>, ServerFnError> {
     let limit = limit.unwrap_or(50); // Default to 50 if not specified
 
     #[cfg(feature = "ssr")]
@@ -93,17 +107,25 @@ pub async fn get_recent_transactions(limit: Option<u32>) -> Result<Vec<Transacti
 
         // Check cache first (10s TTL for recent transactions)
         let cache_key = format!("recent_tx_{}", limit);
-        if let Some(cached) = caches::RECENT_TRANSACTIONS.get(&cache_key) {
-            return Ok(cached);
-        }
-
-        let blockscout = BlockscoutClient::new();
-        // Pass None for max_pages to use default (100 pages = 5000 items)
-        let transactions = blockscout.get_recent_transfers(limit, None).await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
+        let mut transactions = match caches::RECENT_TRANSACTIONS.get(&cache_key) {
+            Some(cached) => cached,
+            None => {
+                let blockscout = BlockscoutClient::new();
+                // Pass None for max_pages to use default (100 pages = 5000 items)
+                let transactions = blockscout.get_recent_transfers(limit, None).await
+                    .map_err(|e| SfnError::ServerError(e.to_string()))?;
+
+                // Store in cache
+                caches::RECENT_TRANSACTIONS.set(cache_key, transactions.clone());
+                transactions
+            }
+        };
 
-        // Store in cache
-        caches::RECENT_TRANSACTIONS.set(cache_key, transactions.clone());
+        if let Some(since) = highlight_since {
+            for tx in transactions.iter_mut() {
+                tx.is_new = tx.timestamp > since;
+            }
+        }
 
         Ok(transactions)
     }
@@ -124,7 +146,7 @@ pub async fn get_recent_transactions(limit: Option<u32>) -> Result<Vec<Transacti
 pub async fn get_troves(limit: Option<u32>, _offset: Option<u32>) -> Result<Vec<Trove>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use crate::rpc::RpcClient;
+        use crate::sources::{fetch_troves, DataSources};
         use crate::cache::caches;
 
         let limit = limit.unwrap_or(100).min(500); // Default 100, max 500
@@ -135,58 +157,11 @@ pub async fn get_troves(limit: Option<u32>, _offset: Option<u32>) -> Result<Vec<
             return Ok(cached);
         }
 
-        let rpc = RpcClient::new();
-
-        // Get troves data - propagate errors to UI for proper error handling
-        let troves_data = rpc.get_multiple_sorted_troves(0, limit).await
-            .map_err(|e| {
-                tracing::error!("RPC error fetching troves: {}", e);
-                ServerFnError::<NoCustomError>::ServerError(format!("Failed to fetch troves: {}", e))
-            })?;
-
-        if troves_data.is_empty() {
-            return Ok(vec![]); // Empty is valid - no troves exist
-        }
-
-        // Get FIL price - propagate errors to UI for proper error handling
-        let fil_price = rpc.get_fil_price().await
-            .map_err(|e| {
-                tracing::error!("RPC error fetching FIL price: {}", e);
-                ServerFnError::<NoCustomError>::ServerError(format!("Failed to fetch FIL price: {}", e))
-            })?;
-
-        if fil_price.is_zero() {
-            tracing::error!("FIL price is zero - invalid data");
-            return Err(ServerFnError::<NoCustomError>::ServerError("FIL price is zero".to_string()));
-        }
-
-        // Convert to Trove type with ICR calculation
-        let troves: Vec<Trove> = troves_data
-            .iter()
-            .map(|t| {
-                let icr = if t.debt.is_zero() {
-                    Decimal::new(10000, 0)
-                } else {
-                    (t.coll * fil_price) / t.debt * Decimal::new(100, 0)
-                };
-
-                let status = if icr < Decimal::new(115, 0) {
-                    TroveStatus::Critical
-                } else if icr < Decimal::new(135, 0) {
-                    TroveStatus::AtRisk
-                } else {
-                    TroveStatus::Active
-                };
-
-                Trove {
-                    address: t.owner.clone(),
-                    collateral: t.coll,
-                    debt: t.debt,
-                    icr,
-                    status,
-                }
-            })
-            .collect();
+        // Fetch troves (paged, one batched round trip), FIL price and block height together
+        let troves = fetch_troves(&DataSources::current(), limit).await.map_err(|e| {
+            tracing::error!("RPC error fetching troves: {}", e);
+            ServerFnError::<NoCustomError>::ServerError(e.to_string())
+        })?;
 
         // Store in cache
         caches::TROVES.set(cache_key, troves.clone());
@@ -213,6 +188,7 @@ pub async fn get_lending_markets() -> Result<Vec<LendingMarketData>, ServerFnErr
     {
         use crate::subgraph::SubgraphClient;
         use crate::subgraph::unit_price_to_apr;
+        use usdfc_core::config::config;
         use crate::cache::caches;
 
         // Check cache first
@@ -229,7 +205,10 @@ pub async fn get_lending_markets() -> Result<Vec<LendingMarketData>, ServerFnErr
         let market_data: Vec<LendingMarketData> = markets
             .into_iter()
             .filter_map(|m| {
-                let maturity_ts = m.maturity.parse::<i64>().ok()?;
+                let Ok(maturity_ts) = m.maturity.parse::<i64>() else {
+                    tracing::warn!("Skipping lending market {} with invalid maturity '{}'", m.id, m.maturity);
+                    return None;
+                };
 
                 // Get real prices - use empty string if no price (will show as N/A in UI)
                 let lend_price = m.last_lend_unit_price.clone().unwrap_or_default();
@@ -261,15 +240,19 @@ pub async fn get_lending_markets() -> Result<Vec<LendingMarketData>, ServerFnErr
 
                 let volume = m.volume.clone().unwrap_or_default();
 
+                let currency = Currency::from_bytes32(&m.currency);
+                let featured = config().is_featured_market(&m.maturity, currency.symbol());
+
                 Some(LendingMarketData {
                     maturity: m.maturity,
-                    currency: m.currency,
+                    currency,
                     lend_unit_price: lend_price,
                     borrow_unit_price: borrow_price,
                     volume,
                     is_active: m.is_active,
                     lend_apr,
                     borrow_apr,
+                    featured,
                 })
             })
             .collect();
@@ -293,8 +276,9 @@ pub async fn get_lending_markets() -> Result<Vec<LendingMarketData>, ServerFnErr
 
 /// Get daily volume data for historical charts
 #[server(GetDailyVolumes, "/api")]
-pub async fn get_daily_volumes(days: Option<i32>) -> Result<Vec<DailyVolumeData>, ServerFnError> {
-    let days = days.unwrap_or(30);
+pub async fn get_daily_volumes(days: Option<i32>) -> Result<DailyVolumesResponse, ServerFnError> {
+    let days = DailyVolumesResponse::validate_days(days)
+        .map_err(|e| SfnError::from(usdfc_core::error::TerminalError::from(e)))?;
 
     #[cfg(feature = "ssr")]
     {
@@ -308,10 +292,11 @@ pub async fn get_daily_volumes(days: Option<i32>) -> Result<Vec<DailyVolumeData>
         }
 
         let subgraph = SubgraphClient::new();
-        let volumes = subgraph.get_daily_volumes(days).await
+        let fetch = subgraph.get_daily_volumes(days).await
             .map_err(|e| SfnError::ServerError(e.to_string()))?;
 
-        let data: Vec<DailyVolumeData> = volumes
+        let total = fetch.volumes.len();
+        let volumes: Vec<DailyVolumeData> = fetch.volumes
             .into_iter()
             .filter_map(|v| {
                 let volume = v.volume.parse::<f64>().ok()? / 1e18;
@@ -325,8 +310,20 @@ pub async fn get_daily_volumes(days: Option<i32>) -> Result<Vec<DailyVolumeData>
             })
             .collect();
 
-        // Store in cache
-        caches::DAILY_VOLUMES.set(cache_key, data.clone());
+        let dropped = total - volumes.len();
+        if dropped > 0 {
+            tracing::warn!("get_daily_volumes: dropped {} of {} unparseable rows", dropped, total);
+        }
+
+        let data = DailyVolumesResponse {
+            volumes,
+            complete: fetch.complete && dropped == 0,
+        };
+
+        // Only cache complete results so a partial fetch is retried on the next request
+        if data.complete {
+            caches::DAILY_VOLUMES.set(cache_key, data.clone());
+        }
 
         Ok(data)
     }
@@ -360,7 +357,8 @@ pub async fn get_address_info(address: String) -> Result<AddressInfo, ServerFnEr
             })?;
 
         // Check cache first
-        let cache_key = format!("addr_{}", normalized);
+        // Lowercased so checksummed, lowercase and f4 inputs share one entry
+        let cache_key = format!("addr_{}", normalized.to_lowercase());
         if let Some(cached) = caches::ADDRESS_INFO.get(&cache_key) {
             return Ok(cached);
         }
@@ -448,7 +446,7 @@ pub async fn get_top_holders(limit: Option<u32>, offset: Option<u32>) -> Result<
         use crate::cache::caches;
 
         let _offset = offset; // Ignored for cursor-based API
-        let cache_key = format!("holders_first_page");
+        let cache_key = "holders_first_page".to_string();
 
         // Check cache first
         if let Some(cached) = caches::TOKEN_HOLDERS.get(&cache_key) {
@@ -465,10 +463,7 @@ pub async fn get_top_holders(limit: Option<u32>, offset: Option<u32>) -> Result<
 
         let holder_info: Vec<TokenHolderInfo> = holders
             .into_iter()
-            .map(|h| TokenHolderInfo {
-                address: h.address,
-                balance: h.balance,
-            })
+            .map(|h| TokenHolderInfo::new(h.address, h.balance))
             .collect();
 
         // Store in cache
@@ -541,36 +536,17 @@ pub async fn get_usdfc_price_data() -> Result<USDFCPriceData, ServerFnError> {
         }
 
         let gecko = GeckoClient::new();
-        let pool_info = gecko
-            .get_pool_info(&config().pool_usdfc_wfil)
-            .await
-            .map_err(|e| SfnError::ServerError(e.to_string()))?;
+        let pools = gecko
+            .get_pools_price_data(&config().price_pool_addresses())
+            .await;
+
+        // Only fail when every pool failed
+        if pools.iter().all(|p| p.error.is_some()) {
+            let errors: Vec<String> = pools.into_iter().filter_map(|p| p.error).collect();
+            return Err(SfnError::ServerError(errors.join("; ")));
+        }
 
-        // SAFETY: Use Option - never fallback to 1.0 for price (masks depegging)
-        let price_usd = pool_info
-            .base_token_price_usd
-            .and_then(|s| s.parse::<f64>().ok());
-
-        let price_change_24h = pool_info
-            .price_change_percentage
-            .and_then(|p| p.h24)
-            .and_then(|s| s.parse::<f64>().ok());
-
-        let volume_24h = pool_info
-            .volume_usd
-            .and_then(|v| v.h24)
-            .and_then(|s| s.parse::<f64>().ok());
-
-        let liquidity_usd = pool_info
-            .reserve_in_usd
-            .and_then(|s| s.parse::<f64>().ok());
-
-        let price_data = USDFCPriceData {
-            price_usd,
-            price_change_24h,
-            volume_24h,
-            liquidity_usd,
-        };
+        let price_data = USDFCPriceData::from_pools(pools, gecko.provenance());
 
         // Store in cache
         caches::USDFC_PRICE.set("default".to_string(), price_data.clone());
@@ -609,20 +585,18 @@ pub async fn check_api_health() -> Result<ApiHealthStatus, ServerFnError> {
         // Check RPC by getting FIL price (simple call)
         let rpc_ok = rpc.get_fil_price().await.is_ok();
 
+        // Disabled sources are skipped and reported as such rather than failing
+        let cfg = config();
+
         // Check Blockscout by getting token info
-        let blockscout_ok = blockscout
-            .gql_get_token_info(&config().usdfc_token)
-            .await
-            .is_ok();
+        let blockscout_ok = cfg.blockscout_enabled
+            && blockscout.gql_get_token_info(&cfg.usdfc_token).await.is_ok();
 
         // Check Subgraph by getting lending markets
-        let subgraph_ok = subgraph.get_lending_markets().await.is_ok();
+        let subgraph_ok = cfg.subgraph_enabled && subgraph.get_lending_markets().await.is_ok();
 
         // Check GeckoTerminal by fetching primary pool info
-        let gecko_ok = gecko
-            .get_pool_info(&config().pool_usdfc_wfil)
-            .await
-            .is_ok();
+        let gecko_ok = cfg.gecko_enabled && gecko.get_pool_info(&cfg.pool_usdfc_wfil).await.is_ok();
 
         // Check historical SQLite database
         let database_ok = historical::check_db_health().is_ok();
@@ -638,6 +612,9 @@ pub async fn check_api_health() -> Result<ApiHealthStatus, ServerFnError> {
             subgraph_ok,
             gecko_ok,
             database_ok,
+            disabled_sources: cfg.disabled_sources(),
+            circuits: crate::circuit_breaker::UPSTREAM_BREAKER.states(),
+            timed_out: Vec::new(),
             timestamp,
         })
     }
@@ -694,7 +671,7 @@ pub async fn get_order_book(maturity: Option<String>) -> Result<OrderBookData, S
     #[cfg(feature = "ssr")]
     {
         use crate::subgraph::SubgraphClient;
-        use usdfc_core::config::config;
+        use usdfc_core::format::{decimal_to_f64, parse_token_amount};
         use crate::cache::caches;
 
         // Check cache first
@@ -704,7 +681,7 @@ pub async fn get_order_book(maturity: Option<String>) -> Result<OrderBookData, S
         }
 
         let subgraph = SubgraphClient::new();
-        let currency = &config().currency_usdfc;
+        let currency = &Currency::Usdfc;
         let maturity_ref = maturity.as_deref();
 
         let book = subgraph.get_order_book(currency, maturity_ref, 100).await
@@ -712,8 +689,8 @@ pub async fn get_order_book(maturity: Option<String>) -> Result<OrderBookData, S
 
         // Convert orders to display format - skip orders with invalid data instead of using fake values
         let convert_order = |o: &crate::subgraph::Order| -> Option<OrderData> {
-            let amount = o.input_amount.parse::<f64>().ok()? / 1e18;
-            let filled = o.filled_amount.parse::<f64>().ok()? / 1e18;
+            let amount = decimal_to_f64(parse_token_amount(&o.input_amount, 18)?);
+            let filled = decimal_to_f64(parse_token_amount(&o.filled_amount, 18)?);
             let price = o.input_unit_price.parse::<f64>().ok()? / 10000.0;
             let maturity_ts = o.maturity.parse::<i64>().ok()?;
             let apr = crate::subgraph::unit_price_to_apr(&o.input_unit_price, maturity_ts).ok()?;
@@ -741,14 +718,22 @@ pub async fn get_order_book(maturity: Option<String>) -> Result<OrderBookData, S
             _ => None,
         };
 
+        // Order flow over unfilled remainders
+        let lend_flow = OrderFlowSide::from_orders(&lend_orders);
+        let borrow_flow = OrderFlowSide::from_orders(&borrow_orders);
+        let imbalance = OrderBookData::volume_imbalance(&lend_flow, &borrow_flow);
+
         let order_book_data = OrderBookData {
-            currency: "USDFC".to_string(),
+            currency: book.currency,
             maturity: book.maturity,
             lend_orders,
             borrow_orders,
             best_lend_price,
             best_borrow_price,
             spread_bps,
+            lend_flow,
+            borrow_flow,
+            imbalance,
         };
 
         // Store in cache
@@ -776,7 +761,6 @@ pub async fn get_recent_lending_trades(limit: Option<i32>) -> Result<Vec<Lending
     #[cfg(feature = "ssr")]
     {
         use crate::subgraph::SubgraphClient;
-        use crate::subgraph::decode_currency;
         use crate::cache::caches;
 
         // Check cache first
@@ -803,7 +787,7 @@ pub async fn get_recent_lending_trades(limit: Option<i32>) -> Result<Vec<Lending
 
                 Some(LendingTradeData {
                     id: tx.id,
-                    currency: decode_currency(&tx.currency),
+                    currency: Currency::from_bytes32(&tx.currency),
                     maturity: tx.maturity,
                     side: if tx.side == 0 { "Lend".to_string() } else { "Borrow".to_string() },
                     amount,
@@ -872,12 +856,14 @@ fn calculate_liquidity_from_volume_impact(
 /// Get comprehensive chart data with all metrics for advanced chart
 /// Fetches real data from GeckoTerminal, RPC, Blockscout, and Subgraph
 /// Uses historical snapshot storage for metrics over time
+/// `ma_windows` selects the SMA/EMA overlay windows (defaults to MA20/MA50)
 #[server(GetAdvancedChartData, "/api")]
 pub async fn get_advanced_chart_data(
     resolution: ChartResolution,
     lookback: ChartLookback,
     start: Option<i64>,
     end: Option<i64>,
+    ma_windows: Option<Vec<u32>>,
 ) -> Result<ChartDataResponse, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
@@ -891,13 +877,31 @@ pub async fn get_advanced_chart_data(
         use std::time::{SystemTime, UNIX_EPOCH, Instant};
         use rust_decimal::prelude::ToPrimitive;
 
+        // Moving-average windows: 2..=500 candles, at most four distinct windows
+        let mut ma_windows: Vec<u32> = ma_windows
+            .unwrap_or_else(|| DEFAULT_MA_WINDOWS.to_vec())
+            .into_iter()
+            .filter(|w| (2..=500).contains(w))
+            .collect();
+        ma_windows.sort_unstable();
+        ma_windows.dedup();
+        ma_windows.truncate(4);
+
+        // Shorten lookbacks the resolution cannot cover (e.g. 1m candles over "all")
+        let combo = validate_combo(resolution, lookback, &config().chart_lookback_overrides);
+        if let Some(reason) = &combo.reason {
+            tracing::info!("Adjusted chart request: {}", reason);
+        }
+        let lookback = combo.lookback;
+
         // Generate cache key from parameters
         let cache_key = format!(
-            "chart_{}_{}_{}_{}",
+            "chart_{}_{}_{}_{}_ma{:?}",
             resolution.label(),
             lookback.label(),
             start.unwrap_or(0),
-            end.unwrap_or(0)
+            end.unwrap_or(0),
+            ma_windows
         );
 
         // Check cache first
@@ -939,7 +943,7 @@ pub async fn get_advanced_chart_data(
         };
 
         // CRITICAL: Enforce API safety limits to prevent data loss
-        let max_safe_lookback = resolution.max_safe_lookback_mins();
+        let max_safe_lookback = resolution.max_lookback_mins(&config().chart_lookback_overrides);
         if effective_lookback_mins > max_safe_lookback {
             tracing::warn!(
                 "Lookback {} mins exceeds safe limit {} mins for resolution {:?}. Clamping to safe limit.",
@@ -950,6 +954,11 @@ pub async fn get_advanced_chart_data(
             effective_lookback_mins = max_safe_lookback;
         }
 
+        // A raised lookback limit (paid tier) needs more candles per request
+
+        let limit = limit.max(max_safe_lookback / resolution_mins);
+
+
         // Determine how many OHLCV points to request from GeckoTerminal.
         let data_points = if effective_lookback_mins == 0 {
             // "All" – use API maximum.
@@ -992,6 +1001,15 @@ pub async fn get_advanced_chart_data(
             price_candles.retain(|c| c.time >= custom_start && c.time <= effective_end);
         }
 
+        // SMA/EMA overlays over the (range-filtered) candles
+        let moving_averages: Vec<MovingAverageSeries> = ma_windows
+            .iter()
+            .flat_map(|&w| {
+                [MovingAverageKind::Simple, MovingAverageKind::Exponential]
+                    .map(|kind| MovingAverageSeries::compute(kind, w, &price_candles))
+            })
+            .collect();
+
         // Extract volume data from candles
         let volume_data: Vec<(i64, f64)> = price_candles
             .iter()
@@ -1069,6 +1087,8 @@ pub async fn get_advanced_chart_data(
             raw_snapshots
         };
 
+        let snapshot_interval = crate::historical::snapshot_interval_secs() as i64;
+
         // Helper function to ensure every metric has at least current value
         let ensure_data = |mut series: Vec<(i64, f64)>, current_value: Option<f64>| -> Vec<(i64, f64)> {
             if series.is_empty() {
@@ -1082,7 +1102,8 @@ pub async fn get_advanced_chart_data(
                 // Have historical data - optionally append current value if newer
                 if let Some(val) = current_value {
                     let last_ts = series.last().map(|(ts, _)| *ts).unwrap_or(0);
-                    if now > last_ts + 120 {
+                    // Only when the last snapshot is more than two collector intervals old
+                    if now > last_ts + 2 * snapshot_interval {
                         series.push((now, val));
                     }
                 }
@@ -1146,7 +1167,8 @@ pub async fn get_advanced_chart_data(
             let mut series = MetricSnapshot::holders_series(&snapshots);
             if let Some(h) = current_holders {
                 let last_ts = series.last().map(|(ts, _)| *ts).unwrap_or(0);
-                if now > last_ts + 120 {
+                // Only when the last snapshot is more than two collector intervals old
+                if now > last_ts + 2 * snapshot_interval {
                     series.push((now, h));
                 }
             }
@@ -1182,6 +1204,7 @@ pub async fn get_advanced_chart_data(
             lookback,
             generated_at: now,
             fetch_time_ms,
+            timings: Default::default(),
             price_candles,
             volume_data,
             liquidity_data,
@@ -1191,6 +1214,7 @@ pub async fn get_advanced_chart_data(
             lend_apr_data,
             borrow_apr_data,
             transfers_data,
+            moving_averages,
             current_price,
             current_volume_24h,
             current_liquidity,
@@ -1201,6 +1225,8 @@ pub async fn get_advanced_chart_data(
             current_borrow_apr,
             snapshot_count: snapshots.len(),
             oldest_snapshot_time: snapshots.first().map(|s| s.timestamp),
+            snapshot_interval_secs: snapshot_interval as u64,
+            lookback_overrides: config().chart_lookback_overrides.clone(),
         };
 
         // Store in cache
@@ -1232,178 +1258,32 @@ pub async fn get_wallet_analytics(
     #[cfg(feature = "ssr")]
     {
         use crate::blockscout::BlockscoutClient;
+        use crate::wallet::{wallet_analytics, wallet_evm_address, wallet_window};
         use usdfc_core::config::config;
         use usdfc_core::error::ValidationError;
-        use rust_decimal::Decimal;
-        use rust_decimal::prelude::ToPrimitive;
-        use std::collections::BTreeMap;
         use std::time::{SystemTime, UNIX_EPOCH};
 
         // Basic address validation
         ValidationError::validate_address(&address)
             .map_err(|e| SfnError::ServerError(e.to_string()))?;
-
-        // Normalize address to EVM hex if needed
-        let wallet_evm = if address.starts_with("0x") {
-            address.to_lowercase()
-        } else if address.starts_with('f') {
-            // Try to convert f4-style Filecoin address to EVM
-            match crate::address_conv::f4_to_evm(&address) {
-                Ok(ev) => ev.to_lowercase(),
-                Err(e) => return Err(SfnError::ServerError(e.to_string())),
-            }
-        } else {
-            address.to_lowercase()
-        };
+        let wallet_evm = wallet_evm_address(&address).map_err(SfnError::ServerError)?;
 
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0);
-
-        let configured_lookback_mins = lookback.minutes();
-        let resolution_mins = resolution.minutes();
-
-        let effective_lookback_mins = if let Some(custom_start) = start {
-            let diff_secs = now.saturating_sub(custom_start);
-            let span_mins = ((diff_secs / 60).max(1)) as u32;
-            if configured_lookback_mins == 0 {
-                span_mins
-            } else {
-                configured_lookback_mins.max(span_mins)
-            }
-        } else {
-            configured_lookback_mins
-        };
-
-        let (window_start, window_end) = if let Some(custom_start) = start {
-            (custom_start, end.unwrap_or(now))
-        } else if effective_lookback_mins == 0 {
-            (0, now)
-        } else {
-            let span_secs = effective_lookback_mins as i64 * 60;
-            (now.saturating_sub(span_secs), now)
-        };
+        let window = wallet_window(lookback, start, end, now);
 
         let blockscout = BlockscoutClient::new();
-        let token_address = &config().usdfc_token;
 
         // Fetch recent transfers with timestamps for USDFC
         let (transfers, _) = blockscout
-            .gql_get_transfers_with_timestamps(token_address, 200, None)
+            .gql_get_transfers_with_timestamps(&config().usdfc_token, 200, None)
             .await
             .map_err(|e| SfnError::ServerError(e.to_string()))?;
 
-        // Filter transfers for this wallet and time window
-        let mut relevant: Vec<crate::blockscout::TransferWithTimestamp> = transfers
-            .into_iter()
-            .filter(|t| t.timestamp >= window_start && t.timestamp <= window_end)
-            .filter(|t| {
-                let from = t.from_address.to_lowercase();
-                let to = t.to_address.to_lowercase();
-                from == wallet_evm || to == wallet_evm
-            })
-            .collect();
-
-        if relevant.is_empty() {
-            return Ok(WalletAnalyticsResponse {
-                address,
-                buckets: Vec::new(),
-                total_in: 0.0,
-                total_out: 0.0,
-                first_seen: None,
-                last_active: None,
-            });
-        }
-
-        // Sort by timestamp to derive first/last activity
-        relevant.sort_by_key(|t| t.timestamp);
-
-        let first_ts = relevant.first().map(|t| t.timestamp);
-        let last_ts = relevant.last().map(|t| t.timestamp);
-
-        let first_seen = first_ts.and_then(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-        });
-        let last_active = last_ts.and_then(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-        });
-
-        // Bucket transfers by resolution
-        let bucket_secs = (resolution_mins as i64 * 60).max(60);
-
-        struct BucketAccum {
-            volume_in: Decimal,
-            volume_out: Decimal,
-            count_in: u64,
-            count_out: u64,
-        }
-
-        let mut buckets_map: BTreeMap<i64, BucketAccum> = BTreeMap::new();
-
-        let decimals = 18u32;
-        let divisor = Decimal::from_i128_with_scale(10_i128.pow(decimals), 0);
-
-        for t in relevant {
-            let is_incoming = t.to_address.to_lowercase() == wallet_evm;
-            let is_outgoing = t.from_address.to_lowercase() == wallet_evm;
-
-            if !is_incoming && !is_outgoing {
-                continue;
-            }
-
-            let raw = match t.amount.parse::<u128>() {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
-
-            let value = Decimal::from_i128_with_scale(raw as i128, 0) / divisor;
-            let bucket_ts = (t.timestamp / bucket_secs) * bucket_secs;
-
-            let entry = buckets_map.entry(bucket_ts).or_insert(BucketAccum {
-                volume_in: Decimal::ZERO,
-                volume_out: Decimal::ZERO,
-                count_in: 0,
-                count_out: 0,
-            });
-
-            if is_incoming {
-                entry.volume_in += value;
-                entry.count_in += 1;
-            }
-            if is_outgoing {
-                entry.volume_out += value;
-                entry.count_out += 1;
-            }
-        }
-
-        let mut buckets = Vec::new();
-        let mut total_in = Decimal::ZERO;
-        let mut total_out = Decimal::ZERO;
-
-        for (ts, acc) in buckets_map {
-            total_in += acc.volume_in;
-            total_out += acc.volume_out;
-
-            buckets.push(WalletBucket {
-                timestamp: ts,
-                volume_in: acc.volume_in.to_f64().unwrap_or(0.0),
-                volume_out: acc.volume_out.to_f64().unwrap_or(0.0),
-                count_in: acc.count_in,
-                count_out: acc.count_out,
-            });
-        }
-
-        Ok(WalletAnalyticsResponse {
-            address,
-            buckets,
-            total_in: total_in.to_f64().unwrap_or(0.0),
-            total_out: total_out.to_f64().unwrap_or(0.0),
-            first_seen,
-            last_active,
-        })
+        let bucket_secs = resolution.minutes() as i64 * 60;
+        Ok(wallet_analytics(&blockscout, address, &wallet_evm, &transfers, window, bucket_secs, end.is_none()).await)
     }
 
     #[cfg(not(feature = "ssr"))]
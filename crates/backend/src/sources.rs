@@ -0,0 +1,422 @@
+//! Data source traits for the server functions
+//!
+//! Server functions used to construct `RpcClient` directly, so the ICR/TCR
+//! math behind them could only run against a live node. The chain reads they
+//! need are behind `ProtocolDataSource` and `PriceSource`; `RpcClient`
+//! implements both, and `mock::MockDataSource` serves fixed values for tests.
+//!
+//! Server functions get their sources from `DataSources::current()`, which
+//! prefers a `DataSources` provided through Leptos context and otherwise uses
+//! the live RPC client.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use usdfc_core::error::{ApiError, ApiResult};
+use usdfc_core::types::{ProtocolMetrics, Provenance, Trove, TroveStatus};
+
+use crate::rpc::{RpcClient, TroveData};
+
+/// TCR reported when the system has no debt
+pub const NO_DEBT_TCR: i64 = 999_999;
+/// ICR reported for a trove with no debt
+pub const NO_DEBT_ICR: i64 = 10_000;
+
+/// Protocol-wide reads behind `fetch_protocol_metrics`, each with its own result
+pub struct ProtocolReads {
+    pub total_supply: ApiResult<Decimal>,
+    pub total_collateral: ApiResult<Decimal>,
+    pub trove_owners_count: ApiResult<u64>,
+    pub stability_pool_balance: ApiResult<Decimal>,
+    pub block_number: ApiResult<u64>,
+}
+
+/// Protocol state read from the chain
+#[async_trait]
+pub trait ProtocolDataSource: Send + Sync {
+    async fn total_supply(&self) -> ApiResult<Decimal>;
+    async fn total_collateral(&self) -> ApiResult<Decimal>;
+    async fn total_debt(&self) -> ApiResult<Decimal>;
+    async fn trove_owners_count(&self) -> ApiResult<u64>;
+    async fn stability_pool_balance(&self) -> ApiResult<Decimal>;
+    /// Up to `count` troves, riskiest first
+    async fn sorted_troves(&self, count: u32) -> ApiResult<Vec<TroveData>>;
+    async fn block_number(&self) -> ApiResult<u64>;
+    /// Provenance stamped on responses built from this source
+    fn provenance(&self, block_number: Option<u64>) -> Provenance;
+
+    /// The protocol-wide reads together; sources that can batch them into
+    /// one round trip override this
+    async fn protocol_reads(&self) -> ProtocolReads {
+        let (total_supply, total_collateral, trove_owners_count, stability_pool_balance, block_number) = tokio::join!(
+            self.total_supply(),
+            self.total_collateral(),
+            self.trove_owners_count(),
+            self.stability_pool_balance(),
+            self.block_number()
+        );
+        ProtocolReads { total_supply, total_collateral, trove_owners_count, stability_pool_balance, block_number }
+    }
+}
+
+/// FIL/USD price used to value collateral
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    async fn fil_price(&self) -> ApiResult<Decimal>;
+}
+
+#[async_trait]
+impl ProtocolDataSource for RpcClient {
+    async fn total_supply(&self) -> ApiResult<Decimal> {
+        self.get_total_supply().await
+    }
+
+    async fn total_collateral(&self) -> ApiResult<Decimal> {
+        self.get_total_collateral().await
+    }
+
+    async fn total_debt(&self) -> ApiResult<Decimal> {
+        self.get_total_debt().await
+    }
+
+    async fn trove_owners_count(&self) -> ApiResult<u64> {
+        self.get_trove_owners_count().await
+    }
+
+    async fn stability_pool_balance(&self) -> ApiResult<Decimal> {
+        self.get_stability_pool_balance().await
+    }
+
+    async fn sorted_troves(&self, count: u32) -> ApiResult<Vec<TroveData>> {
+        self.get_sorted_troves_batched(count).await
+    }
+
+    async fn block_number(&self) -> ApiResult<u64> {
+        self.get_block_number().await
+    }
+
+    fn provenance(&self, block_number: Option<u64>) -> Provenance {
+        RpcClient::provenance(self, block_number)
+    }
+
+    async fn protocol_reads(&self) -> ProtocolReads {
+        self.get_protocol_reads().await
+    }
+}
+
+#[async_trait]
+impl PriceSource for RpcClient {
+    async fn fil_price(&self) -> ApiResult<Decimal> {
+        self.get_fil_price().await
+    }
+}
+
+/// The sources a server function reads from
+#[derive(Clone)]
+pub struct DataSources {
+    pub protocol: Arc<dyn ProtocolDataSource>,
+    pub price: Arc<dyn PriceSource>,
+}
+
+impl DataSources {
+    /// Live sources backed by the configured RPC endpoint
+    pub fn live() -> Self {
+        let rpc = Arc::new(RpcClient::new());
+        Self { protocol: rpc.clone(), price: rpc }
+    }
+
+    /// Sources provided through Leptos context, or the live ones
+    pub fn current() -> Self {
+        leptos::use_context::<DataSources>().unwrap_or_else(Self::live)
+    }
+}
+
+/// Total collateral ratio in percent: collateral value over debt
+pub fn compute_tcr(collateral: Decimal, debt: Decimal, fil_price: Decimal) -> Decimal {
+    if debt.is_zero() {
+        // No debt means infinite collateralization
+        return Decimal::new(NO_DEBT_TCR, 0);
+    }
+    collateral * fil_price / debt * Decimal::new(100, 0)
+}
+
+/// Individual collateral ratio in percent for one trove
+pub fn compute_icr(collateral: Decimal, debt: Decimal, fil_price: Decimal) -> Decimal {
+    if debt.is_zero() {
+        return Decimal::new(NO_DEBT_ICR, 0);
+    }
+    collateral * fil_price / debt * Decimal::new(100, 0)
+}
+
+/// Trove health from its ICR: below 115% is critical, below 135% at risk
+pub fn classify_icr(icr: Decimal) -> TroveStatus {
+    if icr < Decimal::new(115, 0) {
+        TroveStatus::Critical
+    } else if icr < Decimal::new(135, 0) {
+        TroveStatus::AtRisk
+    } else {
+        TroveStatus::Active
+    }
+}
+
+/// The value of a successful read; a failure is logged and kept in `first_error`
+fn read_or_log<T>(upstream: &str, name: &str, result: ApiResult<T>, first_error: &mut Option<ApiError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!(upstream, read = name, error = %e, "Protocol metrics read {} failed: {}", name, e);
+            first_error.get_or_insert(e);
+            None
+        }
+    }
+}
+
+/// Protocol-wide metrics with TCR and collateral value at the current FIL price
+///
+/// Each read can fail on its own: values that depend on a failed read are
+/// left as None. Fails only when every read failed, with the first error.
+pub async fn fetch_protocol_metrics(sources: &DataSources) -> ApiResult<ProtocolMetrics> {
+    let protocol = &sources.protocol;
+    // Debt is summed over every trove, so it stays a separate (paged) read
+    let (reads, total_debt, fil_price) = tokio::join!(
+        protocol.protocol_reads(),
+        protocol.total_debt(),
+        sources.price.fil_price()
+    );
+
+    let mut first_error = None;
+    let total_supply = read_or_log("rpc", "total_supply", reads.total_supply, &mut first_error);
+    let total_collateral = read_or_log("rpc", "total_collateral", reads.total_collateral, &mut first_error);
+    let stability_pool_balance = read_or_log("rpc", "stability_pool_balance", reads.stability_pool_balance, &mut first_error);
+    let active_troves = read_or_log("rpc", "trove_owners_count", reads.trove_owners_count, &mut first_error);
+    let total_debt = read_or_log("rpc", "total_debt", total_debt, &mut first_error);
+    let fil_price = read_or_log("price", "fil_price", fil_price, &mut first_error);
+
+    let metrics = ProtocolMetrics {
+        total_supply,
+        // USDFC in the stability pool is locked and not actively circulating
+        circulating_supply: total_supply.zip(stability_pool_balance).map(|(supply, pool)| supply - pool),
+        collateral_fil: total_collateral,
+        collateral_usd: total_collateral.and_then(|coll| ProtocolMetrics::collateral_value_usd(coll, fil_price)),
+        active_troves,
+        tcr: match (total_collateral, total_debt, fil_price) {
+            (Some(coll), Some(debt), Some(price)) => Some(compute_tcr(coll, debt, price)),
+            _ => None,
+        },
+        stability_pool_balance,
+        provenance: protocol.provenance(reads.block_number.ok()),
+    };
+
+    match first_error {
+        Some(e) if metrics.is_empty() => Err(e),
+        _ => Ok(metrics),
+    }
+}
+
+/// Up to `limit` troves with ICR and health status at the current FIL price
+pub async fn fetch_troves(sources: &DataSources, limit: u32) -> ApiResult<Vec<Trove>> {
+    let (troves_data, fil_price, block_number) = tokio::join!(
+        sources.protocol.sorted_troves(limit),
+        sources.price.fil_price(),
+        sources.protocol.block_number()
+    );
+
+    let troves_data = troves_data.map_err(|e| e.rpc_context("Failed to fetch troves"))?;
+    if troves_data.is_empty() {
+        return Ok(vec![]); // Empty is valid - no troves exist
+    }
+
+    let fil_price = fil_price.map_err(|e| e.rpc_context("Failed to fetch FIL price"))?;
+    if fil_price.is_zero() {
+        return Err(ApiError::InvalidResponse { message: "FIL price is zero".to_string() });
+    }
+
+    let provenance = sources.protocol.provenance(block_number.ok());
+    Ok(troves_data
+        .iter()
+        .map(|t| {
+            let icr = compute_icr(t.coll, t.debt, fil_price);
+            Trove {
+                address: t.owner.clone(),
+                collateral: t.coll,
+                debt: t.debt,
+                icr,
+                status: classify_icr(icr),
+                provenance: provenance.clone(),
+            }
+        })
+        .collect())
+}
+
+/// The trove owned by `owner` (a 0x address, any case), if one is open
+///
+/// There is no per-owner getter on MultiTroveGetter, so this reads every
+/// sorted trove and computes ICR and status the same way as `fetch_troves`.
+pub async fn fetch_trove(sources: &DataSources, owner: &str) -> ApiResult<Option<Trove>> {
+    let count = sources
+        .protocol
+        .trove_owners_count()
+        .await
+        .map_err(|e| e.rpc_context("Failed to fetch trove count"))?;
+    let troves = fetch_troves(sources, count as u32).await?;
+    Ok(troves.into_iter().find(|t| t.address.eq_ignore_ascii_case(owner)))
+}
+
+/// Fixed-value sources for tests
+pub mod mock {
+    use super::*;
+
+    /// Serves the values it was built with; a `None` price fails the price read
+    #[derive(Clone, Default)]
+    pub struct MockDataSource {
+        pub total_supply: Decimal,
+        pub total_collateral: Decimal,
+        pub stability_pool_balance: Decimal,
+        pub fil_price: Option<Decimal>,
+        pub troves: Vec<TroveData>,
+        pub block_number: u64,
+    }
+
+    impl MockDataSource {
+        /// Wrap this mock as both the protocol and price source
+        pub fn into_sources(self) -> DataSources {
+            let mock = Arc::new(self);
+            DataSources { protocol: mock.clone(), price: mock }
+        }
+    }
+
+    /// A trove with only owner, debt and collateral set
+    pub fn trove(owner: &str, debt: Decimal, coll: Decimal) -> TroveData {
+        TroveData {
+            owner: owner.to_string(),
+            debt,
+            coll,
+            stake: Decimal::ZERO,
+            snapshot_fil: Decimal::ZERO,
+            snapshot_debt: Decimal::ZERO,
+        }
+    }
+
+    #[async_trait]
+    impl ProtocolDataSource for MockDataSource {
+        async fn total_supply(&self) -> ApiResult<Decimal> {
+            Ok(self.total_supply)
+        }
+
+        async fn total_collateral(&self) -> ApiResult<Decimal> {
+            Ok(self.total_collateral)
+        }
+
+        async fn total_debt(&self) -> ApiResult<Decimal> {
+            Ok(self.troves.iter().map(|t| t.debt).sum())
+        }
+
+        async fn trove_owners_count(&self) -> ApiResult<u64> {
+            Ok(self.troves.len() as u64)
+        }
+
+        async fn stability_pool_balance(&self) -> ApiResult<Decimal> {
+            Ok(self.stability_pool_balance)
+        }
+
+        async fn sorted_troves(&self, count: u32) -> ApiResult<Vec<TroveData>> {
+            Ok(self.troves.iter().take(count as usize).cloned().collect())
+        }
+
+        async fn block_number(&self) -> ApiResult<u64> {
+            Ok(self.block_number)
+        }
+
+        fn provenance(&self, block_number: Option<u64>) -> Provenance {
+            Provenance { source: "mock".to_string(), fetched_at: 0, block_number, stale: false }
+        }
+    }
+
+    #[async_trait]
+    impl PriceSource for MockDataSource {
+        async fn fil_price(&self) -> ApiResult<Decimal> {
+            self.fil_price.ok_or_else(|| ApiError::RpcError("no price".to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{trove, MockDataSource};
+    use super::*;
+
+    fn dec(value: i64) -> Decimal {
+        Decimal::new(value, 0)
+    }
+
+    #[tokio::test]
+    async fn test_trove_status_boundaries() {
+        // At $1/FIL, collateral over debt is the ICR
+        let sources = MockDataSource {
+            fil_price: Some(dec(1)),
+            troves: vec![
+                trove("0xcritical", dec(10_000), Decimal::new(1_149_999, 2)),
+                trove("0xatrisk_low", dec(10_000), dec(11_500)),
+                trove("0xatrisk_high", dec(10_000), Decimal::new(1_349_999, 2)),
+                trove("0xactive", dec(10_000), dec(13_500)),
+                trove("0xnodebt", Decimal::ZERO, dec(5)),
+            ],
+            ..Default::default()
+        }
+        .into_sources();
+
+        let statuses: Vec<_> = fetch_troves(&sources, 10).await.unwrap().into_iter().map(|t| t.status).collect();
+        assert_eq!(
+            statuses,
+            [TroveStatus::Critical, TroveStatus::AtRisk, TroveStatus::AtRisk, TroveStatus::Active, TroveStatus::Active]
+        );
+
+        let zero_price = MockDataSource { fil_price: Some(Decimal::ZERO), troves: vec![trove("0xa", dec(1), dec(1))], ..Default::default() };
+        assert!(fetch_troves(&zero_price.into_sources(), 10).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trove_by_owner() {
+        let mock = MockDataSource {
+            fil_price: Some(dec(5)),
+            troves: vec![trove("0xAbC", dec(1_000), dec(300)), trove("0xdef", dec(1_000), dec(600))],
+            ..Default::default()
+        };
+        let sources = mock.into_sources();
+
+        let found = fetch_trove(&sources, "0xabc").await.unwrap().unwrap();
+        assert_eq!(found.address, "0xAbC");
+        assert_eq!(found.status, TroveStatus::Active);
+        assert!(fetch_trove(&sources, "0x123").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_protocol_metrics_from_mock() {
+        let mock = MockDataSource {
+            total_supply: dec(1_000),
+            total_collateral: dec(600),
+            stability_pool_balance: dec(250),
+            fil_price: Some(dec(5)),
+            troves: vec![trove("0xa", dec(1_000), dec(600))],
+            block_number: 42,
+        };
+
+        let metrics = fetch_protocol_metrics(&mock.clone().into_sources()).await.unwrap();
+        assert_eq!(metrics.tcr, Some(dec(300)));
+        assert_eq!(metrics.circulating_supply, Some(dec(750)));
+        assert_eq!(metrics.collateral_usd, Some(dec(3_000)));
+        assert_eq!(metrics.active_troves, Some(1));
+        assert_eq!(metrics.provenance.block_number, Some(42));
+        assert!(metrics.missing().is_empty());
+
+        let no_debt = MockDataSource { troves: vec![], ..mock.clone() };
+        assert_eq!(fetch_protocol_metrics(&no_debt.into_sources()).await.unwrap().tcr, Some(dec(NO_DEBT_TCR)));
+
+        // Without a price only the price-derived values are missing
+        let no_price = MockDataSource { fil_price: None, ..mock };
+        let metrics = fetch_protocol_metrics(&no_price.into_sources()).await.unwrap();
+        assert_eq!(metrics.missing(), ["collateral_usd", "tcr"]);
+        assert_eq!(metrics.total_supply, Some(dec(1_000)));
+    }
+}
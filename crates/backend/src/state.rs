@@ -4,17 +4,31 @@
 
 use axum::extract::FromRef;
 use leptos::LeptosOptions;
+use tokio::sync::broadcast;
+use usdfc_core::types::ProtocolMetrics;
+
+/// Protocol metrics updates buffered per `/ws/metrics` subscriber before it lags
+const METRICS_CHANNEL_CAPACITY: usize = 16;
 
 /// Application state shared across requests
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub leptos_options: LeptosOptions,
+    /// Fresh protocol metrics after each snapshot, for `/ws/metrics` subscribers
+    pub metrics_tx: broadcast::Sender<ProtocolMetrics>,
     // Add additional state here:
     // pub db_pool: PgPool,
     // pub redis_client: redis::Client,
     // pub rpc_endpoint: String,
 }
 
+impl AppState {
+    pub fn new(leptos_options: LeptosOptions) -> Self {
+        let (metrics_tx, _) = broadcast::channel(METRICS_CHANNEL_CAPACITY);
+        Self { leptos_options, metrics_tx }
+    }
+}
+
 impl FromRef<AppState> for LeptosOptions {
     fn from_ref(state: &AppState) -> Self {
         state.leptos_options.clone()
@@ -1,13 +1,22 @@
 use usdfc_core::config::config;
 use usdfc_core::error::{ApiError, ApiResult};
-use serde::{Deserialize, Serialize};
+use usdfc_core::types::Currency;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use std::time::Duration;
+use crate::timing::TimedSend;
+
+/// Maximum attempts for a query that fails with a transient error
+const MAX_RETRY_ATTEMPTS: u32 = 3;
+
+/// Page size for paginated daily volume queries (The Graph caps `first` at 1000)
+const DAILY_VOLUME_PAGE_SIZE: i32 = 500;
 
 /// Subgraph GraphQL client
 #[derive(Clone)]
 pub struct SubgraphClient {
     client: reqwest::Client,
-    url: String,
 }
 
 #[derive(Serialize)]
@@ -30,12 +39,19 @@ struct GraphQLError {
 }
 
 // Lending Market types
+//
+// Row structs tolerate minor schema drift: identifiers and descriptive fields
+// default when missing, and numeric fields accept either GraphQL strings
+// (BigInt/BigDecimal) or plain JSON numbers. List responses are parsed row by
+// row (see `parse_rows`) so one malformed row doesn't fail the whole list.
 #[derive(Deserialize, Debug, Clone)]
 pub struct LendingMarket {
+    #[serde(default)]
     pub id: String,
     pub currency: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub maturity: String,
-    #[serde(rename = "isActive")]
+    #[serde(rename = "isActive", default)]
     pub is_active: bool,
     #[serde(rename = "lastLendUnitPrice")]
     pub last_lend_unit_price: Option<String>,
@@ -47,26 +63,30 @@ pub struct LendingMarket {
 #[derive(Deserialize, Debug)]
 struct LendingMarketsData {
     #[serde(rename = "lendingMarkets")]
-    lending_markets: Vec<LendingMarket>,
+    lending_markets: Vec<Value>,
 }
 
 // Order types
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Order {
+    #[serde(default)]
     pub id: String,
-    #[serde(rename = "orderId")]
+    #[serde(rename = "orderId", default, deserialize_with = "string_or_number")]
     pub order_id: String,
+    #[serde(deserialize_with = "side_code")]
     pub side: i32, // 0 = LEND, 1 = BORROW
     pub currency: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub maturity: String,
-    #[serde(rename = "inputAmount")]
+    #[serde(rename = "inputAmount", deserialize_with = "string_or_number")]
     pub input_amount: String,
-    #[serde(rename = "filledAmount")]
+    #[serde(rename = "filledAmount", default = "zero_string", deserialize_with = "string_or_number")]
     pub filled_amount: String,
-    #[serde(rename = "inputUnitPrice")]
+    #[serde(rename = "inputUnitPrice", deserialize_with = "string_or_number")]
     pub input_unit_price: String,
+    #[serde(default)]
     pub status: String,
-    #[serde(rename = "createdAt")]
+    #[serde(rename = "createdAt", default, deserialize_with = "string_or_number")]
     pub created_at: String,
     #[serde(default)]
     pub user: Option<String>,
@@ -74,107 +94,240 @@ pub struct Order {
 
 #[derive(Deserialize, Debug)]
 struct OrdersData {
-    orders: Vec<Order>,
+    orders: Vec<Value>,
 }
 
 // Transaction types
 #[derive(Deserialize, Debug, Clone)]
 pub struct SubgraphTransaction {
+    #[serde(default)]
     pub id: String,
     pub currency: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub maturity: String,
+    #[serde(deserialize_with = "side_code")]
     pub side: i32,
+    #[serde(deserialize_with = "string_or_number")]
     pub amount: String,
-    #[serde(rename = "executionPrice")]
+    #[serde(rename = "executionPrice", default)]
     pub execution_price: Option<String>,
-    #[serde(rename = "createdAt")]
+    #[serde(rename = "createdAt", deserialize_with = "string_or_number")]
     pub created_at: String,
 }
 
 #[derive(Deserialize, Debug)]
 struct TransactionsData {
-    transactions: Vec<SubgraphTransaction>,
+    transactions: Vec<Value>,
 }
 
 // Daily Volume types for historical data
 #[derive(Deserialize, Debug, Clone, Serialize)]
 pub struct DailyVolume {
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
     pub currency: String,
+    #[serde(default, deserialize_with = "string_or_number")]
     pub maturity: String,
+    #[serde(default)]
     pub day: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub volume: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub timestamp: String,
 }
 
 #[derive(Deserialize, Debug)]
 struct DailyVolumesData {
     #[serde(rename = "dailyVolumes")]
-    daily_volumes: Vec<DailyVolume>,
+    daily_volumes: Vec<Value>,
+}
+
+/// Result of a paginated daily volume fetch
+#[derive(Debug, Clone)]
+pub struct DailyVolumesFetch {
+    pub volumes: Vec<DailyVolume>,
+    /// False when a later page failed after retries and only earlier pages were
+    /// returned, or when malformed rows were dropped
+    pub complete: bool,
 }
 
 // Transaction Candlestick for OHLC data
 #[derive(Deserialize, Debug, Clone, Serialize)]
 pub struct TransactionCandleStick {
+    #[serde(default)]
     pub id: String,
+    #[serde(default, deserialize_with = "string_or_number")]
     pub interval: String,
     pub currency: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub maturity: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub timestamp: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub open: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub close: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub high: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub low: String,
+    #[serde(default, deserialize_with = "string_or_number")]
     pub average: String,
+    #[serde(default = "zero_string", deserialize_with = "string_or_number")]
     pub volume: String,
-    #[serde(rename = "volumeInFV")]
+    #[serde(rename = "volumeInFV", default = "zero_string", deserialize_with = "string_or_number")]
     pub volume_in_fv: String,
 }
 
 #[derive(Deserialize, Debug)]
 struct CandleSticksData {
     #[serde(rename = "transactionCandleSticks")]
-    candlesticks: Vec<TransactionCandleStick>,
+    candlesticks: Vec<Value>,
+}
+
+/// Accept a GraphQL BigInt/BigDecimal string or a plain JSON number
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        other => Err(serde::de::Error::custom(format!("expected string or number, got {}", other))),
+    }
+}
+
+/// Accept an order side as 0/1, "0"/"1" or "Lend"/"Borrow"
+fn side_code<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    let side = match &value {
+        Value::Number(n) => n.as_i64().map(|n| n as i32),
+        Value::String(s) if s.eq_ignore_ascii_case("lend") => Some(0),
+        Value::String(s) if s.eq_ignore_ascii_case("borrow") => Some(1),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    side.ok_or_else(|| serde::de::Error::custom(format!("invalid order side {}", value)))
+}
+
+fn zero_string() -> String {
+    "0".to_string()
+}
+
+/// Parse list rows one at a time, dropping (and counting) rows that fail
+///
+/// With `strict` set (`SUBGRAPH_STRICT=true`), the first malformed row fails
+/// the whole query instead, so schema changes surface immediately.
+fn parse_rows<T: DeserializeOwned>(entity: &str, rows: Vec<Value>, strict: bool) -> ApiResult<Vec<T>> {
+    let total = rows.len();
+    let mut parsed = Vec::with_capacity(total);
+    let mut dropped = 0;
+    let mut first_error = None;
+
+    for row in rows {
+        match serde_json::from_value::<T>(row) {
+            Ok(item) => parsed.push(item),
+            Err(e) if strict => {
+                return Err(ApiError::InvalidResponse {
+                    message: format!("Malformed {} row from subgraph: {}", entity, e),
+                });
+            }
+            Err(e) => {
+                dropped += 1;
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    if let Some(e) = first_error {
+        tracing::warn!(
+            upstream = "subgraph",
+            entity,
+            error = %e,
+            "Dropped {} of {} {} rows that failed to parse (first error: {})",
+            dropped,
+            total,
+            entity,
+            e
+        );
+    }
+    Ok(parsed)
 }
 
 impl SubgraphClient {
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .expect("failed to build subgraph HTTP client"),
-            url: config().subgraph_url.clone(),
+            client: crate::http::shared_client(),
         }
     }
 
-    /// Execute a GraphQL query
+    /// Execute a GraphQL query, failing over to mirror endpoints
+    ///
+    /// Endpoints are tried from the last one that answered (see `failover`);
+    /// only transient failures (network, timeout, 5xx, rate limit) move on.
     async fn query<T: for<'de> Deserialize<'de>>(&self, query: String) -> ApiResult<T> {
         let request = GraphQLRequest {
             query,
             variables: None,
         };
 
+        let endpoints = crate::failover::subgraph();
+        let ordered = endpoints.ordered();
+        let mut last_error = None;
+        for (position, &(index, url)) in ordered.iter().enumerate() {
+            match self.query_url(url, &request).await {
+                Err(e) if e.is_retryable() => {
+                    if let Some((_, next)) = ordered.get(position + 1) {
+                        tracing::warn!(
+                            upstream = "subgraph",
+                            url,
+                            error = %e,
+                            "Subgraph endpoint {} failed, failing over to {}",
+                            url,
+                            next
+                        );
+                    }
+                    last_error = Some(e);
+                }
+                result => {
+                    endpoints.mark_good(index);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| ApiError::Config { message: "no subgraph endpoint configured".to_string() }))
+    }
+
+    /// Execute a GraphQL query against one endpoint
+    async fn query_url<T: for<'de> Deserialize<'de>>(&self, url: &str, request: &GraphQLRequest) -> ApiResult<T> {
         let response = self
             .client
-            .post(&self.url)
-            .json(&request)
-            .send()
+            .post(url)
+            .timeout(crate::http::upstream_timeout())
+            .json(request)
+            .timed_send_to("subgraph", url)
             .await
-            .map_err(|e| ApiError::GraphQLError(format!("Request failed: {}", e)))?;
+            .map_err(|e| {
+                e.into_api_error(crate::http::upstream_timeout(), |e| {
+                    ApiError::network(format!("Subgraph request failed: {}", e))
+                })
+            })?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.map_err(|e| {
                 ApiError::GraphQLError(format!("HTTP {}: failed to read body: {}", status, e))
             })?;
+            // 5xx and rate-limit responses are treated as transient so callers
+            // can retry them or fail over
+            if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(ApiError::network(format!("Subgraph HTTP {}: {}", status, body)));
+            }
             return Err(ApiError::GraphQLError(format!("HTTP {}: {}", status, body)));
         }
 
         let gql_response: GraphQLResponse<T> = response
             .json()
             .await
-            .map_err(|e| ApiError::GraphQLError(format!("Parse error: {}", e)))?;
+            .map_err(|e| ApiError::network(format!("Subgraph parse error: {}", e)))?;
 
         if let Some(errors) = gql_response.errors {
             let error_msg = errors
@@ -190,6 +343,31 @@ impl SubgraphClient {
             .ok_or_else(|| ApiError::GraphQLError("No data in response".to_string()))
     }
 
+    /// Execute a GraphQL query, retrying transient failures with exponential backoff
+    async fn query_with_retry<T: for<'de> Deserialize<'de>>(&self, query: String) -> ApiResult<T> {
+        let mut attempt = 0;
+        loop {
+            match self.query(query.clone()).await {
+                Err(e) if e.is_retryable() && attempt + 1 < MAX_RETRY_ATTEMPTS => {
+                    attempt += 1;
+                    // Exponential backoff: 200ms, 400ms, ...
+                    let backoff_ms = 200 * (1 << (attempt - 1));
+                    tracing::warn!(
+                        upstream = "subgraph",
+                        error = %e,
+                        "Subgraph retry attempt {}/{} after {}ms backoff: {}",
+                        attempt,
+                        MAX_RETRY_ATTEMPTS - 1,
+                        backoff_ms,
+                        e
+                    );
+                    tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Get lending markets (yield curve data)
     pub async fn get_lending_markets(&self) -> ApiResult<Vec<LendingMarket>> {
         let query = r#"
@@ -211,7 +389,7 @@ impl SubgraphClient {
         "#.to_string();
 
         let data: LendingMarketsData = self.query(query).await?;
-        Ok(data.lending_markets)
+        parse_rows("lendingMarkets", data.lending_markets, config().subgraph_strict)
     }
 
     /// Get USDFC order book
@@ -242,11 +420,11 @@ impl SubgraphClient {
             }}
         "#,
             limit,
-            config().currency_usdfc
+            Currency::Usdfc.to_bytes32()
         );
 
         let data: OrdersData = self.query(query).await?;
-        Ok(data.orders)
+        parse_rows("orders", data.orders, config().subgraph_strict)
     }
 
     /// Get recent transactions
@@ -273,16 +451,27 @@ impl SubgraphClient {
         );
 
         let data: TransactionsData = self.query(query).await?;
-        Ok(data.transactions)
+        parse_rows("transactions", data.transactions, config().subgraph_strict)
     }
 
     /// Get daily volume data for historical charts
-    pub async fn get_daily_volumes(&self, days: i32) -> ApiResult<Vec<DailyVolume>> {
-        let query = format!(
-            r#"
+    ///
+    /// Fetches `days` rows in pages, retrying transient failures. If a page after
+    /// the first still fails, the rows fetched so far are returned with
+    /// `complete: false`; a failure on the first page is returned as an error.
+    pub async fn get_daily_volumes(&self, days: i32) -> ApiResult<DailyVolumesFetch> {
+        let mut volumes = Vec::new();
+        let mut complete = true;
+        let mut skip = 0;
+
+        while skip < days {
+            let first = DAILY_VOLUME_PAGE_SIZE.min(days - skip);
+            let query = format!(
+                r#"
             query {{
                 dailyVolumes(
                     first: {}
+                    skip: {}
                     orderBy: timestamp
                     orderDirection: desc
                 ) {{
@@ -295,15 +484,42 @@ impl SubgraphClient {
                 }}
             }}
         "#,
-            days
-        );
+                first, skip
+            );
+
+            let rows = match self.query_with_retry::<DailyVolumesData>(query).await {
+                Ok(data) => data.daily_volumes,
+                Err(e) if skip > 0 => {
+                    tracing::warn!(
+                        upstream = "subgraph",
+                        error = %e,
+                        "Daily volumes fetch stopped after {} rows (requested {}): {}",
+                        volumes.len(),
+                        days,
+                        e
+                    );
+                    return Ok(DailyVolumesFetch { volumes, complete: false });
+                }
+                Err(e) => return Err(e),
+            };
+
+            // Paginate on raw rows so dropped rows don't end the fetch early
+            let page_len = rows.len() as i32;
+            let page: Vec<DailyVolume> = parse_rows("dailyVolumes", rows, config().subgraph_strict)?;
+            complete &= page.len() as i32 == page_len;
+            volumes.extend(page);
+            // A short page means the subgraph has no more history
+            if page_len < first {
+                break;
+            }
+            skip += page_len;
+        }
 
-        let data: DailyVolumesData = self.query(query).await?;
-        Ok(data.daily_volumes)
+        Ok(DailyVolumesFetch { volumes, complete })
     }
 
     /// Get OHLC candlestick data for price charts
-    pub async fn get_candlesticks(&self, currency: &str, limit: i32) -> ApiResult<Vec<TransactionCandleStick>> {
+    pub async fn get_candlesticks(&self, currency: &Currency, limit: i32) -> ApiResult<Vec<TransactionCandleStick>> {
         let query = format!(
             r#"
             query {{
@@ -328,15 +544,15 @@ impl SubgraphClient {
                 }}
             }}
         "#,
-            limit, currency
+            limit, currency.to_bytes32()
         );
 
         let data: CandleSticksData = self.query(query).await?;
-        Ok(data.candlesticks)
+        parse_rows("transactionCandleSticks", data.candlesticks, config().subgraph_strict)
     }
 
     /// Get order book grouped by side (lend=0, borrow=1)
-    pub async fn get_order_book(&self, currency: &str, maturity: Option<&str>, limit: i32) -> ApiResult<OrderBook> {
+    pub async fn get_order_book(&self, currency: &Currency, maturity: Option<&str>, limit: i32) -> ApiResult<OrderBook> {
         let maturity_clause = maturity
             .map(|m| format!(r#", maturity: "{}""#, m))
             .unwrap_or_default();
@@ -368,16 +584,17 @@ impl SubgraphClient {
                 }}
             }}
         "#,
-            limit, currency, maturity_clause
+            limit, currency.to_bytes32(), maturity_clause
         );
 
         let data: OrdersData = self.query(query).await?;
+        let orders: Vec<Order> = parse_rows("orders", data.orders, config().subgraph_strict)?;
 
         // Separate into lend (side=0) and borrow (side=1) orders
         let mut lend_orders = Vec::new();
         let mut borrow_orders = Vec::new();
 
-        for order in data.orders {
+        for order in orders {
             if order.side == 0 {
                 lend_orders.push(order);
             } else {
@@ -403,7 +620,7 @@ impl SubgraphClient {
         });
 
         Ok(OrderBook {
-            currency: currency.to_string(),
+            currency: currency.clone(),
             maturity: maturity.map(|s| s.to_string()),
             lend_orders,
             borrow_orders,
@@ -414,12 +631,22 @@ impl SubgraphClient {
 /// Order book with lend and borrow sides
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
-    pub currency: String,
+    pub currency: Currency,
     pub maturity: Option<String>,
     pub lend_orders: Vec<Order>,
     pub borrow_orders: Vec<Order>,
 }
 
+impl OrderBook {
+    /// Best lend (highest) and borrow (lowest) unit prices, scaled like `OrderData::price`
+    pub fn best_prices(&self) -> (Option<f64>, Option<f64>) {
+        let price = |orders: &[Order]| {
+            orders.first().and_then(|o| o.input_unit_price.parse::<f64>().ok()).map(|p| p / 10000.0)
+        };
+        (price(&self.lend_orders), price(&self.borrow_orders))
+    }
+}
+
 impl Default for SubgraphClient {
     fn default() -> Self {
         Self::new()
@@ -471,24 +698,50 @@ pub fn unit_price_to_apr(_unit_price: &str, _maturity_timestamp: i64) -> ApiResu
     Ok(0.0) // APR is pre-calculated server-side
 }
 
-/// Decode currency bytes32 to string
-pub fn decode_currency(bytes32: &str) -> String {
-    if bytes32 == config().currency_usdfc {
-        return "USDFC".to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lending_market_tolerates_schema_drift() {
+        // Numeric maturity, no id/isActive/volume, and an unknown extra field
+        let rows = vec![json!({
+            "currency": "0x5553444643",
+            "maturity": 1735689600,
+            "lastLendUnitPrice": "9800",
+            "lastBorrowUnitPrice": null,
+            "newField": true
+        })];
+        let markets: Vec<LendingMarket> = parse_rows("lendingMarkets", rows, true).unwrap();
+        assert_eq!(markets[0].maturity, "1735689600");
+        assert!(!markets[0].is_active);
+        assert_eq!(markets[0].volume, None);
     }
-    if bytes32 == config().currency_fil {
-        return "FIL".to_string();
+
+    #[test]
+    fn test_order_side_and_amount_variants() {
+        let rows = vec![
+            json!({ "side": "Borrow", "currency": "c", "maturity": "1", "inputAmount": 100, "inputUnitPrice": "9900" }),
+            json!({ "side": "0", "currency": "c", "maturity": "1", "inputAmount": "5", "inputUnitPrice": 9800, "filledAmount": "2" }),
+        ];
+        let orders: Vec<Order> = parse_rows("orders", rows, true).unwrap();
+        assert_eq!((orders[0].side, orders[0].input_amount.as_str(), orders[0].filled_amount.as_str()), (1, "100", "0"));
+        assert_eq!((orders[1].side, orders[1].input_unit_price.as_str()), (0, "9800"));
     }
-    
-    // Try to decode hex to ASCII
-    let hex = bytes32.trim_start_matches("0x");
-    let mut result = String::new();
-    for i in (0..hex.len()).step_by(2) {
-        if let Ok(byte) = u8::from_str_radix(&hex[i..i+2], 16) {
-            if byte > 0 && byte < 128 {
-                result.push(byte as char);
-            }
-        }
+
+    #[test]
+    fn test_malformed_rows_dropped_or_strict() {
+        let rows = || {
+            vec![
+                json!({ "volume": "10", "timestamp": "1700000000", "day": "19675" }),
+                json!({ "timestamp": "1700086400" }), // volume renamed/missing
+            ]
+        };
+        let lenient: Vec<DailyVolume> = parse_rows("dailyVolumes", rows(), false).unwrap();
+        assert_eq!(lenient.len(), 1);
+        assert_eq!(lenient[0].volume, "10");
+
+        assert!(parse_rows::<DailyVolume>("dailyVolumes", rows(), true).is_err());
     }
-    result.trim().to_string()
 }
@@ -0,0 +1,177 @@
+//! Server function latency instrumentation
+//!
+//! `timed` wraps a server function body, measures its duration and logs a
+//! warning when it exceeds the configured latency budget. Upstream HTTP calls
+//! made through `TimedSend::timed_send` are attributed to the surrounding
+//! server function so the warning can name the upstream that dominated, and
+//! counted in the process-wide `/metrics` exposition.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+use usdfc_core::error::ApiError;
+
+use crate::request_id::{self, RequestTagged};
+
+tokio::task_local! {
+    /// Cumulative time spent per upstream within the current server function
+    static UPSTREAM_TIMINGS: RefCell<HashMap<&'static str, Duration>>;
+}
+
+/// Add elapsed time to an upstream (no-op outside of `timed`)
+pub fn record_upstream(upstream: &'static str, elapsed: Duration) {
+    let _ = UPSTREAM_TIMINGS.try_with(|timings| {
+        *timings.borrow_mut().entry(upstream).or_default() += elapsed;
+    });
+}
+
+/// Get the latency budget for a server function in milliseconds
+pub fn budget_ms(fn_name: &str) -> u64 {
+    let cfg = usdfc_core::config::config();
+    cfg.slow_fn_budgets
+        .get(fn_name)
+        .copied()
+        .unwrap_or(cfg.slow_fn_budget_ms)
+}
+
+/// Run a server function body, warning if it exceeds its latency budget
+///
+/// Upstream calls are logged within a span carrying the request id, and a
+/// failure is logged and tagged with it.
+pub async fn timed<F>(fn_name: &'static str, fut: F) -> F::Output
+where
+    F: Future,
+    F::Output: RequestTagged,
+{
+    let start = Instant::now();
+    let request_id = request_id::current();
+    let span = match &request_id {
+        Some(id) => tracing::info_span!("server_fn", server_fn = fn_name, request_id = %id),
+        None => tracing::info_span!("server_fn", server_fn = fn_name),
+    };
+    let (output, timings) = UPSTREAM_TIMINGS
+        .scope(RefCell::new(HashMap::new()), async {
+            let output = fut.await;
+            let timings = UPSTREAM_TIMINGS.with(|t| t.take());
+            (output, timings)
+        })
+        .instrument(span.clone())
+        .await;
+    let _entered = span.enter();
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    let budget = budget_ms(fn_name);
+    if elapsed_ms > budget {
+        let dominant = timings
+            .iter()
+            .max_by_key(|(_, d)| **d)
+            .map(|(name, d)| format!("{} ({}ms)", name, d.as_millis()))
+            .unwrap_or_else(|| "none".to_string());
+        tracing::warn!(
+            "Slow server function {}: {}ms (budget {}ms), dominant upstream: {}",
+            fn_name,
+            elapsed_ms,
+            budget,
+            dominant
+        );
+    } else {
+        tracing::debug!("Server function {} took {}ms", fn_name, elapsed_ms);
+    }
+
+    output.tag_failure(fn_name, request_id.as_deref())
+}
+
+/// Failure of a request sent through `TimedSend::timed_send`
+#[derive(Debug)]
+pub enum UpstreamError {
+    /// Not sent: the upstream's circuit is open
+    CircuitOpen(&'static str),
+    /// Sent, but no response arrived
+    Http(reqwest::Error),
+}
+
+impl UpstreamError {
+    /// Whether the request was sent and timed out
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Http(e) if e.is_timeout())
+    }
+
+    /// Convert to an `ApiError`, keeping timeouts distinct as `ApiError::Timeout`
+    pub fn into_api_error(self, timeout: Duration, other: impl FnOnce(Self) -> ApiError) -> ApiError {
+        if self.is_timeout() {
+            ApiError::Timeout { duration: timeout }
+        } else {
+            other(self)
+        }
+    }
+}
+
+impl std::fmt::Display for UpstreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CircuitOpen(upstream) => write!(f, "{} circuit open, failing fast", upstream),
+            Self::Http(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for UpstreamError {}
+
+/// Send a request and attribute its duration to an upstream
+///
+/// Fails fast without sending while the upstream's circuit is open.
+pub trait TimedSend {
+    fn timed_send(
+        self,
+        upstream: &'static str,
+    ) -> impl Future<Output = Result<reqwest::Response, UpstreamError>> + Send;
+
+    /// Like `timed_send`, but with the circuit of one endpoint of a failover
+    /// upstream, so an open primary does not fail its fallbacks fast
+    fn timed_send_to(
+        self,
+        upstream: &'static str,
+        url: &str,
+    ) -> impl Future<Output = Result<reqwest::Response, UpstreamError>> + Send;
+}
+
+impl TimedSend for reqwest::RequestBuilder {
+    async fn timed_send(self, upstream: &'static str) -> Result<reqwest::Response, UpstreamError> {
+        send_through_circuit(self, upstream, upstream).await
+    }
+
+    async fn timed_send_to(self, upstream: &'static str, url: &str) -> Result<reqwest::Response, UpstreamError> {
+        send_through_circuit(self, upstream, &crate::circuit_breaker::endpoint_key(upstream, url)).await
+    }
+}
+
+/// Send unless `circuit` is open, timing the request and recording its outcome
+async fn send_through_circuit(
+    request: reqwest::RequestBuilder,
+    upstream: &'static str,
+    circuit: &str,
+) -> Result<reqwest::Response, UpstreamError> {
+    let breaker = &crate::circuit_breaker::UPSTREAM_BREAKER;
+    if !breaker.should_allow(circuit) {
+        return Err(UpstreamError::CircuitOpen(upstream));
+    }
+
+    let start = Instant::now();
+    let result = request.send().await;
+    let elapsed = start.elapsed();
+    record_upstream(upstream, elapsed);
+
+    // Other 4xx responses reflect the request, not upstream health
+    let ok = result.as_ref().is_ok_and(|r| {
+        !r.status().is_server_error() && r.status() != reqwest::StatusCode::TOO_MANY_REQUESTS
+    });
+    crate::metrics::record_upstream_request(upstream, elapsed, ok);
+    if ok {
+        breaker.record_success(circuit);
+    } else {
+        breaker.record_failure(circuit);
+    }
+    result.map_err(UpstreamError::Http)
+}
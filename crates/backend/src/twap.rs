@@ -0,0 +1,69 @@
+//! Time-weighted average price from OHLCV candles
+//!
+//! GeckoTerminal omits candles for intervals without trades, so candles are
+//! unevenly spaced. Each candle's close is treated as the price from its
+//! timestamp until the next candle starts (or the window ends), and weighted
+//! by that duration. A candle opened before the window carries its close into
+//! the window start.
+
+use crate::gecko::OHLCV;
+
+/// Time-weighted average close over `[start, end)`
+///
+/// Returns None when the window is empty or no candle starts before `end`.
+pub fn time_weighted_average(candles: &[OHLCV], start: i64, end: i64) -> Option<f64> {
+    if end <= start {
+        return None;
+    }
+
+    let mut sorted: Vec<&OHLCV> = candles.iter().filter(|c| c.timestamp < end).collect();
+    sorted.sort_by_key(|c| c.timestamp);
+
+    let mut weighted_sum = 0.0;
+    let mut total_secs = 0i64;
+    for (i, candle) in sorted.iter().enumerate() {
+        let held_until = sorted.get(i + 1).map_or(end, |next| next.timestamp);
+        let from = candle.timestamp.max(start);
+        let to = held_until.min(end);
+        if to > from {
+            weighted_sum += candle.close * (to - from) as f64;
+            total_secs += to - from;
+        }
+    }
+
+    (total_secs > 0).then(|| weighted_sum / total_secs as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, close: f64) -> OHLCV {
+        OHLCV { timestamp, open: close, high: close, low: close, close, volume: 0.0 }
+    }
+
+    #[test]
+    fn test_twap_weights_by_interval_not_count() {
+        // 1.00 held for 90s, a 10s spike to 2.00: a naive mean of closes would be 1.50
+        let candles = vec![candle(0, 1.0), candle(90, 2.0)];
+        let twap = time_weighted_average(&candles, 0, 100).unwrap();
+        assert!((twap - 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_twap_handles_uneven_spacing_and_window_edges() {
+        // Unsorted input with gaps; the candle at -50 carries 0.98 into the window
+        let candles = vec![candle(60, 1.02), candle(-50, 0.98), candle(20, 1.00), candle(200, 5.0)];
+        // 0..20 at 0.98, 20..60 at 1.00, 60..100 at 1.02; the candle at 200 is outside
+        let twap = time_weighted_average(&candles, 0, 100).unwrap();
+        let expected = (0.98 * 20.0 + 1.00 * 40.0 + 1.02 * 40.0) / 100.0;
+        assert!((twap - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_twap_without_coverage() {
+        assert_eq!(time_weighted_average(&[], 0, 100), None);
+        assert_eq!(time_weighted_average(&[candle(150, 1.0)], 0, 100), None);
+        assert_eq!(time_weighted_average(&[candle(0, 1.0)], 100, 100), None);
+    }
+}
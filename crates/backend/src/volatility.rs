@@ -0,0 +1,70 @@
+//! Rolling TCR z-score
+//!
+//! Flags TCR instability by comparing the live TCR with the mean and standard
+//! deviation of recorded snapshots over a trailing window. The score is only
+//! meaningful once the window holds enough samples, so a cold start (fresh
+//! deployment, short retention) yields `None` rather than a noisy value.
+
+use crate::historical::METRIC_HISTORY;
+
+/// Fewest snapshots in the window before a z-score is reported
+pub const MIN_ZSCORE_SAMPLES: usize = 30;
+
+/// Mean and population standard deviation of `values`
+pub fn mean_std(values: &[f64]) -> Option<(f64, f64)> {
+    if values.is_empty() {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    Some((mean, variance.sqrt()))
+}
+
+/// Standard score of `current` against the window `values`
+///
+/// None with fewer than `MIN_ZSCORE_SAMPLES` values, or when the window is
+/// flat (zero deviation) so any move would be an infinite score.
+pub fn rolling_zscore(values: &[f64], current: f64) -> Option<f64> {
+    if values.len() < MIN_ZSCORE_SAMPLES {
+        return None;
+    }
+    let (mean, std_dev) = mean_std(values)?;
+    (std_dev > f64::EPSILON).then(|| (current - mean) / std_dev)
+}
+
+/// TCR values of snapshots recorded at or after `since`
+pub fn tcr_window(since: i64) -> Vec<f64> {
+    METRIC_HISTORY
+        .read()
+        .map(|history| {
+            history
+                .iter()
+                .filter(|s| s.timestamp >= since)
+                .map(|s| s.tcr)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zscore_against_window() {
+        // Alternating 150/160: mean 155, std 5
+        let window: Vec<f64> = (0..MIN_ZSCORE_SAMPLES).map(|i| if i % 2 == 0 { 150.0 } else { 160.0 }).collect();
+        let z = rolling_zscore(&window, 145.0).unwrap();
+        assert!((z - -2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_zscore_cold_start_and_flat_window() {
+        let short = vec![150.0, 160.0, 170.0];
+        assert_eq!(rolling_zscore(&short, 200.0), None);
+
+        let flat = vec![150.0; MIN_ZSCORE_SAMPLES];
+        assert_eq!(rolling_zscore(&flat, 150.0), None);
+    }
+}
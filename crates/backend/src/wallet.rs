@@ -0,0 +1,188 @@
+//! Per-wallet USDFC flow analytics
+//!
+//! Shared by wallet analytics and wallet comparison so both resolve the
+//! window and bucket transfers the same way.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use usdfc_core::error::ApiError;
+use usdfc_core::types::{ChartLookback, WalletAnalyticsResponse, WalletBucket};
+
+use crate::blockscout::{BlockscoutClient, TransferWithTimestamp};
+
+/// Lowercase EVM form of a wallet address, converting f4 addresses
+pub fn wallet_evm_address(address: &str) -> Result<String, String> {
+    if address.starts_with('f') {
+        crate::address_conv::f4_to_evm(address).map(|evm| evm.to_lowercase())
+    } else {
+        Ok(address.to_lowercase())
+    }
+}
+
+/// `(start, end)` of the analysed window in Unix seconds
+///
+/// A custom `start` wins over the lookback; an "all" lookback starts at zero.
+pub fn wallet_window(lookback: ChartLookback, start: Option<i64>, end: Option<i64>, now: i64) -> (i64, i64) {
+    match start {
+        Some(start) => (start, end.unwrap_or(now)),
+        None if lookback.minutes() == 0 => (0, now),
+        None => (now.saturating_sub(lookback.minutes() as i64 * 60), now),
+    }
+}
+
+/// One wallet's transfers in a window, summed per bucket
+#[derive(Clone, Debug, Default)]
+pub struct WalletFlows {
+    /// Time-ordered buckets; `net_flow` and `running_balance` are left at zero
+    pub buckets: Vec<WalletBucket>,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+    pub first_seen: Option<i64>,
+    pub last_active: Option<i64>,
+}
+
+/// Bucket the window's transfers to or from `wallet_evm`
+///
+/// Raw amounts are scaled by the token's `decimals`; transfers with
+/// unparseable amounts are skipped.
+pub fn wallet_flows(
+    wallet_evm: &str,
+    transfers: &[TransferWithTimestamp],
+    (window_start, window_end): (i64, i64),
+    bucket_secs: i64,
+    decimals: u32,
+) -> WalletFlows {
+    let bucket_secs = bucket_secs.max(60);
+    let mut flows = WalletFlows::default();
+    // bucket start -> (volume in, volume out, count in, count out)
+    let mut buckets: BTreeMap<i64, (Decimal, Decimal, u64, u64)> = BTreeMap::new();
+
+    for t in transfers {
+        if t.timestamp < window_start || t.timestamp > window_end {
+            continue;
+        }
+        let is_incoming = t.to_address.eq_ignore_ascii_case(wallet_evm);
+        let is_outgoing = t.from_address.eq_ignore_ascii_case(wallet_evm);
+        if !is_incoming && !is_outgoing {
+            continue;
+        }
+
+        flows.first_seen = Some(flows.first_seen.map_or(t.timestamp, |ts| ts.min(t.timestamp)));
+        flows.last_active = Some(flows.last_active.map_or(t.timestamp, |ts| ts.max(t.timestamp)));
+
+        let Some(value) = usdfc_core::format::parse_token_amount(&t.amount, decimals) else {
+            continue;
+        };
+        let bucket_ts = (t.timestamp / bucket_secs) * bucket_secs;
+        let entry = buckets.entry(bucket_ts).or_default();
+
+        if is_incoming {
+            entry.0 += value;
+            entry.2 += 1;
+        }
+        if is_outgoing {
+            entry.1 += value;
+            entry.3 += 1;
+        }
+    }
+
+    for (timestamp, (volume_in, volume_out, count_in, count_out)) in buckets {
+        flows.total_in += volume_in;
+        flows.total_out += volume_out;
+        flows.buckets.push(WalletBucket {
+            timestamp,
+            volume_in: volume_in.to_f64().unwrap_or(0.0),
+            volume_out: volume_out.to_f64().unwrap_or(0.0),
+            count_in,
+            count_out,
+            net_flow: 0.0,
+            running_balance: 0.0,
+        });
+    }
+    flows
+}
+
+/// Wallet analytics for `address` over the window
+///
+/// `live` windows end now, so the starting balance can be estimated from the
+/// current Blockscout balance.
+pub async fn wallet_analytics(
+    blockscout: &BlockscoutClient,
+    address: String,
+    wallet_evm: &str,
+    transfers: &[TransferWithTimestamp],
+    window: (i64, i64),
+    bucket_secs: i64,
+    live: bool,
+) -> WalletAnalyticsResponse {
+    let decimals = crate::blockscout::usdfc_decimals(blockscout).await;
+    let flows = wallet_flows(wallet_evm, transfers, window, bucket_secs, decimals);
+
+    // Work back from the current balance; flows after a past window end are not fetched
+    let starting_balance = if live && !flows.buckets.is_empty() {
+        let token_address = &usdfc_core::config::config().usdfc_token;
+        match blockscout.get_address_token_balance(wallet_evm, token_address).await {
+            Ok(balance) => (balance - (flows.total_in - flows.total_out)).to_f64(),
+            // Blockscout omits tokens the address no longer holds
+            Err(ApiError::NotFound { .. }) => (flows.total_out - flows.total_in).to_f64(),
+            Err(e) => {
+                tracing::warn!("Balance lookup failed for wallet analytics of {}: {}", wallet_evm, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let date = |ts: i64| chrono::DateTime::from_timestamp(ts, 0).map(|dt| dt.format("%Y-%m-%d").to_string());
+    let mut analytics = WalletAnalyticsResponse {
+        address,
+        buckets: flows.buckets,
+        total_in: flows.total_in.to_f64().unwrap_or(0.0),
+        total_out: flows.total_out.to_f64().unwrap_or(0.0),
+        first_seen: flows.first_seen.and_then(date),
+        last_active: flows.last_active.and_then(date),
+        starting_balance,
+    };
+    analytics.accumulate_balances();
+    analytics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(from: &str, to: &str, amount: &str, timestamp: i64) -> TransferWithTimestamp {
+        TransferWithTimestamp {
+            id: String::new(),
+            from_address: from.to_string(),
+            to_address: to.to_string(),
+            amount: amount.to_string(),
+            block_number: 0,
+            transaction_hash: String::new(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_wallet_flows_bucket_in_and_out() {
+        let transfers = [
+            transfer("0xother", "0xABC", "5000000000000000000", 3_610),
+            transfer("0xabc", "0xother", "2000000000000000000", 3_700),
+            transfer("0xabc", "0xother", "1000000000000000000", 7_300),
+            // Outside the window, and unrelated
+            transfer("0xother", "0xabc", "9000000000000000000", 100),
+            transfer("0xother", "0xelse", "9000000000000000000", 3_650),
+        ];
+        let flows = wallet_flows("0xabc", &transfers, (3_600, 8_000), 3_600, 18);
+
+        assert_eq!(flows.buckets.len(), 2);
+        assert_eq!(flows.buckets[0].timestamp, 3_600);
+        assert_eq!((flows.buckets[0].volume_in, flows.buckets[0].volume_out), (5.0, 2.0));
+        assert_eq!((flows.buckets[1].count_in, flows.buckets[1].count_out), (0, 1));
+        assert_eq!(flows.total_in, Decimal::from(5));
+        assert_eq!(flows.total_out, Decimal::from(3));
+        assert_eq!((flows.first_seen, flows.last_active), (Some(3_610), Some(7_300)));
+    }
+}
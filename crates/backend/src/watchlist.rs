@@ -0,0 +1,150 @@
+//! Address watchlists
+//!
+//! Users can save addresses they are tracking so the list survives reloads and
+//! follows them across devices. Entries live in the `watchlist` table of the
+//! metrics database, keyed by an opaque client-provided token and the
+//! lowercased address. There is no authentication: whoever knows a token can
+//! read and edit its list, and calls without a token all share one list.
+
+use rusqlite::{params, Connection};
+use usdfc_core::types::{WatchEntry, WATCH_LABEL_MAX_LEN};
+
+use crate::historical::DB_CONN;
+
+/// Maximum length of a watchlist token, in characters
+pub const WATCH_TOKEN_MAX_LEN: usize = 128;
+
+/// Create the watchlist table
+pub fn init_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watchlist (
+            token TEXT NOT NULL,
+            address TEXT NOT NULL,
+            label TEXT NOT NULL,
+            added_at INTEGER NOT NULL,
+            PRIMARY KEY (token, address)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Validate a client token and return the key lists are stored under
+///
+/// A missing or blank token selects the shared list (stored under "").
+pub fn normalize_token(token: Option<&str>) -> Result<String, String> {
+    let token = token.map(str::trim).unwrap_or_default();
+    if token.chars().count() > WATCH_TOKEN_MAX_LEN {
+        return Err(format!("Watchlist token must be at most {} characters", WATCH_TOKEN_MAX_LEN));
+    }
+    if !token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Watchlist token may only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(token.to_string())
+}
+
+/// Validate label text, returning the trimmed label
+pub fn validate_label(label: &str) -> Result<String, String> {
+    let label = label.trim();
+    if label.chars().count() > WATCH_LABEL_MAX_LEN {
+        return Err(format!("Label must be at most {} characters", WATCH_LABEL_MAX_LEN));
+    }
+    if label.chars().any(char::is_control) {
+        return Err("Label must be a single line of text".to_string());
+    }
+    Ok(label.to_string())
+}
+
+/// Entries on a watchlist, oldest first
+pub fn get_entries(token: &str) -> Result<Vec<WatchEntry>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in watchlist::get_entries: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT address, label, added_at FROM watchlist WHERE token = ?1 ORDER BY added_at, address",
+    )?;
+    let entries = stmt
+        .query_map(params![token], |row| {
+            Ok(WatchEntry {
+                address: row.get(0)?,
+                label: row.get(1)?,
+                added_at: row.get(2)?,
+            })
+        })?
+        .collect();
+    entries
+}
+
+/// Add an address to a watchlist, or relabel it if already present
+///
+/// Relabelling keeps the original `added_at`.
+pub fn add_entry(token: &str, address: &str, label: &str) -> Result<WatchEntry, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in watchlist::add_entry: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Err(rusqlite::Error::InvalidQuery);
+    };
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    conn.query_row(
+        "INSERT INTO watchlist (token, address, label, added_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(token, address) DO UPDATE SET label = excluded.label
+         RETURNING address, label, added_at",
+        params![token, address, label, now],
+        |row| {
+            Ok(WatchEntry {
+                address: row.get(0)?,
+                label: row.get(1)?,
+                added_at: row.get(2)?,
+            })
+        },
+    )
+}
+
+/// Remove an address from a watchlist, returning whether it was there
+pub fn remove_entry(token: &str, address: &str) -> Result<bool, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in watchlist::remove_entry: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        return Err(rusqlite::Error::InvalidQuery);
+    };
+
+    let removed = conn.execute(
+        "DELETE FROM watchlist WHERE token = ?1 AND address = ?2",
+        params![token, address],
+    )?;
+    Ok(removed > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_and_label_validation() {
+        assert_eq!(normalize_token(None).unwrap(), "");
+        assert_eq!(normalize_token(Some("  ")).unwrap(), "");
+        assert_eq!(normalize_token(Some(" my-list_1 ")).unwrap(), "my-list_1");
+        assert!(normalize_token(Some("a b")).is_err());
+        assert!(normalize_token(Some(&"x".repeat(WATCH_TOKEN_MAX_LEN + 1))).is_err());
+
+        assert_eq!(validate_label("  market maker ").unwrap(), "market maker");
+        assert!(validate_label(&"x".repeat(WATCH_LABEL_MAX_LEN + 1)).is_err());
+        assert!(validate_label("two\nlines").is_err());
+    }
+}
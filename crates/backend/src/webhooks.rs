@@ -0,0 +1,382 @@
+//! Outbound alert webhooks
+//!
+//! Webhook targets live in the `webhooks` table of the metrics database and are
+//! synced from `ALERT_WEBHOOKS` on startup; URLs no longer listed there are
+//! disabled. When the snapshot collector fires an `AlertEvent`, each enabled
+//! webhook receives a POST:
+//!
+//! - Slack URLs (`hooks.slack.com`) get `{"text": ...}`
+//! - Discord URLs (`discord.com/api/webhooks`) get `{"content": ...}`
+//! - Anything else gets `{"event": AlertEvent}`
+//!
+//! With a secret, the body is signed with HMAC-SHA256 and sent as
+//! `X-USDFC-Signature: sha256=<hex>`. Failed deliveries are retried with
+//! exponential backoff and every delivery is recorded in `webhook_deliveries`.
+
+use std::time::Duration;
+
+use ring::hmac;
+use rusqlite::{params, Connection};
+use usdfc_core::config::config;
+use usdfc_core::types::AlertEvent;
+
+use crate::historical::DB_CONN;
+
+/// Header carrying the payload signature
+pub const SIGNATURE_HEADER: &str = "X-USDFC-Signature";
+
+/// Maximum delivery attempts per webhook and event
+const MAX_DELIVERY_ATTEMPTS: u32 = 4;
+
+/// Timeout for each delivery attempt
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Payload shape sent to a webhook
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebhookFormat {
+    Json,
+    Slack,
+    Discord,
+}
+
+impl WebhookFormat {
+    /// Infer the payload format from the webhook URL
+    pub fn from_url(url: &str) -> Self {
+        if url.contains("hooks.slack.com") {
+            Self::Slack
+        } else if url.contains("discord.com/api/webhooks") || url.contains("discordapp.com/api/webhooks") {
+            Self::Discord
+        } else {
+            Self::Json
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Slack => "slack",
+            Self::Discord => "discord",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "slack" => Self::Slack,
+            "discord" => Self::Discord,
+            _ => Self::Json,
+        }
+    }
+
+    /// Build the JSON payload for an event
+    pub fn payload(&self, event: &AlertEvent) -> serde_json::Value {
+        match self {
+            Self::Json => serde_json::json!({ "event": event }),
+            Self::Slack => serde_json::json!({ "text": event.message }),
+            Self::Discord => serde_json::json!({ "content": event.message }),
+        }
+    }
+}
+
+/// A configured webhook target
+#[derive(Clone, Debug)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    /// HMAC secret; empty means payloads are sent unsigned
+    pub secret: String,
+    pub format: WebhookFormat,
+}
+
+/// Result of delivering one event to one webhook
+#[derive(Clone, Debug)]
+pub struct DeliveryOutcome {
+    pub delivered: bool,
+    pub attempts: u32,
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Create the webhook tables
+pub fn init_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhooks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            url TEXT NOT NULL UNIQUE,
+            secret TEXT NOT NULL DEFAULT '',
+            format TEXT NOT NULL DEFAULT 'json',
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhook_deliveries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id INTEGER NOT NULL REFERENCES webhooks(id),
+            event_kind TEXT NOT NULL,
+            event_timestamp INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            attempts INTEGER NOT NULL,
+            response_status INTEGER,
+            error TEXT,
+            delivered_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Make the enabled webhooks match `ALERT_WEBHOOKS`
+pub fn sync_from_config(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let cfg = config();
+    sync_urls(conn, &cfg.alert_webhooks, &cfg.alert_webhook_secret)?;
+    if !cfg.alert_webhooks.is_empty() {
+        tracing::info!("Synced {} alert webhooks from config", cfg.alert_webhooks.len());
+    }
+    Ok(())
+}
+
+/// Upsert `urls` as enabled webhooks and disable every other row
+///
+/// Removed webhooks are disabled rather than deleted so their delivery
+/// history is kept; listing a URL again re-enables it.
+fn sync_urls(conn: &Connection, urls: &[String], secret: &str) -> Result<(), rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+    let previously_enabled: Vec<String> = tx
+        .prepare("SELECT url FROM webhooks WHERE enabled = 1")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    tx.execute("UPDATE webhooks SET enabled = 0 WHERE enabled = 1", [])?;
+    for url in urls {
+        tx.execute(
+            "INSERT INTO webhooks (url, secret, format, enabled, created_at)
+             VALUES (?1, ?2, ?3, 1, ?4)
+             ON CONFLICT(url) DO UPDATE SET secret = excluded.secret, format = excluded.format, enabled = 1",
+            params![url, secret, WebhookFormat::from_url(url).as_str(), now_secs()],
+        )?;
+    }
+    tx.commit()?;
+
+    let removed = previously_enabled.iter().filter(|url| !urls.contains(url)).count();
+    if removed > 0 {
+        tracing::info!("Disabled {} alert webhooks no longer in config", removed);
+    }
+    Ok(())
+}
+
+/// Load enabled webhooks from the database
+///
+/// Without a database the webhooks from `ALERT_WEBHOOKS` are used directly,
+/// so alerts still go out; deliveries are just not recorded.
+fn load_enabled() -> Result<Vec<Webhook>, rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in webhooks::load_enabled: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    let Some(ref conn) = *db_lock else {
+        let cfg = config();
+        return Ok(cfg
+            .alert_webhooks
+            .iter()
+            .map(|url| Webhook {
+                id: 0,
+                url: url.clone(),
+                secret: cfg.alert_webhook_secret.clone(),
+                format: WebhookFormat::from_url(url),
+            })
+            .collect());
+    };
+
+    let mut stmt = conn.prepare("SELECT id, url, secret, format FROM webhooks WHERE enabled = 1")?;
+    let webhooks = stmt
+        .query_map([], |row| {
+            Ok(Webhook {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                secret: row.get(2)?,
+                format: WebhookFormat::parse(&row.get::<_, String>(3)?),
+            })
+        })?
+        .collect();
+    webhooks
+}
+
+/// Record a delivery attempt
+fn record_delivery(webhook: &Webhook, event: &AlertEvent, outcome: &DeliveryOutcome) -> Result<(), rusqlite::Error> {
+    let db_lock = DB_CONN.lock().map_err(|e| {
+        tracing::error!("Mutex poison error in webhooks::record_delivery: {}", e);
+        rusqlite::Error::InvalidQuery
+    })?;
+
+    if let Some(ref conn) = *db_lock {
+        conn.execute(
+            "INSERT INTO webhook_deliveries
+             (webhook_id, event_kind, event_timestamp, status, attempts, response_status, error, delivered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                webhook.id,
+                format!("{:?}", event.kind),
+                event.timestamp,
+                if outcome.delivered { "delivered" } else { "failed" },
+                outcome.attempts,
+                outcome.response_status,
+                outcome.error,
+                now_secs(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Hex-encoded HMAC-SHA256 of a payload
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hex::encode(hmac::sign(&key, body).as_ref())
+}
+
+/// POST an event to a webhook, retrying network errors, 429 and 5xx responses
+pub async fn deliver(client: &reqwest::Client, webhook: &Webhook, event: &AlertEvent) -> DeliveryOutcome {
+    let body = webhook.format.payload(event).to_string();
+    let mut outcome = DeliveryOutcome {
+        delivered: false,
+        attempts: 0,
+        response_status: None,
+        error: None,
+    };
+
+    while outcome.attempts < MAX_DELIVERY_ATTEMPTS {
+        if outcome.attempts > 0 {
+            // Exponential backoff: 1s, 2s, 4s
+            let backoff_secs = 1u64 << (outcome.attempts - 1);
+            tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
+        }
+        outcome.attempts += 1;
+
+        let mut request = client
+            .post(&webhook.url)
+            .timeout(DELIVERY_TIMEOUT)
+            .header("Content-Type", "application/json")
+            .body(body.clone());
+        if !webhook.secret.is_empty() {
+            request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(&webhook.secret, body.as_bytes())));
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                outcome.response_status = Some(status.as_u16());
+                if status.is_success() {
+                    outcome.delivered = true;
+                    outcome.error = None;
+                    return outcome;
+                }
+                outcome.error = Some(format!("HTTP {}", status));
+                let retryable = status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+                if !retryable {
+                    return outcome;
+                }
+            }
+            Err(e) => {
+                outcome.response_status = None;
+                outcome.error = Some(e.to_string());
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Deliver events to all enabled webhooks in the background
+pub fn dispatch(events: Vec<AlertEvent>) {
+    if events.is_empty() {
+        return;
+    }
+
+    let webhooks = match load_enabled() {
+        Ok(webhooks) if !webhooks.is_empty() => webhooks,
+        Ok(_) => return,
+        Err(e) => {
+            tracing::error!("Failed to load alert webhooks: {}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        let client = crate::http::shared_client();
+        for event in &events {
+            for webhook in &webhooks {
+                let outcome = deliver(&client, webhook, event).await;
+                if outcome.delivered {
+                    tracing::info!("Delivered {:?} alert to webhook #{}", event.kind, webhook.id);
+                } else {
+                    tracing::warn!(
+                        "Failed to deliver {:?} alert to webhook #{} after {} attempts: {}",
+                        event.kind,
+                        webhook.id,
+                        outcome.attempts,
+                        outcome.error.as_deref().unwrap_or("unknown error")
+                    );
+                }
+                if let Err(e) = record_delivery(webhook, event, &outcome) {
+                    tracing::error!("Failed to record webhook delivery: {}", e);
+                }
+            }
+        }
+    });
+}
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_matches_rfc4231() {
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_sync_disables_webhooks_removed_from_config() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_tables(&conn).unwrap();
+        let enabled = |conn: &Connection| {
+            let mut stmt = conn.prepare("SELECT url FROM webhooks WHERE enabled = 1 ORDER BY url").unwrap();
+            stmt.query_map([], |row| row.get::<_, String>(0)).unwrap().collect::<Result<Vec<_>, _>>().unwrap()
+        };
+
+        sync_urls(&conn, &["https://a.example/hook".into(), "https://b.example/hook".into()], "").unwrap();
+        assert_eq!(enabled(&conn), ["https://a.example/hook", "https://b.example/hook"]);
+
+        sync_urls(&conn, &["https://b.example/hook".into()], "").unwrap();
+        assert_eq!(enabled(&conn), ["https://b.example/hook"]);
+
+        sync_urls(&conn, &["https://a.example/hook".into()], "").unwrap();
+        assert_eq!(enabled(&conn), ["https://a.example/hook"]);
+        let rows: i64 = conn.query_row("SELECT COUNT(*) FROM webhooks", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 2);
+    }
+
+    #[test]
+    fn test_format_from_url() {
+        assert_eq!(WebhookFormat::from_url("https://hooks.slack.com/services/T/B/X"), WebhookFormat::Slack);
+        assert_eq!(WebhookFormat::from_url("https://discord.com/api/webhooks/1/abc"), WebhookFormat::Discord);
+        assert_eq!(WebhookFormat::from_url("https://ops.example.com/hook"), WebhookFormat::Json);
+    }
+}
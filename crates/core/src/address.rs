@@ -0,0 +1,153 @@
+//! EVM address checksums (EIP-55)
+//!
+//! A checksummed address uppercases each hex letter whose nibble in the
+//! Keccak-256 hash of the lowercase address is 8 or more. All-lowercase and
+//! all-uppercase addresses carry no checksum and are accepted as they are.
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808A, 0x8000000080008000,
+    0x000000000000808B, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008A, 0x0000000000000088, 0x0000000080008009, 0x000000008000000A,
+    0x000000008000808B, 0x800000000000008B, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800A, 0x800000008000000A,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+/// Rotation offsets indexed by lane `x + 5 * y`
+const ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27,
+    36, 44, 6, 55, 20,
+    3, 10, 43, 25, 39,
+    41, 45, 15, 21, 8,
+    18, 2, 61, 56, 14,
+];
+
+fn keccak_f(state: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+        // rho and pi
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = state[x + 5 * y].rotate_left(ROTATIONS[x + 5 * y]);
+            }
+        }
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        // iota
+        state[0] ^= rc;
+    }
+}
+
+/// Keccak-256 as used by Ethereum (original padding, not SHA3-256)
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    const RATE: usize = 136;
+    let mut padded = data.to_vec();
+    padded.push(0x01);
+    padded.resize(padded.len().div_ceil(RATE) * RATE, 0);
+    *padded.last_mut().expect("padded input is never empty") |= 0x80;
+
+    let mut state = [0u64; 25];
+    for block in padded.chunks(RATE) {
+        for (lane, bytes) in state.iter_mut().zip(block.chunks(8)) {
+            *lane ^= u64::from_le_bytes(bytes.try_into().expect("8-byte lane"));
+        }
+        keccak_f(&mut state);
+    }
+
+    let mut out = [0u8; 32];
+    for (chunk, lane) in out.chunks_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    out
+}
+
+/// EIP-55 checksummed form of a `0x` + 40 hex digit address
+pub fn to_checksummed(addr: &str) -> Result<String, String> {
+    let hex = addr.strip_prefix("0x").ok_or_else(|| "missing 0x prefix".to_string())?;
+    if hex.len() != 40 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("expected 40 hex digits".to_string());
+    }
+    let lower = hex.to_ascii_lowercase();
+    let hash = keccak256(lower.as_bytes());
+
+    let mut out = String::with_capacity(42);
+    out.push_str("0x");
+    for (i, c) in lower.chars().enumerate() {
+        let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+        out.push(if nibble >= 8 { c.to_ascii_uppercase() } else { c });
+    }
+    Ok(out)
+}
+
+/// Whether a well-formed address's letter case is consistent with EIP-55
+///
+/// Single-case addresses have no checksum to verify and pass.
+pub fn has_valid_checksum(addr: &str) -> bool {
+    let Some(hex) = addr.strip_prefix("0x") else {
+        return false;
+    };
+    let has_lower = hex.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = hex.bytes().any(|b| b.is_ascii_uppercase());
+    if !(has_lower && has_upper) {
+        return true;
+    }
+    to_checksummed(addr).is_ok_and(|checksummed| checksummed == addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak256_vectors() {
+        let hex = |bytes: [u8; 32]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        assert_eq!(hex(keccak256(b"")), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+        assert_eq!(hex(keccak256(b"abc")), "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45");
+        // Longer than one 136-byte block
+        assert_eq!(
+            hex(keccak256(&[b'a'; 200])),
+            "96ea54061def936c4be90b518992fdc6f12f535068a256229aca54267b4d084d"
+        );
+    }
+
+    #[test]
+    fn test_eip55_vectors() {
+        for addr in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+            "0x52908400098527886E0F7030069857D2E4169EE7",
+            "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+            "0xde709f2102306220921060314715629080e2fb77",
+            "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        ] {
+            assert_eq!(to_checksummed(&addr.to_lowercase()).as_deref(), Ok(addr));
+            assert!(has_valid_checksum(addr), "{}", addr);
+        }
+    }
+
+    #[test]
+    fn test_checksum_rejects_mixed_case_typos() {
+        assert!(has_valid_checksum("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+        assert!(has_valid_checksum("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"));
+        assert!(!has_valid_checksum("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"));
+        assert!(to_checksummed("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_err());
+        assert!(to_checksummed("0x5aaeb6").is_err());
+    }
+}
@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
+use crate::types::{ChartResolution, DataStaleness, LookbackOverrides};
+
 /// Global application configuration
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -7,8 +10,19 @@ pub struct Config {
     pub rpc_url: String,
     pub rpc_fallback_urls: Vec<String>,
     pub subgraph_url: String,
+    /// Subgraph mirrors tried in order when `subgraph_url` fails
+    pub subgraph_fallback_urls: Vec<String>,
+    /// Fail subgraph queries on malformed rows instead of dropping them
+    pub subgraph_strict: bool,
     pub blockscout_url: String,
     pub geckoterminal_url: String,
+    /// Block explorer origin used for address and transaction links
+    pub explorer_url: String,
+
+    // Data Sources (RPC is always enabled)
+    pub blockscout_enabled: bool,
+    pub subgraph_enabled: bool,
+    pub gecko_enabled: bool,
     
     // Contract Addresses
     pub usdfc_token: String,
@@ -28,6 +42,8 @@ pub struct Config {
     pub pool_usdfc_wfil: String,
     pub pool_usdfc_axlusdc: String,
     pub pool_usdfc_usdc: String,
+    /// Pools averaged into the USDFC price; empty means the USDFC/WFIL pool only
+    pub price_pools: Vec<String>,
     
     // Server Config
     pub host: String,
@@ -42,14 +58,290 @@ pub struct Config {
     pub tcr_danger_threshold: f64,
     pub tcr_warning_threshold: f64,
     pub whale_threshold_usd: f64,
+    pub stability_flow_windows_hours: Vec<u64>,
+    /// Ascending ICR bounds (percent) separating the ICR distribution buckets
+    pub icr_bucket_bounds: Vec<f64>,
+    /// Age in seconds after which a displayed value is dimmed as stale
+    pub stale_data_secs: u64,
+    /// Age in seconds after which stale data gets a stronger warning
+    pub stale_data_warning_secs: u64,
+    /// Trailing window in minutes for the TCR z-score volatility indicator
+    pub tcr_zscore_window_mins: u32,
+    /// USD price USDFC is pegged to
+    pub peg_target: f64,
+    /// Deviation from the peg, in percent, that raises a depeg warning
+    pub depeg_warning_pct: f64,
+    /// Deviation from the peg, in percent, that is critical
+    pub depeg_critical_pct: f64,
 
     // Timing
     pub refresh_interval_ms: u64,
     pub history_retention_secs: u64,
+    /// Age in seconds up to which snapshots keep full resolution
+    pub history_raw_retention_secs: u64,
+    /// Bucket in seconds that older snapshots are averaged into
+    pub history_rollup_secs: u64,
+    pub snapshot_interval_secs: u64,
+    pub backfill_on_start: bool,
+
+    // Upstream HTTP
+    /// Per-request timeout for Blockscout, the subgraph and GeckoTerminal
+    pub upstream_timeout_secs: u64,
 
     // RPC Settings
+    /// Per-request RPC timeout; defaults to `upstream_timeout_secs`
     pub rpc_timeout_secs: u64,
     pub rpc_retry_count: u32,
+
+    // Upstream circuit breaker
+    /// Failures within a minute that open an upstream's circuit
+    pub circuit_failure_threshold: u32,
+    /// Seconds an open circuit waits before admitting half-open probes
+    pub circuit_open_secs: u64,
+    /// Successful probes needed in half-open before the circuit closes
+    pub circuit_half_open_probes: u32,
+
+    // Server-side Cache TTLs
+    pub cache_ttls: CacheTtls,
+
+    // Per-resolution chart lookback limits (minutes), for higher-limit OHLCV sources
+    pub chart_lookback_overrides: LookbackOverrides,
+
+    // Server Function Latency Budgets
+    pub slow_fn_budget_ms: u64,
+    pub slow_fn_budgets: HashMap<String, u64>,
+
+    // Content Security Policy
+    pub csp_script_src: Vec<String>,
+    pub csp_connect_src: Vec<String>,
+    pub csp_nonce: bool,
+
+    // Alert Webhooks
+    pub alert_webhooks: Vec<String>,
+    pub alert_webhook_secret: String,
+
+    // Lending page: markets highlighted at the top, by maturity timestamp or currency
+    pub featured_markets: Vec<String>,
+
+    // Address notes: bearer token required to write notes; empty disables writes
+    pub address_notes_token: String,
+
+    // Alert history: bearer token required to acknowledge or dismiss alerts
+    pub alerts_admin_token: String,
+
+    // Logging: mask API keys and URL credentials in log output
+    pub log_redaction: bool,
+    pub log_format: LogFormat,
+
+    // Routing: page `/` redirects to, and path prefixes behind basic auth
+    pub default_route: String,
+    pub protected_routes: Vec<String>,
+    pub basic_auth_user: String,
+    pub basic_auth_password: String,
+
+    // REST API rate limiting per client IP; 0 requests per minute disables it
+    pub api_rate_limit_per_min: u32,
+    pub api_rate_limit_burst: u32,
+    /// Key clients by the first `X-Forwarded-For` address (only behind a trusted proxy)
+    pub trust_forwarded_for: bool,
+    /// Origins allowed to call the REST API cross-origin; empty allows any
+    pub cors_allowed_origins: Vec<String>,
+
+    // Push feeds (/ws/metrics, /sse/transactions): open connections across
+    // both, and frames queued per connection before a slow client is cut off
+    pub feed_max_connections: usize,
+    pub feed_send_buffer: usize,
+
+    // Branding and embeds
+    pub brand_name: String,
+    /// Icon `/favicon.ico` redirects to; empty serves no icon
+    pub favicon_url: String,
+    /// Origins allowed to frame `/embed/*` widgets; empty allows any
+    pub embed_allowed_origins: Vec<String>,
+
+    /// Invalid settings that fell back to defaults, logged once tracing is up
+    pub load_warnings: Vec<String>,
+}
+
+/// Server-side cache TTLs in seconds, one per cached data type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheTtls {
+    pub protocol_metrics: u64,
+    pub troves: u64,
+    pub trove_leaderboard: u64,
+    pub usdfc_price: u64,
+    pub lending_markets: u64,
+    pub token_holders: u64,
+    pub holder_count: u64,
+    pub advanced_chart_data: u64,
+    pub recent_transactions: u64,
+    pub address_info: u64,
+    pub daily_volumes: u64,
+    pub stability_transfers: u64,
+    pub order_book: u64,
+    pub lending_trades: u64,
+    pub twap: u64,
+    pub chart_image: u64,
+    pub fee_analytics: u64,
+    pub trove_by_address: u64,
+    pub liquidations: u64,
+    pub network_gas: u64,
+    pub stability_depositors: u64,
+    pub icr_distribution: u64,
+    pub token_info: u64,
+}
+
+impl Default for CacheTtls {
+    fn default() -> Self {
+        Self {
+            protocol_metrics: 15,
+            troves: 30,
+            trove_leaderboard: 30,
+            usdfc_price: 30,
+            lending_markets: 60,
+            token_holders: 300,
+            holder_count: 300,
+            advanced_chart_data: 30,
+            recent_transactions: 10,
+            address_info: 30,
+            daily_volumes: 300,
+            stability_transfers: 30,
+            order_book: 5,
+            lending_trades: 30,
+            twap: 30,
+            chart_image: 30,
+            fee_analytics: 300,
+            trove_by_address: 120,
+            liquidations: 60,
+            network_gas: 30,
+            stability_depositors: 120,
+            icr_distribution: 120,
+            token_info: 300,
+        }
+    }
+}
+
+impl CacheTtls {
+    /// Cache names, as used in CACHE_TTLS and the per-cache env vars
+    pub const NAMES: [&'static str; 23] = [
+        "protocol_metrics",
+        "troves",
+        "trove_leaderboard",
+        "usdfc_price",
+        "lending_markets",
+        "token_holders",
+        "holder_count",
+        "advanced_chart_data",
+        "recent_transactions",
+        "address_info",
+        "daily_volumes",
+        "stability_transfers",
+        "order_book",
+        "lending_trades",
+        "twap",
+        "chart_image",
+        "fee_analytics",
+        "trove_by_address",
+        "liquidations",
+        "network_gas",
+        "stability_depositors",
+        "icr_distribution",
+        "token_info",
+    ];
+
+    fn slot_mut(&mut self, name: &str) -> Option<&mut u64> {
+        Some(match name {
+            "protocol_metrics" => &mut self.protocol_metrics,
+            "troves" => &mut self.troves,
+            "trove_leaderboard" => &mut self.trove_leaderboard,
+            "usdfc_price" => &mut self.usdfc_price,
+            "lending_markets" => &mut self.lending_markets,
+            "token_holders" => &mut self.token_holders,
+            "holder_count" => &mut self.holder_count,
+            "advanced_chart_data" => &mut self.advanced_chart_data,
+            "recent_transactions" => &mut self.recent_transactions,
+            "address_info" => &mut self.address_info,
+            "daily_volumes" => &mut self.daily_volumes,
+            "stability_transfers" => &mut self.stability_transfers,
+            "order_book" => &mut self.order_book,
+            "lending_trades" => &mut self.lending_trades,
+            "twap" => &mut self.twap,
+            "chart_image" => &mut self.chart_image,
+            "fee_analytics" => &mut self.fee_analytics,
+            "trove_by_address" => &mut self.trove_by_address,
+            "liquidations" => &mut self.liquidations,
+            "network_gas" => &mut self.network_gas,
+            "stability_depositors" => &mut self.stability_depositors,
+            "icr_distribution" => &mut self.icr_distribution,
+            "token_info" => &mut self.token_info,
+            _ => return None,
+        })
+    }
+
+    /// Env var overriding one cache's TTL, e.g. `USDFC_CACHE_TTL_PROTOCOL_METRICS`
+    pub fn env_var(name: &str) -> String {
+        format!("USDFC_CACHE_TTL_{}", name.to_uppercase())
+    }
+
+    /// Apply per-cache `USDFC_CACHE_TTL_*` overrides read through `lookup`
+    ///
+    /// These take precedence over CACHE_TTLS. A non-positive or non-numeric
+    /// value keeps the current TTL and is returned as a warning.
+    pub fn apply_env_overrides(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for name in Self::NAMES {
+            let var = Self::env_var(name);
+            let Some(value) = lookup(&var) else { continue };
+            let slot = self.slot_mut(name).expect("NAMES entries have slots");
+            match value.trim().parse::<u64>() {
+                Ok(secs) if secs > 0 => *slot = secs,
+                _ => warnings.push(format!(
+                    "Invalid {}='{}' (expected a positive number of seconds), using {}s",
+                    var, value, slot
+                )),
+            }
+        }
+        warnings
+    }
+
+    /// Apply comma-separated `name=secs` overrides on top of the defaults
+    ///
+    /// Unknown names and non-positive or non-numeric TTLs are rejected.
+    pub fn parse(overrides: &str) -> Result<Self, String> {
+        let mut ttls = Self::default();
+        for entry in overrides.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, secs) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected name=secs, got '{}'", entry))?;
+            let secs = match secs.trim().parse::<u64>() {
+                Ok(secs) if secs > 0 => secs,
+                _ => return Err(format!("TTL for '{}' must be a positive integer, got '{}'", name.trim(), secs.trim())),
+            };
+            let slot = ttls
+                .slot_mut(name.trim())
+                .ok_or_else(|| format!("unknown cache '{}'", name.trim()))?;
+            *slot = secs;
+        }
+        Ok(ttls)
+    }
+}
+
+/// Parse comma-separated `resolution=minutes` lookback limits (e.g. `1m=1440,1h=43200`)
+pub fn parse_lookback_overrides(spec: &str) -> Result<LookbackOverrides, String> {
+    let mut overrides = LookbackOverrides::new();
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (res, mins) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("expected resolution=minutes, got '{}'", entry))?;
+        let resolution = ChartResolution::from_url_param(res.trim())
+            .ok_or_else(|| format!("unknown resolution '{}'", res.trim()))?;
+        let mins = match mins.trim().parse::<u32>() {
+            Ok(mins) if mins > 0 => mins,
+            _ => return Err(format!("lookback for '{}' must be a positive number of minutes", res.trim())),
+        };
+        overrides.insert(resolution, mins);
+    }
+    Ok(overrides)
 }
 
 impl Default for Config {
@@ -61,8 +353,16 @@ impl Default for Config {
                 "https://rpc.ankr.com/filecoin".to_string(),
             ],
             subgraph_url: "https://api.goldsky.com/api/public/project_cm8i6ca9k24d601wy45zzbsrq/subgraphs/sf-filecoin-mainnet/latest/gn".to_string(),
+            subgraph_fallback_urls: Vec::new(),
+            subgraph_strict: false,
             blockscout_url: "https://filecoin.blockscout.com/api/v2".to_string(),
             geckoterminal_url: "https://api.geckoterminal.com/api/v2/networks/filecoin".to_string(),
+            explorer_url: "https://filecoin.blockscout.com".to_string(),
+
+            // Data sources - all enabled by default
+            blockscout_enabled: true,
+            subgraph_enabled: true,
+            gecko_enabled: true,
             
             usdfc_token: "0x80B98d3aa09ffff255c3ba4A241111Ff1262F045".to_string(),
             trove_manager: "0x5aB87c2398454125Dd424425e39c8909bBE16022".to_string(),
@@ -79,6 +379,7 @@ impl Default for Config {
             pool_usdfc_wfil: "0x4e07447bd38e60b94176764133788be1a0736b30".to_string(),
             pool_usdfc_axlusdc: "0x21ca72fe39095db9642ca9cc694fa056f906037f".to_string(),
             pool_usdfc_usdc: "0xc8f38dbaf661b897b6a2ee5721aac5a8766ffa13".to_string(),
+            price_pools: Vec::new(),
             
             host: "127.0.0.1".to_string(),
             port: 3000,
@@ -91,36 +392,144 @@ impl Default for Config {
             tcr_danger_threshold: 150.0,
             tcr_warning_threshold: 200.0,
             whale_threshold_usd: 100000.0,
+            stability_flow_windows_hours: vec![24, 168],
+            icr_bucket_bounds: DEFAULT_ICR_BUCKET_BOUNDS.to_vec(),
+            stale_data_secs: 120,
+            stale_data_warning_secs: 600,
+            tcr_zscore_window_mins: 1440,
+            peg_target: 1.0,
+            depeg_warning_pct: 0.5,
+            depeg_critical_pct: 2.0,
 
             // Timing - defaults
             refresh_interval_ms: 30000,
             history_retention_secs: 604800,
+            history_raw_retention_secs: 172800,
+            history_rollup_secs: 3600,
+            snapshot_interval_secs: 60,
+            backfill_on_start: false,
+
+            upstream_timeout_secs: 8,
 
             // RPC Settings - defaults
-            rpc_timeout_secs: 30,
+            rpc_timeout_secs: 8,
             rpc_retry_count: 3,
+            circuit_failure_threshold: 5,
+            circuit_open_secs: 30,
+            circuit_half_open_probes: 3,
+            cache_ttls: CacheTtls::default(),
+            chart_lookback_overrides: LookbackOverrides::new(),
+
+            // Server function latency budgets - defaults
+            slow_fn_budget_ms: 2000,
+            slow_fn_budgets: HashMap::new(),
+
+            // Content Security Policy - defaults
+            csp_script_src: vec!["https://cdn.jsdelivr.net".to_string()],
+            csp_connect_src: Vec::new(),
+            csp_nonce: false,
+            alert_webhooks: Vec::new(),
+            alert_webhook_secret: String::new(),
+            featured_markets: Vec::new(),
+            address_notes_token: String::new(),
+            alerts_admin_token: String::new(),
+            // Off for local debugging, on in release builds
+            log_redaction: !cfg!(debug_assertions),
+            log_format: LogFormat::Pretty,
+            default_route: "/dashboard".to_string(),
+            protected_routes: Vec::new(),
+            basic_auth_user: "admin".to_string(),
+            basic_auth_password: String::new(),
+            api_rate_limit_per_min: 120,
+            api_rate_limit_burst: 30,
+            trust_forwarded_for: false,
+            cors_allowed_origins: Vec::new(),
+            feed_max_connections: 500,
+            feed_send_buffer: 16,
+            brand_name: "USDFC Analytics Terminal".to_string(),
+            favicon_url: String::new(),
+            embed_allowed_origins: Vec::new(),
+            load_warnings: Vec::new(),
         }
     }
 }
 
 impl Config {
+    /// Names of optional data sources disabled by configuration
+    pub fn disabled_sources(&self) -> Vec<String> {
+        [
+            ("blockscout", self.blockscout_enabled),
+            ("subgraph", self.subgraph_enabled),
+            ("gecko", self.gecko_enabled),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| !enabled)
+        .map(|(name, _)| name.to_string())
+        .collect()
+    }
+
     /// Load configuration from environment variables
     pub fn from_env() -> Self {
         // Try to load .env file, but don't fail if it doesn't exist
         let _ = dotenvy::dotenv();
-        
+
+        let mut load_warnings = Vec::new();
+
+        // Cache TTLs - an invalid CACHE_TTLS keeps the defaults with a warning;
+        // per-cache USDFC_CACHE_TTL_* vars override it the same way
+        let mut cache_ttls = match std::env::var("CACHE_TTLS") {
+            Ok(spec) => CacheTtls::parse(&spec).unwrap_or_else(|e| {
+                load_warnings.push(format!("Invalid CACHE_TTLS, using defaults: {}", e));
+                CacheTtls::default()
+            }),
+            Err(_) => CacheTtls::default(),
+        };
+        load_warnings.extend(cache_ttls.apply_env_overrides(|var| std::env::var(var).ok()));
+
+        let upstream_timeout_secs = std::env::var("UPSTREAM_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&secs| secs > 0)
+            .unwrap_or(8);
+
         Self {
             rpc_url: std::env::var("RPC_URL").expect("RPC_URL must be set"),
             rpc_fallback_urls: std::env::var("RPC_FALLBACK_URLS")
                 .ok()
-                .map(|s| s.split(',').map(|u| u.trim().to_string()).collect())
+                .map(|s| parse_url_list(&s))
                 .unwrap_or_else(|| vec![
                     "https://filecoin.chainup.net/rpc/v1".to_string(),
                     "https://rpc.ankr.com/filecoin".to_string(),
                 ]),
             subgraph_url: std::env::var("SUBGRAPH_URL").expect("SUBGRAPH_URL must be set"),
+            subgraph_fallback_urls: std::env::var("SUBGRAPH_FALLBACK_URLS")
+                .map(|s| parse_url_list(&s))
+                .unwrap_or_default(),
+            subgraph_strict: std::env::var("SUBGRAPH_STRICT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(false),
             blockscout_url: std::env::var("BLOCKSCOUT_URL").expect("BLOCKSCOUT_URL must be set"),
             geckoterminal_url: std::env::var("GECKOTERMINAL_URL").expect("GECKOTERMINAL_URL must be set"),
+            explorer_url: std::env::var("EXPLORER_URL")
+                .ok()
+                .map(|u| u.trim().trim_end_matches('/').to_string())
+                .filter(|u| !u.is_empty())
+                .unwrap_or_else(|| "https://filecoin.blockscout.com".to_string()),
+
+            // Data sources - optional, enabled by default
+            blockscout_enabled: std::env::var("BLOCKSCOUT_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
+            subgraph_enabled: std::env::var("SUBGRAPH_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
+            gecko_enabled: std::env::var("GECKO_ENABLED")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
 
             usdfc_token: std::env::var("USDFC_TOKEN").expect("USDFC_TOKEN must be set"),
             trove_manager: std::env::var("TROVE_MANAGER").expect("TROVE_MANAGER must be set"),
@@ -137,6 +546,15 @@ impl Config {
             pool_usdfc_wfil: std::env::var("POOL_USDFC_WFIL").expect("POOL_USDFC_WFIL must be set"),
             pool_usdfc_axlusdc: std::env::var("POOL_USDFC_AXLUSDC").expect("POOL_USDFC_AXLUSDC must be set"),
             pool_usdfc_usdc: std::env::var("POOL_USDFC_USDC").expect("POOL_USDFC_USDC must be set"),
+            price_pools: std::env::var("PRICE_POOLS")
+                .ok()
+                .map(|s| {
+                    s.split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|entry| !entry.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
 
             host: std::env::var("HOST").expect("HOST must be set"),
             port: std::env::var("PORT")
@@ -170,6 +588,41 @@ impl Config {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(100000.0),
+            stability_flow_windows_hours: std::env::var("STABILITY_FLOW_WINDOWS_HOURS")
+                .ok()
+                .map(|s| s.split(',').filter_map(|h| h.trim().parse().ok()).collect())
+                .unwrap_or_else(|| vec![24, 168]),
+            icr_bucket_bounds: match std::env::var("ICR_BUCKETS") {
+                Ok(spec) => parse_icr_bucket_bounds(&spec).unwrap_or_else(|e| {
+                    load_warnings.push(format!("Invalid ICR_BUCKETS, using defaults: {}", e));
+                    DEFAULT_ICR_BUCKET_BOUNDS.to_vec()
+                }),
+                Err(_) => DEFAULT_ICR_BUCKET_BOUNDS.to_vec(),
+            },
+            stale_data_secs: std::env::var("STALE_DATA_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(120),
+            stale_data_warning_secs: std::env::var("STALE_DATA_WARNING_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(600),
+            tcr_zscore_window_mins: std::env::var("TCR_ZSCORE_WINDOW_MINS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1440),
+            peg_target: std::env::var("PEG_TARGET")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1.0),
+            depeg_warning_pct: std::env::var("DEPEG_WARNING_PCT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.5),
+            depeg_critical_pct: std::env::var("DEPEG_CRITICAL_PCT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2.0),
 
             // Timing - optional with defaults
             refresh_interval_ms: std::env::var("REFRESH_INTERVAL_MS")
@@ -180,18 +633,432 @@ impl Config {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(604800),
+            history_raw_retention_secs: std::env::var("HISTORY_RAW_RETENTION_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(172800),
+            history_rollup_secs: std::env::var("HISTORY_ROLLUP_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3600),
+            snapshot_interval_secs: std::env::var("SNAPSHOT_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            backfill_on_start: std::env::var("BACKFILL_ON_START")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(false),
+
+            upstream_timeout_secs,
 
             // RPC Settings - optional with defaults
             rpc_timeout_secs: std::env::var("RPC_TIMEOUT_SECS")
                 .ok()
                 .and_then(|v| v.parse().ok())
-                .unwrap_or(30),
+                .unwrap_or(upstream_timeout_secs),
             rpc_retry_count: std::env::var("RPC_RETRY_COUNT")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(3),
+
+            // Circuit breaker - optional with defaults; zero counts are raised to 1
+            circuit_failure_threshold: std::env::var("CIRCUIT_FAILURE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5u32)
+                .max(1),
+            circuit_open_secs: std::env::var("CIRCUIT_OPEN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            circuit_half_open_probes: std::env::var("CIRCUIT_HALF_OPEN_PROBES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3u32)
+                .max(1),
+
+            cache_ttls,
+
+            // Chart lookback overrides - optional, invalid values fail at startup
+            chart_lookback_overrides: std::env::var("CHART_MAX_LOOKBACK")
+                .map(|s| parse_lookback_overrides(&s).unwrap_or_else(|e| panic!("Invalid CHART_MAX_LOOKBACK: {}", e)))
+                .unwrap_or_default(),
+
+            // Server function latency budgets - optional with defaults
+            slow_fn_budget_ms: std::env::var("SLOW_FN_BUDGET_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2000),
+            slow_fn_budgets: std::env::var("SLOW_FN_BUDGETS")
+                .ok()
+                .map(|s| {
+                    s.split(',')
+                        .filter_map(|entry| {
+                            let (name, ms) = entry.split_once('=')?;
+                            Some((name.trim().to_string(), ms.trim().parse().ok()?))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+
+            // Content Security Policy - optional with defaults
+            csp_script_src: std::env::var("CSP_SCRIPT_SRC")
+                .ok()
+                .map(|s| s.split_whitespace().map(String::from).collect())
+                .unwrap_or_else(|| vec!["https://cdn.jsdelivr.net".to_string()]),
+            csp_connect_src: std::env::var("CSP_CONNECT_SRC")
+                .ok()
+                .map(|s| s.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+            csp_nonce: std::env::var("CSP_NONCE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(false),
+
+            // Alert webhooks - optional, none by default
+            alert_webhooks: std::env::var("ALERT_WEBHOOKS")
+                .ok()
+                .map(|s| s.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+            alert_webhook_secret: std::env::var("ALERT_WEBHOOK_SECRET").unwrap_or_default(),
+
+            // Featured lending markets - optional, none by default
+            featured_markets: std::env::var("FEATURED_MARKETS")
+                .ok()
+                .map(|s| {
+                    s.split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|entry| !entry.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+
+            // Address notes - read-only unless a write token is configured
+            address_notes_token: std::env::var("ADDRESS_NOTES_TOKEN").unwrap_or_default(),
+
+            // Alert history - review actions disabled unless a token is configured
+            alerts_admin_token: std::env::var("ALERTS_ADMIN_TOKEN").unwrap_or_default(),
+
+            // Log redaction - defaults to on in release builds
+            log_redaction: std::env::var("LOG_REDACTION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(!cfg!(debug_assertions)),
+            log_format: match std::env::var("USDFC_LOG_FORMAT") {
+                Ok(format) => LogFormat::parse(&format).unwrap_or_else(|| {
+                    load_warnings.push(format!("Unknown USDFC_LOG_FORMAT '{}', using pretty", format));
+                    LogFormat::Pretty
+                }),
+                Err(_) => LogFormat::Pretty,
+            },
+
+            // Routing - dashboard home, no protected routes unless a password is set
+            default_route: std::env::var("DEFAULT_ROUTE")
+                .ok()
+                .map(|v| normalize_route(&v))
+                .filter(|v| v != "/")
+                .unwrap_or_else(|| "/dashboard".to_string()),
+            protected_routes: std::env::var("PROTECTED_ROUTES")
+                .ok()
+                .map(|s| {
+                    s.split(',')
+                        .map(normalize_route)
+                        .filter(|route| route != "/")
+                        .collect()
+                })
+                .unwrap_or_default(),
+            basic_auth_user: std::env::var("BASIC_AUTH_USER")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| "admin".to_string()),
+            basic_auth_password: {
+                let password = std::env::var("BASIC_AUTH_PASSWORD").unwrap_or_default();
+                if password.is_empty() && std::env::var("PROTECTED_ROUTES").is_ok_and(|s| !s.trim().is_empty()) {
+                    load_warnings.push(
+                        "PROTECTED_ROUTES is set but BASIC_AUTH_PASSWORD is empty, those routes are not protected"
+                            .to_string(),
+                    );
+                }
+                password
+            },
+
+            // REST API rate limiting
+            api_rate_limit_per_min: std::env::var("API_RATE_LIMIT_PER_MIN")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(120),
+            api_rate_limit_burst: std::env::var("API_RATE_LIMIT_BURST")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            trust_forwarded_for: std::env::var("TRUST_X_FORWARDED_FOR")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(false),
+
+            // Branding and embeds
+            brand_name: std::env::var("BRAND_NAME")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| "USDFC Analytics Terminal".to_string()),
+            favicon_url: std::env::var("FAVICON_URL").unwrap_or_default(),
+            cors_allowed_origins: std::env::var("USDFC_CORS_ALLOWED_ORIGINS")
+                .ok()
+                .map(|s| parse_origin_list(&s))
+                .unwrap_or_default(),
+            feed_max_connections: std::env::var("FEED_MAX_CONNECTIONS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(500),
+            feed_send_buffer: std::env::var("FEED_SEND_BUFFER")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(16),
+            embed_allowed_origins: std::env::var("EMBED_ALLOWED_ORIGINS")
+                .ok()
+                .map(|s| {
+                    s.split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|o| !o.is_empty())
+                        .map(|o| o.trim_end_matches('/').to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+
+            load_warnings,
+        }
+    }
+
+    /// Staleness of a value fetched `age_secs` ago
+    pub fn data_staleness(&self, age_secs: u64) -> DataStaleness {
+        if age_secs >= self.stale_data_warning_secs.max(self.stale_data_secs) {
+            DataStaleness::VeryStale
+        } else if age_secs >= self.stale_data_secs {
+            DataStaleness::Stale
+        } else {
+            DataStaleness::Fresh
+        }
+    }
+
+    /// Where `/` redirects, or None when the dashboard is the landing page
+    pub fn landing_redirect(&self) -> Option<&str> {
+        match self.default_route.as_str() {
+            "/" | "/dashboard" => None,
+            route => Some(route),
+        }
+    }
+
+    /// Whether a request path needs basic auth
+    ///
+    /// A `PROTECTED_ROUTES` entry covers the path itself and everything below
+    /// it. Nothing is protected until `BASIC_AUTH_PASSWORD` is set; a load
+    /// warning is logged when routes are listed without one.
+    pub fn route_is_protected(&self, path: &str) -> bool {
+        !self.basic_auth_password.is_empty()
+            && self.protected_routes.iter().any(|route| {
+                path.strip_prefix(route.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+    }
+
+    /// RPC URLs in failover order: `RPC_URL`, then `RPC_FALLBACK_URLS`
+    pub fn rpc_endpoints(&self) -> Vec<String> {
+        endpoint_list(&self.rpc_url, &self.rpc_fallback_urls)
+    }
+
+    /// Subgraph URLs in failover order: `SUBGRAPH_URL`, then `SUBGRAPH_FALLBACK_URLS`
+    pub fn subgraph_endpoints(&self) -> Vec<String> {
+        endpoint_list(&self.subgraph_url, &self.subgraph_fallback_urls)
+    }
+
+    /// Whether a lending market is featured on the lending page
+    ///
+    /// `FEATURED_MARKETS` entries match either the maturity timestamp exactly
+    /// or the decoded currency symbol (case-insensitive).
+    pub fn is_featured_market(&self, maturity: &str, currency: &str) -> bool {
+        self.featured_markets
+            .iter()
+            .any(|entry| entry == maturity || entry.eq_ignore_ascii_case(currency))
+    }
+
+    /// DEX pools the USDFC price is aggregated from
+    pub fn price_pool_addresses(&self) -> Vec<&str> {
+        if self.price_pools.is_empty() {
+            vec![self.pool_usdfc_wfil.as_str()]
+        } else {
+            self.price_pools.iter().map(String::as_str).collect()
+        }
+    }
+
+    /// Built-in label for a known protocol contract or DEX pool
+    pub fn known_address_label(&self, address: &str) -> Option<&'static str> {
+        let known = [
+            (&self.stability_pool, "Stability Pool"),
+            (&self.active_pool, "Active Pool"),
+            (&self.trove_manager, "Trove Manager"),
+            (&self.borrower_operations, "Borrower Ops"),
+            (&self.pool_usdfc_wfil, "USDFC/WFIL Pool"),
+        ];
+        known
+            .iter()
+            .find(|(contract, _)| contract.eq_ignore_ascii_case(address))
+            .map(|(_, label)| *label)
+    }
+
+    /// Build the Content-Security-Policy value
+    ///
+    /// `connect-src` is derived from the configured endpoints plus
+    /// `csp_connect_src`, so reconfiguring an endpoint does not require a CSP
+    /// change. With a nonce, inline scripts must carry it and `'unsafe-inline'`
+    /// is dropped. The policy is always sent as a header, so it includes
+    /// `frame-ancestors`.
+    ///
+    /// `'unsafe-eval'` is not for the wasm loader (that would only need
+    /// `'wasm-unsafe-eval'`): the advanced chart builds its ECharts setup and
+    /// zoom handlers as JavaScript source and runs them with `js_sys::eval`
+    /// (`pages/advanced.rs`), which browsers block without it.
+    pub fn content_security_policy(&self, nonce: Option<&str>) -> String {
+        self.content_security_policy_framed(nonce, "'none'")
+    }
+
+    /// `content_security_policy` with the given `frame-ancestors` sources
+    pub fn content_security_policy_framed(&self, nonce: Option<&str>, frame_ancestors: &str) -> String {
+        let mut script_src = vec!["'self'".to_string()];
+        match nonce {
+            Some(nonce) => script_src.push(format!("'nonce-{}'", nonce)),
+            None => script_src.push("'unsafe-inline'".to_string()),
+        }
+        script_src.push("'unsafe-eval'".to_string());
+        script_src.extend(self.csp_script_src.iter().cloned());
+
+        let mut connect_src = vec!["'self'".to_string()];
+        let endpoints = [
+            &self.rpc_url,
+            &self.blockscout_url,
+            &self.subgraph_url,
+            &self.geckoterminal_url,
+        ];
+        for origin in endpoints
+            .into_iter()
+            .chain(&self.rpc_fallback_urls)
+            .chain(&self.subgraph_fallback_urls)
+            .filter_map(|url| url_origin(url))
+            .chain(self.csp_connect_src.iter().cloned())
+        {
+            if !connect_src.contains(&origin) {
+                connect_src.push(origin);
+            }
+        }
+
+        format!(
+            "default-src 'self'; script-src {}; style-src 'self' 'unsafe-inline'; \
+             img-src 'self' data: https:; font-src 'self'; connect-src {}; frame-ancestors {};",
+            script_src.join(" "),
+            connect_src.join(" "),
+            frame_ancestors
+        )
+    }
+
+    /// `frame-ancestors` sources for a request path: embeds may be framed
+    pub fn frame_ancestors_for(&self, path: &str) -> String {
+        if path.starts_with("/embed/") {
+            self.embed_frame_ancestors()
+        } else {
+            "'none'".to_string()
+        }
+    }
+
+    /// `frame-ancestors` sources for `/embed/*` pages
+    pub fn embed_frame_ancestors(&self) -> String {
+        if self.embed_allowed_origins.is_empty() {
+            "*".to_string()
+        } else {
+            self.embed_allowed_origins.join(" ")
+        }
+    }
+}
+
+/// Log line format for the tracing subscriber
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines (default)
+    Pretty,
+    /// One JSON object per line for log shippers
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "pretty" | "text" => Some(Self::Pretty),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// ICR distribution bounds: <110, 110-125, 125-150, 150-200 and 200+
+pub const DEFAULT_ICR_BUCKET_BOUNDS: [f64; 4] = [110.0, 125.0, 150.0, 200.0];
+
+/// Parse comma-separated, strictly ascending, positive ICR bounds (e.g. `110,125,150,200`)
+pub fn parse_icr_bucket_bounds(spec: &str) -> Result<Vec<f64>, String> {
+    let bounds = spec
+        .split(',')
+        .map(|bound| {
+            let bound = bound.trim();
+            bound
+                .parse::<f64>()
+                .ok()
+                .filter(|b| b.is_finite() && *b > 0.0)
+                .ok_or_else(|| format!("'{}' is not a positive number", bound))
+        })
+        .collect::<Result<Vec<f64>, String>>()?;
+    if bounds.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("bounds must be strictly ascending".to_string());
+    }
+    Ok(bounds)
+}
+
+/// Split a comma-separated origin list, dropping blanks and trailing slashes
+///
+/// Browsers send `Origin` without a trailing slash, so `https://a.example/`
+/// would otherwise never match.
+fn parse_origin_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|o| o.trim().trim_end_matches('/'))
+        .filter(|o| !o.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Split a comma-separated URL list, dropping blanks
+fn parse_url_list(s: &str) -> Vec<String> {
+    s.split(',').map(str::trim).filter(|u| !u.is_empty()).map(String::from).collect()
+}
+
+/// Primary URL then fallbacks, without blanks or repeats
+fn endpoint_list(primary: &str, fallbacks: &[String]) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for url in std::iter::once(primary).chain(fallbacks.iter().map(String::as_str)) {
+        if !url.is_empty() && !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
         }
     }
+    urls
+}
+
+/// Extract the `scheme://host[:port]` origin from a URL
+fn url_origin(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next().filter(|h| !h.is_empty())?;
+    Some(format!("{}://{}", scheme, host))
+}
+
+/// `tools/` or ` /tools ` -> `/tools`
+fn normalize_route(route: &str) -> String {
+    format!("/{}", route.trim().trim_matches('/'))
 }
 
 /// Global config instance
@@ -214,3 +1081,172 @@ pub fn config() -> &'static Config {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_origin() {
+        assert_eq!(
+            url_origin("https://api.node.glif.io/rpc/v1").as_deref(),
+            Some("https://api.node.glif.io")
+        );
+        assert_eq!(url_origin("http://localhost:8545").as_deref(), Some("http://localhost:8545"));
+        assert_eq!(url_origin("not a url"), None);
+    }
+
+    #[test]
+    fn test_log_format_parse() {
+        assert_eq!(LogFormat::parse("JSON"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse(" pretty "), Some(LogFormat::Pretty));
+        assert_eq!(LogFormat::parse("logfmt"), None);
+    }
+
+    #[test]
+    fn test_parse_icr_bucket_bounds() {
+        assert_eq!(parse_icr_bucket_bounds(" 110, 125.5,200"), Ok(vec![110.0, 125.5, 200.0]));
+        assert!(parse_icr_bucket_bounds("125,110").is_err());
+        assert!(parse_icr_bucket_bounds("110,,150").is_err());
+        assert!(parse_icr_bucket_bounds("-5").is_err());
+    }
+
+    #[test]
+    fn test_parse_origin_list() {
+        assert_eq!(
+            parse_origin_list(" https://a.example/, ,http://localhost:3000 "),
+            vec!["https://a.example".to_string(), "http://localhost:3000".to_string()]
+        );
+        assert!(parse_origin_list(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_lists() {
+        assert_eq!(parse_url_list(" https://a.example/rpc/v1, ,https://b.example "), ["https://a.example/rpc/v1", "https://b.example"]);
+        let cfg = Config {
+            rpc_url: "https://a.example".to_string(),
+            rpc_fallback_urls: vec!["https://b.example".into(), "https://a.example".into()],
+            ..Config::default()
+        };
+        assert_eq!(cfg.rpc_endpoints(), ["https://a.example", "https://b.example"]);
+        assert_eq!(cfg.subgraph_endpoints(), std::slice::from_ref(&cfg.subgraph_url));
+    }
+
+    #[test]
+    fn test_featured_markets_match_maturity_or_currency() {
+        let cfg = Config {
+            featured_markets: vec!["1735689600".to_string(), "usdfc".to_string()],
+            ..Config::default()
+        };
+        assert!(cfg.is_featured_market("1735689600", "FIL"));
+        assert!(cfg.is_featured_market("1738368000", "USDFC"));
+        assert!(!cfg.is_featured_market("1738368000", "FIL"));
+        assert!(!Config::default().is_featured_market("1735689600", "USDFC"));
+    }
+
+    #[test]
+    fn test_data_staleness_thresholds() {
+        let cfg = Config { stale_data_secs: 60, stale_data_warning_secs: 300, ..Config::default() };
+        assert_eq!(cfg.data_staleness(59), DataStaleness::Fresh);
+        assert_eq!(cfg.data_staleness(60), DataStaleness::Stale);
+        assert_eq!(cfg.data_staleness(299), DataStaleness::Stale);
+        assert_eq!(cfg.data_staleness(300), DataStaleness::VeryStale);
+
+        // A warning threshold below the stale one never skips the stale state
+        let inverted = Config { stale_data_warning_secs: 10, ..cfg };
+        assert_eq!(inverted.data_staleness(30), DataStaleness::Fresh);
+        assert_eq!(inverted.data_staleness(60), DataStaleness::VeryStale);
+    }
+
+    #[test]
+    fn test_route_guard_and_landing_page() {
+        let cfg = Config {
+            protected_routes: vec![normalize_route("tools/"), normalize_route(" /api/v1/holders")],
+            default_route: "/advanced".to_string(),
+            ..Config::default()
+        };
+        // No password configured, nothing is guarded
+        assert!(!cfg.route_is_protected("/tools"));
+
+        let cfg = Config { basic_auth_password: "pw".to_string(), ..cfg };
+        assert!(cfg.route_is_protected("/tools"));
+        assert!(cfg.route_is_protected("/tools/export"));
+        assert!(!cfg.route_is_protected("/toolsmith"));
+        assert!(!cfg.route_is_protected("/dashboard"));
+        assert!(cfg.route_is_protected("/api/v1/holders"));
+
+        assert_eq!(cfg.landing_redirect(), Some("/advanced"));
+        assert_eq!(Config::default().landing_redirect(), None);
+    }
+
+    #[test]
+    fn test_lookback_overrides() {
+        let overrides = parse_lookback_overrides("1m=1440, 1h=43200").unwrap();
+        assert_eq!(overrides.get(&ChartResolution::M1), Some(&1440));
+        assert_eq!(overrides.get(&ChartResolution::H1), Some(&43200));
+        assert_eq!(overrides.len(), 2);
+
+        assert!(parse_lookback_overrides("2m=60").is_err());
+        assert!(parse_lookback_overrides("1m=0").is_err());
+        assert!(parse_lookback_overrides("1m").is_err());
+    }
+
+    #[test]
+    fn test_cache_ttl_overrides() {
+        let ttls = CacheTtls::parse("troves=120, order_book=10").unwrap();
+        assert_eq!(ttls.troves, 120);
+        assert_eq!(ttls.order_book, 10);
+        assert_eq!(ttls.protocol_metrics, CacheTtls::default().protocol_metrics);
+        assert_eq!(CacheTtls::parse("").unwrap(), CacheTtls::default());
+
+        assert!(CacheTtls::parse("troves=0").is_err());
+        assert!(CacheTtls::parse("troves=-5").is_err());
+        assert!(CacheTtls::parse("nope=10").is_err());
+        assert!(CacheTtls::parse("troves").is_err());
+    }
+
+    #[test]
+    fn test_cache_ttl_env_overrides() {
+        let mut ttls = CacheTtls::parse("troves=120").unwrap();
+        let env = |var: &str| match var {
+            "USDFC_CACHE_TTL_PROTOCOL_METRICS" => Some("5".to_string()),
+            "USDFC_CACHE_TTL_TROVES" => Some("soon".to_string()),
+            "USDFC_CACHE_TTL_ORDER_BOOK" => Some("0".to_string()),
+            _ => None,
+        };
+        let warnings = ttls.apply_env_overrides(env);
+
+        assert_eq!(ttls.protocol_metrics, 5);
+        // Invalid values keep the CACHE_TTLS or default TTL
+        assert_eq!(ttls.troves, 120);
+        assert_eq!(ttls.order_book, CacheTtls::default().order_book);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("USDFC_CACHE_TTL_TROVES"));
+        assert_eq!(CacheTtls::NAMES.len(), 23);
+    }
+
+    #[test]
+    fn test_csp_follows_configured_endpoints() {
+        let cfg = Config {
+            blockscout_url: "https://explorer.example.org/api/v2".to_string(),
+            ..Config::default()
+        };
+        let policy = cfg.content_security_policy(None);
+        assert!(policy.contains("https://explorer.example.org"));
+        assert!(!policy.contains("filecoin.blockscout.com"));
+        assert!(policy.contains("'unsafe-inline'"));
+
+        let policy = cfg.content_security_policy(Some("abc123"));
+        assert!(policy.contains("script-src 'self' 'nonce-abc123' 'unsafe-eval'"));
+        assert!(policy.ends_with("frame-ancestors 'none';"));
+
+        // Embeds may be framed by the allowed origins, or anyone when none are set
+        assert!(cfg.content_security_policy(None).ends_with("frame-ancestors 'none';"));
+        assert_eq!(cfg.embed_frame_ancestors(), "*");
+        assert_eq!(cfg.frame_ancestors_for("/embed/tcr"), "*");
+        assert_eq!(cfg.frame_ancestors_for("/protocol"), "'none'");
+        let cfg = Config { embed_allowed_origins: vec!["https://a.example".into(), "https://b.example".into()], ..cfg };
+        let policy = cfg.content_security_policy_framed(None, &cfg.embed_frame_ancestors());
+        assert!(policy.ends_with("frame-ancestors https://a.example https://b.example;"));
+    }
+}
@@ -70,6 +70,15 @@ impl ApiError {
         Self::RateLimit { retry_after }
     }
     
+    /// Wrap as an `RpcError` prefixed with context, leaving timeouts as they are
+    #[inline]
+    pub fn rpc_context(self, context: &str) -> Self {
+        match self {
+            Self::Timeout { .. } => self,
+            other => Self::RpcError(format!("{}: {}", context, other)),
+        }
+    }
+    
     /// Check if error is retryable
     #[inline]
     pub fn is_retryable(&self) -> bool {
@@ -96,6 +105,9 @@ pub enum ValidationError {
     #[error("Invalid address format: {0}")]
     InvalidAddress(String),
     
+    #[error("Invalid address checksum: {0}")]
+    InvalidChecksum(String),
+    
     #[error("Invalid transaction hash: {0}")]
     InvalidTxHash(String),
     
@@ -116,7 +128,10 @@ impl ValidationError {
         let is_filecoin = (address.starts_with("f1") || address.starts_with("f4"))
             && address.len() > 2
             && address[2..].chars().all(|c| c.is_ascii_alphanumeric());
-        if is_evm || is_filecoin {
+        if is_evm && !crate::address::has_valid_checksum(address) {
+            // Mixed case claims an EIP-55 checksum, so a typo must not pass
+            Err(Self::InvalidChecksum(address.to_string()))
+        } else if is_evm || is_filecoin {
             Ok(())
         } else if address.contains("...") {
             // Truncated address for display
@@ -139,3 +154,169 @@ impl ValidationError {
         }
     }
 }
+
+/// Error returned by server functions, classified for the frontend
+///
+/// Displays as `[code] detail`. Through its `std::error::Error` impl it
+/// converts into `ServerFnError::ServerError` carrying that text, and error
+/// boundaries recover it with `from_server_fn_error` (or `str::parse`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminalError {
+    /// An upstream (RPC, Blockscout, subgraph, price feed) did not answer in time
+    UpstreamTimeout,
+    /// An upstream failed or returned unusable data
+    UpstreamUnavailable { source: String },
+    /// The requested resource does not exist
+    NotFound,
+    /// The caller's input was rejected
+    Validation(String),
+    /// The configured data sources cannot provide this data
+    Unsupported(String),
+    /// Anything else that went wrong on the server
+    Internal(String),
+}
+
+impl TerminalError {
+    /// Stable machine-readable code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UpstreamTimeout => "upstream_timeout",
+            Self::UpstreamUnavailable { .. } => "upstream_unavailable",
+            Self::NotFound => "not_found",
+            Self::Validation(_) => "validation",
+            Self::Unsupported(_) => "unsupported",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    /// Human-readable detail, empty for variants without one
+    pub fn detail(&self) -> &str {
+        match self {
+            Self::UpstreamTimeout | Self::NotFound => "",
+            Self::UpstreamUnavailable { source } => source,
+            Self::Validation(message) | Self::Unsupported(message) | Self::Internal(message) => message,
+        }
+    }
+
+    /// Recover the typed error from a server function failure
+    ///
+    /// None for failures that did not come from a `TerminalError`.
+    #[cfg(feature = "server_fn")]
+    pub fn from_server_fn_error<E>(error: &server_fn::ServerFnError<E>) -> Option<Self> {
+        match error {
+            server_fn::ServerFnError::ServerError(message) => message.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TerminalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.detail() {
+            "" => write!(f, "[{}]", self.code()),
+            detail => write!(f, "[{}] {}", self.code(), detail),
+        }
+    }
+}
+
+impl std::str::FromStr for TerminalError {
+    type Err = ();
+
+    fn from_str(message: &str) -> Result<Self, Self::Err> {
+        let (message, _) = split_request_id(message);
+        let (code, detail) = message.strip_prefix('[').and_then(|m| m.split_once(']')).ok_or(())?;
+        let detail = detail.trim_start().to_string();
+        Ok(match code {
+            "upstream_timeout" => Self::UpstreamTimeout,
+            "upstream_unavailable" => Self::UpstreamUnavailable { source: detail },
+            "not_found" => Self::NotFound,
+            "validation" => Self::Validation(detail),
+            "unsupported" => Self::Unsupported(detail),
+            "internal" => Self::Internal(detail),
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Tag a server function error message with the request's correlation id
+pub fn with_request_id(message: &str, request_id: &str) -> String {
+    format!("{} (request {})", message, request_id)
+}
+
+/// Split a message tagged by `with_request_id` into the message and the id
+pub fn split_request_id(message: &str) -> (&str, Option<&str>) {
+    message
+        .strip_suffix(')')
+        .and_then(|m| m.rsplit_once(" (request "))
+        .filter(|(_, id)| !id.is_empty() && !id.contains(char::is_whitespace))
+        .map_or((message, None), |(message, id)| (message, Some(id)))
+}
+
+// `source` is a message rather than a wrapped error, so thiserror's derive does not fit
+impl std::error::Error for TerminalError {}
+
+impl From<ApiError> for TerminalError {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::Timeout { .. } => Self::UpstreamTimeout,
+            ApiError::NotFound { .. } => Self::NotFound,
+            ApiError::Config { .. } | ApiError::Serialization { .. } => Self::Internal(error.to_string()),
+            _ => Self::UpstreamUnavailable { source: error.to_string() },
+        }
+    }
+}
+
+impl From<ValidationError> for TerminalError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_error_round_trips_through_display() {
+        let errors = [
+            TerminalError::UpstreamTimeout,
+            TerminalError::UpstreamUnavailable { source: "RPC error: HTTP 502".to_string() },
+            TerminalError::NotFound,
+            TerminalError::Validation("Invalid address format: 0x12".to_string()),
+            TerminalError::Unsupported("needs Blockscout".to_string()),
+            TerminalError::Internal("[nested] brackets".to_string()),
+        ];
+        for error in errors {
+            assert_eq!(error.to_string().parse::<TerminalError>(), Ok(error));
+        }
+        assert!("RPC error: HTTP 502".parse::<TerminalError>().is_err());
+        assert!("[unknown] detail".parse::<TerminalError>().is_err());
+    }
+
+    #[test]
+    fn test_request_id_tag() {
+        let tagged = with_request_id("[validation] Invalid limit", "4f2a9c01");
+        assert_eq!(split_request_id(&tagged), ("[validation] Invalid limit", Some("4f2a9c01")));
+        assert_eq!(tagged.parse(), Ok(TerminalError::Validation("Invalid limit".to_string())));
+        assert_eq!(split_request_id("[internal] failed (after retry)"), ("[internal] failed (after retry)", None));
+    }
+
+    #[test]
+    fn test_api_error_classification() {
+        let timeout = ApiError::Timeout { duration: Duration::from_secs(30) };
+        assert_eq!(TerminalError::from(timeout).code(), "upstream_timeout");
+        assert_eq!(TerminalError::from(ApiError::RpcError("down".into())).code(), "upstream_unavailable");
+        assert_eq!(TerminalError::from(ApiError::not_found("trove", "0x1")), TerminalError::NotFound);
+    }
+
+    #[test]
+    fn test_validate_address_checks_mixed_case_checksums() {
+        assert!(ValidationError::validate_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+        assert!(ValidationError::validate_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_ok());
+        assert!(matches!(
+            ValidationError::validate_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(ValidationError::InvalidChecksum(_))
+        ));
+        assert!(ValidationError::validate_address("f410fabc").is_ok());
+    }
+}
@@ -4,7 +4,8 @@
 //! All pages should import from this module instead of defining local helpers.
 
 use rust_decimal::Decimal;
-use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::RoundingStrategy;
 
 // ============================================================================
 // Core Conversion
@@ -16,6 +17,51 @@ pub fn decimal_to_f64(value: Decimal) -> f64 {
     value.to_f64().unwrap_or(0.0)
 }
 
+/// Convert Decimal to f64, logging a warning when significant digits are lost
+///
+/// f64 keeps about 15 significant digits, so 18-decimal amounts always lose
+/// their trailing wei; this only flags conversions off by more than one part
+/// in 10^12 (or that fail outright). `label` names the value in the log.
+pub fn decimal_to_f64_checked(value: Decimal, label: &str) -> f64 {
+    let Some(converted) = value.to_f64() else {
+        tracing::warn!("{} = {} does not fit in f64", label, value);
+        return 0.0;
+    };
+    let tolerance = value.abs() * Decimal::new(1, 12);
+    let lossy = Decimal::from_f64(converted).is_none_or(|back| (back - value).abs() > tolerance);
+    if lossy {
+        tracing::warn!("{} = {} lost significant digits converting to f64 ({})", label, value, converted);
+    }
+    converted
+}
+
+/// Parse a raw integer token amount in base units, scaled down by `decimals`
+/// Returns None for non-numeric input and for `decimals` above 28 (the most a
+/// Decimal holds); amounts beyond 28 significant digits are rounded
+pub fn parse_token_amount(raw: &str, decimals: u32) -> Option<Decimal> {
+    if decimals > 28 {
+        return None;
+    }
+    let raw = raw.parse::<u128>().ok()?;
+    let unit = 10u128.pow(decimals);
+    let whole = Decimal::from_u128(raw / unit)?;
+    let fraction = Decimal::from_i128_with_scale((raw % unit) as i128, decimals);
+    whole.checked_add(fraction)
+}
+
+/// Format a token amount as a raw integer in base units for `decimals`
+/// Output: "1500000000000000000" for 1.5 at 18 decimals (finer digits are truncated)
+pub fn format_token_amount(value: Decimal, decimals: u32) -> String {
+    let truncated = value.round_dp_with_strategy(decimals, RoundingStrategy::ToZero);
+    let mantissa = truncated.mantissa();
+    if mantissa == 0 {
+        return "0".to_string();
+    }
+    let sign = if mantissa < 0 { "-" } else { "" };
+    let zeros = "0".repeat((decimals - truncated.scale()) as usize);
+    format!("{}{}{}", sign, mantissa.unsigned_abs(), zeros)
+}
+
 // ============================================================================
 // Amount Formatting (Tokens - no currency symbol)
 // ============================================================================
@@ -24,7 +70,52 @@ pub fn decimal_to_f64(value: Decimal) -> f64 {
 /// Output: "1,234.56" (no symbol)
 #[inline]
 pub fn format_amount(amount: Decimal) -> String {
-    format_readable(decimal_to_f64(amount), 2)
+    format_amount_with(amount, 2, false)
+}
+
+/// Significant digits kept for non-zero amounts that would round to zero
+const SUB_UNIT_SIGNIFICANT_DIGITS: i32 = 2;
+
+/// Most decimals shown for sub-unit amounts (token precision is 18)
+const MAX_SUB_UNIT_DECIMALS: usize = 18;
+
+/// Format a Decimal token amount with caller-chosen precision
+/// Output: "1,234.56" or, with `compact`, "1.23K" / "4.5M"
+///
+/// Non-zero amounts too small for `max_decimals` are shown with enough
+/// significant digits instead of rounding to "0.00" (e.g. "0.00012").
+pub fn format_amount_with(amount: Decimal, max_decimals: usize, compact: bool) -> String {
+    let value = decimal_to_f64(amount);
+    let abs = value.abs();
+
+    if compact && abs >= 1_000.0 {
+        let (scaled, suffix) = if abs >= 1_000_000_000.0 {
+            (value / 1_000_000_000.0, "B")
+        } else if abs >= 1_000_000.0 {
+            (value / 1_000_000.0, "M")
+        } else {
+            (value / 1_000.0, "K")
+        };
+        return format!("{}{}", trim_fraction(format!("{:.1$}", scaled, max_decimals)), suffix);
+    }
+
+    let rounds_to_zero = abs > 0.0 && abs < 0.5 * 10f64.powi(-(max_decimals as i32));
+    if rounds_to_zero {
+        let leading_zeros = (-abs.log10().floor()) as i32 - 1;
+        let decimals = ((leading_zeros + SUB_UNIT_SIGNIFICANT_DIGITS) as usize).min(MAX_SUB_UNIT_DECIMALS);
+        return trim_fraction(format!("{:.1$}", value, decimals));
+    }
+
+    format_readable(value, max_decimals)
+}
+
+/// Drop trailing zeros (and a dangling '.') from a formatted decimal
+fn trim_fraction(formatted: String) -> String {
+    if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        formatted
+    }
 }
 
 /// Format a Decimal as FIL amount
@@ -175,6 +266,33 @@ pub fn format_time_ago(timestamp: u64) -> String {
     }
 }
 
+/// Format how long before `now` a unix timestamp was, in the largest whole unit
+/// Output: "just now", "45s ago", "3m ago", "2h ago", "4d ago", "6w ago"
+///
+/// Taking `now` as a parameter keeps server and client output identical for
+/// the same clock. Timestamps in the future count as "just now".
+pub fn format_duration_ago(ts: i64, now: i64) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    const WEEK: i64 = 7 * DAY;
+
+    let diff = now.saturating_sub(ts);
+    if diff <= 0 {
+        "just now".to_string()
+    } else if diff < MINUTE {
+        format!("{}s ago", diff)
+    } else if diff < HOUR {
+        format!("{}m ago", diff / MINUTE)
+    } else if diff < DAY {
+        format!("{}h ago", diff / HOUR)
+    } else if diff < WEEK {
+        format!("{}d ago", diff / DAY)
+    } else {
+        format!("{}w ago", diff / WEEK)
+    }
+}
+
 /// Format a unix timestamp as short datetime
 /// Output: "Jan 15, 14:30"
 #[inline]
@@ -232,6 +350,28 @@ pub fn shorten_address(value: &str) -> String {
     shorten_hash(value)
 }
 
+/// Block explorer page for a transaction hash
+/// Output: "https://filecoin.blockscout.com/tx/0x..."
+#[inline]
+pub fn explorer_tx_url(base: &str, hash: &str) -> String {
+    format!("{}/tx/{}", base.trim_end_matches('/'), hash.trim())
+}
+
+/// Block explorer page for an address
+///
+/// Accepts EVM (`0x...`) and Filecoin (`f410f...`, `f0...`) forms; Blockscout
+/// resolves both, so Filecoin addresses are only lowercased.
+#[inline]
+pub fn explorer_address_url(base: &str, address: &str) -> String {
+    let address = address.trim();
+    let address = if address.starts_with("0x") || address.starts_with("0X") {
+        address.to_string()
+    } else {
+        address.to_ascii_lowercase()
+    };
+    format!("{}/address/{}", base.trim_end_matches('/'), address)
+}
+
 // ============================================================================
 // Number Formatting Helpers
 // ============================================================================
@@ -381,3 +521,106 @@ pub fn format_bytes(bytes: u64) -> String {
 pub fn format_date_short(seconds: u64) -> String {
     format_date(seconds)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_decimal_to_f64_checked_large_supply() {
+        let supply = dec("15000000.123456789012345678");
+        let converted = decimal_to_f64_checked(supply, "total_supply");
+        assert!((converted - 15_000_000.123_456_79).abs() < 1e-6);
+        assert_eq!(decimal_to_f64_checked(Decimal::ZERO, "zero"), 0.0);
+    }
+
+    #[test]
+    fn test_tiny_amounts_keep_significant_digits() {
+        assert_eq!(format_amount(dec("0.000123")), "0.00012");
+        assert_eq!(format_amount(dec("0.004")), "0.004");
+        assert_eq!(format_amount(dec("-0.0000005")), "-0.0000005");
+        assert_eq!(format_amount(dec("0.000000000000000001")), "0.000000000000000001");
+        assert_eq!(format_amount(Decimal::ZERO), "0.00");
+        // Values that survive rounding are unchanged
+        assert_eq!(format_amount(dec("0.05")), "0.05");
+    }
+
+    #[test]
+    fn test_huge_amounts() {
+        assert_eq!(format_amount(dec("1234567890.126")), "1,234,567,890.13");
+        assert_eq!(format_amount_with(dec("1234567890"), 2, true), "1.23B");
+        assert_eq!(format_amount_with(dec("2500000"), 2, true), "2.5M");
+        assert_eq!(format_amount_with(dec("-1500"), 1, true), "-1.5K");
+        assert_eq!(format_amount_with(dec("999.5"), 2, true), "999.50");
+    }
+
+    #[test]
+    fn test_max_decimals() {
+        assert_eq!(format_amount_with(dec("1234.56789"), 4, false), "1,234.5679");
+        assert_eq!(format_amount_with(dec("1234.56789"), 0, false), "1,235");
+        assert_eq!(format_amount_with(dec("0.0004"), 4, false), "0.0004");
+    }
+
+    #[test]
+    fn test_format_duration_ago() {
+        let now = 1_700_000_000;
+        let cases = [
+            (now + 30, "just now"),
+            (now, "just now"),
+            (now - 1, "1s ago"),
+            (now - 59, "59s ago"),
+            (now - 60, "1m ago"),
+            (now - 3_599, "59m ago"),
+            (now - 3_600, "1h ago"),
+            (now - 86_399, "23h ago"),
+            (now - 86_400, "1d ago"),
+            (now - 6 * 86_400, "6d ago"),
+            (now - 7 * 86_400, "1w ago"),
+            (now - 100 * 86_400, "14w ago"),
+        ];
+        for (ts, expected) in cases {
+            assert_eq!(format_duration_ago(ts, now), expected, "ts = now - {}", now - ts);
+        }
+    }
+
+    #[test]
+    fn test_explorer_urls() {
+        let base = "https://explorer.example.org/";
+        assert_eq!(explorer_tx_url(base, "0xabc"), "https://explorer.example.org/tx/0xabc");
+        assert_eq!(
+            explorer_address_url(base, " 0xAbCd "),
+            "https://explorer.example.org/address/0xAbCd"
+        );
+        assert_eq!(
+            explorer_address_url(base, "F410FABCDEF"),
+            "https://explorer.example.org/address/f410fabcdef"
+        );
+    }
+    #[test]
+    fn test_token_amounts() {
+        assert_eq!(parse_token_amount("0", 18), Some(Decimal::ZERO));
+        assert_eq!(parse_token_amount("1500000000000000000", 18), Some(dec("1.5")));
+        assert_eq!(parse_token_amount("1", 18), Some(dec("0.000000000000000001")));
+        assert_eq!(parse_token_amount("1234567", 6), Some(dec("1.234567")));
+
+        let max = parse_token_amount(&u128::MAX.to_string(), 18).unwrap();
+        assert_eq!(max.trunc(), dec("340282366920938463463"));
+        assert_eq!(parse_token_amount(&u128::MAX.to_string(), 0), None);
+
+        assert_eq!(parse_token_amount("", 18), None);
+        assert_eq!(parse_token_amount("12abc", 18), None);
+        assert_eq!(parse_token_amount("-1", 18), None);
+        assert_eq!(parse_token_amount("1.5", 18), None);
+        assert_eq!(parse_token_amount("1", 29), None);
+
+        assert_eq!(format_token_amount(Decimal::ZERO, 18), "0");
+        assert_eq!(format_token_amount(dec("1.5"), 18), "1500000000000000000");
+        assert_eq!(format_token_amount(dec("1.2345679"), 6), "1234567");
+        assert_eq!(format_token_amount(max, 18), "340282366920938463463374607430000000000");
+    }
+}
@@ -7,6 +7,7 @@ pub mod types;
 pub mod error;
 pub mod config;
 pub mod format;
+pub mod address;
 
 // Re-export commonly used types
 pub use types::*;
@@ -1,28 +1,104 @@
 use serde::{Deserialize, Serialize};
 use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Protocol-wide metrics snapshot
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+///
+/// Each value is read separately, so a failed upstream call leaves only the
+/// values that depend on it as None instead of failing the whole snapshot.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct ProtocolMetrics {
-    pub total_supply: Decimal,
-    pub circulating_supply: Decimal,
-    pub total_collateral: Decimal,
-    pub active_troves: u64,
-    pub tcr: Decimal,
-    pub stability_pool_balance: Decimal,
-    pub treasury_balance: Decimal,
+    pub total_supply: Option<Decimal>,
+    /// Total supply minus the stability pool; None when either is unavailable
+    pub circulating_supply: Option<Decimal>,
+    /// Total FIL locked as collateral
+    #[serde(alias = "total_collateral")]
+    pub collateral_fil: Option<Decimal>,
+    /// Collateral value at the oracle FIL price; None when no price is available
+    #[serde(default)]
+    pub collateral_usd: Option<Decimal>,
+    pub active_troves: Option<u64>,
+    /// None when collateral, debt or the FIL price is unavailable
+    pub tcr: Option<Decimal>,
+    pub stability_pool_balance: Option<Decimal>,
+    #[serde(default)]
+    pub provenance: Provenance,
+}
+
+impl ProtocolMetrics {
+    /// Names of the values that could not be fetched, in field order
+    pub fn missing(&self) -> Vec<&'static str> {
+        self.missing_flags()
+            .into_iter()
+            .filter(|(_, missing)| *missing)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Whether none of the values could be fetched
+    pub fn is_empty(&self) -> bool {
+        self.missing_flags().iter().all(|(_, missing)| *missing)
+    }
+
+    /// Each value's name and whether it is missing
+    fn missing_flags(&self) -> [(&'static str, bool); 7] {
+        [
+            ("total_supply", self.total_supply.is_none()),
+            ("circulating_supply", self.circulating_supply.is_none()),
+            ("collateral_fil", self.collateral_fil.is_none()),
+            ("collateral_usd", self.collateral_usd.is_none()),
+            ("active_troves", self.active_troves.is_none()),
+            ("tcr", self.tcr.is_none()),
+            ("stability_pool_balance", self.stability_pool_balance.is_none()),
+        ]
+    }
+
+    /// USD value of `collateral_fil` at `fil_price`
+    ///
+    /// A missing or non-positive price (oracle unreachable, not yet set) yields
+    /// None rather than a made-up value.
+    pub fn collateral_value_usd(collateral_fil: Decimal, fil_price: Option<Decimal>) -> Option<Decimal> {
+        fil_price
+            .filter(|price| price.is_sign_positive() && !price.is_zero())
+            .map(|price| collateral_fil * price)
+    }
 }
 
-impl Default for ProtocolMetrics {
-    fn default() -> Self {
-        Self {
-            total_supply: Decimal::ZERO,
-            circulating_supply: Decimal::ZERO,
-            total_collateral: Decimal::ZERO,
-            active_troves: 0,
-            tcr: Decimal::ZERO,
-            stability_pool_balance: Decimal::ZERO,
-            treasury_balance: Decimal::ZERO,
+/// Where and when the numbers in a response were fetched
+///
+/// Cached responses keep the provenance of the original fetch, so
+/// `fetched_at` can be older than the request.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Provenance {
+    /// Upstream source ("rpc", "geckoterminal")
+    pub source: String,
+    /// Unix timestamp (seconds) when the upstream was queried
+    pub fetched_at: i64,
+    /// Chain head when the RPC calls were made - None for off-chain
+    /// sources or when the block number could not be fetched
+    pub block_number: Option<u64>,
+    /// Served past its cache TTL because a refresh is pending or failed
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// Age of displayed data relative to `STALE_DATA_SECS` / `STALE_DATA_WARNING_SECS`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataStaleness {
+    Fresh,
+    /// Past the stale threshold - shown dimmed
+    Stale,
+    /// Past the warning threshold - shown with a warning
+    VeryStale,
+}
+
+impl DataStaleness {
+    /// Extra CSS class for the card showing the value
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Fresh => "",
+            Self::Stale => "stale",
+            Self::VeryStale => "stale stale-warning",
         }
     }
 }
@@ -38,6 +114,29 @@ pub struct Transaction {
     pub timestamp: u64,
     pub block: u64,
     pub status: TransactionStatus,
+    /// Arrived after the caller's `highlight_since` timestamp
+    #[serde(default)]
+    pub is_new: bool,
+}
+
+impl Transaction {
+    /// Header line for transaction CSV exports
+    pub const CSV_HEADER: &'static str = "Hash,Type,Amount,From,To,Timestamp,Block,Status\n";
+
+    /// This transaction as one CSV line, matching `CSV_HEADER`
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{}\n",
+            self.hash,
+            self.tx_type.as_str(),
+            crate::format::decimal_to_f64(self.amount),
+            self.from,
+            self.to,
+            self.timestamp,
+            self.block,
+            self.status.as_str()
+        )
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Copy)]
@@ -114,6 +213,28 @@ pub struct Trove {
     pub debt: Decimal,
     pub icr: Decimal,
     pub status: TroveStatus,
+    #[serde(default)]
+    pub provenance: Provenance,
+}
+
+impl Trove {
+    /// Header line for trove CSV exports
+    pub const CSV_HEADER: &'static str = "Address,Collateral,Debt,ICR,Status\n";
+
+    /// This trove as one CSV line, matching `CSV_HEADER`
+    ///
+    /// Amounts are plain decimals with no thousands separators; ICR is
+    /// rounded to two places.
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{}\n",
+            self.address,
+            self.collateral.normalize(),
+            self.debt.normalize(),
+            self.icr.round_dp(2).normalize(),
+            self.status.as_str()
+        )
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Copy)]
@@ -162,6 +283,139 @@ impl TroveStatus {
     }
 }
 
+/// Dimension a trove leaderboard is ranked by
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TroveRankBy {
+    /// Largest debt first
+    Debt,
+    /// Largest collateral first
+    Collateral,
+    /// Lowest ICR (riskiest) first
+    Icr,
+}
+
+impl TroveRankBy {
+    pub const ALL: [TroveRankBy; 3] = [Self::Debt, Self::Collateral, Self::Icr];
+
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Debt => "debt",
+            Self::Collateral => "collateral",
+            Self::Icr => "icr",
+        }
+    }
+
+    #[inline]
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Debt => "Largest Debt",
+            Self::Collateral => "Largest Collateral",
+            Self::Icr => "Lowest ICR",
+        }
+    }
+
+    /// Rank troves by this dimension and keep the top `limit`
+    ///
+    /// Ties are broken by address so the order is stable between refreshes.
+    pub fn rank(&self, mut troves: Vec<Trove>, limit: usize) -> Vec<Trove> {
+        troves.sort_by(|a, b| {
+            let ord = match self {
+                Self::Debt => b.debt.cmp(&a.debt),
+                Self::Collateral => b.collateral.cmp(&a.collateral),
+                Self::Icr => a.icr.cmp(&b.icr),
+            };
+            ord.then_with(|| a.address.cmp(&b.address))
+        });
+        troves.truncate(limit);
+        troves
+    }
+
+    /// Sort troves by this dimension, ascending unless `descending`
+    ///
+    /// Ties are broken by address (always ascending), as in `rank`.
+    pub fn sort(&self, troves: &mut [Trove], descending: bool) {
+        troves.sort_by(|a, b| {
+            let ord = match self {
+                Self::Debt => a.debt.cmp(&b.debt),
+                Self::Collateral => a.collateral.cmp(&b.collateral),
+                Self::Icr => a.icr.cmp(&b.icr),
+            };
+            let ord = if descending { ord.reverse() } else { ord };
+            ord.then_with(|| a.address.cmp(&b.address))
+        });
+    }
+}
+
+/// Narrow troves to one `status`, sort them by `sort_by`, then keep the first `limit`
+///
+/// Filtering and sorting see every trove passed in, so a sorted page reflects
+/// the whole set rather than the order troves were fetched in.
+pub fn select_troves(
+    troves: Vec<Trove>,
+    status: Option<TroveStatus>,
+    sort_by: Option<TroveRankBy>,
+    descending: bool,
+    limit: usize,
+) -> Vec<Trove> {
+    let mut troves: Vec<Trove> = troves
+        .into_iter()
+        .filter(|t| status.is_none_or(|s| t.status == s))
+        .collect();
+    if let Some(sort_by) = sort_by {
+        sort_by.sort(&mut troves, descending);
+    }
+    troves.truncate(limit);
+    troves
+}
+
+/// Troves whose ICR falls in `[min, max)`, one bar of the ICR distribution
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IcrBucket {
+    /// Lower ICR bound in percent, inclusive; None for the lowest bucket
+    pub min: Option<f64>,
+    /// Upper ICR bound in percent, exclusive; None for the highest bucket
+    pub max: Option<f64>,
+    pub count: u32,
+    pub total_debt: Decimal,
+}
+
+impl IcrBucket {
+    /// Bucket troves by ICR at ascending `bounds` (percent)
+    ///
+    /// Gives one more bucket than there are bounds, empty buckets included,
+    /// so the bars line up between refreshes.
+    pub fn histogram(troves: &[Trove], bounds: &[f64]) -> Vec<Self> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let mut buckets: Vec<Self> = (0..=bounds.len())
+            .map(|i| Self {
+                min: i.checked_sub(1).map(|j| bounds[j]),
+                max: bounds.get(i).copied(),
+                count: 0,
+                total_debt: Decimal::ZERO,
+            })
+            .collect();
+        for trove in troves {
+            let icr = trove.icr.to_f64().unwrap_or(f64::MAX);
+            let index = bounds.iter().take_while(|&&bound| icr >= bound).count();
+            buckets[index].count += 1;
+            buckets[index].total_debt += trove.debt;
+        }
+        buckets
+    }
+
+    /// Range label such as "<110%", "110-125%" or "200%+"
+    pub fn label(&self) -> String {
+        match (self.min, self.max) {
+            (None, Some(max)) => format!("<{}%", max),
+            (Some(min), Some(max)) => format!("{}-{}%", min, max),
+            (Some(min), None) => format!("{}%+", min),
+            (None, None) => "All".to_string(),
+        }
+    }
+}
+
 /// Stability pool depositor
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct StabilityDepositor {
@@ -300,6 +554,32 @@ pub enum AlertCondition {
 }
 
 impl AlertCondition {
+    pub const ALL: [AlertCondition; 6] = [
+        Self::TcrBelow,
+        Self::TcrAbove,
+        Self::LargeMint,
+        Self::LargeBurn,
+        Self::StabilityPoolDrain,
+        Self::PriceDeviation,
+    ];
+
+    /// Stable identifier used in the database and query strings
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TcrBelow => "tcr_below",
+            Self::TcrAbove => "tcr_above",
+            Self::LargeMint => "large_mint",
+            Self::LargeBurn => "large_burn",
+            Self::StabilityPoolDrain => "stability_pool_drain",
+            Self::PriceDeviation => "price_deviation",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == value)
+    }
+
     #[inline]
     pub fn description(&self) -> &'static str {
         match self {
@@ -313,6 +593,78 @@ impl AlertCondition {
     }
 }
 
+/// An alert that fired, e.g. TCR crossing below the danger threshold
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AlertEvent {
+    pub kind: AlertCondition,
+    pub message: String,
+    /// Observed value that triggered the alert
+    pub value: f64,
+    pub threshold: f64,
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+}
+
+/// Review state of a recorded alert event
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AlertEventStatus {
+    /// Fired and not yet reviewed
+    #[default]
+    Open,
+    /// Seen by an operator
+    Acknowledged,
+    /// Reviewed and judged not actionable
+    Dismissed,
+}
+
+impl AlertEventStatus {
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Acknowledged => "acknowledged",
+            Self::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(Self::Open),
+            "acknowledged" => Some(Self::Acknowledged),
+            "dismissed" => Some(Self::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// A fired alert as stored in the alert history
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct StoredAlertEvent {
+    pub id: i64,
+    #[serde(flatten)]
+    pub event: AlertEvent,
+    pub status: AlertEventStatus,
+    /// When the status last changed from open (Unix seconds)
+    pub reviewed_at: Option<i64>,
+}
+
+/// One page of alert history, newest first
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AlertEventPage {
+    pub events: Vec<StoredAlertEvent>,
+    /// Events matching the filters across all pages
+    pub total: u64,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl AlertEventPage {
+    /// Whether events remain past this page
+    pub fn has_more(&self) -> bool {
+        (self.offset as u64 + self.events.len() as u64) < self.total
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Copy)]
 pub enum NotificationChannel {
     Email,
@@ -411,6 +763,7 @@ impl Default for BlockInfo {
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
 pub enum ChartResolution {
     M1,   // 1 minute
+    M3,   // 3 minutes
     M5,   // 5 minutes
     M15,  // 15 minutes
     M30,  // 30 minutes
@@ -427,6 +780,7 @@ impl ChartResolution {
     pub fn from_url_param(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "1m" => Some(Self::M1),
+            "3m" => Some(Self::M3),
             "5m" => Some(Self::M5),
             "15m" => Some(Self::M15),
             "30m" => Some(Self::M30),
@@ -445,11 +799,12 @@ impl ChartResolution {
         self.label()
     }
 
-    /// TradingView code (e.g., "1", "5", "15", "60", "240", "720", "D", "W")
+    /// TradingView code (e.g., "1", "3", "5", "15", "60", "240", "720", "D", "W")
     #[inline]
     pub fn tv_code(&self) -> &'static str {
         match self {
             Self::M1 => "1",
+            Self::M3 => "3",
             Self::M5 => "5",
             Self::M15 => "15",
             Self::M30 => "30",
@@ -466,6 +821,7 @@ impl ChartResolution {
     pub fn minutes(&self) -> u32 {
         match self {
             Self::M1 => 1,
+            Self::M3 => 3,
             Self::M5 => 5,
             Self::M15 => 15,
             Self::M30 => 30,
@@ -488,6 +844,7 @@ impl ChartResolution {
     pub fn label(&self) -> &'static str {
         match self {
             Self::M1 => "1m",
+            Self::M3 => "3m",
             Self::M5 => "5m",
             Self::M15 => "15m",
             Self::M30 => "30m",
@@ -504,6 +861,7 @@ impl ChartResolution {
     pub fn gecko_params(&self) -> (&'static str, u32, u32) {
         match self {
             Self::M1 => ("minute", 1, 100),
+            Self::M3 => ("minute", 3, 100),
             Self::M5 => ("minute", 5, 100),
             Self::M15 => ("minute", 15, 100),
             Self::M30 => ("minute", 30, 100),
@@ -530,10 +888,30 @@ impl ChartResolution {
         lookback_mins <= self.max_safe_lookback_mins()
     }
 
+    /// Maximum lookback, using a deployer override (`CHART_MAX_LOOKBACK`) if set
+    #[inline]
+    pub fn max_lookback_mins(&self, overrides: &LookbackOverrides) -> u32 {
+        overrides.get(self).copied().unwrap_or_else(|| self.max_safe_lookback_mins())
+    }
+
+    /// Human-readable maximum lookback, honouring overrides
+    pub fn lookback_description(&self, overrides: &LookbackOverrides) -> String {
+        let Some(&mins) = overrides.get(self) else {
+            return self.safe_lookback_description().to_string();
+        };
+        let (count, unit) = match mins {
+            m if m >= 1440 => (m / 1440, "day"),
+            m if m >= 60 => (m / 60, "hour"),
+            m => (m, "minute"),
+        };
+        format!("~{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+    }
+
     /// Get human-readable safe lookback description
     pub fn safe_lookback_description(&self) -> &'static str {
         match self {
             Self::M1 => "~1.7 hours",
+            Self::M3 => "~5 hours",
             Self::M5 => "~8 hours",
             Self::M15 => "~1 day",
             Self::M30 => "~2 days",
@@ -545,20 +923,34 @@ impl ChartResolution {
         }
     }
 
+    /// Finest resolution whose candle limit covers `lookback_mins`
+    pub fn finest_for(lookback_mins: u32) -> Self {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|r| r.is_lookback_safe(lookback_mins))
+            .unwrap_or(Self::W1)
+    }
+
     /// All available resolutions
     pub fn all() -> &'static [ChartResolution] {
         &[
-            Self::M1, Self::M5, Self::M15, Self::M30,
+            Self::M1, Self::M3, Self::M5, Self::M15, Self::M30,
             Self::H1, Self::H4, Self::H12, Self::D1, Self::W1,
         ]
     }
 }
 
+/// Per-resolution maximum lookback in minutes, replacing the GeckoTerminal
+/// defaults from `max_safe_lookback_mins` (e.g. for a paid API tier)
+pub type LookbackOverrides = HashMap<ChartResolution, u32>;
+
 /// Chart lookback period for real-time data
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
 pub enum ChartLookback {
     Hour1,   // 1 hour
     Hour4,   // 4 hours
+    Hour6,   // 6 hours
     Hour12,  // 12 hours
     Day1,    // 1 day
     Day3,    // 3 days
@@ -576,6 +968,7 @@ impl ChartLookback {
         match s.to_lowercase().as_str() {
             "1h" => Some(Self::Hour1),
             "4h" => Some(Self::Hour4),
+            "6h" => Some(Self::Hour6),
             "12h" => Some(Self::Hour12),
             "1d" => Some(Self::Day1),
             "3d" => Some(Self::Day3),
@@ -594,6 +987,7 @@ impl ChartLookback {
         match self {
             Self::Hour1 => "1h",
             Self::Hour4 => "4h",
+            Self::Hour6 => "6h",
             Self::Hour12 => "12h",
             Self::Day1 => "1d",
             Self::Day3 => "3d",
@@ -611,6 +1005,7 @@ impl ChartLookback {
         match self {
             Self::Hour1 => "1h",
             Self::Hour4 => "4h",
+            Self::Hour6 => "6h",
             Self::Hour12 => "12h",
             Self::Day1 => "1d",
             Self::Day3 => "3d",
@@ -628,6 +1023,7 @@ impl ChartLookback {
         match self {
             Self::Hour1 => 60,
             Self::Hour4 => 240,
+            Self::Hour6 => 360,
             Self::Hour12 => 720,
             Self::Day1 => 1440,
             Self::Day3 => 4320,
@@ -667,13 +1063,59 @@ impl ChartLookback {
     /// All available lookback periods
     pub fn all() -> &'static [ChartLookback] {
         &[
-            Self::Hour1, Self::Hour4, Self::Hour12, Self::Day1,
-            Self::Day3, Self::Week1, Self::Week2, Self::Month1,
-            Self::Month3, Self::All,
+            Self::Hour1, Self::Hour4, Self::Hour6, Self::Hour12,
+            Self::Day1, Self::Day3, Self::Week1, Self::Week2,
+            Self::Month1, Self::Month3, Self::All,
         ]
     }
 }
 
+/// A resolution/lookback pair after validation against API candle limits
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChartCombo {
+    pub resolution: ChartResolution,
+    pub lookback: ChartLookback,
+    /// Why the lookback was shortened - None when the requested pair was safe
+    pub reason: Option<String>,
+}
+
+/// Validate a resolution/lookback pair
+///
+/// Lookbacks the resolution cannot cover within its maximum lookback
+/// (GeckoTerminal's candle limit unless overridden) are shortened to the
+/// longest preset that fits; the resolution is kept. Used by
+/// `get_advanced_chart_data` and by the chart controls, so the client never
+/// requests a pair the server would clamp.
+pub fn validate_combo(
+    resolution: ChartResolution,
+    lookback: ChartLookback,
+    overrides: &LookbackOverrides,
+) -> ChartCombo {
+    let max_mins = resolution.max_lookback_mins(overrides);
+    if lookback.minutes() <= max_mins {
+        return ChartCombo { resolution, lookback, reason: None };
+    }
+
+    let adjusted = ChartLookback::all()
+        .iter()
+        .copied()
+        .filter(|lb| lb.minutes() <= max_mins)
+        .max_by_key(|lb| lb.minutes())
+        .unwrap_or(ChartLookback::Hour1);
+
+    ChartCombo {
+        resolution,
+        lookback: adjusted,
+        reason: Some(format!(
+            "{} candles cover at most {}; lookback {} shortened to {}",
+            resolution.label(),
+            resolution.lookback_description(overrides),
+            lookback.label(),
+            adjusted.label()
+        )),
+    }
+}
+
 /// TradingView-compatible OHLCV candle
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct TVCandle {
@@ -1115,6 +1557,172 @@ impl ChartType {
     }
 }
 
+/// Chart state that can be serialized to/from URL query params
+///
+/// Shared by the interactive chart's share links and the server-rendered
+/// chart image endpoint, so both accept the same parameters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChartUrlState {
+    pub metrics: Vec<ChartMetric>,
+    pub resolution: Option<ChartResolution>,
+    pub lookback: Option<ChartLookback>,
+    pub chart_type: Option<ChartType>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+impl ChartUrlState {
+    /// Parse chart state from a query string, with or without the leading '?'
+    /// Format: metrics=price,volume&res=1h&lookback=1w&type=area&start=1704067200&end=1704153600
+    pub fn from_query(query: &str) -> Self {
+        let mut state = Self::default();
+        let query = query.strip_prefix('?').unwrap_or(query);
+
+        // Parse query params manually (avoiding external dependencies)
+        for param in query.split('&').filter(|p| !p.is_empty()) {
+            let mut parts = param.splitn(2, '=');
+            let key = parts.next().unwrap_or("");
+            let value = parts.next().unwrap_or("");
+
+            match key {
+                "metrics" => {
+                    // Accept commas percent-encoded by clients that escape them
+                    state.metrics = value
+                        .replace("%2C", ",")
+                        .replace("%2c", ",")
+                        .split(',')
+                        .filter_map(ChartMetric::from_url_param)
+                        .collect();
+                }
+                "res" => {
+                    state.resolution = ChartResolution::from_url_param(value);
+                }
+                "lookback" => {
+                    state.lookback = ChartLookback::from_url_param(value);
+                }
+                "type" => {
+                    state.chart_type = ChartType::from_url_param(value);
+                }
+                "start" => {
+                    state.start = value.parse().ok();
+                }
+                "end" => {
+                    state.end = value.parse().ok();
+                }
+                _ => {}
+            }
+        }
+
+        state
+    }
+
+    /// Build URL query string from current state
+    pub fn to_query_string(
+        metrics: &HashSet<ChartMetric>,
+        resolution: ChartResolution,
+        lookback: ChartLookback,
+        chart_type: ChartType,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> String {
+        let mut params = Vec::new();
+
+        // Metrics (comma-separated)
+        if !metrics.is_empty() {
+            let mut metrics_list: Vec<&str> = metrics.iter().map(|m| m.to_url_param()).collect();
+            metrics_list.sort(); // Consistent ordering
+            params.push(format!("metrics={}", metrics_list.join(",")));
+        }
+
+        // Resolution
+        params.push(format!("res={}", resolution.to_url_param()));
+
+        // Chart type
+        params.push(format!("type={}", chart_type.to_url_param()));
+
+        // Custom date range or lookback
+        if let (Some(s), Some(e)) = (start, end) {
+            params.push(format!("start={}", s));
+            params.push(format!("end={}", e));
+        } else {
+            params.push(format!("lookback={}", lookback.to_url_param()));
+        }
+
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+}
+
+/// Default moving-average windows (in candles) overlaid on the price chart
+pub const DEFAULT_MA_WINDOWS: [u32; 2] = [20, 50];
+
+/// Moving average flavour
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MovingAverageKind {
+    Simple,
+    Exponential,
+}
+
+impl MovingAverageKind {
+    #[inline]
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Simple => "SMA",
+            Self::Exponential => "EMA",
+        }
+    }
+}
+
+/// Moving average overlay computed over price candle closes
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MovingAverageSeries {
+    pub kind: MovingAverageKind,
+    pub window: u32,
+    /// (timestamp, value) - starts at the first candle with a full window
+    pub data: Vec<(i64, f64)>,
+}
+
+impl MovingAverageSeries {
+    /// Compute a moving average over candle closes
+    ///
+    /// The EMA is seeded with the SMA of the first window. Windows longer than
+    /// the candle count produce an empty series.
+    pub fn compute(kind: MovingAverageKind, window: u32, candles: &[TVCandle]) -> Self {
+        let w = window as usize;
+        let mut data = Vec::new();
+        if w > 0 && candles.len() >= w {
+            let seed: f64 = candles[..w].iter().map(|c| c.close).sum::<f64>() / w as f64;
+            data.push((candles[w - 1].time, seed));
+            match kind {
+                MovingAverageKind::Simple => {
+                    let mut sum = seed * w as f64;
+                    for i in w..candles.len() {
+                        sum += candles[i].close - candles[i - w].close;
+                        data.push((candles[i].time, sum / w as f64));
+                    }
+                }
+                MovingAverageKind::Exponential => {
+                    let alpha = 2.0 / (w as f64 + 1.0);
+                    let mut ema = seed;
+                    for c in &candles[w..] {
+                        ema += alpha * (c.close - ema);
+                        data.push((c.time, ema));
+                    }
+                }
+            }
+        }
+        Self { kind, window, data }
+    }
+
+    /// Legend/series name, e.g. "SMA 20"
+    pub fn name(&self) -> String {
+        format!("{} {}", self.kind.label(), self.window)
+    }
+}
+
 /// Aggregated chart data response with all metrics
 /// SAFETY: Critical metrics use Option<f64> - None means unavailable (not fallback values)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -1123,6 +1731,10 @@ pub struct ChartDataResponse {
     pub lookback: ChartLookback,
     pub generated_at: i64,
     pub fetch_time_ms: u32,
+    /// Elapsed millis per upstream call (e.g. "gecko_ohlcv", "rpc_tcr");
+    /// parallel calls overlap, so these do not sum to `fetch_time_ms`
+    #[serde(default)]
+    pub timings: BTreeMap<String, u32>,
     // Price OHLCV candles from GeckoTerminal
     pub price_candles: Vec<TVCandle>,
     // Volume data - extracted from candles for separate rendering
@@ -1141,6 +1753,9 @@ pub struct ChartDataResponse {
     pub borrow_apr_data: Vec<(i64, f64)>,
     // Transfer count from Blockscout
     pub transfers_data: Vec<(i64, u64)>,
+    // SMA/EMA overlays over the price candles
+    #[serde(default)]
+    pub moving_averages: Vec<MovingAverageSeries>,
     // Current values for display - Option means unavailable, NOT fake fallbacks
     /// Current price - None if API failed (NEVER use 1.0 fallback - masks depegging)
     pub current_price: Option<f64>,
@@ -1155,6 +1770,56 @@ pub struct ChartDataResponse {
     // Metadata for progressive enhancement
     pub snapshot_count: usize,
     pub oldest_snapshot_time: Option<i64>,
+    /// Snapshot collector interval used to estimate when history fills in
+    #[serde(default = "default_snapshot_interval_secs")]
+    pub snapshot_interval_secs: u64,
+    /// Configured per-resolution lookback limits, so the chart controls
+    /// match what the server allows
+    #[serde(default)]
+    pub lookback_overrides: LookbackOverrides,
+}
+
+/// Snapshots needed before historical charts show full trends
+pub const FULL_CHART_SNAPSHOTS: usize = 10;
+
+fn default_snapshot_interval_secs() -> u64 {
+    60
+}
+
+impl ChartDataResponse {
+    /// File name for a JSON export, unique per resolution, lookback and fetch time
+    pub fn export_filename(&self) -> String {
+        format!(
+            "usdfc_chart_{}_{}_{}.json",
+            self.resolution.to_url_param(),
+            self.lookback.to_url_param(),
+            self.generated_at
+        )
+    }
+
+    /// Estimated minutes until `FULL_CHART_SNAPSHOTS` have been collected
+    ///
+    /// Returns `None` once enough snapshots exist.
+    pub fn minutes_until_full_charts(&self) -> Option<u64> {
+        let remaining = FULL_CHART_SNAPSHOTS.saturating_sub(self.snapshot_count) as u64;
+        (remaining > 0).then(|| (remaining * self.snapshot_interval_secs.max(1)).div_ceil(60))
+    }
+
+    /// `(timestamp, value)` points for one metric; price uses candle closes
+    pub fn metric_series(&self, metric: ChartMetric) -> Vec<(i64, f64)> {
+        let counts = |series: &[(i64, u64)]| series.iter().map(|&(ts, v)| (ts, v as f64)).collect();
+        match metric {
+            ChartMetric::Price => self.price_candles.iter().map(|c| (c.time, c.close)).collect(),
+            ChartMetric::Volume => self.volume_data.clone(),
+            ChartMetric::Liquidity => self.liquidity_data.clone(),
+            ChartMetric::TCR => self.tcr_data.clone(),
+            ChartMetric::Supply => self.supply_data.clone(),
+            ChartMetric::Holders => counts(&self.holders_data),
+            ChartMetric::LendAPR => self.lend_apr_data.clone(),
+            ChartMetric::BorrowAPR => self.borrow_apr_data.clone(),
+            ChartMetric::Transfers => counts(&self.transfers_data),
+        }
+    }
 }
 
 impl Default for ChartDataResponse {
@@ -1164,6 +1829,7 @@ impl Default for ChartDataResponse {
             lookback: ChartLookback::default(),
             generated_at: 0,
             fetch_time_ms: 0,
+            timings: BTreeMap::new(),
             price_candles: Vec::new(),
             volume_data: Vec::new(),
             liquidity_data: Vec::new(),
@@ -1173,6 +1839,7 @@ impl Default for ChartDataResponse {
             lend_apr_data: Vec::new(),
             borrow_apr_data: Vec::new(),
             transfers_data: Vec::new(),
+            moving_averages: Vec::new(),
             // SAFETY: All None - no fake fallback values that mask real issues
             current_price: None,
             current_volume_24h: None,
@@ -1184,10 +1851,42 @@ impl Default for ChartDataResponse {
             current_borrow_apr: None,
             snapshot_count: 0,
             oldest_snapshot_time: None,
+            snapshot_interval_secs: default_snapshot_interval_secs(),
+            lookback_overrides: LookbackOverrides::new(),
         }
     }
 }
 
+/// Value range of one chart series, for drawing several metrics on a shared
+/// percent-of-range axis
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeriesRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl SeriesRange {
+    /// Range of the finite values, None when there are none
+    pub fn of(values: impl IntoIterator<Item = f64>) -> Option<Self> {
+        values
+            .into_iter()
+            .filter(|v| v.is_finite())
+            .fold(None, |range, v| match range {
+                None => Some(Self { min: v, max: v }),
+                Some(r) => Some(Self { min: r.min.min(v), max: r.max.max(v) }),
+            })
+    }
+
+    /// Position of `value` in the range as 0-100; a flat series sits at 50
+    pub fn percent(&self, value: f64) -> f64 {
+        let span = self.max - self.min;
+        if span.abs() < f64::EPSILON {
+            return 50.0;
+        }
+        ((value - self.min) / span * 100.0).clamp(0.0, 100.0)
+    }
+}
+
 /// Wallet-specific chart data
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct WalletChartData {
@@ -1249,17 +1948,95 @@ pub struct OperationsData {
 // Server Function Data Types
 // ============================================================================
 
+/// Secured Finance market currency
+///
+/// The subgraph identifies currencies by their ASCII symbol packed into a
+/// zero-padded bytes32. Serializes as the symbol ("USDFC") and deserializes
+/// from either the symbol or the bytes32 hex.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum Currency {
+    Usdfc,
+    Fil,
+    /// Any other symbol, as decoded
+    Other(String),
+}
+
+impl Currency {
+    /// Currency for a symbol such as "USDFC" (case-sensitive)
+    pub fn from_symbol(symbol: &str) -> Self {
+        match symbol {
+            "USDFC" => Self::Usdfc,
+            "FIL" => Self::Fil,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Decode a `0x`-prefixed bytes32, dropping padding and non-ASCII bytes
+    pub fn from_bytes32(bytes32: &str) -> Self {
+        let hex = bytes32.trim_start_matches("0x");
+        let symbol: String = hex
+            .as_bytes()
+            .chunks(2)
+            .filter_map(|pair| std::str::from_utf8(pair).ok())
+            .filter_map(|pair| u8::from_str_radix(pair, 16).ok())
+            .filter(|byte| (1..128).contains(byte))
+            .map(char::from)
+            .collect();
+        Self::from_symbol(symbol.trim())
+    }
+
+    /// Encode as the subgraph's `0x`-prefixed, zero-padded bytes32
+    pub fn to_bytes32(&self) -> String {
+        let hex: String = self.symbol().bytes().take(32).map(|b| format!("{:02x}", b)).collect();
+        format!("0x{:0<64}", hex)
+    }
+
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::Usdfc => "USDFC",
+            Self::Fil => "FIL",
+            Self::Other(symbol) => symbol,
+        }
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl From<String> for Currency {
+    fn from(value: String) -> Self {
+        if value.starts_with("0x") {
+            Self::from_bytes32(&value)
+        } else {
+            Self::from_symbol(&value)
+        }
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.symbol().to_string()
+    }
+}
+
 /// Lending market data from subgraph
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LendingMarketData {
     pub maturity: String,
-    pub currency: String,
+    pub currency: Currency,
     pub lend_unit_price: String,
     pub borrow_unit_price: String,
     pub volume: String,
     pub is_active: bool,
     pub lend_apr: f64,
     pub borrow_apr: f64,
+    /// Highlighted at the top of the lending page (see `FEATURED_MARKETS`)
+    #[serde(default)]
+    pub featured: bool,
 }
 
 /// Daily volume data point for charts
@@ -1271,6 +2048,35 @@ pub struct DailyVolumeData {
     pub currency: String,
 }
 
+/// Daily volume series with a completeness flag
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DailyVolumesResponse {
+    pub volumes: Vec<DailyVolumeData>,
+    /// False when the subgraph fetch stopped early or rows failed to parse
+    pub complete: bool,
+}
+
+impl DailyVolumesResponse {
+    /// Days returned when the caller does not ask for a window
+    pub const DEFAULT_DAYS: i32 = 30;
+    /// Longest window served, in days
+    pub const MAX_DAYS: i32 = 365;
+
+    /// Resolve a requested `days` window to `1..=MAX_DAYS`
+    ///
+    /// Zero and oversized windows are clamped; negative windows are rejected.
+    pub fn validate_days(days: Option<i32>) -> Result<i32, crate::error::ValidationError> {
+        match days.unwrap_or(Self::DEFAULT_DAYS) {
+            days if days < 0 => Err(crate::error::ValidationError::OutOfRange {
+                field: "days",
+                min: 1.0,
+                max: Self::MAX_DAYS as f64,
+            }),
+            days => Ok(days.clamp(1, Self::MAX_DAYS)),
+        }
+    }
+}
+
 /// Address info response
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AddressInfo {
@@ -1304,11 +2110,85 @@ pub struct NormalizedAddress {
     pub blockscout: Option<String>,
 }
 
+/// Maximum length of an address note, in characters
+pub const ADDRESS_NOTE_MAX_LEN: usize = 280;
+
+/// Private operator note attached to an address
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddressNote {
+    /// Lowercased address the note is stored under
+    pub address: String,
+    pub note: String,
+    pub updated_at: i64,
+}
+
+/// Maximum length of a watchlist label, in characters
+pub const WATCH_LABEL_MAX_LEN: usize = 64;
+
+/// Address saved to a watchlist
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WatchEntry {
+    /// Lowercased address
+    pub address: String,
+    pub label: String,
+    pub added_at: i64,
+}
+
+/// Decimals assumed for USDFC amounts when the token metadata is unavailable
+pub const DEFAULT_TOKEN_DECIMALS: u32 = 18;
+
+/// ERC-20 metadata of the tracked token
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TokenInfo {
+    /// Contract address
+    pub address: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+    pub total_supply: Decimal,
+}
+
 /// Token holder info
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TokenHolderInfo {
     pub address: String,
     pub balance: Decimal,
+    /// Share of total supply, in percent; None when supply is unavailable
+    #[serde(default)]
+    pub supply_pct: Option<f64>,
+    /// Share of total supply held by this and all larger holders in the list
+    #[serde(default)]
+    pub cumulative_pct: Option<f64>,
+}
+
+impl TokenHolderInfo {
+    pub fn new(address: String, balance: Decimal) -> Self {
+        Self { address, balance, supply_pct: None, cumulative_pct: None }
+    }
+
+    /// Fill `supply_pct` and `cumulative_pct` for holders listed largest first
+    ///
+    /// Both are cleared when `total_supply` is unknown or zero.
+    pub fn set_supply_shares(holders: &mut [Self], total_supply: Option<Decimal>) {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let total = total_supply.filter(|t| *t > Decimal::ZERO);
+        let mut cumulative = Decimal::ZERO;
+        for holder in holders {
+            cumulative += holder.balance;
+            let pct = |amount: Decimal| total.and_then(|t| (amount / t * Decimal::ONE_HUNDRED).to_f64());
+            holder.supply_pct = pct(holder.balance);
+            holder.cumulative_pct = pct(cumulative);
+        }
+    }
+}
+
+/// One cursor-paginated page of USDFC holders, largest balances first
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HoldersPage {
+    pub holders: Vec<TokenHolderInfo>,
+    /// Pass back as `cursor` to fetch the next page; None on the last page
+    pub next_cursor: Option<String>,
 }
 
 /// USDFC price and market data from DEX
@@ -1320,6 +2200,270 @@ pub struct USDFCPriceData {
     pub price_change_24h: Option<f64>,
     pub volume_24h: Option<f64>,
     pub liquidity_usd: Option<f64>,
+    #[serde(default)]
+    pub provenance: Provenance,
+    /// Per-pool figures behind the aggregate, including pools that failed
+    #[serde(default)]
+    pub pools: Option<Vec<PoolPriceData>>,
+}
+
+/// One DEX pool's contribution to the aggregated USDFC price
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PoolPriceData {
+    pub address: String,
+    pub name: Option<String>,
+    pub price_usd: Option<f64>,
+    pub price_change_24h: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub liquidity_usd: Option<f64>,
+    /// Set when the pool could not be fetched
+    pub error: Option<String>,
+}
+
+impl USDFCPriceData {
+    /// Combine per-pool figures into one quote
+    ///
+    /// Price and 24h change are averaged weighted by pool liquidity, falling
+    /// back to a plain mean when no priced pool reports liquidity. Volume and
+    /// liquidity are summed. Each field is None only when no pool reported it.
+    pub fn from_pools(pools: Vec<PoolPriceData>, provenance: Provenance) -> Self {
+        let weighted = |value: fn(&PoolPriceData) -> Option<f64>| -> Option<f64> {
+            let samples: Vec<(f64, f64)> = pools
+                .iter()
+                .filter_map(|p| value(p).map(|v| (v, p.liquidity_usd.unwrap_or(0.0).max(0.0))))
+                .collect();
+            if samples.is_empty() {
+                return None;
+            }
+            let total_weight: f64 = samples.iter().map(|(_, w)| w).sum();
+            if total_weight > 0.0 {
+                Some(samples.iter().map(|(v, w)| v * w).sum::<f64>() / total_weight)
+            } else {
+                Some(samples.iter().map(|(v, _)| v).sum::<f64>() / samples.len() as f64)
+            }
+        };
+        let summed = |value: fn(&PoolPriceData) -> Option<f64>| -> Option<f64> {
+            pools.iter().filter_map(value).reduce(|a, b| a + b)
+        };
+
+        Self {
+            price_usd: weighted(|p| p.price_usd),
+            price_change_24h: weighted(|p| p.price_change_24h),
+            volume_24h: summed(|p| p.volume_24h),
+            liquidity_usd: summed(|p| p.liquidity_usd),
+            provenance,
+            pools: Some(pools),
+        }
+    }
+}
+
+/// Time-weighted average USDFC price over a window, compared with spot
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TwapData {
+    pub window: ChartLookback,
+    /// Candle resolution the TWAP was computed from
+    pub resolution: ChartResolution,
+    /// None when no candles fell in or before the window
+    pub twap: Option<f64>,
+    pub spot_price: Option<f64>,
+    /// (spot - twap) / twap as a percentage
+    pub deviation_pct: Option<f64>,
+    pub candle_count: usize,
+    pub timestamp: i64,
+}
+
+/// How far USDFC has drifted from its peg
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DepegSeverity {
+    #[default]
+    Ok,
+    Warning,
+    Critical,
+}
+
+/// USDFC price checked against the peg target
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DepegStatus {
+    /// None when no price is available, which is reported as critical
+    pub price_usd: Option<f64>,
+    pub peg_target: f64,
+    /// |price - peg| / peg as a percentage
+    pub deviation_pct: Option<f64>,
+    pub severity: DepegSeverity,
+    pub timestamp: i64,
+}
+
+impl DepegStatus {
+    /// Grade a price against the peg; deviations above a threshold (in percent) reach its level
+    pub fn evaluate(
+        price_usd: Option<f64>,
+        peg_target: f64,
+        warning_pct: f64,
+        critical_pct: f64,
+        timestamp: i64,
+    ) -> Self {
+        let deviation_pct = price_usd
+            .filter(|_| peg_target > 0.0)
+            .map(|price| (price - peg_target).abs() / peg_target * 100.0);
+        let severity = match deviation_pct {
+            None => DepegSeverity::Critical,
+            Some(d) if d > critical_pct => DepegSeverity::Critical,
+            Some(d) if d > warning_pct => DepegSeverity::Warning,
+            Some(_) => DepegSeverity::Ok,
+        };
+        Self {
+            price_usd,
+            peg_target,
+            deviation_pct,
+            severity,
+            timestamp,
+        }
+    }
+}
+
+/// Live TCR scored against its trailing mean, as a volatility indicator
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TcrVolatility {
+    pub window_mins: u32,
+    pub tcr: f64,
+    /// Standard deviations from the window mean - None until enough snapshots are recorded
+    pub tcr_zscore: Option<f64>,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,
+    pub samples: usize,
+    pub timestamp: i64,
+}
+
+/// Protocol fee income for one time bucket
+///
+/// A `None` series value means fee events in that bucket could not be
+/// decoded (or were not scanned), not that no fees were paid.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FeeBucket {
+    /// Bucket start (Unix seconds)
+    pub timestamp: i64,
+    /// Borrowing fees paid in the bucket, in USDFC
+    pub borrowing_fees: Option<Decimal>,
+    /// Redemption fees paid in the bucket, in FIL
+    pub redemption_fees_fil: Option<Decimal>,
+    /// Borrowing fees from the window start through this bucket
+    pub cumulative_borrowing_fees: Option<Decimal>,
+    /// Redemption fees from the window start through this bucket
+    pub cumulative_redemption_fees_fil: Option<Decimal>,
+}
+
+/// Borrowing and redemption fee income over a lookback window
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeeAnalytics {
+    pub lookback: ChartLookback,
+    /// Bucket size - coarser than requested when the window would have too many buckets
+    pub resolution: ChartResolution,
+    pub buckets: Vec<FeeBucket>,
+    /// Total borrowing fees in USDFC - None when any bucket is unknown
+    pub total_borrowing_fees: Option<Decimal>,
+    /// Total redemption fees in FIL - None when any bucket is unknown
+    pub total_redemption_fees_fil: Option<Decimal>,
+    pub borrowing_events: usize,
+    pub redemption_events: usize,
+    pub timestamp: i64,
+}
+
+/// Which kind of protocol stress event a `LiquidationEvent` records
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LiquidationKind {
+    Liquidation,
+    Redemption,
+}
+
+/// A liquidation or redemption decoded from TroveManager logs
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LiquidationEvent {
+    pub kind: LiquidationKind,
+    pub tx_hash: String,
+    /// Liquidated trove owner - None for redemptions, which can hit several troves
+    pub borrower: Option<String>,
+    /// FIL taken from the trove, or sent to the redeemer for a redemption
+    pub collateral_liquidated: Decimal,
+    /// USDFC debt cleared
+    pub debt_repaid: Decimal,
+    pub timestamp: i64,
+}
+
+/// A stability pool depositor's current USDFC deposit
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PoolDepositor {
+    pub address: String,
+    /// Compounded deposit, net of USDFC spent absorbing liquidations
+    pub deposit: Decimal,
+    /// Share of all stability pool deposits, in percent; None when the pool total is unavailable
+    pub share_pct: Option<f64>,
+}
+
+impl PoolDepositor {
+    /// Rank non-zero deposits largest first (ties by address) with their pool shares
+    pub fn rank(deposits: Vec<(String, Decimal)>, pool_total: Option<Decimal>) -> Vec<Self> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let total = pool_total.filter(|t| !t.is_zero());
+        let mut depositors: Vec<Self> = deposits
+            .into_iter()
+            .filter(|(_, deposit)| !deposit.is_zero())
+            .map(|(address, deposit)| Self {
+                share_pct: total.and_then(|t| (deposit / t * Decimal::ONE_HUNDRED).to_f64()),
+                address,
+                deposit,
+            })
+            .collect();
+        depositors.sort_by(|a, b| b.deposit.cmp(&a.deposit).then_with(|| a.address.cmp(&b.address)));
+        depositors
+    }
+}
+
+/// Base fee and gas usage of one recent block
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GasSample {
+    pub block: u64,
+    /// Base fee in attoFIL per gas unit
+    pub base_fee: u64,
+    /// Fraction of the block gas target used (0-1)
+    pub gas_used_ratio: f64,
+}
+
+/// Current Filecoin base fee with a short recent history
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct NetworkGas {
+    /// Base fee for the next block, in attoFIL per gas unit
+    pub base_fee: u64,
+    /// Recent blocks, oldest first
+    pub samples: Vec<GasSample>,
+    /// Mean gas used ratio over `samples`; None with no samples
+    pub congestion: Option<f64>,
+}
+
+/// Runtime state of one server-side cache
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub name: String,
+    /// Entries held, including expired ones not yet cleaned up
+    pub entries: usize,
+    pub ttl_secs: u64,
+    /// Age of the oldest and newest entries - None when the cache is empty
+    pub oldest_age_secs: Option<u64>,
+    pub newest_age_secs: Option<u64>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// When a server-side cache was last populated, for `/health/cache`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CacheFreshness {
+    pub name: String,
+    pub ttl_secs: u64,
+    /// Unix seconds of the last successful store - None if never populated
+    pub last_populated: Option<i64>,
+    pub age_secs: Option<u64>,
+    /// Last store is younger than the TTL
+    pub fresh: bool,
 }
 
 /// API health status for all data sources
@@ -1332,19 +2476,113 @@ pub struct ApiHealthStatus {
     pub gecko_ok: bool,
     /// Historical SQLite database health
     pub database_ok: bool,
+    /// Sources disabled by configuration ("blockscout", "subgraph", "gecko")
+    #[serde(default)]
+    pub disabled_sources: Vec<String>,
+    /// Circuit breaker state per upstream ("rpc", "blockscout", "subgraph", "gecko")
+    #[serde(default)]
+    pub circuits: Vec<(String, CircuitState)>,
+    /// Upstreams whose health check failed by timing out
+    #[serde(default)]
+    pub timed_out: Vec<String>,
     pub timestamp: i64,
 }
 
+impl ApiHealthStatus {
+    /// Whether a data source was disabled by configuration rather than failing
+    pub fn is_disabled(&self, source: &str) -> bool {
+        self.disabled_sources.iter().any(|s| s == source)
+    }
+
+    /// Whether an upstream's health check timed out rather than erroring
+    pub fn is_timed_out(&self, source: &str) -> bool {
+        self.timed_out.iter().any(|s| s == source)
+    }
+
+    /// Circuit state of an upstream; closed when it was not reported
+    pub fn circuit(&self, upstream: &str) -> CircuitState {
+        self.circuits
+            .iter()
+            .find(|(name, _)| name == upstream)
+            .map_or(CircuitState::Closed, |(_, state)| state.clone())
+    }
+}
+
+/// Upstream circuit breaker state
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CircuitState {
+    /// Circuit is closed, requests flow normally
+    Closed,
+    /// Circuit is open, requests are blocked
+    Open,
+    /// Circuit is half-open, testing if endpoint has recovered
+    HalfOpen,
+}
+
 /// Order book data for display
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrderBookData {
-    pub currency: String,
+    pub currency: Currency,
     pub maturity: Option<String>,
     pub lend_orders: Vec<OrderData>,
     pub borrow_orders: Vec<OrderData>,
     pub best_lend_price: Option<f64>,
     pub best_borrow_price: Option<f64>,
     pub spread_bps: Option<f64>,
+    /// Open (unfilled) lend side flow
+    #[serde(default)]
+    pub lend_flow: OrderFlowSide,
+    /// Open (unfilled) borrow side flow
+    #[serde(default)]
+    pub borrow_flow: OrderFlowSide,
+    /// (lend - borrow) / (lend + borrow) open volume, from -1.0 to 1.0
+    #[serde(default)]
+    pub imbalance: Option<f64>,
+}
+
+/// Order flow summary for one side of the order book
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct OrderFlowSide {
+    /// Number of orders with an unfilled remainder
+    pub order_count: usize,
+    /// Total unfilled amount
+    pub volume: f64,
+    /// Price weighted by unfilled amount - None when the side is empty
+    pub weighted_avg_price: Option<f64>,
+}
+
+impl OrderFlowSide {
+    /// Summarize open orders, skipping fully-filled ones
+    pub fn from_orders(orders: &[OrderData]) -> Self {
+        let mut flow = Self::default();
+        let mut weighted_sum = 0.0;
+        for order in orders {
+            let remaining = order.amount - order.filled;
+            if remaining <= 0.0 {
+                continue;
+            }
+            flow.order_count += 1;
+            flow.volume += remaining;
+            weighted_sum += order.price * remaining;
+        }
+        if flow.volume > 0.0 {
+            flow.weighted_avg_price = Some(weighted_sum / flow.volume);
+        }
+        flow
+    }
+}
+
+impl OrderBookData {
+    /// Spread between the best borrow and lend prices, in basis points
+    pub fn spread_between(best_lend_price: Option<f64>, best_borrow_price: Option<f64>) -> Option<f64> {
+        Some((best_borrow_price? - best_lend_price?) * 10000.0)
+    }
+
+    /// Lend/borrow volume imbalance - positive when lend volume dominates
+    pub fn volume_imbalance(lend: &OrderFlowSide, borrow: &OrderFlowSide) -> Option<f64> {
+        let total = lend.volume + borrow.volume;
+        (total > 0.0).then(|| (lend.volume - borrow.volume) / total)
+    }
 }
 
 /// Single order for display
@@ -1364,7 +2602,7 @@ pub struct OrderData {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LendingTradeData {
     pub id: String,
-    pub currency: String,
+    pub currency: Currency,
     pub maturity: String,
     pub side: String,
     pub amount: f64,
@@ -1381,6 +2619,13 @@ pub struct WalletBucket {
     pub volume_out: f64,
     pub count_in: u64,
     pub count_out: u64,
+    /// volume_in - volume_out
+    #[serde(default)]
+    pub net_flow: f64,
+    /// Balance after this bucket: net flow accumulated from the window start,
+    /// on top of `starting_balance` when known, otherwise relative to zero
+    #[serde(default)]
+    pub running_balance: f64,
 }
 
 /// Wallet analytics response
@@ -1392,4 +2637,545 @@ pub struct WalletAnalyticsResponse {
     pub total_out: f64,
     pub first_seen: Option<String>,
     pub last_active: Option<String>,
+    /// Estimated balance at the window start: the current Blockscout balance
+    /// minus the window's net flow
+    ///
+    /// An approximation: it assumes every transfer since the window start was
+    /// seen, but only the most recent transfers are scanned, so older flows
+    /// in a long window can be missed. None when the window ends in the past
+    /// (later flows are not fetched) or the balance lookup fails.
+    #[serde(default)]
+    pub starting_balance: Option<f64>,
+}
+
+impl WalletAnalyticsResponse {
+    /// Fill `net_flow` and `running_balance` on the time-ordered buckets
+    pub fn accumulate_balances(&mut self) {
+        let mut balance = self.starting_balance.unwrap_or(0.0);
+        for bucket in &mut self.buckets {
+            bucket.net_flow = bucket.volume_in - bucket.volume_out;
+            balance += bucket.net_flow;
+            bucket.running_balance = balance;
+        }
+    }
+}
+
+/// Two wallets' analytics over the same window, for side-by-side comparison
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WalletComparison {
+    /// Buckets are aligned with `b`: both hold the same timestamps in the
+    /// same order, with empty buckets where only the other wallet was active
+    pub a: WalletAnalyticsResponse,
+    pub b: WalletAnalyticsResponse,
+    /// Transfers between the two wallets in the window, oldest first
+    pub direct_transfers: Vec<Transaction>,
+}
+
+impl WalletComparison {
+    /// Pair two wallets' analytics, padding their buckets onto a shared timeline
+    pub fn new(mut a: WalletAnalyticsResponse, mut b: WalletAnalyticsResponse, direct_transfers: Vec<Transaction>) -> Self {
+        let timestamps: std::collections::BTreeSet<i64> =
+            a.buckets.iter().chain(&b.buckets).map(|bucket| bucket.timestamp).collect();
+        for side in [&mut a, &mut b] {
+            for &timestamp in &timestamps {
+                if !side.buckets.iter().any(|bucket| bucket.timestamp == timestamp) {
+                    side.buckets.push(WalletBucket {
+                        timestamp,
+                        volume_in: 0.0,
+                        volume_out: 0.0,
+                        count_in: 0,
+                        count_out: 0,
+                        net_flow: 0.0,
+                        running_balance: 0.0,
+                    });
+                }
+            }
+            side.buckets.sort_by_key(|bucket| bucket.timestamp);
+            side.accumulate_balances();
+        }
+        Self { a, b, direct_transfers }
+    }
+}
+
+/// Stability pool deposit/withdrawal flow over a time window
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StabilityFlowWindow {
+    pub window_hours: u64,
+    pub deposits: Decimal,
+    pub withdrawals: Decimal,
+    pub net_flow: Decimal,
+    pub deposit_count: u64,
+    pub withdrawal_count: u64,
+}
+
+/// Stability pool analytics response
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StabilityPoolAnalytics {
+    pub pool_balance: Decimal,
+    /// Addresses with a positive net deposit across the fetched transfers
+    pub depositor_count: u64,
+    pub windows: Vec<StabilityFlowWindow>,
+    pub recent_transfers: Vec<Transaction>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trove_csv_row_uses_plain_decimals() {
+        let trove = Trove {
+            address: "0xabc".to_string(),
+            collateral: Decimal::new(12345675000, 4),
+            debt: Decimal::new(2500000, 0),
+            icr: Decimal::new(1734567, 4),
+            status: TroveStatus::AtRisk,
+            provenance: Provenance::default(),
+        };
+        assert_eq!(trove.csv_row(), "0xabc,1234567.5,2500000,173.46,At Risk\n");
+        assert_eq!(Trove::CSV_HEADER.matches(',').count(), trove.csv_row().matches(',').count());
+    }
+
+    #[test]
+    fn test_validate_combo_shortens_unsafe_lookbacks() {
+        let defaults = LookbackOverrides::new();
+        let combo = validate_combo(ChartResolution::M1, ChartLookback::All, &defaults);
+        assert_eq!(combo.lookback, ChartLookback::Hour1);
+        assert!(combo.reason.is_some());
+
+        let combo = validate_combo(ChartResolution::H1, ChartLookback::Month1, &defaults);
+        assert_eq!(combo.lookback, ChartLookback::Week1);
+        assert_eq!(combo.resolution, ChartResolution::H1);
+
+        // 100 three-minute candles cover 5 hours
+        let combo = validate_combo(ChartResolution::M3, ChartLookback::Day1, &defaults);
+        assert_eq!(combo.lookback, ChartLookback::Hour4);
+        assert_eq!(ChartResolution::from_url_param("3m"), Some(ChartResolution::M3));
+
+        let combo = validate_combo(ChartResolution::D1, ChartLookback::Month3, &defaults);
+        assert_eq!(combo.lookback, ChartLookback::Month3);
+        assert_eq!(combo.reason, None);
+
+        // Every adjusted pair is safe
+        for &res in ChartResolution::all() {
+            for &lb in ChartLookback::all() {
+                let combo = validate_combo(res, lb, &defaults);
+                assert!(res.is_lookback_safe(combo.lookback.minutes()), "{:?} {:?}", res, lb);
+            }
+        }
+    }
+
+    #[test]
+    fn test_intraday_lookbacks() {
+        for &lb in ChartLookback::all() {
+            assert_eq!(ChartLookback::from_url_param(lb.to_url_param()), Some(lb));
+        }
+        assert_eq!(ChartLookback::from_url_param("6h"), Some(ChartLookback::Hour6));
+        assert_eq!(ChartLookback::Hour6.minutes(), 360);
+
+        // 100 one-minute candles cover 1h, 100 five-minute candles cover 6h
+        let defaults = LookbackOverrides::new();
+        assert_eq!(validate_combo(ChartResolution::M1, ChartLookback::Hour1, &defaults).reason, None);
+        assert_eq!(validate_combo(ChartResolution::M5, ChartLookback::Hour6, &defaults).reason, None);
+        let combo = validate_combo(ChartResolution::M1, ChartLookback::Hour6, &defaults);
+        assert_eq!(combo.lookback, ChartLookback::Hour1);
+    }
+
+    #[test]
+    fn test_trove_sort_direction() {
+        let trove = |address: &str, debt: i64| Trove {
+            address: address.to_string(),
+            collateral: Decimal::ONE,
+            debt: Decimal::from(debt),
+            icr: Decimal::from(200),
+            status: TroveStatus::Active,
+            provenance: Provenance::default(),
+        };
+        let mut troves = vec![trove("0xb", 5), trove("0xc", 9), trove("0xa", 5)];
+
+        TroveRankBy::Debt.sort(&mut troves, false);
+        let order: Vec<&str> = troves.iter().map(|t| t.address.as_str()).collect();
+        assert_eq!(order, ["0xa", "0xb", "0xc"]);
+
+        TroveRankBy::Debt.sort(&mut troves, true);
+        let order: Vec<&str> = troves.iter().map(|t| t.address.as_str()).collect();
+        assert_eq!(order, ["0xc", "0xa", "0xb"]);
+    }
+
+    #[test]
+    fn test_select_troves_sorts_before_truncating() {
+        let trove = |address: &str, debt: i64, status: TroveStatus| Trove {
+            address: address.to_string(),
+            collateral: Decimal::ONE,
+            debt: Decimal::from(debt),
+            icr: Decimal::from(200),
+            status,
+            provenance: Provenance::default(),
+        };
+        // The largest debt and the only at-risk trove are past the first page of 2
+        let troves = vec![
+            trove("0xa", 1, TroveStatus::Active),
+            trove("0xb", 2, TroveStatus::Active),
+            trove("0xc", 3, TroveStatus::Active),
+            trove("0xd", 9, TroveStatus::AtRisk),
+        ];
+        let addresses = |troves: Vec<Trove>| troves.into_iter().map(|t| t.address).collect::<Vec<_>>();
+
+        let top = select_troves(troves.clone(), None, Some(TroveRankBy::Debt), true, 2);
+        assert_eq!(addresses(top), ["0xd", "0xc"]);
+
+        let at_risk = select_troves(troves.clone(), Some(TroveStatus::AtRisk), None, false, 2);
+        assert_eq!(addresses(at_risk), ["0xd"]);
+
+        assert_eq!(addresses(select_troves(troves, None, None, false, 2)), ["0xa", "0xb"]);
+    }
+
+    #[test]
+    fn test_holder_supply_shares() {
+        let mut holders = vec![
+            TokenHolderInfo::new("0xa".to_string(), Decimal::from(50)),
+            TokenHolderInfo::new("0xb".to_string(), Decimal::from(25)),
+        ];
+        TokenHolderInfo::set_supply_shares(&mut holders, Some(Decimal::from(200)));
+        assert_eq!((holders[0].supply_pct, holders[0].cumulative_pct), (Some(25.0), Some(25.0)));
+        assert_eq!((holders[1].supply_pct, holders[1].cumulative_pct), (Some(12.5), Some(37.5)));
+
+        // Unknown or zero supply gives no share rather than 0%
+        TokenHolderInfo::set_supply_shares(&mut holders, Some(Decimal::ZERO));
+        assert_eq!((holders[0].supply_pct, holders[1].cumulative_pct), (None, None));
+        TokenHolderInfo::set_supply_shares(&mut holders, None);
+        assert_eq!(holders[1].supply_pct, None);
+    }
+
+    #[test]
+    fn test_icr_histogram() {
+        let trove = |icr: i64, debt: i64| Trove {
+            address: format!("0x{}", icr),
+            collateral: Decimal::ZERO,
+            debt: Decimal::from(debt),
+            icr: Decimal::from(icr),
+            status: TroveStatus::Active,
+            provenance: Provenance::default(),
+        };
+        let troves = [trove(105, 10), trove(110, 20), trove(124, 30), trove(300, 40)];
+        let buckets = IcrBucket::histogram(&troves, &[110.0, 125.0, 150.0]);
+
+        let labels: Vec<String> = buckets.iter().map(IcrBucket::label).collect();
+        assert_eq!(labels, ["<110%", "110-125%", "125-150%", "150%+"]);
+        let counts: Vec<u32> = buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [1, 2, 0, 1]);
+        assert_eq!(buckets[1].total_debt, Decimal::from(50));
+        assert_eq!(IcrBucket::histogram(&troves, &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_pool_depositor_ranking() {
+        let deposits = vec![
+            ("0xb".to_string(), Decimal::from(25)),
+            ("0xc".to_string(), Decimal::ZERO),
+            ("0xa".to_string(), Decimal::from(75)),
+            ("0xd".to_string(), Decimal::from(25)),
+        ];
+        let ranked = PoolDepositor::rank(deposits.clone(), Some(Decimal::from(200)));
+        let order: Vec<&str> = ranked.iter().map(|d| d.address.as_str()).collect();
+        assert_eq!(order, ["0xa", "0xb", "0xd"]);
+        assert_eq!(ranked[0].share_pct, Some(37.5));
+
+        let ranked = PoolDepositor::rank(deposits, Some(Decimal::ZERO));
+        assert!(ranked.iter().all(|d| d.share_pct.is_none()));
+    }
+
+    #[test]
+    fn test_currency_bytes32_round_trip() {
+        let usdfc = "0x5553444643000000000000000000000000000000000000000000000000000000";
+        assert_eq!(Currency::from_bytes32(usdfc), Currency::Usdfc);
+        assert_eq!(Currency::Usdfc.to_bytes32(), usdfc);
+        assert_eq!(Currency::from_bytes32("0x46494c00"), Currency::Fil);
+        assert_eq!(Currency::from_bytes32("0x455448"), Currency::Other("ETH".to_string()));
+
+        // Serialized as the symbol, read back from either form
+        assert_eq!(String::from(Currency::Usdfc), "USDFC");
+        assert_eq!(Currency::from(usdfc.to_string()), Currency::Usdfc);
+        assert_eq!(Currency::from("FIL".to_string()), Currency::Fil);
+    }
+
+    #[test]
+    fn test_daily_volumes_validate_days() {
+        assert_eq!(DailyVolumesResponse::validate_days(None).ok(), Some(30));
+        assert_eq!(DailyVolumesResponse::validate_days(Some(0)).ok(), Some(1));
+        assert_eq!(DailyVolumesResponse::validate_days(Some(10000)).ok(), Some(365));
+        assert!(matches!(
+            DailyVolumesResponse::validate_days(Some(-5)),
+            Err(crate::error::ValidationError::OutOfRange { field: "days", .. })
+        ));
+    }
+
+    #[test]
+    fn test_chart_export_filename() {
+        let data = ChartDataResponse {
+            resolution: ChartResolution::M15,
+            lookback: ChartLookback::Day3,
+            generated_at: 1_704_067_200,
+            ..Default::default()
+        };
+        assert_eq!(data.export_filename(), "usdfc_chart_15m_3d_1704067200.json");
+    }
+
+    #[test]
+    fn test_validate_combo_honours_overrides() {
+        let overrides = LookbackOverrides::from([(ChartResolution::H1, 43200), (ChartResolution::D1, 1440)]);
+
+        let combo = validate_combo(ChartResolution::H1, ChartLookback::Month1, &overrides);
+        assert_eq!(combo.lookback, ChartLookback::Month1);
+        assert_eq!(combo.reason, None);
+
+        // Overrides can also lower a limit
+        let combo = validate_combo(ChartResolution::D1, ChartLookback::Week1, &overrides);
+        assert_eq!(combo.lookback, ChartLookback::Day1);
+        assert_eq!(ChartResolution::D1.lookback_description(&overrides), "~1 day");
+    }
+
+    fn candles(closes: &[f64]) -> Vec<TVCandle> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| TVCandle {
+                time: i as i64,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chart_url_state_round_trips_share_query() {
+        let metrics = HashSet::from([ChartMetric::Volume, ChartMetric::Price]);
+        let query = ChartUrlState::to_query_string(
+            &metrics,
+            ChartResolution::H4,
+            ChartLookback::Day3,
+            ChartType::Candle,
+            None,
+            None,
+        );
+        assert_eq!(query, "?metrics=price,volume&res=4h&type=candle&lookback=3d");
+
+        let state = ChartUrlState::from_query(&query);
+        assert_eq!(state.metrics, vec![ChartMetric::Price, ChartMetric::Volume]);
+        assert_eq!(state.resolution, Some(ChartResolution::H4));
+        assert_eq!(state.lookback, Some(ChartLookback::Day3));
+        assert_eq!(state.chart_type, Some(ChartType::Candle));
+
+        // Percent-encoded commas and unknown keys from other clients
+        let state = ChartUrlState::from_query("metrics=tcr%2Csupply&utm=x&start=10&end=20");
+        assert_eq!(state.metrics, vec![ChartMetric::TCR, ChartMetric::Supply]);
+        assert_eq!((state.start, state.end), (Some(10), Some(20)));
+    }
+
+    #[test]
+    fn test_sma_starts_once_window_is_full() {
+        let ma = MovingAverageSeries::compute(MovingAverageKind::Simple, 3, &candles(&[1.0, 2.0, 3.0, 4.0, 5.0]));
+        assert_eq!(ma.data, vec![(2, 2.0), (3, 3.0), (4, 4.0)]);
+    }
+
+    #[test]
+    fn test_ema_seeded_with_sma() {
+        let ma = MovingAverageSeries::compute(MovingAverageKind::Exponential, 3, &candles(&[1.0, 2.0, 3.0, 7.0]));
+        assert_eq!(ma.data[0], (2, 2.0));
+        // alpha = 0.5: 2.0 + 0.5 * (7.0 - 2.0)
+        assert_eq!(ma.data[1], (3, 4.5));
+        assert_eq!(ma.name(), "EMA 3");
+    }
+
+    #[test]
+    fn test_order_flow_skips_filled_orders() {
+        let order = |amount: f64, filled: f64, price: f64| OrderData {
+            id: String::new(),
+            side: "Lend".to_string(),
+            amount,
+            filled,
+            price,
+            apr: 0.0,
+            user: None,
+            created_at: String::new(),
+        };
+        let lend = OrderFlowSide::from_orders(&[order(100.0, 100.0, 90.0), order(30.0, 10.0, 95.0), order(60.0, 0.0, 98.0)]);
+        assert_eq!(lend.order_count, 2);
+        assert_eq!(lend.volume, 80.0);
+        assert_eq!(lend.weighted_avg_price, Some((95.0 * 20.0 + 98.0 * 60.0) / 80.0));
+
+        let borrow = OrderFlowSide::from_orders(&[order(20.0, 0.0, 99.0)]);
+        assert_eq!(OrderBookData::volume_imbalance(&lend, &borrow), Some(0.6));
+        assert_eq!(OrderBookData::volume_imbalance(&OrderFlowSide::default(), &OrderFlowSide::default()), None);
+    }
+
+    #[test]
+    fn test_trove_rank_breaks_ties_by_address() {
+        let trove = |address: &str, debt: i64, icr: i64| Trove {
+            address: address.to_string(),
+            collateral: Decimal::new(debt * 2, 0),
+            debt: Decimal::new(debt, 0),
+            icr: Decimal::new(icr, 0),
+            status: TroveStatus::Active,
+            provenance: Provenance::default(),
+        };
+        let troves = vec![trove("0xc", 100, 200), trove("0xa", 100, 150), trove("0xb", 300, 120)];
+
+        let by_debt: Vec<_> = TroveRankBy::Debt.rank(troves.clone(), 3).into_iter().map(|t| t.address).collect();
+        assert_eq!(by_debt, ["0xb", "0xa", "0xc"]);
+
+        let by_icr = TroveRankBy::Icr.rank(troves, 2);
+        assert_eq!(by_icr.len(), 2);
+        assert_eq!(by_icr[0].address, "0xb");
+    }
+
+    #[test]
+    fn test_window_longer_than_candles_is_empty() {
+        let ma = MovingAverageSeries::compute(MovingAverageKind::Simple, 50, &candles(&[1.0, 2.0]));
+        assert!(ma.data.is_empty());
+        assert!(MovingAverageSeries::compute(MovingAverageKind::Simple, 0, &candles(&[1.0])).data.is_empty());
+    }
+
+    #[test]
+    fn test_collateral_usd_requires_a_real_price() {
+        let fil = Decimal::new(1_000, 0);
+        assert_eq!(ProtocolMetrics::collateral_value_usd(fil, Some(Decimal::new(45, 1))), Some(Decimal::new(4_500, 0)));
+        assert_eq!(ProtocolMetrics::collateral_value_usd(fil, None), None);
+        assert_eq!(ProtocolMetrics::collateral_value_usd(fil, Some(Decimal::ZERO)), None);
+    }
+
+    #[test]
+    fn test_protocol_metrics_missing_fields() {
+        let metrics = ProtocolMetrics {
+            total_supply: Some(Decimal::ONE),
+            collateral_fil: Some(Decimal::ONE),
+            active_troves: Some(3),
+            stability_pool_balance: Some(Decimal::ONE),
+            circulating_supply: Some(Decimal::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(metrics.missing(), ["collateral_usd", "tcr"]);
+        assert!(!metrics.is_empty());
+        assert_eq!(ProtocolMetrics::default().missing().len(), 7);
+        assert!(ProtocolMetrics::default().is_empty());
+    }
+
+    #[test]
+    fn test_series_range_percent() {
+        let range = SeriesRange::of([2.0, f64::NAN, 6.0, 4.0]).unwrap();
+        assert_eq!(range, SeriesRange { min: 2.0, max: 6.0 });
+        assert_eq!(range.percent(2.0), 0.0);
+        assert_eq!(range.percent(4.0), 50.0);
+        assert_eq!(range.percent(6.0), 100.0);
+
+        assert_eq!(SeriesRange::of([3.0, 3.0]).unwrap().percent(3.0), 50.0);
+        assert!(SeriesRange::of(std::iter::empty()).is_none());
+    }
+    #[test]
+    fn test_price_weighted_by_pool_liquidity() {
+        let pool = |price: Option<f64>, volume: Option<f64>, liquidity: Option<f64>| PoolPriceData {
+            price_usd: price,
+            volume_24h: volume,
+            liquidity_usd: liquidity,
+            ..Default::default()
+        };
+        let data = USDFCPriceData::from_pools(
+            vec![
+                pool(Some(1.0), Some(100.0), Some(300_000.0)),
+                pool(Some(0.96), Some(50.0), Some(100_000.0)),
+                PoolPriceData { error: Some("timeout".to_string()), ..Default::default() },
+            ],
+            Provenance::default(),
+        );
+        assert!((data.price_usd.unwrap() - 0.99).abs() < 1e-9);
+        assert_eq!(data.volume_24h, Some(150.0));
+        assert_eq!(data.liquidity_usd, Some(400_000.0));
+        assert_eq!(data.pools.as_ref().map(Vec::len), Some(3));
+
+        // Without liquidity figures the price is a plain mean
+        let data = USDFCPriceData::from_pools(
+            vec![pool(Some(1.0), None, None), pool(Some(0.98), None, None)],
+            Provenance::default(),
+        );
+        assert!((data.price_usd.unwrap() - 0.99).abs() < 1e-9);
+        assert_eq!(data.volume_24h, None);
+    }
+
+    #[test]
+    fn test_depeg_severity() {
+        let status = |price| DepegStatus::evaluate(price, 1.0, 0.5, 2.0, 0).severity;
+        assert_eq!(status(Some(1.004)), DepegSeverity::Ok);
+        assert_eq!(status(Some(0.99)), DepegSeverity::Warning);
+        assert_eq!(status(Some(1.03)), DepegSeverity::Critical);
+        assert_eq!(status(None), DepegSeverity::Critical);
+
+        let depeg = DepegStatus::evaluate(Some(0.97), 1.0, 0.5, 2.0, 0);
+        assert!((depeg.deviation_pct.unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_wallet_running_balance() {
+        let bucket = |timestamp, volume_in, volume_out| WalletBucket {
+            timestamp,
+            volume_in,
+            volume_out,
+            count_in: 0,
+            count_out: 0,
+            net_flow: 0.0,
+            running_balance: 0.0,
+        };
+        let mut analytics = WalletAnalyticsResponse {
+            address: "0xabc".to_string(),
+            buckets: vec![bucket(0, 100.0, 0.0), bucket(3600, 20.0, 50.0), bucket(7200, 0.0, 10.0)],
+            total_in: 120.0,
+            total_out: 60.0,
+            first_seen: None,
+            last_active: None,
+            starting_balance: None,
+        };
+
+        analytics.accumulate_balances();
+        let series = |a: &WalletAnalyticsResponse| a.buckets.iter().map(|b| (b.net_flow, b.running_balance)).collect::<Vec<_>>();
+        assert_eq!(series(&analytics), vec![(100.0, 100.0), (-30.0, 70.0), (-10.0, 60.0)]);
+
+        analytics.starting_balance = Some(40.0);
+        analytics.accumulate_balances();
+        assert_eq!(series(&analytics), vec![(100.0, 140.0), (-30.0, 110.0), (-10.0, 100.0)]);
+    }
+
+    #[test]
+    fn test_wallet_comparison_aligns_buckets() {
+        let analytics = |buckets: Vec<(i64, f64)>| WalletAnalyticsResponse {
+            address: String::new(),
+            buckets: buckets
+                .into_iter()
+                .map(|(timestamp, volume_in)| WalletBucket {
+                    timestamp,
+                    volume_in,
+                    volume_out: 0.0,
+                    count_in: 1,
+                    count_out: 0,
+                    net_flow: 0.0,
+                    running_balance: 0.0,
+                })
+                .collect(),
+            total_in: 0.0,
+            total_out: 0.0,
+            first_seen: None,
+            last_active: None,
+            starting_balance: None,
+        };
+
+        let comparison = WalletComparison::new(
+            analytics(vec![(0, 10.0), (7200, 5.0)]),
+            analytics(vec![(3600, 1.0)]),
+            Vec::new(),
+        );
+        let timeline = |a: &WalletAnalyticsResponse| a.buckets.iter().map(|b| (b.timestamp, b.running_balance)).collect::<Vec<_>>();
+        assert_eq!(timeline(&comparison.a), vec![(0, 10.0), (3600, 10.0), (7200, 15.0)]);
+        assert_eq!(timeline(&comparison.b), vec![(0, 0.0), (3600, 1.0), (7200, 1.0)]);
+    }
 }
@@ -37,6 +37,29 @@ fn LoadingSpinner() -> impl IntoView {
     }
 }
 
+/// Configured `BRAND_NAME`
+///
+/// The client has no access to server config, so after hydration it keeps
+/// the title the server rendered.
+pub fn brand_name() -> String {
+    #[cfg(feature = "hydrate")]
+    {
+        document().title()
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        usdfc_core::config::config().brand_name.clone()
+    }
+}
+
+/// localStorage key for the persisted theme preference
+#[cfg(feature = "hydrate")]
+const THEME_STORAGE_KEY: &str = "usdfc_theme";
+
+/// localStorage key for the persisted density preference
+#[cfg(feature = "hydrate")]
+const DENSITY_STORAGE_KEY: &str = "usdfc_density";
+
 /// Theme mode for the application
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum ThemeMode {
@@ -45,6 +68,118 @@ pub enum ThemeMode {
     Light,
 }
 
+impl ThemeMode {
+    /// ECharts `init` theme argument as a JS literal
+    pub fn echarts_theme(&self) -> &'static str {
+        match self {
+            Self::Dark => "'dark'",
+            Self::Light => "null",
+        }
+    }
+}
+
+/// User theme preference - `System` follows `prefers-color-scheme`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ThemePreference {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl ThemePreference {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Dark => "dark",
+            Self::Light => "light",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "system" => Some(Self::System),
+            "dark" => Some(Self::Dark),
+            "light" => Some(Self::Light),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::System => "Auto",
+            Self::Dark => "Dark",
+            Self::Light => "Light",
+        }
+    }
+
+    /// Next preference for the header toggle (Auto -> Light -> Dark)
+    pub fn next(&self) -> Self {
+        match self {
+            Self::System => Self::Light,
+            Self::Light => Self::Dark,
+            Self::Dark => Self::System,
+        }
+    }
+
+    /// Resolve to a concrete theme given the OS color scheme
+    pub fn resolve(&self, system_prefers_light: bool) -> ThemeMode {
+        match self {
+            Self::Dark => ThemeMode::Dark,
+            Self::Light => ThemeMode::Light,
+            Self::System if system_prefers_light => ThemeMode::Light,
+            Self::System => ThemeMode::Dark,
+        }
+    }
+}
+
+/// Data density - `Compact` tightens tables, cards and charts for dense monitoring setups
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Density {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+impl Density {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Comfortable => "comfortable",
+            Self::Compact => "compact",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "comfortable" => Some(Self::Comfortable),
+            "compact" => Some(Self::Compact),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Comfortable => "Comfortable",
+            Self::Compact => "Compact",
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Comfortable => Self::Compact,
+            Self::Compact => Self::Comfortable,
+        }
+    }
+
+    /// Default `limit` for list fetches, given the comfortable-mode default
+    pub fn list_limit(&self, comfortable: u32) -> u32 {
+        match self {
+            Self::Comfortable => comfortable,
+            Self::Compact => (comfortable / 2).max(10),
+        }
+    }
+}
+
 /// Network connection status
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum NetworkStatus {
@@ -59,8 +194,14 @@ pub enum NetworkStatus {
 pub struct AppState {
     /// Whether the sidebar is expanded or collapsed
     pub sidebar_expanded: RwSignal<bool>,
-    /// Current theme mode (dark/light)
-    pub theme_mode: RwSignal<ThemeMode>,
+    /// Theme preference chosen by the user (persisted in localStorage)
+    pub theme_preference: RwSignal<ThemePreference>,
+    /// Whether the OS reports `prefers-color-scheme: light`
+    pub system_prefers_light: RwSignal<bool>,
+    /// Resolved theme mode (dark/light) - read this to style components
+    pub theme_mode: Memo<ThemeMode>,
+    /// Data density chosen by the user (persisted in localStorage)
+    pub density: RwSignal<Density>,
     /// Network connection status
     pub network_status: RwSignal<NetworkStatus>,
     /// Number of unread alerts
@@ -72,9 +213,14 @@ pub struct AppState {
 impl AppState {
     /// Create a new AppState with default values
     pub fn new() -> Self {
+        let theme_preference = create_rw_signal(ThemePreference::default());
+        let system_prefers_light = create_rw_signal(false);
         Self {
             sidebar_expanded: create_rw_signal(true),
-            theme_mode: create_rw_signal(ThemeMode::Dark),
+            theme_preference,
+            system_prefers_light,
+            theme_mode: create_memo(move |_| theme_preference.get().resolve(system_prefers_light.get())),
+            density: create_rw_signal(Density::default()),
             network_status: create_rw_signal(NetworkStatus::Connected),
             unread_alerts: create_rw_signal(3), // Default from sidebar badge
             mobile_menu_open: create_rw_signal(false),
@@ -89,31 +235,147 @@ pub fn App() -> impl IntoView {
 
     // Global app state with all reactive signals
     let app_state = AppState::new();
-    let sidebar_expanded = app_state.sidebar_expanded;
-    let theme_mode = app_state.theme_mode;
-    let mobile_menu_open = app_state.mobile_menu_open;
+    #[cfg(feature = "hydrate")]
+    {
+        let density = app_state.density;
+        let theme_preference = app_state.theme_preference;
+        let system_prefers_light = app_state.system_prefers_light;
+
+        // Restore the saved preference and follow OS color scheme changes.
+        // Runs after hydration so the server-rendered (dark) markup matches.
+        create_effect(move |_| {
+            use wasm_bindgen::{closure::Closure, JsCast};
+
+            let Some(window) = web_sys::window() else { return };
+            if let Some(pref) = window
+                .local_storage()
+                .ok()
+                .flatten()
+                .and_then(|s| s.get_item(THEME_STORAGE_KEY).ok().flatten())
+                .and_then(|v| ThemePreference::parse(&v))
+            {
+                theme_preference.set(pref);
+            }
+            if let Ok(Some(query)) = window.match_media("(prefers-color-scheme: light)") {
+                system_prefers_light.set(query.matches());
+                let on_change = Closure::<dyn FnMut(web_sys::MediaQueryListEvent)>::new(
+                    move |e: web_sys::MediaQueryListEvent| system_prefers_light.set(e.matches()),
+                );
+                query.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+                on_change.forget();
+            }
+        });
+
+        // Persist manual overrides
+        create_effect(move |prev: Option<ThemePreference>| {
+            let pref = theme_preference.get();
+            if prev.is_some() {
+                if let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) {
+                    let _ = storage.set_item(THEME_STORAGE_KEY, pref.as_str());
+                }
+            }
+            pref
+        });
+
+        // Restore the saved density after hydration
+        create_effect(move |_| {
+            if let Some(saved) = web_sys::window()
+                .and_then(|w| w.local_storage().ok().flatten())
+                .and_then(|s| s.get_item(DENSITY_STORAGE_KEY).ok().flatten())
+                .and_then(|v| Density::parse(&v))
+            {
+                density.set(saved);
+            }
+        });
+
+        // Persist changes and let charts re-measure their (CSS-sized) containers
+        create_effect(move |prev: Option<Density>| {
+            let current = density.get();
+            if prev.is_some_and(|p| p != current) {
+                if let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) {
+                    let _ = storage.set_item(DENSITY_STORAGE_KEY, current.as_str());
+                }
+                request_animation_frame(|| {
+                    if let (Some(window), Ok(event)) = (web_sys::window(), web_sys::Event::new("resize")) {
+                        let _ = window.dispatch_event(&event);
+                    }
+                });
+            }
+            current
+        });
+    }
     provide_context(app_state);
 
     // Global metrics context - shared across all pages
     let global_metrics = GlobalMetrics::new();
     provide_context(global_metrics);
 
+    // Nonce-based CSP (CSP_NONCE=true): the server render sends the whole
+    // policy as a response header with this response's nonce, which
+    // leptos_meta and the hydration scripts carry. A <meta> policy could not
+    // set frame-ancestors or cover content before it.
+    #[cfg(feature = "ssr")]
+    {
+        let cfg = usdfc_core::config::config();
+        let nonce = leptos::nonce::use_nonce().filter(|_| cfg.csp_nonce);
+        if let (Some(nonce), Some(response)) = (nonce, use_context::<leptos_axum::ResponseOptions>()) {
+            let path = use_context::<http::request::Parts>().map(|parts| parts.uri.path().to_string());
+            let frame_ancestors = cfg.frame_ancestors_for(path.as_deref().unwrap_or("/"));
+            let policy = cfg.content_security_policy_framed(Some(&nonce), &frame_ancestors);
+            if let Ok(value) = http::HeaderValue::from_str(&policy) {
+                response.insert_header(http::header::CONTENT_SECURITY_POLICY, value);
+            }
+        }
+    }
+
     view! {
         <Stylesheet href="/pkg/usdfc-terminal.css"/>
         <Meta name="description" content="Real-time analytics terminal for the USDFC stablecoin protocol"/>
         <Meta name="viewport" content="width=device-width, initial-scale=1.0"/>
-        <Title text="USDFC Analytics Terminal"/>
+        <Title text=brand_name()/>
+        <Link rel="icon" href="/favicon.ico"/>
         <Script src="https://cdn.jsdelivr.net/npm/echarts@5.5.0/dist/echarts.min.js"/>
 
         <Router>
-            <div
-                class="app-container"
-                class:sidebar-collapsed=move || !sidebar_expanded.get()
-                class:theme-light=move || theme_mode.get() == ThemeMode::Light
-            >
-                <style>{include_str!("styles.css")}</style>
+            <AppLayout />
+        </Router>
+    }
+}
+
+/// Page chrome around the routes; `/embed/*` widgets get a bare layout
+#[component]
+fn AppLayout() -> impl IntoView {
+    let app_state = use_context::<AppState>().expect("AppState must be provided");
+    let sidebar_expanded = app_state.sidebar_expanded;
+    let theme_mode = app_state.theme_mode;
+    let density = app_state.density;
+    let mobile_menu_open = app_state.mobile_menu_open;
+
+    let location = use_location();
+    let embed = move || location.pathname.with(|p| p.starts_with("/embed/"));
+    // Embeds take their theme from `?theme=` rather than the saved preference
+    let light = move || {
+        if embed() {
+            location.query.with(|q| q.get("theme").is_some_and(|t| t == "light"))
+        } else {
+            theme_mode.get() == ThemeMode::Light
+        }
+    };
+
+    view! {
+        <div
+            class="app-container"
+            class:sidebar-collapsed=move || !sidebar_expanded.get()
+            class:theme-light=light
+            class:embed-mode=embed
+            class:density-compact=move || density.get() == Density::Compact
+        >
+            <style>{include_str!("styles.css")}</style>
+            <Show when=move || !embed()>
                 <Header />
-                <div class="main-wrapper">
+            </Show>
+            <div class="main-wrapper">
+                <Show when=move || !embed()>
                     // Mobile overlay - closes menu when clicked
                     <div
                         class="mobile-overlay"
@@ -121,54 +383,57 @@ pub fn App() -> impl IntoView {
                         on:click=move |_| mobile_menu_open.set(false)
                     ></div>
                     <Sidebar />
-                    <main class="main-content">
-                        <ErrorBoundary fallback=|errors| {
-                            let error_msg = errors.get()
-                                .into_iter()
-                                .map(|(_, e)| e.to_string())
-                                .collect::<Vec<_>>()
-                                .join(", ");
-                            view! {
-                                <ErrorFallback error=error_msg />
-                            }
-                        }>
-                            <Routes>
-                                // Main routes
-                                <Route path="/" view=dashboard::Dashboard />
-                                <Route path="/dashboard" view=dashboard::Dashboard />
-                                <Route path="/protocol" view=protocol::Protocol />
-                                <Route path="/transactions" view=transactions::TransactionSearch />
-                                <Route path="/address" view=transactions::TransactionSearch />
-                                <Route path="/address/:addr" view=AddressDetail />
-                                <Route path="/lending" view=lending::LendingMarkets />
-                                <Route path="/entities" view=entities::EntityRegistry />
-                                <Route path="/analytics" view=analytics::Analytics />
-                                <Route path="/advanced" view=advanced::AdvancedAnalytics />
-                                <Route path="/infrastructure" view=infrastructure::Infrastructure />
-                                <Route path="/tools" view=tools::Tools />
-
-                                // Redirects for old routes
-                                <Route path="/supply" view=|| view! { <Redirect path="/protocol" /> } />
-                                <Route path="/collateral" view=|| view! { <Redirect path="/protocol" /> } />
-                                <Route path="/stability" view=|| view! { <Redirect path="/protocol" /> } />
-                                <Route path="/flow" view=|| view! { <Redirect path="/analytics" /> } />
-                                <Route path="/network" view=|| view! { <Redirect path="/analytics" /> } />
-                                <Route path="/sankey" view=|| view! { <Redirect path="/analytics" /> } />
-                                <Route path="/contracts" view=|| view! { <Redirect path="/infrastructure" /> } />
-                                <Route path="/architecture" view=|| view! { <Redirect path="/infrastructure" /> } />
-                                <Route path="/api" view=|| view! { <Redirect path="/tools" /> } />
-                                <Route path="/export" view=|| view! { <Redirect path="/tools" /> } />
-                                <Route path="/alerts" view=|| view! { <Redirect path="/tools" /> } />
-
-                                // 404 fallback route - must be last
-                                <Route path="/*any" view=NotFoundPage />
-                            </Routes>
-                        </ErrorBoundary>
-                    </main>
-                </div>
-                <Footer />
+                </Show>
+                <main class="main-content">
+                    <ErrorBoundary fallback=|errors| {
+                        let error_msg = errors.get()
+                            .into_iter()
+                            .map(|(_, e)| e.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        view! {
+                            <ErrorFallback error=error_msg />
+                        }
+                    }>
+                        <Routes>
+                            // Main routes
+                            <Route path="/" view=dashboard::Dashboard />
+                            <Route path="/dashboard" view=dashboard::Dashboard />
+                            <Route path="/protocol" view=protocol::Protocol />
+                            <Route path="/transactions" view=transactions::TransactionSearch />
+                            <Route path="/address" view=transactions::TransactionSearch />
+                            <Route path="/address/:addr" view=AddressDetail />
+                            <Route path="/lending" view=lending::LendingMarkets />
+                            <Route path="/entities" view=entities::EntityRegistry />
+                            <Route path="/analytics" view=analytics::Analytics />
+                            <Route path="/advanced" view=advanced::AdvancedAnalytics />
+                            <Route path="/infrastructure" view=infrastructure::Infrastructure />
+                            <Route path="/tools" view=tools::Tools />
+                            <Route path="/embed/:metric" view=embed::EmbedWidget />
+
+                            // Redirects for old routes
+                            <Route path="/supply" view=|| view! { <Redirect path="/protocol" /> } />
+                            <Route path="/collateral" view=|| view! { <Redirect path="/protocol" /> } />
+                            <Route path="/stability" view=|| view! { <Redirect path="/protocol" /> } />
+                            <Route path="/flow" view=|| view! { <Redirect path="/analytics" /> } />
+                            <Route path="/network" view=|| view! { <Redirect path="/analytics" /> } />
+                            <Route path="/sankey" view=|| view! { <Redirect path="/analytics" /> } />
+                            <Route path="/contracts" view=|| view! { <Redirect path="/infrastructure" /> } />
+                            <Route path="/architecture" view=|| view! { <Redirect path="/infrastructure" /> } />
+                            <Route path="/api" view=|| view! { <Redirect path="/tools" /> } />
+                            <Route path="/export" view=|| view! { <Redirect path="/tools" /> } />
+                            <Route path="/alerts" view=|| view! { <Redirect path="/tools" /> } />
+
+                            // 404 fallback route - must be last
+                            <Route path="/*any" view=NotFoundPage />
+                        </Routes>
+                    </ErrorBoundary>
+                </main>
             </div>
-        </Router>
+            <Show when=move || !embed()>
+                <Footer />
+            </Show>
+        </div>
     }
 }
 
@@ -4,6 +4,7 @@ use leptos::*;
 use usdfc_core::types::{ChartResolution, ChartLookback, ChartMetric, ChartType, ChartDataResponse};
 use usdfc_api::{get_advanced_chart_data, get_wallet_analytics, WalletAnalyticsResponse};
 use super::{ChartHeader, ChartCanvas, ChartLegend};
+use crate::components::EmptyState;
 
 /// Main Advanced Chart container component
 #[component]
@@ -53,7 +54,7 @@ pub fn AdvancedChart() -> impl IntoView {
     let chart_resource = create_local_resource(
         move || (resolution.get(), lookback.get()),
         move |(res, lb)| async move {
-            get_advanced_chart_data(res, lb, None, None).await
+            get_advanced_chart_data(res, lb, None, None, None).await
         }
     );
 
@@ -182,6 +183,15 @@ pub fn AdvancedChart() -> impl IntoView {
                     >
                         {move || {
                             match wallet_analytics.get() {
+                                // Unseen wallets come back with no buckets rather than an error
+                                Some(Ok(Some(data))) if data.buckets.is_empty() => view! {
+                                    <div class="wallet-analytics-body">
+                                        <EmptyState
+                                            title="No activity"
+                                            description="This wallet has no USDFC transfers in the selected window."
+                                        />
+                                    </div>
+                                }.into_view(),
                                 Some(Ok(Some(data))) => {
                                     let WalletAnalyticsResponse {
                                         address: _,
@@ -190,6 +200,7 @@ pub fn AdvancedChart() -> impl IntoView {
                                         total_out,
                                         first_seen,
                                         last_active,
+                                        starting_balance,
                                     } = data;
 
                                     let bucket_count = buckets.len();
@@ -230,6 +241,8 @@ pub fn AdvancedChart() -> impl IntoView {
                                                     <span class="wallet-summary-value">{bucket_count.to_string()}</span>
                                                 </div>
                                                 {last_bucket.map(|b| {
+                                                    // Without a starting balance the running balance is only the window's net flow
+                                                    let balance_label = if starting_balance.is_some() { "Balance (approx.)" } else { "Net flow in window" };
                                                     view! {
                                                         <div class="wallet-summary-line">
                                                             <span class="wallet-summary-label">"Latest bucket in/out"</span>
@@ -237,6 +250,12 @@ pub fn AdvancedChart() -> impl IntoView {
                                                                 {format!("{:.2} / {:.2} USDFC", b.volume_in, b.volume_out)}
                                                             </span>
                                                         </div>
+                                                        <div class="wallet-summary-line">
+                                                            <span class="wallet-summary-label">{balance_label}</span>
+                                                            <span class="wallet-summary-value">
+                                                                {format!("{:.2} USDFC", b.running_balance)}
+                                                            </span>
+                                                        </div>
                                                     }
                                                 })}
                                             </div>
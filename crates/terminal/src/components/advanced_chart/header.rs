@@ -107,6 +107,7 @@ pub fn ChartHeader(
                         each={move || [
                             ChartLookback::Hour1,
                             ChartLookback::Hour4,
+                            ChartLookback::Hour6,
                             ChartLookback::Day1,
                             ChartLookback::Week1,
                             ChartLookback::Month1,
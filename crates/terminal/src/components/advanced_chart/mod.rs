@@ -9,6 +9,7 @@ mod canvas;
 mod series;
 mod tooltip;
 mod legend;
+mod option;
 
 pub use container::AdvancedChart;
 pub use header::ChartHeader;
@@ -16,3 +17,4 @@ pub use canvas::ChartCanvas;
 pub use series::{AreaSeries, LineSeries, CandlestickSeries, BarSeries};
 pub use tooltip::ChartTooltip;
 pub use legend::ChartLegend;
+pub use option::{axis_index, build_chart_option};
@@ -0,0 +1,393 @@
+//! ECharts Option Builder - Series and axis layout for the advanced analytics chart
+//!
+//! The option is plain JSON so it can be tested without a browser. Label and
+//! tooltip formatters are JS functions, which JSON cannot carry; the page
+//! attaches them by axis name before calling `setOption`.
+
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use usdfc_core::types::{ChartDataResponse, ChartMetric, ChartType, MovingAverageKind, MovingAverageSeries, SeriesRange};
+
+/// Value axes in `yAxisIndex` order: name, colour, and the metrics drawn on it
+const VALUE_AXES: [(&str, &str, &[ChartMetric]); 8] = [
+    ("Price", "#00d4ff", &[ChartMetric::Price]),
+    ("Volume", "#8b5cf6", &[ChartMetric::Volume]),
+    ("Liquidity", "#06b6d4", &[ChartMetric::Liquidity]),
+    ("TCR", "#22c55e", &[ChartMetric::TCR]),
+    ("Supply", "#f59e0b", &[ChartMetric::Supply]),
+    ("Holders", "#ec4899", &[ChartMetric::Holders]),
+    ("APR", "#10b981", &[ChartMetric::LendAPR, ChartMetric::BorrowAPR]),
+    ("Transfers", "#6366f1", &[ChartMetric::Transfers]),
+];
+
+/// Snapshot metrics drawn as lines, with their line colour
+const LINE_METRICS: [(ChartMetric, &str); 6] = [
+    (ChartMetric::Liquidity, "#06b6d4"),
+    (ChartMetric::TCR, "#22c55e"),
+    (ChartMetric::Supply, "#f59e0b"),
+    (ChartMetric::Holders, "#ec4899"),
+    (ChartMetric::LendAPR, "#10b981"),
+    (ChartMetric::BorrowAPR, "#f97316"),
+];
+
+/// Moving-average overlay colours, one per SMA/EMA pair
+const MA_COLORS: [&str; 4] = ["#facc15", "#a78bfa", "#fb7185", "#34d399"];
+
+const GRID_LINE: &str = "rgba(255,255,255,0.05)";
+
+/// `yAxisIndex` a metric is drawn on outside normalized mode
+pub fn axis_index(metric: ChartMetric) -> usize {
+    VALUE_AXES
+        .iter()
+        .position(|(_, _, metrics)| metrics.contains(&metric))
+        .unwrap_or(0)
+}
+
+/// Build the ECharts option for the visible metrics
+///
+/// In normalized mode every series shares one percent-of-range axis and each
+/// point is `[ms, percent, raw value]` so the tooltip can still show the real
+/// value; candles can't be rescaled, so price is drawn as a line there.
+pub fn build_chart_option(
+    data: &ChartDataResponse,
+    metrics: &HashSet<ChartMetric>,
+    chart_type: ChartType,
+    normalized: bool,
+) -> Value {
+    let show = |metric: ChartMetric| metrics.contains(&metric);
+    let range_for = |points: &[(i64, f64)]| {
+        normalized.then(|| SeriesRange::of(points.iter().map(|&(_, v)| v))).flatten()
+    };
+    let candlestick = chart_type == ChartType::Candle && !normalized;
+    let show_area = matches!(chart_type, ChartType::Area | ChartType::Bar);
+    let price_range = range_for(&data.metric_series(ChartMetric::Price));
+
+    let mut series = Vec::new();
+
+    if show(ChartMetric::Price) {
+        let points: Vec<Value> = if candlestick {
+            // [timestamp, open, close, low, high]
+            data.price_candles
+                .iter()
+                .map(|c| json!([c.time * 1000, c.open, c.close, c.low, c.high]))
+                .collect()
+        } else {
+            data.price_candles
+                .iter()
+                .map(|c| point(c.time, c.close, price_range))
+                .collect()
+        };
+        let mut price = json!({
+            "name": "Price",
+            "type": if candlestick { "candlestick" } else { "line" },
+            "data": points,
+            "yAxisIndex": 0,
+        });
+        if candlestick {
+            price["itemStyle"] = json!({
+                "color": "#22c55e",
+                "color0": "#ef4444",
+                "borderColor": "#22c55e",
+                "borderColor0": "#ef4444",
+            });
+        } else {
+            price["smooth"] = json!(true);
+            price["showSymbol"] = json!(false);
+            price["lineStyle"] = json!({ "width": 2, "color": "#00d4ff" });
+            price["itemStyle"] = json!({ "color": "#00d4ff" });
+        }
+        if show_area {
+            price["areaStyle"] = json!({
+                "color": {
+                    "type": "linear",
+                    "x": 0, "y": 0, "x2": 0, "y2": 1,
+                    "colorStops": [
+                        { "offset": 0, "color": "rgba(0, 212, 255, 0.4)" },
+                        { "offset": 1, "color": "rgba(0, 212, 255, 0.02)" },
+                    ],
+                },
+            });
+        }
+        series.push(price);
+
+        // Scaled with price so the overlays stay on the price line
+        for (i, ma) in moving_averages(data).enumerate() {
+            let color = MA_COLORS[(i / 2) % MA_COLORS.len()];
+            let dashed = ma.kind == MovingAverageKind::Exponential;
+            series.push(json!({
+                "name": ma.name(),
+                "type": "line",
+                "data": ma.data.iter().map(|&(ts, v)| point(ts, v, price_range)).collect::<Vec<_>>(),
+                "smooth": true,
+                "showSymbol": false,
+                "lineStyle": { "width": 1, "color": color, "type": if dashed { "dashed" } else { "solid" } },
+                "itemStyle": { "color": color },
+                "yAxisIndex": 0,
+            }));
+        }
+    }
+
+    if show(ChartMetric::Volume) {
+        let volume_range = normalized
+            .then(|| SeriesRange::of(data.price_candles.iter().map(|c| c.volume)))
+            .flatten();
+        let points: Vec<Value> = data.price_candles
+            .iter()
+            .map(|c| {
+                let color = if c.close >= c.open { "rgba(139, 92, 246, 0.7)" } else { "rgba(139, 92, 246, 0.4)" };
+                json!({ "value": point(c.time, c.volume, volume_range), "itemStyle": { "color": color } })
+            })
+            .collect();
+        series.push(json!({
+            "name": "Volume",
+            "type": "bar",
+            "yAxisIndex": axis_index(ChartMetric::Volume),
+            "data": points,
+            "barMaxWidth": 20,
+            "itemStyle": { "color": "rgba(139, 92, 246, 0.6)" },
+            "animation": true,
+            "animationDuration": 300,
+        }));
+    }
+
+    let metric_points = |metric: ChartMetric| -> Vec<Value> {
+        let points = data.metric_series(metric);
+        let range = range_for(&points);
+        points.iter().map(|&(ts, v)| point(ts, v, range)).collect()
+    };
+
+    // Historical metrics from snapshots
+    for (metric, color) in LINE_METRICS {
+        if !show(metric) {
+            continue;
+        }
+        let points = metric_points(metric);
+        series.push(json!({
+            "name": metric.label(),
+            "type": "line",
+            "smooth": true,
+            "showSymbol": points.len() <= 1,
+            "symbolSize": 8,
+            "lineStyle": { "width": 2, "color": color },
+            "itemStyle": { "color": color },
+            "data": points,
+            "yAxisIndex": axis_index(metric),
+            "animation": true,
+            "animationDuration": 300,
+        }));
+    }
+
+    if show(ChartMetric::Transfers) {
+        series.push(json!({
+            "name": "Transfers",
+            "type": "bar",
+            "barMaxWidth": 15,
+            "itemStyle": { "color": "rgba(99, 102, 241, 0.7)" },
+            "data": metric_points(ChartMetric::Transfers),
+            "yAxisIndex": axis_index(ChartMetric::Transfers),
+            "animation": true,
+            "animationDuration": 300,
+        }));
+    }
+
+    // Normalized mode: all series share the percent-of-range axis
+    if normalized {
+        for s in &mut series {
+            s["yAxisIndex"] = json!(0);
+        }
+    }
+
+    json!({
+        "backgroundColor": "transparent",
+        "animation": true,
+        "grid": { "left": "3%", "right": "8%", "top": 60, "bottom": 80, "containLabel": true },
+        "tooltip": {
+            "trigger": "axis",
+            "axisPointer": {
+                "type": "cross",
+                "crossStyle": { "color": "#00d4ff" },
+                "lineStyle": { "type": "dashed", "color": "#00d4ff" },
+            },
+            "backgroundColor": "rgba(20, 20, 30, 0.95)",
+            "borderColor": "#00d4ff",
+            "borderWidth": 1,
+            "textStyle": { "color": "#fff" },
+        },
+        "legend": legend(data, show(ChartMetric::Price)),
+        "toolbox": {
+            "show": true,
+            "right": 10,
+            "top": 10,
+            "feature": {
+                "saveAsImage": {
+                    "show": true,
+                    "title": "Export PNG",
+                    "pixelRatio": 2,
+                    "backgroundColor": "#0a0a0a",
+                },
+            },
+            "iconStyle": { "borderColor": "#00d4ff" },
+        },
+        "xAxis": {
+            "type": "time",
+            "boundaryGap": false,
+            "axisLine": { "lineStyle": { "color": "#333" } },
+            "axisLabel": { "color": "#888", "rotate": 0, "hideOverlap": true },
+            "splitLine": { "show": true, "lineStyle": { "color": GRID_LINE } },
+        },
+        "yAxis": y_axes(metrics, normalized),
+        "dataZoom": [
+            { "type": "inside", "xAxisIndex": 0, "start": 0, "end": 100 },
+            {
+                "type": "slider",
+                "xAxisIndex": 0,
+                "start": 0,
+                "end": 100,
+                "bottom": 10,
+                "height": 30,
+                "borderColor": "#333",
+                "backgroundColor": "rgba(0,0,0,0.3)",
+                "fillerColor": "rgba(0, 212, 255, 0.2)",
+                "handleStyle": { "color": "#00d4ff" },
+                "textStyle": { "color": "#888" },
+            },
+        ],
+        "series": series,
+    })
+}
+
+/// `[ms, value]`, or `[ms, percent of range, value]` when normalizing
+fn point(ts: i64, value: f64, range: Option<SeriesRange>) -> Value {
+    match range {
+        Some(r) => json!([ts * 1000, r.percent(value), value]),
+        None => json!([ts * 1000, value]),
+    }
+}
+
+fn moving_averages(data: &ChartDataResponse) -> impl Iterator<Item = &MovingAverageSeries> {
+    data.moving_averages.iter().filter(|ma| !ma.data.is_empty())
+}
+
+/// Legend for the moving-average overlays; EMAs start hidden
+fn legend(data: &ChartDataResponse, show_price: bool) -> Value {
+    let names: Vec<String> = moving_averages(data).map(|ma| ma.name()).collect();
+    let selected: Map<String, Value> = moving_averages(data)
+        .map(|ma| (ma.name(), json!(ma.kind != MovingAverageKind::Exponential)))
+        .collect();
+    json!({
+        "show": show_price && !names.is_empty(),
+        "data": names,
+        "selected": selected,
+        "top": 10,
+        "left": "center",
+        "itemWidth": 14,
+        "itemHeight": 2,
+        "textStyle": { "color": "#888", "fontSize": 11 },
+    })
+}
+
+/// Value axes; metric axes are only shown while one of their metrics is visible
+fn y_axes(metrics: &HashSet<ChartMetric>, normalized: bool) -> Value {
+    if normalized {
+        return json!([{
+            "type": "value",
+            "name": "% of range",
+            "position": "left",
+            "min": 0,
+            "max": 100,
+            "axisLine": { "lineStyle": { "color": "#888" } },
+            "axisLabel": { "color": "#888" },
+            "splitLine": { "lineStyle": { "color": GRID_LINE } },
+        }]);
+    }
+
+    let axes: Vec<Value> = VALUE_AXES
+        .iter()
+        .enumerate()
+        .map(|(i, (name, color, axis_metrics))| match i {
+            0 => json!({
+                "type": "value",
+                "name": name,
+                "position": "left",
+                "scale": true,
+                "axisLine": { "lineStyle": { "color": color } },
+                "axisLabel": { "color": color },
+                "splitLine": { "lineStyle": { "color": GRID_LINE } },
+            }),
+            // Volume bars sit under the price without their own labels
+            1 => json!({
+                "type": "value",
+                "name": name,
+                "position": "right",
+                "scale": true,
+                "axisLine": { "lineStyle": { "color": color }, "show": false },
+                "axisLabel": { "show": false },
+                "splitLine": { "show": false },
+            }),
+            // Stacked outward on the right, 60px apart
+            _ => json!({
+                "type": "value",
+                "name": name,
+                "position": "right",
+                "offset": (i - 2) * 60,
+                "scale": true,
+                "show": axis_metrics.iter().any(|m| metrics.contains(m)),
+                "axisLine": { "lineStyle": { "color": color } },
+                "axisLabel": { "color": color },
+                "splitLine": { "show": false },
+            }),
+        })
+        .collect();
+    Value::Array(axes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use usdfc_core::types::TVCandle;
+
+    fn sample_data() -> ChartDataResponse {
+        let candle = |time: i64, close: f64| TVCandle { time, open: 1.0, high: 1.01, low: 0.99, close, volume: 500.0 };
+        ChartDataResponse {
+            price_candles: vec![candle(100, 1.0), candle(160, 0.98)],
+            tcr_data: vec![(100, 180.0), (160, 200.0)],
+            ..Default::default()
+        }
+    }
+
+    fn series_named<'a>(option: &'a Value, name: &str) -> Option<&'a Value> {
+        option["series"].as_array().unwrap().iter().find(|s| s["name"] == name)
+    }
+
+    #[test]
+    fn test_tcr_series_uses_its_own_axis() {
+        let data = sample_data();
+        let price_only = HashSet::from([ChartMetric::Price]);
+        let option = build_chart_option(&data, &price_only, ChartType::Candle, false);
+        assert!(series_named(&option, "TCR").is_none());
+        assert_eq!(option["yAxis"][3]["show"], false);
+        assert_eq!(series_named(&option, "Price").unwrap()["type"], "candlestick");
+
+        let with_tcr = HashSet::from([ChartMetric::Price, ChartMetric::TCR]);
+        let option = build_chart_option(&data, &with_tcr, ChartType::Candle, false);
+        let tcr = series_named(&option, "TCR").unwrap();
+        assert_eq!(tcr["yAxisIndex"], 3);
+        assert_eq!(tcr["data"], json!([[100_000, 180.0], [160_000, 200.0]]));
+        assert_eq!(option["yAxis"][3]["name"], "TCR");
+        assert_eq!(option["yAxis"][3]["show"], true);
+    }
+
+    #[test]
+    fn test_normalized_series_share_percent_axis() {
+        let data = sample_data();
+        let metrics = HashSet::from([ChartMetric::Price, ChartMetric::TCR]);
+        let option = build_chart_option(&data, &metrics, ChartType::Candle, true);
+
+        assert_eq!(option["yAxis"].as_array().unwrap().len(), 1);
+        // Candles can't be rescaled, so price becomes a line
+        assert_eq!(series_named(&option, "Price").unwrap()["type"], "line");
+        let tcr = series_named(&option, "TCR").unwrap();
+        assert_eq!(tcr["yAxisIndex"], 0);
+        assert_eq!(tcr["data"][1], json!([160_000, 100.0, 200.0]));
+    }
+}
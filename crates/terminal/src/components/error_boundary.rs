@@ -4,6 +4,7 @@
 //! instead of crashing the application.
 
 use leptos::*;
+use usdfc_core::error::{split_request_id, TerminalError};
 
 /// A reusable error fallback component
 #[component]
@@ -58,6 +59,31 @@ pub fn InlineError(
     }
 }
 
+/// User-facing text for a failed server function call
+///
+/// Typed `TerminalError`s get wording per variant, followed by the request
+/// id to quote when reporting it; anything else falls back to the raw error
+/// text, which already carries the id.
+pub fn error_message(error: &ServerFnError) -> String {
+    let request_id = match error {
+        ServerFnError::ServerError(message) => split_request_id(message).1,
+        _ => None,
+    };
+    let message = match TerminalError::from_server_fn_error(error) {
+        Some(TerminalError::UpstreamTimeout) => "Data source timed out, try again in a moment".to_string(),
+        Some(TerminalError::UpstreamUnavailable { source }) => format!("Data source unavailable: {}", source),
+        Some(TerminalError::NotFound) => "Not found".to_string(),
+        Some(TerminalError::Validation(message)) => message,
+        Some(TerminalError::Unsupported(message)) => format!("Not available: {}", message),
+        Some(TerminalError::Internal(message)) => format!("Server error: {}", message),
+        None => return error.to_string(),
+    };
+    match request_id {
+        Some(id) => format!("{} (ref {})", message, id),
+        None => message,
+    }
+}
+
 /// Data loading error with retry capability
 #[component]
 pub fn DataLoadError<F>(
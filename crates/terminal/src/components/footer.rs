@@ -26,9 +26,18 @@ pub fn Footer() -> impl IntoView {
                         health.get().map(|res| {
                             match res {
                                 Ok(h) => {
-                                    let rpc_dot = if h.rpc_ok { "status-dot healthy" } else { "status-dot disconnected" };
-                                    let blockscout_dot = if h.blockscout_ok { "status-dot healthy" } else { "status-dot disconnected" };
-                                    let subgraph_dot = if h.subgraph_ok { "status-dot healthy" } else { "status-dot disconnected" };
+                                    let dot = |ok: bool, source: &str| {
+                                        if h.is_disabled(source) {
+                                            "status-dot unknown"
+                                        } else if ok {
+                                            "status-dot healthy"
+                                        } else {
+                                            "status-dot disconnected"
+                                        }
+                                    };
+                                    let rpc_dot = dot(h.rpc_ok, "rpc");
+                                    let blockscout_dot = dot(h.blockscout_ok, "blockscout");
+                                    let subgraph_dot = dot(h.subgraph_ok, "subgraph");
 
                                     view! {
                                         <span class="status-item">
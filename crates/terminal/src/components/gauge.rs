@@ -7,12 +7,17 @@ pub fn GaugeChart(
     #[prop(default = 100.0)] max: f64,
     #[prop(default = "TCR")] label: &'static str,
     #[prop(default = "%")] suffix: &'static str,
+    /// Labels for the left, top and right of the scale
+    #[prop(default = ["110%", "150%", "200%+"])] scale: [&'static str; 3],
+    /// Fixed needle color instead of the TCR risk thresholds
+    #[prop(optional)] color: Option<&'static str>,
 ) -> impl IntoView {
     let normalized = ((value - min) / (max - min)).clamp(0.0, 1.0);
     let angle = -90.0 + normalized * 180.0;
     
     let get_color = move || {
-        if value < 120.0 { "var(--accent-red)" }
+        if let Some(color) = color { color }
+        else if value < 120.0 { "var(--accent-red)" }
         else if value < 150.0 { "var(--accent-yellow)" }
         else { "var(--accent-cyan)" }
     };
@@ -67,9 +72,9 @@ pub fn GaugeChart(
                     <circle cx="100" cy="100" r="8" fill={get_color()} />
                 </g>
                 // Scale labels
-                <text x="20" y="115" fill="var(--text-muted)" font-size="10" text-anchor="middle">"110%"</text>
-                <text x="100" y="12" fill="var(--text-muted)" font-size="10" text-anchor="middle">"150%"</text>
-                <text x="180" y="115" fill="var(--text-muted)" font-size="10" text-anchor="middle">"200%+"</text>
+                <text x="20" y="115" fill="var(--text-muted)" font-size="10" text-anchor="middle">{scale[0]}</text>
+                <text x="100" y="12" fill="var(--text-muted)" font-size="10" text-anchor="middle">{scale[1]}</text>
+                <text x="180" y="115" fill="var(--text-muted)" font-size="10" text-anchor="middle">{scale[2]}</text>
             </svg>
             <div class="gauge-value" style=format!("color: {}", get_color())>
                 {format!("{:.1}{}", value, suffix)}
@@ -3,7 +3,7 @@
 //! Fixed for hydration - uses Suspense with stable fallback.
 
 use leptos::*;
-use crate::app::{AppState, NetworkStatus};
+use crate::app::{AppState, Density, NetworkStatus};
 use usdfc_api::{get_usdfc_price_data, get_holder_count, get_protocol_metrics};
 use rust_decimal::prelude::ToPrimitive;
 
@@ -13,6 +13,8 @@ pub fn Header() -> impl IntoView {
     let sidebar_expanded = app_state.sidebar_expanded;
     let network_status = app_state.network_status;
     let mobile_menu_open = app_state.mobile_menu_open;
+    let theme_preference = app_state.theme_preference;
+    let density = app_state.density;
 
     // Use regular resources for SSR compatibility
     let price_data = create_resource(
@@ -203,7 +205,7 @@ pub fn Header() -> impl IntoView {
                         protocol_metrics.get().map(|res| {
                             match res {
                                 Ok(metrics) => {
-                                    let tcr_display = metrics.tcr.to_f64()
+                                    let tcr_display = metrics.tcr.and_then(|tcr| tcr.to_f64())
                                         .map(|v| format!("{:.1}%", v))
                                         .unwrap_or_else(|| "--".to_string());
                                     view! {
@@ -226,6 +228,21 @@ pub fn Header() -> impl IntoView {
             </div>
 
             <div class="header-right">
+                <button
+                    class="theme-toggle"
+                    on:click=move |_| theme_preference.update(|p| *p = p.next())
+                    title="Theme: Auto follows your system setting"
+                >
+                    {move || theme_preference.get().label()}
+                </button>
+                <button
+                    class="theme-toggle"
+                    class:active=move || density.get() == Density::Compact
+                    on:click=move |_| density.update(|d| *d = d.toggled())
+                    title="Density: Compact fits more rows on screen"
+                >
+                    {move || density.get().label()}
+                </button>
                 <span
                     class="status-dot"
                     class:connected=move || network_status.get() == NetworkStatus::Connected
@@ -4,6 +4,10 @@
 //! Includes skeleton loaders for visual loading placeholders.
 
 use leptos::*;
+use usdfc_core::config::config;
+use usdfc_core::format::{format_duration_ago, format_timestamp_full};
+
+use crate::components::metric_card::current_timestamp;
 
 /// Generic skeleton loader component
 /// Use this for creating custom skeleton placeholders
@@ -200,13 +204,67 @@ pub fn EmptyState(
     }
 }
 
+/// Empty state spanning a whole table row, for tables whose query succeeded
+/// but returned nothing
+#[component]
+pub fn EmptyTableRow(
+    colspan: u32,
+    #[prop(into)]
+    title: String,
+    #[prop(into, optional)]
+    description: Option<String>,
+) -> impl IntoView {
+    view! {
+        <tr>
+            <td colspan=colspan.to_string()>
+                {match description {
+                    Some(description) => view! { <EmptyState title=title description=description /> },
+                    None => view! { <EmptyState title=title /> },
+                }}
+            </td>
+        </tr>
+    }
+}
+
 /// Pulse animation indicator for live data
+///
+/// Given `updated_at` (unix seconds of the last successful refresh) it shows
+/// the data's age and turns stale once that exceeds `stale_after_secs`,
+/// which defaults to twice the configured refresh interval.
 #[component]
-pub fn LiveIndicator() -> impl IntoView {
+pub fn LiveIndicator(
+    #[prop(into, optional)]
+    updated_at: Option<Signal<Option<i64>>>,
+    #[prop(optional)]
+    stale_after_secs: Option<u64>,
+) -> impl IntoView {
+    let stale_after = stale_after_secs.unwrap_or_else(|| (config().refresh_interval_ms / 1000).max(1) * 2) as i64;
+    let updated_at = move || updated_at.and_then(|s| s.get());
+
+    // Re-render the age every second (client-side only)
+    let now = create_rw_signal(current_timestamp());
+    #[cfg(any(feature = "hydrate", feature = "csr"))]
+    {
+        let interval = gloo_timers::callback::Interval::new(1000, move || now.set(current_timestamp()));
+        on_cleanup(move || drop(interval));
+    }
+
+    let is_stale = move || updated_at().is_some_and(|t| now.get() - t > stale_after);
+
     view! {
-        <span class="live-indicator" title="Live data">
+        <span
+            class=move || if is_stale() { "live-indicator stale" } else { "live-indicator" }
+            title=move || match updated_at() {
+                Some(t) if is_stale() => format!("Refresh failing - last updated {}", format_timestamp_full(t as u64)),
+                Some(t) => format!("Last updated {}", format_timestamp_full(t as u64)),
+                None => "Live data".to_string(),
+            }
+        >
             <span class="live-dot"></span>
-            "LIVE"
+            {move || if is_stale() { "STALE" } else { "LIVE" }}
+            {move || updated_at().map(|t| view! {
+                <span class="live-age">{format_duration_ago(t, now.get())}</span>
+            })}
         </span>
     }
 }
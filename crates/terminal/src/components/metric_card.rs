@@ -1,5 +1,8 @@
 use leptos::*;
 use crate::components::icons::*;
+use usdfc_core::config::config;
+use usdfc_core::format::format_relative_time;
+use usdfc_core::types::DataStaleness;
 
 #[component]
 pub fn MetricCard(
@@ -11,6 +14,8 @@ pub fn MetricCard(
     #[prop(optional)] sparkline: Option<Vec<f64>>,
     #[prop(default = false)] is_loading: bool,
     #[prop(optional)] tooltip: Option<&'static str>,
+    /// Unix seconds when the value was fetched upstream (`Provenance::fetched_at`)
+    #[prop(optional)] fetched_at: Option<i64>,
 ) -> impl IntoView {
     // Memoize sparkline calculation
     let sparkline_points = sparkline.as_ref().map(|data| {
@@ -26,11 +31,16 @@ pub fn MetricCard(
         }.into_view();
     }
 
+    let (staleness, age_title) = data_staleness(fetched_at);
+
     view! {
-        <div class="metric-card">
+        <div class=format!("metric-card {}", staleness.css_class()) title=age_title>
             <div style="display: flex; justify-content: space-between; align-items: flex-start;">
                 <div class="metric-label-row">
                     <span class="metric-label">{label}</span>
+                    {(staleness == DataStaleness::VeryStale).then(|| view! {
+                        <span class="stale-badge">"Stale"</span>
+                    })}
                     {tooltip.map(|tip| view! {
                         <span class="info-tooltip" data-tooltip=tip>
                             <InfoIcon />
@@ -75,6 +85,34 @@ pub fn MetricCard(
     }.into_view()
 }
 
+/// Staleness of data fetched at `fetched_at`, with an age tooltip once stale
+///
+/// Unknown fetch times (missing or zero) count as fresh.
+pub fn data_staleness(fetched_at: Option<i64>) -> (DataStaleness, Option<String>) {
+    let Some(fetched_at) = fetched_at.filter(|&t| t > 0) else {
+        return (DataStaleness::Fresh, None);
+    };
+    let age = current_timestamp().saturating_sub(fetched_at).max(0) as u64;
+    let staleness = config().data_staleness(age);
+    let title = (staleness != DataStaleness::Fresh)
+        .then(|| format!("Last updated {}", format_relative_time(age)));
+    (staleness, title)
+}
+
+pub(crate) fn current_timestamp() -> i64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 1000.0) as i64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+}
+
 // Phase 4: Extract computation to non-generic function
 #[inline]
 fn compute_sparkline_points(data: &[f64]) -> (String, String) {
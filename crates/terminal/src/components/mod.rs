@@ -18,9 +18,12 @@ pub mod pagination;
 pub use loading::{
     Skeleton, CardSkeleton, MetricCardSkeleton, MetricRowSkeleton,
     ActivityItemSkeleton, TableRowSkeleton, TableSkeleton, ChartSkeleton,
-    LoadingSpinner, InlineSpinner, LoadingOverlay, EmptyState, LiveIndicator,
+    LoadingSpinner, InlineSpinner, LoadingOverlay, EmptyState, EmptyTableRow, LiveIndicator,
     ProgressBar,
 };
 
+// Re-export error display components
+pub use error_boundary::{error_message, DataLoadError};
+
 // Re-export pagination components
 pub use pagination::{Pagination, PaginationWithSize};
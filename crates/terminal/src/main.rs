@@ -21,26 +21,39 @@ async fn main() {
     use axum::{routing::get, Router, Json};
     use axum::body::Body;
     use axum::http::header::{HeaderName, HeaderValue};
-    use axum::http::{Method, Request};
+    use axum::http::{Method, Request, StatusCode};
     use axum::middleware::{self, Next};
-    use axum::response::Response;
+    use axum::response::{IntoResponse, Redirect, Response};
     use leptos::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
     use serde::Serialize;
     use tower_http::compression::CompressionLayer;
-    use tower_http::cors::{CorsLayer, Any};
+    use tower_http::cors::{AllowOrigin, CorsLayer, Any};
     use tower_http::services::ServeDir;
+    use tracing::Instrument;
     use usdfc_analytics_terminal::app::App;
     use usdfc_backend::{fileserv::file_and_error_handler, state::AppState, api::handlers};
     
 
-    // Initialize tracing
-    tracing_subscriber::fmt()
+    // Initialize tracing; secrets in upstream URLs are masked when redaction is on
+    let redact_logs = usdfc_core::config::config().log_redaction;
+    let subscriber = tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "info,tower_http=debug".into()),
         )
-        .init();
+        .with_writer(move || usdfc_backend::log_redact::RedactingWriter::new(std::io::stdout(), redact_logs));
+    match usdfc_core::config::config().log_format {
+        usdfc_core::config::LogFormat::Json => subscriber
+            .fmt_fields(usdfc_backend::log_format::JsonFields)
+            .event_format(usdfc_backend::log_format::JsonFormat)
+            .init(),
+        usdfc_core::config::LogFormat::Pretty => subscriber.init(),
+    }
+
+    for warning in &usdfc_core::config::config().load_warnings {
+        tracing::warn!("{}", warning);
+    }
 
     // Load .env file
     let _ = dotenvy::dotenv();
@@ -62,36 +75,182 @@ async fn main() {
 
     // Explicitly register all server functions (required on some platforms)
     use server_fn::axum::register_explicit;
+    use server_fn::ServerFn;
     use usdfc_api::*;
 
-    register_explicit::<GetProtocolMetrics>();
-    register_explicit::<GetRecentTransactions>();
-    register_explicit::<GetTroves>();
-    register_explicit::<GetLendingMarkets>();
-    register_explicit::<GetDailyVolumes>();
-    register_explicit::<GetAddressInfo>();
-    register_explicit::<GetNormalizedAddress>();
-    register_explicit::<GetTopHolders>();
-    register_explicit::<GetStabilityPoolTransfers>();
-    register_explicit::<GetUSDFCPriceData>();
-    register_explicit::<CheckApiHealth>();
-    register_explicit::<GetHolderCount>();
-    register_explicit::<GetOrderBook>();
-    register_explicit::<GetRecentLendingTrades>();
-    register_explicit::<GetAdvancedChartData>();
-
-    tracing::info!("Registered {} server functions", 15);
+    macro_rules! register_server_fns {
+        ($($server_fn:ty),* $(,)?) => {{
+            $(register_explicit::<$server_fn>();)*
+            [$(stringify!($server_fn)),*].len()
+        }};
+    }
+
+    let registered = register_server_fns![
+        GetProtocolMetrics,
+        GetRecentTransactions,
+        GetTroves,
+        GetTroveLeaderboard,
+        GetIcrDistribution,
+        GetTokenInfo,
+        GetTroveByAddress,
+        ExportTrovesCsv,
+        GetLendingMarkets,
+        GetDailyVolumes,
+        GetAddressInfo,
+        GetNormalizedAddress,
+        GetAddressNote,
+        GetAddressNotes,
+        SetAddressNote,
+        GetWatchlist,
+        AddWatchAddress,
+        RemoveWatchAddress,
+        GetTopHolders,
+        GetHoldersPage,
+        GetStabilityPoolTransfers,
+        GetStabilityPoolAnalytics,
+        GetUSDFCPriceData,
+        GetDepegStatus,
+        GetTwap,
+        GetTcrVolatility,
+        GetMetricHistory,
+        GetFeeAnalytics,
+        GetRecentLiquidations,
+        GetStabilityPoolDepositors,
+        CheckApiHealth,
+        GetCacheStats,
+        GetHolderCount,
+        GetNetworkGas,
+        GetOrderBook,
+        GetSpreadHistory,
+        GetRecentLendingTrades,
+        GetAdvancedChartData,
+        GetWalletComparison,
+        GetAlertEvents,
+        AcknowledgeAlert,
+    ];
+
+    tracing::info!("Registered {} server functions", registered);
 
     // Generate route list from App component for SSR
     let routes = generate_route_list(App);
 
     // Build application state
-    let app_state = AppState {
-        leptos_options: leptos_options.clone(),
-    };
+    let app_state = AppState::new(leptos_options.clone());
+    let metrics_tx = app_state.metrics_tx.clone();
+
+    // Keep or generate an X-Request-Id, echo it on the response and log the
+    // request (server functions and their upstream calls included) under it
+    async fn request_id(mut request: Request<Body>, next: Next) -> Response {
+        use usdfc_backend::request_id::{resolve, HEADER};
+
+        let id = resolve(request.headers().get(HEADER).and_then(|v| v.to_str().ok()));
+        let header = HeaderValue::from_str(&id).expect("request ids are visible ASCII");
+        request.headers_mut().insert(HEADER, header.clone());
+        let span = tracing::info_span!(
+            "request",
+            request_id = %id,
+            method = %request.method(),
+            path = %request.uri().path()
+        );
+        let mut response = next.run(request).instrument(span).await;
+        response.headers_mut().insert(HEADER, header);
+        response
+    }
+
+    // Send `/` to the configured landing page, or `/?embed=<metric>` to its widget
+    async fn landing_redirect(request: Request<Body>, next: Next) -> Response {
+        if request.uri().path() == "/" {
+            let query = request.uri().query().unwrap_or_default();
+            let (embed, rest): (Vec<&str>, Vec<&str>) = query.split('&').partition(|p| p.starts_with("embed="));
+            if let Some(metric) = embed.first().map(|p| p.trim_start_matches("embed=")).filter(|m| !m.is_empty()) {
+                let rest: Vec<&str> = rest.into_iter().filter(|p| !p.is_empty()).collect();
+                let target = if rest.is_empty() {
+                    format!("/embed/{}", metric)
+                } else {
+                    format!("/embed/{}?{}", metric, rest.join("&"))
+                };
+                return Redirect::temporary(&target).into_response();
+            }
+            if let Some(route) = usdfc_core::config::config().landing_redirect() {
+                return Redirect::temporary(route).into_response();
+            }
+        }
+        next.run(request).await
+    }
+
+    // Server functions that only back one page. They are guarded along with
+    // that page; functions shared with other pages (metrics, prices, recent
+    // transactions) are only guarded when their own /api path is listed.
+    const PAGE_SERVER_FNS: &[(&str, &str)] = &[
+        (<GetAlertEvents as ServerFn>::PATH, "/tools"),
+        (<AcknowledgeAlert as ServerFn>::PATH, "/tools"),
+        (<ExportTrovesCsv as ServerFn>::PATH, "/tools"),
+        (<GetTroveByAddress as ServerFn>::PATH, "/address"),
+        (<GetNormalizedAddress as ServerFn>::PATH, "/address"),
+        (<GetAddressNote as ServerFn>::PATH, "/address"),
+        (<SetAddressNote as ServerFn>::PATH, "/address"),
+        (<GetTroveLeaderboard as ServerFn>::PATH, "/entities"),
+        (<GetOrderBook as ServerFn>::PATH, "/lending"),
+        (<GetRecentLendingTrades as ServerFn>::PATH, "/lending"),
+        (<GetTroves as ServerFn>::PATH, "/protocol"),
+        (<GetIcrDistribution as ServerFn>::PATH, "/protocol"),
+        (<GetStabilityPoolTransfers as ServerFn>::PATH, "/protocol"),
+        (<GetTcrVolatility as ServerFn>::PATH, "/protocol"),
+        (<GetFeeAnalytics as ServerFn>::PATH, "/protocol"),
+    ];
+
+    // Basic auth for routes listed in PROTECTED_ROUTES and the server
+    // functions behind them
+    async fn basic_auth_guard(request: Request<Body>, next: Next) -> Response {
+        let cfg = usdfc_core::config::config();
+        let path = request.uri().path();
+        let page = PAGE_SERVER_FNS
+            .iter()
+            .find(|(fn_path, _)| *fn_path == path)
+            .map(|(_, page)| *page);
+        if cfg.route_is_protected(path) || page.is_some_and(|page| cfg.route_is_protected(page)) {
+            let header = request
+                .headers()
+                .get(axum::http::header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok());
+            if !usdfc_backend::auth::basic_auth_matches(header, &cfg.basic_auth_user, &cfg.basic_auth_password) {
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    [(axum::http::header::WWW_AUTHENTICATE, "Basic realm=\"USDFC Terminal\"")],
+                    "Authentication required",
+                )
+                    .into_response();
+            }
+        }
+        next.run(request).await
+    }
+
+    // Per-IP rate limit for the public REST API; applied to `api_routes` only
+    async fn api_rate_limit(request: Request<Body>, next: Next) -> Response {
+        let peer = request
+            .extensions()
+            .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+            .map(|info| info.0.ip());
+        let trust_forwarded = usdfc_core::config::config().trust_forwarded_for;
+        let Some(ip) = usdfc_backend::rate_limit::client_ip(request.headers(), peer, trust_forwarded) else {
+            return next.run(request).await;
+        };
+        if let Err(wait) = usdfc_backend::rate_limit::check(ip) {
+            tracing::debug!("Rate limited {} on {}", ip, request.uri().path());
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(axum::http::header::RETRY_AFTER, usdfc_backend::rate_limit::retry_after_secs(wait).to_string())],
+                Json(usdfc_backend::api::ApiResponse::<()>::error("Rate limit exceeded")),
+            )
+                .into_response();
+        }
+        next.run(request).await
+    }
 
     // Security headers middleware for production deployment
     async fn security_headers(request: Request<Body>, next: Next) -> Response {
+        // Embed widgets are meant to be framed by other sites
+        let embed = request.uri().path().starts_with("/embed/");
         let mut response = next.run(request).await;
         let headers = response.headers_mut();
 
@@ -101,11 +260,13 @@ async fn main() {
             HeaderValue::from_static("nosniff"),
         );
 
-        // Prevent clickjacking
-        headers.insert(
-            HeaderName::from_static("x-frame-options"),
-            HeaderValue::from_static("DENY"),
-        );
+        // Prevent clickjacking; embeds rely on CSP frame-ancestors instead
+        if !embed {
+            headers.insert(
+                HeaderName::from_static("x-frame-options"),
+                HeaderValue::from_static("DENY"),
+            );
+        }
 
         // XSS protection (legacy but still useful)
         headers.insert(
@@ -125,23 +286,36 @@ async fn main() {
             HeaderValue::from_static("max-age=31536000; includeSubDomains"),
         );
 
-        // Content Security Policy
-        headers.insert(
-            HeaderName::from_static("content-security-policy"),
-            HeaderValue::from_static(
-                "default-src 'self'; \
-                 script-src 'self' 'unsafe-inline' 'unsafe-eval' https://cdn.jsdelivr.net; \
-                 style-src 'self' 'unsafe-inline'; \
-                 img-src 'self' data: https:; \
-                 font-src 'self'; \
-                 connect-src 'self' https://api.node.glif.io https://filecoin.blockscout.com https://api.goldsky.com https://api.geckoterminal.com; \
-                 frame-ancestors 'none';",
-            ),
-        );
+        // Content Security Policy - built from configured endpoints. In nonce
+        // mode rendered pages already carry the full policy with their
+        // per-response nonce; other responses only restrict framing.
+        let cfg = usdfc_core::config::config();
+        let frame_ancestors = if embed { cfg.embed_frame_ancestors() } else { "'none'".to_string() };
+        let csp_header = HeaderName::from_static("content-security-policy");
+        let csp = if !cfg.csp_nonce {
+            Some(cfg.content_security_policy_framed(None, &frame_ancestors))
+        } else if !headers.contains_key(&csp_header) {
+            Some(format!("frame-ancestors {};", frame_ancestors))
+        } else {
+            None
+        };
+        if let Some(value) = csp.and_then(|csp| HeaderValue::from_str(&csp).ok()) {
+            headers.insert(csp_header, value);
+        }
 
         response
     }
 
+    // FAVICON_URL, when configured
+    async fn favicon_handler() -> Response {
+        let favicon = &usdfc_core::config::config().favicon_url;
+        if favicon.is_empty() {
+            StatusCode::NO_CONTENT.into_response()
+        } else {
+            Redirect::temporary(favicon).into_response()
+        }
+    }
+
     // Health check response types
     #[derive(Serialize)]
     struct HealthStatus {
@@ -167,6 +341,17 @@ async fn main() {
         error: Option<String>,
     }
 
+    impl CheckResult {
+        /// Result for a source turned off in config (not counted as a failure)
+        fn disabled() -> Self {
+            CheckResult {
+                status: "disabled".to_string(),
+                latency_ms: None,
+                error: None,
+            }
+        }
+    }
+
     // Simple health check - lightweight, returns immediately
     // Used by Railway health checks - should respond quickly
     async fn health_check() -> &'static str {
@@ -200,8 +385,10 @@ async fn main() {
             }
         };
 
-        // Check Blockscout
-        let blockscout_check = {
+        // Check Blockscout (skipped when disabled)
+        let blockscout_check = if !config().blockscout_enabled {
+            CheckResult::disabled()
+        } else {
             let start = std::time::Instant::now();
             let blockscout = BlockscoutClient::new();
             match blockscout.gql_get_token_info(&config().usdfc_token).await {
@@ -218,8 +405,10 @@ async fn main() {
             }
         };
 
-        // Check Subgraph
-        let subgraph_check = {
+        // Check Subgraph (skipped when disabled)
+        let subgraph_check = if !config().subgraph_enabled {
+            CheckResult::disabled()
+        } else {
             let start = std::time::Instant::now();
             let subgraph = SubgraphClient::new();
             match subgraph.get_lending_markets().await {
@@ -236,8 +425,10 @@ async fn main() {
             }
         };
 
-        // Check GeckoTerminal
-        let gecko_check = {
+        // Check GeckoTerminal (skipped when disabled)
+        let gecko_check = if !config().gecko_enabled {
+            CheckResult::disabled()
+        } else {
             let start = std::time::Instant::now();
             let gecko = GeckoClient::new();
             match gecko.get_pool_info(&config().pool_usdfc_wfil).await {
@@ -272,9 +463,9 @@ async fn main() {
         };
 
         // Determine overall status - degraded if any non-critical service fails
-        // Critical services: RPC, Blockscout, Database
+        // Critical services: RPC, Blockscout (unless disabled), Database
         let all_critical_healthy = rpc_check.status == "ok"
-            && blockscout_check.status == "ok"
+            && blockscout_check.status != "error"
             && db_check.status == "ok";
 
         let status = if all_critical_healthy {
@@ -297,6 +488,30 @@ async fn main() {
         })
     }
 
+    #[derive(Serialize)]
+    struct CacheHealth {
+        status: &'static str,
+        uptime_secs: u64,
+        /// Caches populated within their TTL
+        fresh: usize,
+        /// Caches populated at least once since startup
+        populated: usize,
+        caches: Vec<usdfc_core::types::CacheFreshness>,
+    }
+
+    // Cache freshness - reads in-memory state only, never an upstream, so
+    // it is safe for high-frequency load balancer probes
+    async fn cache_health_handler() -> Json<CacheHealth> {
+        let caches = usdfc_backend::cache::caches::freshness();
+        Json(CacheHealth {
+            status: "ok",
+            uptime_secs: get_uptime_secs(),
+            fresh: caches.iter().filter(|c| c.fresh).count(),
+            populated: caches.iter().filter(|c| c.last_populated.is_some()).count(),
+            caches,
+        })
+    }
+
     // Simple readiness check - returns ok if server is running
     async fn ready_handler() -> &'static str {
         // Initialize START_TIME on first call if not already done
@@ -304,26 +519,297 @@ async fn main() {
         "ok"
     }
 
+    // Live protocol metrics: the latest cached value on connect, then one
+    // `GetProtocolMetrics`-shaped JSON frame per collected snapshot. Frames
+    // the client has not taken within the send buffer are dropped.
+    async fn metrics_ws_handler(
+        ws: axum::extract::ws::WebSocketUpgrade,
+        axum::extract::State(state): axum::extract::State<AppState>,
+    ) -> Response {
+        use axum::extract::ws::{CloseFrame, Message};
+        use usdfc_backend::feeds;
+
+        let Some(slot) = feeds::slots().try_acquire() else {
+            tracing::warn!("Refusing metrics socket: {} feed connections open", feeds::slots().max());
+            return ws.on_upgrade(|mut socket| async move {
+                let close = CloseFrame {
+                    code: feeds::CLOSE_TRY_AGAIN_LATER,
+                    reason: "Too many live feed connections, try again later".into(),
+                };
+                let _ = socket.send(Message::Close(Some(close))).await;
+            });
+        };
+        let updates = state.metrics_tx.subscribe();
+        ws.on_upgrade(move |socket| stream_protocol_metrics(socket, updates, slot))
+    }
+
+    async fn stream_protocol_metrics(
+        socket: axum::extract::ws::WebSocket,
+        mut updates: tokio::sync::broadcast::Receiver<usdfc_core::types::ProtocolMetrics>,
+        _slot: usdfc_backend::feeds::FeedSlot,
+    ) {
+        use axum::extract::ws::Message;
+        use futures::{SinkExt, StreamExt};
+        use tokio::sync::broadcast::error::RecvError;
+        use tokio::sync::mpsc::error::TrySendError;
+
+        // The writer drains a bounded queue, so a slow client never blocks
+        // this loop or buffers more than `FEED_SEND_BUFFER` frames
+        let (mut sink, mut incoming) = socket.split();
+        let (frames, mut queued) = tokio::sync::mpsc::channel::<Message>(usdfc_backend::feeds::send_buffer());
+        let mut writer = tokio::spawn(async move {
+            while let Some(frame) = queued.recv().await {
+                if sink.send(frame).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        if let Some(current) = usdfc_backend::cache::caches::PROTOCOL_METRICS.get("default") {
+            if let Ok(json) = serde_json::to_string(&current) {
+                let _ = frames.try_send(Message::Text(json));
+            }
+        }
+
+        let mut dropped = 0u64;
+        loop {
+            tokio::select! {
+                update = updates.recv() => match update {
+                    Ok(metrics) => {
+                        let Ok(json) = serde_json::to_string(&metrics) else { continue };
+                        match frames.try_send(Message::Text(json)) {
+                            Ok(()) => {}
+                            // A slow client only needs the newest value
+                            Err(TrySendError::Full(_)) => dropped += 1,
+                            Err(TrySendError::Closed(_)) => break,
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => dropped += skipped,
+                    Err(RecvError::Closed) => break,
+                },
+                message = incoming.next() => match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // Pings are answered by axum; other client frames are ignored
+                    Some(Ok(_)) => {}
+                },
+                _ = &mut writer => break,
+            }
+        }
+        if dropped > 0 {
+            tracing::debug!("Metrics socket closed after dropping {} updates for a slow client", dropped);
+        }
+        writer.abort();
+    }
+
+    // New USDFC transfers as `text/event-stream`: polls recent transfers (the
+    // shared 10s cache) and sends each transfer not seen before as one
+    // `Transaction` JSON event, oldest first, with a heartbeat comment every 15s.
+    // A client more than `FEED_SEND_BUFFER` events behind is disconnected.
+    async fn transactions_sse_handler() -> Response {
+        use axum::response::sse::{Event, KeepAlive, Sse};
+        use std::time::Duration;
+        use tokio::sync::mpsc::error::TrySendError;
+        use usdfc_backend::feeds;
+        use usdfc_core::types::Transaction;
+
+        let Some(slot) = feeds::slots().try_acquire() else {
+            tracing::warn!("Refusing transaction stream: {} feed connections open", feeds::slots().max());
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(axum::http::header::RETRY_AFTER, "30")],
+                "Too many live feed connections, try again later",
+            )
+                .into_response();
+        };
+
+        let (events, queued) = tokio::sync::mpsc::channel::<Transaction>(feeds::send_buffer());
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(10));
+            let mut last_seen: Option<String> = None;
+            loop {
+                interval.tick().await;
+                if events.is_closed() {
+                    return;
+                }
+                let txs = match usdfc_api::get_recent_transactions(Some(50), None).await {
+                    Ok(txs) => txs,
+                    Err(e) => {
+                        tracing::debug!("Transaction stream poll failed: {}", e);
+                        continue;
+                    }
+                };
+                // Newest first; the first poll only marks where the stream starts
+                if let Some(last_seen) = &last_seen {
+                    let fresh: Vec<_> = txs.iter().take_while(|tx| &tx.hash != last_seen).cloned().collect();
+                    for tx in fresh.into_iter().rev() {
+                        match events.try_send(tx) {
+                            Ok(()) => {}
+                            Err(TrySendError::Full(_)) => {
+                                tracing::warn!(
+                                    "Disconnecting transaction stream client {} events behind",
+                                    feeds::send_buffer()
+                                );
+                                return;
+                            }
+                            Err(TrySendError::Closed(_)) => return,
+                        }
+                    }
+                }
+                if let Some(newest) = txs.first() {
+                    last_seen = Some(newest.hash.clone());
+                }
+            }
+        });
+
+        // The slot is held until the response stream is dropped
+        let stream = futures::stream::unfold((queued, slot), |(mut queued, slot)| async move {
+            loop {
+                let tx = queued.recv().await?;
+                if let Ok(event) = Event::default().id(tx.hash.clone()).json_data(&tx) {
+                    return Some((Ok::<_, std::convert::Infallible>(event), (queued, slot)));
+                }
+            }
+        });
+
+        Sse::new(stream)
+            .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)).text("heartbeat"))
+            .into_response()
+    }
+
+    // Prometheus scrape endpoint - upstream, cache and circuit breaker metrics
+    async fn metrics_handler() -> Response {
+        use axum::http::header;
+        use axum::response::IntoResponse;
+
+        (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+            usdfc_backend::metrics::render(),
+        )
+            .into_response()
+    }
+
+    // Server-rendered chart image, same query parameters as /advanced share links:
+    // /api/v1/chart.svg?metrics=price,volume&res=1h&lookback=1w&type=area
+    async fn chart_svg_handler(
+        axum::extract::RawQuery(query): axum::extract::RawQuery,
+    ) -> Response {
+        use axum::http::{header, StatusCode};
+        use axum::response::IntoResponse;
+        use usdfc_backend::cache::caches;
+        use usdfc_backend::chart_image;
+        use usdfc_core::config::config;
+        use usdfc_core::types::{validate_combo, ChartMetric, ChartUrlState};
+
+        let state = ChartUrlState::from_query(query.as_deref().unwrap_or(""));
+        let mut metrics = state.metrics;
+        metrics.dedup();
+        if metrics.is_empty() {
+            metrics.push(ChartMetric::Price);
+        }
+        let resolution = state.resolution.unwrap_or_default();
+        let chart_type = state.chart_type.unwrap_or_default();
+        // A custom range needs both ends, as in the interactive chart
+        let (start, end) = match (state.start, state.end) {
+            (Some(start), Some(end)) if start < end => (Some(start), Some(end)),
+            _ => (None, None),
+        };
+        // Same safety clamp the chart data server function applies
+        let lookback = validate_combo(
+            resolution,
+            state.lookback.unwrap_or_default(),
+            &config().chart_lookback_overrides,
+        )
+        .lookback;
+
+        let cache_key = format!(
+            "{}|{}|{}|{}|{:?}|{:?}",
+            metrics.iter().map(|m| m.to_url_param()).collect::<Vec<_>>().join(","),
+            resolution.to_url_param(),
+            lookback.to_url_param(),
+            chart_type.to_url_param(),
+            start,
+            end,
+        );
+
+        let (svg, range) = match caches::CHART_IMAGES.get(&cache_key) {
+            Some(cached) => cached,
+            None => {
+                let data = match usdfc_api::get_advanced_chart_data(resolution, lookback, start, end, None).await {
+                    Ok(data) => data,
+                    Err(e) => {
+                        tracing::error!("Chart image data error: {}", e);
+                        return (StatusCode::BAD_GATEWAY, format!("Failed to load chart data: {}", e)).into_response();
+                    }
+                };
+                let range = chart_image::effective_range(&data, start, end);
+                let svg = chart_image::render_svg(&data, &metrics, chart_type, range);
+                caches::CHART_IMAGES.set(cache_key, (svg.clone(), range));
+                (svg, range)
+            }
+        };
+
+        (
+            [
+                (header::CONTENT_TYPE, "image/svg+xml".to_string()),
+                (header::CACHE_CONTROL, "public, max-age=30".to_string()),
+                (HeaderName::from_static("x-chart-start"), range.0.to_string()),
+                (HeaderName::from_static("x-chart-end"), range.1.to_string()),
+                (HeaderName::from_static("x-chart-lookback"), lookback.to_url_param().to_string()),
+            ],
+            svg,
+        )
+            .into_response()
+    }
+
     // Build REST API router with CORS support
-    // CORS layer allows cross-origin requests to API endpoints
+    // CORS layer allows cross-origin requests to API endpoints, from any origin
+    // unless USDFC_CORS_ALLOWED_ORIGINS lists them. With a list, tower-http echoes
+    // the matching origin back and adds `Vary: Origin` so caches keep them apart.
+    let cors_origins = &usdfc_core::config::config().cors_allowed_origins;
+    let allow_origin = if cors_origins.is_empty() {
+        AllowOrigin::any()
+    } else {
+        let origins: Vec<HeaderValue> = cors_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin).unwrap_or_else(|e| {
+                    panic!("Invalid USDFC_CORS_ALLOWED_ORIGINS entry {:?}: {}", origin, e)
+                })
+            })
+            .collect();
+        tracing::info!("CORS restricted to {} origin(s): {}", origins.len(), cors_origins.join(", "));
+        AllowOrigin::list(origins)
+    };
     let cors = CorsLayer::new()
-        .allow_origin(Any)
+        .allow_origin(allow_origin)
         .allow_methods([Method::GET])
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([axum::http::header::ETAG, HeaderName::from_static(usdfc_backend::request_id::HEADER)]);
 
     // Create API router with all REST endpoints
     let api_routes = Router::new()
+        .route("/openapi.json", get(handlers::get_openapi))
         .route("/v1/price", get(handlers::get_price))
         .route("/v1/metrics", get(handlers::get_metrics))
         .route("/v1/health", get(handlers::get_health))
         .route("/v1/version", get(handlers::get_version))
         .route("/v1/history", get(handlers::get_history))
+        .route("/v1/history/export", get(handlers::export_history))
         .route("/v1/troves", get(handlers::get_troves_list))
+        .route("/v1/troves.csv", get(handlers::get_troves_csv))
         .route("/v1/troves/:addr", get(handlers::get_trove_by_address))
         .route("/v1/transactions", get(handlers::get_transactions))
+        .route("/v1/transactions.csv", get(handlers::get_transactions_csv))
         .route("/v1/address/:addr", get(handlers::get_address))
         .route("/v1/lending", get(handlers::get_lending))
+        .route("/v1/orderbook", get(handlers::get_orderbook))
+        .route("/v1/trades", get(handlers::get_trades))
         .route("/v1/holders", get(handlers::get_holders))
+        .route("/v1/chart.svg", get(chart_svg_handler))
+        // Brotli when the client accepts it (preferred at equal q-values), else gzip;
+        // large trove and holder lists shrink noticeably more than with gzip
+        .layer(CompressionLayer::new().br(true).gzip(true))
+        .layer(middleware::from_fn(api_rate_limit))
         .layer(cors);
 
     // Build Axum router with leptos_router integration
@@ -335,44 +821,104 @@ async fn main() {
         .route("/api/health", get(health_check))           // Railway health check (lightweight)
         .route("/api/health/detailed", get(health_detailed_handler))  // Detailed diagnostics
         .route("/health", get(ready_handler))              // Kubernetes-style (lightweight)
+        .route("/health/cache", get(cache_health_handler)) // Cache freshness (no upstream calls)
         .route("/ready", get(ready_handler))               // Readiness probe (lightweight)
+        .route("/metrics", get(metrics_handler))           // Prometheus scrape target
+        .route("/ws/metrics", get(metrics_ws_handler))     // Live protocol metrics (WebSocket)
+        .route("/sse/transactions", get(transactions_sse_handler)) // New transfers (Server-Sent Events)
+        .route("/favicon.ico", get(favicon_handler))
         // Static files - MUST be before leptos_routes to prevent /*any from catching them
         .nest_service("/pkg", ServeDir::new(format!("{}/pkg", leptos_options.site_root)))
         // Leptos routes with SSR (this also handles server functions automatically)
         .leptos_routes(&app_state, routes, App)
         // Static file serving and 404 handler
         .fallback(file_and_error_handler)
+        // Landing page redirect and basic auth guards
+        .layer(middleware::from_fn(landing_redirect))
+        .layer(middleware::from_fn(basic_auth_guard))
         // Add security headers
         .layer(middleware::from_fn(security_headers))
-        // Add compression
-        .layer(CompressionLayer::new())
+        // Add compression (gzip only: on-the-fly Brotli costs too much CPU for
+        // the wasm bundle; API responses are already compressed above)
+        .layer(CompressionLayer::new().no_br())
+        // Outermost, so every response carries the correlation id
+        .layer(middleware::from_fn(request_id))
         // Add state
         .with_state(app_state);
 
     // Initialize SQLite database for metric history persistence
     match usdfc_backend::historical::init_db() {
         Ok(()) => tracing::info!("Initialized metrics database"),
-        Err(e) => tracing::error!("Failed to initialize metrics database: {}", e),
+        Err(e) => tracing::error!("Failed to initialize metrics database, history will be kept in memory only: {}", e),
     }
 
+    // Populate the hot caches so the first visitor after a deploy is not
+    // served at full upstream latency
+    warm_caches().await;
+
     // Start background metric snapshot collector
-    usdfc_backend::historical::start_snapshot_collector();
-    tracing::info!("Started background metric snapshot collector (60s interval)");
+    usdfc_backend::historical::start_snapshot_collector(metrics_tx);
+    tracing::info!(
+        "Started background metric snapshot collector ({}s interval)",
+        usdfc_backend::historical::snapshot_interval_secs()
+    );
+    usdfc_backend::historical::start_retention_job();
 
     // Start background cache cleanup task
     usdfc_backend::cache::caches::start_cache_cleanup();
     tracing::info!("Started background cache cleanup task (60s interval)");
+    usdfc_backend::rate_limit::start_cleanup();
 
     // Start server
     tracing::info!("Starting USDFC Analytics Terminal on http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await
         .unwrap();
 }
 
+/// Longest startup is held up waiting for cache warming
+#[cfg(feature = "ssr")]
+const CACHE_WARM_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Fill the protocol metrics, USDFC price and lending market caches once
+///
+/// Best effort: failures are logged and the server starts regardless.
+#[cfg(feature = "ssr")]
+async fn warm_caches() {
+    let start = Instant::now();
+    let warm = async {
+        let (metrics, price, markets) = tokio::join!(
+            usdfc_api::get_protocol_metrics(),
+            usdfc_api::get_usdfc_price_data(),
+            usdfc_api::get_lending_markets(),
+        );
+        let failures = [
+            ("protocol_metrics", metrics.err()),
+            ("usdfc_price", price.err()),
+            ("lending_markets", markets.err()),
+        ];
+        let mut warmed = 0;
+        for (cache, error) in failures {
+            match error {
+                Some(e) => tracing::warn!("Cache warming failed for {}: {}", cache, e),
+                None => warmed += 1,
+            }
+        }
+        warmed
+    };
+
+    match tokio::time::timeout(CACHE_WARM_TIMEOUT, warm).await {
+        Ok(warmed) => tracing::info!("Warmed {}/3 caches in {}ms", warmed, start.elapsed().as_millis()),
+        Err(_) => tracing::warn!(
+            "Cache warming timed out after {}s, starting with cold caches",
+            CACHE_WARM_TIMEOUT.as_secs()
+        ),
+    }
+}
+
 #[cfg(feature = "ssr")]
 async fn shutdown_signal() {
     tokio::signal::ctrl_c()
@@ -4,17 +4,25 @@
 //! - USDFC and FIL balances
 //! - Activity statistics
 //! - Transaction history with filtering and pagination
+//! - Known-contract label and private operator note
+
+use std::collections::HashMap;
 
 use leptos::*;
 use leptos_router::*;
 use crate::components::icons::*;
 use crate::components::PaginationWithSize;
-use usdfc_api::{get_address_info, get_recent_transactions};
+use usdfc_api::{
+    get_address_info, get_address_note, get_address_notes, get_normalized_address, get_recent_transactions,
+    get_trove_by_address, set_address_note,
+};
+use usdfc_core::config::config;
 use usdfc_core::format::{
     format_timestamp, format_balance, shorten_hash, format_count,
-    format_amount, decimal_to_f64, format_date,
+    format_amount, decimal_to_f64, format_date, explorer_address_url, explorer_tx_url,
+    format_fil, format_usdfc,
 };
-use usdfc_core::types::Transaction;
+use usdfc_core::types::{Transaction, ADDRESS_NOTE_MAX_LEN};
 
 const DEFAULT_PAGE_SIZE: u32 = 25;
 
@@ -24,10 +32,38 @@ pub fn AddressDetail() -> impl IntoView {
     let params = use_params_map();
 
     // Extract address from URL params
-    let address = move || {
+    let raw_address = move || {
         params.with(|p| p.get("addr").cloned().unwrap_or_default())
     };
 
+    // Resolve both f4 and 0x representations of the address
+    let normalized = create_resource(raw_address, |addr| async move {
+        get_normalized_address(addr).await
+    });
+
+    // Canonical (lowercase EVM) form so f4 and 0x URLs converge on one page.
+    // Falls back to the raw input when conversion fails.
+    let address = create_memo(move |_| {
+        normalized
+            .get()
+            .and_then(|r| r.ok())
+            .and_then(|n| n.evm)
+            .map(|evm| evm.to_lowercase())
+            .unwrap_or_else(raw_address)
+    });
+
+    // Rewrite the URL to the canonical form without adding a history entry
+    #[cfg(feature = "hydrate")]
+    create_effect(move |_| {
+        let canonical = address.get();
+        if normalized.with(|n| matches!(n, Some(Ok(_)))) && canonical != raw_address() {
+            if let Some(history) = web_sys::window().and_then(|w| w.history().ok()) {
+                let url = format!("/address/{}", canonical);
+                let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+            }
+        }
+    });
+
     // Pagination state
     let (current_page, set_current_page) = create_signal(1u32);
     let (total_pages, set_total_pages) = create_signal(1u32);
@@ -45,7 +81,7 @@ pub fn AddressDetail() -> impl IntoView {
 
     // Fetch address info
     let address_info = create_resource(
-        address,
+        move || address.get(),
         |addr| async move {
             if addr.is_empty() {
                 return Err(ServerFnError::ServerError("No address provided".to_string()));
@@ -54,15 +90,29 @@ pub fn AddressDetail() -> impl IntoView {
         }
     );
 
+    // The address's trove, if it has one open
+    let trove = create_resource(
+        move || address.get(),
+        |addr| async move {
+            if addr.is_empty() {
+                return Ok(None);
+            }
+            get_trove_by_address(addr).await
+        }
+    );
+
     // Fetch transactions (we'll filter client-side for the address)
     let transactions = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(200)).await }
+        |_| async move { get_recent_transactions(Some(200), None).await }
     );
 
+    // Notes for labelling counterparties in the history table
+    let address_notes = create_resource(|| (), |_| async move { get_address_notes().await });
+
     // Copy address to clipboard handler
     let on_copy = move |_| {
-        let addr = address();
+        let addr = address.get();
         #[cfg(feature = "hydrate")]
         {
             use wasm_bindgen::prelude::*;
@@ -120,13 +170,13 @@ pub fn AddressDetail() -> impl IntoView {
                     </div>
                 </div>
                 <a
-                    href=move || format!("https://filecoin.blockscout.com/address/{}", address())
+                    href=move || explorer_address_url(&config().explorer_url, &address.get())
                     target="_blank"
                     class="btn btn-secondary"
                     style="display: flex; align-items: center; gap: 6px;"
                 >
                     <ExternalLinkIcon />
-                    "View on Blockscout"
+                    "View in Explorer"
                 </a>
             </div>
 
@@ -137,7 +187,7 @@ pub fn AddressDetail() -> impl IntoView {
                         <div class="metric-label" style="margin-bottom: 8px;">"Address"</div>
                         <div style="display: flex; align-items: center; gap: 12px; flex-wrap: wrap;">
                             <code style="font-size: 14px; color: var(--accent-cyan); word-break: break-all; background: var(--bg-tertiary); padding: 8px 12px; border-radius: 6px; flex: 1; min-width: 280px;">
-                                {address}
+                                {move || address.get()}
                             </code>
                             <button
                                 class="btn btn-secondary"
@@ -152,6 +202,33 @@ pub fn AddressDetail() -> impl IntoView {
                                 }}
                             </button>
                         </div>
+                        // Both representations of the address
+                        <Suspense fallback=|| ()>
+                            {move || normalized.get().map(|res| match res {
+                                Ok(n) => view! {
+                                    <div style="display: flex; flex-direction: column; gap: 4px; margin-top: 8px; font-size: 12px;">
+                                        {n.evm.map(|evm| view! {
+                                            <div>
+                                                <span style="color: var(--text-muted);">"EVM: "</span>
+                                                <code style="color: var(--text-secondary);">{evm.to_lowercase()}</code>
+                                            </div>
+                                        })}
+                                        {n.f4.map(|f4| view! {
+                                            <div>
+                                                <span style="color: var(--text-muted);">"Filecoin (f4): "</span>
+                                                <code style="color: var(--text-secondary);">{f4}</code>
+                                            </div>
+                                        })}
+                                    </div>
+                                }.into_view(),
+                                Err(e) => view! {
+                                    <div style="margin-top: 8px; font-size: 12px; color: var(--text-muted);">
+                                        "Could not resolve alternate address format: " {e.to_string()}
+                                    </div>
+                                }.into_view(),
+                            })}
+                        </Suspense>
+                        <AddressNotePanel address=address />
                     </div>
                 </div>
 
@@ -200,6 +277,32 @@ pub fn AddressDetail() -> impl IntoView {
                 </Suspense>
             </div>
 
+            // Trove (only shown when the address has one open)
+            <Suspense fallback=|| ()>
+                {move || trove.get().and_then(|res| res.ok()).flatten().map(|t| view! {
+                    <div class="card" style="margin-bottom: 24px;">
+                        <div style="display: flex; align-items: center; justify-content: space-between; margin-bottom: 16px;">
+                            <h3 class="card-title" style="margin: 0;">"Trove"</h3>
+                            <span class=t.status.css_class()>{t.status.as_str()}</span>
+                        </div>
+                        <div class="grid-3">
+                            <div style="padding: 16px; background: var(--bg-tertiary); border-radius: 8px;">
+                                <div class="metric-label">"Collateral"</div>
+                                <div class="metric-value cyan">{format_fil(t.collateral)}</div>
+                            </div>
+                            <div style="padding: 16px; background: var(--bg-tertiary); border-radius: 8px;">
+                                <div class="metric-label">"Debt"</div>
+                                <div class="metric-value purple">{format_usdfc(t.debt)}</div>
+                            </div>
+                            <div style="padding: 16px; background: var(--bg-tertiary); border-radius: 8px;">
+                                <div class="metric-label">"ICR"</div>
+                                <div class="metric-value">{format!("{:.1}%", decimal_to_f64(t.icr))}</div>
+                            </div>
+                        </div>
+                    </div>
+                })}
+            </Suspense>
+
             // Activity Stats Grid
             <div class="card" style="margin-bottom: 24px;">
                 <div style="display: flex; align-items: center; justify-content: space-between; margin-bottom: 16px; flex-wrap: wrap; gap: 12px;">
@@ -248,7 +351,7 @@ pub fn AddressDetail() -> impl IntoView {
                     </div>
                 }>
                     {move || {
-                        let addr = address().to_lowercase();
+                        let addr = address.get().to_lowercase();
                         let current_timeframe = timeframe.get();
 
                         // Get both address info and transactions
@@ -473,7 +576,7 @@ pub fn AddressDetail() -> impl IntoView {
                                 <tr><td colspan="5" style="text-align: center; padding: 40px;">"Loading transactions..."</td></tr>
                             }>
                                 {move || {
-                                    let addr = address().to_lowercase();
+                                    let addr = address.get().to_lowercase();
                                     let filter = tx_filter.get();
                                     let page = current_page.get() as usize;
                                     let items_per_page = page_size.get() as usize;
@@ -537,6 +640,10 @@ pub fn AddressDetail() -> impl IntoView {
                                                     let tx_hash = tx.hash.clone();
                                                     let tx_hash_link = tx.hash.clone();
                                                     let counterparty_link = counterparty.clone();
+                                                    let counterparty_tag = address_notes.with(|notes| {
+                                                        let notes = notes.as_ref().and_then(|r| r.as_ref().ok());
+                                                        address_tag(&counterparty, notes)
+                                                    });
 
                                                     view! {
                                                         <tr>
@@ -551,10 +658,11 @@ pub fn AddressDetail() -> impl IntoView {
                                                                 >
                                                                     {shorten_hash(&counterparty)}
                                                                 </a>
+                                                                {counterparty_tag}
                                                             </td>
                                                             <td style="font-family: monospace; font-size: 11px;">
                                                                 <a
-                                                                    href=format!("https://filecoin.blockscout.com/tx/{}", tx_hash_link)
+                                                                    href=explorer_tx_url(&config().explorer_url, &tx_hash_link)
                                                                     target="_blank"
                                                                     class="tx-hash-link"
                                                                     title=tx_hash.clone()
@@ -596,6 +704,126 @@ pub fn AddressDetail() -> impl IntoView {
     }
 }
 
+/// Small label under an address in transaction tables
+///
+/// Merges the built-in contract label with the operator note, when either exists.
+pub(crate) fn address_tag(address: &str, notes: Option<&HashMap<String, String>>) -> Option<View> {
+    let label = config().known_address_label(address);
+    let note = notes.and_then(|n| n.get(&address.to_lowercase()));
+    let text = match (label, note) {
+        (Some(label), Some(note)) => format!("{} · {}", label, note),
+        (Some(label), None) => label.to_string(),
+        (None, Some(note)) => note.clone(),
+        (None, None) => return None,
+    };
+    let class = if note.is_some() { "address-tag note" } else { "address-tag" };
+    Some(view! { <span class=class title=text.clone()>{text}</span> }.into_view())
+}
+
+/// Known-contract label plus the operator note for an address, with an editor
+///
+/// Saving requires the server's ADDRESS_NOTES_TOKEN; an empty note clears it.
+#[component]
+fn AddressNotePanel(address: Memo<String>) -> impl IntoView {
+    let (editing, set_editing) = create_signal(false);
+    let (draft, set_draft) = create_signal(String::new());
+    let (token, set_token) = create_signal(String::new());
+
+    let save_note = create_action(|(addr, note, token): &(String, String, String)| {
+        let (addr, note, token) = (addr.clone(), note.clone(), token.clone());
+        async move { set_address_note(addr, note, token).await }
+    });
+
+    // Refetch after every save so the panel shows what the server stored
+    let note = create_resource(
+        move || (address.get(), save_note.version().get()),
+        |(addr, _)| async move {
+            if addr.is_empty() {
+                return Ok(None);
+            }
+            get_address_note(addr).await
+        },
+    );
+
+    create_effect(move |_| {
+        if let Some(Ok(_)) = save_note.value().get() {
+            set_editing.set(false);
+        }
+    });
+
+    let current_note = move || {
+        note.get()
+            .and_then(|res| res.ok())
+            .flatten()
+            .map(|n| n.note)
+            .unwrap_or_default()
+    };
+
+    view! {
+        <div style="display: flex; align-items: center; gap: 8px; flex-wrap: wrap; margin-top: 12px; font-size: 12px;">
+            {move || config().known_address_label(&address.get()).map(|label| view! {
+                <span class="entity-badge protocol">{label}</span>
+            })}
+            <Suspense fallback=|| ()>
+                {move || {
+                    let text = current_note();
+                    (!text.is_empty() && !editing.get()).then(|| view! {
+                        <span class="address-note" title="Private note">{text}</span>
+                    })
+                }}
+            </Suspense>
+            <Show when=move || !editing.get()>
+                <button
+                    class="btn btn-ghost"
+                    style="padding: 4px 8px; font-size: 12px;"
+                    on:click=move |_| {
+                        set_draft.set(current_note());
+                        set_editing.set(true);
+                    }
+                >
+                    {move || if current_note().is_empty() { "Add note" } else { "Edit note" }}
+                </button>
+            </Show>
+        </div>
+        <Show when=move || editing.get()>
+            <form
+                style="display: flex; gap: 8px; flex-wrap: wrap; margin-top: 8px;"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    save_note.dispatch((address.get(), draft.get(), token.get()));
+                }
+            >
+                <input
+                    class="input"
+                    style="flex: 2; min-width: 200px; padding: 8px 12px; font-size: 12px;"
+                    type="text"
+                    placeholder="Note (leave empty to remove)"
+                    maxlength=ADDRESS_NOTE_MAX_LEN.to_string()
+                    prop:value=draft
+                    on:input=move |ev| set_draft.set(event_target_value(&ev))
+                />
+                <input
+                    class="input"
+                    style="flex: 1; min-width: 140px; padding: 8px 12px; font-size: 12px;"
+                    type="password"
+                    placeholder="Notes token"
+                    prop:value=token
+                    on:input=move |ev| set_token.set(event_target_value(&ev))
+                />
+                <button class="btn btn-secondary" type="submit" disabled=move || save_note.pending().get()>
+                    "Save"
+                </button>
+                <button class="btn btn-ghost" type="button" on:click=move |_| set_editing.set(false)>
+                    "Cancel"
+                </button>
+                {move || save_note.value().get().and_then(|res| res.err()).map(|e| view! {
+                    <div style="width: 100%; font-size: 12px; color: var(--accent-red);">{e.to_string()}</div>
+                })}
+            </form>
+        </Show>
+    }
+}
+
 /// Legacy AddressLookup component - redirects to Explorer
 #[component]
 pub fn AddressLookup() -> impl IntoView {
@@ -9,7 +9,12 @@ use usdfc_api::{
     get_recent_transactions, get_lending_markets, get_holder_count,
     check_api_health, get_advanced_chart_data,
 };
-use usdfc_core::types::{ChartResolution, ChartLookback, ChartMetric, ChartType, ChartDataResponse};
+use usdfc_core::types::{ChartResolution, ChartLookback, ChartMetric, ChartType, ChartDataResponse, validate_combo};
+#[cfg(feature = "hydrate")]
+use usdfc_core::types::ChartUrlState;
+#[cfg(feature = "hydrate")]
+use crate::components::advanced_chart::build_chart_option;
+use crate::components::LiveIndicator;
 use usdfc_core::format::{format_volume, format_usd_compact, decimal_to_f64, format_count};
 use std::collections::HashSet;
 
@@ -17,113 +22,14 @@ use std::collections::HashSet;
 // URL State Management
 // ============================================================================
 
-/// Chart state that can be serialized to/from URL query params
-#[derive(Clone, Debug, Default)]
-pub struct ChartUrlState {
-    pub metrics: Vec<ChartMetric>,
-    pub resolution: Option<ChartResolution>,
-    pub lookback: Option<ChartLookback>,
-    pub chart_type: Option<ChartType>,
-    pub start: Option<i64>,
-    pub end: Option<i64>,
-}
-
-impl ChartUrlState {
-    /// Parse chart state from URL query string
-    /// Format: /advanced?metrics=price,volume&res=1h&lookback=1w&type=area&start=1704067200&end=1704153600
-    #[cfg(feature = "hydrate")]
-    pub fn from_url() -> Self {
-        use web_sys::window;
-
-        let mut state = Self::default();
-
-        let Some(window) = window() else { return state };
-        let Ok(location) = window.location().search() else { return state };
-
-        // Skip the leading '?'
-        let query = if location.starts_with('?') {
-            &location[1..]
-        } else {
-            &location
-        };
-
-        if query.is_empty() {
-            return state;
-        }
-
-        // Parse query params manually (avoiding external dependencies)
-        for param in query.split('&') {
-            let mut parts = param.splitn(2, '=');
-            let key = parts.next().unwrap_or("");
-            let value = parts.next().unwrap_or("");
-
-            match key {
-                "metrics" => {
-                    state.metrics = value
-                        .split(',')
-                        .filter_map(|m| ChartMetric::from_url_param(m))
-                        .collect();
-                }
-                "res" => {
-                    state.resolution = ChartResolution::from_url_param(value);
-                }
-                "lookback" => {
-                    state.lookback = ChartLookback::from_url_param(value);
-                }
-                "type" => {
-                    state.chart_type = ChartType::from_url_param(value);
-                }
-                "start" => {
-                    state.start = value.parse().ok();
-                }
-                "end" => {
-                    state.end = value.parse().ok();
-                }
-                _ => {}
-            }
-        }
-
-        state
-    }
-
-    /// Build URL query string from current state
-    pub fn to_query_string(
-        metrics: &HashSet<ChartMetric>,
-        resolution: ChartResolution,
-        lookback: ChartLookback,
-        chart_type: ChartType,
-        start: Option<i64>,
-        end: Option<i64>,
-    ) -> String {
-        let mut params = Vec::new();
-
-        // Metrics (comma-separated)
-        if !metrics.is_empty() {
-            let mut metrics_list: Vec<&str> = metrics.iter().map(|m| m.to_url_param()).collect();
-            metrics_list.sort(); // Consistent ordering
-            params.push(format!("metrics={}", metrics_list.join(",")));
-        }
-
-        // Resolution
-        params.push(format!("res={}", resolution.to_url_param()));
-
-        // Chart type
-        params.push(format!("type={}", chart_type.to_url_param()));
-
-        // Custom date range or lookback
-        if let (Some(s), Some(e)) = (start, end) {
-            params.push(format!("start={}", s));
-            params.push(format!("end={}", e));
-        } else {
-            params.push(format!("lookback={}", lookback.to_url_param()));
-        }
-
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
-        }
-    }
+/// Parse chart state from the page URL
+/// Format: /advanced?metrics=price,volume&res=1h&lookback=1w&type=area&start=1704067200&end=1704153600
+#[cfg(feature = "hydrate")]
+fn chart_state_from_url() -> ChartUrlState {
+    web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .map(|query| ChartUrlState::from_query(&query))
+        .unwrap_or_default()
 }
 
 /// Update the browser URL without reloading the page
@@ -162,17 +68,87 @@ fn copy_to_clipboard(text: &str) -> bool {
     false
 }
 
+/// Save `json` as a file download via a data URL
+#[cfg(feature = "hydrate")]
+fn download_json(filename: &str, json: &str) {
+    use wasm_bindgen::JsCast;
+
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let Some(link) = document
+        .create_element("a")
+        .ok()
+        .and_then(|el| el.dyn_into::<web_sys::HtmlElement>().ok())
+    else {
+        return;
+    };
+    let href = format!("data:application/json;charset=utf-8,{}", js_sys::encode_uri_component(json));
+    let _ = link.set_attribute("href", &href);
+    let _ = link.set_attribute("download", filename);
+    link.click();
+}
+
 /// Get the full shareable URL
 #[cfg(feature = "hydrate")]
-fn get_share_url(query: &str) -> String {
+fn get_share_url(path: &str, query: &str) -> String {
     use web_sys::window;
 
     if let Some(window) = window() {
         if let Ok(location) = window.location().origin() {
-            return format!("{}/advanced{}", location, query);
+            return format!("{}{}{}", location, path, query);
         }
     }
-    format!("/advanced{}", query)
+    format!("{}{}", path, query)
+}
+
+/// Keyboard shortcuts shown in the `?` help overlay
+const CHART_SHORTCUTS: &[(&str, &str)] = &[
+    ("\u{2190} / \u{2192}", "Pan chart"),
+    ("+ / -", "Zoom in / out"),
+    ("1 - 9", "Toggle metric"),
+    ("a / l / c", "Area / line / candle chart"),
+    ("?", "Toggle this help"),
+    ("Esc", "Close help"),
+];
+
+/// Whether a key event originated from a text field (shortcuts are skipped)
+#[cfg(feature = "hydrate")]
+fn is_typing_target(ev: &web_sys::KeyboardEvent) -> bool {
+    use wasm_bindgen::JsCast;
+
+    ev.target()
+        .and_then(|t| t.dyn_into::<web_sys::HtmlElement>().ok())
+        .map(|el| {
+            matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") || el.is_content_editable()
+        })
+        .unwrap_or(false)
+}
+
+/// Pan and/or scale the chart's dataZoom window
+///
+/// `pan` is a fraction of the visible window width, `scale` multiplies its size.
+#[cfg(feature = "hydrate")]
+fn adjust_chart_zoom(pan: f64, scale: f64) {
+    let js = format!(
+        r#"
+        (function() {{
+            var chart = window.__usdfc_echarts;
+            if (!chart || chart.isDisposed()) return;
+            var dz = chart.getOption().dataZoom;
+            if (!dz || !dz.length) return;
+            var start = dz[0].start || 0;
+            var end = dz[0].end === undefined ? 100 : dz[0].end;
+            var half = Math.min(50, Math.max(1, (end - start) / 2 * {scale}));
+            var mid = (start + end) / 2 + (end - start) * {pan};
+            mid = Math.min(100 - half, Math.max(half, mid));
+            chart.dispatchAction({{ type: 'dataZoom', start: mid - half, end: mid + half }});
+        }})()
+        "#,
+        pan = pan,
+        scale = scale,
+    );
+    let _ = js_sys::eval(&js);
 }
 
 #[component]
@@ -181,7 +157,13 @@ pub fn AdvancedAnalytics() -> impl IntoView {
     let resolution = create_rw_signal(ChartResolution::H1);
     let lookback = create_rw_signal(ChartLookback::Week1);
     let chart_type = create_rw_signal(ChartType::Area);
+    // Percent-of-range mode: every metric on one shared 0-100 axis
+    let normalized = create_rw_signal(false);
     let wallet_address = create_rw_signal(None::<String>);
+    #[allow(unused_variables)]
+    let theme_mode = use_context::<crate::app::AppState>()
+        .expect("AppState must be provided")
+        .theme_mode;
 
     // Custom date range state
     let (custom_start, set_custom_start) = create_signal(None::<i64>);  // Unix timestamp
@@ -212,7 +194,7 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                 return;
             }
 
-            let url_state = ChartUrlState::from_url();
+            let url_state = chart_state_from_url();
 
             // Apply URL state to signals if present
             if !url_state.metrics.is_empty() {
@@ -269,7 +251,7 @@ pub fn AdvancedAnalytics() -> impl IntoView {
     let chart_resource = create_local_resource(
         move || (resolution.get(), lookback.get(), custom_start.get(), custom_end.get()),
         move |(res, lb, start, end)| async move {
-            get_advanced_chart_data(res, lb, start, end).await
+            get_advanced_chart_data(res, lb, start, end, None).await
         }
     );
 
@@ -277,6 +259,12 @@ pub fn AdvancedAnalytics() -> impl IntoView {
     create_effect(move |_| {
         match chart_resource.get() {
             Some(Ok(data)) => {
+                // The server shortens lookbacks its configured limits can't cover
+                // (e.g. a shared res=1m&lookback=all URL); follow its choice
+                let custom_range = custom_start.get_untracked().is_some() && custom_end.get_untracked().is_some();
+                if data.lookback != lookback.get_untracked() && !custom_range {
+                    lookback.set(data.lookback);
+                }
                 chart_data.set(data);
                 is_loading.set(false);
             }
@@ -300,129 +288,16 @@ pub fn AdvancedAnalytics() -> impl IntoView {
             let loading = is_loading.get();
             let ct = chart_type.get();
             let metrics = visible_metrics.get();
+            let normalize = normalized.get();
+            // Re-render with the matching ECharts theme when the app theme changes
+            let echarts_theme = theme_mode.get().echarts_theme();
 
             // Only initialize when we have data and not loading
             if !loading && !data.price_candles.is_empty() {
-                // Build chart configuration based on chart type and visible metrics
-                let candles = &data.price_candles;
-
-                // Determine series type based on chart_type
-                let (series_type, show_area) = match ct {
-                    ChartType::Candle => ("candlestick", false),
-                    ChartType::Line => ("line", false),
-                    ChartType::Area | ChartType::Bar => ("line", true),
-                };
-
-                // Build price data array
-                let price_data_json: String = if series_type == "candlestick" {
-                    // Candlestick: [timestamp, open, close, low, high]
-                    candles.iter()
-                        .map(|c| format!("[{},{},{},{},{}]", c.time * 1000, c.open, c.close, c.low, c.high))
-                        .collect::<Vec<_>>()
-                        .join(",")
-                } else {
-                    // Line/Area: [timestamp, close]
-                    candles.iter()
-                        .map(|c| format!("[{},{}]", c.time * 1000, c.close))
-                        .collect::<Vec<_>>()
-                        .join(",")
-                };
-                let price_data_json = format!("[{}]", price_data_json);
-
-                // Build volume data array
-                let volume_data_json: String = candles.iter()
-                    .map(|c| {
-                        let color = if c.close >= c.open {
-                            "rgba(139, 92, 246, 0.7)"
-                        } else {
-                            "rgba(139, 92, 246, 0.4)"
-                        };
-                        format!(r#"{{"value":[{},{}],"itemStyle":{{"color":"{}"}}}}"#, c.time * 1000, c.volume, color)
-                    })
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let volume_data_json = format!("[{}]", volume_data_json);
-
-                // Build data arrays for historical metrics (from MetricSnapshot)
-                let liquidity_data_json: String = data.liquidity_data.iter()
-                    .map(|(ts, v)| format!("[{},{}]", ts * 1000, v))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let liquidity_data_json = format!("[{}]", liquidity_data_json);
-
-                let tcr_data_json: String = data.tcr_data.iter()
-                    .map(|(ts, v)| format!("[{},{}]", ts * 1000, v))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let tcr_data_json = format!("[{}]", tcr_data_json);
-
-                let supply_data_json: String = data.supply_data.iter()
-                    .map(|(ts, v)| format!("[{},{}]", ts * 1000, v))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let supply_data_json = format!("[{}]", supply_data_json);
-
-                let holders_data_json: String = data.holders_data.iter()
-                    .map(|(ts, v)| format!("[{},{}]", ts * 1000, v))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let holders_data_json = format!("[{}]", holders_data_json);
-
-                let lend_apr_data_json: String = data.lend_apr_data.iter()
-                    .map(|(ts, v)| format!("[{},{}]", ts * 1000, v))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let lend_apr_data_json = format!("[{}]", lend_apr_data_json);
-
-                let borrow_apr_data_json: String = data.borrow_apr_data.iter()
-                    .map(|(ts, v)| format!("[{},{}]", ts * 1000, v))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let borrow_apr_data_json = format!("[{}]", borrow_apr_data_json);
-
-                let transfers_data_json: String = data.transfers_data.iter()
-                    .map(|(ts, v)| format!("[{},{}]", ts * 1000, v))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                let transfers_data_json = format!("[{}]", transfers_data_json);
-
-                // Build series configuration
-                let show_price = metrics.contains(&ChartMetric::Price);
-                let show_volume = metrics.contains(&ChartMetric::Volume);
-                let show_liquidity = metrics.contains(&ChartMetric::Liquidity);
-                let show_tcr = metrics.contains(&ChartMetric::TCR);
-                let show_supply = metrics.contains(&ChartMetric::Supply);
-                let show_holders = metrics.contains(&ChartMetric::Holders);
-                let show_lend_apr = metrics.contains(&ChartMetric::LendAPR);
-                let show_borrow_apr = metrics.contains(&ChartMetric::BorrowAPR);
-                let show_transfers = metrics.contains(&ChartMetric::Transfers);
-
-                let area_style = if show_area {
-                    r#"areaStyle: {
-                        color: new echarts.graphic.LinearGradient(0, 0, 0, 1, [
-                            { offset: 0, color: 'rgba(0, 212, 255, 0.4)' },
-                            { offset: 1, color: 'rgba(0, 212, 255, 0.02)' }
-                        ])
-                    },"#
-                } else {
-                    ""
-                };
-
-                let candlestick_style = if series_type == "candlestick" {
-                    r#"itemStyle: {
-                        color: '#22c55e',
-                        color0: '#ef4444',
-                        borderColor: '#22c55e',
-                        borderColor0: '#ef4444'
-                    },"#
-                } else {
-                    r#"smooth: true,
-                    showSymbol: false,
-                    lineStyle: { width: 2, color: '#00d4ff' },
-                    itemStyle: { color: '#00d4ff' },"#
-                };
+                let option = build_chart_option(&data, &metrics, ct, normalize);
 
-                // Build the full ECharts configuration
+                // Formatters are JS functions, so they are attached to the
+                // option here rather than built in Rust
                 let js_code = format!(r#"
                     (function() {{
                         var el = document.getElementById('advanced-chart');
@@ -434,20 +309,12 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                             existingChart.dispose();
                         }}
 
-                        var chart = echarts.init(el, 'dark');
+                        var chart = echarts.init(el, {echarts_theme});
+                        var option = {option};
 
                         // Store price data for % change calculations
-                        var priceData = {price_data};
-                        var volumeData = {volume_data};
-
-                        // Historical metric data from snapshots
-                        var liquidityData = {liquidity_data};
-                        var tcrData = {tcr_data};
-                        var supplyData = {supply_data};
-                        var holdersData = {holders_data};
-                        var lendAprData = {lend_apr_data};
-                        var borrowAprData = {borrow_apr_data};
-                        var transfersData = {transfers_data};
+                        var priceSeries = option.series.filter(function(s) {{ return s.name === 'Price'; }})[0];
+                        var priceData = priceSeries ? priceSeries.data : [];
 
                         // Track visible range start value (updated on dataZoom)
                         var visibleStartIndex = 0;
@@ -465,14 +332,19 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                                 return dataPoint[2]; // close price for candlestick
                             }}
                             if (Array.isArray(dataPoint)) {{
-                                return dataPoint[1];
+                                return dataPoint.length === 3 ? dataPoint[2] : dataPoint[1];
                             }}
                             if (dataPoint.value) {{
-                                return dataPoint.value[1];
+                                return getValue(dataPoint.value, seriesType);
                             }}
                             return dataPoint;
                         }}
 
+                        // Raw value of a tooltip item, also in normalized mode
+                        function rawValue(item) {{
+                            return getValue(item.data, item.seriesType);
+                        }}
+
                         // Helper function to format % change with color
                         function formatChange(change, label) {{
                             if (isNaN(change) || !isFinite(change)) return '';
@@ -481,482 +353,174 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                             return '<span style="color: ' + color + '; font-size: 11px;">' + label + ': ' + sign + change.toFixed(2) + '%</span>';
                         }}
 
-                        var series = [];
-
-                        // Price series
-                        if ({show_price}) {{
-                            series.push({{
-                                name: 'Price',
-                                type: '{series_type}',
-                                data: priceData,
-                                {candlestick_style}
-                                {area_style}
-                                yAxisIndex: 0
-                            }});
-                        }}
-
-                        // Volume series
-                        if ({show_volume}) {{
-                            series.push({{
-                                name: 'Volume',
-                                type: 'bar',
-                                yAxisIndex: 1,
-                                data: volumeData,
-                                barMaxWidth: 20,
-                                itemStyle: {{ color: 'rgba(139, 92, 246, 0.6)' }},
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        // Liquidity series (from historical snapshots)
-                        if ({show_liquidity}) {{
-                            series.push({{
-                                name: 'Liquidity',
-                                type: 'line',
-                                smooth: true,
-                                showSymbol: liquidityData.length <= 1,
-                                symbolSize: 8,
-                                lineStyle: {{ width: 2, color: '#06b6d4' }},
-                                itemStyle: {{ color: '#06b6d4' }},
-                                data: liquidityData,
-                                yAxisIndex: 2,
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        // TCR series (from historical snapshots)
-                        if ({show_tcr}) {{
-                            series.push({{
-                                name: 'TCR',
-                                type: 'line',
-                                smooth: true,
-                                showSymbol: tcrData.length <= 1,
-                                symbolSize: 8,
-                                lineStyle: {{ width: 2, color: '#22c55e' }},
-                                itemStyle: {{ color: '#22c55e' }},
-                                data: tcrData,
-                                yAxisIndex: 3,
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        // Supply series (from historical snapshots)
-                        if ({show_supply}) {{
-                            series.push({{
-                                name: 'Supply',
-                                type: 'line',
-                                smooth: true,
-                                showSymbol: supplyData.length <= 1,
-                                symbolSize: 8,
-                                lineStyle: {{ width: 2, color: '#f59e0b' }},
-                                itemStyle: {{ color: '#f59e0b' }},
-                                data: supplyData,
-                                yAxisIndex: 4,
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        // Holders series (from historical snapshots)
-                        if ({show_holders}) {{
-                            series.push({{
-                                name: 'Holders',
-                                type: 'line',
-                                smooth: true,
-                                showSymbol: holdersData.length <= 1,
-                                symbolSize: 8,
-                                lineStyle: {{ width: 2, color: '#ec4899' }},
-                                itemStyle: {{ color: '#ec4899' }},
-                                data: holdersData,
-                                yAxisIndex: 5,
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        // Lend APR series (from historical snapshots)
-                        if ({show_lend_apr}) {{
-                            series.push({{
-                                name: 'Lend APR',
-                                type: 'line',
-                                smooth: true,
-                                showSymbol: lendAprData.length <= 1,
-                                symbolSize: 8,
-                                lineStyle: {{ width: 2, color: '#10b981' }},
-                                itemStyle: {{ color: '#10b981' }},
-                                data: lendAprData,
-                                yAxisIndex: 6,
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        // Borrow APR series (from historical snapshots)
-                        if ({show_borrow_apr}) {{
-                            series.push({{
-                                name: 'Borrow APR',
-                                type: 'line',
-                                smooth: true,
-                                showSymbol: borrowAprData.length <= 1,
-                                symbolSize: 8,
-                                lineStyle: {{ width: 2, color: '#f97316' }},
-                                itemStyle: {{ color: '#f97316' }},
-                                data: borrowAprData,
-                                yAxisIndex: 6,  // Share with Lend APR
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        // Transfers series (from Blockscout)
-                        if ({show_transfers}) {{
-                            series.push({{
-                                name: 'Transfers',
-                                type: 'bar',
-                                barMaxWidth: 15,
-                                itemStyle: {{ color: 'rgba(99, 102, 241, 0.7)' }},
-                                data: transfersData,
-                                yAxisIndex: 7,
-                                animation: true,
-                                animationDuration: 300
-                            }});
-                        }}
-
-                        chart.setOption({{
-                            backgroundColor: 'transparent',
-                            animation: true,
-                            grid: {{
-                                left: '3%',
-                                right: '8%',
-                                top: 60,
-                                bottom: 80,
-                                containLabel: true
-                            }},
-                            tooltip: {{
-                                trigger: 'axis',
-                                axisPointer: {{
-                                    type: 'cross',
-                                    crossStyle: {{ color: '#00d4ff' }},
-                                    lineStyle: {{ type: 'dashed', color: '#00d4ff' }}
-                                }},
-                                backgroundColor: 'rgba(20, 20, 30, 0.95)',
-                                borderColor: '#00d4ff',
-                                borderWidth: 1,
-                                textStyle: {{ color: '#fff' }},
-                                formatter: function(params) {{
-                                    // If locked, show locked data instead
-                                    if (isLocked && lockedParams) {{
-                                        params = lockedParams;
-                                    }}
+                        option.tooltip.formatter = function(params) {{
+                            // If locked, show locked data instead
+                            if (isLocked && lockedParams) {{
+                                params = lockedParams;
+                            }}
 
-                                    if (!params || params.length === 0) return '';
-
-                                    var timestamp = params[0].data[0] || params[0].data.value[0];
-                                    var date = new Date(timestamp);
-                                    var dateStr = date.toLocaleDateString() + ' ' + date.toLocaleTimeString([], {{hour: '2-digit', minute:'2-digit'}});
-
-                                    // Lock indicator
-                                    var lockIndicator = isLocked ? '<div style="color: #f59e0b; font-size: 10px; margin-bottom: 4px;">LOCKED (click to unlock)</div>' : '';
-
-                                    var result = lockIndicator + '<div style="font-weight: bold; margin-bottom: 4px;">' + dateStr + '</div>';
-
-                                    params.forEach(function(item) {{
-                                        var value;
-                                        var currentValue;
-                                        var dataIndex = item.dataIndex;
-
-                                        if (item.seriesType === 'candlestick') {{
-                                            currentValue = item.data[2]; // close
-                                            value = 'O: $' + item.data[1].toFixed(4) + ' C: $' + item.data[2].toFixed(4);
-
-                                            // Calculate % changes for price
-                                            var prevValue = dataIndex > 0 ? getValue(priceData[dataIndex - 1], 'candlestick') : currentValue;
-                                            var startValue = getValue(priceData[visibleStartIndex], 'candlestick');
-
-                                            var changeFromPrev = prevValue ? ((currentValue - prevValue) / prevValue) * 100 : 0;
-                                            var changeFromStart = startValue ? ((currentValue - startValue) / startValue) * 100 : 0;
-
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                            result += '<div style="display: flex; gap: 12px; margin-left: 4px;">'
-                                                + formatChange(changeFromPrev, 'Prev')
-                                                + formatChange(changeFromStart, 'Start')
-                                                + '</div>';
-
-                                        }} else if (item.seriesName === 'Price') {{
-                                            currentValue = item.data[1] || item.data.value[1];
-                                            value = '$' + currentValue.toFixed(4);
-
-                                            // Calculate % changes for price
-                                            var prevValue = dataIndex > 0 ? getValue(priceData[dataIndex - 1], 'line') : currentValue;
-                                            var startValue = getValue(priceData[visibleStartIndex], 'line');
-
-                                            var changeFromPrev = prevValue ? ((currentValue - prevValue) / prevValue) * 100 : 0;
-                                            var changeFromStart = startValue ? ((currentValue - startValue) / startValue) * 100 : 0;
-
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                            result += '<div style="display: flex; gap: 12px; margin-left: 4px;">'
-                                                + formatChange(changeFromPrev, 'Prev')
-                                                + formatChange(changeFromStart, 'Start')
-                                                + '</div>';
-
-                                        }} else if (item.seriesName === 'Volume') {{
-                                            var vol = item.data[1] || item.data.value[1];
-                                            value = '$' + vol.toLocaleString(undefined, {{maximumFractionDigits: 0}});
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }} else if (item.seriesName === 'Liquidity') {{
-                                            var liq = item.data[1] || item.data.value[1];
-                                            if (liq >= 1000000) value = '$' + (liq/1000000).toFixed(2) + 'M';
-                                            else if (liq >= 1000) value = '$' + (liq/1000).toFixed(1) + 'K';
-                                            else value = '$' + liq.toFixed(0);
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }} else if (item.seriesName === 'TCR') {{
-                                            var tcr = item.data[1] || item.data.value[1];
-                                            value = tcr.toFixed(1) + '%';
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }} else if (item.seriesName === 'Supply') {{
-                                            var supply = item.data[1] || item.data.value[1];
-                                            if (supply >= 1000000) value = (supply/1000000).toFixed(2) + 'M';
-                                            else if (supply >= 1000) value = (supply/1000).toFixed(1) + 'K';
-                                            else value = supply.toFixed(0);
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }} else if (item.seriesName === 'Holders') {{
-                                            var holders = item.data[1] || item.data.value[1];
-                                            value = holders.toFixed(0);
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }} else if (item.seriesName === 'Lend APR' || item.seriesName === 'Borrow APR') {{
-                                            var apr = item.data[1] || item.data.value[1];
-                                            value = apr.toFixed(2) + '%';
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }} else if (item.seriesName === 'Transfers') {{
-                                            var transfers = item.data[1] || item.data.value[1];
-                                            value = transfers.toFixed(0);
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }} else {{
-                                            // Generic fallback
-                                            var val = item.data[1] || item.data.value[1];
-                                            value = val.toLocaleString();
-                                            result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
-                                                + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
-                                                + '<span style="font-weight: bold;">' + value + '</span></div>';
-                                        }}
-                                    }});
-                                    return result;
+                            if (!params || params.length === 0) return '';
+
+                            var timestamp = params[0].data[0] || params[0].data.value[0];
+                            var date = new Date(timestamp);
+                            var dateStr = date.toLocaleDateString() + ' ' + date.toLocaleTimeString([], {{hour: '2-digit', minute:'2-digit'}});
+
+                            // Lock indicator
+                            var lockIndicator = isLocked ? '<div style="color: #f59e0b; font-size: 10px; margin-bottom: 4px;">LOCKED (click to unlock)</div>' : '';
+
+                            var result = lockIndicator + '<div style="font-weight: bold; margin-bottom: 4px;">' + dateStr + '</div>';
+
+                            params.forEach(function(item) {{
+                                var value;
+                                var currentValue;
+                                var dataIndex = item.dataIndex;
+
+                                if (item.seriesType === 'candlestick') {{
+                                    currentValue = item.data[2]; // close
+                                    value = 'O: $' + item.data[1].toFixed(4) + ' C: $' + item.data[2].toFixed(4);
+
+                                    // Calculate % changes for price
+                                    var prevValue = dataIndex > 0 ? getValue(priceData[dataIndex - 1], 'candlestick') : currentValue;
+                                    var startValue = getValue(priceData[visibleStartIndex], 'candlestick');
+
+                                    var changeFromPrev = prevValue ? ((currentValue - prevValue) / prevValue) * 100 : 0;
+                                    var changeFromStart = startValue ? ((currentValue - startValue) / startValue) * 100 : 0;
+
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                    result += '<div style="display: flex; gap: 12px; margin-left: 4px;">'
+                                        + formatChange(changeFromPrev, 'Prev')
+                                        + formatChange(changeFromStart, 'Start')
+                                        + '</div>';
+
+                                }} else if (item.seriesName === 'Price') {{
+                                    currentValue = rawValue(item);
+                                    value = '$' + currentValue.toFixed(4);
+
+                                    // Calculate % changes for price
+                                    var prevValue = dataIndex > 0 ? getValue(priceData[dataIndex - 1], 'line') : currentValue;
+                                    var startValue = getValue(priceData[visibleStartIndex], 'line');
+
+                                    var changeFromPrev = prevValue ? ((currentValue - prevValue) / prevValue) * 100 : 0;
+                                    var changeFromStart = startValue ? ((currentValue - startValue) / startValue) * 100 : 0;
+
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                    result += '<div style="display: flex; gap: 12px; margin-left: 4px;">'
+                                        + formatChange(changeFromPrev, 'Prev')
+                                        + formatChange(changeFromStart, 'Start')
+                                        + '</div>';
+
+                                }} else if (item.seriesName.indexOf('SMA ') === 0 || item.seriesName.indexOf('EMA ') === 0) {{
+                                    value = '$' + rawValue(item).toFixed(4);
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else if (item.seriesName === 'Volume') {{
+                                    var vol = rawValue(item);
+                                    value = '$' + vol.toLocaleString(undefined, {{maximumFractionDigits: 0}});
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else if (item.seriesName === 'Liquidity') {{
+                                    var liq = rawValue(item);
+                                    if (liq >= 1000000) value = '$' + (liq/1000000).toFixed(2) + 'M';
+                                    else if (liq >= 1000) value = '$' + (liq/1000).toFixed(1) + 'K';
+                                    else value = '$' + liq.toFixed(0);
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else if (item.seriesName === 'TCR') {{
+                                    var tcr = rawValue(item);
+                                    value = tcr.toFixed(1) + '%';
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else if (item.seriesName === 'Supply') {{
+                                    var supply = rawValue(item);
+                                    if (supply >= 1000000) value = (supply/1000000).toFixed(2) + 'M';
+                                    else if (supply >= 1000) value = (supply/1000).toFixed(1) + 'K';
+                                    else value = supply.toFixed(0);
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else if (item.seriesName === 'Holders') {{
+                                    var holders = rawValue(item);
+                                    value = holders.toFixed(0);
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else if (item.seriesName === 'Lend APR' || item.seriesName === 'Borrow APR') {{
+                                    var apr = rawValue(item);
+                                    value = apr.toFixed(2) + '%';
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else if (item.seriesName === 'Transfers') {{
+                                    var transfers = rawValue(item);
+                                    value = transfers.toFixed(0);
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
+                                }} else {{
+                                    // Generic fallback
+                                    var val = rawValue(item);
+                                    value = val.toLocaleString();
+                                    result += '<div style="display: flex; justify-content: space-between; gap: 16px;">'
+                                        + '<span style="color: ' + item.color + ';">' + item.seriesName + '</span>'
+                                        + '<span style="font-weight: bold;">' + value + '</span></div>';
                                 }}
+                            }});
+                            return result;
+                        }};
+
+                        option.xAxis.axisLabel.formatter = function(value) {{
+                            var date = new Date(value);
+                            var now = new Date();
+                            var diffHours = (now - date) / (1000 * 60 * 60);
+
+                            // Dynamic formatting based on time range
+                            if (diffHours < 24) {{
+                                // Last 24 hours: show time
+                                return date.toLocaleTimeString('en-US', {{ hour: '2-digit', minute: '2-digit', hour12: false }});
+                            }} else if (diffHours < 168) {{
+                                // Last week: show day and time
+                                return date.toLocaleDateString('en-US', {{ month: 'short', day: 'numeric' }})
+                                    + ' ' + date.toLocaleTimeString('en-US', {{ hour: '2-digit', hour12: false }});
+                            }} else {{
+                                // Longer: show date only
+                                return date.toLocaleDateString('en-US', {{ month: 'short', day: 'numeric' }});
+                            }}
+                        }};
+
+                        // Value axis labels, by axis name
+                        var axisFormatters = {{
+                            '% of range': function(v) {{ return v + '%'; }},
+                            'Price': function(v) {{ return '$' + v.toFixed(4); }},
+                            'Liquidity': function(v) {{
+                                if (v >= 1000000) return '$' + (v/1000000).toFixed(1) + 'M';
+                                if (v >= 1000) return '$' + (v/1000).toFixed(0) + 'K';
+                                return '$' + v.toFixed(0);
                             }},
-                            toolbox: {{
-                                show: true,
-                                right: 10,
-                                top: 10,
-                                feature: {{
-                                    saveAsImage: {{
-                                        show: true,
-                                        title: 'Export PNG',
-                                        pixelRatio: 2,
-                                        backgroundColor: '#0a0a0a'
-                                    }}
-                                }},
-                                iconStyle: {{ borderColor: '#00d4ff' }}
-                            }},
-                            xAxis: {{
-                                type: 'time',
-                                boundaryGap: false,
-                                axisLine: {{ lineStyle: {{ color: '#333' }} }},
-                                axisLabel: {{
-                                    color: '#888',
-                                    formatter: function(value) {{
-                                        var date = new Date(value);
-                                        var now = new Date();
-                                        var diffHours = (now - date) / (1000 * 60 * 60);
-
-                                        // Dynamic formatting based on time range
-                                        if (diffHours < 24) {{
-                                            // Last 24 hours: show time
-                                            return date.toLocaleTimeString('en-US', {{ hour: '2-digit', minute: '2-digit', hour12: false }});
-                                        }} else if (diffHours < 168) {{
-                                            // Last week: show day and time
-                                            return date.toLocaleDateString('en-US', {{ month: 'short', day: 'numeric' }})
-                                                + ' ' + date.toLocaleTimeString('en-US', {{ hour: '2-digit', hour12: false }});
-                                        }} else {{
-                                            // Longer: show date only
-                                            return date.toLocaleDateString('en-US', {{ month: 'short', day: 'numeric' }});
-                                        }}
-                                    }},
-                                    rotate: 0,
-                                    hideOverlap: true
-                                }},
-                                splitLine: {{
-                                    show: true,
-                                    lineStyle: {{ color: 'rgba(255,255,255,0.05)' }}
-                                }}
+                            'TCR': function(v) {{ return v.toFixed(0) + '%'; }},
+                            'Supply': function(v) {{
+                                if (v >= 1000000) return (v/1000000).toFixed(1) + 'M';
+                                if (v >= 1000) return (v/1000).toFixed(0) + 'K';
+                                return v.toFixed(0);
                             }},
-                            yAxis: [
-                                // 0: Price
-                                {{
-                                    type: 'value',
-                                    name: 'Price',
-                                    position: 'left',
-                                    scale: true,
-                                    axisLine: {{ lineStyle: {{ color: '#00d4ff' }} }},
-                                    axisLabel: {{
-                                        color: '#00d4ff',
-                                        formatter: function(v) {{ return '$' + v.toFixed(4); }}
-                                    }},
-                                    splitLine: {{ lineStyle: {{ color: 'rgba(255,255,255,0.05)' }} }}
-                                }},
-                                // 1: Volume
-                                {{
-                                    type: 'value',
-                                    name: 'Volume',
-                                    position: 'right',
-                                    scale: true,
-                                    axisLine: {{ lineStyle: {{ color: '#8b5cf6' }}, show: false }},
-                                    axisLabel: {{ show: false }},
-                                    splitLine: {{ show: false }}
-                                }},
-                                // 2: Liquidity (USD)
-                                {{
-                                    type: 'value',
-                                    name: 'Liquidity',
-                                    position: 'right',
-                                    offset: 0,
-                                    scale: true,
-                                    show: {show_liquidity},
-                                    axisLine: {{ lineStyle: {{ color: '#06b6d4' }} }},
-                                    axisLabel: {{
-                                        color: '#06b6d4',
-                                        formatter: function(v) {{
-                                            if (v >= 1000000) return '$' + (v/1000000).toFixed(1) + 'M';
-                                            if (v >= 1000) return '$' + (v/1000).toFixed(0) + 'K';
-                                            return '$' + v.toFixed(0);
-                                        }}
-                                    }},
-                                    splitLine: {{ show: false }}
-                                }},
-                                // 3: TCR (%)
-                                {{
-                                    type: 'value',
-                                    name: 'TCR',
-                                    position: 'right',
-                                    offset: 60,
-                                    scale: true,
-                                    show: {show_tcr},
-                                    axisLine: {{ lineStyle: {{ color: '#22c55e' }} }},
-                                    axisLabel: {{
-                                        color: '#22c55e',
-                                        formatter: function(v) {{ return v.toFixed(0) + '%'; }}
-                                    }},
-                                    splitLine: {{ show: false }}
-                                }},
-                                // 4: Supply
-                                {{
-                                    type: 'value',
-                                    name: 'Supply',
-                                    position: 'right',
-                                    offset: 120,
-                                    scale: true,
-                                    show: {show_supply},
-                                    axisLine: {{ lineStyle: {{ color: '#f59e0b' }} }},
-                                    axisLabel: {{
-                                        color: '#f59e0b',
-                                        formatter: function(v) {{
-                                            if (v >= 1000000) return (v/1000000).toFixed(1) + 'M';
-                                            if (v >= 1000) return (v/1000).toFixed(0) + 'K';
-                                            return v.toFixed(0);
-                                        }}
-                                    }},
-                                    splitLine: {{ show: false }}
-                                }},
-                                // 5: Holders
-                                {{
-                                    type: 'value',
-                                    name: 'Holders',
-                                    position: 'right',
-                                    offset: 180,
-                                    scale: true,
-                                    show: {show_holders},
-                                    axisLine: {{ lineStyle: {{ color: '#ec4899' }} }},
-                                    axisLabel: {{
-                                        color: '#ec4899',
-                                        formatter: function(v) {{ return v.toFixed(0); }}
-                                    }},
-                                    splitLine: {{ show: false }}
-                                }},
-                                // 6: APR (%) - shared by Lend & Borrow
-                                {{
-                                    type: 'value',
-                                    name: 'APR',
-                                    position: 'right',
-                                    offset: 240,
-                                    scale: true,
-                                    show: {show_lend_apr} || {show_borrow_apr},
-                                    axisLine: {{ lineStyle: {{ color: '#10b981' }} }},
-                                    axisLabel: {{
-                                        color: '#10b981',
-                                        formatter: function(v) {{ return v.toFixed(1) + '%'; }}
-                                    }},
-                                    splitLine: {{ show: false }}
-                                }},
-                                // 7: Transfers (count)
-                                {{
-                                    type: 'value',
-                                    name: 'Transfers',
-                                    position: 'right',
-                                    offset: 300,
-                                    scale: true,
-                                    show: {show_transfers},
-                                    axisLine: {{ lineStyle: {{ color: '#6366f1' }} }},
-                                    axisLabel: {{
-                                        color: '#6366f1',
-                                        formatter: function(v) {{ return v.toFixed(0); }}
-                                    }},
-                                    splitLine: {{ show: false }}
-                                }}
-                            ],
-                            dataZoom: [
-                                {{
-                                    type: 'inside',
-                                    xAxisIndex: 0,
-                                    start: 0,
-                                    end: 100
-                                }},
-                                {{
-                                    type: 'slider',
-                                    xAxisIndex: 0,
-                                    start: 0,
-                                    end: 100,
-                                    bottom: 10,
-                                    height: 30,
-                                    borderColor: '#333',
-                                    backgroundColor: 'rgba(0,0,0,0.3)',
-                                    fillerColor: 'rgba(0, 212, 255, 0.2)',
-                                    handleStyle: {{ color: '#00d4ff' }},
-                                    textStyle: {{ color: '#888' }}
-                                }}
-                            ],
-                            series: series
+                            'Holders': function(v) {{ return v.toFixed(0); }},
+                            'APR': function(v) {{ return v.toFixed(1) + '%'; }},
+                            'Transfers': function(v) {{ return v.toFixed(0); }}
+                        }};
+                        option.yAxis.forEach(function(axis) {{
+                            var formatter = axisFormatters[axis.name];
+                            if (formatter) axis.axisLabel.formatter = formatter;
                         }});
 
+                        chart.setOption(option);
+
                         // Update visible range indices on dataZoom
                         chart.on('dataZoom', function(params) {{
                             var option = chart.getOption();
@@ -1040,27 +604,8 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                         window.__usdfc_echarts = chart;
                     }})()
                 "#,
-                    show_price = show_price,
-                    show_volume = show_volume,
-                    show_liquidity = show_liquidity,
-                    show_tcr = show_tcr,
-                    show_supply = show_supply,
-                    show_holders = show_holders,
-                    show_lend_apr = show_lend_apr,
-                    show_borrow_apr = show_borrow_apr,
-                    show_transfers = show_transfers,
-                    series_type = series_type,
-                    price_data = price_data_json,
-                    volume_data = volume_data_json,
-                    liquidity_data = liquidity_data_json,
-                    tcr_data = tcr_data_json,
-                    supply_data = supply_data_json,
-                    holders_data = holders_data_json,
-                    lend_apr_data = lend_apr_data_json,
-                    borrow_apr_data = borrow_apr_data_json,
-                    transfers_data = transfers_data_json,
-                    candlestick_style = candlestick_style,
-                    area_style = area_style,
+                    option = option,
+                    echarts_theme = echarts_theme,
                 );
 
                 // Use setTimeout(0) to defer chart init until after DOM updates
@@ -1091,7 +636,7 @@ pub fn AdvancedAnalytics() -> impl IntoView {
     // Data sources for stats (client-only to avoid hydration mismatch)
     let protocol = create_local_resource(|| (), |_| async move { get_protocol_metrics().await });
     let price = create_local_resource(|| (), |_| async move { get_usdfc_price_data().await });
-    let transactions = create_local_resource(|| (), |_| async move { get_recent_transactions(Some(50)).await });
+    let transactions = create_local_resource(|| (), |_| async move { get_recent_transactions(Some(50), None).await });
     let lending = create_local_resource(|| (), |_| async move { get_lending_markets().await });
     let holders = create_local_resource(|| (), |_| async move { get_holder_count().await });
     let health = create_local_resource(|| (), |_| async move { check_api_health().await });
@@ -1107,8 +652,69 @@ pub fn AdvancedAnalytics() -> impl IntoView {
         });
     };
 
+    // Keyboard shortcut help overlay
+    let show_shortcuts = create_rw_signal(false);
+
+    // Keyboard shortcuts (client-side only)
+    #[cfg(feature = "hydrate")]
+    {
+        let handle = window_event_listener(ev::keydown, move |ev| {
+            if ev.ctrl_key() || ev.meta_key() || ev.alt_key() || is_typing_target(&ev) {
+                return;
+            }
+
+            match ev.key().as_str() {
+                "ArrowLeft" => adjust_chart_zoom(-0.25, 1.0),
+                "ArrowRight" => adjust_chart_zoom(0.25, 1.0),
+                "+" | "=" => adjust_chart_zoom(0.0, 0.8),
+                "-" | "_" => adjust_chart_zoom(0.0, 1.25),
+                "a" => chart_type.set(ChartType::Area),
+                "l" => chart_type.set(ChartType::Line),
+                "c" => chart_type.set(ChartType::Candle),
+                "?" => show_shortcuts.update(|v| *v = !*v),
+                "Escape" => show_shortcuts.set(false),
+                key => {
+                    let metric = key
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| n.checked_sub(1))
+                        .and_then(|i| ChartMetric::all().get(i));
+                    match metric {
+                        Some(metric) => toggle_metric(*metric),
+                        None => return,
+                    }
+                }
+            }
+            ev.prevent_default();
+        });
+
+        on_cleanup(move || handle.remove());
+    }
+
     view! {
         <div class="lz-page">
+            // Keyboard shortcut help
+            <Show when=move || show_shortcuts.get()>
+                <div class="address-modal-backdrop" on:click=move |_| show_shortcuts.set(false)>
+                    <div class="address-modal-content shortcut-panel" on:click=|e| e.stop_propagation()>
+                        <div class="address-modal-header">
+                            <h2>"Keyboard Shortcuts"</h2>
+                            <button class="btn btn-secondary" on:click=move |_| show_shortcuts.set(false)>"×"</button>
+                        </div>
+                        <table class="table">
+                            <tbody>
+                                {CHART_SHORTCUTS.iter().map(|(keys, action)| view! {
+                                    <tr>
+                                        <td><kbd class="shortcut-key">{*keys}</kbd></td>
+                                        <td>{*action}</td>
+                                    </tr>
+                                }).collect_view()}
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+            </Show>
+
             // Page Header
             <div class="lz-header">
                 <div class="lz-title-section">
@@ -1116,6 +722,7 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                     <p class="lz-subtitle">"Multi-source USDFC protocol data"</p>
                 </div>
                 <div class="lz-controls">
+                    <LiveIndicator updated_at=Signal::derive(move || Some(chart_data.get().generated_at).filter(|&t| t > 0)) />
                     // Wallet search
                     <div class="address-search">
                         <input
@@ -1189,6 +796,14 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                             <svg viewBox="0 0 20 20" fill="currentColor"><rect x="3" y="6" width="3" height="8"/><line x1="4.5" y1="3" x2="4.5" y2="14" stroke="currentColor" stroke-width="1"/><rect x="9" y="8" width="3" height="6"/><line x1="10.5" y1="5" x2="10.5" y2="16" stroke="currentColor" stroke-width="1"/><rect x="15" y="4" width="3" height="10"/><line x1="16.5" y1="2" x2="16.5" y2="16" stroke="currentColor" stroke-width="1"/></svg>
                         </button>
 
+                        <button
+                            class="lz-type-btn"
+                            title="Keyboard shortcuts (?)"
+                            on:click=move |_| show_shortcuts.set(true)
+                        >
+                            "?"
+                        </button>
+
                         // Share button
                         <button
                             class="lz-share-btn"
@@ -1204,7 +819,7 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                                         custom_start.get(),
                                         custom_end.get(),
                                     );
-                                    let url = get_share_url(&query);
+                                    let url = get_share_url("/advanced", &query);
                                     if copy_to_clipboard(&url) {
                                         set_toast_message.set("Link copied!".to_string());
                                         set_show_toast.set(true);
@@ -1223,6 +838,55 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                             </svg>
                             <span>"Share"</span>
                         </button>
+
+                        // Static image link: same parameters, rendered server-side
+                        <button
+                            class="lz-share-btn"
+                            title="Copy link to a static SVG image of this chart"
+                            on:click=move |_| {
+                                #[cfg(feature = "hydrate")]
+                                {
+                                    let query = ChartUrlState::to_query_string(
+                                        &visible_metrics.get(),
+                                        resolution.get(),
+                                        lookback.get(),
+                                        chart_type.get(),
+                                        custom_start.get(),
+                                        custom_end.get(),
+                                    );
+                                    let url = get_share_url("/api/v1/chart.svg", &query);
+                                    if copy_to_clipboard(&url) {
+                                        set_toast_message.set("Image link copied!".to_string());
+                                        set_show_toast.set(true);
+                                        let set_show = set_show_toast;
+                                        wasm_bindgen_futures::spawn_local(async move {
+                                            gloo_timers::future::TimeoutFuture::new(2000).await;
+                                            set_show.set(false);
+                                        });
+                                    }
+                                }
+                            }
+                        >
+                            <span>"Image"</span>
+                        </button>
+
+                        // Raw numbers behind the chart, as loaded
+                        <button
+                            class="lz-share-btn"
+                            title="Download the chart dataset as JSON"
+                            disabled=move || is_loading.get()
+                            on:click=move |_| {
+                                #[cfg(feature = "hydrate")]
+                                {
+                                    let data = chart_data.get_untracked();
+                                    if let Ok(json) = serde_json::to_string_pretty(&data) {
+                                        download_json(&data.export_filename(), &json);
+                                    }
+                                }
+                            }
+                        >
+                            <span>"JSON"</span>
+                        </button>
                     </div>
                 </div>
 
@@ -1235,26 +899,33 @@ pub fn AdvancedAnalytics() -> impl IntoView {
 
                     <Show when=move || !is_loading.get()>
                         // ECharts container
-                        <div id="advanced-chart" style="width: 100%; height: 400px;"></div>
+                        <div id="advanced-chart" class="advanced-chart-canvas"></div>
                     </Show>
                 </div>
 
                 // Chart Footer with controls
                 <div class="lz-chart-footer">
-                    // WARNING BANNER: Show when lookback exceeds safe limit
+                    // WARNING BANNER: Show when a custom range exceeds the safe limit
+                    // (preset lookbacks are kept safe by validate_combo)
                     <Show when=move || {
-                        !resolution.get().is_lookback_safe(lookback.get().minutes())
+                        match (custom_start.get(), custom_end.get()) {
+                            (Some(start), Some(end)) => {
+                                let span_mins = (end.saturating_sub(start) / 60).max(0) as u32;
+                                span_mins > resolution.get().max_lookback_mins(&chart_data.get().lookback_overrides)
+                            }
+                            _ => false,
+                        }
                     }>
                         <div class="api-limit-warning">
                             <span class="warning-icon">"⚠"</span>
                             <span class="warning-text">
                                 "Data limited to last "
-                                {resolution.get().safe_lookback_description()}
+                                {move || resolution.get().lookback_description(&chart_data.get().lookback_overrides)}
                                 " due to API constraints. Consider using "
                                 {move || {
                                     // Suggest better resolution
                                     match resolution.get() {
-                                        ChartResolution::M1 | ChartResolution::M5 => "15-minute or hourly",
+                                        ChartResolution::M1 | ChartResolution::M3 | ChartResolution::M5 => "15-minute or hourly",
                                         ChartResolution::M15 | ChartResolution::M30 => "hourly",
                                         _ => "daily"
                                     }
@@ -1266,20 +937,18 @@ pub fn AdvancedAnalytics() -> impl IntoView {
 
                     // INFO BANNER: Show when historical snapshots are still building
                     <Show when=move || {
-                        match chart_data.get() {
-                            ChartDataResponse { snapshot_count, .. } if snapshot_count < 10 => true,
-                            _ => false
-                        }
+                        chart_data.get().minutes_until_full_charts().is_some()
                     }>
                         <div class="snapshot-info-banner">
                             <span class="info-icon">"ℹ️"</span>
                             <span class="info-text">
                                 {move || {
-                                    let count = chart_data.get().snapshot_count;
-                                    if count == 0 {
-                                        "Historical data collecting... Charts will show full trends in a few minutes (currently showing latest values only)".to_string()
+                                    let data = chart_data.get();
+                                    let minutes = data.minutes_until_full_charts().unwrap_or(0);
+                                    if data.snapshot_count == 0 {
+                                        format!("Historical data collecting... Charts will show full trends in ~{} minutes (currently showing latest values only)", minutes)
                                     } else {
-                                        format!("Building historical data: {} snapshots collected. Full charts available in ~{} minutes.", count, 10 - count)
+                                        format!("Building historical data: {} snapshots collected. Full charts available in ~{} minutes.", data.snapshot_count, minutes)
                                     }
                                 }}
                             </span>
@@ -1299,6 +968,11 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                                             // Only update if different from current selection
                                             if resolution.get() != r {
                                                 resolution.set(r);
+                                                // Shorten the lookback if the new resolution can't cover it
+                                                let combo = validate_combo(r, lookback.get_untracked(), &chart_data.get_untracked().lookback_overrides);
+                                                if combo.reason.is_some() {
+                                                    lookback.set(combo.lookback);
+                                                }
                                             }
                                         }
                                     >
@@ -1332,9 +1006,19 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                                 key=|lb| lb.label()
                                 children=move |lb| {
                                     let is_active = move || lookback.get() == lb && !is_custom_range_active();
+                                    let is_unsafe = move || {
+                                        validate_combo(resolution.get(), lb, &chart_data.get().lookback_overrides).reason.is_some()
+                                    };
                                     view! {
                                         <button
                                             class=move || if is_active() { "lb-btn active" } else { "lb-btn" }
+                                            disabled=is_unsafe
+                                            title=move || if is_unsafe() {
+                                                let overrides = chart_data.get().lookback_overrides;
+                                                format!("{} candles cover at most {}", resolution.get().label(), resolution.get().lookback_description(&overrides))
+                                            } else {
+                                                String::new()
+                                            }
                                             on:click=move |_| {
                                                 // Only update if different from current selection
                                                 if lookback.get() != lb || is_custom_range_active() {
@@ -1407,6 +1091,14 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                             </button>
                         }
                     }).collect_view()}
+                    <button
+                        class="legend-chip legend-normalize"
+                        class:active=move || normalized.get()
+                        title="Rescale each metric to 0-100% of its range on one shared axis"
+                        on:click=move |_| normalized.update(|n| *n = !*n)
+                    >
+                        <span class="legend-text">"Normalize"</span>
+                    </button>
                 </div>
             </div>
 
@@ -1487,9 +1179,9 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                 // TCR Card
                 <Suspense fallback=move || view! { <LzStatLoading label="System TCR" /> }>
                     {move || {
-                        protocol.get().map(|res| match res {
-                            Ok(m) => {
-                                let tcr = decimal_to_f64(m.tcr);
+                        protocol.get().map(|res| match res.map(|m| m.tcr) {
+                            Ok(Some(tcr)) => {
+                                let tcr = decimal_to_f64(tcr);
                                 let status = if tcr < 125.0 { "critical" } else if tcr < config().tcr_danger_threshold { "warning" } else { "healthy" };
                                 view! {
                                     <div class="lz-stat-card">
@@ -1504,7 +1196,8 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                                     </div>
                                 }.into_view()
                             }
-                            Err(_) => view! { <LzStatError label="System TCR" /> }.into_view()
+                            // Failed outright, or the TCR read alone failed
+                            _ => view! { <LzStatError label="System TCR" /> }.into_view()
                         })
                     }}
                 </Suspense>
@@ -1512,8 +1205,8 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                 // Supply Card
                 <Suspense fallback=move || view! { <LzStatLoading label="Total Supply" /> }>
                     {move || {
-                        protocol.get().map(|res| match res {
-                            Ok(m) => view! {
+                        protocol.get().map(|res| match res.map(|m| m.total_supply) {
+                            Ok(Some(total_supply)) => view! {
                                 <div class="lz-stat-card">
                                     <div class="lz-stat-header">
                                         <span class="lz-stat-icon">"S"</span>
@@ -1521,11 +1214,11 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                                     </div>
                                     <div class="lz-stat-label">"Total Supply"</div>
                                     <div class="lz-stat-row">
-                                        <span class="lz-stat-value">{format_volume(decimal_to_f64(m.total_supply))}</span>
+                                        <span class="lz-stat-value">{format_volume(decimal_to_f64(total_supply))}</span>
                                     </div>
                                 </div>
                             }.into_view(),
-                            Err(_) => view! { <LzStatError label="Total Supply" /> }.into_view()
+                            _ => view! { <LzStatError label="Total Supply" /> }.into_view()
                         })
                     }}
                 </Suspense>
@@ -1606,9 +1299,9 @@ pub fn AdvancedAnalytics() -> impl IntoView {
                             Ok(h) => view! {
                                 <div class="lz-sources-grid">
                                     <LzSource name="Filecoin RPC" endpoint="api.node.glif.io" connected=h.rpc_ok />
-                                    <LzSource name="Blockscout API" endpoint="filecoin.blockscout.com" connected=h.blockscout_ok />
-                                    <LzSource name="Secured Finance" endpoint="api.goldsky.com" connected=h.subgraph_ok />
-                                    <LzSource name="GeckoTerminal" endpoint="api.geckoterminal.com" connected=h.gecko_ok />
+                                    <LzSource name="Blockscout API" endpoint="filecoin.blockscout.com" connected=h.blockscout_ok disabled=h.is_disabled("blockscout") />
+                                    <LzSource name="Secured Finance" endpoint="api.goldsky.com" connected=h.subgraph_ok disabled=h.is_disabled("subgraph") />
+                                    <LzSource name="GeckoTerminal" endpoint="api.geckoterminal.com" connected=h.gecko_ok disabled=h.is_disabled("gecko") />
                                     <LzSource name="History DB" endpoint="metrics_history.db" connected=h.database_ok />
                                 </div>
                             }.into_view(),
@@ -1700,16 +1393,29 @@ fn LzStatError(label: &'static str) -> impl IntoView {
 }
 
 #[component]
-fn LzSource(name: &'static str, endpoint: &'static str, connected: bool) -> impl IntoView {
+fn LzSource(
+    name: &'static str,
+    endpoint: &'static str,
+    connected: bool,
+    #[prop(optional)] disabled: bool,
+) -> impl IntoView {
+    let (state, label) = if disabled {
+        ("disabled", "Disabled")
+    } else if connected {
+        ("online", "Connected")
+    } else {
+        ("offline", "Offline")
+    };
+
     view! {
         <div class="lz-source-card">
-            <div class=if connected { "lz-source-dot online" } else { "lz-source-dot offline" }></div>
+            <div class=format!("lz-source-dot {}", state)></div>
             <div class="lz-source-info">
                 <div class="lz-source-name">{name}</div>
                 <div class="lz-source-endpoint">{endpoint}</div>
             </div>
-            <div class=if connected { "lz-source-status online" } else { "lz-source-status offline" }>
-                {if connected { "Connected" } else { "Offline" }}
+            <div class=format!("lz-source-status {}", state)>
+                {label}
             </div>
         </div>
     }
@@ -1,6 +1,22 @@
 use leptos::*;
-use usdfc_api::{get_protocol_metrics, get_recent_transactions};
-use usdfc_core::format::{format_amount, shorten_hash};
+use crate::components::{DataLoadError, EmptyState, Pagination};
+use usdfc_api::{acknowledge_alert, get_alert_events, get_depeg_status, get_protocol_metrics, get_recent_transactions};
+use usdfc_core::format::{format_amount, format_timestamp_full, shorten_hash};
+use usdfc_core::types::{AlertCondition, AlertEventStatus, DepegSeverity};
+
+/// Alert history rows per page
+const HISTORY_PAGE_SIZE: u32 = 20;
+
+/// Default alert history window (7 days)
+const DEFAULT_HISTORY_WINDOW: Option<i64> = Some(7 * 86_400);
+
+/// Time filters for the alert history: (label, window in seconds)
+const HISTORY_WINDOWS: [(&str, Option<i64>); 4] = [
+    ("24h", Some(86_400)),
+    ("7d", DEFAULT_HISTORY_WINDOW),
+    ("30d", Some(30 * 86_400)),
+    ("All", None),
+];
 
 #[component]
 pub fn Alerts() -> impl IntoView {
@@ -10,7 +26,11 @@ pub fn Alerts() -> impl IntoView {
     );
     let transactions: leptos::Resource<(), Result<Vec<usdfc_core::types::Transaction>, leptos::ServerFnError>> = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(25)).await }
+        |_| async move { get_recent_transactions(Some(25), None).await }
+    );
+    let depeg = create_resource(
+        || (),
+        |_| async move { get_depeg_status().await }
     );
 
     view! {
@@ -31,6 +51,7 @@ pub fn Alerts() -> impl IntoView {
                         on:click=move |_| {
                             metrics.refetch();
                             transactions.refetch();
+                            depeg.refetch();
                         }
                     >
                         "Refresh"
@@ -40,8 +61,34 @@ pub fn Alerts() -> impl IntoView {
                     <Suspense fallback=move || view! { <div style="text-align: center; padding: 20px;">"Loading alerts..."</div> }>
                         {move || {
                             let mut alerts: Vec<View> = Vec::new();
-                            if let Some(Ok(m)) = metrics.get() {
-                                let tcr_f64: f64 = m.tcr.to_string().parse().unwrap_or(0.0);
+                            if let Some(Ok(status)) = depeg.get() {
+                                let peg_desc = |d: f64| format!("USDFC is {:.2}% off its ${:.2} peg.", d, status.peg_target);
+                                let alert = match (status.severity, status.deviation_pct) {
+                                    (DepegSeverity::Ok, _) => None,
+                                    (DepegSeverity::Critical, None) => Some((
+                                        "alert-card danger",
+                                        "USDFC Price Unavailable",
+                                        "No USDFC price is available, so a depeg cannot be ruled out.".to_string(),
+                                    )),
+                                    (DepegSeverity::Critical, Some(d)) => Some(("alert-card danger", "USDFC Depeg", peg_desc(d))),
+                                    (DepegSeverity::Warning, d) => Some(("alert-card warning", "Peg Deviation", peg_desc(d.unwrap_or(0.0)))),
+                                };
+                                if let Some((class, title, desc)) = alert {
+                                    let price = status.price_usd.map(|p| format!("${:.4}", p)).unwrap_or_else(|| "N/A".to_string());
+                                    alerts.push(view! {
+                                        <div class=class>
+                                            <div class="alert-icon">"!"</div>
+                                            <div class="alert-content">
+                                                <div class="alert-title">{title}</div>
+                                                <div class="alert-desc">{desc}</div>
+                                            </div>
+                                            <div class="alert-time">{price}</div>
+                                        </div>
+                                    }.into_view());
+                                }
+                            }
+                            if let Some(tcr) = metrics.get().and_then(|r| r.ok()).and_then(|m| m.tcr) {
+                                let tcr_f64: f64 = tcr.to_string().parse().unwrap_or(0.0);
                                 if tcr_f64 < 125.0 {
                                     alerts.push(view! {
                                         <div class="alert-card danger">
@@ -111,7 +158,161 @@ pub fn Alerts() -> impl IntoView {
                     </Suspense>
                 </div>
             </div>
+
+            <AlertHistory />
         </div>
     }
 }
 
+/// Recorded alert events with filters, pagination and review actions
+#[component]
+pub fn AlertHistory() -> impl IntoView {
+    let (kind, set_kind) = create_signal(None::<AlertCondition>);
+    let (window, set_window) = create_signal(DEFAULT_HISTORY_WINDOW);
+    let (page, set_page) = create_signal(1u32);
+    let (total_pages, set_total_pages) = create_signal(1u32);
+    let (token, set_token) = create_signal(String::new());
+
+    let review = create_action(|(id, status, token): &(i64, AlertEventStatus, String)| {
+        let (id, status, token) = (*id, *status, token.clone());
+        async move { acknowledge_alert(id, status, token).await }
+    });
+
+    // Refetch after every review so rows show the stored status
+    let events = create_resource(
+        move || (kind.get(), window.get(), page.get(), review.version().get()),
+        |(kind, window, page, _)| async move {
+            let since = window.map(|w| get_current_timestamp() - w);
+            let offset = page.saturating_sub(1) * HISTORY_PAGE_SIZE;
+            get_alert_events(Some(HISTORY_PAGE_SIZE), Some(offset), kind, since).await
+        },
+    );
+
+    create_effect(move |_| {
+        if let Some(Ok(history)) = events.get() {
+            let pages = (history.total as u32).div_ceil(HISTORY_PAGE_SIZE).max(1);
+            set_total_pages.set(pages);
+        }
+    });
+
+    let on_page_change = Callback::new(move |p: u32| set_page.set(p));
+
+    view! {
+        <div class="card" style="margin-top: 24px;">
+            <div class="card-header">
+                <div>
+                    <h3 class="card-title">"Alert History"</h3>
+                    <p class="card-subtitle">"Alerts fired by the metrics collector"</p>
+                </div>
+                <div style="display: flex; gap: 8px; flex-wrap: wrap;">
+                    <select
+                        class="input"
+                        style="width: auto; min-width: 180px;"
+                        on:change=move |ev| {
+                            set_kind.set(AlertCondition::parse(&event_target_value(&ev)));
+                            set_page.set(1);
+                        }
+                    >
+                        <option value="">"All kinds"</option>
+                        {AlertCondition::ALL.into_iter().map(|c| view! {
+                            <option value=c.as_str()>{c.description()}</option>
+                        }).collect_view()}
+                    </select>
+                    <select
+                        class="input"
+                        style="width: auto;"
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            if let Some((_, w)) = HISTORY_WINDOWS.iter().find(|(label, _)| *label == value) {
+                                set_window.set(*w);
+                                set_page.set(1);
+                            }
+                        }
+                    >
+                        {HISTORY_WINDOWS.into_iter().map(|(label, w)| view! {
+                            <option value=label selected=w == DEFAULT_HISTORY_WINDOW>{label}</option>
+                        }).collect_view()}
+                    </select>
+                    <input
+                        class="input"
+                        style="width: 160px;"
+                        type="password"
+                        placeholder="Alerts token"
+                        prop:value=token
+                        on:input=move |ev| set_token.set(event_target_value(&ev))
+                    />
+                </div>
+            </div>
+            {move || review.value().get().and_then(|res| res.err()).map(|e| view! {
+                <div style="margin-bottom: 12px; font-size: 12px; color: var(--accent-red);">{e.to_string()}</div>
+            })}
+            <div class="alert-history">
+                <Suspense fallback=move || view! { <div style="text-align: center; padding: 20px;">"Loading alert history..."</div> }>
+                    {move || events.get().map(|res| match res {
+                        Ok(history) if history.events.is_empty() => view! {
+                            <EmptyState title="No alerts recorded" description="No alerts fired for these filters." />
+                        }.into_view(),
+                        Ok(history) => history.events.into_iter().map(|stored| {
+                            let id = stored.id;
+                            let status = stored.status;
+                            let class = match (status, stored.event.kind) {
+                                (AlertEventStatus::Open, AlertCondition::TcrBelow | AlertCondition::StabilityPoolDrain) => "alert-card danger",
+                                (AlertEventStatus::Open, _) => "alert-card warning",
+                                _ => "alert-card reviewed",
+                            };
+                            view! {
+                                <div class=class>
+                                    <div class="alert-icon">"!"</div>
+                                    <div class="alert-content">
+                                        <div class="alert-title">
+                                            {stored.event.kind.description()}
+                                            <span class="alert-status">{status.as_str()}</span>
+                                        </div>
+                                        <div class="alert-desc">{stored.event.message}</div>
+                                    </div>
+                                    <div class="alert-time">{format_timestamp_full(stored.event.timestamp.max(0) as u64)}</div>
+                                    <Show when=move || status == AlertEventStatus::Open>
+                                        <div class="alert-actions">
+                                            <button
+                                                class="btn btn-secondary"
+                                                disabled=move || review.pending().get()
+                                                on:click=move |_| review.dispatch((id, AlertEventStatus::Acknowledged, token.get()))
+                                            >
+                                                "Acknowledge"
+                                            </button>
+                                            <button
+                                                class="btn btn-ghost"
+                                                disabled=move || review.pending().get()
+                                                on:click=move |_| review.dispatch((id, AlertEventStatus::Dismissed, token.get()))
+                                            >
+                                                "Dismiss"
+                                            </button>
+                                        </div>
+                                    </Show>
+                                </div>
+                            }
+                        }).collect_view(),
+                        Err(err) => view! {
+                            <DataLoadError error=err.to_string() on_retry=move || events.refetch() />
+                        }.into_view(),
+                    })}
+                </Suspense>
+            </div>
+            <Pagination current_page=page total_pages=total_pages on_page_change=on_page_change />
+        </div>
+    }
+}
+
+fn get_current_timestamp() -> i64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 1000.0) as i64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+}
@@ -7,65 +7,11 @@ use leptos::*;
 use crate::components::tabs::{TabNav, TabContent, Tab};
 use crate::components::icons::*;
 use usdfc_api::{get_recent_transactions, get_daily_volumes};
-use usdfc_core::format::{format_amount, format_timestamp, shorten_hash, format_volume, decimal_to_f64};
+use usdfc_core::config::config;
+use usdfc_core::format::{format_amount, format_duration_ago, format_timestamp, shorten_hash, format_volume, decimal_to_f64, explorer_address_url};
 use usdfc_core::types::Transaction;
 use std::collections::HashMap;
 
-/// Generate CSV content from transaction data
-#[allow(dead_code)]
-fn generate_csv(transactions: &[Transaction]) -> String {
-    let mut csv = String::from("Hash,Type,Amount,From,To,Timestamp,Block,Status\n");
-    for tx in transactions {
-        csv.push_str(&format!(
-            "{},{},{},{},{},{},{},{}\n",
-            tx.hash,
-            tx.tx_type.as_str(),
-            decimal_to_f64(tx.amount),
-            tx.from,
-            tx.to,
-            tx.timestamp,
-            tx.block,
-            tx.status.as_str()
-        ));
-    }
-    csv
-}
-
-/// Trigger CSV download in browser using data URL
-#[cfg(feature = "hydrate")]
-fn download_csv(filename: &str, content: &str) {
-    use wasm_bindgen::JsCast;
-
-    let window = web_sys::window().expect("no window");
-    let document = window.document().expect("no document");
-
-    // URL-encode the CSV content for data URL
-    let encoded: String = content
-        .bytes()
-        .map(|b| {
-            if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'.' || b == b'~' {
-                format!("{}", b as char)
-            } else {
-                format!("%{:02X}", b)
-            }
-        })
-        .collect();
-    let data_url = format!("data:text/csv;charset=utf-8,{}", encoded);
-
-    // Create temporary link and click it
-    let link = document.create_element("a").expect("create element failed");
-    let link: web_sys::HtmlAnchorElement = link.dyn_into().expect("cast failed");
-    link.set_href(&data_url);
-    link.set_download(filename);
-    link.click();
-}
-
-#[allow(dead_code)]
-#[cfg(not(feature = "hydrate"))]
-fn download_csv(_filename: &str, _content: &str) {
-    // No-op on server side
-}
-
 #[component]
 pub fn Analytics() -> impl IntoView {
     let active_tab = create_rw_signal("flow".to_string());
@@ -73,7 +19,7 @@ pub fn Analytics() -> impl IntoView {
     // Shared transaction data for all tabs
     let recent_tx = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(100)).await }
+        |_| async move { get_recent_transactions(Some(100), None).await }
     );
 
     let tabs = vec![
@@ -91,19 +37,11 @@ pub fn Analytics() -> impl IntoView {
                     <p class="page-subtitle">"USDFC flow and network visualizations"</p>
                 </div>
                 <div style="display: flex; gap: 8px;">
-                    <button
-                        class="btn btn-primary"
-                        on:click=move |_| {
-                            #[cfg(target_arch = "wasm32")]
-                            if let Some(Ok(txs)) = recent_tx.get() {
-                                let csv = generate_csv(&txs);
-                                download_csv("usdfc_analytics.csv", &csv);
-                            }
-                        }
-                    >
+                    // Streamed by the server, so large exports are not built in the browser
+                    <a class="btn btn-primary" href="/api/v1/transactions.csv" download="usdfc_transactions.csv" rel="external">
                         <DownloadIcon />
                         "Export CSV"
-                    </button>
+                    </a>
                     <button
                         class="btn btn-secondary"
                         on:click=move |_| recent_tx.refetch()
@@ -395,14 +333,15 @@ fn FlowTab(
                                         <div style="display: flex; flex-direction: column; gap: 8px;">
                                             {filtered.iter().take(6).map(|tx| {
                                                 let amount = format_amount(tx.amount);
-                                                let time = format_timestamp(tx.timestamp);
+                                                let time = format_duration_ago(tx.timestamp as i64, get_current_timestamp());
+                                                let full_time = format_timestamp(tx.timestamp);
                                                 view! {
                                                     <div class="stat-row" style="padding: 8px 0; border-bottom: 1px solid var(--bg-tertiary);">
                                                         <div>
                                                             <div style="font-family: monospace; font-size: 11px; color: var(--text-secondary);">
                                                                 {format!("{} → {}", shorten_hash(&tx.from), shorten_hash(&tx.to))}
                                                             </div>
-                                                            <div style="font-size: 10px; color: var(--text-muted); margin-top: 2px;">{time}</div>
+                                                            <div style="font-size: 10px; color: var(--text-muted); margin-top: 2px;" title=full_time>{time}</div>
                                                         </div>
                                                         <div style="font-family: monospace; color: var(--accent-cyan);">{amount}</div>
                                                     </div>
@@ -657,7 +596,13 @@ fn NetworkTab(
                                                 view! {
                                                     <div class="stat-row">
                                                         <span style="font-family: monospace; font-size: 11px; color: var(--text-secondary);">
-                                                            {format!("{} → {}", shorten_hash(from), shorten_hash(to))}
+                                                            <a href=explorer_address_url(&config().explorer_url, from) target="_blank" style="color: inherit; text-decoration: none;" title=from.clone()>
+                                                                {shorten_hash(from)}
+                                                            </a>
+                                                            " → "
+                                                            <a href=explorer_address_url(&config().explorer_url, to) target="_blank" style="color: inherit; text-decoration: none;" title=to.clone()>
+                                                                {shorten_hash(to)}
+                                                            </a>
                                                         </span>
                                                         <span style="font-family: monospace; font-size: 12px;">
                                                             <span style="color: var(--accent-cyan);">{format_volume(*amount)}</span>
@@ -725,7 +670,9 @@ fn VolumeTab() -> impl IntoView {
                 {move || {
                     daily_volumes.get().map(|res| {
                         match res {
-                            Ok(data) => {
+                            Ok(response) => {
+                                let complete = response.complete;
+                                let data = response.volumes;
                                 if data.is_empty() {
                                     view! {
                                         <div class="empty-state">
@@ -743,6 +690,12 @@ fn VolumeTab() -> impl IntoView {
                                     let max_vol = bars.iter().map(|(_, v)| *v).fold(0.0f64, f64::max);
 
                                     view! {
+                                        {(!complete).then(|| view! {
+                                            <div class="api-limit-warning" style="margin-bottom: 12px;">
+                                                <span class="warning-icon">"⚠"</span>
+                                                <span class="warning-text">"Partial data: some of the requested range could not be loaded from the subgraph"</span>
+                                            </div>
+                                        })}
                                         <HistoricalVolumeChart bars=bars max_volume=max_vol />
                                     }.into_view()
                                 }
@@ -871,7 +824,7 @@ fn HistoricalVolumeChart(bars: Vec<(String, f64)>, max_volume: f64) -> impl Into
                             y=y
                             width=bar_width
                             height=height.max(1)
-                            fill="#6366f1"
+                            fill="var(--viz-indigo)"
                             rx=1
                             opacity="0.8"
                         >
@@ -1183,29 +1136,29 @@ fn NetworkSVG(
 // ============================================================================
 
 fn get_bar_color(index: usize) -> &'static str {
-    if index < 6 { "#00d4ff" }
-    else if index < 12 { "#6366f1" }
-    else if index < 18 { "#a855f7" }
-    else { "#ec4899" }
+    if index < 6 { "var(--viz-cyan)" }
+    else if index < 12 { "var(--viz-indigo)" }
+    else if index < 18 { "var(--viz-purple)" }
+    else { "var(--viz-pink)" }
 }
 
 fn get_pie_color(index: usize) -> &'static str {
-    const COLORS: [&str; 6] = ["#00d4ff", "#a855f7", "#22c55e", "#f59e0b", "#ec4899", "#6366f1"];
+    const COLORS: [&str; 6] = ["var(--viz-cyan)", "var(--viz-purple)", "var(--viz-green)", "var(--viz-amber)", "var(--viz-pink)", "var(--viz-indigo)"];
     COLORS[index % COLORS.len()]
 }
 
 fn get_flow_color(index: usize) -> &'static str {
-    const COLORS: [&str; 8] = ["#00d4ff", "#a855f7", "#22c55e", "#f59e0b", "#ec4899", "#6366f1", "#14b8a6", "#f97316"];
+    const COLORS: [&str; 8] = ["var(--viz-cyan)", "var(--viz-purple)", "var(--viz-green)", "var(--viz-amber)", "var(--viz-pink)", "var(--viz-indigo)", "var(--viz-teal)", "var(--viz-orange)"];
     COLORS[index % COLORS.len()]
 }
 
 fn get_node_color(index: usize) -> &'static str {
-    const COLORS: [&str; 6] = ["#00d4ff", "#22c55e", "#f59e0b", "#a855f7", "#ec4899", "#6366f1"];
+    const COLORS: [&str; 6] = ["var(--viz-cyan)", "var(--viz-green)", "var(--viz-amber)", "var(--viz-purple)", "var(--viz-pink)", "var(--viz-indigo)"];
     COLORS[index % COLORS.len()]
 }
 
 fn get_edge_color(index: usize) -> &'static str {
-    const COLORS: [&str; 4] = ["#a855f7", "#00d4ff", "#6366f1", "#14b8a6"];
+    const COLORS: [&str; 4] = ["var(--viz-purple)", "var(--viz-cyan)", "var(--viz-indigo)", "var(--viz-teal)"];
     COLORS[index % COLORS.len()]
 }
 
@@ -1,7 +1,73 @@
 use leptos::*;
+use crate::components::error_message;
 use crate::components::gauge::GaugeChart;
 use usdfc_api::{get_protocol_metrics, get_troves};
-use usdfc_core::format::{format_value, format_fil, format_usdfc};
+use rust_decimal::Decimal;
+use usdfc_core::config::config;
+use usdfc_core::format::{format_fil, format_usd, format_usdfc, explorer_address_url};
+use usdfc_core::types::ProtocolMetrics;
+
+/// Unit collateral amounts are shown in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollateralUnit {
+    Fil,
+    Usd,
+    Both,
+}
+
+impl CollateralUnit {
+    const ALL: [CollateralUnit; 3] = [Self::Fil, Self::Usd, Self::Both];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Fil => "FIL",
+            Self::Usd => "USD",
+            Self::Both => "Both",
+        }
+    }
+
+    /// Table column header for collateral amounts
+    pub fn column_header(&self) -> &'static str {
+        match self {
+            Self::Fil => "FIL Collateral",
+            Self::Usd => "Collateral (USD)",
+            Self::Both => "Collateral",
+        }
+    }
+
+    /// Format a FIL amount, with its USD value when one is known
+    pub fn format(&self, fil: Decimal, usd: Option<Decimal>) -> String {
+        match (self, usd) {
+            (Self::Fil, _) | (Self::Both, None) => format_fil(fil),
+            (Self::Usd, Some(usd)) => format_usd(usd),
+            (Self::Usd, None) => "Price unavailable".to_string(),
+            (Self::Both, Some(usd)) => format!("{} ({})", format_fil(fil), format_usd(usd)),
+        }
+    }
+}
+
+/// Oracle FIL price implied by the metrics; None when the server had no price
+pub fn implied_fil_price(metrics: &ProtocolMetrics) -> Option<Decimal> {
+    let collateral_fil = metrics.collateral_fil.filter(|fil| !fil.is_zero())?;
+    metrics.collateral_usd.map(|usd| usd / collateral_fil)
+}
+
+/// FIL / USD / Both selector for collateral amounts
+#[component]
+pub fn CollateralUnitToggle(unit: RwSignal<CollateralUnit>) -> impl IntoView {
+    view! {
+        <div class="unit-toggle" role="group" aria-label="Collateral unit">
+            {CollateralUnit::ALL.into_iter().map(|u| view! {
+                <button
+                    class=move || if unit.get() == u { "unit-btn active" } else { "unit-btn" }
+                    on:click=move |_| unit.set(u)
+                >
+                    {u.label()}
+                </button>
+            }).collect_view()}
+        </div>
+    }
+}
 
 #[component]
 pub fn CollateralHealth() -> impl IntoView {
@@ -9,10 +75,13 @@ pub fn CollateralHealth() -> impl IntoView {
         || (),
         |_| async move { get_protocol_metrics().await }
     );
+    let unit = create_rw_signal(CollateralUnit::Fil);
+
+    let fil_price = move || metrics.get().and_then(|res| res.ok()).and_then(|m| implied_fil_price(&m));
     
     let troves = create_resource(
         || (),
-        |_| async move { get_troves(Some(20), None).await }
+        |_| async move { get_troves(Some(20), None, None, None, None).await }
     );
 
     view! {
@@ -20,6 +89,7 @@ pub fn CollateralHealth() -> impl IntoView {
             <div class="page-header">
                 <h1 class="page-title">"Collateral Health"</h1>
                 <p class="page-subtitle">"FIL collateral and trove health on Filecoin"</p>
+                <CollateralUnitToggle unit=unit />
             </div>
 
             <Suspense fallback=move || view! { <div class="card"><p>"Loading..."</p></div> }>
@@ -27,16 +97,25 @@ pub fn CollateralHealth() -> impl IntoView {
                     metrics.get().map(|res| {
                         match res {
                             Ok(m) => {
-                                let total_collateral = format_value(m.total_collateral);
-                                let tcr_f64: f64 = m.tcr.to_string().parse().unwrap_or(0.0);
-                                let tcr = format!("{:.1}%", tcr_f64);
-                                let active_troves = m.active_troves;
+                                let collateral_fil = m.collateral_fil;
+                                let collateral_usd = m.collateral_usd;
+                                let collateral_label = move || match unit.get() {
+                                    CollateralUnit::Fil => "Total FIL Collateral",
+                                    CollateralUnit::Usd => "Collateral Value (USD)",
+                                    CollateralUnit::Both => "Total Collateral",
+                                };
+                                // Values whose read failed show as unknown
+                                let tcr_f64: Option<f64> = m.tcr.map(|tcr| tcr.to_string().parse().unwrap_or(0.0));
+                                let tcr = tcr_f64.map(|tcr| format!("{:.1}%", tcr)).unwrap_or_else(|| "--".to_string());
+                                let active_troves = m.active_troves.map(|n| n.to_string()).unwrap_or_else(|| "--".to_string());
                                 
                                 view! {
                                     <div class="grid-3" style="margin-bottom: 24px;">
                                         <div class="card">
-                                            <div class="metric-label">"Total FIL Collateral"</div>
-                                            <div class="metric-value green">{total_collateral}</div>
+                                            <div class="metric-label">{collateral_label}</div>
+                                            <div class="metric-value green">
+                                                {move || collateral_fil.map(|fil| unit.get().format(fil, collateral_usd)).unwrap_or_else(|| "--".to_string())}
+                                            </div>
                                         </div>
                                         <div class="card">
                                             <div class="metric-label">"System TCR"</div>
@@ -44,20 +123,22 @@ pub fn CollateralHealth() -> impl IntoView {
                                         </div>
                                         <div class="card">
                                             <div class="metric-label">"Active Troves"</div>
-                                            <div class="metric-value purple">{active_troves.to_string()}</div>
+                                            <div class="metric-value purple">{active_troves}</div>
                                         </div>
                                     </div>
                                     
-                                    <div class="card" style="margin-bottom: 24px;">
-                                        <h3 style="margin-bottom: 16px;">"Total Collateral Ratio"</h3>
-                                        <GaugeChart value=tcr_f64 min=110.0 max=250.0 label="System TCR" suffix="%" />
-                                    </div>
+                                    {tcr_f64.map(|tcr_f64| view! {
+                                        <div class="card" style="margin-bottom: 24px;">
+                                            <h3 style="margin-bottom: 16px;">"Total Collateral Ratio"</h3>
+                                            <GaugeChart value=tcr_f64 min=110.0 max=250.0 label="System TCR" suffix="%" />
+                                        </div>
+                                    })}
                                 }.into_view()
                             }
                             Err(err) => view! {
                                 <div class="card">
                                     <div class="metric-label">"Metrics Error"</div>
-                                    <div class="metric-value red">{err.to_string()}</div>
+                                    <div class="metric-value red">{error_message(&err)}</div>
                                 </div>
                             }.into_view()
                         }
@@ -72,7 +153,7 @@ pub fn CollateralHealth() -> impl IntoView {
                         <thead>
                             <tr>
                                 <th>"Address"</th>
-                                <th>"FIL Collateral"</th>
+                                <th>{move || unit.get().column_header()}</th>
                                 <th>"USDFC Debt"</th>
                                 <th>"ICR"</th>
                                 <th>"Status"</th>
@@ -100,14 +181,18 @@ pub fn CollateralHealth() -> impl IntoView {
                                                         } else {
                                                             ("color: var(--accent-green);", "Healthy")
                                                         };
-                                                        let collateral = format_fil(t.collateral);
+                                                        let trove_collateral = t.collateral;
+                                                        let collateral = move || {
+                                                            let usd = fil_price().map(|price| trove_collateral * price);
+                                                            unit.get().format(trove_collateral, usd)
+                                                        };
                                                         let debt = format_usdfc(t.debt);
                                                         let short_addr = format!("{}...{}", &t.address[..8], &t.address[t.address.len()-6..]);
                                                         view! {
                                                             <tr>
                                                                 <td style="font-family: monospace; font-size: 12px;">
                                                                     <a
-                                                                        href=format!("https://filecoin.blockscout.com/address/{}", t.address)
+                                                                        href=explorer_address_url(&config().explorer_url, &t.address)
                                                                         target="_blank"
                                                                         style="color: var(--text-primary); text-decoration: none;"
                                                                         title=t.address.clone()
@@ -125,7 +210,7 @@ pub fn CollateralHealth() -> impl IntoView {
                                                 }
                                             }
                                             Err(err) => view! {
-                                                <tr><td colspan="5" style="text-align: center; padding: 20px; color: var(--accent-red);">{err.to_string()}</td></tr>
+                                                <tr><td colspan="5" style="text-align: center; padding: 20px; color: var(--accent-red);">{error_message(&err)}</td></tr>
                                             }.into_view()
                                         }
                                     })
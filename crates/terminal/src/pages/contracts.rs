@@ -1,4 +1,6 @@
 use leptos::*;
+use usdfc_core::config::config;
+use usdfc_core::format::explorer_address_url;
 
 #[component]
 pub fn SmartContracts() -> impl IntoView {
@@ -30,7 +32,7 @@ pub fn SmartContracts() -> impl IntoView {
                     </thead>
                     <tbody>
                         {contracts.into_iter().map(|(name, addr, desc)| {
-                            let explorer_url = format!("https://filfox.info/en/address/{}", addr);
+                            let explorer_url = explorer_address_url(&config().explorer_url, addr);
                             view! {
                                 <tr>
                                     <td style="font-weight: 600;">{name}</td>
@@ -8,13 +8,14 @@
 
 use leptos::*;
 use crate::components::controls::{TimeRange, TimeRangeSelector, ChartTypeSelector, StatusLevel};
-use crate::components::{MetricRowSkeleton, ActivityItemSkeleton, ChartSkeleton};
+use crate::components::{MetricRowSkeleton, ActivityItemSkeleton, ChartSkeleton, LiveIndicator};
+use crate::components::metric_card::data_staleness;
 use usdfc_core::config::config;
 use usdfc_api::{
     get_protocol_metrics, get_usdfc_price_data, get_daily_volumes,
     get_recent_transactions, check_api_health,
 };
-use usdfc_core::format::{format_volume, format_usd_compact, decimal_to_f64};
+use usdfc_core::format::{format_volume, format_usd_compact, decimal_to_f64, format_timestamp_full, format_duration_ago};
 
 #[component]
 pub fn Dashboard() -> impl IntoView {
@@ -52,7 +53,7 @@ pub fn Dashboard() -> impl IntoView {
                 TimeRange::Day7 => Some(100),
                 _ => Some(200),
             };
-            get_recent_transactions(limit).await
+            get_recent_transactions(limit, None).await
         }
     );
 
@@ -82,7 +83,15 @@ pub fn Dashboard() -> impl IntoView {
     let tcr_display = create_memo(move |_| {
         protocol.get()
             .and_then(|r| r.ok())
-            .map(|m| decimal_to_f64(m.tcr))
+            .and_then(|m| m.tcr)
+            .map(decimal_to_f64)
+    });
+
+    // Block height and fetch time behind the protocol metrics
+    let metrics_provenance = create_memo(move |_| {
+        protocol.get()
+            .and_then(|r| r.ok())
+            .map(|m| m.provenance)
     });
 
     let tcr_status = create_memo(move |_| {
@@ -97,12 +106,24 @@ pub fn Dashboard() -> impl IntoView {
     let source_status = create_memo(move |_| {
         health.get()
             .and_then(|r| r.ok())
-            .map(|h| vec![
-                ("RPC", if h.rpc_ok { StatusLevel::Online } else { StatusLevel::Offline }),
-                ("Blockscout", if h.blockscout_ok { StatusLevel::Online } else { StatusLevel::Offline }),
-                ("Subgraph", if h.subgraph_ok { StatusLevel::Online } else { StatusLevel::Offline }),
-                ("Gecko", StatusLevel::Online),
-            ])
+            .map(|h| {
+                // Sources disabled in config show as unknown rather than offline
+                let level = |ok: bool, source: &str| {
+                    if h.is_disabled(source) {
+                        StatusLevel::Unknown
+                    } else if ok {
+                        StatusLevel::Online
+                    } else {
+                        StatusLevel::Offline
+                    }
+                };
+                vec![
+                    ("RPC", level(h.rpc_ok, "rpc")),
+                    ("Blockscout", level(h.blockscout_ok, "blockscout")),
+                    ("Subgraph", level(h.subgraph_ok, "subgraph")),
+                    ("Gecko", level(h.gecko_ok, "gecko")),
+                ]
+            })
             .unwrap_or_else(|| vec![
                 ("RPC", StatusLevel::Unknown),
                 ("Blockscout", StatusLevel::Unknown),
@@ -111,6 +132,27 @@ pub fn Dashboard() -> impl IntoView {
             ])
     });
 
+    // Client time of the last successful metrics refresh, for the live indicator
+    let last_updated = create_rw_signal(None::<i64>);
+    create_effect(move |_| {
+        if let Some(Ok(_)) = protocol.get() {
+            last_updated.set(Some(get_current_timestamp()));
+        }
+    });
+
+    // Auto-refresh headline metrics (client-side only)
+    #[cfg(feature = "hydrate")]
+    {
+        use gloo_timers::callback::Interval;
+
+        let interval = Interval::new(config().refresh_interval_ms as u32, move || {
+            protocol.refetch();
+            price.refetch();
+        });
+
+        on_cleanup(move || drop(interval));
+    }
+
     // Refresh all data
     let refresh_all = move || {
         protocol.refetch();
@@ -158,9 +200,23 @@ pub fn Dashboard() -> impl IntoView {
                             }).collect_view()
                         }}
                     </div>
+
+                    {move || metrics_provenance.get().and_then(|p| {
+                        let block = p.block_number?;
+                        let title = format!("Fetched from {} at {}", p.source, format_timestamp_full(p.fetched_at as u64));
+                        let label = if p.stale {
+                            format!("as of block {} (refreshing)", block)
+                        } else {
+                            format!("as of block {}", block)
+                        };
+                        Some(view! {
+                            <span class="inline-stat-label" title=title>{label}</span>
+                        })
+                    })}
                 </div>
 
                 <div class="page-header-right">
+                    <LiveIndicator updated_at=last_updated />
                     <TimeRangeSelector
                         selected=time_range
                         options=vec![TimeRange::Hour24, TimeRange::Day7, TimeRange::Day30, TimeRange::All]
@@ -192,7 +248,8 @@ pub fn Dashboard() -> impl IntoView {
                                     let ct = chart_type.get();
                                     volumes.get().map(|res: Result<_, leptos::ServerFnError>| {
                                         match res {
-                                            Ok(data) => {
+                                            Ok(response) => {
+                                                let data = response.volumes;
                                                 if data.is_empty() {
                                                     return view! {
                                                         <div style="text-align: center; color: #666;">"No volume data for selected range"</div>
@@ -242,35 +299,44 @@ pub fn Dashboard() -> impl IntoView {
                                 {move || {
                                     let proto = protocol.get();
                                     let pr = price.get();
+                                    // Cached values keep their original fetch time, so old data is dimmed
+                                    let (proto_stale, proto_age) = data_staleness(
+                                        proto.as_ref().and_then(|r| r.as_ref().ok()).map(|m| m.provenance.fetched_at),
+                                    );
+                                    let (price_stale, price_age) = data_staleness(
+                                        pr.as_ref().and_then(|r| r.as_ref().ok()).map(|p| p.provenance.fetched_at),
+                                    );
+                                    let proto_class = format!("metric-row {}", proto_stale.css_class());
+                                    let price_class = format!("metric-row {}", price_stale.css_class());
 
                                     view! {
                                         <div style="display: flex; flex-direction: column; gap: 10px;">
                                             // Supply
-                                            <div class="metric-row">
+                                            <div class=proto_class.clone() title=proto_age.clone()>
                                                 <span class="metric-row-label">"Total Supply"</span>
                                                 <span class="metric-row-value">
-                                                    {proto.as_ref().and_then(|r| r.as_ref().ok()).map(|m| format_volume(decimal_to_f64(m.total_supply))).unwrap_or_else(|| "--".to_string())}
+                                                    {proto.as_ref().and_then(|r| r.as_ref().ok()).and_then(|m| m.total_supply).map(|v| format_volume(decimal_to_f64(v))).unwrap_or_else(|| "--".to_string())}
                                                 </span>
                                             </div>
                                             // Volume - handle Option<f64>
-                                            <div class="metric-row">
+                                            <div class=price_class.clone() title=price_age.clone()>
                                                 <span class="metric-row-label">"24h Volume"</span>
                                                 <span class="metric-row-value">
                                                     {pr.as_ref().and_then(|r| r.as_ref().ok()).and_then(|p| p.volume_24h).map(format_usd_compact).unwrap_or_else(|| "--".to_string())}
                                                 </span>
                                             </div>
                                             // Liquidity - handle Option<f64>
-                                            <div class="metric-row">
+                                            <div class=price_class title=price_age>
                                                 <span class="metric-row-label">"Liquidity"</span>
                                                 <span class="metric-row-value">
                                                     {pr.as_ref().and_then(|r| r.as_ref().ok()).and_then(|p| p.liquidity_usd).map(format_usd_compact).unwrap_or_else(|| "--".to_string())}
                                                 </span>
                                             </div>
                                             // Active Troves
-                                            <div class="metric-row">
+                                            <div class=proto_class title=proto_age>
                                                 <span class="metric-row-label">"Active Troves"</span>
                                                 <span class="metric-row-value">
-                                                    {proto.as_ref().and_then(|r| r.as_ref().ok()).map(|m| m.active_troves.to_string()).unwrap_or_else(|| "--".to_string())}
+                                                    {proto.as_ref().and_then(|r| r.as_ref().ok()).and_then(|m| m.active_troves).map(|v| v.to_string()).unwrap_or_else(|| "--".to_string())}
                                                 </span>
                                             </div>
                                         </div>
@@ -469,11 +535,5 @@ fn get_current_timestamp() -> i64 {
 }
 
 fn format_time_ago(timestamp: u64) -> String {
-    let now = get_current_timestamp() as u64;
-    let diff = now.saturating_sub(timestamp);
-
-    if diff < 60 { return format!("{}s ago", diff); }
-    if diff < 3600 { return format!("{}m ago", diff / 60); }
-    if diff < 86400 { return format!("{}h ago", diff / 3600); }
-    format!("{}d ago", diff / 86400)
+    format_duration_ago(timestamp as i64, get_current_timestamp())
 }
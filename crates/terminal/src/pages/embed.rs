@@ -0,0 +1,121 @@
+//! Embed Widgets
+//!
+//! Single-metric views served at `/embed/:metric` for framing on other sites.
+//! The app layout drops the header, sidebar and footer on these paths.
+//!
+//! Query params: `theme=dark|light` (default dark), `size=sm|md|lg` (default md).
+
+use leptos::*;
+use leptos_router::{use_params_map, use_query_map};
+use crate::components::gauge::GaugeChart;
+use crate::global_metrics::use_global_metrics;
+use usdfc_core::format::{decimal_to_f64, format_volume};
+
+/// Metrics available as embeds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmbedMetric {
+    /// USDFC price and deviation from $1
+    Peg,
+    /// Total collateral ratio gauge
+    Tcr,
+    /// Total USDFC supply
+    Supply,
+    /// USDFC held in the stability pool
+    StabilityPool,
+}
+
+impl EmbedMetric {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "peg" | "price" => Some(Self::Peg),
+            "tcr" => Some(Self::Tcr),
+            "supply" => Some(Self::Supply),
+            "stability" | "stability-pool" => Some(Self::StabilityPool),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Peg => "USDFC Peg",
+            Self::Tcr => "Total Collateral Ratio",
+            Self::Supply => "USDFC Supply",
+            Self::StabilityPool => "Stability Pool",
+        }
+    }
+}
+
+/// Peg deviation in percent above which the badge turns red
+const PEG_ALERT_PCT: f64 = 1.0;
+/// Peg deviation in percent above which the badge turns yellow
+const PEG_WARN_PCT: f64 = 0.5;
+
+#[component]
+pub fn EmbedWidget() -> impl IntoView {
+    let params = use_params_map();
+    let query = use_query_map();
+    let metric = move || params.with(|p| p.get("metric").and_then(|m| EmbedMetric::parse(m)));
+    let size = move || {
+        query.with(|q| match q.get("size").map(String::as_str) {
+            Some("sm") => "embed-sm",
+            Some("lg") => "embed-lg",
+            _ => "embed-md",
+        })
+    };
+    let global = use_global_metrics();
+
+    view! {
+        <div class=move || format!("embed-widget {}", size())>
+            {move || match metric() {
+                None => view! {
+                    <div class="embed-label">"Unknown metric"</div>
+                    <div class="embed-note">"Use peg, tcr, supply or stability"</div>
+                }.into_view(),
+                Some(metric) => view! {
+                    <div class="embed-label">{metric.label()}</div>
+                    <Suspense fallback=move || view! { <div class="skeleton embed-skeleton"></div> }>
+                        {move || match metric {
+                            EmbedMetric::Peg => global.price.get().map(|res| match res.ok().and_then(|p| p.price_usd) {
+                                Some(price) => {
+                                    let deviation = (price - 1.0) * 100.0;
+                                    let status = if deviation.abs() >= PEG_ALERT_PCT {
+                                        "red"
+                                    } else if deviation.abs() >= PEG_WARN_PCT {
+                                        "yellow"
+                                    } else {
+                                        "green"
+                                    };
+                                    view! {
+                                        <div class=format!("embed-value {}", status)>{format!("${:.4}", price)}</div>
+                                        <div class="embed-note">{format!("{:+.2}% from peg", deviation)}</div>
+                                    }.into_view()
+                                }
+                                None => view! { <div class="embed-value">"--"</div> }.into_view(),
+                            }),
+                            _ => global.protocol.get().map(|res| match res {
+                                Ok(m) => match metric {
+                                    EmbedMetric::Tcr => match m.tcr {
+                                        Some(tcr) => view! {
+                                            <GaugeChart value=decimal_to_f64(tcr) min=110.0 max=200.0 label="TCR" suffix="%" />
+                                        }.into_view(),
+                                        None => view! { <div class="embed-value">"--"</div> }.into_view(),
+                                    },
+                                    EmbedMetric::StabilityPool => view! {
+                                        <div class="embed-value cyan">{m.stability_pool_balance.map(|v| format_volume(decimal_to_f64(v))).unwrap_or_else(|| "--".to_string())}</div>
+                                        <div class="embed-note">"USDFC"</div>
+                                    }.into_view(),
+                                    _ => view! {
+                                        <div class="embed-value cyan">{m.total_supply.map(|v| format_volume(decimal_to_f64(v))).unwrap_or_else(|| "--".to_string())}</div>
+                                        <div class="embed-note">"USDFC"</div>
+                                    }.into_view(),
+                                },
+                                Err(_) => view! { <div class="embed-value">"--"</div> }.into_view(),
+                            }),
+                        }}
+                    </Suspense>
+                }.into_view(),
+            }}
+            <a class="embed-brand" href="/" target="_blank" rel="noopener">{crate::app::brand_name()}</a>
+        </div>
+    }
+}
@@ -1,11 +1,15 @@
 use leptos::*;
-use crate::components::Pagination;
-use usdfc_api::{get_top_holders, get_usdfc_price_data};
-use usdfc_core::format::{format_amount, shorten_hash, format_usd_compact};
+use crate::components::{error_message, DataLoadError, EmptyTableRow, Pagination};
+use usdfc_api::{get_top_holders, get_trove_leaderboard, get_usdfc_price_data};
+use usdfc_core::format::{format_amount, format_fil, shorten_hash, format_usd_compact, explorer_address_url};
+use usdfc_core::types::TroveRankBy;
 use usdfc_core::config::config;
 
 const HOLDERS_PER_PAGE: usize = 25;
 
+/// Rows shown in each trove leaderboard table
+const LEADERBOARD_SIZE: u32 = 10;
+
 #[component]
 pub fn EntityRegistry() -> impl IntoView {
     // Pagination state for holders
@@ -98,7 +102,7 @@ pub fn EntityRegistry() -> impl IntoView {
                                                     <tr>
                                                         <td>
                                                             <a
-                                                                href=format!("https://filecoin.blockscout.com/address/{}", cfg.pool_usdfc_wfil)
+                                                                href=explorer_address_url(&cfg.explorer_url, &cfg.pool_usdfc_wfil)
                                                                 target="_blank"
                                                                 style="color: var(--accent-cyan); text-decoration: none; font-weight: 500;"
                                                             >
@@ -119,7 +123,7 @@ pub fn EntityRegistry() -> impl IntoView {
                                     }.into_view()
                                 }
                                 Err(err) => view! {
-                                    <div style="padding: 20px; text-align: center; color: var(--accent-red);">{err.to_string()}</div>
+                                    <DataLoadError error=err.to_string() on_retry=move || pool_data.refetch() />
                                 }.into_view()
                             }
                         })
@@ -127,6 +131,11 @@ pub fn EntityRegistry() -> impl IntoView {
                 </Suspense>
             </div>
 
+            // Trove Leaderboards
+            <div class="grid-3" style="margin-bottom: 24px;">
+                {TroveRankBy::ALL.into_iter().map(|sort_by| view! { <TroveLeaderboard sort_by=sort_by /> }).collect_view()}
+            </div>
+
             // Top Holders Section
             <div class="card">
                 <div class="card-header">
@@ -166,7 +175,7 @@ pub fn EntityRegistry() -> impl IntoView {
                                             Ok(items) => {
                                                 if items.is_empty() {
                                                     view! {
-                                                        <tr><td colspan="4" style="text-align: center; padding: 20px; color: var(--text-muted);">"No holders found"</td></tr>
+                                                        <EmptyTableRow colspan=4 title="No holders found" />
                                                     }.into_view()
                                                 } else {
                                                     // Calculate pagination
@@ -195,10 +204,10 @@ pub fn EntityRegistry() -> impl IntoView {
                                                                             {shorten_hash(&item.address)}
                                                                         </a>
                                                                         <a
-                                                                            href=format!("https://filecoin.blockscout.com/address/{}", item.address)
+                                                                            href=explorer_address_url(&config().explorer_url, &item.address)
                                                                             target="_blank"
                                                                             style="color: var(--text-muted); text-decoration: none; font-size: 10px;"
-                                                                            title="View on Blockscout"
+                                                                            title="View in explorer"
                                                                         >
                                                                             <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                                                                                 <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
@@ -217,7 +226,9 @@ pub fn EntityRegistry() -> impl IntoView {
                                                 }
                                             }
                                             Err(err) => view! {
-                                                <tr><td colspan="4" style="text-align: center; padding: 20px; color: var(--accent-red);">{err.to_string()}</td></tr>
+                                                <tr><td colspan="4">
+                                                    <DataLoadError error=err.to_string() on_retry=move || holders.refetch() />
+                                                </td></tr>
                                             }.into_view()
                                         }
                                     })
@@ -238,6 +249,100 @@ pub fn EntityRegistry() -> impl IntoView {
     }
 }
 
+/// Top troves ranked by a single dimension
+#[component]
+fn TroveLeaderboard(sort_by: TroveRankBy) -> impl IntoView {
+    let troves = create_resource(
+        || (),
+        move |_| async move { get_trove_leaderboard(sort_by, Some(LEADERBOARD_SIZE)).await }
+    );
+
+    view! {
+        <div class="card">
+            <div class="card-header">
+                <div>
+                    <h3 class="card-title">{sort_by.label()}</h3>
+                    <p class="card-subtitle">{format!("Top {} troves", LEADERBOARD_SIZE)}</p>
+                </div>
+            </div>
+            <div class="table-container">
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>"Rank"</th>
+                            <th>"Owner"</th>
+                            <th>{match sort_by {
+                                TroveRankBy::Debt => "Debt",
+                                TroveRankBy::Collateral => "Collateral",
+                                TroveRankBy::Icr => "ICR",
+                            }}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <Suspense fallback=move || view! {
+                            <tr><td colspan="3" style="text-align: center; padding: 20px;">"Loading troves..."</td></tr>
+                        }>
+                            {move || {
+                                troves.get().map(|res| {
+                                    match res {
+                                        Ok(items) if items.is_empty() => view! {
+                                            <EmptyTableRow colspan=3 title="No troves found" />
+                                        }.into_view(),
+                                        Ok(items) => items.into_iter().enumerate().map(|(i, trove)| {
+                                            let value = match sort_by {
+                                                TroveRankBy::Debt => format!("{} USDFC", format_amount(trove.debt)),
+                                                TroveRankBy::Collateral => format_fil(trove.collateral),
+                                                TroveRankBy::Icr => format!("{:.1}%", trove.icr),
+                                            };
+                                            view! {
+                                                <tr>
+                                                    <td style="color: var(--text-muted);">{format!("#{}", i + 1)}</td>
+                                                    <td style="font-family: monospace; font-size: 12px;">
+                                                        <div style="display: flex; align-items: center; gap: 4px;">
+                                                            <a
+                                                                href=format!("/address/{}", trove.address)
+                                                                style="color: var(--accent-cyan); text-decoration: none;"
+                                                                title="View address details"
+                                                            >
+                                                                {shorten_hash(&trove.address)}
+                                                            </a>
+                                                            <a
+                                                                href=explorer_address_url(&config().explorer_url, &trove.address)
+                                                                target="_blank"
+                                                                style="color: var(--text-muted); text-decoration: none; font-size: 10px;"
+                                                                title="View in explorer"
+                                                            >
+                                                                <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+                                                                <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
+                                                                <polyline points="15 3 21 3 21 9"></polyline>
+                                                                <line x1="10" y1="14" x2="21" y2="3"></line>
+                                                            </svg>
+                                                            </a>
+                                                        </div>
+                                                    </td>
+                                                    <td style="font-family: monospace;">
+                                                        {value}" "
+                                                        <span class=trove.status.css_class()>{trove.status.as_str()}</span>
+                                                    </td>
+                                                </tr>
+                                            }
+                                        }).collect_view(),
+                                        Err(err) => view! {
+                                            <tr><td colspan="3">
+                                                <DataLoadError error=error_message(&err) on_retry=move || troves.refetch() />
+                                            </td></tr>
+                                        }.into_view()
+                                    }
+                                })
+                            }}
+                        </Suspense>
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
 /// Copy-to-clipboard button with feedback
 #[component]
 fn CopyButton(address: String) -> impl IntoView {
@@ -326,10 +431,10 @@ fn ProtocolContracts() -> impl IntoView {
                             {shorten_hash(addr)}
                         </a>
                         <a
-                            href=format!("https://filecoin.blockscout.com/address/{}", addr)
+                            href=explorer_address_url(&config().explorer_url, addr)
                             target="_blank"
                             style="color: var(--text-muted); text-decoration: none; font-size: 10px;"
-                            title="View on Blockscout"
+                            title="View in explorer"
                         >
                             <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                                 <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
@@ -347,20 +452,7 @@ fn ProtocolContracts() -> impl IntoView {
 }
 
 fn identify_entity(address: &str) -> &'static str {
-    let addr = address.to_lowercase();
-    let cfg = config();
-
-    // Check known protocol contracts
-    if addr == cfg.stability_pool.to_lowercase() { return "Stability Pool"; }
-    if addr == cfg.active_pool.to_lowercase() { return "Active Pool"; }
-    if addr == cfg.trove_manager.to_lowercase() { return "Trove Manager"; }
-    if addr == cfg.borrower_operations.to_lowercase() { return "Borrower Ops"; }
-
-    // Check known DEX pools
-    if addr == cfg.pool_usdfc_wfil.to_lowercase() { return "USDFC/WFIL Pool"; }
-
-    // Default
-    "Unknown"
+    config().known_address_label(address).unwrap_or("Unknown")
 }
 
 fn entity_badge_class(entity: &str) -> &'static str {
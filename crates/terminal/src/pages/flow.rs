@@ -1,14 +1,17 @@
 use leptos::*;
 use usdfc_api::{get_recent_transactions, get_daily_volumes};
-use usdfc_core::format::{format_amount, format_timestamp, shorten_hash};
+use usdfc_core::format::{format_amount_with, format_duration_ago, format_timestamp, shorten_hash};
 use usdfc_core::types::Transaction;
 use std::collections::HashMap;
 
+/// Decimals shown for compact transfer amounts (e.g. "1.25M")
+const FLOW_AMOUNT_DECIMALS: usize = 2;
+
 #[component]
 pub fn FlowDiagrams() -> impl IntoView {
     let recent_tx = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(100)).await }
+        |_| async move { get_recent_transactions(Some(100), None).await }
     );
 
     // Historical daily volume data from subgraph
@@ -293,15 +296,16 @@ pub fn FlowDiagrams() -> impl IntoView {
                                         view! {
                                             <div style="display: flex; flex-direction: column; gap: 8px;">
                                                 {filtered.iter().take(6).map(|tx| {
-                                                    let amount = format_amount(tx.amount);
-                                                    let time = format_timestamp(tx.timestamp);
+                                                    let amount = format_amount_with(tx.amount, FLOW_AMOUNT_DECIMALS, true);
+                                                    let time = format_duration_ago(tx.timestamp as i64, get_current_timestamp());
+                                                    let full_time = format_timestamp(tx.timestamp);
                                                     view! {
                                                         <div class="stat-row" style="padding: 8px 0; border-bottom: 1px solid var(--bg-tertiary);">
                                                             <div>
                                                                 <div style="font-family: monospace; font-size: 11px; color: var(--text-secondary);">
                                                                     {format!("{} → {}", shorten_hash(&tx.from), shorten_hash(&tx.to))}
                                                                 </div>
-                                                                <div style="font-size: 10px; color: var(--text-muted); margin-top: 2px;">{time}</div>
+                                                                <div style="font-size: 10px; color: var(--text-muted); margin-top: 2px;" title=full_time>{time}</div>
                                                             </div>
                                                             <div style="font-family: monospace; color: var(--accent-cyan);">{amount}</div>
                                                         </div>
@@ -344,7 +348,9 @@ pub fn FlowDiagrams() -> impl IntoView {
                     {move || {
                         daily_volumes.get().map(|res| {
                             match res {
-                                Ok(data) => {
+                                Ok(response) => {
+                                    let complete = response.complete;
+                                    let data = response.volumes;
                                     if data.is_empty() {
                                         view! {
                                             <div class="empty-state">
@@ -364,6 +370,12 @@ pub fn FlowDiagrams() -> impl IntoView {
                                         let max_vol = bars.iter().map(|(_, v)| *v).fold(0.0f64, f64::max);
 
                                         view! {
+                                            {(!complete).then(|| view! {
+                                                <div class="api-limit-warning" style="margin-bottom: 12px;">
+                                                    <span class="warning-icon">"⚠"</span>
+                                                    <span class="warning-text">"Partial data: some of the requested range could not be loaded from the subgraph"</span>
+                                                </div>
+                                            })}
                                             <HistoricalVolumeChart bars=bars max_volume=max_vol />
                                         }.into_view()
                                     }
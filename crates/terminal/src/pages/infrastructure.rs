@@ -6,6 +6,9 @@
 use leptos::*;
 use crate::components::tabs::{TabNav, TabContent, Tab};
 use usdfc_api::check_api_health;
+use usdfc_core::config::config;
+use usdfc_core::format::explorer_address_url;
+use usdfc_core::types::CircuitState;
 
 #[component]
 pub fn Infrastructure() -> impl IntoView {
@@ -80,7 +83,7 @@ fn ContractsTab() -> impl IntoView {
                     </thead>
                     <tbody>
                         {contracts.into_iter().map(|(name, addr, desc)| {
-                            let explorer_url = format!("https://filecoin.blockscout.com/address/{}", addr);
+                            let explorer_url = explorer_address_url(&config().explorer_url, addr);
                             view! {
                                 <tr>
                                     <td style="font-weight: 600; color: var(--text-primary);">{name}</td>
@@ -107,7 +110,7 @@ fn ContractsTab() -> impl IntoView {
                             <tr>
                                 <td style="font-weight: 600;">"USDFC/WFIL Pool"</td>
                                 <td>
-                                    <a href="https://filecoin.blockscout.com/address/0x4e07447bd38e60b94176764133788be1a0736b30" target="_blank" style="color: var(--accent-cyan); text-decoration: none; font-family: monospace; font-size: 12px;">
+                                    <a href=explorer_address_url(&config().explorer_url, &config().pool_usdfc_wfil) target="_blank" style="color: var(--accent-cyan); text-decoration: none; font-family: monospace; font-size: 12px;">
                                         "0x4e07...6b30"
                                     </a>
                                 </td>
@@ -324,26 +327,25 @@ fn ErrorLogsTab() -> impl IntoView {
             let mut new_errors = Vec::new();
             let now = get_current_time_string();
 
-            if !h.rpc_ok {
-                new_errors.push((
-                    now.clone(),
-                    "RPC Connection".to_string(),
-                    "Failed to connect to Filecoin RPC (api.node.glif.io)".to_string(),
-                ));
-            }
-            if !h.blockscout_ok {
-                new_errors.push((
-                    now.clone(),
-                    "Blockscout API".to_string(),
-                    "Failed to connect to Blockscout API".to_string(),
-                ));
-            }
-            if !h.subgraph_ok {
-                new_errors.push((
-                    now.clone(),
-                    "Subgraph".to_string(),
-                    "Failed to connect to Secured Finance subgraph".to_string(),
-                ));
+            let sources = [
+                ("rpc", h.rpc_ok, "RPC Connection", "Failed to connect to Filecoin RPC (api.node.glif.io)"),
+                ("blockscout", h.blockscout_ok, "Blockscout API", "Failed to connect to Blockscout API"),
+                ("subgraph", h.subgraph_ok, "Subgraph", "Failed to connect to Secured Finance subgraph"),
+                ("gecko", h.gecko_ok, "GeckoTerminal", "Failed to connect to GeckoTerminal API"),
+            ];
+            for (upstream, ok, source, message) in sources {
+                if ok || h.is_disabled(upstream) {
+                    continue;
+                }
+                // A tripped circuit fails calls without contacting the upstream
+                let message = if h.circuit(upstream) == CircuitState::Open {
+                    "Circuit breaker open after repeated failures; requests fail fast until it recovers".to_string()
+                } else if h.is_timed_out(upstream) {
+                    format!("{} (timeout)", message)
+                } else {
+                    message.to_string()
+                };
+                new_errors.push((now.clone(), source.to_string(), message));
             }
 
             errors.set(new_errors);
@@ -419,10 +421,10 @@ fn ErrorLogsTab() -> impl IntoView {
                     health.get().map(|res| match res {
                         Ok(h) => view! {
                             <div class="grid-2" style="gap: 16px;">
-                                <StatusCard name="Filecoin RPC" connected=h.rpc_ok endpoint="api.node.glif.io" />
-                                <StatusCard name="Blockscout API" connected=h.blockscout_ok endpoint="filecoin.blockscout.com" />
-                                <StatusCard name="Secured Finance" connected=h.subgraph_ok endpoint="api.goldsky.com" />
-                                <StatusCard name="GeckoTerminal" connected=true endpoint="api.geckoterminal.com" />
+                                <StatusCard name="Filecoin RPC" connected=h.rpc_ok circuit=h.circuit("rpc") timed_out=h.is_timed_out("rpc") endpoint="api.node.glif.io" />
+                                <StatusCard name="Blockscout API" connected=h.blockscout_ok circuit=h.circuit("blockscout") timed_out=h.is_timed_out("blockscout") disabled=h.is_disabled("blockscout") endpoint="filecoin.blockscout.com" />
+                                <StatusCard name="Secured Finance" connected=h.subgraph_ok circuit=h.circuit("subgraph") timed_out=h.is_timed_out("subgraph") disabled=h.is_disabled("subgraph") endpoint="api.goldsky.com" />
+                                <StatusCard name="GeckoTerminal" connected=h.gecko_ok circuit=h.circuit("gecko") timed_out=h.is_timed_out("gecko") disabled=h.is_disabled("gecko") endpoint="api.geckoterminal.com" />
                             </div>
                         }.into_view(),
                         Err(e) => view! {
@@ -438,19 +440,43 @@ fn ErrorLogsTab() -> impl IntoView {
 }
 
 #[component]
-fn StatusCard(name: &'static str, connected: bool, endpoint: &'static str) -> impl IntoView {
+fn StatusCard(
+    name: &'static str,
+    connected: bool,
+    endpoint: &'static str,
+    /// Upstream circuit breaker state - a tripped circuit is named as such
+    circuit: CircuitState,
+    /// Source turned off in config - shown muted instead of offline
+    #[prop(optional)]
+    disabled: bool,
+    /// Health check timed out rather than failing outright
+    #[prop(optional)]
+    timed_out: bool,
+) -> impl IntoView {
+    let (class, label, background, color) = if disabled {
+        ("status-badge disabled", "Disabled", "rgba(85, 85, 85, 0.2)", "var(--text-muted)")
+    } else if circuit == CircuitState::Open {
+        ("status-badge offline", "Circuit Open", "rgba(239, 68, 68, 0.2)", "var(--accent-red)")
+    } else if circuit == CircuitState::HalfOpen {
+        ("status-badge warning", "Recovering", "rgba(245, 158, 11, 0.2)", "var(--accent-yellow)")
+    } else if connected {
+        ("status-badge online", "Online", "rgba(34, 197, 94, 0.2)", "var(--accent-green)")
+    } else if timed_out {
+        ("status-badge offline", "Timeout", "rgba(239, 68, 68, 0.2)", "var(--accent-red)")
+    } else {
+        ("status-badge offline", "Offline", "rgba(239, 68, 68, 0.2)", "var(--accent-red)")
+    };
+    let border = if disabled { "var(--border-color)" } else { color };
+
     view! {
-        <div class="stat-card" style=format!("border-left: 3px solid {};", if connected { "var(--accent-green)" } else { "var(--accent-red)" })>
+        <div class="stat-card" style=format!("border-left: 3px solid {};", border)>
             <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: 8px;">
                 <span style="font-weight: 600; color: var(--text-primary);">{name}</span>
                 <span
-                    class=if connected { "status-badge online" } else { "status-badge offline" }
-                    style=format!("padding: 2px 8px; border-radius: 4px; font-size: 11px; background: {}; color: {};",
-                        if connected { "rgba(34, 197, 94, 0.2)" } else { "rgba(239, 68, 68, 0.2)" },
-                        if connected { "var(--accent-green)" } else { "var(--accent-red)" }
-                    )
+                    class=class
+                    style=format!("padding: 2px 8px; border-radius: 4px; font-size: 11px; background: {}; color: {};", background, color)
                 >
-                    {if connected { "Online" } else { "Offline" }}
+                    {label}
                 </span>
             </div>
             <div style="font-family: monospace; font-size: 11px; color: var(--text-muted);">{endpoint}</div>
@@ -1,7 +1,10 @@
 use leptos::*;
 use crate::components::icons::*;
 use crate::components::charts::BarChart;
+use crate::components::gauge::GaugeChart;
+use crate::components::{DataLoadError, EmptyState, EmptyTableRow};
 use usdfc_api::{get_lending_markets, get_order_book, get_recent_lending_trades, get_daily_volumes};
+use usdfc_core::types::Currency;
 use usdfc_core::format::{shorten_hash, format_date};
 use std::collections::HashMap;
 
@@ -134,7 +137,6 @@ pub fn LendingMarkets() -> impl IntoView {
                                                 
                                                 for market in &markets {
                                                     let maturity_date = format_maturity(&market.maturity);
-                                                    let currency = decode_currency(&market.currency);
                                                     let lend_apr = format_apr(market.lend_apr);
                                                     let borrow_apr = format_apr(market.borrow_apr);
                                                     
@@ -146,33 +148,60 @@ pub fn LendingMarkets() -> impl IntoView {
                                                         usdfc_lend: "—".to_string(),
                                                         usdfc_borrow: "—".to_string(),
                                                         is_active: market.is_active,
+                                                        featured: false,
                                                     });
                                                     
-                                                    if currency == "FIL" {
-                                                        entry.fil_lend = lend_apr;
-                                                        entry.fil_borrow = borrow_apr;
-                                                    } else if currency == "USDFC" {
-                                                        entry.usdfc_lend = lend_apr;
-                                                        entry.usdfc_borrow = borrow_apr;
+                                                    match market.currency {
+                                                        Currency::Fil => {
+                                                            entry.fil_lend = lend_apr;
+                                                            entry.fil_borrow = borrow_apr;
+                                                        }
+                                                        Currency::Usdfc => {
+                                                            entry.usdfc_lend = lend_apr;
+                                                            entry.usdfc_borrow = borrow_apr;
+                                                        }
+                                                        Currency::Other(_) => {}
                                                     }
                                                     
                                                     if market.is_active {
                                                         entry.is_active = true;
                                                     }
+                                                    if market.featured {
+                                                        entry.featured = true;
+                                                    }
                                                 }
                                                 
-                                                // Sort by maturity timestamp
+                                                // Featured maturities first, then by maturity timestamp
                                                 let mut sorted_pairs: Vec<_> = pairs.into_values().collect();
-                                                sorted_pairs.sort_by_key(|p| p.maturity_ts);
+                                                sorted_pairs.sort_by_key(|p| (!p.featured, p.maturity_ts));
+                                                let has_featured = sorted_pairs.first().is_some_and(|p| p.featured);
+                                                let first_regular = sorted_pairs.iter().position(|p| !p.featured);
                                                 
-                                                sorted_pairs.iter().map(|pair| {
+                                                sorted_pairs.iter().enumerate().map(|(i, pair)| {
+                                                    // Section labels only when operators configured featured markets
+                                                    let group_label = if has_featured && i == 0 {
+                                                        Some("Featured")
+                                                    } else if has_featured && Some(i) == first_regular {
+                                                        Some("All Maturities")
+                                                    } else {
+                                                        None
+                                                    };
                                                     let status = if pair.is_active { "Active" } else { "Matured" };
                                                     let status_class = if pair.is_active { "color: var(--accent-green);" } else { "color: var(--text-muted);" };
                                                     let fil_color = if pair.fil_lend == "—" { "color: var(--text-muted);" } else { "color: var(--accent-cyan);" };
                                                     let usdfc_color = if pair.usdfc_lend == "—" { "color: var(--text-muted);" } else { "color: var(--accent-green);" };
                                                     
+                                                    let row_style = if pair.featured { "background: rgba(34, 197, 94, 0.06);" } else { "" };
+                                                    
                                                     view! {
-                                                        <tr>
+                                                        {group_label.map(|label| view! {
+                                                            <tr>
+                                                                <td colspan="6" style="font-size: 11px; font-weight: 600; text-transform: uppercase; letter-spacing: 0.05em; color: var(--text-muted); padding-top: 12px;">
+                                                                    {label}
+                                                                </td>
+                                                            </tr>
+                                                        })}
+                                                        <tr style=row_style>
                                                             <td style="font-weight: 500;">{&pair.maturity}</td>
                                                             <td class="hide-mobile" style=fil_color>{&pair.fil_lend}</td>
                                                             <td class="hide-mobile" style=fil_color>{&pair.fil_borrow}</td>
@@ -184,18 +213,12 @@ pub fn LendingMarkets() -> impl IntoView {
                                                 }).collect_view()
                                             },
                                             Ok(_) => view! {
-                                                <tr>
-                                                    <td colspan="6" style="text-align: center; padding: 20px; color: var(--text-muted);">
-                                                        "No markets found"
-                                                    </td>
-                                                </tr>
+                                                <EmptyTableRow colspan=6 title="No markets found" description="Secured Finance has no active USDFC markets." />
                                             }.into_view(),
                                             Err(err) => view! {
-                                                <tr>
-                                                    <td colspan="6" style="text-align: center; padding: 20px; color: var(--accent-red);">
-                                                        {err.to_string()}
-                                                    </td>
-                                                </tr>
+                                                <tr><td colspan="6">
+                                                    <DataLoadError error=err.to_string() on_retry=move || markets.refetch() />
+                                                </td></tr>
                                             }.into_view()
                                         }
                                     })
@@ -206,6 +229,75 @@ pub fn LendingMarkets() -> impl IntoView {
                 </div>
             </div>
 
+            // Order Flow Section
+            <div class="card" style="margin-top: 24px;">
+                <div class="card-header">
+                    <div>
+                        <h3 class="card-title">"Order Flow"</h3>
+                        <p class="card-subtitle">"Open lend vs borrow volume (fully-filled orders excluded)"</p>
+                    </div>
+                </div>
+                <Suspense fallback=move || view! {
+                    <div style="padding: 20px; text-align: center; color: var(--text-muted);">"Loading..."</div>
+                }>
+                    {move || {
+                        order_book.get().map(|res| match res {
+                            Ok(book) => {
+                                let fmt_price = |p: Option<f64>| p.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string());
+                                let leaning = match book.imbalance {
+                                    Some(v) if v > 0.1 => "Leaning lend",
+                                    Some(v) if v < -0.1 => "Leaning borrow",
+                                    Some(_) => "Balanced",
+                                    None => "No open orders",
+                                };
+                                view! {
+                                    <div class="grid-2" style="align-items: center;">
+                                        <GaugeChart
+                                            value=book.imbalance.unwrap_or(0.0) * 100.0
+                                            min=-100.0
+                                            max=100.0
+                                            label=leaning
+                                            suffix="%"
+                                            scale=["Borrow", "Balanced", "Lend"]
+                                            color="var(--accent-cyan)"
+                                        />
+                                        <table class="table">
+                                            <thead>
+                                                <tr>
+                                                    <th></th>
+                                                    <th>"Lend"</th>
+                                                    <th>"Borrow"</th>
+                                                </tr>
+                                            </thead>
+                                            <tbody>
+                                                <tr>
+                                                    <td style="color: var(--text-muted);">"Open Orders"</td>
+                                                    <td>{book.lend_flow.order_count}</td>
+                                                    <td>{book.borrow_flow.order_count}</td>
+                                                </tr>
+                                                <tr>
+                                                    <td style="color: var(--text-muted);">"Open Volume"</td>
+                                                    <td style="font-family: monospace;">{format!("{:.2}", book.lend_flow.volume)}</td>
+                                                    <td style="font-family: monospace;">{format!("{:.2}", book.borrow_flow.volume)}</td>
+                                                </tr>
+                                                <tr>
+                                                    <td style="color: var(--text-muted);">"Weighted Avg Price"</td>
+                                                    <td style="color: var(--accent-green); font-family: monospace;">{fmt_price(book.lend_flow.weighted_avg_price)}</td>
+                                                    <td style="color: var(--accent-red); font-family: monospace;">{fmt_price(book.borrow_flow.weighted_avg_price)}</td>
+                                                </tr>
+                                            </tbody>
+                                        </table>
+                                    </div>
+                                }.into_view()
+                            }
+                            Err(err) => view! {
+                                <div style="padding: 20px; text-align: center; color: var(--accent-red);">{err.to_string()}</div>
+                            }.into_view()
+                        })
+                    }}
+                </Suspense>
+            </div>
+
             // Order Book Section
             <div class="grid-2" style="margin-top: 24px;">
                 <div class="card">
@@ -249,7 +341,7 @@ pub fn LendingMarkets() -> impl IntoView {
                                                 Ok(book) => {
                                                     if book.lend_orders.is_empty() {
                                                         view! {
-                                                            <tr><td colspan="3" style="text-align: center; padding: 20px; color: var(--text-muted);">"No open lend orders"</td></tr>
+                                                            <EmptyTableRow colspan=3 title="No open lend orders" />
                                                         }.into_view()
                                                     } else {
                                                         let orders_to_show: Vec<_> = if is_expanded {
@@ -269,7 +361,9 @@ pub fn LendingMarkets() -> impl IntoView {
                                                     }
                                                 }
                                                 Err(err) => view! {
-                                                    <tr><td colspan="3" style="text-align: center; padding: 20px; color: var(--accent-red);">{err.to_string()}</td></tr>
+                                                    <tr><td colspan="3">
+                                                        <DataLoadError error=err.to_string() on_retry=move || order_book.refetch() />
+                                                    </td></tr>
                                                 }.into_view()
                                             }
                                         })
@@ -320,7 +414,7 @@ pub fn LendingMarkets() -> impl IntoView {
                                                 Ok(book) => {
                                                     if book.borrow_orders.is_empty() {
                                                         view! {
-                                                            <tr><td colspan="3" style="text-align: center; padding: 20px; color: var(--text-muted);">"No open borrow orders"</td></tr>
+                                                            <EmptyTableRow colspan=3 title="No open borrow orders" />
                                                         }.into_view()
                                                     } else {
                                                         let orders_to_show: Vec<_> = if is_expanded {
@@ -340,7 +434,9 @@ pub fn LendingMarkets() -> impl IntoView {
                                                     }
                                                 }
                                                 Err(err) => view! {
-                                                    <tr><td colspan="3" style="text-align: center; padding: 20px; color: var(--accent-red);">{err.to_string()}</td></tr>
+                                                    <tr><td colspan="3">
+                                                        <DataLoadError error=err.to_string() on_retry=move || order_book.refetch() />
+                                                    </td></tr>
                                                 }.into_view()
                                             }
                                         })
@@ -389,7 +485,7 @@ pub fn LendingMarkets() -> impl IntoView {
                                             Ok(trades) => {
                                                 if trades.is_empty() {
                                                     view! {
-                                                        <tr><td colspan="6" style="text-align: center; padding: 20px; color: var(--text-muted);">"No recent trades"</td></tr>
+                                                        <EmptyTableRow colspan=6 title="No recent trades" />
                                                     }.into_view()
                                                 } else {
                                                     trades.iter().map(|trade| {
@@ -399,7 +495,7 @@ pub fn LendingMarkets() -> impl IntoView {
                                                             <tr>
                                                                 <td style="font-family: monospace; font-size: 11px;">{shorten_hash(&trade.id)}</td>
                                                                 <td style=side_color>{&trade.side}</td>
-                                                                <td>{&trade.currency}</td>
+                                                                <td>{trade.currency.to_string()}</td>
                                                                 <td style="font-family: monospace;">{format!("{:.2}", trade.amount)}</td>
                                                                 <td>{format!("{:.2}%", trade.apr)}</td>
                                                                 <td>{time}</td>
@@ -409,7 +505,9 @@ pub fn LendingMarkets() -> impl IntoView {
                                                 }
                                             }
                                             Err(err) => view! {
-                                                <tr><td colspan="6" style="text-align: center; padding: 20px; color: var(--accent-red);">{err.to_string()}</td></tr>
+                                                <tr><td colspan="6">
+                                                    <DataLoadError error=err.to_string() on_retry=move || recent_trades.refetch() />
+                                                </td></tr>
                                             }.into_view()
                                         }
                                     })
@@ -436,12 +534,12 @@ pub fn LendingMarkets() -> impl IntoView {
                     {move || {
                         daily_volumes.get().map(|res| {
                             match res {
-                                Ok(volumes) => {
+                                Ok(response) => {
+                                    let complete = response.complete;
+                                    let volumes = response.volumes;
                                     if volumes.is_empty() {
                                         view! {
-                                            <div style="height: 200px; display: flex; align-items: center; justify-content: center; color: var(--text-muted);">
-                                                "No volume data available"
-                                            </div>
+                                            <EmptyState title="No volume data available" description="No lending trades in the last 14 days." />
                                         }.into_view()
                                     } else {
                                         // Convert to chart data format
@@ -451,14 +549,18 @@ pub fn LendingMarkets() -> impl IntoView {
                                             .map(|v| (v.day.clone(), v.volume))
                                             .collect();
                                         view! {
+                                            {(!complete).then(|| view! {
+                                                <div class="api-limit-warning" style="margin-bottom: 12px;">
+                                                    <span class="warning-icon">"⚠"</span>
+                                                    <span class="warning-text">"Partial data: some of the requested range could not be loaded from the subgraph"</span>
+                                                </div>
+                                            })}
                                             <BarChart data=chart_data color="#00d4ff" height=200 />
                                         }.into_view()
                                     }
                                 }
                                 Err(err) => view! {
-                                    <div style="height: 200px; display: flex; align-items: center; justify-content: center; color: var(--accent-red);">
-                                        {err.to_string()}
-                                    </div>
+                                    <DataLoadError error=err.to_string() on_retry=move || daily_volumes.refetch() />
                                 }.into_view()
                             }
                         })
@@ -496,6 +598,7 @@ struct MarketPair {
     usdfc_lend: String,
     usdfc_borrow: String,
     is_active: bool,
+    featured: bool,
 }
 
 fn format_maturity(timestamp: &str) -> String {
@@ -505,26 +608,6 @@ fn format_maturity(timestamp: &str) -> String {
         .unwrap_or_else(|| "Invalid date".to_string())
 }
 
-fn decode_currency(bytes32: &str) -> String {
-    let hex = bytes32.trim_start_matches("0x");
-    let mut result = String::new();
-    
-    let chars: Vec<char> = hex.chars().collect();
-    for chunk in chars.chunks(2) {
-        if chunk.len() == 2 {
-            let byte_str: String = chunk.iter().collect();
-            if let Ok(byte) = u8::from_str_radix(&byte_str, 16) {
-                if byte > 0 && byte < 128 {
-                    result.push(byte as char);
-                }
-            }
-        }
-    }
-    
-    let decoded = result.trim().to_string();
-    if decoded.is_empty() { "Unknown".to_string() } else { decoded }
-}
-
 fn format_apr(apr: f64) -> String {
     if apr <= 0.0 {
         "N/A".to_string()
@@ -8,6 +8,7 @@ pub mod analytics;
 pub mod advanced;
 pub mod infrastructure;
 pub mod tools;
+pub mod embed;
 
 // Address submodule
 pub mod address;
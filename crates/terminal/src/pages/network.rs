@@ -6,7 +6,7 @@ use std::collections::HashMap;
 pub fn NetworkGraph() -> impl IntoView {
     let recent_tx = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(100)).await }
+        |_| async move { get_recent_transactions(Some(100), None).await }
     );
 
     view! {
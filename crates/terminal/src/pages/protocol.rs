@@ -7,11 +7,16 @@ use leptos::*;
 use crate::components::tabs::{TabNav, TabContent, Tab};
 use crate::components::icons::*;
 use crate::components::gauge::GaugeChart;
+use crate::components::controls::{TimeRange, TimeRangeSelector};
+use crate::app::AppState;
+use crate::components::{error_message, DataLoadError, EmptyState, EmptyTableRow};
+use crate::pages::collateral::{implied_fil_price, CollateralUnit, CollateralUnitToggle};
 use usdfc_core::config::config;
 use crate::global_metrics::use_global_metrics;
-use usdfc_api::{get_troves, get_top_holders, get_recent_transactions, get_stability_pool_transfers};
-use usdfc_core::format::{format_usd, format_fil, format_usdfc, format_amount, format_timestamp, shorten_hash, format_volume, decimal_to_f64};
-use usdfc_core::types::TransactionType;
+use usdfc_api::{get_troves, get_top_holders, get_recent_transactions, get_stability_pool_transfers, get_fee_analytics, get_tcr_volatility, get_icr_distribution};
+use usdfc_core::format::{format_usd, format_usdfc, format_amount, format_timestamp, shorten_hash, format_volume, decimal_to_f64, explorer_address_url, explorer_tx_url};
+use usdfc_core::types::{ChartLookback, ChartResolution, TcrVolatility, TransactionType};
+use rust_decimal::Decimal;
 
 /// Normalize negative zero to positive zero for display purposes
 #[inline]
@@ -28,6 +33,7 @@ pub fn Protocol() -> impl IntoView {
         Tab { id: "supply", label: "Supply Dynamics" },
         Tab { id: "collateral", label: "Risk Analysis" },
         Tab { id: "stability", label: "Pool Activity" },
+        Tab { id: "fees", label: "Fees" },
     ];
 
     view! {
@@ -59,6 +65,10 @@ pub fn Protocol() -> impl IntoView {
             <TabContent id="stability" active=active_tab>
                 <PoolActivityTab />
             </TabContent>
+
+            <TabContent id="fees" active=active_tab>
+                <FeesTab />
+            </TabContent>
         </div>
     }
 }
@@ -78,7 +88,7 @@ fn SupplyDynamicsTab() -> impl IntoView {
 
     let recent_tx = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(50)).await }
+        |_| async move { get_recent_transactions(Some(50), None).await }
     );
 
     view! {
@@ -91,21 +101,31 @@ fn SupplyDynamicsTab() -> impl IntoView {
                     let protocol_data = global.protocol.get();
 
                     match (holders_data, protocol_data) {
+                        (Some(Err(e)), _) | (_, Some(Err(e))) => view! {
+                            <DataLoadError error=e.to_string() on_retry=move || top_holders.refetch() />
+                        }.into_view(),
+                        (Some(Ok(holders)), Some(Ok(_))) if holders.is_empty() => view! {
+                            <EmptyState title="No holder data" description="Blockscout returned no USDFC holders." />
+                        }.into_view(),
+                        (Some(Ok(_)), Some(Ok(metrics))) if metrics.total_supply.is_none() => view! {
+                            <EmptyState title="Total supply unavailable" description="Holder shares need the total supply, which could not be fetched." />
+                        }.into_view(),
                         (Some(Ok(holders)), Some(Ok(metrics))) => {
-                            let total_supply_f64 = decimal_to_f64(metrics.total_supply);
-                            let top_10_total: f64 = holders.iter()
-                                .map(|h| decimal_to_f64(h.balance))
-                                .sum();
-                            let top_10_pct = if total_supply_f64 > 0.0 {
-                                top_10_total / total_supply_f64 * 100.0
+                            let total_supply_f64 = metrics.total_supply.map(decimal_to_f64).unwrap_or(0.0);
+                            // Share of supply; zero supply means no meaningful share
+                            let pct = |amount: f64| if total_supply_f64 > 0.0 {
+                                amount / total_supply_f64 * 100.0
                             } else { 0.0 };
+                            let top_10_pct = pct(holders.iter()
+                                .map(|h| decimal_to_f64(h.balance))
+                                .sum());
 
-                            let top_1 = holders.first()
-                                .map(|h| decimal_to_f64(h.balance) / total_supply_f64 * 100.0)
-                                .unwrap_or(0.0);
-                            let top_5: f64 = holders.iter().take(5)
+                            let top_1 = pct(holders.first()
+                                .map(|h| decimal_to_f64(h.balance))
+                                .unwrap_or(0.0));
+                            let top_5 = pct(holders.iter().take(5)
                                 .map(|h| decimal_to_f64(h.balance))
-                                .sum::<f64>() / total_supply_f64 * 100.0;
+                                .sum());
 
                             // Concentration indicator
                             let (concentration_status, concentration_color) = if top_10_pct > 80.0 {
@@ -154,6 +174,9 @@ fn SupplyDynamicsTab() -> impl IntoView {
                 {move || {
                     recent_tx.get().map(|res| {
                         match res {
+                            Ok(txs) if txs.is_empty() => view! {
+                                <EmptyState title="No recent activity" description="No USDFC transfers in the latest window." />
+                            }.into_view(),
                             Ok(txs) => {
                                 let mints: Vec<_> = txs.iter()
                                     .filter(|tx| matches!(tx.tx_type, TransactionType::Mint))
@@ -219,7 +242,9 @@ fn SupplyDynamicsTab() -> impl IntoView {
                                     </div>
                                 }.into_view()
                             }
-                            Err(_) => view! { <div>"Error loading activity"</div> }.into_view()
+                            Err(e) => view! {
+                                <DataLoadError error=e.to_string() on_retry=move || recent_tx.refetch() />
+                            }.into_view()
                         }
                     })
                 }}
@@ -258,18 +283,24 @@ fn SupplyDynamicsTab() -> impl IntoView {
                                     let protocol_data = global.protocol.get();
 
                                     match (holders_data, protocol_data) {
+                                        (Some(Err(e)), _) | (_, Some(Err(e))) => view! {
+                                            <tr><td colspan="5">
+                                                <DataLoadError error=e.to_string() on_retry=move || top_holders.refetch() />
+                                            </td></tr>
+                                        }.into_view(),
                                         (Some(Ok(holders)), Some(Ok(metrics))) => {
                                             if holders.is_empty() {
                                                 view! {
-                                                    <tr><td colspan="5" style="text-align: center; padding: 20px; color: var(--text-muted);">"No holder data"</td></tr>
+                                                    <EmptyTableRow colspan=5 title="No holder data" />
                                                 }.into_view()
                                             } else {
-                                                let total_supply = decimal_to_f64(metrics.total_supply).max(1.0);
+                                                // Unknown supply leaves shares blank rather than guessed
+                                                let total_supply = metrics.total_supply.map(|s| decimal_to_f64(s).max(1.0));
                                                 holders.iter().enumerate().map(|(i, holder)| {
                                                     let balance_f64 = decimal_to_f64(holder.balance);
                                                     let balance_display = format_usd(holder.balance);
-                                                    let share = balance_f64 / total_supply * 100.0;
-                                                    let bar_width = (share * 2.0).min(100.0);
+                                                    let share = total_supply.map(|total| balance_f64 / total * 100.0);
+                                                    let bar_width = share.map(|share| (share * 2.0).min(100.0)).unwrap_or(0.0);
                                                     let addr = holder.address.clone();
                                                     view! {
                                                         <tr>
@@ -279,7 +310,7 @@ fn SupplyDynamicsTab() -> impl IntoView {
                                                                     <a href=format!("/address/{}", addr) style="color: var(--accent-cyan); text-decoration: none;" title=addr.clone()>
                                                                         {shorten_hash(&addr)}
                                                                     </a>
-                                                                    <a href=format!("https://filecoin.blockscout.com/address/{}", addr) target="_blank" style="color: var(--text-muted); text-decoration: none; font-size: 10px;" title="View on Blockscout">
+                                                                    <a href=explorer_address_url(&config().explorer_url, &addr) target="_blank" style="color: var(--text-muted); text-decoration: none; font-size: 10px;" title="View in explorer">
                                                                         <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                                                                             <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
                                                                             <polyline points="15 3 21 3 21 9"></polyline>
@@ -289,7 +320,7 @@ fn SupplyDynamicsTab() -> impl IntoView {
                                                                 </div>
                                                             </td>
                                                             <td style="font-family: monospace; color: var(--accent-cyan);">{balance_display}</td>
-                                                            <td style="color: var(--text-muted);">{format!("{:.2}%", normalize_zero(share))}</td>
+                                                            <td style="color: var(--text-muted);">{share.map(|share| format!("{:.2}%", normalize_zero(share))).unwrap_or_else(|| "—".to_string())}</td>
                                                             <td style="min-width: 80px; flex: 1;">
                                                                 <div style="background: var(--bg-tertiary); border-radius: 4px; height: 8px; width: 100%;">
                                                                     <div style=format!("background: var(--accent-cyan); border-radius: 4px; height: 100%; width: {}%;", bar_width)></div>
@@ -318,13 +349,69 @@ fn SupplyDynamicsTab() -> impl IntoView {
 // Risk Analysis Tab - Trove Health, Liquidation Risk
 // ============================================================================
 
+/// |z| at or above which the TCR z-score needle turns yellow, and red
+const ZSCORE_WARN: f64 = 1.0;
+const ZSCORE_ALERT: f64 = 2.0;
+
+/// Small gauge of the live TCR's z-score against its trailing window
+#[component]
+fn TcrZScoreGauge(
+    volatility: Resource<Option<Decimal>, Result<TcrVolatility, ServerFnError>>,
+) -> impl IntoView {
+    view! {
+        <Suspense fallback=move || view! { <div class="skeleton" style="height: 110px; width: 140px;"></div> }>
+            {move || volatility.get().map(|res| match res {
+                Ok(vol) => match vol.tcr_zscore {
+                    Some(z) => {
+                        let color = if z.abs() >= ZSCORE_ALERT {
+                            "var(--accent-red)"
+                        } else if z.abs() >= ZSCORE_WARN {
+                            "var(--accent-yellow)"
+                        } else {
+                            "var(--accent-cyan)"
+                        };
+                        view! {
+                            <div class="gauge-compact" title=format!("TCR vs {}-minute mean over {} snapshots", vol.window_mins, vol.samples)>
+                                <GaugeChart
+                                    value=z
+                                    min=-3.0
+                                    max=3.0
+                                    label="TCR Volatility (z)"
+                                    suffix="σ"
+                                    scale=["-3σ", "0", "+3σ"]
+                                    color=color
+                                />
+                            </div>
+                        }.into_view()
+                    }
+                    None => view! {
+                        <div class="gauge-compact gauge-pending">
+                            <div class="gauge-label">"TCR Volatility (z)"</div>
+                            <div class="gauge-label">{format!("Collecting history ({} snapshots)", vol.samples)}</div>
+                        </div>
+                    }.into_view(),
+                },
+                Err(_) => view! {}.into_view(),
+            })}
+        </Suspense>
+    }
+}
+
 #[component]
 fn RiskAnalysisTab() -> impl IntoView {
     let global = use_global_metrics();
+    let density = use_context::<AppState>().expect("AppState must be provided").density;
     let troves = create_resource(
-        || (),
-        |_| async move { get_troves(Some(50), None).await }
+        move || density.get(),
+        |density| async move { get_troves(Some(density.list_limit(50)), None, None, None, None).await }
+    );
+    let volatility = create_resource(
+        move || global.protocol.get().and_then(|res| res.ok()).and_then(|m| m.tcr),
+        |_| async move { get_tcr_volatility().await }
     );
+    let icr_distribution = create_resource(|| (), |_| async move { get_icr_distribution().await });
+    let unit = create_rw_signal(CollateralUnit::Fil);
+    let fil_price = move || global.protocol.get().and_then(|res| res.ok()).and_then(|m| implied_fil_price(&m));
 
     view! {
         // TCR Gauge - Prominent display at top
@@ -335,9 +422,11 @@ fn RiskAnalysisTab() -> impl IntoView {
                     global.protocol.get().map(|res| {
                         match res {
                             Ok(metrics) => {
-                                let tcr = decimal_to_f64(metrics.tcr);
+                                let Some(tcr) = metrics.tcr.map(decimal_to_f64) else {
+                                    return view! { <div style="text-align: center; color: var(--text-muted);">"TCR unavailable"</div> }.into_view();
+                                };
                                 view! {
-                                    <div style="display: flex; justify-content: center;">
+                                    <div style="display: flex; justify-content: center; align-items: center; gap: 24px; flex-wrap: wrap;">
                                         <GaugeChart
                                             value=tcr
                                             min=110.0
@@ -345,6 +434,7 @@ fn RiskAnalysisTab() -> impl IntoView {
                                             label="TCR"
                                             suffix="%"
                                         />
+                                        <TcrZScoreGauge volatility=volatility />
                                     </div>
                                 }.into_view()
                             }
@@ -368,10 +458,10 @@ fn RiskAnalysisTab() -> impl IntoView {
                                 // Show message if no active troves
                                 if total == 0 {
                                     return view! {
-                                        <div style="text-align: center; padding: 40px 20px; color: var(--text-muted);">
-                                            <div style="font-size: 14px; margin-bottom: 8px;">"No active troves"</div>
-                                            <div style="font-size: 12px;">"There are currently no open borrowing positions in the protocol."</div>
-                                        </div>
+                                        <EmptyState
+                                            title="No active troves"
+                                            description="There are currently no open borrowing positions in the protocol."
+                                        />
                                     }.into_view();
                                 }
 
@@ -485,13 +575,61 @@ fn RiskAnalysisTab() -> impl IntoView {
                                     </div>
                                 }.into_view()
                             }
-                            Err(_) => view! { <div>"Error loading troves"</div> }.into_view()
+                            Err(e) => view! {
+                                <DataLoadError error=error_message(&e) on_retry=move || troves.refetch() />
+                            }.into_view()
                         }
                     })
                 }}
             </Suspense>
         </div>
 
+        // ICR distribution, weighted by debt
+        <div class="card" style="margin-bottom: 24px;">
+            <h3 class="card-title" style="margin-bottom: 16px;">"ICR Distribution"</h3>
+            <Suspense fallback=move || view! { <div class="skeleton" style="height: 60px;"></div> }>
+                {move || {
+                    icr_distribution.get().map(|res| match res {
+                        Ok(buckets) => {
+                            let total_debt: f64 = buckets.iter().map(|b| decimal_to_f64(b.total_debt)).sum();
+                            if total_debt <= 0.0 {
+                                return view! {
+                                    <div style="color: var(--text-muted); font-size: 12px;">"No trove debt outstanding"</div>
+                                }.into_view();
+                            }
+                            // Lowest ICR bucket first: red through to green
+                            let colors = ["var(--accent-red)", "var(--accent-yellow)", "var(--chart-2)", "var(--color-info)", "var(--accent-green)"];
+                            let color = move |i: usize| colors[i.min(colors.len() - 1)];
+                            view! {
+                                <div style="display: flex; height: 24px; border-radius: 4px; overflow: hidden;">
+                                    {buckets.iter().enumerate().filter(|(_, b)| b.count > 0).map(|(i, b)| {
+                                        let pct = decimal_to_f64(b.total_debt) / total_debt * 100.0;
+                                        view! {
+                                            <div
+                                                style=format!("background: {}; width: {}%;", color(i), pct)
+                                                title=format!("{}: {} troves, {}", b.label(), b.count, format_usdfc(b.total_debt))
+                                            ></div>
+                                        }
+                                    }).collect_view()}
+                                </div>
+                                <div style="display: flex; justify-content: space-between; flex-wrap: wrap; gap: 8px; margin-top: 8px; font-size: 11px; color: var(--text-muted);">
+                                    {buckets.iter().enumerate().map(|(i, b)| view! {
+                                        <span>
+                                            <span style=format!("display: inline-block; width: 8px; height: 8px; border-radius: 2px; margin-right: 4px; background: {};", color(i))></span>
+                                            {format!("{} · {} troves · {:.0}%", b.label(), b.count, decimal_to_f64(b.total_debt) / total_debt * 100.0)}
+                                        </span>
+                                    }).collect_view()}
+                                </div>
+                            }.into_view()
+                        }
+                        Err(e) => view! {
+                            <DataLoadError error=error_message(&e) on_retry=move || icr_distribution.refetch() />
+                        }.into_view()
+                    })
+                }}
+            </Suspense>
+        </div>
+
         // Troves Table (sorted by risk)
         <div class="card">
             <div class="card-header">
@@ -499,9 +637,12 @@ fn RiskAnalysisTab() -> impl IntoView {
                     <h3 class="card-title">"Active Troves"</h3>
                     <p class="card-subtitle">"Sorted by collateral ratio (lowest first)"</p>
                 </div>
-                <button class="btn btn-secondary" on:click=move |_| troves.refetch()>
-                    "Refresh"
-                </button>
+                <div style="display: flex; align-items: center; gap: 8px;">
+                    <CollateralUnitToggle unit=unit />
+                    <button class="btn btn-secondary" on:click=move |_| troves.refetch()>
+                        "Refresh"
+                    </button>
+                </div>
             </div>
             <div class="table-responsive">
                 <div class="table-container">
@@ -509,7 +650,7 @@ fn RiskAnalysisTab() -> impl IntoView {
                         <thead>
                             <tr>
                                 <th>"Address"</th>
-                                <th>"FIL Collateral"</th>
+                                <th>{move || unit.get().column_header()}</th>
                                 <th>"USDFC Debt"</th>
                                 <th>"ICR"</th>
                                 <th class="hide-mobile">"Status"</th>
@@ -525,7 +666,7 @@ fn RiskAnalysisTab() -> impl IntoView {
                                             Ok(mut all_troves) => {
                                                 if all_troves.is_empty() {
                                                     view! {
-                                                        <tr><td colspan="5" style="text-align: center; padding: 20px; color: var(--text-muted);">"No active troves"</td></tr>
+                                                        <EmptyTableRow colspan=5 title="No active troves" />
                                                     }.into_view()
                                                 } else {
                                                     // Sort by ICR ascending (riskiest first)
@@ -545,7 +686,11 @@ fn RiskAnalysisTab() -> impl IntoView {
                                                         } else {
                                                             ("color: var(--accent-green);", "Safe")
                                                         };
-                                                        let collateral = format_fil(t.collateral);
+                                                        let trove_collateral = t.collateral;
+                                                        let collateral = move || {
+                                                            let usd = fil_price().map(|price| trove_collateral * price);
+                                                            unit.get().format(trove_collateral, usd)
+                                                        };
                                                         let debt = format_usdfc(t.debt);
                                                         let short_addr = format!("{}...{}", &t.address[..8], &t.address[t.address.len()-6..]);
                                                         view! {
@@ -555,7 +700,7 @@ fn RiskAnalysisTab() -> impl IntoView {
                                                                         <a href=format!("/address/{}", t.address) style="color: var(--accent-cyan); text-decoration: none;" title=t.address.clone()>
                                                                             {short_addr}
                                                                         </a>
-                                                                        <a href=format!("https://filecoin.blockscout.com/address/{}", t.address) target="_blank" style="color: var(--text-muted); text-decoration: none; font-size: 10px;" title="View on Blockscout">
+                                                                        <a href=explorer_address_url(&config().explorer_url, &t.address) target="_blank" style="color: var(--text-muted); text-decoration: none; font-size: 10px;" title="View in explorer">
                                                                             <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                                                                                 <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
                                                                                 <polyline points="15 3 21 3 21 9"></polyline>
@@ -574,7 +719,9 @@ fn RiskAnalysisTab() -> impl IntoView {
                                                 }
                                             }
                                             Err(err) => view! {
-                                                <tr><td colspan="5" style="text-align: center; padding: 20px; color: var(--accent-red);">{err.to_string()}</td></tr>
+                                                <tr><td colspan="5">
+                                                    <DataLoadError error=error_message(&err) on_retry=move || troves.refetch() />
+                                                </td></tr>
                                             }.into_view()
                                         }
                                     })
@@ -632,9 +779,13 @@ fn PoolActivityTab() -> impl IntoView {
                             let ratio = if withdrawal_vol > 0.0 { deposit_vol / withdrawal_vol } else { 0.0 };
 
                             // Pool utilization
-                            let pool_balance = decimal_to_f64(metrics.stability_pool_balance);
-                            let total_supply = decimal_to_f64(metrics.total_supply).max(1.0);
-                            let utilization = pool_balance / total_supply * 100.0;
+                            // Unknown when either the pool balance or the supply could not be fetched
+                            let utilization = metrics.stability_pool_balance.zip(metrics.total_supply)
+                                .map(|(pool, supply)| decimal_to_f64(pool) / decimal_to_f64(supply).max(1.0) * 100.0);
+                            let utilization_label = utilization
+                                .map(|u| format!("{:.1}%", normalize_zero(u)))
+                                .unwrap_or_else(|| "--".to_string());
+                            let utilization = utilization.unwrap_or(0.0);
 
                             // Flow direction indicator
                             let (flow_text, flow_color) = if net_flow > 1000.0 {
@@ -697,16 +848,19 @@ fn PoolActivityTab() -> impl IntoView {
                                             )
                                         >
                                             {if utilization > 10.0 {
-                                                view! { <span style="color: white; font-size: 11px; font-weight: 600;">{format!("{:.1}%", normalize_zero(utilization))}</span> }.into_view()
+                                                view! { <span style="color: white; font-size: 11px; font-weight: 600;">{utilization_label.clone()}</span> }.into_view()
                                             } else { view! {}.into_view() }}
                                         </div>
                                         {if utilization <= 10.0 {
-                                            view! { <span style="color: var(--text-muted); font-size: 12px;">{format!("{:.1}%", normalize_zero(utilization))}</span> }.into_view()
+                                            view! { <span style="color: var(--text-muted); font-size: 12px;">{utilization_label}</span> }.into_view()
                                         } else { view! {}.into_view() }}
                                     </div>
                                 </div>
                             }.into_view()
                         }
+                        (Some(Err(e)), _) | (_, Some(Err(e))) => view! {
+                            <DataLoadError error=e.to_string() on_retry=move || pool_transfers.refetch() />
+                        }.into_view(),
                         _ => view! { <div style="color: var(--text-muted);">"Loading pool data..."</div> }.into_view()
                     }
                 }}
@@ -766,7 +920,11 @@ fn PoolActivityTab() -> impl IntoView {
                                             Ok(txs) => {
                                                 if txs.is_empty() {
                                                     view! {
-                                                        <tr><td colspan="5" style="text-align: center; padding: 20px; color: var(--text-muted);">"No pool activity"</td></tr>
+                                                        <EmptyTableRow
+                                                            colspan=5
+                                                            title="No pool activity"
+                                                            description="No Stability Pool deposits or withdrawals in the latest transfers."
+                                                        />
                                                     }.into_view()
                                                 } else {
                                                     let stability_pool_addr = "0x791ad78bbc58324089d3e0a8689e7d045b9592b5".to_lowercase();
@@ -782,7 +940,7 @@ fn PoolActivityTab() -> impl IntoView {
                                                         view! {
                                                             <tr>
                                                                 <td class="hide-mobile" style="font-family: monospace; font-size: 12px;">
-                                                                    <a href=format!("https://filecoin.blockscout.com/tx/{}", tx.hash) target="_blank" style="color: var(--text-primary); text-decoration: none;" title=tx.hash.clone()>
+                                                                    <a href=explorer_tx_url(&config().explorer_url, &tx.hash) target="_blank" style="color: var(--text-primary); text-decoration: none;" title=tx.hash.clone()>
                                                                         {shorten_hash(&tx.hash)}
                                                                     </a>
                                                                 </td>
@@ -793,7 +951,7 @@ fn PoolActivityTab() -> impl IntoView {
                                                                         <a href=format!("/address/{}", user_addr) style="color: var(--accent-cyan); text-decoration: none;" title=user_addr.clone()>
                                                                             {shorten_hash(&user_addr)}
                                                                         </a>
-                                                                        <a href=format!("https://filecoin.blockscout.com/address/{}", user_addr) target="_blank" style="color: var(--text-muted); text-decoration: none; font-size: 10px;" title="View on Blockscout">
+                                                                        <a href=explorer_address_url(&config().explorer_url, &user_addr) target="_blank" style="color: var(--text-muted); text-decoration: none; font-size: 10px;" title="View in explorer">
                                                                             <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                                                                                 <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
                                                                                 <polyline points="15 3 21 3 21 9"></polyline>
@@ -809,7 +967,9 @@ fn PoolActivityTab() -> impl IntoView {
                                                 }
                                             }
                                             Err(err) => view! {
-                                                <tr><td colspan="5" style="text-align: center; padding: 20px; color: var(--accent-red);">{err.to_string()}</td></tr>
+                                                <tr><td colspan="5">
+                                                    <DataLoadError error=err.to_string() on_retry=move || pool_transfers.refetch() />
+                                                </td></tr>
                                             }.into_view()
                                         }
                                     })
@@ -827,6 +987,125 @@ fn PoolActivityTab() -> impl IntoView {
 // Loading Component
 // ============================================================================
 
+// ============================================================================
+// Fees Tab - Borrowing and redemption fee income
+// ============================================================================
+
+/// Lookback and bucket size for a fees time range
+fn fee_window(range: TimeRange) -> (ChartLookback, ChartResolution) {
+    match range {
+        TimeRange::Hour24 => (ChartLookback::Day1, ChartResolution::H1),
+        TimeRange::Day30 => (ChartLookback::Month1, ChartResolution::D1),
+        TimeRange::Day90 => (ChartLookback::Month3, ChartResolution::W1),
+        _ => (ChartLookback::Week1, ChartResolution::H12),
+    }
+}
+
+#[component]
+fn FeesTab() -> impl IntoView {
+    let time_range = create_rw_signal(TimeRange::Day7);
+
+    let fees = create_resource(
+        move || fee_window(time_range.get()),
+        |(lookback, resolution)| async move { get_fee_analytics(lookback, resolution).await }
+    );
+
+    // Unknown values are shown as "--": the fee events could not be decoded
+    let show = |value: Option<rust_decimal::Decimal>| value.map(format_amount).unwrap_or_else(|| "--".to_string());
+
+    view! {
+        <div class="card" style="margin-bottom: 24px;">
+            <div class="card-header">
+                <div>
+                    <h3 class="card-title">"Protocol Fee Income"</h3>
+                    <p class="card-subtitle">"Borrowing fees (USDFC) and redemption fees (FIL) from contract events"</p>
+                </div>
+                <TimeRangeSelector
+                    selected=time_range
+                    options=vec![TimeRange::Hour24, TimeRange::Day7, TimeRange::Day30, TimeRange::Day90]
+                />
+            </div>
+            <Suspense fallback=move || view! { <div class="skeleton" style="height: 100px;"></div> }>
+                {move || fees.get().map(|res| match res {
+                    Ok(data) => view! {
+                        <div class="grid-4">
+                            <div>
+                                <div class="metric-label">"Borrowing Fees"</div>
+                                <div class="metric-value cyan">{show(data.total_borrowing_fees)}</div>
+                                <div style="color: var(--text-muted); font-size: 11px;">"USDFC"</div>
+                            </div>
+                            <div>
+                                <div class="metric-label">"Redemption Fees"</div>
+                                <div class="metric-value purple">{show(data.total_redemption_fees_fil)}</div>
+                                <div style="color: var(--text-muted); font-size: 11px;">"FIL"</div>
+                            </div>
+                            <div>
+                                <div class="metric-label">"Borrowing Events"</div>
+                                <div class="metric-value green">{data.borrowing_events}</div>
+                            </div>
+                            <div>
+                                <div class="metric-label">"Redemptions"</div>
+                                <div class="metric-value yellow">{data.redemption_events}</div>
+                            </div>
+                        </div>
+                    }.into_view(),
+                    Err(e) => view! {
+                        <DataLoadError error=e.to_string() on_retry=move || fees.refetch() />
+                    }.into_view(),
+                })}
+            </Suspense>
+        </div>
+
+        <div class="card">
+            <div class="card-header">
+                <div>
+                    <h3 class="card-title">"Fees by Period"</h3>
+                    <p class="card-subtitle">"\"--\" marks periods whose fee events could not be decoded"</p>
+                </div>
+            </div>
+            <div class="table-responsive">
+                <div class="table-container">
+                    <table class="table">
+                        <thead>
+                            <tr>
+                                <th>"Period"</th>
+                                <th>"Borrowing (USDFC)"</th>
+                                <th>"Cumulative"</th>
+                                <th>"Redemption (FIL)"</th>
+                                <th class="hide-mobile">"Cumulative"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            <Suspense fallback=move || view! {
+                                <tr><td colspan="5" style="text-align: center; padding: 20px;">"Loading..."</td></tr>
+                            }>
+                                {move || fees.get().map(|res| match res {
+                                    Ok(data) if data.buckets.is_empty() => view! {
+                                        <EmptyTableRow colspan=5 title="No fee data" />
+                                    }.into_view(),
+                                    // Newest period first
+                                    Ok(data) => data.buckets.iter().rev().map(|bucket| view! {
+                                        <tr>
+                                            <td style="color: var(--text-muted);">{format_timestamp(bucket.timestamp.max(0) as u64)}</td>
+                                            <td>{show(bucket.borrowing_fees)}</td>
+                                            <td>{show(bucket.cumulative_borrowing_fees)}</td>
+                                            <td>{show(bucket.redemption_fees_fil)}</td>
+                                            <td class="hide-mobile">{show(bucket.cumulative_redemption_fees_fil)}</td>
+                                        </tr>
+                                    }).collect_view(),
+                                    Err(_) => view! {
+                                        <EmptyTableRow colspan=5 title="Fee data unavailable" />
+                                    }.into_view(),
+                                })}
+                            </Suspense>
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    }
+}
+
 #[component]
 fn MetricsLoading() -> impl IntoView {
     view! {
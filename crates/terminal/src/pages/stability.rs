@@ -1,7 +1,7 @@
 use leptos::*;
-use rust_decimal::prelude::ToPrimitive;
-use usdfc_api::{get_protocol_metrics, get_stability_pool_transfers};
-use usdfc_core::format::{format_value, format_amount, format_timestamp, shorten_hash};
+use usdfc_api::{get_protocol_metrics, get_stability_pool_analytics};
+use usdfc_core::config::config;
+use usdfc_core::format::{format_value, format_amount, format_timestamp, shorten_hash, format_count, explorer_address_url, explorer_tx_url};
 
 #[component]
 pub fn StabilityPool() -> impl IntoView {
@@ -10,9 +10,9 @@ pub fn StabilityPool() -> impl IntoView {
         |_| async move { get_protocol_metrics().await }
     );
     
-    let pool_transfers = create_resource(
+    let analytics = create_resource(
         || (),
-        |_| async move { get_stability_pool_transfers(Some(20)).await }
+        |_| async move { get_stability_pool_analytics().await }
     );
 
     view! {
@@ -27,12 +27,19 @@ pub fn StabilityPool() -> impl IntoView {
                     metrics.get().map(|res| {
                         match res {
                             Ok(m) => {
-                                let pool_balance = format_value(m.stability_pool_balance);
-                                let total_supply = format_value(m.total_supply);
-                                
-                                let supply_f64: f64 = m.total_supply.to_string().parse().unwrap_or(0.0);
-                                let pool_f64: f64 = m.stability_pool_balance.to_string().parse().unwrap_or(0.0);
-                                let coverage = if supply_f64 > 0.0 { pool_f64 / supply_f64 * 100.0 } else { 0.0 };
+                                // Values whose read failed show as unknown
+                                let pool_balance = m.stability_pool_balance.map(format_value).unwrap_or_else(|| "--".to_string());
+                                let total_supply = m.total_supply.map(format_value).unwrap_or_else(|| "--".to_string());
+
+                                let coverage = match (m.stability_pool_balance, m.total_supply) {
+                                    (Some(pool), Some(supply)) => {
+                                        let supply_f64: f64 = supply.to_string().parse().unwrap_or(0.0);
+                                        let pool_f64: f64 = pool.to_string().parse().unwrap_or(0.0);
+                                        let coverage = if supply_f64 > 0.0 { pool_f64 / supply_f64 * 100.0 } else { 0.0 };
+                                        format!("{:.1}%", coverage)
+                                    }
+                                    _ => "--".to_string(),
+                                };
                                 
                                 view! {
                                     <div class="grid-3" style="margin-bottom: 24px;">
@@ -46,7 +53,7 @@ pub fn StabilityPool() -> impl IntoView {
                                         </div>
                                         <div class="card">
                                             <div class="metric-label">"Coverage Ratio"</div>
-                                            <div class="metric-value yellow">{format!("{:.1}%", coverage)}</div>
+                                            <div class="metric-value yellow">{coverage}</div>
                                         </div>
                                     </div>
                                 }.into_view()
@@ -85,58 +92,52 @@ pub fn StabilityPool() -> impl IntoView {
             // Pool Activity Summary
             <Suspense fallback=move || view! { <div></div> }>
                 {move || {
-                    pool_transfers.get().map(|res| {
+                    analytics.get().map(|res| {
                         match res {
-                            Ok(txs) => {
-                                // Calculate deposits (transfers TO pool) and withdrawals (transfers FROM pool)
-                                let stability_pool_addr = "0x791ad78bbc58324089d3e0a8689e7d045b9592b5".to_lowercase();
-
-                                let deposits: f64 = txs.iter()
-                                    .filter(|tx| tx.to.to_lowercase() == stability_pool_addr)
-                                    .map(|tx| tx.amount.to_f64().unwrap_or(0.0))
-                                    .sum();
-                                let withdrawals: f64 = txs.iter()
-                                    .filter(|tx| tx.from.to_lowercase() == stability_pool_addr)
-                                    .map(|tx| tx.amount.to_f64().unwrap_or(0.0))
-                                    .sum();
-                                let deposit_count = txs.iter()
-                                    .filter(|tx| tx.to.to_lowercase() == stability_pool_addr)
-                                    .count();
-                                let withdrawal_count = txs.iter()
-                                    .filter(|tx| tx.from.to_lowercase() == stability_pool_addr)
-                                    .count();
-
-                                let format_volume = |v: f64| {
-                                    if v >= 1_000_000.0 { format!("{:.2}M", v / 1_000_000.0) }
-                                    else if v >= 1_000.0 { format!("{:.1}K", v / 1_000.0) }
-                                    else { format!("{:.0}", v) }
+                            Ok(a) => {
+                                let window_label = |hours: u64| {
+                                    if hours.is_multiple_of(24) { format!("{}d", hours / 24) } else { format!("{}h", hours) }
                                 };
 
                                 view! {
                                     <div class="card" style="margin-bottom: 24px;">
-                                        <h3 class="card-title" style="margin-bottom: 16px;">"Recent Activity"</h3>
-                                        <div class="grid-4">
-                                            <div>
-                                                <div class="metric-label">"Deposits"</div>
-                                                <div class="metric-value green">{format_volume(deposits)}</div>
-                                                <div style="color: var(--text-muted); font-size: 12px;">{format!("{} txs", deposit_count)}</div>
-                                            </div>
-                                            <div>
-                                                <div class="metric-label">"Withdrawals"</div>
-                                                <div class="metric-value red">{format_volume(withdrawals)}</div>
-                                                <div style="color: var(--text-muted); font-size: 12px;">{format!("{} txs", withdrawal_count)}</div>
-                                            </div>
-                                            <div>
-                                                <div class="metric-label">"Net Flow"</div>
-                                                <div class={if deposits >= withdrawals { "metric-value green" } else { "metric-value red" }}>
-                                                    {if deposits >= withdrawals { "+" } else { "" }}{format_volume(deposits - withdrawals)}
-                                                </div>
-                                            </div>
-                                            <div>
-                                                <div class="metric-label">"Total Activity"</div>
-                                                <div class="metric-value cyan">{format_volume(deposits + withdrawals)}</div>
-                                            </div>
+                                        <div class="card-header" style="margin-bottom: 16px;">
+                                            <h3 class="card-title">"Pool Flows"</h3>
+                                            <span style="color: var(--text-muted); font-size: 12px;">
+                                                {format!("{} active depositors", format_count(a.depositor_count as usize))}
+                                            </span>
                                         </div>
+                                        <table class="table">
+                                            <thead>
+                                                <tr>
+                                                    <th>"Window"</th>
+                                                    <th>"Deposits"</th>
+                                                    <th>"Withdrawals"</th>
+                                                    <th>"Net Flow"</th>
+                                                </tr>
+                                            </thead>
+                                            <tbody>
+                                                {a.windows.into_iter().map(|w| {
+                                                    let positive = w.net_flow >= rust_decimal::Decimal::ZERO;
+                                                    view! {
+                                                        <tr>
+                                                            <td><span class="timeframe-badge">{window_label(w.window_hours)}</span></td>
+                                                            <td style="font-family: monospace;">
+                                                                {format_value(w.deposits)}
+                                                                <span style="color: var(--text-muted); font-size: 12px;">{format!(" ({} txs)", w.deposit_count)}</span>
+                                                            </td>
+                                                            <td style="font-family: monospace;">
+                                                                {format_value(w.withdrawals)}
+                                                                <span style="color: var(--text-muted); font-size: 12px;">{format!(" ({} txs)", w.withdrawal_count)}</span>
+                                                            </td>
+                                                            <td style={if positive { "font-family: monospace; color: var(--accent-green);" } else { "font-family: monospace; color: var(--accent-red);" }}>
+                                                                {if positive { "+" } else { "" }}{format_value(w.net_flow)}
+                                                            </td>
+                                                        </tr>
+                                                    }
+                                                }).collect_view()}
+                                            </tbody>
+                                        </table>
                                     </div>
                                 }.into_view()
                             }
@@ -154,7 +155,7 @@ pub fn StabilityPool() -> impl IntoView {
                     </div>
                     <button
                         class="btn btn-secondary"
-                        on:click=move |_| pool_transfers.refetch()
+                        on:click=move |_| analytics.refetch()
                     >
                         "Refresh"
                     </button>
@@ -176,9 +177,10 @@ pub fn StabilityPool() -> impl IntoView {
                                 <tr><td colspan="6" style="text-align: center; padding: 20px;">"Loading..."</td></tr>
                             }>
                                 {move || {
-                                    pool_transfers.get().map(|res| {
+                                    analytics.get().map(|res| {
                                         match res {
-                                            Ok(txs) => {
+                                            Ok(a) => {
+                                                let txs = a.recent_transfers;
                                                 if txs.is_empty() {
                                                     view! {
                                                         <tr><td colspan="6" style="text-align: center; padding: 20px; color: var(--text-muted);">"No transfers found"</td></tr>
@@ -191,7 +193,7 @@ pub fn StabilityPool() -> impl IntoView {
                                                             <tr>
                                                                 <td style="font-family: monospace;">
                                                                     <a
-                                                                        href=format!("https://filecoin.blockscout.com/tx/{}", tx.hash)
+                                                                        href=explorer_tx_url(&config().explorer_url, &tx.hash)
                                                                         target="_blank"
                                                                         style="color: var(--text-primary); text-decoration: none;"
                                                                         title=tx.hash.clone()
@@ -203,7 +205,7 @@ pub fn StabilityPool() -> impl IntoView {
                                                                 <td style="font-family: monospace;">{amount}</td>
                                                                 <td style="font-family: monospace;">
                                                                     <a
-                                                                        href=format!("https://filecoin.blockscout.com/address/{}", tx.from)
+                                                                        href=explorer_address_url(&config().explorer_url, &tx.from)
                                                                         target="_blank"
                                                                         style="color: var(--text-primary); text-decoration: none;"
                                                                         title=tx.from.clone()
@@ -213,7 +215,7 @@ pub fn StabilityPool() -> impl IntoView {
                                                                 </td>
                                                                 <td style="font-family: monospace;">
                                                                     <a
-                                                                        href=format!("https://filecoin.blockscout.com/address/{}", tx.to)
+                                                                        href=explorer_address_url(&config().explorer_url, &tx.to)
                                                                         target="_blank"
                                                                         style="color: var(--text-primary); text-decoration: none;"
                                                                         title=tx.to.clone()
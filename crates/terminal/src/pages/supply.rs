@@ -1,7 +1,8 @@
 use leptos::*;
-use rust_decimal::prelude::ToPrimitive;
+use crate::components::error_message;
 use usdfc_api::{get_protocol_metrics, get_recent_transactions, get_holder_count, get_top_holders};
-use usdfc_core::format::{format_value, format_amount, format_timestamp, shorten_hash};
+use usdfc_core::config::config;
+use usdfc_core::format::{format_value, format_amount, format_timestamp, shorten_hash, explorer_address_url, explorer_tx_url};
 
 #[component]
 pub fn SupplyMetrics() -> impl IntoView {
@@ -12,7 +13,7 @@ pub fn SupplyMetrics() -> impl IntoView {
     
     let recent_tx = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(25)).await }
+        |_| async move { get_recent_transactions(Some(25), None).await }
     );
 
     let holder_count = create_resource(
@@ -37,9 +38,10 @@ pub fn SupplyMetrics() -> impl IntoView {
                     metrics.get().map(|res| {
                         match res {
                             Ok(m) => {
-                                let total_supply = format_value(m.total_supply);
-                                let circulating = format_value(m.circulating_supply);
-                                let treasury = format_value(m.treasury_balance);
+                                // Values whose read failed show as unknown
+                                let total_supply = m.total_supply.map(format_value).unwrap_or_else(|| "--".to_string());
+                                let circulating = m.circulating_supply.map(format_value).unwrap_or_else(|| "--".to_string());
+                                let stability_pool = m.stability_pool_balance.map(format_value).unwrap_or_else(|| "--".to_string());
 
                                 // Get holder count
                                 let holders = holder_count.get()
@@ -58,8 +60,8 @@ pub fn SupplyMetrics() -> impl IntoView {
                                             <div class="metric-value green">{circulating}</div>
                                         </div>
                                         <div class="card">
-                                            <div class="metric-label">"Treasury"</div>
-                                            <div class="metric-value purple">{treasury}</div>
+                                            <div class="metric-label">"Stability Pool"</div>
+                                            <div class="metric-value purple">{stability_pool}</div>
                                         </div>
                                         <div class="card">
                                             <div class="metric-label">"Token Holders"</div>
@@ -71,7 +73,7 @@ pub fn SupplyMetrics() -> impl IntoView {
                             Err(err) => view! {
                                 <div class="card">
                                     <div class="metric-label">"Metrics Error"</div>
-                                    <div class="metric-value red">{err.to_string()}</div>
+                                    <div class="metric-value red">{error_message(&err)}</div>
                                 </div>
                             }.into_view()
                         }
@@ -116,22 +118,18 @@ pub fn SupplyMetrics() -> impl IntoView {
                                                         <tr><td colspan="4" style="text-align: center; padding: 20px; color: var(--text-muted);">"No holder data available"</td></tr>
                                                     }.into_view()
                                                 } else {
-                                                    // Calculate total for share percentage
-                                                    let total: f64 = holders.iter().map(|h| {
-                                                        h.balance.to_f64().unwrap_or(0.0)
-                                                    }).sum();
-
                                                     holders.iter().enumerate().map(|(i, holder)| {
-                                                        let balance_f64 = holder.balance.to_f64().unwrap_or(0.0);
                                                         let balance_display = format_value(holder.balance);
-                                                        let share = if total > 0.0 { balance_f64 / total * 100.0 } else { 0.0 };
+                                                        let share = holder.supply_pct
+                                                            .map(|pct| format!("{:.1}%", pct))
+                                                            .unwrap_or_else(|| "—".to_string());
                                                         let addr = holder.address.clone();
                                                         view! {
                                                             <tr>
                                                                 <td style="color: var(--text-muted);">{format!("#{}", i + 1)}</td>
                                                                 <td style="font-family: monospace; font-size: 12px;">
                                                                     <a
-                                                                        href=format!("https://filecoin.blockscout.com/address/{}", addr)
+                                                                        href=explorer_address_url(&config().explorer_url, &addr)
                                                                         target="_blank"
                                                                         style="color: var(--text-primary); text-decoration: none;"
                                                                         title=addr.clone()
@@ -140,7 +138,7 @@ pub fn SupplyMetrics() -> impl IntoView {
                                                                     </a>
                                                                 </td>
                                                                 <td style="font-family: monospace; color: var(--accent-cyan);">{balance_display}</td>
-                                                                <td style="color: var(--text-muted);">{format!("{:.1}%", share)}</td>
+                                                                <td style="color: var(--text-muted);" title="Share of total supply">{share}</td>
                                                             </tr>
                                                         }
                                                     }).collect_view()
@@ -205,7 +203,7 @@ pub fn SupplyMetrics() -> impl IntoView {
                                                             <tr>
                                                                 <td style="font-family: monospace;">
                                                                     <a
-                                                                        href=format!("https://filecoin.blockscout.com/tx/{}", tx.hash)
+                                                                        href=explorer_tx_url(&config().explorer_url, &tx.hash)
                                                                         target="_blank"
                                                                         style="color: var(--text-primary); text-decoration: none;"
                                                                         title=tx.hash.clone()
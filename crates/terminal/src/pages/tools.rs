@@ -6,6 +6,7 @@
 use leptos::*;
 use crate::components::tabs::{TabNav, TabContent, Tab};
 use crate::components::icons::*;
+use crate::pages::alerts::AlertHistory;
 use usdfc_core::config::config;
 use usdfc_api::get_recent_transactions;
 use crate::global_metrics::use_global_metrics;
@@ -412,7 +413,7 @@ fn AlertsTab() -> impl IntoView {
 
     let transactions = create_resource(
         || (),
-        |_| async move { get_recent_transactions(Some(25)).await }
+        |_| async move { get_recent_transactions(Some(25), None).await }
     );
 
     view! {
@@ -438,8 +439,8 @@ fn AlertsTab() -> impl IntoView {
                         let mut alerts: Vec<View> = Vec::new();
 
                         // Check TCR from global metrics
-                        if let Some(Ok(m)) = global.protocol.get() {
-                            let tcr_f64 = decimal_to_f64(m.tcr);
+                        if let Some(tcr) = global.protocol.get().and_then(|r| r.ok()).and_then(|m| m.tcr) {
+                            let tcr_f64 = decimal_to_f64(tcr);
                             if tcr_f64 < 125.0 {
                                 alerts.push(view! {
                                     <div class="alert-card danger">
@@ -529,5 +530,7 @@ fn AlertsTab() -> impl IntoView {
                 </div>
             </div>
         </div>
+
+        <AlertHistory />
     }
 }
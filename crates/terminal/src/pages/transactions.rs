@@ -1,12 +1,25 @@
 use leptos::*;
+use crate::app::AppState;
 use crate::components::icons::*;
 use crate::components::PaginationWithSize;
-use usdfc_api::{get_recent_transactions, get_address_info};
-use usdfc_core::format::{format_timestamp, format_timestamp_full, format_balance, shorten_hash, format_volume, format_amount, decimal_to_f64, format_count};
+use usdfc_api::{get_recent_transactions, get_address_info, get_address_notes};
+use usdfc_core::config::config;
+use usdfc_core::format::{format_duration_ago, format_timestamp, format_timestamp_full, format_balance, shorten_hash, format_volume, format_amount_with, decimal_to_f64, format_count, explorer_address_url, explorer_tx_url};
 use usdfc_core::types::Transaction;
+use crate::pages::address::address_tag;
 
 const DEFAULT_PAGE_SIZE: u32 = 20;
 
+/// Decimals shown for amounts in the transaction table
+const TABLE_AMOUNT_DECIMALS: usize = 2;
+
+/// Decimals shown for the amount in the transaction detail modal
+const DETAIL_AMOUNT_DECIMALS: usize = 6;
+
+/// localStorage key holding the timestamp of the previous visit
+#[cfg(feature = "hydrate")]
+const LAST_SEEN_KEY: &str = "usdfc_tx_last_seen";
+
 #[component]
 pub fn TransactionSearch() -> impl IntoView {
     // Search and filter state
@@ -29,12 +42,45 @@ pub fn TransactionSearch() -> impl IntoView {
     // Modal state for transaction detail
     let (selected_tx, set_selected_tx) = create_signal(None::<Transaction>);
 
-    // Fetch more transactions for pagination (100 instead of 50)
+    // "Since last visit" highlighting: previous visit timestamp and dismissal state
+    let last_seen = create_rw_signal(None::<u64>);
+    let highlight_cleared = create_rw_signal(false);
+
+    // Read the previous visit from localStorage and record this one (client only)
+    #[cfg(feature = "hydrate")]
+    create_effect(move |_| {
+        let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) else {
+            return;
+        };
+        let previous = storage
+            .get_item(LAST_SEEN_KEY)
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse::<u64>().ok());
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        let _ = storage.set_item(LAST_SEEN_KEY, &now.to_string());
+        last_seen.set(previous);
+    });
+
+    // Fetch more transactions for pagination (100 instead of 50, halved in compact mode)
+    let density = use_context::<AppState>().expect("AppState must be provided").density;
     let transactions = create_resource(
-        || (),
-        |_| async move { get_recent_transactions(Some(100)).await }
+        move || (last_seen.get(), density.get()),
+        |(since, density)| async move { get_recent_transactions(Some(density.list_limit(100)), since).await }
     );
 
+    // Operator notes for labelling from/to addresses
+    let address_notes = create_resource(|| (), |_| async move { get_address_notes().await });
+
+    let new_count = move || {
+        transactions.with(|res| {
+            res.as_ref()
+                .and_then(|r| r.as_ref().ok())
+                .map(|txs| txs.iter().filter(|tx| tx.is_new).count())
+                .unwrap_or(0)
+        })
+    };
+
     // Real-time address lookup
     let address_info = create_resource(
         move || search_address.get(),
@@ -228,13 +274,13 @@ pub fn TransactionSearch() -> impl IntoView {
                                                     "View Transactions"
                                                 </button>
                                                 <a
-                                                    href=format!("https://filecoin.blockscout.com/address/{}", &info.address)
+                                                    href=explorer_address_url(&config().explorer_url, &info.address)
                                                     target="_blank"
                                                     class="btn btn-secondary"
                                                     style="font-size: 12px; padding: 6px 12px;"
                                                 >
                                                     <ExternalLinkIcon />
-                                                    "Blockscout"
+                                                    "Explorer"
                                                 </a>
                                             </div>
                                         </div>
@@ -464,13 +510,19 @@ pub fn TransactionSearch() -> impl IntoView {
                 }
             }}
 
-            // Transactions Table
-            <div class="card">
+            // Transactions Table (any interaction dismisses the "new" highlight)
+            <div class="card" on:click=move |_| highlight_cleared.set(true)>
                 <div class="card-header">
                     <div>
                         <h3 class="card-title">"USDFC Transfers"</h3>
                         <p class="card-subtitle">"Click on addresses to filter by sender/receiver"</p>
                     </div>
+                    {move || {
+                        let count = new_count();
+                        (count > 0 && !highlight_cleared.get()).then(|| view! {
+                            <span class="tx-new-badge">{format!("{} new", count)}</span>
+                        })
+                    }}
                 </div>
                 <div class="table-container">
                     <table class="table">
@@ -550,20 +602,26 @@ pub fn TransactionSearch() -> impl IntoView {
                                                     }.into_view()
                                                 } else {
                                                     page_items.into_iter().map(|tx| {
-                                                        let time_ago = format_timestamp(tx.timestamp);
-                                                        let amount_str = format_amount(tx.amount);
+                                                        let time_ago = format_duration_ago(tx.timestamp as i64, get_current_timestamp());
+                                                        let full_time = format_timestamp(tx.timestamp);
+                                                        let amount_str = format_amount_with(tx.amount, TABLE_AMOUNT_DECIMALS, false);
                                                         let tx_type_class = tx.tx_type.css_class().to_string();
                                                         let tx_type_str = tx.tx_type.as_str().to_string();
                                                         let from_addr = tx.from.clone();
                                                         let to_addr = tx.to.clone();
                                                         let from_for_modal = tx.from.clone();
                                                         let to_for_modal = tx.to.clone();
+                                                        let (from_tag, to_tag) = address_notes.with(|notes| {
+                                                            let notes = notes.as_ref().and_then(|r| r.as_ref().ok());
+                                                            (address_tag(&tx.from, notes), address_tag(&tx.to, notes))
+                                                        });
                                                         let hash_display = tx.hash.clone();
+                                                        let is_new = tx.is_new;
                                                         let tx_for_modal = tx;
 
                                                         view! {
-                                                            <tr>
-                                                                <td>{time_ago}</td>
+                                                            <tr class:tx-new=move || is_new && !highlight_cleared.get()>
+                                                                <td title=full_time>{time_ago}</td>
                                                                 <td><span class={tx_type_class}>{tx_type_str}</span></td>
                                                                 <td style="font-family: monospace;">{amount_str}</td>
                                                                 <td style="font-family: monospace; font-size: 11px;">
@@ -576,10 +634,10 @@ pub fn TransactionSearch() -> impl IntoView {
                                                                             {shorten_hash(&from_addr)}
                                                                         </a>
                                                                         <a
-                                                                            href=format!("https://filecoin.blockscout.com/address/{}", from_for_modal)
+                                                                            href=explorer_address_url(&config().explorer_url, &from_for_modal)
                                                                             target="_blank"
                                                                             style="color: var(--text-muted); text-decoration: none; font-size: 10px;"
-                                                                            title="View on Blockscout"
+                                                                            title="View in explorer"
                                                                         >
                                                                             <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                                                                                 <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
@@ -588,6 +646,7 @@ pub fn TransactionSearch() -> impl IntoView {
                                                                             </svg>
                                                                         </a>
                                                                     </div>
+                                                                    {from_tag}
                                                                 </td>
                                                                 <td style="font-family: monospace; font-size: 11px;">
                                                                     <div style="display: flex; align-items: center; gap: 4px;">
@@ -599,10 +658,10 @@ pub fn TransactionSearch() -> impl IntoView {
                                                                             {shorten_hash(&to_addr)}
                                                                         </a>
                                                                         <a
-                                                                            href=format!("https://filecoin.blockscout.com/address/{}", to_for_modal)
+                                                                            href=explorer_address_url(&config().explorer_url, &to_for_modal)
                                                                             target="_blank"
                                                                             style="color: var(--text-muted); text-decoration: none; font-size: 10px;"
-                                                                            title="View on Blockscout"
+                                                                            title="View in explorer"
                                                                         >
                                                                             <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                                                                                 <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
@@ -611,17 +670,32 @@ pub fn TransactionSearch() -> impl IntoView {
                                                                             </svg>
                                                                         </a>
                                                                     </div>
+                                                                    {to_tag}
                                                                 </td>
                                                                 <td style="font-family: monospace; font-size: 11px;">
-                                                                    <span
-                                                                        class="tx-hash-link clickable"
-                                                                        title=hash_display.clone()
-                                                                        on:click=move |_| {
-                                                                            set_selected_tx.set(Some(tx_for_modal.clone()));
-                                                                        }
-                                                                    >
-                                                                        {shorten_hash(&hash_display)}
-                                                                    </span>
+                                                                    <div style="display: flex; align-items: center; gap: 4px;">
+                                                                        <span
+                                                                            class="tx-hash-link clickable"
+                                                                            title=hash_display.clone()
+                                                                            on:click=move |_| {
+                                                                                set_selected_tx.set(Some(tx_for_modal.clone()));
+                                                                            }
+                                                                        >
+                                                                            {shorten_hash(&hash_display)}
+                                                                        </span>
+                                                                        <a
+                                                                            href=explorer_tx_url(&config().explorer_url, &hash_display)
+                                                                            target="_blank"
+                                                                            style="color: var(--text-muted); text-decoration: none; font-size: 10px;"
+                                                                            title="View in explorer"
+                                                                        >
+                                                                            <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+                                                                            <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>
+                                                                            <polyline points="15 3 21 3 21 9"></polyline>
+                                                                            <line x1="10" y1="14" x2="21" y2="3"></line>
+                                                                        </svg>
+                                                                        </a>
+                                                                    </div>
                                                                 </td>
                                                             </tr>
                                                         }
@@ -673,6 +747,20 @@ pub fn TransactionSearch() -> impl IntoView {
     }
 }
 
+fn get_current_timestamp() -> i64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 1000.0) as i64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+}
+
 fn validate_address(address: &str) -> bool {
     // EVM address: 0x + 40 hex chars
     let is_evm = address.len() == 42
@@ -754,11 +842,11 @@ fn AddressDetailModal(
 
                                 <div style="margin-top: 16px;">
                                     <a
-                                        href=format!("https://filecoin.blockscout.com/address/{}", link_addr)
+                                        href=explorer_address_url(&config().explorer_url, &link_addr)
                                         target="_blank"
                                         class="btn btn-primary"
                                     >
-                                        "View on Blockscout →"
+                                        "View in Explorer →"
                                     </a>
                                 </div>
                             }.into_view()
@@ -869,7 +957,7 @@ fn TransactionDetailModal(
 
     // Format timestamp
     let time_display = format_timestamp_full(transaction.timestamp);
-    let amount_display = format_amount(transaction.amount);
+    let amount_display = format_amount_with(transaction.amount, DETAIL_AMOUNT_DECIMALS, false);
 
     // Status display
     let status_class = transaction.status.css_class();
@@ -1003,7 +1091,7 @@ fn TransactionDetailModal(
                 // External Link
                 <div style="margin-top: 20px; padding-top: 16px; border-top: 1px solid var(--border-color);">
                     <a
-                        href=format!("https://filecoin.blockscout.com/tx/{}", tx_hash_for_link)
+                        href=explorer_tx_url(&config().explorer_url, &tx_hash_for_link)
                         target="_blank"
                         class="btn btn-primary"
                         style="display: inline-flex; align-items: center; gap: 8px;"
@@ -1013,7 +1101,7 @@ fn TransactionDetailModal(
                             <polyline points="15,3 21,3 21,9"/>
                             <line x1="10" y1="14" x2="21" y2="3"/>
                         </svg>
-                        "View on Blockscout"
+                        "View in Explorer"
                     </a>
                 </div>
             </div>